@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 不带取值的开关参数；其余 `--name` 均需紧跟一个取值。
+const BOOL_FLAGS: &[&str] = &["follow", "help", "json"];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParsedArgs {
+    positionals: Vec<String>,
+    options: BTreeMap<String, Vec<String>>,
+    flags: BTreeSet<String>,
+}
+
+impl ParsedArgs {
+    /// 函数 `positional`
+    ///
+    /// 按出现顺序读取第 `index` 个位置参数。
+    pub(crate) fn positional(&self, index: usize) -> Option<&str> {
+        self.positionals.get(index).map(String::as_str)
+    }
+
+    pub(crate) fn positionals_from(&self, index: usize) -> &[String] {
+        self.positionals.get(index..).unwrap_or(&[])
+    }
+
+    /// 函数 `option`
+    ///
+    /// 同名参数重复出现时以最后一次为准，空字符串视为未设置。
+    pub(crate) fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .get(name)
+            .and_then(|values| values.last())
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub(crate) fn option_i64(&self, name: &str) -> Result<Option<i64>, String> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .map_err(|_| format!("--{name} 需要整数(--{name} expects an integer)"))
+            })
+            .transpose()
+    }
+
+    /// 函数 `option_list`
+    ///
+    /// 合并重复参数并按逗号拆分，如 `--ids a,b --ids c`。
+    pub(crate) fn option_list(&self, name: &str) -> Vec<String> {
+        self.options
+            .get(name)
+            .into_iter()
+            .flatten()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub(crate) fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+}
+
+/// 函数 `parse_args`
+///
+/// 解析 `--name value`、`--name=value`、开关参数与位置参数；`--` 之后全部视为位置参数。
+pub(crate) fn parse_args<I>(args: I) -> Result<ParsedArgs, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = ParsedArgs::default();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            parsed.positionals.extend(iter.by_ref());
+            break;
+        }
+        if arg == "-h" {
+            parsed.flags.insert("help".to_string());
+            continue;
+        }
+        let Some(name) = arg.strip_prefix("--") else {
+            parsed.positionals.push(arg);
+            continue;
+        };
+        if let Some((name, value)) = name.split_once('=') {
+            if BOOL_FLAGS.contains(&name) {
+                return Err(format!("--{name} 不接受取值(--{name} takes no value)"));
+            }
+            push_option(&mut parsed, name, value.to_string());
+            continue;
+        }
+        if BOOL_FLAGS.contains(&name) {
+            parsed.flags.insert(name.to_string());
+            continue;
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("--{name} 缺少取值(--{name} requires a value)"))?;
+        push_option(&mut parsed, name, value);
+    }
+    Ok(parsed)
+}
+
+fn push_option(parsed: &mut ParsedArgs, name: &str, value: String) {
+    parsed
+        .options
+        .entry(name.to_string())
+        .or_default()
+        .push(value);
+}
+
+#[cfg(test)]
+#[path = "args_tests.rs"]
+mod tests;
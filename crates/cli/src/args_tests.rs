@@ -0,0 +1,45 @@
+use super::*;
+
+fn parse(items: &[&str]) -> Result<ParsedArgs, String> {
+    parse_args(items.iter().map(|item| item.to_string()))
+}
+
+#[test]
+fn parse_args_splits_positionals_options_and_flags() {
+    let parsed = parse(&[
+        "--addr",
+        "127.0.0.1:48760",
+        "logs",
+        "tail",
+        "--limit=50",
+        "--follow",
+        "--ids",
+        "a,b",
+        "--ids",
+        " c ,",
+        "--json",
+    ])
+    .expect("parse");
+    assert_eq!(parsed.positional(0), Some("logs"));
+    assert_eq!(parsed.positional(1), Some("tail"));
+    assert_eq!(parsed.positional(2), None);
+    assert_eq!(parsed.option("addr"), Some("127.0.0.1:48760"));
+    assert_eq!(parsed.option_i64("limit"), Ok(Some(50)));
+    assert_eq!(parsed.option_list("ids"), vec!["a", "b", "c"]);
+    assert!(parsed.flag("follow"));
+    assert!(parsed.flag("json"));
+    assert!(!parsed.flag("help"));
+}
+
+#[test]
+fn parse_args_rejects_missing_values_and_keeps_args_after_double_dash() {
+    assert!(parse(&["keys", "create", "--name"]).is_err());
+    assert!(parse(&["--json=true"]).is_err());
+    assert!(parse(&["logs", "tail", "--limit", "many"])
+        .expect("parse")
+        .option_i64("limit")
+        .is_err());
+
+    let parsed = parse(&["settings", "set", "--", "--odd=1"]).expect("parse");
+    assert_eq!(parsed.positionals_from(2), ["--odd=1".to_string()]);
+}
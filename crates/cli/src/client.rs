@@ -0,0 +1,262 @@
+use codexmanager_core::process_env::{
+    exe_dir, load_env_file_from_dir, read_rpc_token_from_env_or_file, DEFAULT_ADDR,
+    DEFAULT_WEB_ADDR, WEB_ACCESS_SESSION_COOKIE_NAME,
+};
+use codexmanager_core::rpc::types::{JsonRpcRequest, JsonRpcResponse};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::args::ParsedArgs;
+
+const ENV_CLI_SESSION: &str = "CODEXMANAGER_CLI_SESSION";
+const ENV_SERVICE_ADDR: &str = "CODEXMANAGER_SERVICE_ADDR";
+const ENV_WEB_ADDR: &str = "CODEXMANAGER_WEB_ADDR";
+const RPC_TOKEN_HEADER: &str = "X-CodexManager-Rpc-Token";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RpcAuth {
+    /// 直连 service `/rpc`，以管理员身份调用。
+    Token(String),
+    /// 经 web `/api/rpc` 转发，沿用 web 登录会话的角色与权限。
+    Session(String),
+}
+
+pub(crate) struct RpcClient {
+    http: reqwest::blocking::Client,
+    url: String,
+    auth: RpcAuth,
+    next_id: Cell<i64>,
+}
+
+impl RpcClient {
+    /// 函数 `from_args`
+    ///
+    /// 优先使用 `--session`（或 `CODEXMANAGER_CLI_SESSION`）走 web 会话；
+    /// 否则使用 `--token`，缺省时按 service 相同规则只读 env 文件与 RPC token 文件，
+    /// 找不到 token 时报错而不是生成新 token。
+    pub(crate) fn from_args(args: &ParsedArgs) -> Result<Self, String> {
+        let session = args
+            .option("session")
+            .map(str::to_string)
+            .or_else(|| non_empty_env(ENV_CLI_SESSION));
+        let (url, auth) = if let Some(session) = session {
+            (
+                format!("{}/api/rpc", web_base_url(args)),
+                RpcAuth::Session(session),
+            )
+        } else {
+            let token = match args.option("token") {
+                Some(token) => token.to_string(),
+                None => {
+                    let _ = load_env_file_from_dir(&exe_dir());
+                    read_rpc_token_from_env_or_file().ok_or_else(|| {
+                        "no RPC token found in CODEXMANAGER_RPC_TOKEN or the token file; pass --token/--session"
+                            .to_string()
+                    })?
+                }
+            };
+            let base = args
+                .option("addr")
+                .map(str::to_string)
+                .or_else(|| non_empty_env(ENV_SERVICE_ADDR))
+                .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+            (
+                format!("{}/rpc", normalize_base_url(&base)),
+                RpcAuth::Token(token),
+            )
+        };
+        let http = build_http_client()?;
+        Ok(Self {
+            http,
+            url,
+            auth,
+            next_id: Cell::new(1),
+        })
+    }
+
+    /// 函数 `call`
+    ///
+    /// 发送一次 JSON-RPC 请求；业务错误（`result.error`）同样转换为 `Err`。
+    pub(crate) fn call(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let request = JsonRpcRequest {
+            id: id.into(),
+            method: method.to_string(),
+            params,
+            trace: None,
+        };
+        let body = serde_json::to_string(&request)
+            .map_err(|err| format!("encode rpc request failed: {err}"))?;
+        let builder = self
+            .http
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        let builder = match &self.auth {
+            RpcAuth::Token(token) => builder.header(RPC_TOKEN_HEADER, token),
+            RpcAuth::Session(session) => builder.header(
+                reqwest::header::COOKIE,
+                format!("{}={session}", WEB_ACCESS_SESSION_COOKIE_NAME),
+            ),
+        };
+        let response = builder
+            .body(body)
+            .send()
+            .map_err(|err| format!("request {} failed: {err}", self.url))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .map_err(|err| format!("read rpc response failed: {err}"))?;
+        if !status.is_success() {
+            return Err(http_status_error(status.as_u16(), &text));
+        }
+        let response: JsonRpcResponse = serde_json::from_str(&text)
+            .map_err(|err| format!("decode rpc response failed: {err}"))?;
+        match rpc_result_error(&response.result) {
+            Some(err) => Err(format!("{method}: {err}")),
+            None => Ok(response.result),
+        }
+    }
+
+    pub(crate) fn call_as<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<T, String> {
+        let value = self.call(method, params)?;
+        serde_json::from_value(value).map_err(|err| format!("decode {method} result failed: {err}"))
+    }
+}
+
+/// 函数 `web_base_url`
+///
+/// `--web-url` 优先，其次 `CODEXMANAGER_WEB_ADDR`，最后回退默认 web 地址。
+pub(crate) fn web_base_url(args: &ParsedArgs) -> String {
+    let raw = args
+        .option("web-url")
+        .map(str::to_string)
+        .or_else(|| non_empty_env(ENV_WEB_ADDR))
+        .unwrap_or_else(|| DEFAULT_WEB_ADDR.to_string());
+    normalize_base_url(&raw)
+}
+
+/// 函数 `login_session`
+///
+/// 以 web 登录表单换取会话 token，供后续 `--session` 使用。
+pub(crate) fn login_session(
+    web_base_url: &str,
+    username: Option<&str>,
+    password: &str,
+) -> Result<String, String> {
+    let http = build_http_client()?;
+    let url = format!("{web_base_url}/__login");
+    let mut form = vec![("password", password)];
+    if let Some(username) = username {
+        form.push(("username", username));
+    }
+    let response = http
+        .post(&url)
+        .form(&form)
+        .send()
+        .map_err(|err| format!("request {url} failed: {err}"))?;
+    let status = response.status();
+    let session = response
+        .headers()
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(session_from_set_cookie);
+    match session {
+        Some(session) if status.is_success() => Ok(session),
+        _ if status.as_u16() == 401 => {
+            Err("登录失败：用户名或密码错误(login failed: invalid credentials)".to_string())
+        }
+        _ => Err(format!(
+            "登录未返回会话，web 可能未启用访问认证(login returned no session, status {})",
+            status.as_u16()
+        )),
+    }
+}
+
+fn build_http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .build()
+        .map_err(|err| format!("build http client failed: {err}"))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 函数 `normalize_base_url`
+///
+/// 接受 `48760`、`host:port` 或完整 URL；监听全部网卡的地址改写为 localhost 以便连接。
+pub(crate) fn normalize_base_url(raw: &str) -> String {
+    let trimmed = raw.trim().trim_end_matches('/');
+    let (scheme, rest) = match trimmed.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+        None => ("http".to_string(), trimmed),
+    };
+    let rest = if rest.parse::<u16>().is_ok() {
+        format!("localhost:{rest}")
+    } else {
+        rest.to_string()
+    };
+    let rest = match rest.rsplit_once(':') {
+        Some(("0.0.0.0" | "::" | "[::]", port)) => format!("localhost:{port}"),
+        _ => rest,
+    };
+    format!("{scheme}://{rest}")
+}
+
+fn session_from_set_cookie(header: &str) -> Option<String> {
+    let pair = header.split(';').next()?.trim();
+    let (name, value) = pair.split_once('=')?;
+    if name.trim() != WEB_ACCESS_SESSION_COOKIE_NAME {
+        return None;
+    }
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// 函数 `rpc_result_error`
+///
+/// 识别 service 的错误载荷：`{ "error": "..." }` 或 `{ "ok": false, "error": "..." }`。
+pub(crate) fn rpc_result_error(result: &Value) -> Option<String> {
+    let object = result.as_object()?;
+    if let Some(message) = object.get("error").and_then(Value::as_str) {
+        return Some(message.to_string());
+    }
+    if object.get("ok").and_then(Value::as_bool) == Some(false) {
+        return Some("request failed".to_string());
+    }
+    None
+}
+
+fn http_status_error(status: u16, body: &str) -> String {
+    match status {
+        401 => "RPC 认证失败，请检查 --token 或 --session(rpc unauthorized)".to_string(),
+        403 => "RPC 请求被拒绝(rpc forbidden)".to_string(),
+        _ => {
+            let body = body.trim();
+            if body.is_empty() || body == "{}" {
+                format!("rpc http status {status}")
+            } else {
+                format!("rpc http status {status}: {body}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "client_tests.rs"]
+mod tests;
@@ -0,0 +1,47 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn normalize_base_url_accepts_ports_addresses_and_urls() {
+    assert_eq!(normalize_base_url("48760"), "http://localhost:48760");
+    assert_eq!(
+        normalize_base_url("0.0.0.0:48760"),
+        "http://localhost:48760"
+    );
+    assert_eq!(
+        normalize_base_url(" https://gateway.example.com:8443/ "),
+        "https://gateway.example.com:8443"
+    );
+    assert_eq!(
+        normalize_base_url("10.0.0.2:48761"),
+        "http://10.0.0.2:48761"
+    );
+}
+
+#[test]
+fn rpc_result_error_detects_service_error_payloads() {
+    assert_eq!(
+        rpc_result_error(&json!({ "error": "api key not found", "errorCode": "not_found" })),
+        Some("api key not found".to_string())
+    );
+    assert_eq!(
+        rpc_result_error(&json!({ "ok": false })),
+        Some("request failed".to_string())
+    );
+    assert_eq!(rpc_result_error(&json!({ "ok": true })), None);
+    assert_eq!(rpc_result_error(&json!({ "items": [] })), None);
+    assert_eq!(rpc_result_error(&json!([1, 2])), None);
+}
+
+#[test]
+fn session_is_read_from_web_auth_cookie_only() {
+    assert_eq!(
+        session_from_set_cookie("codexmanager_web_auth=abc123; Path=/; HttpOnly; SameSite=Lax"),
+        Some("abc123".to_string())
+    );
+    assert_eq!(session_from_set_cookie("other=abc123; Path=/"), None);
+    assert_eq!(
+        session_from_set_cookie("codexmanager_web_auth=; Max-Age=0"),
+        None
+    );
+}
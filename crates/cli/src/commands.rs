@@ -0,0 +1,649 @@
+use codexmanager_core::rpc::types::{
+    AccountListResult, ApiKeyCreateResult, ApiKeyListResult, ApiKeySecretResult,
+    QuotaOverviewResult, RequestLogListResult, RequestLogSummary,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::args::ParsedArgs;
+use crate::client::{login_session, web_base_url, RpcClient};
+use crate::output::{cell, format_ts, print_json, print_table, value_cell, OutputFormat};
+
+const DEFAULT_LOG_TAIL_LIMIT: i64 = 20;
+const MAX_LOG_TAIL_LIMIT: i64 = 500;
+const DEFAULT_LOG_FOLLOW_INTERVAL_SECS: u64 = 2;
+const ENV_CLI_PASSWORD: &str = "CODEXMANAGER_CLI_PASSWORD";
+
+pub(crate) const USAGE: &str = "\
+codexmanager-cli：CodexManager 命令行管理客户端
+
+用法: codexmanager-cli [全局参数] <命令> [子命令] [参数]
+
+命令:
+  accounts list                           列出账号
+  accounts import <文件|目录|->...        导入 auth.json（目录下的 *.json 全部导入，- 读取 stdin）
+  accounts export [--out 目录] [--ids a,b] [--mode multiple|single]
+                                          导出账号到本地目录
+  keys list                               列出平台 Key
+  keys create --model 模型 [--name 名称] [--protocol 协议] [--custom-key 密钥] [--quota-limit-tokens N]
+  keys rotate <id> [--custom-key 密钥]    轮换密钥，旧密钥立即失效
+  keys enable|disable|delete <id>
+  logs tail [--limit N] [--query 关键词] [--status 状态] [--follow] [--interval 秒]
+  quota overview                          额度总览
+  settings get [键...]                    读取设置
+  settings set 键=值...                   写入设置（值可为 JSON，否则按字符串处理）
+  login [--username 用户名] [--password 密码]
+                                          登录 web 并输出会话 token（供 --session 使用）
+  call <方法> [JSON 参数]                 直接调用任意 RPC 方法
+
+全局参数:
+  --addr 地址       service 地址，默认 CODEXMANAGER_SERVICE_ADDR 或 localhost:48760
+  --token 令牌      RPC token，默认读取 CODEXMANAGER_RPC_TOKEN 或 token 文件
+  --session 会话    使用 web 登录会话（CODEXMANAGER_CLI_SESSION），经 web 转发并按会话权限执行
+  --web-url 地址    web 地址，默认 CODEXMANAGER_WEB_ADDR 或 localhost:48761
+  --json            以 JSON 输出
+";
+
+/// 函数 `run`
+///
+/// 按位置参数分发子命令；返回的错误由 `main` 输出并以非零码退出。
+pub(crate) fn run(args: &ParsedArgs) -> Result<(), String> {
+    let format = if args.flag("json") {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Table
+    };
+    let command = args.positional(0);
+    if args.flag("help") || command.is_none() || command == Some("help") {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    if command == Some("login") {
+        return login(args, format);
+    }
+    let client = RpcClient::from_args(args)?;
+    match (command.unwrap_or(""), args.positional(1).unwrap_or("")) {
+        ("accounts", "list") => accounts_list(&client, format),
+        ("accounts", "import") => accounts_import(&client, args, format),
+        ("accounts", "export") => accounts_export(&client, args, format),
+        ("keys", "list") => keys_list(&client, format),
+        ("keys", "create") => keys_create(&client, args, format),
+        ("keys", "rotate") => keys_rotate(&client, args, format),
+        ("keys", action @ ("enable" | "disable" | "delete")) => {
+            keys_action(&client, args, action, format)
+        }
+        ("logs", "tail") => logs_tail(&client, args, format),
+        ("quota", "overview") => quota_overview(&client, format),
+        ("settings", "get") => settings_get(&client, args, format),
+        ("settings", "set") => settings_set(&client, args, format),
+        ("call", _) => raw_call(&client, args),
+        (command, sub) => {
+            let name = format!("{command} {sub}");
+            Err(format!(
+                "未知命令 `{}`，运行 codexmanager-cli help 查看用法(unknown command)",
+                name.trim()
+            ))
+        }
+    }
+}
+
+fn required_positional<'a>(
+    args: &'a ParsedArgs,
+    index: usize,
+    name: &str,
+) -> Result<&'a str, String> {
+    args.positional(index)
+        .ok_or_else(|| format!("缺少参数 <{name}>(missing <{name}>)"))
+}
+
+fn login(args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let password = match args
+        .option("password")
+        .map(str::to_string)
+        .or_else(|| std::env::var(ENV_CLI_PASSWORD).ok())
+    {
+        Some(password) => password,
+        None => read_password_from_stdin()?,
+    };
+    let session = login_session(&web_base_url(args), args.option("username"), &password)?;
+    match format {
+        OutputFormat::Json => print_json(&json!({ "session": session })),
+        OutputFormat::Table => {
+            println!("{session}");
+            Ok(())
+        }
+    }
+}
+
+fn read_password_from_stdin() -> Result<String, String> {
+    eprint!("password: ");
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|err| format!("read password failed: {err}"))?;
+    let password = line.trim_end_matches(['\r', '\n']).to_string();
+    if password.is_empty() {
+        return Err("密码不能为空(password is empty)".to_string());
+    }
+    Ok(password)
+}
+
+fn accounts_list(client: &RpcClient, format: OutputFormat) -> Result<(), String> {
+    let result: AccountListResult = client.call_as("account/list", None)?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let rows: Vec<Vec<String>> = result
+        .items
+        .iter()
+        .map(|account| {
+            vec![
+                account.id.clone(),
+                account.label.clone(),
+                cell(account.group_name.as_deref()),
+                cell(account.plan_type.as_deref()),
+                account.status.clone(),
+                if account.has_token { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["ID", "LABEL", "GROUP", "PLAN", "STATUS", "TOKEN"], &rows);
+    eprintln!("total: {}", result.total);
+    Ok(())
+}
+
+/// 函数 `collect_import_contents`
+///
+/// 文件按原样读取；目录只取其下的 `*.json`（按文件名排序）；`-` 读取标准输入。
+pub(crate) fn collect_import_contents(sources: &[String]) -> Result<Vec<String>, String> {
+    let mut contents = Vec::new();
+    for source in sources {
+        if source == "-" {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .map_err(|err| format!("read stdin failed: {err}"))?;
+            contents.push(buf);
+            continue;
+        }
+        let path = Path::new(source);
+        if path.is_dir() {
+            let mut files: Vec<PathBuf> = fs::read_dir(path)
+                .map_err(|err| format!("read dir {source} failed: {err}"))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+                })
+                .collect();
+            files.sort();
+            for file in files {
+                contents.push(read_text_file(&file)?);
+            }
+            continue;
+        }
+        contents.push(read_text_file(path)?);
+    }
+    contents.retain(|content| !content.trim().is_empty());
+    Ok(contents)
+}
+
+fn read_text_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("read {} failed: {err}", path.display()))
+}
+
+fn accounts_import(
+    client: &RpcClient,
+    args: &ParsedArgs,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let sources = args.positionals_from(2);
+    if sources.is_empty() {
+        return Err("缺少导入文件(missing import files)".to_string());
+    }
+    let contents = collect_import_contents(sources)?;
+    if contents.is_empty() {
+        return Err("没有可导入的内容(nothing to import)".to_string());
+    }
+    let result = client.call("account/import", Some(json!({ "contents": contents })))?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let count = |key: &str| result.get(key).and_then(Value::as_u64).unwrap_or(0);
+    println!(
+        "total: {}  created: {}  updated: {}  failed: {}",
+        count("total"),
+        count("created"),
+        count("updated"),
+        count("failed")
+    );
+    for error in result
+        .get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        eprintln!(
+            "  #{}: {}",
+            error.get("index").and_then(Value::as_u64).unwrap_or(0),
+            error.get("message").and_then(Value::as_str).unwrap_or("")
+        );
+    }
+    Ok(())
+}
+
+fn accounts_export(
+    client: &RpcClient,
+    args: &ParsedArgs,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let mut params = json!({ "selectedAccountIds": args.option_list("ids") });
+    if let Some(mode) = args.option("mode") {
+        params["exportMode"] = Value::String(mode.to_string());
+    }
+    let result = client.call("account/exportData", Some(params))?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let out_dir = PathBuf::from(args.option("out").unwrap_or("."));
+    fs::create_dir_all(&out_dir)
+        .map_err(|err| format!("create {} failed: {err}", out_dir.display()))?;
+    let mut written = 0usize;
+    for file in result
+        .get("files")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let (Some(name), Some(content)) = (
+            file.get("fileName").and_then(Value::as_str),
+            file.get("content").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let Some(file_name) = Path::new(name).file_name() else {
+            continue;
+        };
+        let path = out_dir.join(file_name);
+        fs::write(&path, content)
+            .map_err(|err| format!("write {} failed: {err}", path.display()))?;
+        println!("{}", path.display());
+        written += 1;
+    }
+    eprintln!(
+        "exported: {written}  skipped (missing token): {}",
+        result
+            .get("skippedMissingToken")
+            .and_then(Value::as_u64)
+            .unwrap_or(0)
+    );
+    Ok(())
+}
+
+fn keys_list(client: &RpcClient, format: OutputFormat) -> Result<(), String> {
+    let result: ApiKeyListResult = client.call_as("apikey/list", None)?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let rows: Vec<Vec<String>> = result
+        .items
+        .iter()
+        .map(|key| {
+            vec![
+                key.id.clone(),
+                cell(key.name.as_deref()),
+                cell(key.model_slug.as_deref()),
+                key.protocol_type.clone(),
+                key.rotation_strategy.clone(),
+                key.status.clone(),
+                cell(key.quota_limit_tokens),
+                cell(key.last_used_at.map(format_ts)),
+            ]
+        })
+        .collect();
+    print_table(
+        &[
+            "ID",
+            "NAME",
+            "MODEL",
+            "PROTOCOL",
+            "ROTATION",
+            "STATUS",
+            "QUOTA",
+            "LAST USED",
+        ],
+        &rows,
+    );
+    Ok(())
+}
+
+fn keys_create(client: &RpcClient, args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let mut params = Map::new();
+    for (option, field) in [
+        ("name", "name"),
+        ("model", "modelSlug"),
+        ("reasoning-effort", "reasoningEffort"),
+        ("service-tier", "serviceTier"),
+        ("protocol", "protocolType"),
+        ("rotation", "rotationStrategy"),
+        ("custom-key", "customKey"),
+    ] {
+        if let Some(value) = args.option(option) {
+            params.insert(field.to_string(), Value::String(value.to_string()));
+        }
+    }
+    if let Some(limit) = args.option_i64("quota-limit-tokens")? {
+        params.insert("quotaLimitTokens".to_string(), Value::from(limit));
+    }
+    let result: ApiKeyCreateResult =
+        client.call_as("apikey/create", Some(Value::Object(params)))?;
+    print_secret(&result.id, &result.key, format)
+}
+
+fn keys_rotate(client: &RpcClient, args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let id = required_positional(args, 2, "id")?;
+    let mut params = json!({ "id": id });
+    if let Some(custom_key) = args.option("custom-key") {
+        params["customKey"] = Value::String(custom_key.to_string());
+    }
+    let result: ApiKeySecretResult = client.call_as("apikey/rotate", Some(params))?;
+    print_secret(&result.id, &result.key, format)
+}
+
+fn print_secret(id: &str, key: &str, format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => print_json(&json!({ "id": id, "key": key })),
+        OutputFormat::Table => {
+            println!("id:  {id}");
+            println!("key: {key}");
+            Ok(())
+        }
+    }
+}
+
+fn keys_action(
+    client: &RpcClient,
+    args: &ParsedArgs,
+    action: &str,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let id = required_positional(args, 2, "id")?;
+    let result = client.call(&format!("apikey/{action}"), Some(json!({ "id": id })))?;
+    match format {
+        OutputFormat::Json => print_json(&result),
+        OutputFormat::Table => {
+            println!("{action}d: {id}");
+            Ok(())
+        }
+    }
+}
+
+/// 函数 `log_row_key`
+///
+/// 请求日志没有自增 id，`--follow` 时以时间、trace 与路径组合去重。
+fn log_row_key(item: &RequestLogSummary) -> String {
+    format!(
+        "{}|{}|{}|{}|{}",
+        item.created_at,
+        item.trace_id.as_deref().unwrap_or(""),
+        item.key_id.as_deref().unwrap_or(""),
+        item.request_path,
+        item.status_code.unwrap_or_default()
+    )
+}
+
+/// 函数 `select_new_log_rows`
+///
+/// 从最新在前的一页日志里挑出尚未输出过的记录，按时间正序返回。
+pub(crate) fn select_new_log_rows(
+    items: Vec<RequestLogSummary>,
+    seen: &mut HashSet<String>,
+    since_ts: i64,
+) -> Vec<RequestLogSummary> {
+    let mut fresh: Vec<RequestLogSummary> = items
+        .into_iter()
+        .filter(|item| item.created_at >= since_ts)
+        .filter(|item| seen.insert(log_row_key(item)))
+        .collect();
+    fresh.reverse();
+    fresh
+}
+
+fn log_row(item: &RequestLogSummary) -> Vec<String> {
+    vec![
+        format_ts(item.created_at),
+        cell(item.status_code),
+        item.method.clone(),
+        item.request_path.clone(),
+        cell(item.model.as_deref().or(item.client_model.as_deref())),
+        cell(item.key_id.as_deref()),
+        cell(item.account_id.as_deref()),
+        cell(item.total_tokens),
+        cell(item.duration_ms),
+        cell(item.error.as_deref()),
+    ]
+}
+
+const LOG_HEADERS: [&str; 10] = [
+    "TIME", "STATUS", "METHOD", "PATH", "MODEL", "KEY", "ACCOUNT", "TOKENS", "MS", "ERROR",
+];
+
+fn logs_tail(client: &RpcClient, args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let limit = args
+        .option_i64("limit")?
+        .unwrap_or(DEFAULT_LOG_TAIL_LIMIT)
+        .clamp(1, MAX_LOG_TAIL_LIMIT);
+    let interval = args
+        .option_i64("interval")?
+        .map(|secs| secs.max(1) as u64)
+        .unwrap_or(DEFAULT_LOG_FOLLOW_INTERVAL_SECS);
+    let mut params = json!({ "page": 1, "pageSize": limit });
+    if let Some(query) = args.option("query") {
+        params["query"] = Value::String(query.to_string());
+    }
+    if let Some(status) = args.option("status") {
+        params["statusFilter"] = Value::String(status.to_string());
+    }
+    let mut seen = HashSet::new();
+    let mut since_ts = i64::MIN;
+    let mut first = true;
+    loop {
+        let page: RequestLogListResult = client.call_as("requestlog/list", Some(params.clone()))?;
+        let rows = select_new_log_rows(page.items, &mut seen, since_ts);
+        if let Some(latest) = rows.last() {
+            // 中文注释：只保留与最新时间同秒的去重键，避免长时间 follow 时集合无限增长。
+            since_ts = latest.created_at;
+            let keep: HashSet<String> = rows
+                .iter()
+                .filter(|item| item.created_at == since_ts)
+                .map(log_row_key)
+                .collect();
+            seen.retain(|key| keep.contains(key));
+        }
+        match format {
+            OutputFormat::Json => {
+                for item in &rows {
+                    let line = serde_json::to_string(item)
+                        .map_err(|err| format!("encode json output failed: {err}"))?;
+                    println!("{line}");
+                }
+            }
+            OutputFormat::Table => {
+                let table: Vec<Vec<String>> = rows.iter().map(log_row).collect();
+                if first {
+                    print_table(&LOG_HEADERS, &table);
+                } else {
+                    for row in &table {
+                        println!("{}", row.join("  "));
+                    }
+                }
+            }
+        }
+        first = false;
+        if !args.flag("follow") {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+fn quota_overview(client: &RpcClient, format: OutputFormat) -> Result<(), String> {
+    let result: QuotaOverviewResult = client.call_as("quota/overview", None)?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let api_key = &result.api_key;
+    let account = &result.openai_account;
+    let aggregate = &result.aggregate_api;
+    let today = &result.today_usage;
+    let rows = vec![
+        vec!["api keys".to_string(), api_key.key_count.to_string()],
+        vec![
+            "api key used tokens".to_string(),
+            api_key.total_used_tokens.to_string(),
+        ],
+        vec![
+            "api key remaining tokens".to_string(),
+            cell(api_key.total_remaining_tokens),
+        ],
+        vec![
+            "accounts available".to_string(),
+            format!("{}/{}", account.available_count, account.account_count),
+        ],
+        vec![
+            "accounts low quota".to_string(),
+            account.low_quota_count.to_string(),
+        ],
+        vec![
+            "primary window remain %".to_string(),
+            cell(account.primary_remain_percent),
+        ],
+        vec![
+            "secondary window remain %".to_string(),
+            cell(account.secondary_remain_percent),
+        ],
+        vec![
+            "aggregate api sources".to_string(),
+            format!(
+                "{} (errors {})",
+                aggregate.source_count, aggregate.error_count
+            ),
+        ],
+        vec![
+            "aggregate api balance usd".to_string(),
+            cell(
+                aggregate
+                    .total_balance_usd
+                    .map(|value| format!("{value:.2}")),
+            ),
+        ],
+        vec!["today tokens".to_string(), today.total_tokens.to_string()],
+        vec![
+            "today cost usd".to_string(),
+            format!("{:.4}", today.estimated_cost_usd),
+        ],
+    ];
+    print_table(&["METRIC", "VALUE"], &rows);
+    Ok(())
+}
+
+fn settings_get(client: &RpcClient, args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let result = client.call("appSettings/get", None)?;
+    let keys = args.positionals_from(2);
+    let object = result
+        .as_object()
+        .ok_or_else(|| "appSettings/get 返回格式异常(unexpected settings payload)".to_string())?;
+    let selected: Map<String, Value> = if keys.is_empty() {
+        object.clone()
+    } else {
+        let mut selected = Map::new();
+        for key in keys {
+            let value = object
+                .get(key)
+                .ok_or_else(|| format!("未知设置项 `{key}`(unknown setting)"))?;
+            selected.insert(key.clone(), value.clone());
+        }
+        selected
+    };
+    if format == OutputFormat::Json {
+        return print_json(&selected);
+    }
+    let rows: Vec<Vec<String>> = selected
+        .iter()
+        .map(|(key, value)| vec![key.clone(), value_cell(value)])
+        .collect();
+    print_table(&["KEY", "VALUE"], &rows);
+    Ok(())
+}
+
+/// 函数 `parse_settings_assignments`
+///
+/// 解析 `键=值`：值能按 JSON 解析时保留类型（`true`、`30`、`{...}`），否则按字符串提交。
+pub(crate) fn parse_settings_assignments(items: &[String]) -> Result<Map<String, Value>, String> {
+    let mut patch = Map::new();
+    for item in items {
+        let (key, raw) = item
+            .split_once('=')
+            .ok_or_else(|| format!("设置项需写成 键=值(expected key=value): {item}"))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("设置项缺少键名(missing key): {item}"));
+        }
+        let value = serde_json::from_str::<Value>(raw.trim())
+            .unwrap_or_else(|_| Value::String(raw.to_string()));
+        patch.insert(key.to_string(), value);
+    }
+    Ok(patch)
+}
+
+fn settings_set(client: &RpcClient, args: &ParsedArgs, format: OutputFormat) -> Result<(), String> {
+    let patch = parse_settings_assignments(args.positionals_from(2))?;
+    if patch.is_empty() {
+        return Err("缺少设置项(missing key=value)".to_string());
+    }
+    let keys: Vec<String> = patch.keys().cloned().collect();
+    let result = client.call("appSettings/set", Some(Value::Object(patch)))?;
+    if format == OutputFormat::Json {
+        return print_json(&result);
+    }
+    let rows: Vec<Vec<String>> = keys
+        .iter()
+        .map(|key| {
+            vec![
+                key.clone(),
+                result
+                    .get(key)
+                    .map(value_cell)
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    print_table(&["KEY", "VALUE"], &rows);
+    Ok(())
+}
+
+fn raw_call(client: &RpcClient, args: &ParsedArgs) -> Result<(), String> {
+    let method = required_positional(args, 1, "method")?;
+    let params = args
+        .positional(2)
+        .map(|raw| {
+            serde_json::from_str::<Value>(raw)
+                .map_err(|err| format!("参数不是合法 JSON(invalid params json): {err}"))
+        })
+        .transpose()?;
+    let result = client.call(method, params)?;
+    print_json(&result)
+}
+
+#[cfg(test)]
+#[path = "commands_tests.rs"]
+mod tests;
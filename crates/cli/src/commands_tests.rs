@@ -0,0 +1,75 @@
+use super::*;
+use serde_json::json;
+
+fn log(created_at: i64, trace_id: &str) -> RequestLogSummary {
+    RequestLogSummary {
+        trace_id: Some(trace_id.to_string()),
+        request_path: "/v1/responses".to_string(),
+        method: "POST".to_string(),
+        status_code: Some(200),
+        created_at,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parse_settings_assignments_keeps_json_types() {
+    let patch = parse_settings_assignments(&[
+        "uiTheme=dark".to_string(),
+        "updateAutoCheck=false".to_string(),
+        "gatewayAccountMaxInflight=4".to_string(),
+        "note= spaced value ".to_string(),
+    ])
+    .expect("parse settings");
+    assert_eq!(patch["uiTheme"], json!("dark"));
+    assert_eq!(patch["updateAutoCheck"], json!(false));
+    assert_eq!(patch["gatewayAccountMaxInflight"], json!(4));
+    assert_eq!(patch["note"], json!(" spaced value "));
+
+    assert!(parse_settings_assignments(&["uiTheme".to_string()]).is_err());
+    assert!(parse_settings_assignments(&["=dark".to_string()]).is_err());
+}
+
+#[test]
+fn select_new_log_rows_skips_seen_rows_and_returns_oldest_first() {
+    let mut seen = HashSet::new();
+    let first = select_new_log_rows(vec![log(20, "b"), log(10, "a")], &mut seen, i64::MIN);
+    let traces: Vec<_> = first.iter().map(|item| item.trace_id.clone()).collect();
+    assert_eq!(traces, vec![Some("a".to_string()), Some("b".to_string())]);
+
+    let next = select_new_log_rows(
+        vec![log(30, "c"), log(20, "b"), log(20, "b2"), log(10, "a")],
+        &mut seen,
+        20,
+    );
+    let traces: Vec<_> = next.iter().map(|item| item.trace_id.clone()).collect();
+    assert_eq!(traces, vec![Some("b2".to_string()), Some("c".to_string())]);
+}
+
+#[test]
+fn collect_import_contents_reads_json_files_from_directories() {
+    let dir = std::env::temp_dir().join(format!("codexmanager-cli-import-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create dir");
+    fs::write(dir.join("b.json"), r#"{"b":1}"#).expect("write b");
+    fs::write(dir.join("a.JSON"), r#"{"a":1}"#).expect("write a");
+    fs::write(dir.join("notes.txt"), "skip").expect("write txt");
+    fs::write(dir.join("empty.json"), "  ").expect("write empty");
+    let single = dir.join("single.auth");
+    fs::write(&single, r#"{"single":1}"#).expect("write single");
+
+    let contents = collect_import_contents(&[
+        dir.to_string_lossy().to_string(),
+        single.to_string_lossy().to_string(),
+    ])
+    .expect("collect");
+    assert_eq!(
+        contents,
+        vec![
+            r#"{"a":1}"#.to_string(),
+            r#"{"b":1}"#.to_string(),
+            r#"{"single":1}"#.to_string(),
+        ]
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
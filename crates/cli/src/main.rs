@@ -0,0 +1,22 @@
+mod args;
+mod client;
+mod commands;
+mod output;
+
+/// 函数 `main`
+///
+/// 命令行管理客户端入口：通过 RPC 管理账号、平台 Key、请求日志、额度与设置。
+fn main() {
+    let parsed = match args::parse_args(std::env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{err}");
+            eprint!("{}", commands::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(err) = commands::run(&parsed) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
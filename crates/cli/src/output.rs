@@ -0,0 +1,117 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Table,
+    Json,
+}
+
+/// 函数 `print_json`
+///
+/// `--json` 模式下原样输出 RPC 结果，便于脚本配合 `jq` 使用。
+pub(crate) fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|err| format!("encode json output failed: {err}"))?;
+    println!("{text}");
+    Ok(())
+}
+
+/// 函数 `render_table`
+///
+/// 按列最大显示宽度左对齐，列间两个空格；宽字符（中文等）按两列计算。
+pub(crate) fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| display_width(header)).collect();
+    for row in rows {
+        for (index, cell) in row.iter().enumerate().take(widths.len()) {
+            widths[index] = widths[index].max(display_width(cell));
+        }
+    }
+    let mut out = String::new();
+    let header_cells: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    push_row(&mut out, &header_cells, &widths);
+    for row in rows {
+        push_row(&mut out, row, &widths);
+    }
+    out
+}
+
+fn push_row(out: &mut String, cells: &[String], widths: &[usize]) {
+    let mut line = String::new();
+    for (index, width) in widths.iter().enumerate() {
+        let cell = cells.get(index).map(String::as_str).unwrap_or("");
+        if index > 0 {
+            line.push_str("  ");
+        }
+        line.push_str(cell);
+        if index + 1 < widths.len() {
+            line.push_str(&" ".repeat(width.saturating_sub(display_width(cell))));
+        }
+    }
+    out.push_str(line.trim_end());
+    out.push('\n');
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| if is_wide_char(ch) { 2 } else { 1 })
+        .sum()
+}
+
+fn is_wide_char(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1FAFF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+pub(crate) fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    print!("{}", render_table(headers, rows));
+}
+
+/// 函数 `cell`
+///
+/// 表格单元格统一的空值占位。
+pub(crate) fn cell<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 函数 `value_cell`
+///
+/// 设置项等任意 JSON 值：字符串去掉引号，其余按紧凑 JSON 显示。
+pub(crate) fn value_cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 函数 `format_ts`
+///
+/// 把 Unix 秒格式化为本地时间 `YYYY-MM-DD HH:MM:SS`。
+pub(crate) fn format_ts(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|value| {
+            value
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| ts.to_string())
+}
+
+#[cfg(test)]
+#[path = "output_tests.rs"]
+mod tests;
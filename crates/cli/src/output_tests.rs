@@ -0,0 +1,32 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn render_table_aligns_columns_by_display_width() {
+    let rows = vec![
+        vec![
+            "acc-1".to_string(),
+            "主账号".to_string(),
+            "active".to_string(),
+        ],
+        vec!["acc-22".to_string(), "backup".to_string(), "-".to_string()],
+    ];
+    let table = render_table(&["ID", "LABEL", "STATUS"], &rows);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[0], "ID      LABEL   STATUS");
+    assert_eq!(lines[1], "acc-1   主账号  active");
+    assert_eq!(lines[2], "acc-22  backup  -");
+}
+
+#[test]
+fn cells_use_placeholder_for_missing_values() {
+    assert_eq!(cell(None::<&str>), "-");
+    assert_eq!(cell(Some("")), "-");
+    assert_eq!(cell(Some(42)), "42");
+    assert_eq!(value_cell(&json!(null)), "-");
+    assert_eq!(value_cell(&json!("light")), "light");
+    assert_eq!(
+        value_cell(&json!({ "enabled": true })),
+        r#"{"enabled":true}"#
+    );
+}
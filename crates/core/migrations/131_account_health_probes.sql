@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS account_health_states (
+  account_id TEXT PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+  consecutive_failures INTEGER NOT NULL DEFAULT 0,
+  consecutive_successes INTEGER NOT NULL DEFAULT 0,
+  last_outcome TEXT,
+  last_status_code INTEGER,
+  last_error TEXT,
+  last_probe_at INTEGER,
+  quarantined_at INTEGER,
+  recovered_at INTEGER,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS account_health_probes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  outcome TEXT NOT NULL, -- 'success', 'auth_failure', 'server_error' or 'inconclusive'
+  status_code INTEGER,
+  duration_ms INTEGER,
+  error TEXT,
+  transition TEXT, -- 'quarantined' or 'recovered' when the probe changed routing state
+  probed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_health_probes_account
+  ON account_health_probes(account_id, probed_at DESC, id DESC);
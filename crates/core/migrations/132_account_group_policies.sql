@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS account_group_policies (
+  group_name TEXT PRIMARY KEY,
+  route_strategy TEXT, -- NULL follows the global route strategy
+  account_max_inflight INTEGER, -- NULL follows the global cap, 0 disables the cap for the group
+  allowed_models_json TEXT, -- JSON array of model slugs, a trailing '*' matches by prefix
+  fallback_groups_json TEXT, -- JSON array of groups tried in order when this group has no candidates
+  cooldown_multiplier_millis INTEGER, -- 1000 keeps the default cooldown length
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
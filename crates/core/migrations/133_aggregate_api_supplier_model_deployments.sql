@@ -0,0 +1 @@
+ALTER TABLE aggregate_api_supplier_models ADD COLUMN deployment TEXT;
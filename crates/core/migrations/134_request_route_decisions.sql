@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS request_route_decisions (
+  request_log_id INTEGER PRIMARY KEY REFERENCES request_logs(id) ON DELETE CASCADE,
+  trace_id TEXT,
+  strategy TEXT NOT NULL,
+  decision_json TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_route_decisions_trace_id
+  ON request_route_decisions(trace_id);
@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS alert_channels (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  kind TEXT NOT NULL, -- 'webhook', 'slack' or 'smtp'
+  config_json TEXT NOT NULL,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  condition TEXT NOT NULL,
+  severity TEXT NOT NULL, -- 'info', 'warning' or 'critical'
+  threshold REAL,
+  cooldown_secs INTEGER NOT NULL,
+  channel_ids_json TEXT,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS alert_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  rule_id TEXT NOT NULL,
+  rule_name TEXT NOT NULL,
+  condition TEXT NOT NULL,
+  severity TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  message TEXT NOT NULL,
+  delivery_status TEXT NOT NULL, -- 'sent', 'partial', 'failed' or 'no_channel'
+  delivery_json TEXT,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_rule_subject
+  ON alert_history(rule_id, subject, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_created_at
+  ON alert_history(created_at DESC, id DESC);
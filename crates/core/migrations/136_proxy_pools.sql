@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS proxy_pools (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  tag TEXT NOT NULL, -- member profiles carry this tag in tags_json
+  policy TEXT NOT NULL DEFAULT 'sticky', -- 'sticky', 'round_robin' or 'least_latency'
+  enabled INTEGER NOT NULL DEFAULT 1,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS account_proxy_pool_bindings (
+  account_id TEXT PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+  proxy_pool_id TEXT NOT NULL REFERENCES proxy_pools(id) ON DELETE CASCADE,
+  proxy_profile_id TEXT REFERENCES proxy_profiles(id) ON DELETE SET NULL,
+  assigned_at INTEGER,
+  failover_count INTEGER NOT NULL DEFAULT 0,
+  last_failover_at INTEGER,
+  last_failover_from TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_proxy_pool_bindings_pool
+  ON account_proxy_pool_bindings(proxy_pool_id, account_id);
+
+CREATE INDEX IF NOT EXISTS idx_account_proxy_pool_bindings_profile
+  ON account_proxy_pool_bindings(proxy_profile_id);
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS proxy_subscriptions (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  url TEXT, -- NULL for one-off imports of pasted content, which cannot be refreshed
+  tags_json TEXT,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  last_refreshed_at INTEGER,
+  last_error TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS proxy_subscription_profiles (
+  subscription_id TEXT NOT NULL REFERENCES proxy_subscriptions(id) ON DELETE CASCADE,
+  proxy_profile_id TEXT NOT NULL REFERENCES proxy_profiles(id) ON DELETE CASCADE,
+  proxy_url TEXT NOT NULL,
+  retired_at INTEGER, -- set when the entry disappeared from the subscription payload
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY (subscription_id, proxy_profile_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_proxy_subscription_profiles_profile
+  ON proxy_subscription_profiles(proxy_profile_id);
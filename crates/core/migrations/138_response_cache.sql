@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS api_key_response_cache_policies (
+  key_id TEXT PRIMARY KEY REFERENCES api_keys(id) ON DELETE CASCADE,
+  enabled INTEGER NOT NULL DEFAULT 0,
+  ttl_secs INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS response_cache_entries (
+  cache_key TEXT PRIMARY KEY, -- sha256 of key id, path, stream flag and the normalized request body
+  key_id TEXT NOT NULL,
+  model TEXT,
+  request_path TEXT NOT NULL,
+  status_code INTEGER NOT NULL,
+  content_type TEXT,
+  body BLOB NOT NULL, -- raw upstream body, replayed through the response bridge on hit
+  body_bytes INTEGER NOT NULL,
+  hit_count INTEGER NOT NULL DEFAULT 0,
+  created_at INTEGER NOT NULL,
+  expires_at INTEGER NOT NULL,
+  last_hit_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_response_cache_entries_key_id
+  ON response_cache_entries(key_id);
+
+CREATE INDEX IF NOT EXISTS idx_response_cache_entries_expires_at
+  ON response_cache_entries(expires_at);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS gateway_responses (
+  response_id TEXT PRIMARY KEY,
+  key_id TEXT NOT NULL,
+  account_id TEXT, -- upstream account that produced the response, other accounts need a local replay
+  previous_response_id TEXT,
+  model TEXT,
+  input_json TEXT NOT NULL, -- normalized input items of this turn only
+  output_json TEXT NOT NULL,
+  response_json TEXT NOT NULL, -- full response object served by GET /v1/responses/{id}
+  retained_bytes INTEGER NOT NULL,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_responses_key_id
+  ON gateway_responses(key_id);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_responses_created_at
+  ON gateway_responses(created_at);
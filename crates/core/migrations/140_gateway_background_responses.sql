@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS gateway_background_responses (
+  response_id TEXT PRIMARY KEY,
+  key_id TEXT NOT NULL,
+  status TEXT NOT NULL, -- queued / in_progress / completed / failed / incomplete / cancelled
+  response_json TEXT NOT NULL, -- latest response snapshot served by GET /v1/responses/{id}
+  last_sequence_number INTEGER NOT NULL DEFAULT -1,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_background_responses_key_id
+  ON gateway_background_responses(key_id);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_background_responses_updated_at
+  ON gateway_background_responses(updated_at);
+
+CREATE TABLE IF NOT EXISTS gateway_background_response_events (
+  response_id TEXT NOT NULL,
+  sequence_number INTEGER NOT NULL,
+  event_json TEXT NOT NULL,
+  PRIMARY KEY (response_id, sequence_number)
+);
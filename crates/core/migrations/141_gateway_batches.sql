@@ -0,0 +1,57 @@
+CREATE TABLE IF NOT EXISTS gateway_files (
+  file_id TEXT PRIMARY KEY,
+  key_id TEXT NOT NULL,
+  purpose TEXT NOT NULL, -- batch / batch_output
+  filename TEXT NOT NULL,
+  bytes INTEGER NOT NULL,
+  content BLOB NOT NULL,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_files_key_id_created_at
+  ON gateway_files(key_id, created_at);
+
+CREATE TABLE IF NOT EXISTS gateway_batches (
+  batch_id TEXT PRIMARY KEY,
+  key_id TEXT NOT NULL,
+  endpoint TEXT NOT NULL,
+  input_file_id TEXT NOT NULL,
+  completion_window TEXT NOT NULL,
+  status TEXT NOT NULL, -- validating / in_progress / finalizing / completed / failed / expired / cancelling / cancelled
+  output_file_id TEXT,
+  error_file_id TEXT,
+  errors_json TEXT,
+  metadata_json TEXT,
+  created_at INTEGER NOT NULL,
+  in_progress_at INTEGER,
+  finalizing_at INTEGER,
+  completed_at INTEGER,
+  failed_at INTEGER,
+  expired_at INTEGER,
+  cancelling_at INTEGER,
+  cancelled_at INTEGER,
+  expires_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_batches_key_id_created_at
+  ON gateway_batches(key_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_batches_status
+  ON gateway_batches(status);
+
+CREATE TABLE IF NOT EXISTS gateway_batch_items (
+  batch_id TEXT NOT NULL,
+  line_index INTEGER NOT NULL,
+  custom_id TEXT NOT NULL,
+  url TEXT NOT NULL,
+  body_json TEXT NOT NULL,
+  status TEXT NOT NULL, -- pending / running / completed / failed
+  status_code INTEGER,
+  result_json TEXT, -- output or error JSONL line in OpenAI batch format
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY (batch_id, line_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_batch_items_batch_status
+  ON gateway_batch_items(batch_id, status);
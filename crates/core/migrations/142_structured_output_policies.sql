@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS api_key_structured_output_policies (
+  key_id TEXT PRIMARY KEY REFERENCES api_keys(id) ON DELETE CASCADE,
+  enabled INTEGER NOT NULL DEFAULT 0,
+  max_repairs INTEGER NOT NULL, -- bounded repair retries after a json_schema mismatch
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS api_key_content_policies (
+  key_id TEXT PRIMARY KEY REFERENCES api_keys(id) ON DELETE CASCADE,
+  enabled INTEGER NOT NULL DEFAULT 0,
+  action TEXT NOT NULL, -- block / redact / log
+  source_actions_json TEXT NOT NULL DEFAULT '{}', -- per upstream source kind override
+  detectors_json TEXT NOT NULL DEFAULT '[]', -- empty means every built-in detector
+  custom_rules_json TEXT NOT NULL DEFAULT '[]',
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS request_content_policy_matches (
+  request_log_id INTEGER PRIMARY KEY REFERENCES request_logs(id) ON DELETE CASCADE,
+  trace_id TEXT,
+  matches_json TEXT NOT NULL, -- detector / action / count / paths, never the matched text
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_content_policy_matches_trace_id
+  ON request_content_policy_matches(trace_id);
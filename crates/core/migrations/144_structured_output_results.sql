@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS request_structured_output_results (
+  request_log_id INTEGER PRIMARY KEY REFERENCES request_logs(id) ON DELETE CASCADE,
+  trace_id TEXT,
+  outcome TEXT NOT NULL, -- valid / repaired / invalid / skipped
+  repairs INTEGER NOT NULL DEFAULT 0,
+  skip_reason TEXT, -- no_message_text / aggregate_api when outcome = skipped
+  errors_json TEXT NOT NULL DEFAULT '[]', -- schema violations of the delivered output
+  repair_input_tokens INTEGER, -- usage of responses replaced by a repair, already in request_logs totals
+  repair_output_tokens INTEGER,
+  repair_total_tokens INTEGER,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_structured_output_results_trace_id
+  ON request_structured_output_results(trace_id);
@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod process_env;
 pub mod rpc;
 pub mod storage;
 pub mod usage;
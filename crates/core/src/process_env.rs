@@ -0,0 +1,175 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const ENV_CANDIDATES: [&str; 3] = ["codexmanager.env", "CodexManager.env", ".env"];
+pub const DEFAULT_DB_FILENAME: &str = "codexmanager.db";
+pub const DEFAULT_RPC_TOKEN_FILENAME: &str = "codexmanager.rpc-token";
+
+pub const DEFAULT_ADDR: &str = "localhost:48760";
+pub const DEFAULT_WEB_ADDR: &str = "localhost:48761";
+pub const WEB_ACCESS_SESSION_COOKIE_NAME: &str = "codexmanager_web_auth";
+
+pub const ENV_DB_PATH: &str = "CODEXMANAGER_DB_PATH";
+pub const ENV_RPC_TOKEN: &str = "CODEXMANAGER_RPC_TOKEN";
+pub const ENV_RPC_TOKEN_FILE: &str = "CODEXMANAGER_RPC_TOKEN_FILE";
+
+/// 函数 `exe_dir`
+///
+/// 当前可执行文件所在目录；取不到时退回工作目录。
+pub fn exe_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// 函数 `strip_inline_comment`
+///
+/// 只把 ` #` 视为行内注释起点（与常见 dotenv 行为一致）。
+fn strip_inline_comment(value: &str) -> &str {
+    let Some(pos) = value.find(" #") else {
+        return value;
+    };
+    value[..pos].trim_end()
+}
+
+/// 函数 `parse_dotenv_kv`
+///
+/// 解析 env 文件中的一行 `KEY=value`，支持 `export` 前缀与成对引号。
+pub fn parse_dotenv_kv(line: &str) -> Option<(String, String)> {
+    let mut line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        return None;
+    }
+    if let Some(rest) = line.strip_prefix("export ") {
+        line = rest.trim();
+    }
+    let (key, raw_value) = line.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    let mut value = raw_value.trim();
+    // Handle quoted values: KEY="a b", KEY='a b'
+    if (value.starts_with('"') && value.ends_with('"') && value.len() >= 2)
+        || (value.starts_with('\'') && value.ends_with('\'') && value.len() >= 2)
+    {
+        value = &value[1..value.len() - 1];
+    } else {
+        value = strip_inline_comment(value);
+    }
+    Some((key.to_string(), value.to_string()))
+}
+
+/// 函数 `find_env_file_in_dir`
+///
+/// 按 `ENV_CANDIDATES` 顺序查找目录中的 env 文件。
+pub fn find_env_file_in_dir(dir: &Path) -> Option<PathBuf> {
+    for name in ENV_CANDIDATES {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// 函数 `load_env_file_from_dir`
+///
+/// 把目录中 env 文件的变量写入进程环境，已存在的变量保持不变；
+/// 返回读取的文件与实际写入的变量数。
+pub fn load_env_file_from_dir(dir: &Path) -> Option<(PathBuf, usize)> {
+    let path = find_env_file_in_dir(dir)?;
+    let mut text = String::new();
+    fs::File::open(&path).ok()?.read_to_string(&mut text).ok()?;
+
+    let mut applied = 0usize;
+    for line in text.lines() {
+        let Some((key, value)) = parse_dotenv_kv(line) else {
+            continue;
+        };
+        if std::env::var_os(&key).is_some() {
+            continue;
+        }
+        std::env::set_var(key, value);
+        applied += 1;
+    }
+    Some((path, applied))
+}
+
+/// 函数 `resolve_path_with_base`
+///
+/// 相对路径按 `base_dir` 解析，绝对路径原样返回。
+pub fn resolve_path_with_base(raw: &str, base_dir: &Path) -> PathBuf {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return PathBuf::new();
+    }
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        return path;
+    }
+    base_dir.join(path)
+}
+
+/// 函数 `resolve_db_path`
+///
+/// 按 `CODEXMANAGER_DB_PATH`、可执行文件目录的顺序解析 SQLite 数据库路径，不修改进程环境。
+pub fn resolve_db_path() -> PathBuf {
+    let dir = exe_dir();
+    match std::env::var(ENV_DB_PATH) {
+        Ok(raw) if !raw.trim().is_empty() => resolve_path_with_base(&raw, &dir),
+        _ => dir.join(DEFAULT_DB_FILENAME),
+    }
+}
+
+/// 函数 `rpc_token_file_path_in`
+///
+/// `CODEXMANAGER_RPC_TOKEN_FILE` 优先（相对可执行文件目录），否则放在数据库目录下。
+pub fn rpc_token_file_path_in(db_dir: &Path) -> PathBuf {
+    if let Ok(raw) = std::env::var(ENV_RPC_TOKEN_FILE) {
+        let trimmed = raw.trim();
+        if !trimmed.is_empty() {
+            return resolve_path_with_base(trimmed, &exe_dir());
+        }
+    }
+    db_dir.join(DEFAULT_RPC_TOKEN_FILENAME)
+}
+
+/// 函数 `read_rpc_token_from_file`
+///
+/// 读取 token 文件，文件缺失或内容为空时返回 None。
+pub fn read_rpc_token_from_file(path: &Path) -> Option<String> {
+    let Ok(mut f) = fs::File::open(path) else {
+        return None;
+    };
+    let mut buf = String::new();
+    if f.read_to_string(&mut buf).is_err() {
+        return None;
+    }
+    let token = buf.trim();
+    if token.is_empty() {
+        return None;
+    }
+    Some(token.to_string())
+}
+
+/// 函数 `read_rpc_token_from_env_or_file`
+///
+/// 先读 `CODEXMANAGER_RPC_TOKEN`，再读 token 文件；只读不写，找不到时返回 None。
+pub fn read_rpc_token_from_env_or_file() -> Option<String> {
+    if let Ok(raw) = std::env::var(ENV_RPC_TOKEN) {
+        let trimmed = raw.trim();
+        if !trimmed.is_empty() {
+            return Some(trimmed.to_string());
+        }
+    }
+    let db_path = resolve_db_path();
+    let db_dir = db_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(exe_dir);
+    read_rpc_token_from_file(&rpc_token_file_path_in(&db_dir))
+}
@@ -640,6 +640,108 @@ pub struct ApiKeySecretResult {
     pub key: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponseCacheEntry {
+    pub key_id: String,
+    pub enabled: bool,
+    pub ttl_secs: i64,
+    pub entries: i64,
+    pub body_bytes: i64,
+    pub hit_count: i64,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponseCacheSetParams {
+    pub id: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub ttl_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyBatchEntry {
+    pub batch_id: String,
+    pub key_id: String,
+    pub endpoint: String,
+    pub status: String,
+    pub input_file_id: String,
+    pub output_file_id: Option<String>,
+    pub error_file_id: Option<String>,
+    pub total: i64,
+    pub completed: i64,
+    pub failed: i64,
+    pub running: bool,
+    pub created_at: i64,
+    pub in_progress_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub expires_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyBatchListResult {
+    pub items: Vec<ApiKeyBatchEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyStructuredOutputEntry {
+    pub key_id: String,
+    pub enabled: bool,
+    pub max_repairs: i64,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyStructuredOutputSetParams {
+    pub id: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_repairs: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentPolicyCustomRule {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyContentPolicyEntry {
+    pub key_id: String,
+    pub enabled: bool,
+    pub action: String,
+    pub source_actions: BTreeMap<String, String>,
+    pub detectors: Vec<String>,
+    pub custom_rules: Vec<ContentPolicyCustomRule>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyContentPolicySetParams {
+    pub id: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub source_actions: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub detectors: Option<Vec<String>>,
+    #[serde(default)]
+    pub custom_rules: Option<Vec<ContentPolicyCustomRule>>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProxyProfileEntry {
@@ -684,6 +786,97 @@ pub struct ProxyProfileListResult {
     pub items: Vec<ProxyProfileEntry>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyPoolMemberEntry {
+    pub proxy_profile_id: String,
+    pub name: String,
+    pub proxy_url_redacted: String,
+    pub enabled: bool,
+    pub status: String,
+    pub latency_ms: Option<i64>,
+    pub down_until: Option<i64>,
+    pub assigned_accounts: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyPoolEntry {
+    pub id: String,
+    pub name: String,
+    pub tag: String,
+    pub policy: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub members: Vec<ProxyPoolMemberEntry>,
+    pub accounts_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyPoolListResult {
+    #[serde(default)]
+    pub items: Vec<ProxyPoolEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyPoolSetParams {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub tag: String,
+    #[serde(default)]
+    pub policy: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySubscriptionEntry {
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+    pub tags_json: Option<String>,
+    pub enabled: bool,
+    pub profiles_count: i64,
+    pub active_profiles_count: i64,
+    pub last_refreshed_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySubscriptionListResult {
+    #[serde(default)]
+    pub items: Vec<ProxySubscriptionEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySubscriptionSkippedItem {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySubscriptionSyncResult {
+    pub subscription: ProxySubscriptionEntry,
+    pub created: i64,
+    pub reactivated: i64,
+    pub unchanged: i64,
+    pub duplicates: i64,
+    pub retired: i64,
+    #[serde(default)]
+    pub skipped: Vec<ProxySubscriptionSkippedItem>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProxyTestFileSizePreset {
@@ -1027,6 +1220,8 @@ pub struct AggregateApiSupplierModelEntry {
     pub provider_type: String,
     pub upstream_model: String,
     pub display_name: Option<String>,
+    #[serde(default)]
+    pub deployment: Option<String>,
     pub status: String,
     pub created_at: i64,
     pub updated_at: i64,
@@ -1048,6 +1243,36 @@ pub struct AggregateApiSupplierModelUpsertParams {
     #[serde(default)]
     pub display_name: Option<String>,
     #[serde(default)]
+    pub deployment: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateApiDeploymentEntry {
+    pub api_id: String,
+    pub upstream_model: String,
+    pub deployment: String,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateApiDeploymentListResult {
+    #[serde(default)]
+    pub items: Vec<AggregateApiDeploymentEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateApiDeploymentSetParams {
+    pub api_id: String,
+    pub upstream_model: String,
+    pub deployment: String,
+    #[serde(default)]
     pub status: Option<String>,
 }
 
@@ -1255,6 +1480,113 @@ pub struct ModelGroupListResult {
     pub user_assignments: Vec<UserModelGroupEntry>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountGroupPolicyEntry {
+    pub group_name: String,
+    pub route_strategy: Option<String>,
+    pub account_max_inflight: Option<i64>,
+    #[serde(default)]
+    pub allowed_models: Vec<String>,
+    #[serde(default)]
+    pub fallback_groups: Vec<String>,
+    pub cooldown_multiplier_millis: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountGroupPolicySetParams {
+    pub group_name: String,
+    #[serde(default)]
+    pub route_strategy: Option<String>,
+    #[serde(default)]
+    pub account_max_inflight: Option<i64>,
+    #[serde(default)]
+    pub allowed_models: Vec<String>,
+    #[serde(default)]
+    pub fallback_groups: Vec<String>,
+    #[serde(default)]
+    pub cooldown_multiplier_millis: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertChannelEntry {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub config: Value,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertChannelSetParams {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub config: Value,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleEntry {
+    pub id: String,
+    pub name: String,
+    pub condition: String,
+    pub severity: String,
+    pub threshold: Option<f64>,
+    pub cooldown_secs: i64,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleSetParams {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub condition: String,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    #[serde(default)]
+    pub cooldown_secs: Option<i64>,
+    #[serde(default)]
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertHistoryEntry {
+    pub id: i64,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub condition: String,
+    pub severity: String,
+    pub subject: String,
+    pub message: String,
+    pub delivery_status: String,
+    #[serde(default)]
+    pub deliveries: Value,
+    pub created_at: i64,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelGroupUpsertParams {
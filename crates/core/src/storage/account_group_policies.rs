@@ -0,0 +1,91 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{AccountGroupPolicy, Storage};
+
+fn map_policy(row: &Row<'_>) -> Result<AccountGroupPolicy> {
+    Ok(AccountGroupPolicy {
+        group_name: row.get(0)?,
+        route_strategy: row.get(1)?,
+        account_max_inflight: row.get(2)?,
+        allowed_models_json: row.get(3)?,
+        fallback_groups_json: row.get(4)?,
+        cooldown_multiplier_millis: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn policy_columns() -> &'static str {
+    "group_name, route_strategy, account_max_inflight, allowed_models_json,
+     fallback_groups_json, cooldown_multiplier_millis, created_at, updated_at"
+}
+
+impl Storage {
+    pub fn find_account_group_policy(
+        &self,
+        group_name: &str,
+    ) -> Result<Option<AccountGroupPolicy>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_group_policies
+             WHERE group_name = ?1
+             LIMIT 1",
+            policy_columns()
+        );
+        self.conn
+            .query_row(&sql, [group_name], map_policy)
+            .optional()
+    }
+
+    pub fn list_account_group_policies(&self) -> Result<Vec<AccountGroupPolicy>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_group_policies
+             ORDER BY group_name ASC",
+            policy_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_policy)?;
+        rows.collect()
+    }
+
+    /// 按分组名写入策略；已存在时保留原创建时间。
+    pub fn upsert_account_group_policy(&self, policy: &AccountGroupPolicy) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO account_group_policies (
+                group_name, route_strategy, account_max_inflight, allowed_models_json,
+                fallback_groups_json, cooldown_multiplier_millis, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(group_name) DO UPDATE SET
+                route_strategy = excluded.route_strategy,
+                account_max_inflight = excluded.account_max_inflight,
+                allowed_models_json = excluded.allowed_models_json,
+                fallback_groups_json = excluded.fallback_groups_json,
+                cooldown_multiplier_millis = excluded.cooldown_multiplier_millis,
+                updated_at = excluded.updated_at",
+            params![
+                policy.group_name,
+                policy.route_strategy,
+                policy.account_max_inflight,
+                policy.allowed_models_json,
+                policy.fallback_groups_json,
+                policy.cooldown_multiplier_millis,
+                policy.created_at,
+                policy.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_account_group_policy(&self, group_name: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM account_group_policies WHERE group_name = ?1",
+            [group_name],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+#[path = "account_group_policies_tests.rs"]
+mod tests;
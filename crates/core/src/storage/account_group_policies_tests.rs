@@ -0,0 +1,70 @@
+use super::*;
+
+fn policy(group_name: &str, created_at: i64) -> AccountGroupPolicy {
+    AccountGroupPolicy {
+        group_name: group_name.to_string(),
+        route_strategy: Some("balanced".to_string()),
+        account_max_inflight: Some(2),
+        allowed_models_json: Some("[\"gpt-5*\"]".to_string()),
+        fallback_groups_json: Some("[\"shared\"]".to_string()),
+        cooldown_multiplier_millis: Some(500),
+        created_at,
+        updated_at: created_at,
+    }
+}
+
+#[test]
+fn account_group_policy_upsert_keeps_created_at_and_lists_by_name() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+
+    storage
+        .upsert_account_group_policy(&policy("team", 100))
+        .expect("insert team");
+    storage
+        .upsert_account_group_policy(&policy("batch", 100))
+        .expect("insert batch");
+    let mut updated = policy("team", 200);
+    updated.route_strategy = None;
+    updated.account_max_inflight = Some(0);
+    storage
+        .upsert_account_group_policy(&updated)
+        .expect("update team");
+
+    let team = storage
+        .find_account_group_policy("team")
+        .expect("find team")
+        .expect("team exists");
+    assert_eq!(team.route_strategy, None);
+    assert_eq!(team.account_max_inflight, Some(0));
+    assert_eq!(team.created_at, 100);
+    assert_eq!(team.updated_at, 200);
+
+    let names = storage
+        .list_account_group_policies()
+        .expect("list policies")
+        .into_iter()
+        .map(|item| item.group_name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["batch".to_string(), "team".to_string()]);
+}
+
+#[test]
+fn account_group_policy_delete_reports_whether_a_row_was_removed() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .upsert_account_group_policy(&policy("team", 100))
+        .expect("insert team");
+
+    assert!(storage
+        .delete_account_group_policy("team")
+        .expect("delete team"));
+    assert!(!storage
+        .delete_account_group_policy("team")
+        .expect("delete missing"));
+    assert!(storage
+        .find_account_group_policy("team")
+        .expect("find team")
+        .is_none());
+}
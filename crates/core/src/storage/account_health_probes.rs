@@ -0,0 +1,156 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{AccountHealthProbeRecord, AccountHealthState, Storage};
+
+fn map_state(row: &Row<'_>) -> Result<AccountHealthState> {
+    Ok(AccountHealthState {
+        account_id: row.get(0)?,
+        consecutive_failures: row.get(1)?,
+        consecutive_successes: row.get(2)?,
+        last_outcome: row.get(3)?,
+        last_status_code: row.get(4)?,
+        last_error: row.get(5)?,
+        last_probe_at: row.get(6)?,
+        quarantined_at: row.get(7)?,
+        recovered_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn map_probe(row: &Row<'_>) -> Result<AccountHealthProbeRecord> {
+    Ok(AccountHealthProbeRecord {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        outcome: row.get(2)?,
+        status_code: row.get(3)?,
+        duration_ms: row.get(4)?,
+        error: row.get(5)?,
+        transition: row.get(6)?,
+        probed_at: row.get(7)?,
+    })
+}
+
+fn state_columns() -> &'static str {
+    "account_id, consecutive_failures, consecutive_successes, last_outcome,
+     last_status_code, last_error, last_probe_at, quarantined_at, recovered_at, updated_at"
+}
+
+fn probe_columns() -> &'static str {
+    "id, account_id, outcome, status_code, duration_ms, error, transition, probed_at"
+}
+
+impl Storage {
+    pub fn get_account_health_state(&self, account_id: &str) -> Result<Option<AccountHealthState>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_health_states
+             WHERE account_id = ?1
+             LIMIT 1",
+            state_columns()
+        );
+        self.conn
+            .query_row(&sql, [account_id], map_state)
+            .optional()
+    }
+
+    pub fn list_account_health_states(&self) -> Result<Vec<AccountHealthState>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_health_states
+             ORDER BY account_id ASC",
+            state_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_state)?;
+        rows.collect()
+    }
+
+    pub fn save_account_health_state(&self, state: &AccountHealthState) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO account_health_states (
+                account_id, consecutive_failures, consecutive_successes, last_outcome,
+                last_status_code, last_error, last_probe_at, quarantined_at, recovered_at,
+                updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(account_id) DO UPDATE SET
+                consecutive_failures = excluded.consecutive_failures,
+                consecutive_successes = excluded.consecutive_successes,
+                last_outcome = excluded.last_outcome,
+                last_status_code = excluded.last_status_code,
+                last_error = excluded.last_error,
+                last_probe_at = excluded.last_probe_at,
+                quarantined_at = excluded.quarantined_at,
+                recovered_at = excluded.recovered_at,
+                updated_at = excluded.updated_at",
+            params![
+                state.account_id.trim(),
+                state.consecutive_failures.max(0),
+                state.consecutive_successes.max(0),
+                &state.last_outcome,
+                state.last_status_code,
+                &state.last_error,
+                state.last_probe_at,
+                state.quarantined_at,
+                state.recovered_at,
+                state.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_account_health_probe(&self, probe: &AccountHealthProbeRecord) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO account_health_probes (
+                account_id, outcome, status_code, duration_ms, error, transition, probed_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                probe.account_id.trim(),
+                probe.outcome.trim(),
+                probe.status_code,
+                probe.duration_ms,
+                &probe.error,
+                &probe.transition,
+                probe.probed_at,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn list_account_health_probes(
+        &self,
+        account_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AccountHealthProbeRecord>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_health_probes
+             WHERE account_id = ?1
+             ORDER BY probed_at DESC, id DESC
+             LIMIT ?2",
+            probe_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![account_id, limit.max(1)], map_probe)?;
+        rows.collect()
+    }
+
+    /// 只保留每个账号最近 `keep` 条探测记录，避免周期探测让历史表无限增长。
+    pub fn prune_account_health_probes(&self, account_id: &str, keep: i64) -> Result<usize> {
+        self.conn.execute(
+            "DELETE FROM account_health_probes
+             WHERE account_id = ?1
+               AND id NOT IN (
+                 SELECT id
+                 FROM account_health_probes
+                 WHERE account_id = ?1
+                 ORDER BY probed_at DESC, id DESC
+                 LIMIT ?2
+               )",
+            params![account_id, keep.max(1)],
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "account_health_probes_tests.rs"]
+mod tests;
@@ -0,0 +1,147 @@
+use super::*;
+use crate::storage::{now_ts, Account};
+
+fn storage_with_account(account_id: &str) -> Storage {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    let now = now_ts();
+    storage
+        .insert_account(&Account {
+            id: account_id.to_string(),
+            label: account_id.to_string(),
+            issuer: "issuer".to_string(),
+            chatgpt_account_id: None,
+            workspace_id: None,
+            group_name: None,
+            sort: 0,
+            status: "active".to_string(),
+            created_at: now,
+            updated_at: now,
+        })
+        .expect("insert account");
+    storage
+}
+
+fn probe(account_id: &str, outcome: &str, probed_at: i64) -> AccountHealthProbeRecord {
+    AccountHealthProbeRecord {
+        id: 0,
+        account_id: account_id.to_string(),
+        outcome: outcome.to_string(),
+        status_code: Some(200),
+        duration_ms: Some(12),
+        error: None,
+        transition: None,
+        probed_at,
+    }
+}
+
+#[test]
+fn health_state_round_trips_and_upserts() {
+    let storage = storage_with_account("acc-1");
+    assert!(storage
+        .get_account_health_state("acc-1")
+        .expect("read missing state")
+        .is_none());
+
+    let mut state = AccountHealthState {
+        account_id: "acc-1".to_string(),
+        consecutive_failures: 2,
+        consecutive_successes: 0,
+        last_outcome: Some("server_error".to_string()),
+        last_status_code: Some(502),
+        last_error: Some("status=502 body=bad gateway".to_string()),
+        last_probe_at: Some(100),
+        quarantined_at: None,
+        recovered_at: None,
+        updated_at: 100,
+    };
+    storage
+        .save_account_health_state(&state)
+        .expect("insert state");
+    state.consecutive_failures = 3;
+    state.quarantined_at = Some(200);
+    state.updated_at = 200;
+    storage
+        .save_account_health_state(&state)
+        .expect("update state");
+
+    assert_eq!(
+        storage
+            .get_account_health_state("acc-1")
+            .expect("read state"),
+        Some(state.clone())
+    );
+    assert_eq!(
+        storage.list_account_health_states().expect("list states"),
+        vec![state]
+    );
+}
+
+#[test]
+fn health_probe_history_is_newest_first_and_prunable() {
+    let storage = storage_with_account("acc-1");
+    for probed_at in 1..=5 {
+        storage
+            .insert_account_health_probe(&probe("acc-1", "success", probed_at))
+            .expect("insert probe");
+    }
+
+    let history = storage
+        .list_account_health_probes("acc-1", 3)
+        .expect("list probes");
+    assert_eq!(
+        history
+            .iter()
+            .map(|item| item.probed_at)
+            .collect::<Vec<_>>(),
+        vec![5, 4, 3]
+    );
+
+    let removed = storage
+        .prune_account_health_probes("acc-1", 2)
+        .expect("prune probes");
+    assert_eq!(removed, 3);
+    let remaining = storage
+        .list_account_health_probes("acc-1", 10)
+        .expect("list remaining probes");
+    assert_eq!(
+        remaining
+            .iter()
+            .map(|item| item.probed_at)
+            .collect::<Vec<_>>(),
+        vec![5, 4]
+    );
+}
+
+#[test]
+fn health_rows_are_removed_with_account() {
+    let mut storage = storage_with_account("acc-1");
+    storage
+        .insert_account_health_probe(&probe("acc-1", "auth_failure", 10))
+        .expect("insert probe");
+    storage
+        .save_account_health_state(&AccountHealthState {
+            account_id: "acc-1".to_string(),
+            consecutive_failures: 1,
+            consecutive_successes: 0,
+            last_outcome: Some("auth_failure".to_string()),
+            last_status_code: Some(401),
+            last_error: None,
+            last_probe_at: Some(10),
+            quarantined_at: None,
+            recovered_at: None,
+            updated_at: 10,
+        })
+        .expect("save state");
+
+    storage.delete_account("acc-1").expect("delete account");
+
+    assert!(storage
+        .list_account_health_probes("acc-1", 10)
+        .expect("list probes")
+        .is_empty());
+    assert!(storage
+        .get_account_health_state("acc-1")
+        .expect("read state")
+        .is_none());
+}
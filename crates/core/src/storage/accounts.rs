@@ -986,6 +986,25 @@ impl Storage {
         Ok(out)
     }
 
+    /// 列出被健康探测隔离的账号及其 token，供探测调度器继续探测以便自动恢复。
+    pub fn list_quarantined_accounts_with_tokens(&self) -> Result<Vec<(Account, Token)>> {
+        let sql = format!(
+            "SELECT
+               {account_select},
+               {token_select}
+             FROM accounts a
+             JOIN tokens t
+               ON t.account_id = a.id
+             WHERE LOWER(TRIM(COALESCE(a.status, ''))) = 'quarantined'
+             ORDER BY a.sort ASC, a.updated_at DESC, a.id ASC",
+            account_select = account_select_columns("a"),
+            token_select = token_select_columns("t"),
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_gateway_candidate_row)?;
+        rows.collect()
+    }
+
     pub fn find_account_with_token_by_id(
         &self,
         account_id: &str,
@@ -1320,6 +1339,14 @@ impl Storage {
             "DELETE FROM proxy_diagnostics_history WHERE account_id = ?1",
             [account_id],
         )?;
+        tx.execute(
+            "DELETE FROM account_health_probes WHERE account_id = ?1",
+            [account_id],
+        )?;
+        tx.execute(
+            "DELETE FROM account_health_states WHERE account_id = ?1",
+            [account_id],
+        )?;
         tx.execute(delete_account_by_id_sql(), [account_id])?;
         tx.commit()?;
         Ok(())
@@ -1341,6 +1368,8 @@ impl Storage {
             delete_accounts_from_table(&tx, "usage_snapshots", "account_id", chunk)?;
             delete_accounts_from_table(&tx, "events", "account_id", chunk)?;
             delete_accounts_from_table(&tx, "conversation_bindings", "account_id", chunk)?;
+            delete_accounts_from_table(&tx, "account_health_probes", "account_id", chunk)?;
+            delete_accounts_from_table(&tx, "account_health_states", "account_id", chunk)?;
             deleted += delete_accounts_from_table(&tx, "accounts", "id", chunk)?;
         }
         tx.commit()?;
@@ -2304,7 +2333,7 @@ fn available_usage_clause(usage_alias: &str) -> String {
 /// 返回函数执行结果
 fn gateway_account_usage_filter_clause(account_alias: &str, usage_alias: &str) -> String {
     format!(
-        "LOWER(TRIM(COALESCE({account_alias}.status, ''))) NOT IN ('inactive', 'disabled', 'unavailable', 'limited', 'banned')
+        "LOWER(TRIM(COALESCE({account_alias}.status, ''))) NOT IN ('inactive', 'disabled', 'unavailable', 'limited', 'banned', 'quarantined')
          AND ({usage_alias}.account_id IS NULL OR ({}))",
         available_usage_clause(usage_alias)
     )
@@ -2256,6 +2256,41 @@ fn list_gateway_candidates_only_returns_active_available_accounts() {
     );
 }
 
+#[test]
+fn quarantined_accounts_are_excluded_from_gateway_candidates_but_listed_for_probes() {
+    let storage = Storage::open_in_memory().expect("open");
+    storage.init().expect("init");
+    let now = now_ts();
+
+    let active = sample_account("acc-active", "active", now);
+    let quarantined = sample_account("acc-quarantined", "quarantined", now);
+    for account in [&active, &quarantined] {
+        storage.insert_account(account).expect("insert account");
+        storage
+            .insert_token(&sample_token(account.id.as_str(), now))
+            .expect("insert token");
+    }
+
+    let candidate_ids = storage
+        .list_gateway_candidates()
+        .expect("list gateway candidates")
+        .into_iter()
+        .map(|(account, _)| account.id)
+        .collect::<Vec<_>>();
+    assert_eq!(candidate_ids, vec!["acc-active".to_string()]);
+
+    let quarantined_ids = storage
+        .list_quarantined_accounts_with_tokens()
+        .expect("list quarantined accounts")
+        .into_iter()
+        .map(|(account, token)| (account.id, token.access_token))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        quarantined_ids,
+        vec![("acc-quarantined".to_string(), "access".to_string())]
+    );
+}
+
 #[test]
 fn list_gateway_candidates_uses_account_id_as_stable_final_tiebreaker() {
     let storage = Storage::open_in_memory().expect("open");
@@ -584,6 +584,10 @@ impl Storage {
             "DELETE FROM model_routes WHERE source_kind='aggregate_api' AND source_id=?1",
             [api_id],
         )?;
+        tx.execute(
+            "DELETE FROM aggregate_api_supplier_models WHERE supplier_key=?1",
+            [api_id],
+        )?;
         tx.execute(delete_aggregate_api_by_id_sql(), [api_id])?;
         tx.commit()?;
         Ok(())
@@ -929,6 +933,10 @@ impl Storage {
         ))
     }
 
+    pub(super) fn ensure_aggregate_api_supplier_model_deployment_column(&self) -> Result<()> {
+        self.ensure_column("aggregate_api_supplier_models", "deployment", "TEXT")
+    }
+
     pub fn list_aggregate_api_supplier_models(
         &self,
         supplier_key: Option<&str>,
@@ -964,11 +972,12 @@ impl Storage {
         self.conn.execute(
             "INSERT INTO aggregate_api_supplier_models (
                 supplier_key, provider_type, upstream_model, display_name,
-                status, created_at, updated_at
-             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                status, created_at, updated_at, deployment
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
              ON CONFLICT(supplier_key, provider_type, upstream_model) DO UPDATE SET
                 display_name = excluded.display_name,
                 status = excluded.status,
+                deployment = excluded.deployment,
                 updated_at = excluded.updated_at",
             params![
                 &model.supplier_key,
@@ -978,6 +987,7 @@ impl Storage {
                 &model.status,
                 model.created_at,
                 model.updated_at,
+                &model.deployment,
             ],
         )?;
         Ok(())
@@ -1113,6 +1123,7 @@ fn map_aggregate_api_supplier_model_row(row: &Row<'_>) -> Result<AggregateApiSup
         provider_type: row.get(1)?,
         upstream_model: row.get(2)?,
         display_name: row.get(3)?,
+        deployment: row.get(7)?,
         status: row.get(4)?,
         created_at: row.get(5)?,
         updated_at: row.get(6)?,
@@ -1307,6 +1318,8 @@ fn aggregate_api_provider_type_condition(provider_type: &str) -> Option<(String,
 
     const CLAUDE_NATIVE_ALIASES: &[&str] =
         &["claude", "anthropic", "anthropic_native", "claude_code"];
+    // 中文注释：Bedrock 承载 Anthropic Messages 协议，归入 claude 候选池。
+    const BEDROCK_ALIASES: &[&str] = &["bedrock", "aws_bedrock"];
     const COMPATIBLE_PROVIDER: &str = "compatible";
     const GEMINI_ALIASES: &[&str] = &[
         "gemini",
@@ -1320,6 +1333,7 @@ fn aggregate_api_provider_type_condition(provider_type: &str) -> Option<(String,
         "claude" | "anthropic" | "anthropic_native" | "claude_code" => {
             let aliases = CLAUDE_NATIVE_ALIASES
                 .iter()
+                .chain(BEDROCK_ALIASES.iter())
                 .copied()
                 .chain(std::iter::once(COMPATIBLE_PROVIDER))
                 .map(|value| Value::Text(value.to_string()))
@@ -1340,6 +1354,16 @@ fn aggregate_api_provider_type_condition(provider_type: &str) -> Option<(String,
                     .collect(),
             ))
         }
+        "bedrock" | "aws_bedrock" => {
+            let placeholders = vec!["?"; BEDROCK_ALIASES.len()].join(", ");
+            Some((
+                format!("{AGGREGATE_API_NORMALIZED_PROVIDER_SQL} IN ({placeholders})"),
+                BEDROCK_ALIASES
+                    .iter()
+                    .map(|value| Value::Text((*value).to_string()))
+                    .collect(),
+            ))
+        }
         COMPATIBLE_PROVIDER => Some((
             format!("{AGGREGATE_API_NORMALIZED_PROVIDER_SQL} = ?"),
             vec![Value::Text(COMPATIBLE_PROVIDER.to_string())],
@@ -1347,6 +1371,7 @@ fn aggregate_api_provider_type_condition(provider_type: &str) -> Option<(String,
         _ => {
             let aliases = CLAUDE_NATIVE_ALIASES
                 .iter()
+                .chain(BEDROCK_ALIASES.iter())
                 .chain(GEMINI_ALIASES.iter())
                 .map(|value| Value::Text((*value).to_string()))
                 .collect::<Vec<_>>();
@@ -2540,12 +2565,16 @@ mod supplier_model_tests {
         storage
             .ensure_aggregate_api_supplier_model_tables()
             .expect("ensure tables");
+        storage
+            .ensure_aggregate_api_supplier_model_deployment_column()
+            .expect("ensure deployment column");
         let now = now_ts();
         let model = AggregateApiSupplierModel {
             supplier_key: "test-supplier".to_string(),
             provider_type: "codex".to_string(),
             upstream_model: "provider-model".to_string(),
             display_name: Some("Provider Model".to_string()),
+            deployment: None,
             status: "available".to_string(),
             created_at: now,
             updated_at: now,
@@ -2570,6 +2599,7 @@ mod supplier_model_tests {
 
         let mut disabled = model.clone();
         disabled.status = "disabled".to_string();
+        disabled.deployment = Some("prod-deployment".to_string());
         disabled.updated_at = now + 1;
         storage
             .upsert_aggregate_api_supplier_model(&disabled)
@@ -2578,6 +2608,7 @@ mod supplier_model_tests {
             .list_aggregate_api_supplier_models(Some("test-supplier"), Some("codex"))
             .expect("list updated models");
         assert_eq!(items[0].status, "disabled");
+        assert_eq!(items[0].deployment.as_deref(), Some("prod-deployment"));
 
         storage
             .delete_aggregate_api_supplier_model("test-supplier", "codex", "provider-model")
@@ -2594,6 +2625,9 @@ mod supplier_model_tests {
         storage
             .ensure_aggregate_api_supplier_model_tables()
             .expect("ensure tables");
+        storage
+            .ensure_aggregate_api_supplier_model_deployment_column()
+            .expect("ensure deployment column");
 
         let sql = aggregate_api_supplier_models_list_sql(true, true);
         let details = collect_query_plan_details_with_params(
@@ -209,7 +209,7 @@ pub(super) fn aggregate_api_supplier_models_list_sql(
     };
     format!(
         "SELECT supplier_key, provider_type, upstream_model, display_name,
-                status, created_at, updated_at
+                status, created_at, updated_at, deployment
          FROM aggregate_api_supplier_models{where_clause}
          ORDER BY supplier_key ASC, provider_type ASC, upstream_model ASC"
     )
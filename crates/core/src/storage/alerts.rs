@@ -0,0 +1,235 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{AlertChannel, AlertHistoryRecord, AlertRule, Storage};
+
+fn alert_channel_columns() -> &'static str {
+    "id, name, kind, config_json, enabled, created_at, updated_at"
+}
+
+fn alert_rule_columns() -> &'static str {
+    "id, name, condition, severity, threshold, cooldown_secs, channel_ids_json, enabled,
+     created_at, updated_at"
+}
+
+fn alert_history_columns() -> &'static str {
+    "id, rule_id, rule_name, condition, severity, subject, message, delivery_status,
+     delivery_json, created_at"
+}
+
+fn map_alert_channel(row: &Row<'_>) -> Result<AlertChannel> {
+    Ok(AlertChannel {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: row.get(2)?,
+        config_json: row.get(3)?,
+        enabled: row.get::<_, i64>(4)? != 0,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn map_alert_rule(row: &Row<'_>) -> Result<AlertRule> {
+    Ok(AlertRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        condition: row.get(2)?,
+        severity: row.get(3)?,
+        threshold: row.get(4)?,
+        cooldown_secs: row.get(5)?,
+        channel_ids_json: row.get(6)?,
+        enabled: row.get::<_, i64>(7)? != 0,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn map_alert_history(row: &Row<'_>) -> Result<AlertHistoryRecord> {
+    Ok(AlertHistoryRecord {
+        id: row.get(0)?,
+        rule_id: row.get(1)?,
+        rule_name: row.get(2)?,
+        condition: row.get(3)?,
+        severity: row.get(4)?,
+        subject: row.get(5)?,
+        message: row.get(6)?,
+        delivery_status: row.get(7)?,
+        delivery_json: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+impl Storage {
+    pub fn list_alert_channels(&self) -> Result<Vec<AlertChannel>> {
+        let sql = format!(
+            "SELECT {}
+             FROM alert_channels
+             ORDER BY created_at ASC, id ASC",
+            alert_channel_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_alert_channel)?;
+        rows.collect()
+    }
+
+    pub fn find_alert_channel(&self, id: &str) -> Result<Option<AlertChannel>> {
+        let sql = format!(
+            "SELECT {}
+             FROM alert_channels
+             WHERE id = ?1
+             LIMIT 1",
+            alert_channel_columns()
+        );
+        self.conn
+            .query_row(&sql, [id], map_alert_channel)
+            .optional()
+    }
+
+    /// 按 id 写入通知渠道；已存在时保留原创建时间。
+    pub fn upsert_alert_channel(&self, channel: &AlertChannel) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO alert_channels (
+                id, name, kind, config_json, enabled, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                kind = excluded.kind,
+                config_json = excluded.config_json,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            params![
+                channel.id,
+                channel.name,
+                channel.kind,
+                channel.config_json,
+                channel.enabled as i64,
+                channel.created_at,
+                channel.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_alert_channel(&self, id: &str) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM alert_channels WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn list_alert_rules(&self) -> Result<Vec<AlertRule>> {
+        let sql = format!(
+            "SELECT {}
+             FROM alert_rules
+             ORDER BY created_at ASC, id ASC",
+            alert_rule_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_alert_rule)?;
+        rows.collect()
+    }
+
+    pub fn find_alert_rule(&self, id: &str) -> Result<Option<AlertRule>> {
+        let sql = format!(
+            "SELECT {}
+             FROM alert_rules
+             WHERE id = ?1
+             LIMIT 1",
+            alert_rule_columns()
+        );
+        self.conn.query_row(&sql, [id], map_alert_rule).optional()
+    }
+
+    /// 按 id 写入告警规则；已存在时保留原创建时间。
+    pub fn upsert_alert_rule(&self, rule: &AlertRule) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO alert_rules (
+                id, name, condition, severity, threshold, cooldown_secs, channel_ids_json,
+                enabled, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                condition = excluded.condition,
+                severity = excluded.severity,
+                threshold = excluded.threshold,
+                cooldown_secs = excluded.cooldown_secs,
+                channel_ids_json = excluded.channel_ids_json,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            params![
+                rule.id,
+                rule.name,
+                rule.condition,
+                rule.severity,
+                rule.threshold,
+                rule.cooldown_secs,
+                rule.channel_ids_json,
+                rule.enabled as i64,
+                rule.created_at,
+                rule.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_alert_rule(&self, id: &str) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM alert_rules WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn insert_alert_history(&self, record: &AlertHistoryRecord) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO alert_history (
+                rule_id, rule_name, condition, severity, subject, message, delivery_status,
+                delivery_json, created_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                record.rule_id,
+                record.rule_name,
+                record.condition,
+                record.severity,
+                record.subject,
+                record.message,
+                record.delivery_status,
+                record.delivery_json,
+                record.created_at,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 最近记录优先；`rule_id` 为空时返回全部规则的历史。
+    pub fn list_alert_history(
+        &self,
+        rule_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AlertHistoryRecord>> {
+        let sql = format!(
+            "SELECT {}
+             FROM alert_history
+             WHERE (?1 IS NULL OR rule_id = ?1)
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?2",
+            alert_history_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![rule_id, limit.max(1)], map_alert_history)?;
+        rows.collect()
+    }
+
+    /// 同一规则、同一对象最近一次触发时间，用于冷却判断。
+    pub fn latest_alert_fired_at(&self, rule_id: &str, subject: &str) -> Result<Option<i64>> {
+        self.conn.query_row(
+            "SELECT MAX(created_at)
+             FROM alert_history
+             WHERE rule_id = ?1 AND subject = ?2",
+            [rule_id, subject],
+            |row| row.get(0),
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "alerts_tests.rs"]
+mod tests;
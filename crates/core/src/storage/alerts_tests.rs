@@ -0,0 +1,136 @@
+use super::*;
+
+fn rule(id: &str, created_at: i64) -> AlertRule {
+    AlertRule {
+        id: id.to_string(),
+        name: format!("rule {id}"),
+        condition: "wallet_low".to_string(),
+        severity: "warning".to_string(),
+        threshold: Some(1.5),
+        cooldown_secs: 3600,
+        channel_ids_json: Some("[\"ops\"]".to_string()),
+        enabled: true,
+        created_at,
+        updated_at: created_at,
+    }
+}
+
+fn history(rule_id: &str, subject: &str, created_at: i64) -> AlertHistoryRecord {
+    AlertHistoryRecord {
+        id: 0,
+        rule_id: rule_id.to_string(),
+        rule_name: format!("rule {rule_id}"),
+        condition: "wallet_low".to_string(),
+        severity: "warning".to_string(),
+        subject: subject.to_string(),
+        message: format!("{subject} is low"),
+        delivery_status: "sent".to_string(),
+        delivery_json: None,
+        created_at,
+    }
+}
+
+#[test]
+fn alert_rules_and_channels_upsert_keep_created_at() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+
+    storage
+        .upsert_alert_channel(&AlertChannel {
+            id: "ops".to_string(),
+            name: "ops".to_string(),
+            kind: "webhook".to_string(),
+            config_json: "{\"url\":\"https://hooks.example.com/a\"}".to_string(),
+            enabled: true,
+            created_at: 100,
+            updated_at: 100,
+        })
+        .expect("insert channel");
+    let mut channel = storage
+        .find_alert_channel("ops")
+        .expect("find channel")
+        .expect("channel exists");
+    channel.enabled = false;
+    channel.created_at = 200;
+    channel.updated_at = 200;
+    storage
+        .upsert_alert_channel(&channel)
+        .expect("update channel");
+
+    let channels = storage.list_alert_channels().expect("list channels");
+    assert_eq!(channels.len(), 1);
+    assert!(!channels[0].enabled);
+    assert_eq!(channels[0].created_at, 100);
+    assert_eq!(channels[0].updated_at, 200);
+
+    storage
+        .upsert_alert_rule(&rule("b", 100))
+        .expect("insert b");
+    storage
+        .upsert_alert_rule(&rule("a", 150))
+        .expect("insert a");
+    let mut updated = rule("b", 300);
+    updated.threshold = None;
+    updated.enabled = false;
+    storage.upsert_alert_rule(&updated).expect("update b");
+
+    let rules = storage.list_alert_rules().expect("list rules");
+    assert_eq!(
+        rules
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b", "a"]
+    );
+    assert_eq!(rules[0].threshold, None);
+    assert!(!rules[0].enabled);
+    assert_eq!(rules[0].created_at, 100);
+
+    assert!(storage.delete_alert_rule("b").expect("delete b"));
+    assert!(!storage.delete_alert_rule("b").expect("delete missing b"));
+    assert!(storage.delete_alert_channel("ops").expect("delete channel"));
+}
+
+#[test]
+fn alert_history_lists_latest_first_and_tracks_cooldown_per_subject() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+
+    storage
+        .insert_alert_history(&history("wallet", "wallet-1", 100))
+        .expect("insert first");
+    storage
+        .insert_alert_history(&history("wallet", "wallet-1", 300))
+        .expect("insert second");
+    storage
+        .insert_alert_history(&history("wallet", "wallet-2", 200))
+        .expect("insert other subject");
+    storage
+        .insert_alert_history(&history("banned", "acc-1", 400))
+        .expect("insert other rule");
+
+    assert_eq!(
+        storage
+            .latest_alert_fired_at("wallet", "wallet-1")
+            .expect("latest wallet-1"),
+        Some(300)
+    );
+    assert_eq!(
+        storage
+            .latest_alert_fired_at("wallet", "wallet-3")
+            .expect("latest wallet-3"),
+        None
+    );
+
+    let all = storage.list_alert_history(None, 10).expect("list all");
+    assert_eq!(
+        all.iter().map(|item| item.created_at).collect::<Vec<_>>(),
+        vec![400, 300, 200, 100]
+    );
+    let wallet = storage
+        .list_alert_history(Some("wallet"), 2)
+        .expect("list wallet");
+    assert_eq!(wallet.len(), 2);
+    assert_eq!(wallet[0].subject, "wallet-1");
+    assert_eq!(wallet[1].subject, "wallet-2");
+}
@@ -1,7 +1,21 @@
 use rusqlite::{params_from_iter, OptionalExtension, Result, Row};
 
 use super::api_key_quota_limits::delete_api_key_quota_limit_by_key_sql;
+use super::content_policies::delete_api_key_content_policy_by_key_sql;
+use super::gateway_background_responses::{
+    delete_gateway_background_response_events_by_key_sql,
+    delete_gateway_background_responses_by_key_sql,
+};
+use super::gateway_batches::{
+    delete_gateway_batch_items_by_key_sql, delete_gateway_batches_by_key_sql,
+    delete_gateway_files_by_key_sql,
+};
+use super::gateway_responses::delete_gateway_responses_by_key_sql;
 use super::key_id_filters::{key_id_in_clause, normalize_key_ids, SQLITE_IN_CLAUSE_BATCH_SIZE};
+use super::response_cache::{
+    delete_api_key_response_cache_policy_by_key_sql, delete_response_cache_entries_by_key_sql,
+};
+use super::structured_output_policies::delete_api_key_structured_output_policy_by_key_sql;
 use super::{
     now_ts, ApiKey, ApiKeyCodexProfileCandidate, ApiKeyGatewayAuth, ApiKeyListSummary,
     ApiKeyProfileConfig, ApiKeyQuotaSummary, ApiKeyStatus, Storage,
@@ -403,6 +417,35 @@ impl Storage {
         Ok(())
     }
 
+    /// 函数 `rotate_api_key_secret`
+    ///
+    /// 轮换平台 Key：哈希与密钥明文在同一事务内替换，任一写入失败整体回滚；Key 不存在时返回 false。
+    pub fn rotate_api_key_secret(
+        &self,
+        key_id: &str,
+        key_hash: &str,
+        key_value: &str,
+    ) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE api_keys SET key_hash = ?1 WHERE id = ?2",
+            (key_hash, key_id),
+        )?;
+        if updated == 0 {
+            return Ok(false);
+        }
+        tx.execute(
+            "INSERT INTO api_key_secrets (key_id, key_value, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT(key_id) DO UPDATE SET
+               key_value = excluded.key_value,
+               updated_at = excluded.updated_at",
+            (key_id, key_value, now_ts()),
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
     /// 函数 `update_api_key_rotation_config`
     ///
     /// 作者: gaohongshun
@@ -645,6 +688,30 @@ impl Storage {
     pub fn delete_api_key(&self, key_id: &str) -> Result<()> {
         self.conn
             .execute(delete_api_key_quota_limit_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_api_key_response_cache_policy_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_response_cache_entries_by_key_sql(), [key_id])?;
+        self.conn.execute(
+            delete_api_key_structured_output_policy_by_key_sql(),
+            [key_id],
+        )?;
+        self.conn
+            .execute(delete_api_key_content_policy_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_gateway_responses_by_key_sql(), [key_id])?;
+        self.conn.execute(
+            delete_gateway_background_response_events_by_key_sql(),
+            [key_id],
+        )?;
+        self.conn
+            .execute(delete_gateway_background_responses_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_gateway_batch_items_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_gateway_batches_by_key_sql(), [key_id])?;
+        self.conn
+            .execute(delete_gateway_files_by_key_sql(), [key_id])?;
         self.conn
             .execute(delete_api_key_secret_by_id_sql(), [key_id])?;
         self.conn.execute(delete_api_key_by_id_sql(), [key_id])?;
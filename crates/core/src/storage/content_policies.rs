@@ -0,0 +1,143 @@
+use rusqlite::{params, params_from_iter, types::Value, OptionalExtension, Result, Row};
+
+use super::{ApiKeyContentPolicy, RequestContentPolicyMatches, Storage};
+
+fn map_policy(row: &Row<'_>) -> Result<ApiKeyContentPolicy> {
+    Ok(ApiKeyContentPolicy {
+        key_id: row.get(0)?,
+        enabled: row.get::<_, i64>(1)? != 0,
+        action: row.get(2)?,
+        source_actions_json: row.get(3)?,
+        detectors_json: row.get(4)?,
+        custom_rules_json: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn map_matches(row: &Row<'_>) -> Result<RequestContentPolicyMatches> {
+    Ok(RequestContentPolicyMatches {
+        request_log_id: row.get(0)?,
+        trace_id: row.get(1)?,
+        matches_json: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+pub(super) fn delete_api_key_content_policy_by_key_sql() -> &'static str {
+    "DELETE FROM api_key_content_policies WHERE key_id = ?1"
+}
+
+impl Storage {
+    pub fn find_api_key_content_policy(&self, key_id: &str) -> Result<Option<ApiKeyContentPolicy>> {
+        self.conn
+            .query_row(
+                "SELECT key_id, enabled, action, source_actions_json, detectors_json,
+                        custom_rules_json, created_at, updated_at
+                 FROM api_key_content_policies
+                 WHERE key_id = ?1
+                 LIMIT 1",
+                [key_id],
+                map_policy,
+            )
+            .optional()
+    }
+
+    /// 按平台 Key 写入出站内容策略；已存在时保留原创建时间。
+    pub fn upsert_api_key_content_policy(&self, policy: &ApiKeyContentPolicy) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO api_key_content_policies (
+                key_id, enabled, action, source_actions_json, detectors_json,
+                custom_rules_json, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(key_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                action = excluded.action,
+                source_actions_json = excluded.source_actions_json,
+                detectors_json = excluded.detectors_json,
+                custom_rules_json = excluded.custom_rules_json,
+                updated_at = excluded.updated_at",
+            params![
+                policy.key_id,
+                policy.enabled as i64,
+                policy.action,
+                policy.source_actions_json,
+                policy.detectors_json,
+                policy.custom_rules_json,
+                policy.created_at,
+                policy.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_request_content_policy_matches(
+        &self,
+        matches: &RequestContentPolicyMatches,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO request_content_policy_matches (
+                request_log_id, trace_id, matches_json, created_at
+             ) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(request_log_id) DO UPDATE SET
+                trace_id = excluded.trace_id,
+                matches_json = excluded.matches_json,
+                created_at = excluded.created_at",
+            params![
+                matches.request_log_id,
+                &matches.trace_id,
+                &matches.matches_json,
+                matches.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn find_request_content_policy_matches_by_trace_id(
+        &self,
+        trace_id: &str,
+    ) -> Result<Option<RequestContentPolicyMatches>> {
+        self.conn
+            .query_row(
+                "SELECT request_log_id, trace_id, matches_json, created_at
+                 FROM request_content_policy_matches
+                 WHERE trace_id = ?1
+                 ORDER BY request_log_id DESC
+                 LIMIT 1",
+                [trace_id.trim()],
+                map_matches,
+            )
+            .optional()
+    }
+
+    pub fn find_request_content_policy_matches_by_trace_id_for_key_ids(
+        &self,
+        trace_id: &str,
+        key_ids: &[String],
+    ) -> Result<Option<RequestContentPolicyMatches>> {
+        if key_ids.is_empty() {
+            return Ok(None);
+        }
+        let placeholders = (0..key_ids.len())
+            .map(|index| format!("?{}", index + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT m.request_log_id, m.trace_id, m.matches_json, m.created_at
+             FROM request_content_policy_matches m
+             JOIN request_logs r ON r.id = m.request_log_id
+             WHERE m.trace_id = ?1 AND r.key_id IN ({placeholders})
+             ORDER BY m.request_log_id DESC
+             LIMIT 1"
+        );
+        let values = std::iter::once(Value::Text(trace_id.trim().to_string()))
+            .chain(key_ids.iter().map(|key_id| Value::Text(key_id.clone())));
+        self.conn
+            .query_row(&sql, params_from_iter(values), map_matches)
+            .optional()
+    }
+}
+
+#[cfg(test)]
+#[path = "content_policies_tests.rs"]
+mod tests;
@@ -0,0 +1,124 @@
+use super::*;
+use crate::storage::{ApiKey, RequestLog, RequestTokenStat};
+
+fn insert_api_key(storage: &Storage, key_id: &str) {
+    storage
+        .insert_api_key(&ApiKey {
+            id: key_id.to_string(),
+            name: Some(key_id.to_string()),
+            model_slug: None,
+            reasoning_effort: None,
+            service_tier: None,
+            aggregate_api_id: None,
+            account_plan_filter: None,
+            aggregate_api_url: None,
+            key_hash: format!("hash-{key_id}"),
+            status: "enabled".to_string(),
+            rotation_strategy: "account_rotation".to_string(),
+            client_type: "codex".to_string(),
+            protocol_type: "openai_compat".to_string(),
+            auth_scheme: "authorization_bearer".to_string(),
+            upstream_base_url: None,
+            static_headers_json: None,
+            created_at: 100,
+            last_used_at: None,
+        })
+        .expect("insert api key");
+}
+
+#[test]
+fn content_policy_upsert_keeps_created_at_and_is_removed_with_key() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    insert_api_key(&storage, "key-a");
+
+    let mut policy = ApiKeyContentPolicy {
+        key_id: "key-a".to_string(),
+        enabled: true,
+        action: "redact".to_string(),
+        source_actions_json: "{}".to_string(),
+        detectors_json: "[]".to_string(),
+        custom_rules_json: "[]".to_string(),
+        created_at: 100,
+        updated_at: 100,
+    };
+    storage
+        .upsert_api_key_content_policy(&policy)
+        .expect("insert policy");
+    policy.action = "block".to_string();
+    policy.source_actions_json = r#"{"openai_account":"log"}"#.to_string();
+    policy.created_at = 200;
+    policy.updated_at = 200;
+    storage
+        .upsert_api_key_content_policy(&policy)
+        .expect("update policy");
+
+    let stored = storage
+        .find_api_key_content_policy("key-a")
+        .expect("read policy")
+        .expect("policy exists");
+    assert_eq!(stored.action, "block");
+    assert_eq!(stored.source_actions_json, r#"{"openai_account":"log"}"#);
+    assert_eq!(stored.created_at, 100);
+    assert_eq!(stored.updated_at, 200);
+
+    storage.delete_api_key("key-a").expect("delete key");
+    assert!(storage
+        .find_api_key_content_policy("key-a")
+        .expect("read policy after delete")
+        .is_none());
+}
+
+#[test]
+fn content_policy_matches_round_trip_by_trace_id_and_key_scope() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    let (request_log_id, _) = storage
+        .insert_request_log_with_token_stat(
+            &RequestLog {
+                trace_id: Some("trc-policy".to_string()),
+                key_id: Some("gk-policy".to_string()),
+                request_path: "/v1/responses".to_string(),
+                method: "POST".to_string(),
+                status_code: Some(400),
+                created_at: 100,
+                ..Default::default()
+            },
+            &RequestTokenStat {
+                key_id: Some("gk-policy".to_string()),
+                created_at: 100,
+                ..Default::default()
+            },
+        )
+        .expect("insert request log");
+
+    storage
+        .insert_request_content_policy_matches(&RequestContentPolicyMatches {
+            request_log_id,
+            trace_id: Some("trc-policy".to_string()),
+            matches_json: r#"[{"detector":"email","action":"block","count":1}]"#.to_string(),
+            created_at: 100,
+        })
+        .expect("insert matches");
+
+    let found = storage
+        .find_request_content_policy_matches_by_trace_id(" trc-policy ")
+        .expect("find matches")
+        .expect("matches exist");
+    assert_eq!(found.request_log_id, request_log_id);
+    assert!(found.matches_json.contains("\"email\""));
+    assert!(storage
+        .find_request_content_policy_matches_by_trace_id_for_key_ids(
+            "trc-policy",
+            &["gk-policy".to_string()]
+        )
+        .expect("find scoped")
+        .is_some());
+    assert!(storage
+        .find_request_content_policy_matches_by_trace_id_for_key_ids(
+            "trc-policy",
+            &["gk-other".to_string()]
+        )
+        .expect("find scoped other")
+        .is_none());
+}
@@ -0,0 +1,183 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{GatewayBackgroundResponse, GatewayBackgroundResponseEvent, Storage};
+
+const ACTIVE_STATUSES_SQL: &str = "('queued', 'in_progress')";
+
+fn map_background_response(row: &Row<'_>) -> Result<GatewayBackgroundResponse> {
+    Ok(GatewayBackgroundResponse {
+        response_id: row.get(0)?,
+        key_id: row.get(1)?,
+        status: row.get(2)?,
+        response_json: row.get(3)?,
+        last_sequence_number: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn map_background_event(row: &Row<'_>) -> Result<GatewayBackgroundResponseEvent> {
+    Ok(GatewayBackgroundResponseEvent {
+        response_id: row.get(0)?,
+        sequence_number: row.get(1)?,
+        event_json: row.get(2)?,
+    })
+}
+
+pub(super) fn delete_gateway_background_response_events_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_background_response_events
+     WHERE response_id IN (
+        SELECT response_id FROM gateway_background_responses WHERE key_id = ?1
+     )"
+}
+
+pub(super) fn delete_gateway_background_responses_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_background_responses WHERE key_id = ?1"
+}
+
+impl Storage {
+    pub fn insert_gateway_background_response(
+        &self,
+        response: &GatewayBackgroundResponse,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO gateway_background_responses (
+                response_id, key_id, status, response_json, last_sequence_number,
+                created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                response.response_id,
+                response.key_id,
+                response.status,
+                response.response_json,
+                response.last_sequence_number,
+                response.created_at,
+                response.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 按平台 Key 读取后台任务；不同 Key 之间的 response id 互不可见。
+    pub fn find_gateway_background_response(
+        &self,
+        key_id: &str,
+        response_id: &str,
+    ) -> Result<Option<GatewayBackgroundResponse>> {
+        self.conn
+            .query_row(
+                "SELECT response_id, key_id, status, response_json, last_sequence_number,
+                        created_at, updated_at
+                 FROM gateway_background_responses
+                 WHERE response_id = ?1 AND key_id = ?2
+                 LIMIT 1",
+                params![response_id, key_id],
+                map_background_response,
+            )
+            .optional()
+    }
+
+    /// 更新状态与快照；已进入终态的任务不会再被覆盖，返回值表示是否实际更新。
+    pub fn update_gateway_background_response(
+        &self,
+        response_id: &str,
+        status: &str,
+        response_json: &str,
+        updated_at: i64,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            &format!(
+                "UPDATE gateway_background_responses
+                 SET status = ?2, response_json = ?3, updated_at = ?4
+                 WHERE response_id = ?1 AND status IN {ACTIVE_STATUSES_SQL}"
+            ),
+            params![response_id, status, response_json, updated_at],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn append_gateway_background_response_event(
+        &self,
+        event: &GatewayBackgroundResponseEvent,
+        updated_at: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO gateway_background_response_events (
+                response_id, sequence_number, event_json
+             ) VALUES (?1, ?2, ?3)",
+            params![event.response_id, event.sequence_number, event.event_json],
+        )?;
+        self.conn.execute(
+            "UPDATE gateway_background_responses
+             SET last_sequence_number = MAX(last_sequence_number, ?2), updated_at = ?3
+             WHERE response_id = ?1",
+            params![event.response_id, event.sequence_number, updated_at],
+        )?;
+        Ok(())
+    }
+
+    /// 读取游标之后的事件，按序号升序返回。
+    pub fn list_gateway_background_response_events(
+        &self,
+        response_id: &str,
+        after_sequence_number: i64,
+        limit: usize,
+    ) -> Result<Vec<GatewayBackgroundResponseEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT response_id, sequence_number, event_json
+             FROM gateway_background_response_events
+             WHERE response_id = ?1 AND sequence_number > ?2
+             ORDER BY sequence_number ASC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![response_id, after_sequence_number, limit as i64],
+            map_background_event,
+        )?;
+        rows.collect()
+    }
+
+    pub fn delete_gateway_background_response(
+        &self,
+        key_id: &str,
+        response_id: &str,
+    ) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM gateway_background_responses WHERE response_id = ?1 AND key_id = ?2",
+            params![response_id, key_id],
+        )?;
+        if deleted > 0 {
+            self.conn.execute(
+                "DELETE FROM gateway_background_response_events WHERE response_id = ?1",
+                [response_id],
+            )?;
+        }
+        Ok(deleted > 0)
+    }
+
+    /// 清理 `updated_before` 之前进入终态的后台任务及其事件；进行中的任务保留。
+    pub fn prune_gateway_background_responses(&self, updated_before: i64) -> Result<usize> {
+        self.conn.execute(
+            &format!(
+                "DELETE FROM gateway_background_response_events
+                 WHERE response_id IN (
+                    SELECT response_id FROM gateway_background_responses
+                    WHERE updated_at < ?1 AND status NOT IN {ACTIVE_STATUSES_SQL}
+                 )"
+            ),
+            [updated_before],
+        )?;
+        let removed = self.conn.execute(
+            &format!(
+                "DELETE FROM gateway_background_responses
+                 WHERE updated_at < ?1 AND status NOT IN {ACTIVE_STATUSES_SQL}"
+            ),
+            [updated_before],
+        )?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+#[path = "gateway_background_responses_tests.rs"]
+mod tests;
@@ -0,0 +1,107 @@
+use super::*;
+
+fn background(
+    response_id: &str,
+    key_id: &str,
+    status: &str,
+    updated_at: i64,
+) -> GatewayBackgroundResponse {
+    GatewayBackgroundResponse {
+        response_id: response_id.to_string(),
+        key_id: key_id.to_string(),
+        status: status.to_string(),
+        response_json: format!(r#"{{"id":"{response_id}","status":"{status}"}}"#),
+        last_sequence_number: -1,
+        created_at: updated_at,
+        updated_at,
+    }
+}
+
+fn event(response_id: &str, sequence_number: i64) -> GatewayBackgroundResponseEvent {
+    GatewayBackgroundResponseEvent {
+        response_id: response_id.to_string(),
+        sequence_number,
+        event_json: format!(r#"{{"sequence_number":{sequence_number}}}"#),
+    }
+}
+
+#[test]
+fn background_response_events_follow_cursor_and_terminal_status_is_sticky() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .insert_gateway_background_response(&background("resp_bg_1", "key-a", "queued", 100))
+        .expect("insert background response");
+    for sequence_number in 0..3 {
+        storage
+            .append_gateway_background_response_event(&event("resp_bg_1", sequence_number), 101)
+            .expect("append event");
+    }
+
+    let events = storage
+        .list_gateway_background_response_events("resp_bg_1", 0, 10)
+        .expect("list events");
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.sequence_number)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert!(storage
+        .find_gateway_background_response("key-b", "resp_bg_1")
+        .expect("find other key")
+        .is_none());
+
+    assert!(storage
+        .update_gateway_background_response("resp_bg_1", "cancelled", "{}", 102)
+        .expect("cancel"));
+    assert!(!storage
+        .update_gateway_background_response("resp_bg_1", "completed", "{}", 103)
+        .expect("complete after cancel"));
+    let stored = storage
+        .find_gateway_background_response("key-a", "resp_bg_1")
+        .expect("find")
+        .expect("background response exists");
+    assert_eq!(stored.status, "cancelled");
+    assert_eq!(stored.last_sequence_number, 2);
+}
+
+#[test]
+fn prune_background_responses_keeps_active_runs() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .insert_gateway_background_response(&background("resp_done", "key-a", "completed", 100))
+        .expect("insert completed");
+    storage
+        .insert_gateway_background_response(&background(
+            "resp_running",
+            "key-a",
+            "in_progress",
+            100,
+        ))
+        .expect("insert running");
+    storage
+        .append_gateway_background_response_event(&event("resp_done", 0), 100)
+        .expect("append event");
+
+    assert_eq!(
+        storage
+            .prune_gateway_background_responses(200)
+            .expect("prune"),
+        1
+    );
+    assert!(storage
+        .find_gateway_background_response("key-a", "resp_done")
+        .expect("find done")
+        .is_none());
+    assert!(storage
+        .list_gateway_background_response_events("resp_done", -1, 10)
+        .expect("list events")
+        .is_empty());
+    assert!(storage
+        .find_gateway_background_response("key-a", "resp_running")
+        .expect("find running")
+        .is_some());
+}
@@ -0,0 +1,382 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{GatewayBatch, GatewayBatchItem, GatewayBatchItemCounts, GatewayFile, Storage};
+
+const TERMINAL_BATCH_STATUSES_SQL: &str = "('completed', 'failed', 'expired', 'cancelled')";
+const BATCH_COLUMNS_SQL: &str = "batch_id, key_id, endpoint, input_file_id, completion_window,
+    status, output_file_id, error_file_id, errors_json, metadata_json, created_at,
+    in_progress_at, finalizing_at, completed_at, failed_at, expired_at, cancelling_at,
+    cancelled_at, expires_at, updated_at";
+const BATCH_ITEM_COLUMNS_SQL: &str =
+    "batch_id, line_index, custom_id, url, body_json, status, status_code, result_json, updated_at";
+
+fn map_file(row: &Row<'_>) -> Result<GatewayFile> {
+    Ok(GatewayFile {
+        file_id: row.get(0)?,
+        key_id: row.get(1)?,
+        purpose: row.get(2)?,
+        filename: row.get(3)?,
+        bytes: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn map_batch(row: &Row<'_>) -> Result<GatewayBatch> {
+    Ok(GatewayBatch {
+        batch_id: row.get(0)?,
+        key_id: row.get(1)?,
+        endpoint: row.get(2)?,
+        input_file_id: row.get(3)?,
+        completion_window: row.get(4)?,
+        status: row.get(5)?,
+        output_file_id: row.get(6)?,
+        error_file_id: row.get(7)?,
+        errors_json: row.get(8)?,
+        metadata_json: row.get(9)?,
+        created_at: row.get(10)?,
+        in_progress_at: row.get(11)?,
+        finalizing_at: row.get(12)?,
+        completed_at: row.get(13)?,
+        failed_at: row.get(14)?,
+        expired_at: row.get(15)?,
+        cancelling_at: row.get(16)?,
+        cancelled_at: row.get(17)?,
+        expires_at: row.get(18)?,
+        updated_at: row.get(19)?,
+    })
+}
+
+fn map_batch_item(row: &Row<'_>) -> Result<GatewayBatchItem> {
+    Ok(GatewayBatchItem {
+        batch_id: row.get(0)?,
+        line_index: row.get(1)?,
+        custom_id: row.get(2)?,
+        url: row.get(3)?,
+        body_json: row.get(4)?,
+        status: row.get(5)?,
+        status_code: row.get(6)?,
+        result_json: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// 状态对应的时间戳列；`validating` 只记录 `created_at`。
+fn batch_status_timestamp_column(status: &str) -> Option<&'static str> {
+    match status {
+        "in_progress" => Some("in_progress_at"),
+        "finalizing" => Some("finalizing_at"),
+        "completed" => Some("completed_at"),
+        "failed" => Some("failed_at"),
+        "expired" => Some("expired_at"),
+        "cancelling" => Some("cancelling_at"),
+        "cancelled" => Some("cancelled_at"),
+        _ => None,
+    }
+}
+
+pub(super) fn delete_gateway_files_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_files WHERE key_id = ?1"
+}
+
+pub(super) fn delete_gateway_batch_items_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_batch_items
+     WHERE batch_id IN (SELECT batch_id FROM gateway_batches WHERE key_id = ?1)"
+}
+
+pub(super) fn delete_gateway_batches_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_batches WHERE key_id = ?1"
+}
+
+impl Storage {
+    pub fn insert_gateway_file(&self, file: &GatewayFile, content: &[u8]) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO gateway_files (
+                file_id, key_id, purpose, filename, bytes, content, created_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                file.file_id,
+                file.key_id,
+                file.purpose,
+                file.filename,
+                file.bytes,
+                content,
+                file.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn find_gateway_file(&self, key_id: &str, file_id: &str) -> Result<Option<GatewayFile>> {
+        self.conn
+            .query_row(
+                "SELECT file_id, key_id, purpose, filename, bytes, created_at
+                 FROM gateway_files
+                 WHERE file_id = ?1 AND key_id = ?2
+                 LIMIT 1",
+                params![file_id, key_id],
+                map_file,
+            )
+            .optional()
+    }
+
+    pub fn find_gateway_file_content(
+        &self,
+        key_id: &str,
+        file_id: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        self.conn
+            .query_row(
+                "SELECT content FROM gateway_files WHERE file_id = ?1 AND key_id = ?2 LIMIT 1",
+                params![file_id, key_id],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// 按创建时间倒序列出平台 Key 的文件；`purpose` 为空时不过滤。
+    pub fn list_gateway_files(
+        &self,
+        key_id: &str,
+        purpose: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<GatewayFile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_id, key_id, purpose, filename, bytes, created_at
+             FROM gateway_files
+             WHERE key_id = ?1 AND (?2 IS NULL OR purpose = ?2)
+             ORDER BY created_at DESC, file_id DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![key_id, purpose, limit as i64], map_file)?;
+        rows.collect()
+    }
+
+    pub fn delete_gateway_file(&self, key_id: &str, file_id: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM gateway_files WHERE file_id = ?1 AND key_id = ?2",
+            params![file_id, key_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// 在同一事务里写入批任务及其全部请求行。
+    pub fn insert_gateway_batch(
+        &self,
+        batch: &GatewayBatch,
+        items: &[GatewayBatchItem],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            &format!(
+                "INSERT INTO gateway_batches ({BATCH_COLUMNS_SQL})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                         ?17, ?18, ?19, ?20)"
+            ),
+            params![
+                batch.batch_id,
+                batch.key_id,
+                batch.endpoint,
+                batch.input_file_id,
+                batch.completion_window,
+                batch.status,
+                batch.output_file_id,
+                batch.error_file_id,
+                batch.errors_json,
+                batch.metadata_json,
+                batch.created_at,
+                batch.in_progress_at,
+                batch.finalizing_at,
+                batch.completed_at,
+                batch.failed_at,
+                batch.expired_at,
+                batch.cancelling_at,
+                batch.cancelled_at,
+                batch.expires_at,
+                batch.updated_at,
+            ],
+        )?;
+        {
+            let stmt = tx.prepare(&format!(
+                "INSERT INTO gateway_batch_items ({BATCH_ITEM_COLUMNS_SQL})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+            ))?;
+            for item in items {
+                stmt.execute(params![
+                    item.batch_id,
+                    item.line_index,
+                    item.custom_id,
+                    item.url,
+                    item.body_json,
+                    item.status,
+                    item.status_code,
+                    item.result_json,
+                    item.updated_at,
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    pub fn find_gateway_batch(&self, key_id: &str, batch_id: &str) -> Result<Option<GatewayBatch>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {BATCH_COLUMNS_SQL}
+                     FROM gateway_batches
+                     WHERE batch_id = ?1 AND key_id = ?2
+                     LIMIT 1"
+                ),
+                params![batch_id, key_id],
+                map_batch,
+            )
+            .optional()
+    }
+
+    /// 按创建时间倒序分页；`after` 为上一页最后一个 batch id。
+    pub fn list_gateway_batches(
+        &self,
+        key_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<GatewayBatch>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {BATCH_COLUMNS_SQL}
+             FROM gateway_batches
+             WHERE key_id = ?1
+               AND (?2 IS NULL OR (created_at, batch_id) < (
+                    SELECT created_at, batch_id FROM gateway_batches
+                    WHERE batch_id = ?2 AND key_id = ?1
+               ))
+             ORDER BY created_at DESC, batch_id DESC
+             LIMIT ?3"
+        ))?;
+        let rows = stmt.query_map(params![key_id, after, limit as i64], map_batch)?;
+        rows.collect()
+    }
+
+    /// 尚未进入终态的批任务；服务启动时据此恢复执行。
+    pub fn list_unfinished_gateway_batches(&self) -> Result<Vec<GatewayBatch>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {BATCH_COLUMNS_SQL}
+             FROM gateway_batches
+             WHERE status NOT IN {TERMINAL_BATCH_STATUSES_SQL}
+             ORDER BY created_at ASC"
+        ))?;
+        let rows = stmt.query_map([], map_batch)?;
+        rows.collect()
+    }
+
+    /// 切换批任务状态并记录对应时间戳；终态不可再变，`cancelling` 只能进入 `cancelled` 或 `failed`。
+    pub fn set_gateway_batch_status(&self, batch_id: &str, status: &str, at: i64) -> Result<bool> {
+        let timestamp_sql = batch_status_timestamp_column(status)
+            .map(|column| format!(", {column} = COALESCE({column}, ?3)"))
+            .unwrap_or_default();
+        let updated = self.conn.execute(
+            &format!(
+                "UPDATE gateway_batches
+                 SET status = ?2, updated_at = ?3{timestamp_sql}
+                 WHERE batch_id = ?1
+                   AND status NOT IN {TERMINAL_BATCH_STATUSES_SQL}
+                   AND (status != 'cancelling' OR ?2 IN ('cancelled', 'failed'))"
+            ),
+            params![batch_id, status, at],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn set_gateway_batch_results(
+        &self,
+        batch_id: &str,
+        output_file_id: Option<&str>,
+        error_file_id: Option<&str>,
+        errors_json: Option<&str>,
+        updated_at: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE gateway_batches
+             SET output_file_id = ?2, error_file_id = ?3,
+                 errors_json = COALESCE(?4, errors_json), updated_at = ?5
+             WHERE batch_id = ?1",
+            params![
+                batch_id,
+                output_file_id,
+                error_file_id,
+                errors_json,
+                updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 把上次中断时仍在执行的请求行放回待执行队列。
+    pub fn reset_running_gateway_batch_items(&self, batch_id: &str, at: i64) -> Result<usize> {
+        self.conn.execute(
+            "UPDATE gateway_batch_items
+             SET status = 'pending', updated_at = ?2
+             WHERE batch_id = ?1 AND status = 'running'",
+            params![batch_id, at],
+        )
+    }
+
+    pub fn list_gateway_batch_items(
+        &self,
+        batch_id: &str,
+        status: &str,
+    ) -> Result<Vec<GatewayBatchItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {BATCH_ITEM_COLUMNS_SQL}
+             FROM gateway_batch_items
+             WHERE batch_id = ?1 AND status = ?2
+             ORDER BY line_index ASC"
+        ))?;
+        let rows = stmt.query_map(params![batch_id, status], map_batch_item)?;
+        rows.collect()
+    }
+
+    pub fn update_gateway_batch_item(
+        &self,
+        batch_id: &str,
+        line_index: i64,
+        status: &str,
+        status_code: Option<i64>,
+        result_json: Option<&str>,
+        updated_at: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE gateway_batch_items
+             SET status = ?3, status_code = ?4, result_json = ?5, updated_at = ?6
+             WHERE batch_id = ?1 AND line_index = ?2",
+            params![
+                batch_id,
+                line_index,
+                status,
+                status_code,
+                result_json,
+                updated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn gateway_batch_item_counts(&self, batch_id: &str) -> Result<GatewayBatchItemCounts> {
+        self.conn.query_row(
+            "SELECT COUNT(1),
+                    COALESCE(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0)
+             FROM gateway_batch_items
+             WHERE batch_id = ?1",
+            [batch_id],
+            |row| {
+                Ok(GatewayBatchItemCounts {
+                    total: row.get(0)?,
+                    completed: row.get(1)?,
+                    failed: row.get(2)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "gateway_batches_tests.rs"]
+mod tests;
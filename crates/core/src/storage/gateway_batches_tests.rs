@@ -0,0 +1,201 @@
+use super::*;
+
+fn open_storage() -> Storage {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+}
+
+fn batch(batch_id: &str, key_id: &str, created_at: i64) -> GatewayBatch {
+    GatewayBatch {
+        batch_id: batch_id.to_string(),
+        key_id: key_id.to_string(),
+        endpoint: "/v1/responses".to_string(),
+        input_file_id: "file-in".to_string(),
+        completion_window: "24h".to_string(),
+        status: "validating".to_string(),
+        created_at,
+        expires_at: created_at + 86_400,
+        updated_at: created_at,
+        ..Default::default()
+    }
+}
+
+fn item(batch_id: &str, line_index: i64) -> GatewayBatchItem {
+    GatewayBatchItem {
+        batch_id: batch_id.to_string(),
+        line_index,
+        custom_id: format!("req-{line_index}"),
+        url: "/v1/responses".to_string(),
+        body_json: "{}".to_string(),
+        status: "pending".to_string(),
+        status_code: None,
+        result_json: None,
+        updated_at: 100,
+    }
+}
+
+#[test]
+fn gateway_files_are_scoped_by_key_and_purpose() {
+    let storage = open_storage();
+    let file = GatewayFile {
+        file_id: "file-1".to_string(),
+        key_id: "key-a".to_string(),
+        purpose: "batch".to_string(),
+        filename: "input.jsonl".to_string(),
+        bytes: 3,
+        created_at: 100,
+    };
+    storage
+        .insert_gateway_file(&file, b"{}\n")
+        .expect("insert file");
+
+    assert_eq!(
+        storage
+            .find_gateway_file("key-a", "file-1")
+            .expect("find file"),
+        Some(file)
+    );
+    assert!(storage
+        .find_gateway_file_content("key-b", "file-1")
+        .expect("find other key")
+        .is_none());
+    assert_eq!(
+        storage
+            .find_gateway_file_content("key-a", "file-1")
+            .expect("find content")
+            .as_deref(),
+        Some(&b"{}\n"[..])
+    );
+    assert_eq!(
+        storage
+            .list_gateway_files("key-a", Some("batch_output"), 10)
+            .expect("list outputs")
+            .len(),
+        0
+    );
+    assert_eq!(
+        storage
+            .list_gateway_files("key-a", None, 10)
+            .expect("list all")
+            .len(),
+        1
+    );
+    assert!(storage
+        .delete_gateway_file("key-a", "file-1")
+        .expect("delete file"));
+}
+
+#[test]
+fn gateway_batch_status_transitions_respect_cancel_and_terminal_states() {
+    let storage = open_storage();
+    storage
+        .insert_gateway_batch(
+            &batch("batch_1", "key-a", 100),
+            &[item("batch_1", 0), item("batch_1", 1), item("batch_1", 2)],
+        )
+        .expect("insert batch");
+
+    assert!(storage
+        .set_gateway_batch_status("batch_1", "in_progress", 101)
+        .expect("start"));
+    storage
+        .update_gateway_batch_item("batch_1", 0, "completed", Some(200), Some("{}"), 102)
+        .expect("complete item");
+    storage
+        .update_gateway_batch_item("batch_1", 1, "running", None, None, 102)
+        .expect("run item");
+    assert!(storage
+        .set_gateway_batch_status("batch_1", "cancelling", 103)
+        .expect("cancelling"));
+    assert!(!storage
+        .set_gateway_batch_status("batch_1", "finalizing", 104)
+        .expect("finalizing after cancel"));
+    assert!(storage
+        .set_gateway_batch_status("batch_1", "cancelled", 105)
+        .expect("cancelled"));
+    assert!(!storage
+        .set_gateway_batch_status("batch_1", "completed", 106)
+        .expect("complete after cancel"));
+
+    let stored = storage
+        .find_gateway_batch("key-a", "batch_1")
+        .expect("find batch")
+        .expect("batch exists");
+    assert_eq!(stored.status, "cancelled");
+    assert_eq!(stored.in_progress_at, Some(101));
+    assert_eq!(stored.cancelling_at, Some(103));
+    assert_eq!(stored.cancelled_at, Some(105));
+    assert_eq!(stored.finalizing_at, None);
+
+    assert_eq!(
+        storage
+            .reset_running_gateway_batch_items("batch_1", 107)
+            .expect("reset running"),
+        1
+    );
+    assert_eq!(
+        storage
+            .list_gateway_batch_items("batch_1", "pending")
+            .expect("pending items")
+            .iter()
+            .map(|item| item.line_index)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        storage
+            .gateway_batch_item_counts("batch_1")
+            .expect("counts"),
+        GatewayBatchItemCounts {
+            total: 3,
+            completed: 1,
+            failed: 0,
+        }
+    );
+    assert!(storage
+        .list_unfinished_gateway_batches()
+        .expect("unfinished")
+        .is_empty());
+}
+
+#[test]
+fn list_gateway_batches_pages_newest_first() {
+    let storage = open_storage();
+    for (batch_id, created_at) in [("batch_a", 100), ("batch_b", 200), ("batch_c", 300)] {
+        storage
+            .insert_gateway_batch(&batch(batch_id, "key-a", created_at), &[])
+            .expect("insert batch");
+    }
+    storage
+        .insert_gateway_batch(&batch("batch_other", "key-b", 400), &[])
+        .expect("insert other batch");
+
+    let first_page = storage
+        .list_gateway_batches("key-a", None, 2)
+        .expect("first page");
+    assert_eq!(
+        first_page
+            .iter()
+            .map(|batch| batch.batch_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["batch_c", "batch_b"]
+    );
+    let second_page = storage
+        .list_gateway_batches("key-a", Some("batch_b"), 2)
+        .expect("second page");
+    assert_eq!(
+        second_page
+            .iter()
+            .map(|batch| batch.batch_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["batch_a"]
+    );
+    assert_eq!(
+        storage
+            .list_unfinished_gateway_batches()
+            .expect("unfinished")
+            .len(),
+        4
+    );
+}
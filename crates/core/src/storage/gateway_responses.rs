@@ -0,0 +1,124 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{GatewayStoredResponse, Storage};
+
+fn map_response(row: &Row<'_>) -> Result<GatewayStoredResponse> {
+    Ok(GatewayStoredResponse {
+        response_id: row.get(0)?,
+        key_id: row.get(1)?,
+        account_id: row.get(2)?,
+        previous_response_id: row.get(3)?,
+        model: row.get(4)?,
+        input_json: row.get(5)?,
+        output_json: row.get(6)?,
+        response_json: row.get(7)?,
+        retained_bytes: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+pub(super) fn delete_gateway_responses_by_key_sql() -> &'static str {
+    "DELETE FROM gateway_responses WHERE key_id = ?1"
+}
+
+impl Storage {
+    /// 写入网关侧 Responses 记录；同一 response id 重复写入时以最新内容为准。
+    pub fn upsert_gateway_response(&self, response: &GatewayStoredResponse) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO gateway_responses (
+                response_id, key_id, account_id, previous_response_id, model, input_json,
+                output_json, response_json, retained_bytes, created_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(response_id) DO UPDATE SET
+                key_id = excluded.key_id,
+                account_id = excluded.account_id,
+                previous_response_id = excluded.previous_response_id,
+                model = excluded.model,
+                input_json = excluded.input_json,
+                output_json = excluded.output_json,
+                response_json = excluded.response_json,
+                retained_bytes = excluded.retained_bytes,
+                created_at = excluded.created_at",
+            params![
+                response.response_id,
+                response.key_id,
+                response.account_id,
+                response.previous_response_id,
+                response.model,
+                response.input_json,
+                response.output_json,
+                response.response_json,
+                response.retained_bytes,
+                response.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 按平台 Key 读取记录；不同 Key 之间的 response id 互不可见。
+    pub fn find_gateway_response(
+        &self,
+        key_id: &str,
+        response_id: &str,
+    ) -> Result<Option<GatewayStoredResponse>> {
+        self.conn
+            .query_row(
+                "SELECT response_id, key_id, account_id, previous_response_id, model, input_json,
+                        output_json, response_json, retained_bytes, created_at
+                 FROM gateway_responses
+                 WHERE response_id = ?1 AND key_id = ?2
+                 LIMIT 1",
+                params![response_id, key_id],
+                map_response,
+            )
+            .optional()
+    }
+
+    pub fn delete_gateway_response(&self, key_id: &str, response_id: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM gateway_responses WHERE response_id = ?1 AND key_id = ?2",
+            params![response_id, key_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// 按创建时间从旧到新淘汰，直到总字节数不超过 `max_total_bytes`。
+    pub fn prune_gateway_responses(&self, max_total_bytes: i64) -> Result<usize> {
+        let total_bytes: i64 = self.conn.query_row(
+            "SELECT IFNULL(SUM(retained_bytes), 0) FROM gateway_responses",
+            [],
+            |row| row.get(0),
+        )?;
+        let budget = max_total_bytes.max(0);
+        if total_bytes <= budget {
+            return Ok(0);
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT response_id, retained_bytes
+             FROM gateway_responses
+             ORDER BY created_at ASC, response_id ASC",
+        )?;
+        let candidates = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        let mut remaining = total_bytes;
+        let mut removed = 0;
+        for (response_id, retained_bytes) in candidates {
+            if remaining <= budget {
+                break;
+            }
+            removed += self.conn.execute(
+                "DELETE FROM gateway_responses WHERE response_id = ?1",
+                [response_id.as_str()],
+            )?;
+            remaining -= retained_bytes;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+#[path = "gateway_responses_tests.rs"]
+mod tests;
@@ -0,0 +1,79 @@
+use super::*;
+
+fn stored(
+    response_id: &str,
+    key_id: &str,
+    retained_bytes: i64,
+    created_at: i64,
+) -> GatewayStoredResponse {
+    GatewayStoredResponse {
+        response_id: response_id.to_string(),
+        key_id: key_id.to_string(),
+        account_id: Some("acc-1".to_string()),
+        previous_response_id: None,
+        model: Some("gpt-5".to_string()),
+        input_json: "[]".to_string(),
+        output_json: "[]".to_string(),
+        response_json: format!(r#"{{"id":"{response_id}"}}"#),
+        retained_bytes,
+        created_at,
+    }
+}
+
+#[test]
+fn gateway_response_lookup_and_delete_are_scoped_to_key() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .upsert_gateway_response(&stored("resp_1", "key-a", 10, 100))
+        .expect("insert response");
+
+    assert!(storage
+        .find_gateway_response("key-b", "resp_1")
+        .expect("find other key")
+        .is_none());
+    assert!(!storage
+        .delete_gateway_response("key-b", "resp_1")
+        .expect("delete other key"));
+
+    let mut updated = stored("resp_1", "key-a", 12, 100);
+    updated.account_id = Some("acc-2".to_string());
+    storage
+        .upsert_gateway_response(&updated)
+        .expect("update response");
+    let found = storage
+        .find_gateway_response("key-a", "resp_1")
+        .expect("find response")
+        .expect("response exists");
+    assert_eq!(found, updated);
+
+    assert!(storage
+        .delete_gateway_response("key-a", "resp_1")
+        .expect("delete response"));
+    assert!(storage
+        .find_gateway_response("key-a", "resp_1")
+        .expect("find deleted")
+        .is_none());
+}
+
+#[test]
+fn prune_gateway_responses_drops_oldest_until_under_budget() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    for (idx, response_id) in ["resp_1", "resp_2", "resp_3"].into_iter().enumerate() {
+        storage
+            .upsert_gateway_response(&stored(response_id, "key-a", 10, 100 + idx as i64))
+            .expect("insert response");
+    }
+
+    assert_eq!(storage.prune_gateway_responses(30).expect("prune noop"), 0);
+    assert_eq!(storage.prune_gateway_responses(15).expect("prune"), 2);
+    assert!(storage
+        .find_gateway_response("key-a", "resp_3")
+        .expect("find newest")
+        .is_some());
+    assert!(storage
+        .find_gateway_response("key-a", "resp_1")
+        .expect("find oldest")
+        .is_none());
+}
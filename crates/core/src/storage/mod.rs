@@ -5,6 +5,8 @@ use std::path::Path;
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+mod account_group_policies;
+mod account_health_probes;
 mod account_manager;
 mod account_metadata;
 mod account_proxy_settings;
@@ -14,11 +16,16 @@ mod accounts_sql;
 mod agent_identities;
 mod aggregate_apis;
 mod aggregate_apis_sql;
+mod alerts;
 mod api_key_quota_limits;
 mod api_keys;
 mod codex_skill_repositories;
+mod content_policies;
 mod conversation_bindings;
 mod events;
+mod gateway_background_responses;
+mod gateway_batches;
+mod gateway_responses;
 mod key_id_filters;
 mod model_billing_v2;
 mod model_catalog_v2;
@@ -27,14 +34,19 @@ mod model_options;
 mod model_price_rules;
 mod model_sources;
 mod plugins;
+mod proxy_pools;
 mod proxy_profiles;
+mod proxy_subscriptions;
 mod proxy_tests;
 mod quota_pools;
 mod request_log_filters;
 mod request_log_query;
 mod request_logs;
+mod request_route_decisions;
 mod request_token_stats;
+mod response_cache;
 mod settings;
+mod structured_output_policies;
 mod tokens;
 mod usage;
 
@@ -257,6 +269,83 @@ pub struct AccountSubscription {
     pub updated_at: i64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountHealthState {
+    pub account_id: String,
+    pub consecutive_failures: i64,
+    pub consecutive_successes: i64,
+    pub last_outcome: Option<String>,
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_probe_at: Option<i64>,
+    pub quarantined_at: Option<i64>,
+    pub recovered_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountGroupPolicy {
+    pub group_name: String,
+    pub route_strategy: Option<String>,
+    pub account_max_inflight: Option<i64>,
+    pub allowed_models_json: Option<String>,
+    pub fallback_groups_json: Option<String>,
+    pub cooldown_multiplier_millis: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertChannel {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub config_json: String,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub id: String,
+    pub name: String,
+    pub condition: String,
+    pub severity: String,
+    pub threshold: Option<f64>,
+    pub cooldown_secs: i64,
+    pub channel_ids_json: Option<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertHistoryRecord {
+    pub id: i64,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub condition: String,
+    pub severity: String,
+    pub subject: String,
+    pub message: String,
+    pub delivery_status: String,
+    pub delivery_json: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountHealthProbeRecord {
+    pub id: i64,
+    pub account_id: String,
+    pub outcome: String,
+    pub status_code: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub error: Option<String>,
+    pub transition: Option<String>,
+    pub probed_at: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct AccountProxySettings {
     pub account_id: String,
@@ -326,6 +415,191 @@ pub struct ProxyProfile {
     pub updated_at: i64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyPool {
+    pub id: String,
+    pub name: String,
+    pub tag: String,
+    pub policy: String,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProxyPoolBinding {
+    pub account_id: String,
+    pub proxy_pool_id: String,
+    pub proxy_profile_id: Option<String>,
+    pub assigned_at: Option<i64>,
+    pub failover_count: i64,
+    pub last_failover_at: Option<i64>,
+    pub last_failover_from: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyResponseCachePolicy {
+    pub key_id: String,
+    pub enabled: bool,
+    pub ttl_secs: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyContentPolicy {
+    pub key_id: String,
+    pub enabled: bool,
+    pub action: String,
+    pub source_actions_json: String,
+    pub detectors_json: String,
+    pub custom_rules_json: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyStructuredOutputPolicy {
+    pub key_id: String,
+    pub enabled: bool,
+    pub max_repairs: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseCacheEntry {
+    pub cache_key: String,
+    pub key_id: String,
+    pub model: Option<String>,
+    pub request_path: String,
+    pub status_code: i64,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    pub hit_count: i64,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_hit_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseCacheStats {
+    pub entries: i64,
+    pub body_bytes: i64,
+    pub hit_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayBackgroundResponse {
+    pub response_id: String,
+    pub key_id: String,
+    pub status: String,
+    pub response_json: String,
+    pub last_sequence_number: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayBackgroundResponseEvent {
+    pub response_id: String,
+    pub sequence_number: i64,
+    pub event_json: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayFile {
+    pub file_id: String,
+    pub key_id: String,
+    pub purpose: String,
+    pub filename: String,
+    pub bytes: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GatewayBatch {
+    pub batch_id: String,
+    pub key_id: String,
+    pub endpoint: String,
+    pub input_file_id: String,
+    pub completion_window: String,
+    pub status: String,
+    pub output_file_id: Option<String>,
+    pub error_file_id: Option<String>,
+    pub errors_json: Option<String>,
+    pub metadata_json: Option<String>,
+    pub created_at: i64,
+    pub in_progress_at: Option<i64>,
+    pub finalizing_at: Option<i64>,
+    pub completed_at: Option<i64>,
+    pub failed_at: Option<i64>,
+    pub expired_at: Option<i64>,
+    pub cancelling_at: Option<i64>,
+    pub cancelled_at: Option<i64>,
+    pub expires_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayBatchItem {
+    pub batch_id: String,
+    pub line_index: i64,
+    pub custom_id: String,
+    pub url: String,
+    pub body_json: String,
+    pub status: String,
+    pub status_code: Option<i64>,
+    pub result_json: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GatewayBatchItemCounts {
+    pub total: i64,
+    pub completed: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayStoredResponse {
+    pub response_id: String,
+    pub key_id: String,
+    pub account_id: Option<String>,
+    pub previous_response_id: Option<String>,
+    pub model: Option<String>,
+    pub input_json: String,
+    pub output_json: String,
+    pub response_json: String,
+    pub retained_bytes: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxySubscription {
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+    pub tags_json: Option<String>,
+    pub enabled: bool,
+    pub last_refreshed_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxySubscriptionProfile {
+    pub subscription_id: String,
+    pub proxy_profile_id: String,
+    pub proxy_url: String,
+    pub retired_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProxyProfileCreateInput {
     pub id: String,
@@ -843,6 +1117,45 @@ pub struct RequestLog {
     pub created_at: i64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContentPolicyMatches {
+    pub request_log_id: i64,
+    pub trace_id: Option<String>,
+    pub matches_json: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestStructuredOutputResult {
+    pub request_log_id: i64,
+    pub trace_id: Option<String>,
+    pub outcome: String,
+    pub repairs: i64,
+    pub skip_reason: Option<String>,
+    pub errors_json: String,
+    pub repair_input_tokens: Option<i64>,
+    pub repair_output_tokens: Option<i64>,
+    pub repair_total_tokens: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestRouteDecision {
+    pub request_log_id: i64,
+    pub trace_id: Option<String>,
+    pub strategy: String,
+    pub decision_json: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecentTokenMix {
+    pub sample_count: i64,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct RequestTokenStat {
     pub request_log_id: i64,
@@ -1387,6 +1700,8 @@ pub struct AggregateApiSupplierModel {
     pub provider_type: String,
     pub upstream_model: String,
     pub display_name: Option<String>,
+    /// Azure OpenAI 等按部署寻址的供应商，记录上游模型对应的部署名。
+    pub deployment: Option<String>,
     pub status: String,
     pub created_at: i64,
     pub updated_at: i64,
@@ -1682,6 +1997,11 @@ impl Storage {
         })
     }
 
+    /// 停机前把 WAL 中尚未合并的请求日志、token 统计等写回主库文件。
+    pub fn checkpoint_wal(&self) -> Result<()> {
+        self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+    }
+
     /// 函数 `init`
     ///
     /// 作者: gaohongshun
@@ -2274,6 +2594,63 @@ impl Storage {
             "130_accounts_subject_identity",
             include_str!("../../migrations/130_accounts_subject_identity.sql"),
         )?;
+        self.apply_sql_migration(
+            "131_account_health_probes",
+            include_str!("../../migrations/131_account_health_probes.sql"),
+        )?;
+        self.apply_sql_migration(
+            "132_account_group_policies",
+            include_str!("../../migrations/132_account_group_policies.sql"),
+        )?;
+        self.apply_sql_or_compat_migration(
+            "133_aggregate_api_supplier_model_deployments",
+            include_str!("../../migrations/133_aggregate_api_supplier_model_deployments.sql"),
+            |s| s.ensure_aggregate_api_supplier_model_deployment_column(),
+        )?;
+        self.apply_sql_migration(
+            "134_request_route_decisions",
+            include_str!("../../migrations/134_request_route_decisions.sql"),
+        )?;
+        self.apply_sql_migration(
+            "135_alerts",
+            include_str!("../../migrations/135_alerts.sql"),
+        )?;
+        self.apply_sql_migration(
+            "136_proxy_pools",
+            include_str!("../../migrations/136_proxy_pools.sql"),
+        )?;
+        self.apply_sql_migration(
+            "137_proxy_subscriptions",
+            include_str!("../../migrations/137_proxy_subscriptions.sql"),
+        )?;
+        self.apply_sql_migration(
+            "138_response_cache",
+            include_str!("../../migrations/138_response_cache.sql"),
+        )?;
+        self.apply_sql_migration(
+            "139_gateway_responses",
+            include_str!("../../migrations/139_gateway_responses.sql"),
+        )?;
+        self.apply_sql_migration(
+            "140_gateway_background_responses",
+            include_str!("../../migrations/140_gateway_background_responses.sql"),
+        )?;
+        self.apply_sql_migration(
+            "141_gateway_batches",
+            include_str!("../../migrations/141_gateway_batches.sql"),
+        )?;
+        self.apply_sql_migration(
+            "142_structured_output_policies",
+            include_str!("../../migrations/142_structured_output_policies.sql"),
+        )?;
+        self.apply_sql_migration(
+            "143_content_policies",
+            include_str!("../../migrations/143_content_policies.sql"),
+        )?;
+        self.apply_sql_migration(
+            "144_structured_output_results",
+            include_str!("../../migrations/144_structured_output_results.sql"),
+        )?;
         self.ensure_api_key_rotation_columns()?;
         self.ensure_api_key_account_group_filter_column()?;
         self.ensure_aggregate_apis_table()?;
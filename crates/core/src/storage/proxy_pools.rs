@@ -0,0 +1,188 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{now_ts, AccountProxyPoolBinding, ProxyPool, Storage};
+
+fn proxy_pool_columns() -> &'static str {
+    "id, name, tag, policy, enabled, created_at, updated_at"
+}
+
+fn account_proxy_pool_binding_columns() -> &'static str {
+    "account_id, proxy_pool_id, proxy_profile_id, assigned_at, failover_count,
+     last_failover_at, last_failover_from, created_at, updated_at"
+}
+
+fn map_proxy_pool(row: &Row<'_>) -> Result<ProxyPool> {
+    Ok(ProxyPool {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        tag: row.get(2)?,
+        policy: row.get(3)?,
+        enabled: row.get::<_, i64>(4)? != 0,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn map_account_proxy_pool_binding(row: &Row<'_>) -> Result<AccountProxyPoolBinding> {
+    Ok(AccountProxyPoolBinding {
+        account_id: row.get(0)?,
+        proxy_pool_id: row.get(1)?,
+        proxy_profile_id: row.get(2)?,
+        assigned_at: row.get(3)?,
+        failover_count: row.get(4)?,
+        last_failover_at: row.get(5)?,
+        last_failover_from: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+impl Storage {
+    pub fn list_proxy_pools(&self) -> Result<Vec<ProxyPool>> {
+        let sql = format!(
+            "SELECT {}
+             FROM proxy_pools
+             ORDER BY created_at ASC, id ASC",
+            proxy_pool_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_proxy_pool)?;
+        rows.collect()
+    }
+
+    pub fn find_proxy_pool(&self, id: &str) -> Result<Option<ProxyPool>> {
+        let sql = format!(
+            "SELECT {}
+             FROM proxy_pools
+             WHERE id = ?1
+             LIMIT 1",
+            proxy_pool_columns()
+        );
+        self.conn.query_row(&sql, [id], map_proxy_pool).optional()
+    }
+
+    /// 按 id 写入代理池；已存在时保留原创建时间。
+    pub fn upsert_proxy_pool(&self, pool: &ProxyPool) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO proxy_pools (
+                id, name, tag, policy, enabled, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                tag = excluded.tag,
+                policy = excluded.policy,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            params![
+                pool.id,
+                pool.name,
+                pool.tag,
+                pool.policy,
+                pool.enabled as i64,
+                pool.created_at,
+                pool.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_proxy_pool(&self, id: &str) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM proxy_pools WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn find_account_proxy_pool_binding(
+        &self,
+        account_id: &str,
+    ) -> Result<Option<AccountProxyPoolBinding>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_proxy_pool_bindings
+             WHERE account_id = ?1
+             LIMIT 1",
+            account_proxy_pool_binding_columns()
+        );
+        self.conn
+            .query_row(&sql, [account_id], map_account_proxy_pool_binding)
+            .optional()
+    }
+
+    /// `proxy_pool_id` 为空时返回全部绑定。
+    pub fn list_account_proxy_pool_bindings(
+        &self,
+        proxy_pool_id: Option<&str>,
+    ) -> Result<Vec<AccountProxyPoolBinding>> {
+        let sql = format!(
+            "SELECT {}
+             FROM account_proxy_pool_bindings
+             WHERE (?1 IS NULL OR proxy_pool_id = ?1)
+             ORDER BY proxy_pool_id ASC, account_id ASC",
+            account_proxy_pool_binding_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![proxy_pool_id], map_account_proxy_pool_binding)?;
+        rows.collect()
+    }
+
+    /// 绑定账号到代理池；换池时清空已分配的成员，同池重复绑定保留原分配。
+    pub fn bind_account_proxy_pool(&self, account_id: &str, proxy_pool_id: &str) -> Result<()> {
+        let now = now_ts();
+        self.conn.execute(
+            "INSERT INTO account_proxy_pool_bindings (
+                account_id, proxy_pool_id, proxy_profile_id, assigned_at, failover_count,
+                last_failover_at, last_failover_from, created_at, updated_at
+             ) VALUES (?1, ?2, NULL, NULL, 0, NULL, NULL, ?3, ?3)
+             ON CONFLICT(account_id) DO UPDATE SET
+                proxy_profile_id = CASE
+                    WHEN account_proxy_pool_bindings.proxy_pool_id = excluded.proxy_pool_id
+                    THEN account_proxy_pool_bindings.proxy_profile_id
+                    ELSE NULL
+                END,
+                assigned_at = CASE
+                    WHEN account_proxy_pool_bindings.proxy_pool_id = excluded.proxy_pool_id
+                    THEN account_proxy_pool_bindings.assigned_at
+                    ELSE NULL
+                END,
+                proxy_pool_id = excluded.proxy_pool_id,
+                updated_at = excluded.updated_at",
+            params![account_id, proxy_pool_id, now],
+        )?;
+        Ok(())
+    }
+
+    /// 记录账号当前使用的池成员；`failover_from` 非空表示这是一次故障切换。
+    pub fn assign_account_proxy_pool_member(
+        &self,
+        account_id: &str,
+        proxy_profile_id: &str,
+        failover_from: Option<&str>,
+    ) -> Result<bool> {
+        let now = now_ts();
+        let updated = self.conn.execute(
+            "UPDATE account_proxy_pool_bindings
+             SET proxy_profile_id = ?2,
+                 assigned_at = ?3,
+                 failover_count = failover_count + CASE WHEN ?4 IS NULL THEN 0 ELSE 1 END,
+                 last_failover_at = CASE WHEN ?4 IS NULL THEN last_failover_at ELSE ?3 END,
+                 last_failover_from = CASE WHEN ?4 IS NULL THEN last_failover_from ELSE ?4 END,
+                 updated_at = ?3
+             WHERE account_id = ?1",
+            params![account_id, proxy_profile_id, now, failover_from],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn delete_account_proxy_pool_binding(&self, account_id: &str) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM account_proxy_pool_bindings WHERE account_id = ?1",
+            [account_id],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+#[path = "proxy_pools_tests.rs"]
+mod tests;
@@ -0,0 +1,182 @@
+use super::*;
+use crate::storage::{Account, ProxyProfileCreateInput, ProxyProfileUrlTestInsertInput};
+
+fn account(id: &str) -> Account {
+    Account {
+        id: id.to_string(),
+        label: id.to_string(),
+        issuer: "chatgpt".to_string(),
+        chatgpt_account_id: None,
+        workspace_id: None,
+        group_name: None,
+        sort: 0,
+        status: "active".to_string(),
+        created_at: 1,
+        updated_at: 1,
+    }
+}
+
+fn pool(id: &str, tag: &str) -> ProxyPool {
+    ProxyPool {
+        id: id.to_string(),
+        name: format!("pool {id}"),
+        tag: tag.to_string(),
+        policy: "sticky".to_string(),
+        enabled: true,
+        created_at: 100,
+        updated_at: 100,
+    }
+}
+
+fn create_profile(storage: &Storage, id: &str) {
+    storage
+        .create_proxy_profile(&ProxyProfileCreateInput {
+            id: id.to_string(),
+            name: id.to_string(),
+            proxy_url: format!("http://{id}.example:8080"),
+            enabled: true,
+            tags_json: Some(r#"["exit"]"#.to_string()),
+            notes: None,
+        })
+        .expect("create proxy profile");
+}
+
+fn insert_url_test(
+    storage: &Storage,
+    profile_id: &str,
+    status: &str,
+    latency_ms: i64,
+    tested_at: i64,
+) {
+    storage
+        .insert_proxy_profile_url_test(&ProxyProfileUrlTestInsertInput {
+            proxy_profile_id: profile_id.to_string(),
+            status: status.to_string(),
+            url_latency_ms: Some(latency_ms),
+            status_code: Some(204),
+            test_url: "https://example.com/generate_204".to_string(),
+            final_url: None,
+            redirected: false,
+            tested_at,
+            error_code: None,
+            error: None,
+        })
+        .expect("insert url test");
+}
+
+#[test]
+fn proxy_pool_upsert_keeps_created_at() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+
+    storage
+        .upsert_proxy_pool(&pool("pool-a", "exit"))
+        .expect("insert pool");
+    let mut updated = pool("pool-a", "exit-us");
+    updated.policy = "least_latency".to_string();
+    updated.created_at = 999;
+    updated.updated_at = 200;
+    storage.upsert_proxy_pool(&updated).expect("update pool");
+
+    let stored = storage
+        .find_proxy_pool("pool-a")
+        .expect("find pool")
+        .expect("pool exists");
+    assert_eq!(stored.tag, "exit-us");
+    assert_eq!(stored.policy, "least_latency");
+    assert_eq!(stored.created_at, 100);
+    assert_eq!(stored.updated_at, 200);
+    assert!(storage.delete_proxy_pool("pool-a").expect("delete pool"));
+    assert!(storage.list_proxy_pools().expect("list pools").is_empty());
+}
+
+#[test]
+fn account_pool_binding_tracks_assignment_and_failover() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .insert_account(&account("acc-1"))
+        .expect("insert account");
+    create_profile(&storage, "proxy-a");
+    create_profile(&storage, "proxy-b");
+    storage
+        .upsert_proxy_pool(&pool("pool-a", "exit"))
+        .expect("insert pool a");
+    storage
+        .upsert_proxy_pool(&pool("pool-b", "exit"))
+        .expect("insert pool b");
+
+    storage
+        .bind_account_proxy_pool("acc-1", "pool-a")
+        .expect("bind pool");
+    assert!(storage
+        .assign_account_proxy_pool_member("acc-1", "proxy-a", None)
+        .expect("assign member"));
+    // 中文注释：同池重复绑定不能丢掉已分配的出口。
+    storage
+        .bind_account_proxy_pool("acc-1", "pool-a")
+        .expect("rebind pool");
+    let binding = storage
+        .find_account_proxy_pool_binding("acc-1")
+        .expect("find binding")
+        .expect("binding exists");
+    assert_eq!(binding.proxy_profile_id.as_deref(), Some("proxy-a"));
+    assert_eq!(binding.failover_count, 0);
+
+    storage
+        .assign_account_proxy_pool_member("acc-1", "proxy-b", Some("proxy-a"))
+        .expect("failover member");
+    let binding = storage
+        .find_account_proxy_pool_binding("acc-1")
+        .expect("find binding")
+        .expect("binding exists");
+    assert_eq!(binding.proxy_profile_id.as_deref(), Some("proxy-b"));
+    assert_eq!(binding.failover_count, 1);
+    assert_eq!(binding.last_failover_from.as_deref(), Some("proxy-a"));
+    assert!(binding.last_failover_at.is_some());
+
+    storage
+        .bind_account_proxy_pool("acc-1", "pool-b")
+        .expect("switch pool");
+    let binding = storage
+        .find_account_proxy_pool_binding("acc-1")
+        .expect("find binding")
+        .expect("binding exists");
+    assert_eq!(binding.proxy_pool_id, "pool-b");
+    assert_eq!(binding.proxy_profile_id, None);
+    assert_eq!(
+        storage
+            .list_account_proxy_pool_bindings(Some("pool-a"))
+            .expect("list bindings")
+            .len(),
+        0
+    );
+    assert!(storage
+        .delete_account_proxy_pool_binding("acc-1")
+        .expect("delete binding"));
+}
+
+#[test]
+fn average_url_latency_uses_recent_successful_samples() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    create_profile(&storage, "proxy-a");
+
+    assert_eq!(
+        storage
+            .average_proxy_profile_url_latency_ms("proxy-a", 3)
+            .expect("empty average"),
+        None
+    );
+    insert_url_test(&storage, "proxy-a", "ok", 900, 1);
+    insert_url_test(&storage, "proxy-a", "ok", 100, 2);
+    insert_url_test(&storage, "proxy-a", "failed", 5, 3);
+    insert_url_test(&storage, "proxy-a", "ok", 200, 4);
+
+    assert_eq!(
+        storage
+            .average_proxy_profile_url_latency_ms("proxy-a", 2)
+            .expect("average"),
+        Some(150)
+    );
+}
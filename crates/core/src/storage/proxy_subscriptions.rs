@@ -0,0 +1,167 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{now_ts, ProxySubscription, ProxySubscriptionProfile, Storage};
+
+fn proxy_subscription_columns() -> &'static str {
+    "id, name, url, tags_json, enabled, last_refreshed_at, last_error, created_at, updated_at"
+}
+
+fn map_proxy_subscription(row: &Row<'_>) -> Result<ProxySubscription> {
+    Ok(ProxySubscription {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        tags_json: row.get(3)?,
+        enabled: row.get::<_, i64>(4)? != 0,
+        last_refreshed_at: row.get(5)?,
+        last_error: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn map_proxy_subscription_profile(row: &Row<'_>) -> Result<ProxySubscriptionProfile> {
+    Ok(ProxySubscriptionProfile {
+        subscription_id: row.get(0)?,
+        proxy_profile_id: row.get(1)?,
+        proxy_url: row.get(2)?,
+        retired_at: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Storage {
+    pub fn list_proxy_subscriptions(&self) -> Result<Vec<ProxySubscription>> {
+        let sql = format!(
+            "SELECT {}
+             FROM proxy_subscriptions
+             ORDER BY created_at ASC, id ASC",
+            proxy_subscription_columns()
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([], map_proxy_subscription)?;
+        rows.collect()
+    }
+
+    pub fn find_proxy_subscription(&self, id: &str) -> Result<Option<ProxySubscription>> {
+        let sql = format!(
+            "SELECT {}
+             FROM proxy_subscriptions
+             WHERE id = ?1
+             LIMIT 1",
+            proxy_subscription_columns()
+        );
+        self.conn
+            .query_row(&sql, [id], map_proxy_subscription)
+            .optional()
+    }
+
+    /// 按 id 写入订阅；已存在时保留原创建时间与刷新结果。
+    pub fn upsert_proxy_subscription(&self, subscription: &ProxySubscription) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO proxy_subscriptions (
+                id, name, url, tags_json, enabled, last_refreshed_at, last_error,
+                created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                url = excluded.url,
+                tags_json = excluded.tags_json,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            params![
+                subscription.id,
+                subscription.name,
+                subscription.url,
+                subscription.tags_json,
+                subscription.enabled as i64,
+                subscription.last_refreshed_at,
+                subscription.last_error,
+                subscription.created_at,
+                subscription.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 记录一次刷新结果；`last_error` 为空表示刷新成功。
+    pub fn update_proxy_subscription_refresh(
+        &self,
+        id: &str,
+        refreshed_at: i64,
+        last_error: Option<&str>,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE proxy_subscriptions
+             SET last_refreshed_at = ?2,
+                 last_error = ?3,
+                 updated_at = ?2
+             WHERE id = ?1",
+            params![id, refreshed_at, last_error],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn delete_proxy_subscription(&self, id: &str) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM proxy_subscriptions WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    pub fn list_proxy_subscription_profiles(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<ProxySubscriptionProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT subscription_id, proxy_profile_id, proxy_url, retired_at, created_at, updated_at
+             FROM proxy_subscription_profiles
+             WHERE subscription_id = ?1
+             ORDER BY created_at ASC, proxy_profile_id ASC",
+        )?;
+        let rows = stmt.query_map([subscription_id], map_proxy_subscription_profile)?;
+        rows.collect()
+    }
+
+    /// 记录订阅拥有的代理配置；重复写入会清除退役标记。
+    pub fn upsert_proxy_subscription_profile(
+        &self,
+        subscription_id: &str,
+        proxy_profile_id: &str,
+        proxy_url: &str,
+    ) -> Result<()> {
+        let now = now_ts();
+        self.conn.execute(
+            "INSERT INTO proxy_subscription_profiles (
+                subscription_id, proxy_profile_id, proxy_url, retired_at, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, NULL, ?4, ?4)
+             ON CONFLICT(subscription_id, proxy_profile_id) DO UPDATE SET
+                proxy_url = excluded.proxy_url,
+                retired_at = NULL,
+                updated_at = excluded.updated_at",
+            params![subscription_id, proxy_profile_id, proxy_url, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn retire_proxy_subscription_profile(
+        &self,
+        subscription_id: &str,
+        proxy_profile_id: &str,
+        retired_at: i64,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE proxy_subscription_profiles
+             SET retired_at = ?3,
+                 updated_at = ?3
+             WHERE subscription_id = ?1 AND proxy_profile_id = ?2 AND retired_at IS NULL",
+            params![subscription_id, proxy_profile_id, retired_at],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+#[path = "proxy_subscriptions_tests.rs"]
+mod tests;
@@ -0,0 +1,109 @@
+use super::*;
+use crate::storage::ProxyProfileCreateInput;
+
+fn subscription(id: &str) -> ProxySubscription {
+    ProxySubscription {
+        id: id.to_string(),
+        name: format!("subscription {id}"),
+        url: Some("https://provider.example/sub?token=abc".to_string()),
+        tags_json: Some(r#"["provider"]"#.to_string()),
+        enabled: true,
+        last_refreshed_at: None,
+        last_error: None,
+        created_at: 100,
+        updated_at: 100,
+    }
+}
+
+fn create_profile(storage: &Storage, id: &str) {
+    storage
+        .create_proxy_profile(&ProxyProfileCreateInput {
+            id: id.to_string(),
+            name: id.to_string(),
+            proxy_url: format!("socks5://{id}.example:1080"),
+            enabled: true,
+            tags_json: None,
+            notes: None,
+        })
+        .expect("create proxy profile");
+}
+
+#[test]
+fn proxy_subscription_upsert_keeps_refresh_state() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+
+    storage
+        .upsert_proxy_subscription(&subscription("sub-a"))
+        .expect("insert subscription");
+    assert!(storage
+        .update_proxy_subscription_refresh("sub-a", 150, Some("timeout"))
+        .expect("record refresh"));
+    let mut updated = subscription("sub-a");
+    updated.name = "renamed".to_string();
+    updated.created_at = 999;
+    updated.updated_at = 200;
+    storage
+        .upsert_proxy_subscription(&updated)
+        .expect("update subscription");
+
+    let stored = storage
+        .find_proxy_subscription("sub-a")
+        .expect("find subscription")
+        .expect("subscription exists");
+    assert_eq!(stored.name, "renamed");
+    assert_eq!(stored.created_at, 100);
+    assert_eq!(stored.last_refreshed_at, Some(150));
+    assert_eq!(stored.last_error.as_deref(), Some("timeout"));
+    assert_eq!(storage.list_proxy_subscriptions().expect("list").len(), 1);
+}
+
+#[test]
+fn proxy_subscription_profiles_track_retirement_and_cascade() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .upsert_proxy_subscription(&subscription("sub-a"))
+        .expect("insert subscription");
+    create_profile(&storage, "proxy-a");
+    create_profile(&storage, "proxy-b");
+
+    storage
+        .upsert_proxy_subscription_profile("sub-a", "proxy-a", "socks5://proxy-a.example:1080")
+        .expect("link a");
+    storage
+        .upsert_proxy_subscription_profile("sub-a", "proxy-b", "socks5://proxy-b.example:1080")
+        .expect("link b");
+    assert!(storage
+        .retire_proxy_subscription_profile("sub-a", "proxy-b", 300)
+        .expect("retire b"));
+    assert!(!storage
+        .retire_proxy_subscription_profile("sub-a", "proxy-b", 400)
+        .expect("retire b again"));
+
+    let links = storage
+        .list_proxy_subscription_profiles("sub-a")
+        .expect("list links");
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[1].retired_at, Some(300));
+
+    // 中文注释：订阅里重新出现的条目要清除退役标记。
+    storage
+        .upsert_proxy_subscription_profile("sub-a", "proxy-b", "socks5://proxy-b.example:1080")
+        .expect("relink b");
+    storage.delete_proxy_profile("proxy-a").expect("delete a");
+    let links = storage
+        .list_proxy_subscription_profiles("sub-a")
+        .expect("list links");
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].proxy_profile_id, "proxy-b");
+    assert_eq!(links[0].retired_at, None);
+
+    assert!(storage
+        .delete_proxy_subscription("sub-a")
+        .expect("delete subscription"));
+    assert!(storage
+        .list_proxy_subscription_profiles("sub-a")
+        .expect("list links")
+        .is_empty());
+}
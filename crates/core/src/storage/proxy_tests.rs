@@ -101,6 +101,28 @@ impl Storage {
         Ok(items)
     }
 
+    /// 最近若干次成功 URL 测试的平均延迟；没有成功记录时返回 `None`。
+    pub fn average_proxy_profile_url_latency_ms(
+        &self,
+        proxy_profile_id: &str,
+        sample_limit: usize,
+    ) -> Result<Option<i64>> {
+        let normalized_limit = sample_limit.max(1).min(i64::MAX as usize) as i64;
+        let average: Option<f64> = self.conn.query_row(
+            "SELECT AVG(url_latency_ms)
+             FROM (
+                SELECT url_latency_ms
+                FROM proxy_profile_url_tests
+                WHERE proxy_profile_id = ?1 AND status = 'ok' AND url_latency_ms IS NOT NULL
+                ORDER BY tested_at DESC, id DESC
+                LIMIT ?2
+             )",
+            params![proxy_profile_id.trim(), normalized_limit],
+            |row| row.get(0),
+        )?;
+        Ok(average.map(|value| value.round() as i64))
+    }
+
     pub(super) fn ensure_proxy_profile_url_tests_table(&self) -> Result<()> {
         self.conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS proxy_profile_url_tests (
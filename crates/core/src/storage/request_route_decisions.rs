@@ -0,0 +1,84 @@
+use rusqlite::{params, params_from_iter, types::Value, OptionalExtension, Result, Row};
+
+use super::{RequestRouteDecision, Storage};
+
+fn map_decision(row: &Row<'_>) -> Result<RequestRouteDecision> {
+    Ok(RequestRouteDecision {
+        request_log_id: row.get(0)?,
+        trace_id: row.get(1)?,
+        strategy: row.get(2)?,
+        decision_json: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+impl Storage {
+    pub fn insert_request_route_decision(&self, decision: &RequestRouteDecision) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO request_route_decisions (
+                request_log_id, trace_id, strategy, decision_json, created_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(request_log_id) DO UPDATE SET
+                trace_id = excluded.trace_id,
+                strategy = excluded.strategy,
+                decision_json = excluded.decision_json,
+                created_at = excluded.created_at",
+            params![
+                decision.request_log_id,
+                &decision.trace_id,
+                &decision.strategy,
+                &decision.decision_json,
+                decision.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn find_request_route_decision_by_trace_id(
+        &self,
+        trace_id: &str,
+    ) -> Result<Option<RequestRouteDecision>> {
+        self.conn
+            .query_row(
+                "SELECT request_log_id, trace_id, strategy, decision_json, created_at
+                 FROM request_route_decisions
+                 WHERE trace_id = ?1
+                 ORDER BY request_log_id DESC
+                 LIMIT 1",
+                [trace_id.trim()],
+                map_decision,
+            )
+            .optional()
+    }
+
+    pub fn find_request_route_decision_by_trace_id_for_key_ids(
+        &self,
+        trace_id: &str,
+        key_ids: &[String],
+    ) -> Result<Option<RequestRouteDecision>> {
+        if key_ids.is_empty() {
+            return Ok(None);
+        }
+        let placeholders = (0..key_ids.len())
+            .map(|index| format!("?{}", index + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT d.request_log_id, d.trace_id, d.strategy, d.decision_json, d.created_at
+             FROM request_route_decisions d
+             JOIN request_logs r ON r.id = d.request_log_id
+             WHERE d.trace_id = ?1 AND r.key_id IN ({placeholders})
+             ORDER BY d.request_log_id DESC
+             LIMIT 1"
+        );
+        let values = std::iter::once(Value::Text(trace_id.trim().to_string()))
+            .chain(key_ids.iter().map(|key_id| Value::Text(key_id.clone())));
+        self.conn
+            .query_row(&sql, params_from_iter(values), map_decision)
+            .optional()
+    }
+}
+
+#[cfg(test)]
+#[path = "request_route_decisions_tests.rs"]
+mod tests;
@@ -0,0 +1,88 @@
+use super::*;
+use crate::storage::{RequestLog, RequestTokenStat};
+
+fn insert_log(storage: &Storage, trace_id: &str, model: &str, created_at: i64) -> i64 {
+    let (request_log_id, _) = storage
+        .insert_request_log_with_token_stat(
+            &RequestLog {
+                trace_id: Some(trace_id.to_string()),
+                key_id: Some("gk-cost".to_string()),
+                request_path: "/v1/responses".to_string(),
+                method: "POST".to_string(),
+                model: Some(model.to_string()),
+                status_code: Some(200),
+                created_at,
+                ..Default::default()
+            },
+            &RequestTokenStat {
+                key_id: Some("gk-cost".to_string()),
+                model: Some(model.to_string()),
+                input_tokens: Some(1_000),
+                cached_input_tokens: Some(400),
+                output_tokens: Some(200),
+                created_at,
+                ..Default::default()
+            },
+        )
+        .expect("insert request log");
+    request_log_id
+}
+
+#[test]
+fn request_route_decision_round_trips_by_trace_id() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    let request_log_id = insert_log(&storage, "trc-cost", "gpt-5.4", 100);
+
+    storage
+        .insert_request_route_decision(&RequestRouteDecision {
+            request_log_id,
+            trace_id: Some("trc-cost".to_string()),
+            strategy: "cheapest".to_string(),
+            decision_json: "{\"candidates\":[]}".to_string(),
+            created_at: 100,
+        })
+        .expect("insert decision");
+
+    let found = storage
+        .find_request_route_decision_by_trace_id(" trc-cost ")
+        .expect("find decision")
+        .expect("decision exists");
+    assert_eq!(found.request_log_id, request_log_id);
+    assert_eq!(found.strategy, "cheapest");
+    assert!(storage
+        .find_request_route_decision_by_trace_id("trc-missing")
+        .expect("find missing")
+        .is_none());
+    assert!(storage
+        .find_request_route_decision_by_trace_id_for_key_ids("trc-cost", &["gk-cost".to_string()])
+        .expect("find scoped")
+        .is_some());
+    assert!(storage
+        .find_request_route_decision_by_trace_id_for_key_ids("trc-cost", &["gk-other".to_string()])
+        .expect("find scoped other")
+        .is_none());
+}
+
+#[test]
+fn recent_token_mix_only_counts_latest_samples_for_key_and_model() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    for ts in 0..3 {
+        insert_log(&storage, &format!("trc-{ts}"), "gpt-5.4", 100 + ts);
+    }
+    insert_log(&storage, "trc-other", "gpt-5.4-mini", 200);
+
+    let mix = storage
+        .summarize_recent_token_mix(Some("gk-cost"), "gpt-5.4", 2)
+        .expect("summarize mix");
+    assert_eq!(mix.sample_count, 2);
+    assert_eq!(mix.input_tokens, 2_000);
+    assert_eq!(mix.cached_input_tokens, 800);
+    assert_eq!(mix.output_tokens, 400);
+
+    let empty = storage
+        .summarize_recent_token_mix(Some("gk-missing"), "gpt-5.4", 10)
+        .expect("summarize empty");
+    assert_eq!(empty.sample_count, 0);
+}
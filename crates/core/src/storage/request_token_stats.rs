@@ -4,9 +4,9 @@ use std::sync::atomic::{AtomicI64, Ordering};
 use super::key_id_filters::{PairedKeyIdSqlFilter, TempKeyIdFilter};
 use super::{
     now_ts, ApiKeyModelTokenUsageSummary, ApiKeyTokenUsageSummary, DailyTokenUsageRollup,
-    MemberDashboardUsageBreakdownSnapshot, ModelTokenUsageRollup, RequestLogQuerySummary,
-    RequestLogTodaySummary, RequestTokenStat, SourceTokenUsageRollup, Storage, TokenUsageRollup,
-    TokenUsageSummary, UserTokenUsageRollup,
+    MemberDashboardUsageBreakdownSnapshot, ModelTokenUsageRollup, RecentTokenMix,
+    RequestLogQuerySummary, RequestLogTodaySummary, RequestTokenStat, SourceTokenUsageRollup,
+    Storage, TokenUsageRollup, TokenUsageSummary, UserTokenUsageRollup,
 };
 
 const DEFAULT_REQUEST_TOKEN_STATS_RETAIN_DAYS: i64 = 14;
@@ -705,6 +705,40 @@ impl Storage {
         Ok(())
     }
 
+    /// 汇总某个 key+模型最近若干条成功请求的 token 构成，供成本路由估算缓存命中与输出长度
+    pub fn summarize_recent_token_mix(
+        &self,
+        key_id: Option<&str>,
+        model: &str,
+        limit: i64,
+    ) -> Result<RecentTokenMix> {
+        self.conn.query_row(
+            "SELECT
+                COUNT(1),
+                IFNULL(SUM(IFNULL(input_tokens, 0)), 0),
+                IFNULL(SUM(IFNULL(cached_input_tokens, 0)), 0),
+                IFNULL(SUM(IFNULL(output_tokens, 0)), 0)
+             FROM (
+                SELECT input_tokens, cached_input_tokens, output_tokens
+                FROM request_token_stats
+                WHERE usage_included = 1
+                  AND model = ?1
+                  AND (?2 IS NULL OR key_id = ?2)
+                ORDER BY created_at DESC, id DESC
+                LIMIT ?3
+             )",
+            params![model, key_id, limit.max(1)],
+            |row| {
+                Ok(RecentTokenMix {
+                    sample_count: row.get(0)?,
+                    input_tokens: row.get(1)?,
+                    cached_input_tokens: row.get(2)?,
+                    output_tokens: row.get(3)?,
+                })
+            },
+        )
+    }
+
     pub fn maybe_run_observability_maintenance(&self, now: i64) -> Result<()> {
         let interval = observability_maintenance_interval_secs().max(60);
         let last = LAST_OBSERVABILITY_MAINTENANCE_AT.load(Ordering::Relaxed);
@@ -0,0 +1,213 @@
+use rusqlite::{params, OptionalExtension, Result, Row};
+
+use super::{ApiKeyResponseCachePolicy, ResponseCacheEntry, ResponseCacheStats, Storage};
+
+fn map_policy(row: &Row<'_>) -> Result<ApiKeyResponseCachePolicy> {
+    Ok(ApiKeyResponseCachePolicy {
+        key_id: row.get(0)?,
+        enabled: row.get::<_, i64>(1)? != 0,
+        ttl_secs: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn map_entry(row: &Row<'_>) -> Result<ResponseCacheEntry> {
+    Ok(ResponseCacheEntry {
+        cache_key: row.get(0)?,
+        key_id: row.get(1)?,
+        model: row.get(2)?,
+        request_path: row.get(3)?,
+        status_code: row.get(4)?,
+        content_type: row.get(5)?,
+        body: row.get(6)?,
+        hit_count: row.get(7)?,
+        created_at: row.get(8)?,
+        expires_at: row.get(9)?,
+        last_hit_at: row.get(10)?,
+    })
+}
+
+fn entry_columns() -> &'static str {
+    "cache_key, key_id, model, request_path, status_code, content_type, body, hit_count,
+     created_at, expires_at, last_hit_at"
+}
+
+pub(super) fn delete_api_key_response_cache_policy_by_key_sql() -> &'static str {
+    "DELETE FROM api_key_response_cache_policies WHERE key_id = ?1"
+}
+
+pub(super) fn delete_response_cache_entries_by_key_sql() -> &'static str {
+    "DELETE FROM response_cache_entries WHERE key_id = ?1"
+}
+
+impl Storage {
+    pub fn find_api_key_response_cache_policy(
+        &self,
+        key_id: &str,
+    ) -> Result<Option<ApiKeyResponseCachePolicy>> {
+        self.conn
+            .query_row(
+                "SELECT key_id, enabled, ttl_secs, created_at, updated_at
+                 FROM api_key_response_cache_policies
+                 WHERE key_id = ?1
+                 LIMIT 1",
+                [key_id],
+                map_policy,
+            )
+            .optional()
+    }
+
+    /// 按平台 Key 写入缓存策略；已存在时保留原创建时间。
+    pub fn upsert_api_key_response_cache_policy(
+        &self,
+        policy: &ApiKeyResponseCachePolicy,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO api_key_response_cache_policies (
+                key_id, enabled, ttl_secs, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(key_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                ttl_secs = excluded.ttl_secs,
+                updated_at = excluded.updated_at",
+            params![
+                policy.key_id,
+                policy.enabled as i64,
+                policy.ttl_secs,
+                policy.created_at,
+                policy.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 读取未过期的缓存条目；过期条目留给 `prune_response_cache` 统一清理。
+    pub fn find_response_cache_entry(
+        &self,
+        cache_key: &str,
+        now: i64,
+    ) -> Result<Option<ResponseCacheEntry>> {
+        let sql = format!(
+            "SELECT {}
+             FROM response_cache_entries
+             WHERE cache_key = ?1 AND expires_at > ?2
+             LIMIT 1",
+            entry_columns()
+        );
+        self.conn
+            .query_row(&sql, params![cache_key, now], map_entry)
+            .optional()
+    }
+
+    /// 写入缓存条目；同一 key 重复写入会覆盖响应体并重置命中计数。
+    pub fn upsert_response_cache_entry(&self, entry: &ResponseCacheEntry) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO response_cache_entries (
+                cache_key, key_id, model, request_path, status_code, content_type, body,
+                body_bytes, hit_count, created_at, expires_at, last_hit_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(cache_key) DO UPDATE SET
+                key_id = excluded.key_id,
+                model = excluded.model,
+                request_path = excluded.request_path,
+                status_code = excluded.status_code,
+                content_type = excluded.content_type,
+                body = excluded.body,
+                body_bytes = excluded.body_bytes,
+                hit_count = excluded.hit_count,
+                created_at = excluded.created_at,
+                expires_at = excluded.expires_at,
+                last_hit_at = excluded.last_hit_at",
+            params![
+                entry.cache_key,
+                entry.key_id,
+                entry.model,
+                entry.request_path,
+                entry.status_code,
+                entry.content_type,
+                entry.body,
+                entry.body.len() as i64,
+                entry.hit_count,
+                entry.created_at,
+                entry.expires_at,
+                entry.last_hit_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_response_cache_hit(&self, cache_key: &str, now: i64) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE response_cache_entries
+             SET hit_count = hit_count + 1,
+                 last_hit_at = ?2
+             WHERE cache_key = ?1",
+            params![cache_key, now],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// 清理过期条目，再按最近使用时间从旧到新淘汰，直到总字节数不超过 `max_total_bytes`。
+    pub fn prune_response_cache(&self, now: i64, max_total_bytes: i64) -> Result<usize> {
+        let mut removed = self.conn.execute(
+            "DELETE FROM response_cache_entries WHERE expires_at <= ?1",
+            [now],
+        )?;
+        let total_bytes: i64 = self.conn.query_row(
+            "SELECT IFNULL(SUM(body_bytes), 0) FROM response_cache_entries",
+            [],
+            |row| row.get(0),
+        )?;
+        if total_bytes <= max_total_bytes.max(0) {
+            return Ok(removed);
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT cache_key, body_bytes
+             FROM response_cache_entries
+             ORDER BY COALESCE(last_hit_at, created_at) ASC, created_at ASC",
+        )?;
+        let candidates = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        let mut remaining = total_bytes;
+        for (cache_key, body_bytes) in candidates {
+            if remaining <= max_total_bytes.max(0) {
+                break;
+            }
+            removed += self.conn.execute(
+                "DELETE FROM response_cache_entries WHERE cache_key = ?1",
+                [cache_key.as_str()],
+            )?;
+            remaining -= body_bytes;
+        }
+        Ok(removed)
+    }
+
+    pub fn delete_response_cache_entries_for_key(&self, key_id: &str) -> Result<usize> {
+        self.conn
+            .execute(delete_response_cache_entries_by_key_sql(), [key_id])
+    }
+
+    pub fn response_cache_stats(&self, key_id: Option<&str>) -> Result<ResponseCacheStats> {
+        self.conn.query_row(
+            "SELECT COUNT(1), IFNULL(SUM(body_bytes), 0), IFNULL(SUM(hit_count), 0)
+             FROM response_cache_entries
+             WHERE ?1 IS NULL OR key_id = ?1",
+            [key_id],
+            |row| {
+                Ok(ResponseCacheStats {
+                    entries: row.get(0)?,
+                    body_bytes: row.get(1)?,
+                    hit_count: row.get(2)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "response_cache_tests.rs"]
+mod tests;
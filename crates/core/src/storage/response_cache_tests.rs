@@ -0,0 +1,173 @@
+use super::*;
+use crate::storage::ApiKey;
+
+fn insert_api_key(storage: &Storage, key_id: &str) {
+    storage
+        .insert_api_key(&ApiKey {
+            id: key_id.to_string(),
+            name: Some(key_id.to_string()),
+            model_slug: None,
+            reasoning_effort: None,
+            service_tier: None,
+            aggregate_api_id: None,
+            account_plan_filter: None,
+            aggregate_api_url: None,
+            key_hash: format!("hash-{key_id}"),
+            status: "enabled".to_string(),
+            rotation_strategy: "account_rotation".to_string(),
+            client_type: "codex".to_string(),
+            protocol_type: "openai_compat".to_string(),
+            auth_scheme: "authorization_bearer".to_string(),
+            upstream_base_url: None,
+            static_headers_json: None,
+            created_at: 100,
+            last_used_at: None,
+        })
+        .expect("insert api key");
+}
+
+fn entry(cache_key: &str, key_id: &str, body_len: usize, created_at: i64) -> ResponseCacheEntry {
+    ResponseCacheEntry {
+        cache_key: cache_key.to_string(),
+        key_id: key_id.to_string(),
+        model: Some("gpt-5".to_string()),
+        request_path: "/v1/responses".to_string(),
+        status_code: 200,
+        content_type: Some("text/event-stream".to_string()),
+        body: vec![b'x'; body_len],
+        hit_count: 0,
+        created_at,
+        expires_at: created_at + 60,
+        last_hit_at: None,
+    }
+}
+
+#[test]
+fn response_cache_policy_upsert_keeps_created_at_and_is_removed_with_key() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    insert_api_key(&storage, "key-a");
+
+    let mut policy = ApiKeyResponseCachePolicy {
+        key_id: "key-a".to_string(),
+        enabled: true,
+        ttl_secs: 600,
+        created_at: 100,
+        updated_at: 100,
+    };
+    storage
+        .upsert_api_key_response_cache_policy(&policy)
+        .expect("insert policy");
+    policy.enabled = false;
+    policy.created_at = 999;
+    policy.updated_at = 200;
+    storage
+        .upsert_api_key_response_cache_policy(&policy)
+        .expect("update policy");
+
+    let stored = storage
+        .find_api_key_response_cache_policy("key-a")
+        .expect("find policy")
+        .expect("policy exists");
+    assert!(!stored.enabled);
+    assert_eq!(stored.created_at, 100);
+    assert_eq!(stored.updated_at, 200);
+
+    storage
+        .upsert_response_cache_entry(&entry("cache-a", "key-a", 4, 100))
+        .expect("insert entry");
+    storage.delete_api_key("key-a").expect("delete api key");
+    assert!(storage
+        .find_api_key_response_cache_policy("key-a")
+        .expect("find policy")
+        .is_none());
+    assert_eq!(
+        storage.response_cache_stats(None).expect("stats"),
+        ResponseCacheStats::default()
+    );
+}
+
+#[test]
+fn response_cache_entry_expires_and_counts_hits() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .upsert_response_cache_entry(&entry("cache-a", "key-a", 8, 100))
+        .expect("insert entry");
+
+    assert!(storage
+        .record_response_cache_hit("cache-a", 120)
+        .expect("record hit"));
+    let found = storage
+        .find_response_cache_entry("cache-a", 130)
+        .expect("find entry")
+        .expect("entry exists");
+    assert_eq!(found.body.len(), 8);
+    assert_eq!(found.hit_count, 1);
+    assert_eq!(found.last_hit_at, Some(120));
+    assert!(storage
+        .find_response_cache_entry("cache-a", 160)
+        .expect("find expired entry")
+        .is_none());
+
+    let stats = storage.response_cache_stats(Some("key-a")).expect("stats");
+    assert_eq!(
+        stats,
+        ResponseCacheStats {
+            entries: 1,
+            body_bytes: 8,
+            hit_count: 1,
+        }
+    );
+    assert_eq!(
+        storage
+            .response_cache_stats(Some("key-b"))
+            .expect("stats")
+            .entries,
+        0
+    );
+}
+
+#[test]
+fn prune_response_cache_drops_expired_then_least_recently_used() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    storage
+        .upsert_response_cache_entry(&entry("expired", "key-a", 10, 10))
+        .expect("insert expired");
+    storage
+        .upsert_response_cache_entry(&entry("old", "key-a", 10, 100))
+        .expect("insert old");
+    storage
+        .upsert_response_cache_entry(&entry("hot", "key-a", 10, 101))
+        .expect("insert hot");
+    storage
+        .upsert_response_cache_entry(&entry("new", "key-a", 10, 110))
+        .expect("insert new");
+    // 中文注释：最早写入的条目被命中过，淘汰顺序按最近使用时间而非创建时间。
+    storage
+        .record_response_cache_hit("old", 120)
+        .expect("record hit");
+
+    let removed = storage.prune_response_cache(115, 20).expect("prune cache");
+    assert_eq!(removed, 2);
+    assert!(storage
+        .find_response_cache_entry("hot", 115)
+        .expect("find hot")
+        .is_none());
+    assert!(storage
+        .find_response_cache_entry("old", 115)
+        .expect("find old")
+        .is_some());
+    assert!(storage
+        .find_response_cache_entry("new", 115)
+        .expect("find new")
+        .is_some());
+
+    assert_eq!(
+        storage
+            .delete_response_cache_entries_for_key("key-a")
+            .expect("clear key"),
+        2
+    );
+}
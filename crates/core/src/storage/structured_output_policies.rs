@@ -0,0 +1,160 @@
+use rusqlite::{params, params_from_iter, types::Value, OptionalExtension, Result, Row};
+
+use super::{ApiKeyStructuredOutputPolicy, RequestStructuredOutputResult, Storage};
+
+fn map_policy(row: &Row<'_>) -> Result<ApiKeyStructuredOutputPolicy> {
+    Ok(ApiKeyStructuredOutputPolicy {
+        key_id: row.get(0)?,
+        enabled: row.get::<_, i64>(1)? != 0,
+        max_repairs: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn map_result(row: &Row<'_>) -> Result<RequestStructuredOutputResult> {
+    Ok(RequestStructuredOutputResult {
+        request_log_id: row.get(0)?,
+        trace_id: row.get(1)?,
+        outcome: row.get(2)?,
+        repairs: row.get(3)?,
+        skip_reason: row.get(4)?,
+        errors_json: row.get(5)?,
+        repair_input_tokens: row.get(6)?,
+        repair_output_tokens: row.get(7)?,
+        repair_total_tokens: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+pub(super) fn delete_api_key_structured_output_policy_by_key_sql() -> &'static str {
+    "DELETE FROM api_key_structured_output_policies WHERE key_id = ?1"
+}
+
+impl Storage {
+    pub fn find_api_key_structured_output_policy(
+        &self,
+        key_id: &str,
+    ) -> Result<Option<ApiKeyStructuredOutputPolicy>> {
+        self.conn
+            .query_row(
+                "SELECT key_id, enabled, max_repairs, created_at, updated_at
+                 FROM api_key_structured_output_policies
+                 WHERE key_id = ?1
+                 LIMIT 1",
+                [key_id],
+                map_policy,
+            )
+            .optional()
+    }
+
+    /// 按平台 Key 写入结构化输出校验策略；已存在时保留原创建时间。
+    pub fn upsert_api_key_structured_output_policy(
+        &self,
+        policy: &ApiKeyStructuredOutputPolicy,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO api_key_structured_output_policies (
+                key_id, enabled, max_repairs, created_at, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(key_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                max_repairs = excluded.max_repairs,
+                updated_at = excluded.updated_at",
+            params![
+                policy.key_id,
+                policy.enabled as i64,
+                policy.max_repairs,
+                policy.created_at,
+                policy.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_request_structured_output_result(
+        &self,
+        result: &RequestStructuredOutputResult,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO request_structured_output_results (
+                request_log_id, trace_id, outcome, repairs, skip_reason, errors_json,
+                repair_input_tokens, repair_output_tokens, repair_total_tokens, created_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(request_log_id) DO UPDATE SET
+                trace_id = excluded.trace_id,
+                outcome = excluded.outcome,
+                repairs = excluded.repairs,
+                skip_reason = excluded.skip_reason,
+                errors_json = excluded.errors_json,
+                repair_input_tokens = excluded.repair_input_tokens,
+                repair_output_tokens = excluded.repair_output_tokens,
+                repair_total_tokens = excluded.repair_total_tokens,
+                created_at = excluded.created_at",
+            params![
+                result.request_log_id,
+                &result.trace_id,
+                &result.outcome,
+                result.repairs,
+                &result.skip_reason,
+                &result.errors_json,
+                result.repair_input_tokens,
+                result.repair_output_tokens,
+                result.repair_total_tokens,
+                result.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn find_request_structured_output_result_by_trace_id(
+        &self,
+        trace_id: &str,
+    ) -> Result<Option<RequestStructuredOutputResult>> {
+        self.conn
+            .query_row(
+                "SELECT request_log_id, trace_id, outcome, repairs, skip_reason, errors_json,
+                        repair_input_tokens, repair_output_tokens, repair_total_tokens, created_at
+                 FROM request_structured_output_results
+                 WHERE trace_id = ?1
+                 ORDER BY request_log_id DESC
+                 LIMIT 1",
+                [trace_id.trim()],
+                map_result,
+            )
+            .optional()
+    }
+
+    pub fn find_request_structured_output_result_by_trace_id_for_key_ids(
+        &self,
+        trace_id: &str,
+        key_ids: &[String],
+    ) -> Result<Option<RequestStructuredOutputResult>> {
+        if key_ids.is_empty() {
+            return Ok(None);
+        }
+        let placeholders = (0..key_ids.len())
+            .map(|index| format!("?{}", index + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT s.request_log_id, s.trace_id, s.outcome, s.repairs, s.skip_reason,
+                    s.errors_json, s.repair_input_tokens, s.repair_output_tokens,
+                    s.repair_total_tokens, s.created_at
+             FROM request_structured_output_results s
+             JOIN request_logs r ON r.id = s.request_log_id
+             WHERE s.trace_id = ?1 AND r.key_id IN ({placeholders})
+             ORDER BY s.request_log_id DESC
+             LIMIT 1"
+        );
+        let values = std::iter::once(Value::Text(trace_id.trim().to_string()))
+            .chain(key_ids.iter().map(|key_id| Value::Text(key_id.clone())));
+        self.conn
+            .query_row(&sql, params_from_iter(values), map_result)
+            .optional()
+    }
+}
+
+#[cfg(test)]
+#[path = "structured_output_policies_tests.rs"]
+mod tests;
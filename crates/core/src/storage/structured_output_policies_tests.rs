@@ -0,0 +1,128 @@
+use super::*;
+use crate::storage::{ApiKey, RequestLog, RequestTokenStat};
+
+fn insert_api_key(storage: &Storage, key_id: &str) {
+    storage
+        .insert_api_key(&ApiKey {
+            id: key_id.to_string(),
+            name: Some(key_id.to_string()),
+            model_slug: None,
+            reasoning_effort: None,
+            service_tier: None,
+            aggregate_api_id: None,
+            account_plan_filter: None,
+            aggregate_api_url: None,
+            key_hash: format!("hash-{key_id}"),
+            status: "enabled".to_string(),
+            rotation_strategy: "account_rotation".to_string(),
+            client_type: "codex".to_string(),
+            protocol_type: "openai_compat".to_string(),
+            auth_scheme: "authorization_bearer".to_string(),
+            upstream_base_url: None,
+            static_headers_json: None,
+            created_at: 100,
+            last_used_at: None,
+        })
+        .expect("insert api key");
+}
+
+#[test]
+fn structured_output_policy_upsert_keeps_created_at_and_is_removed_with_key() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    insert_api_key(&storage, "key-a");
+
+    let mut policy = ApiKeyStructuredOutputPolicy {
+        key_id: "key-a".to_string(),
+        enabled: true,
+        max_repairs: 1,
+        created_at: 100,
+        updated_at: 100,
+    };
+    storage
+        .upsert_api_key_structured_output_policy(&policy)
+        .expect("insert policy");
+    policy.enabled = false;
+    policy.max_repairs = 3;
+    policy.created_at = 200;
+    policy.updated_at = 200;
+    storage
+        .upsert_api_key_structured_output_policy(&policy)
+        .expect("update policy");
+
+    let stored = storage
+        .find_api_key_structured_output_policy("key-a")
+        .expect("read policy")
+        .expect("policy exists");
+    assert!(!stored.enabled);
+    assert_eq!(stored.max_repairs, 3);
+    assert_eq!(stored.created_at, 100);
+    assert_eq!(stored.updated_at, 200);
+
+    storage.delete_api_key("key-a").expect("delete key");
+    assert!(storage
+        .find_api_key_structured_output_policy("key-a")
+        .expect("read policy after delete")
+        .is_none());
+}
+
+#[test]
+fn structured_output_result_round_trips_by_trace_id_and_key_scope() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    let (request_log_id, _) = storage
+        .insert_request_log_with_token_stat(
+            &RequestLog {
+                trace_id: Some("trc-structured".to_string()),
+                key_id: Some("gk-structured".to_string()),
+                request_path: "/v1/responses".to_string(),
+                method: "POST".to_string(),
+                status_code: Some(200),
+                created_at: 100,
+                ..Default::default()
+            },
+            &RequestTokenStat {
+                key_id: Some("gk-structured".to_string()),
+                created_at: 100,
+                ..Default::default()
+            },
+        )
+        .expect("insert request log");
+
+    storage
+        .insert_request_structured_output_result(&RequestStructuredOutputResult {
+            request_log_id,
+            trace_id: Some("trc-structured".to_string()),
+            outcome: "repaired".to_string(),
+            repairs: 1,
+            skip_reason: None,
+            errors_json: "[]".to_string(),
+            repair_input_tokens: Some(40),
+            repair_output_tokens: Some(12),
+            repair_total_tokens: Some(52),
+            created_at: 100,
+        })
+        .expect("insert result");
+
+    let found = storage
+        .find_request_structured_output_result_by_trace_id(" trc-structured ")
+        .expect("find result")
+        .expect("result exists");
+    assert_eq!(found.request_log_id, request_log_id);
+    assert_eq!(found.outcome, "repaired");
+    assert_eq!(found.repair_total_tokens, Some(52));
+    assert!(storage
+        .find_request_structured_output_result_by_trace_id_for_key_ids(
+            "trc-structured",
+            &["gk-structured".to_string()]
+        )
+        .expect("find scoped")
+        .is_some());
+    assert!(storage
+        .find_request_structured_output_result_by_trace_id_for_key_ids(
+            "trc-structured",
+            &["gk-other".to_string()]
+        )
+        .expect("find scoped other")
+        .is_none());
+}
@@ -109,6 +109,54 @@ fn update_api_key_last_used_at_by_id_updates_recent_call_time() {
     assert_eq!(loaded.last_used_at, Some(12345));
 }
 
+#[test]
+fn rotate_api_key_secret_rolls_back_hash_when_secret_write_fails() {
+    let storage = Storage::open_in_memory().expect("open");
+    storage.init().expect("init");
+    let key = make_test_api_key(1);
+    storage.insert_api_key(&key).expect("insert api key");
+
+    assert!(storage
+        .rotate_api_key_secret(&key.id, "hash-rotated", "sk-rotated")
+        .expect("rotate"));
+    assert_eq!(
+        storage
+            .find_api_key_by_id(&key.id)
+            .expect("load api key")
+            .map(|loaded| loaded.key_hash),
+        Some("hash-rotated".to_string())
+    );
+    assert_eq!(
+        storage
+            .find_api_key_secret_by_id(&key.id)
+            .expect("load secret")
+            .as_deref(),
+        Some("sk-rotated")
+    );
+    assert!(!storage
+        .rotate_api_key_secret("missing-key", "hash-missing", "sk-missing")
+        .expect("rotate missing"));
+
+    storage
+        .conn
+        .execute(
+            "CREATE TRIGGER reject_api_key_secret_update BEFORE UPDATE ON api_key_secrets
+             BEGIN SELECT RAISE(ABORT, 'secret write rejected'); END",
+            [],
+        )
+        .expect("create trigger");
+    assert!(storage
+        .rotate_api_key_secret(&key.id, "hash-lost", "sk-lost")
+        .is_err());
+    assert_eq!(
+        storage
+            .find_api_key_by_id(&key.id)
+            .expect("load api key")
+            .map(|loaded| loaded.key_hash),
+        Some("hash-rotated".to_string())
+    );
+}
+
 #[test]
 fn api_key_account_group_filter_migrates_defaults_updates_and_clears() {
     let storage = Storage::open_in_memory().expect("open");
@@ -0,0 +1,59 @@
+use codexmanager_core::process_env::{
+    parse_dotenv_kv, read_rpc_token_from_env_or_file, resolve_db_path, ENV_DB_PATH, ENV_RPC_TOKEN,
+    ENV_RPC_TOKEN_FILE,
+};
+
+/// 函数 `rpc_token_lookup_reads_env_then_file_without_creating_one`
+///
+/// 同一进程内的环境变量是共享状态，几种情况放在一个测试里顺序验证。
+#[test]
+fn rpc_token_lookup_reads_env_then_file_without_creating_one() {
+    let dir = std::env::temp_dir().join(format!(
+        "codexmanager-core-process-env-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let db_path = dir.join("codexmanager.db");
+    let token_path = dir.join("codexmanager.rpc-token");
+    let _ = std::fs::remove_file(&token_path);
+    std::env::remove_var(ENV_RPC_TOKEN);
+    std::env::remove_var(ENV_RPC_TOKEN_FILE);
+    std::env::set_var(ENV_DB_PATH, &db_path);
+
+    assert_eq!(resolve_db_path(), db_path);
+    assert_eq!(read_rpc_token_from_env_or_file(), None);
+    assert!(!token_path.exists());
+
+    std::fs::write(&token_path, " file-token \n").expect("write token file");
+    assert_eq!(
+        read_rpc_token_from_env_or_file().as_deref(),
+        Some("file-token")
+    );
+
+    std::env::set_var(ENV_RPC_TOKEN, "env-token");
+    assert_eq!(
+        read_rpc_token_from_env_or_file().as_deref(),
+        Some("env-token")
+    );
+
+    std::env::remove_var(ENV_RPC_TOKEN);
+    std::env::remove_var(ENV_DB_PATH);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn dotenv_lines_support_export_quotes_and_inline_comments() {
+    assert_eq!(
+        parse_dotenv_kv("export CODEXMANAGER_RPC_TOKEN=\"a b\""),
+        Some(("CODEXMANAGER_RPC_TOKEN".to_string(), "a b".to_string()))
+    );
+    assert_eq!(
+        parse_dotenv_kv("CODEXMANAGER_SERVICE_ADDR=localhost:1 # local"),
+        Some((
+            "CODEXMANAGER_SERVICE_ADDR".to_string(),
+            "localhost:1".to_string()
+        ))
+    );
+    assert_eq!(parse_dotenv_kv("# comment"), None);
+    assert_eq!(parse_dotenv_kv("=value"), None);
+}
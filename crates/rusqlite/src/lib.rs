@@ -410,6 +410,19 @@ impl FromValue for bool {
     }
 }
 
+impl FromValue for Vec<u8> {
+    fn from_value(value: &types::Value) -> Result<Self> {
+        match value {
+            types::Value::Blob(value) => Ok(value.clone()),
+            types::Value::Text(value) => Ok(value.clone().into_bytes()),
+            types::Value::Null => Err(Error::FromSql("cannot read NULL as BLOB".to_string())),
+            types::Value::Integer(_) | types::Value::Real(_) => {
+                Err(Error::FromSql("cannot read number as BLOB".to_string()))
+            }
+        }
+    }
+}
+
 impl<T: FromValue> FromValue for Option<T> {
     fn from_value(value: &types::Value) -> Result<Self> {
         match value {
@@ -555,12 +568,24 @@ impl ToValue for Vec<u8> {
     }
 }
 
+impl ToValue for &Vec<u8> {
+    fn to_value(self) -> types::Value {
+        types::Value::Blob(self.clone())
+    }
+}
+
 impl ToValue for &[u8] {
     fn to_value(self) -> types::Value {
         types::Value::Blob(self.to_vec())
     }
 }
 
+impl ToValue for &&[u8] {
+    fn to_value(self) -> types::Value {
+        types::Value::Blob(self.to_vec())
+    }
+}
+
 pub trait IntoParams {
     fn into_params(self) -> Vec<types::Value>;
 }
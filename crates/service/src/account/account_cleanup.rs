@@ -46,6 +46,7 @@ const CLEANUP_STATUS_ALLOWLIST: &[&str] = &[
     "banned",
     "limited",
     "disabled",
+    "quarantined",
     "inactive",
     "unknown",
 ];
@@ -0,0 +1,134 @@
+use codexmanager_core::rpc::types::{AccountGroupPolicyEntry, AccountGroupPolicySetParams};
+use codexmanager_core::storage::{now_ts, AccountGroupPolicy};
+
+use crate::storage_helpers::open_storage;
+
+const MAX_COOLDOWN_MULTIPLIER_MILLIS: i64 = 100_000;
+
+pub(crate) fn list_account_group_policies() -> Result<Vec<AccountGroupPolicyEntry>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_account_group_policies()
+        .map(|items| items.into_iter().map(policy_entry).collect())
+        .map_err(|err| format!("list account group policies failed: {err}"))
+}
+
+/// 整体覆盖分组策略；未传字段表示沿用全局网关设置。
+pub(crate) fn set_account_group_policy(
+    params: AccountGroupPolicySetParams,
+) -> Result<AccountGroupPolicyEntry, String> {
+    let group_name = params.group_name.trim().to_string();
+    if group_name.is_empty() {
+        return Err("missing groupName".to_string());
+    }
+    let route_strategy = match params
+        .route_strategy
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(raw) => Some(
+            crate::gateway::normalize_route_strategy(raw)
+                .ok_or_else(|| {
+                    format!("invalid routeStrategy: {raw}; use ordered, balanced or cheapest")
+                })?
+                .to_string(),
+        ),
+        None => None,
+    };
+    if params.account_max_inflight.is_some_and(|value| value < 0) {
+        return Err("accountMaxInflight must be >= 0".to_string());
+    }
+    if params
+        .cooldown_multiplier_millis
+        .is_some_and(|value| !(0..=MAX_COOLDOWN_MULTIPLIER_MILLIS).contains(&value))
+    {
+        return Err(format!(
+            "cooldownMultiplierMillis must be between 0 and {MAX_COOLDOWN_MULTIPLIER_MILLIS}"
+        ));
+    }
+    let allowed_models = normalize_list(params.allowed_models);
+    let fallback_groups = normalize_list(params.fallback_groups)
+        .into_iter()
+        .filter(|item| item != &group_name)
+        .collect::<Vec<_>>();
+
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let existing = storage
+        .find_account_group_policy(group_name.as_str())
+        .map_err(|err| format!("read account group policy failed: {err}"))?;
+    let now = now_ts();
+    let policy = AccountGroupPolicy {
+        group_name: group_name.clone(),
+        route_strategy,
+        account_max_inflight: params.account_max_inflight,
+        allowed_models_json: list_json(&allowed_models)?,
+        fallback_groups_json: list_json(&fallback_groups)?,
+        cooldown_multiplier_millis: params.cooldown_multiplier_millis,
+        created_at: existing.map(|item| item.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    storage
+        .upsert_account_group_policy(&policy)
+        .map_err(|err| format!("save account group policy failed: {err}"))?;
+    crate::gateway::invalidate_account_group_policy_cache();
+    Ok(policy_entry(policy))
+}
+
+pub(crate) fn delete_account_group_policy(group_name: &str) -> Result<(), String> {
+    let group_name = group_name.trim();
+    if group_name.is_empty() {
+        return Err("missing groupName".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let deleted = storage
+        .delete_account_group_policy(group_name)
+        .map_err(|err| format!("delete account group policy failed: {err}"))?;
+    if !deleted {
+        return Err(format!("account group policy not found: {group_name}"));
+    }
+    crate::gateway::invalidate_account_group_policy_cache();
+    Ok(())
+}
+
+fn normalize_list(items: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for item in items {
+        let item = item.trim();
+        if !item.is_empty() && !normalized.iter().any(|existing| existing == item) {
+            normalized.push(item.to_string());
+        }
+    }
+    normalized
+}
+
+fn list_json(items: &[String]) -> Result<Option<String>, String> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(items)
+        .map(Some)
+        .map_err(|err| format!("serialize account group policy failed: {err}"))
+}
+
+fn parse_list(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|value| serde_json::from_str::<Vec<String>>(value).ok())
+        .unwrap_or_default()
+}
+
+fn policy_entry(policy: AccountGroupPolicy) -> AccountGroupPolicyEntry {
+    AccountGroupPolicyEntry {
+        allowed_models: parse_list(policy.allowed_models_json.as_deref()),
+        fallback_groups: parse_list(policy.fallback_groups_json.as_deref()),
+        group_name: policy.group_name,
+        route_strategy: policy.route_strategy,
+        account_max_inflight: policy.account_max_inflight,
+        cooldown_multiplier_millis: policy.cooldown_multiplier_millis,
+        created_at: policy.created_at,
+        updated_at: policy.updated_at,
+    }
+}
+
+#[cfg(test)]
+#[path = "account_group_policy_tests.rs"]
+mod tests;
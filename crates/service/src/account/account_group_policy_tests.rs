@@ -0,0 +1,112 @@
+use super::*;
+use codexmanager_core::storage::Storage;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::test_env_guard;
+
+static GROUP_POLICY_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = GROUP_POLICY_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn params(group_name: &str) -> AccountGroupPolicySetParams {
+    AccountGroupPolicySetParams {
+        group_name: group_name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn set_account_group_policy_normalizes_and_round_trips() {
+    let _lock = test_env_guard();
+    let dir = new_test_dir("account-group-policy-set");
+    let db_path = dir.join("codexmanager.db");
+    Storage::open(&db_path)
+        .expect("open db")
+        .init()
+        .expect("init db");
+    let _guard = EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref());
+
+    let saved = set_account_group_policy(AccountGroupPolicySetParams {
+        route_strategy: Some(" RR ".to_string()),
+        account_max_inflight: Some(4),
+        allowed_models: vec![" gpt-5* ".to_string(), "gpt-5*".to_string(), String::new()],
+        fallback_groups: vec!["premium".to_string(), "shared".to_string()],
+        cooldown_multiplier_millis: Some(2500),
+        ..params(" premium ")
+    })
+    .expect("save policy");
+
+    assert_eq!(saved.group_name, "premium");
+    assert_eq!(saved.route_strategy.as_deref(), Some("balanced"));
+    assert_eq!(saved.allowed_models, vec!["gpt-5*".to_string()]);
+    assert_eq!(saved.fallback_groups, vec!["shared".to_string()]);
+
+    let listed = list_account_group_policies().expect("list policies");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].account_max_inflight, Some(4));
+    assert_eq!(listed[0].cooldown_multiplier_millis, Some(2500));
+
+    delete_account_group_policy("premium").expect("delete policy");
+    assert!(list_account_group_policies()
+        .expect("list after delete")
+        .is_empty());
+    let err = delete_account_group_policy("premium").expect_err("delete missing policy");
+    assert!(err.contains("not found"));
+}
+
+#[test]
+fn set_account_group_policy_rejects_invalid_values() {
+    let err = set_account_group_policy(AccountGroupPolicySetParams {
+        route_strategy: Some("random".to_string()),
+        ..params("batch")
+    })
+    .expect_err("invalid strategy");
+    assert!(err.contains("invalid routeStrategy"));
+
+    let err = set_account_group_policy(AccountGroupPolicySetParams {
+        account_max_inflight: Some(-1),
+        ..params("batch")
+    })
+    .expect_err("negative inflight");
+    assert!(err.contains("accountMaxInflight"));
+
+    let err = set_account_group_policy(AccountGroupPolicySetParams {
+        cooldown_multiplier_millis: Some(MAX_COOLDOWN_MULTIPLIER_MILLIS + 1),
+        ..params("batch")
+    })
+    .expect_err("multiplier out of range");
+    assert!(err.contains("cooldownMultiplierMillis"));
+
+    let err = set_account_group_policy(params("  ")).expect_err("missing group");
+    assert_eq!(err, "missing groupName");
+}
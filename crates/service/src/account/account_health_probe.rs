@@ -0,0 +1,443 @@
+use codexmanager_core::storage::{
+    now_ts, Account, AccountHealthProbeRecord, AccountHealthState, Storage, Token,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::account_status::set_account_status;
+use crate::account_warmup::{
+    resolve_warmup_model_slug, send_account_responses_request, upstream_status_code_from_message,
+};
+use crate::storage_helpers::open_storage;
+
+pub(crate) const ACCOUNT_STATUS_QUARANTINED: &str = "quarantined";
+const HEALTH_PROBE_MESSAGE: &str = "hi";
+const HEALTH_PROBE_HISTORY_RETAIN_PER_ACCOUNT: i64 = 200;
+const DEFAULT_HEALTH_PROBE_HISTORY_LIMIT: i64 = 50;
+const MAX_HEALTH_PROBE_HISTORY_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HealthProbeOutcome {
+    Success,
+    AuthFailure,
+    ServerError,
+    Inconclusive,
+}
+
+impl HealthProbeOutcome {
+    pub(crate) fn as_code(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::AuthFailure => "auth_failure",
+            Self::ServerError => "server_error",
+            Self::Inconclusive => "inconclusive",
+        }
+    }
+
+    fn counts_as_failure(self) -> bool {
+        matches!(self, Self::AuthFailure | Self::ServerError)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HealthTransition {
+    Unchanged,
+    Quarantine,
+    Recover,
+}
+
+impl HealthTransition {
+    fn as_code(self) -> Option<&'static str> {
+        match self {
+            Self::Unchanged => None,
+            Self::Quarantine => Some("quarantined"),
+            Self::Recover => Some("recovered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HealthProbeSample<'a> {
+    pub outcome: HealthProbeOutcome,
+    pub status_code: Option<u16>,
+    pub error: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HealthProbePolicy {
+    pub failure_threshold: u32,
+    pub recovery_threshold: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountHealthProbeRunResult {
+    pub(crate) requested: usize,
+    pub(crate) succeeded: usize,
+    pub(crate) failed: usize,
+    pub(crate) quarantined: usize,
+    pub(crate) recovered: usize,
+    pub(crate) results: Vec<AccountHealthProbeItemResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountHealthProbeItemResult {
+    pub(crate) account_id: String,
+    pub(crate) account_name: String,
+    pub(crate) outcome: &'static str,
+    pub(crate) status_code: Option<u16>,
+    pub(crate) transition: Option<&'static str>,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountHealthStateItem {
+    pub(crate) account_id: String,
+    pub(crate) consecutive_failures: i64,
+    pub(crate) consecutive_successes: i64,
+    pub(crate) last_outcome: Option<String>,
+    pub(crate) last_status_code: Option<i64>,
+    pub(crate) last_error: Option<String>,
+    pub(crate) last_probe_at: Option<i64>,
+    pub(crate) quarantined_at: Option<i64>,
+    pub(crate) recovered_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountHealthProbeHistoryItem {
+    pub(crate) id: i64,
+    pub(crate) outcome: String,
+    pub(crate) status_code: Option<i64>,
+    pub(crate) duration_ms: Option<i64>,
+    pub(crate) error: Option<String>,
+    pub(crate) transition: Option<String>,
+    pub(crate) probed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountHealthProbeHistoryResult {
+    pub(crate) account_id: String,
+    pub(crate) state: Option<AccountHealthStateItem>,
+    pub(crate) items: Vec<AccountHealthProbeHistoryItem>,
+}
+
+/// 将探测错误归类：只有鉴权失败与上游 5xx 计入连续失败，限流或传输抖动不影响隔离计数。
+pub(crate) fn classify_health_probe_error(err: &str) -> (HealthProbeOutcome, Option<u16>) {
+    let status_code = upstream_status_code_from_message(err);
+    let outcome = match status_code {
+        Some(401 | 403) => HealthProbeOutcome::AuthFailure,
+        Some(code) if code >= 500 => HealthProbeOutcome::ServerError,
+        _ => HealthProbeOutcome::Inconclusive,
+    };
+    (outcome, status_code)
+}
+
+/// 根据上一轮状态与本次探测结果计算新的健康状态，以及是否需要隔离或恢复账号。
+pub(crate) fn next_health_state(
+    previous: Option<&AccountHealthState>,
+    account_id: &str,
+    account_status: &str,
+    sample: HealthProbeSample<'_>,
+    now: i64,
+    policy: HealthProbePolicy,
+) -> (AccountHealthState, HealthTransition) {
+    let mut state = previous.cloned().unwrap_or(AccountHealthState {
+        account_id: account_id.to_string(),
+        consecutive_failures: 0,
+        consecutive_successes: 0,
+        last_outcome: None,
+        last_status_code: None,
+        last_error: None,
+        last_probe_at: None,
+        quarantined_at: None,
+        recovered_at: None,
+        updated_at: now,
+    });
+    let outcome = sample.outcome;
+    state.last_outcome = Some(outcome.as_code().to_string());
+    state.last_status_code = sample.status_code.map(i64::from);
+    state.last_error = sample.error.map(str::to_string);
+    state.last_probe_at = Some(now);
+    state.updated_at = now;
+
+    if outcome == HealthProbeOutcome::Success {
+        state.consecutive_successes = state.consecutive_successes.saturating_add(1);
+        state.consecutive_failures = 0;
+    } else if outcome.counts_as_failure() {
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        state.consecutive_successes = 0;
+    }
+
+    let status = account_status.trim();
+    let transition = if status.eq_ignore_ascii_case(ACCOUNT_STATUS_QUARANTINED) {
+        if state.consecutive_successes >= i64::from(policy.recovery_threshold.max(1)) {
+            HealthTransition::Recover
+        } else {
+            HealthTransition::Unchanged
+        }
+    } else if status.eq_ignore_ascii_case("active")
+        && state.consecutive_failures >= i64::from(policy.failure_threshold.max(1))
+    {
+        HealthTransition::Quarantine
+    } else {
+        HealthTransition::Unchanged
+    };
+    match transition {
+        HealthTransition::Quarantine => {
+            state.quarantined_at = Some(now);
+            state.consecutive_successes = 0;
+        }
+        HealthTransition::Recover => {
+            state.recovered_at = Some(now);
+            state.consecutive_failures = 0;
+        }
+        HealthTransition::Unchanged => {}
+    }
+    (state, transition)
+}
+
+/// 对指定账号（为空时为全部可路由账号与已隔离账号）执行一次健康探测。
+pub(crate) fn run_account_health_probes(
+    account_ids: Vec<String>,
+) -> Result<AccountHealthProbeRunResult, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let targets = resolve_health_probe_targets(&storage, &account_ids)?;
+    let policy = crate::usage_refresh::health_probe_policy();
+    let model_slug = resolve_warmup_model_slug(&storage);
+
+    let mut result = AccountHealthProbeRunResult {
+        requested: targets.len(),
+        succeeded: 0,
+        failed: 0,
+        quarantined: 0,
+        recovered: 0,
+        results: Vec::with_capacity(targets.len()),
+    };
+    for (account, token) in targets {
+        let item = probe_single_account(&storage, account, token, model_slug.as_str(), policy);
+        if item.outcome == HealthProbeOutcome::Success.as_code() {
+            result.succeeded += 1;
+        } else {
+            result.failed += 1;
+        }
+        match item.transition {
+            Some("quarantined") => result.quarantined += 1,
+            Some("recovered") => result.recovered += 1,
+            _ => {}
+        }
+        result.results.push(item);
+    }
+    Ok(result)
+}
+
+/// 后台探测循环的单轮任务。
+pub(crate) fn run_health_probe_cycle() -> Result<(), String> {
+    let result = run_account_health_probes(Vec::new())?;
+    if result.quarantined > 0 || result.recovered > 0 {
+        log::info!(
+            "account health probe finished: requested={} succeeded={} failed={} quarantined={} recovered={}",
+            result.requested,
+            result.succeeded,
+            result.failed,
+            result.quarantined,
+            result.recovered
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn list_account_health_states() -> Result<Vec<AccountHealthStateItem>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_account_health_states()
+        .map(|states| states.into_iter().map(health_state_item).collect())
+        .map_err(|err| err.to_string())
+}
+
+pub(crate) fn read_account_health_history(
+    account_id: &str,
+    limit: Option<i64>,
+) -> Result<AccountHealthProbeHistoryResult, String> {
+    let account_id = account_id.trim();
+    if account_id.is_empty() {
+        return Err("missing accountId".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let limit = limit
+        .unwrap_or(DEFAULT_HEALTH_PROBE_HISTORY_LIMIT)
+        .clamp(1, MAX_HEALTH_PROBE_HISTORY_LIMIT);
+    let state = storage
+        .get_account_health_state(account_id)
+        .map_err(|err| err.to_string())?
+        .map(health_state_item);
+    let items = storage
+        .list_account_health_probes(account_id, limit)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .map(|record| AccountHealthProbeHistoryItem {
+            id: record.id,
+            outcome: record.outcome,
+            status_code: record.status_code,
+            duration_ms: record.duration_ms,
+            error: record.error,
+            transition: record.transition,
+            probed_at: record.probed_at,
+        })
+        .collect();
+    Ok(AccountHealthProbeHistoryResult {
+        account_id: account_id.to_string(),
+        state,
+        items,
+    })
+}
+
+fn health_state_item(state: AccountHealthState) -> AccountHealthStateItem {
+    AccountHealthStateItem {
+        account_id: state.account_id,
+        consecutive_failures: state.consecutive_failures,
+        consecutive_successes: state.consecutive_successes,
+        last_outcome: state.last_outcome,
+        last_status_code: state.last_status_code,
+        last_error: state.last_error,
+        last_probe_at: state.last_probe_at,
+        quarantined_at: state.quarantined_at,
+        recovered_at: state.recovered_at,
+    }
+}
+
+fn resolve_health_probe_targets(
+    storage: &Storage,
+    account_ids: &[String],
+) -> Result<Vec<(Account, Token)>, String> {
+    let mut targets = if account_ids.is_empty() {
+        storage.list_gateway_candidates()
+    } else {
+        storage.list_gateway_candidates_for_accounts(account_ids)
+    }
+    .map_err(|err| err.to_string())?;
+    let requested = account_ids
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .collect::<HashSet<_>>();
+    let mut seen = targets
+        .iter()
+        .map(|(account, _)| account.id.clone())
+        .collect::<HashSet<_>>();
+    for (account, token) in storage
+        .list_quarantined_accounts_with_tokens()
+        .map_err(|err| err.to_string())?
+    {
+        if !requested.is_empty() && !requested.contains(account.id.as_str()) {
+            continue;
+        }
+        if seen.insert(account.id.clone()) {
+            targets.push((account, token));
+        }
+    }
+    Ok(targets)
+}
+
+fn probe_single_account(
+    storage: &Storage,
+    account: Account,
+    mut token: Token,
+    model_slug: &str,
+    policy: HealthProbePolicy,
+) -> AccountHealthProbeItemResult {
+    let started_at = Instant::now();
+    let send_result = crate::gateway::fresh_upstream_client_for_account(&account.id)
+        .map_err(|err| format!("build health probe client failed: {err}"))
+        .and_then(|client| {
+            send_account_responses_request(
+                storage,
+                &client,
+                &account,
+                &mut token,
+                model_slug,
+                HEALTH_PROBE_MESSAGE,
+            )
+        });
+    let duration_ms = started_at.elapsed().as_millis() as i64;
+    let (outcome, status_code, error) = match send_result {
+        Ok(_) => (HealthProbeOutcome::Success, Some(200), None),
+        Err(err) => {
+            let (outcome, status_code) = classify_health_probe_error(&err);
+            (outcome, status_code, Some(err))
+        }
+    };
+
+    let now = now_ts();
+    let previous = storage.get_account_health_state(&account.id).ok().flatten();
+    let (state, transition) = next_health_state(
+        previous.as_ref(),
+        &account.id,
+        &account.status,
+        HealthProbeSample {
+            outcome,
+            status_code,
+            error: error.as_deref(),
+        },
+        now,
+        policy,
+    );
+    if let Err(err) = storage.save_account_health_state(&state) {
+        log::warn!(
+            "event=account_health_state_save_failed account_id={} error={}",
+            account.id,
+            err
+        );
+    }
+    let _ = storage.insert_account_health_probe(&AccountHealthProbeRecord {
+        id: 0,
+        account_id: account.id.clone(),
+        outcome: outcome.as_code().to_string(),
+        status_code: status_code.map(i64::from),
+        duration_ms: Some(duration_ms.max(0)),
+        error: error.clone(),
+        transition: transition.as_code().map(str::to_string),
+        probed_at: now,
+    });
+    let _ =
+        storage.prune_account_health_probes(&account.id, HEALTH_PROBE_HISTORY_RETAIN_PER_ACCOUNT);
+
+    match transition {
+        HealthTransition::Quarantine => {
+            let reason = format!("health_probe_{}", outcome.as_code());
+            set_account_status(storage, &account.id, ACCOUNT_STATUS_QUARANTINED, &reason);
+            log::warn!(
+                "event=account_health_quarantined account_id={} failures={} status_code={:?}",
+                account.id,
+                state.consecutive_failures,
+                status_code
+            );
+        }
+        HealthTransition::Recover => {
+            set_account_status(storage, &account.id, "active", "health_probe_recovered");
+            log::info!(
+                "event=account_health_recovered account_id={} successes={}",
+                account.id,
+                state.consecutive_successes
+            );
+        }
+        HealthTransition::Unchanged => {}
+    }
+
+    AccountHealthProbeItemResult {
+        account_id: account.id,
+        account_name: account.label,
+        outcome: outcome.as_code(),
+        status_code,
+        transition: transition.as_code(),
+        message: error.unwrap_or_else(|| "ok".to_string()),
+    }
+}
+
+#[cfg(test)]
+#[path = "account_health_probe_tests.rs"]
+mod tests;
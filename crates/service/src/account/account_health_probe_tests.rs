@@ -0,0 +1,99 @@
+use super::*;
+
+const POLICY: HealthProbePolicy = HealthProbePolicy {
+    failure_threshold: 3,
+    recovery_threshold: 2,
+};
+
+fn advance(
+    previous: Option<&AccountHealthState>,
+    status: &str,
+    outcome: HealthProbeOutcome,
+    now: i64,
+) -> (AccountHealthState, HealthTransition) {
+    let sample = HealthProbeSample {
+        outcome,
+        status_code: None,
+        error: None,
+    };
+    next_health_state(previous, "acc-1", status, sample, now, POLICY)
+}
+
+#[test]
+fn classify_health_probe_error_only_counts_auth_and_server_failures() {
+    assert_eq!(
+        classify_health_probe_error("warmup request failed: status=401 body=unauthorized"),
+        (HealthProbeOutcome::AuthFailure, Some(401))
+    );
+    assert_eq!(
+        classify_health_probe_error("warmup request failed: status=403 body=forbidden"),
+        (HealthProbeOutcome::AuthFailure, Some(403))
+    );
+    assert_eq!(
+        classify_health_probe_error("warmup request failed: status=502 body=bad gateway"),
+        (HealthProbeOutcome::ServerError, Some(502))
+    );
+    assert_eq!(
+        classify_health_probe_error("warmup request failed: status=429 body=rate limited"),
+        (HealthProbeOutcome::Inconclusive, Some(429))
+    );
+    assert_eq!(
+        classify_health_probe_error("warmup request failed: connection reset"),
+        (HealthProbeOutcome::Inconclusive, None)
+    );
+}
+
+#[test]
+fn consecutive_failures_quarantine_active_account_at_threshold() {
+    let (state, transition) = advance(None, "active", HealthProbeOutcome::ServerError, 10);
+    assert_eq!(transition, HealthTransition::Unchanged);
+    let (state, transition) = advance(Some(&state), "active", HealthProbeOutcome::AuthFailure, 20);
+    assert_eq!(transition, HealthTransition::Unchanged);
+    let (state, transition) = advance(Some(&state), "active", HealthProbeOutcome::ServerError, 30);
+
+    assert_eq!(transition, HealthTransition::Quarantine);
+    assert_eq!(state.consecutive_failures, 3);
+    assert_eq!(state.quarantined_at, Some(30));
+    assert_eq!(state.last_outcome.as_deref(), Some("server_error"));
+}
+
+#[test]
+fn success_or_inconclusive_probe_breaks_failure_streak_differently() {
+    let (state, _) = advance(None, "active", HealthProbeOutcome::ServerError, 10);
+    let (state, _) = advance(Some(&state), "active", HealthProbeOutcome::ServerError, 20);
+    let (state, transition) = advance(Some(&state), "active", HealthProbeOutcome::Inconclusive, 30);
+    assert_eq!(transition, HealthTransition::Unchanged);
+    assert_eq!(state.consecutive_failures, 2);
+
+    let (state, transition) = advance(Some(&state), "active", HealthProbeOutcome::Success, 40);
+    assert_eq!(transition, HealthTransition::Unchanged);
+    assert_eq!(state.consecutive_failures, 0);
+    assert_eq!(state.consecutive_successes, 1);
+}
+
+#[test]
+fn failures_do_not_quarantine_accounts_outside_active_status() {
+    let mut previous = None;
+    for now in 1..=5 {
+        let (state, transition) = advance(
+            previous.as_ref(),
+            "disabled",
+            HealthProbeOutcome::AuthFailure,
+            now,
+        );
+        assert_eq!(transition, HealthTransition::Unchanged);
+        previous = Some(state);
+    }
+}
+
+#[test]
+fn quarantined_account_recovers_after_consecutive_successes() {
+    let (state, _) = advance(None, "quarantined", HealthProbeOutcome::AuthFailure, 10);
+    let (state, transition) = advance(Some(&state), "quarantined", HealthProbeOutcome::Success, 20);
+    assert_eq!(transition, HealthTransition::Unchanged);
+    let (state, transition) = advance(Some(&state), "quarantined", HealthProbeOutcome::Success, 30);
+
+    assert_eq!(transition, HealthTransition::Recover);
+    assert_eq!(state.recovered_at, Some(30));
+    assert_eq!(state.consecutive_successes, 2);
+}
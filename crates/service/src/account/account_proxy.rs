@@ -19,6 +19,7 @@ pub(crate) const STATUS_INVALID_URL: &str = "invalid_url";
 pub(crate) const ENV_ACCOUNT_PROXY_DEBUG: &str = "CODEXMANAGER_ACCOUNT_PROXY_DEBUG";
 pub(crate) const SOURCE_CUSTOM: &str = "custom";
 pub(crate) const SOURCE_PROFILE: &str = "profile";
+pub(crate) const SOURCE_POOL: &str = "pool";
 const LOCAL_PROXY_EXPECTED_MESSAGE: &str = "Codex-Manager supports HTTP, HTTPS, SOCKS4, and SOCKS5 proxy URLs, for example http://host:port or socks5://host:port. For sing-box, paste the local mixed inbound address, e.g. http://127.0.0.1:7891.";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,6 +55,9 @@ pub(crate) struct AccountProxySettingsResponse {
     pub proxy_profile_id: Option<String>,
     pub proxy_profile_name: Option<String>,
     pub proxy_profile_enabled: Option<bool>,
+    pub proxy_pool_id: Option<String>,
+    pub proxy_pool_name: Option<String>,
+    pub proxy_pool_failover_count: Option<i64>,
     pub proxy_url: String,
     pub proxy_url_redacted: String,
     pub status: String,
@@ -84,6 +88,7 @@ pub(crate) struct AccountProxySettingsResponse {
 pub(crate) enum AccountProxySource {
     Custom,
     Profile,
+    Pool,
 }
 
 #[derive(Debug, Clone)]
@@ -97,6 +102,7 @@ impl AccountProxySource {
         match self {
             Self::Custom => SOURCE_CUSTOM,
             Self::Profile => SOURCE_PROFILE,
+            Self::Pool => SOURCE_POOL,
         }
     }
 }
@@ -115,6 +121,7 @@ pub(crate) fn set_account_proxy_settings(
     enabled: bool,
     source: Option<&str>,
     proxy_profile_id: Option<&str>,
+    proxy_pool_id: Option<&str>,
     proxy_url: Option<&str>,
     status: Option<&str>,
     latency_ms: Option<i64>,
@@ -134,13 +141,37 @@ pub(crate) fn set_account_proxy_settings(
     let previous = storage
         .find_account_proxy_settings(account_id)
         .map_err(|err| format!("read account proxy settings failed: {err}"))?;
+    let source = source.or_else(|| {
+        proxy_pool_id
+            .is_some_and(|value| !value.trim().is_empty())
+            .then_some(SOURCE_POOL)
+    });
     let requested_source =
         resolve_requested_source(source, proxy_profile_id, proxy_url, previous.as_ref());
     let normalized_profile_id =
         normalize_profile_id_for_source(requested_source, proxy_profile_id, previous.as_ref())?;
+    let previous_pool_id = storage
+        .find_account_proxy_pool_binding(account_id)
+        .map_err(|err| format!("read account proxy pool binding failed: {err}"))?
+        .map(|binding| binding.proxy_pool_id);
+    let normalized_pool_id = match requested_source {
+        AccountProxySource::Pool => {
+            let pool_id = normalize_optional_str(proxy_pool_id)
+                .or_else(|| previous_pool_id.clone())
+                .ok_or_else(|| {
+                    "proxyPoolId is required when account proxy source is pool".to_string()
+                })?;
+            storage
+                .find_proxy_pool(pool_id.as_str())
+                .map_err(|err| format!("read proxy pool failed: {err}"))?
+                .ok_or_else(|| format!("proxy pool not found: {pool_id}"))?;
+            Some(pool_id)
+        }
+        _ => None,
+    };
     let normalized_proxy_url = match requested_source {
         AccountProxySource::Custom => normalize_proxy_url_for_setting(enabled, proxy_url)?,
-        AccountProxySource::Profile => {
+        AccountProxySource::Profile | AccountProxySource::Pool => {
             normalize_proxy_url_for_storage_only(proxy_url).or_else(|| {
                 previous
                     .as_ref()
@@ -161,6 +192,7 @@ pub(crate) fn set_account_proxy_settings(
         .and_then(|settings| normalize_optional_str(settings.proxy_url.as_deref()));
     let binding_changed = requested_source != previous_source
         || normalized_profile_id.as_deref() != previous_profile_id.as_deref()
+        || (requested_source == AccountProxySource::Pool && normalized_pool_id != previous_pool_id)
         || (requested_source == AccountProxySource::Custom
             && normalized_proxy_url_ref != previous_proxy_url.as_deref());
     let default_status = if enabled {
@@ -296,6 +328,14 @@ pub(crate) fn set_account_proxy_settings(
             final_flag_emoji,
         )
         .map_err(|err| format!("store account proxy settings failed: {err}"))?;
+    match normalized_pool_id.as_deref() {
+        Some(pool_id) => storage.bind_account_proxy_pool(account_id, pool_id),
+        None => storage
+            .delete_account_proxy_pool_binding(account_id)
+            .map(|_| ()),
+    }
+    .map_err(|err| format!("store account proxy pool binding failed: {err}"))?;
+    crate::proxy_pools::forget_account_pool_assignment(account_id);
     crate::gateway::invalidate_account_proxy_cache(account_id);
 
     if enabled && status.is_none() {
@@ -324,6 +364,10 @@ pub(crate) fn clear_account_proxy_settings(
     storage
         .clear_account_proxy_settings(account_id)
         .map_err(|err| format!("clear account proxy settings failed: {err}"))?;
+    storage
+        .delete_account_proxy_pool_binding(account_id)
+        .map_err(|err| format!("clear account proxy pool binding failed: {err}"))?;
+    crate::proxy_pools::forget_account_pool_assignment(account_id);
     crate::gateway::invalidate_account_proxy_cache(account_id);
     Ok(default_response(account_id))
 }
@@ -547,6 +591,24 @@ where
                 }
             }
         }
+        // 中文注释：池的出口由运行时分配，草稿阶段没有可测的具体成员，需保存后按已分配出口测试。
+        AccountProxySource::Pool => {
+            return Ok(response_from_parts(
+                account_id,
+                enabled,
+                draft_source,
+                normalized_profile_id,
+                bound_profile.as_ref(),
+                custom_proxy_url,
+                STATUS_INVALID_URL,
+                None,
+                None,
+                None,
+                Some(now_ts()),
+                Some("save the proxy pool binding before testing it".to_string()),
+                None,
+            ));
+        }
         AccountProxySource::Profile => match resolve_profile_proxy_url(
             storage,
             account_id,
@@ -732,6 +794,16 @@ pub(crate) fn resolve_account_proxy_mode_from_storage(
                 },
             }
         }
+        AccountProxySource::Pool => {
+            match crate::proxy_pools::resolve_account_pool_proxy(storage, account_id) {
+                Ok((_, proxy_url)) => AccountProxyMode::Explicit { proxy_url, source },
+                Err(error) => AccountProxyMode::Invalid {
+                    proxy_url: None,
+                    error,
+                    source,
+                },
+            }
+        }
     }
 }
 
@@ -769,6 +841,7 @@ fn resolve_requested_source(
 ) -> AccountProxySource {
     match source.map(str::trim).filter(|value| !value.is_empty()) {
         Some(value) if value.eq_ignore_ascii_case(SOURCE_PROFILE) => AccountProxySource::Profile,
+        Some(value) if value.eq_ignore_ascii_case(SOURCE_POOL) => AccountProxySource::Pool,
         Some(value) if value.eq_ignore_ascii_case(SOURCE_CUSTOM) => AccountProxySource::Custom,
         Some(_) => AccountProxySource::Custom,
         None if proxy_profile_id.is_some_and(|value| !value.trim().is_empty()) => {
@@ -791,6 +864,7 @@ fn account_proxy_source_from_settings(settings: &AccountProxySettings) -> Accoun
         .filter(|value| !value.is_empty())
     {
         Some(value) if value.eq_ignore_ascii_case(SOURCE_PROFILE) => AccountProxySource::Profile,
+        Some(value) if value.eq_ignore_ascii_case(SOURCE_POOL) => AccountProxySource::Pool,
         Some(value) if value.eq_ignore_ascii_case(SOURCE_CUSTOM) => AccountProxySource::Custom,
         _ if settings
             .proxy_profile_id
@@ -809,7 +883,7 @@ fn normalize_profile_id_for_source(
     previous: Option<&AccountProxySettings>,
 ) -> Result<Option<String>, String> {
     match source {
-        AccountProxySource::Custom => Ok(None),
+        AccountProxySource::Custom | AccountProxySource::Pool => Ok(None),
         AccountProxySource::Profile => {
             let current = normalize_optional_str(proxy_profile_id).or_else(|| {
                 previous.and_then(|settings| {
@@ -916,6 +990,14 @@ fn resolve_stored_account_proxy_test_target(
                 proxy_url,
             })
         }
+        AccountProxySource::Pool => {
+            let (profile, proxy_url) =
+                crate::proxy_pools::resolve_account_pool_proxy(storage, account_id)?;
+            Ok(AccountProxyTestTarget {
+                proxy_profile_id: Some(profile.id),
+                proxy_url,
+            })
+        }
     }
 }
 
@@ -1123,15 +1205,52 @@ fn account_proxy_settings_response(
     settings: AccountProxySettings,
 ) -> Result<AccountProxySettingsResponse, String> {
     let source = account_proxy_source_from_settings(&settings);
-    let proxy_profile = load_proxy_profile(storage, settings.proxy_profile_id.as_deref())?;
-    if source == AccountProxySource::Profile && proxy_profile.is_some() {
-        let p = proxy_profile.as_ref().unwrap();
-        Ok(response_from_parts(
+    let pool_binding = if source == AccountProxySource::Pool {
+        storage
+            .find_account_proxy_pool_binding(settings.account_id.as_str())
+            .map_err(|err| format!("read account proxy pool binding failed: {err}"))?
+    } else {
+        None
+    };
+    let proxy_profile_id = match source {
+        AccountProxySource::Pool => pool_binding
+            .as_ref()
+            .and_then(|binding| binding.proxy_profile_id.clone()),
+        _ => settings.proxy_profile_id.clone(),
+    };
+    let proxy_profile = load_proxy_profile(storage, proxy_profile_id.as_deref())?;
+    let mut response = account_proxy_settings_response_for_profile(
+        settings,
+        source,
+        proxy_profile_id,
+        proxy_profile.as_ref(),
+    );
+    if let Some(binding) = pool_binding {
+        response.proxy_pool_name = storage
+            .find_proxy_pool(binding.proxy_pool_id.as_str())
+            .map_err(|err| format!("read proxy pool failed: {err}"))?
+            .map(|pool| pool.name);
+        response.proxy_pool_id = Some(binding.proxy_pool_id);
+        response.proxy_pool_failover_count = Some(binding.failover_count);
+    }
+    Ok(response)
+}
+
+fn account_proxy_settings_response_for_profile(
+    settings: AccountProxySettings,
+    source: AccountProxySource,
+    proxy_profile_id: Option<String>,
+    proxy_profile: Option<&ProxyProfile>,
+) -> AccountProxySettingsResponse {
+    if let (AccountProxySource::Profile | AccountProxySource::Pool, Some(p)) =
+        (source, proxy_profile)
+    {
+        response_from_parts(
             settings.account_id.as_str(),
             settings.enabled,
             source,
-            settings.proxy_profile_id.clone(),
-            proxy_profile.as_ref(),
+            proxy_profile_id,
+            Some(p),
             settings.proxy_url.unwrap_or_default(),
             p.status.as_str(),
             p.last_url_latency_ms,
@@ -1157,14 +1276,14 @@ fn account_proxy_settings_response(
                 flag_img_url: p.flag_img_url.clone(),
                 flag_emoji: p.flag_emoji.clone(),
             }),
-        ))
+        )
     } else {
-        Ok(response_from_parts(
+        response_from_parts(
             settings.account_id.as_str(),
             settings.enabled,
             source,
-            settings.proxy_profile_id.clone(),
-            proxy_profile.as_ref(),
+            proxy_profile_id,
+            proxy_profile,
             settings.proxy_url.unwrap_or_default(),
             settings.status.as_str(),
             settings.latency_ms,
@@ -1190,7 +1309,7 @@ fn account_proxy_settings_response(
                 flag_img_url: settings.flag_img_url,
                 flag_emoji: settings.flag_emoji,
             }),
-        ))
+        )
     }
 }
 
@@ -1218,7 +1337,7 @@ fn response_from_parts(
                 derive_proxy_profile_url_metadata(proxy_url.as_str()).proxy_url_redacted
             }
         }
-        AccountProxySource::Profile => proxy_profile
+        AccountProxySource::Profile | AccountProxySource::Pool => proxy_profile
             .map(|profile| profile.proxy_url_redacted.clone())
             .unwrap_or_else(|| {
                 if proxy_profile_id.is_some() {
@@ -1235,6 +1354,9 @@ fn response_from_parts(
         proxy_profile_id,
         proxy_profile_name: proxy_profile.map(|profile| profile.name.clone()),
         proxy_profile_enabled: proxy_profile.map(|profile| profile.enabled),
+        proxy_pool_id: None,
+        proxy_pool_name: None,
+        proxy_pool_failover_count: None,
         proxy_url,
         proxy_url_redacted,
         status: status.to_string(),
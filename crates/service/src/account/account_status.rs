@@ -91,6 +91,15 @@ pub(crate) fn set_account_status_with_context(
         .unwrap_or((false, false));
     if changed {
         crate::gateway::invalidate_candidate_cache();
+        crate::event_bus::publish_event(
+            crate::event_bus::TOPIC_ACCOUNT_STATUS,
+            crate::event_bus::EventAudience::Admin,
+            serde_json::json!({
+                "accountId": account_id,
+                "status": status,
+                "reason": reason,
+            }),
+        );
     }
     let should_insert_event = if !account_exists || changed {
         account_exists
@@ -161,54 +161,8 @@ fn warmup_single_account(
     let AccountWarmupTarget { account, mut token } = target;
     let account_name = account.label.clone();
     let started_at = Instant::now();
-    let authorization = resolve_warmup_authorization(storage, client, &account, &token);
-    let uses_agent_identity = authorization
-        .as_ref()
-        .map(|authorization| authorization.uses_agent_identity)
-        .unwrap_or(false);
-    let failed_agent_task_id = authorization
-        .as_ref()
-        .ok()
-        .and_then(|authorization| authorization.task_id.clone());
-    let mut outcome = authorization.and_then(|authorization| {
-        send_warmup_request_with_fallback(client, &account, &authorization, model_slug, message)
-    });
-
-    if let Err(err) = outcome.as_ref() {
-        if uses_agent_identity && crate::agent_identity::is_agent_identity_task_invalid_error(err) {
-            outcome = recover_warmup_agent_identity_task(
-                storage,
-                client,
-                &account,
-                &token,
-                model_slug,
-                message,
-                failed_agent_task_id.as_deref(),
-            );
-        } else if !uses_agent_identity && should_retry_warmup_with_refresh(&token, err) {
-            let issuer = std::env::var("CODEXMANAGER_ISSUER")
-                .unwrap_or_else(|_| codexmanager_core::auth::DEFAULT_ISSUER.to_string());
-            let client_id = std::env::var("CODEXMANAGER_CLIENT_ID")
-                .unwrap_or_else(|_| codexmanager_core::auth::DEFAULT_CLIENT_ID.to_string());
-            outcome = refresh_and_persist_access_token(
-                storage,
-                &mut token,
-                &issuer,
-                &client_id,
-                token_refresh_ahead_secs(),
-            )
-            .and_then(|_| resolve_warmup_authorization(storage, client, &account, &token))
-            .and_then(|authorization| {
-                send_warmup_request_with_fallback(
-                    client,
-                    &account,
-                    &authorization,
-                    model_slug,
-                    message,
-                )
-            });
-        }
-    }
+    let outcome =
+        send_account_responses_request(storage, client, &account, &mut token, model_slug, message);
 
     match outcome {
         Ok(ok_message) => {
@@ -251,6 +205,66 @@ fn warmup_single_account(
     }
 }
 
+/// 以账号自身凭据与代理发送一次最小 Responses 请求；鉴权失效时按预热同样的策略刷新或恢复一次。
+pub(crate) fn send_account_responses_request(
+    storage: &Storage,
+    client: &Client,
+    account: &Account,
+    token: &mut Token,
+    model_slug: &str,
+    message: &str,
+) -> Result<String, String> {
+    let authorization = resolve_warmup_authorization(storage, client, account, token);
+    let uses_agent_identity = authorization
+        .as_ref()
+        .map(|authorization| authorization.uses_agent_identity)
+        .unwrap_or(false);
+    let failed_agent_task_id = authorization
+        .as_ref()
+        .ok()
+        .and_then(|authorization| authorization.task_id.clone());
+    let mut outcome = authorization.and_then(|authorization| {
+        send_warmup_request_with_fallback(client, account, &authorization, model_slug, message)
+    });
+
+    if let Err(err) = outcome.as_ref() {
+        if uses_agent_identity && crate::agent_identity::is_agent_identity_task_invalid_error(err) {
+            outcome = recover_warmup_agent_identity_task(
+                storage,
+                client,
+                account,
+                token,
+                model_slug,
+                message,
+                failed_agent_task_id.as_deref(),
+            );
+        } else if !uses_agent_identity && should_retry_warmup_with_refresh(token, err) {
+            let issuer = std::env::var("CODEXMANAGER_ISSUER")
+                .unwrap_or_else(|_| codexmanager_core::auth::DEFAULT_ISSUER.to_string());
+            let client_id = std::env::var("CODEXMANAGER_CLIENT_ID")
+                .unwrap_or_else(|_| codexmanager_core::auth::DEFAULT_CLIENT_ID.to_string());
+            outcome = refresh_and_persist_access_token(
+                storage,
+                token,
+                &issuer,
+                &client_id,
+                token_refresh_ahead_secs(),
+            )
+            .and_then(|_| resolve_warmup_authorization(storage, client, account, token))
+            .and_then(|authorization| {
+                send_warmup_request_with_fallback(
+                    client,
+                    account,
+                    &authorization,
+                    model_slug,
+                    message,
+                )
+            });
+        }
+    }
+    outcome
+}
+
 fn persist_warmup_observability(
     storage: &Storage,
     account: &Account,
@@ -298,18 +312,23 @@ fn persist_warmup_observability(
 }
 
 fn extract_status_code_from_message(message: &str) -> i64 {
+    upstream_status_code_from_message(message)
+        .map(i64::from)
+        .unwrap_or(500)
+}
+
+/// 从预热/探测错误消息中的 `status=NNN` 片段提取上游 HTTP 状态码；传输层错误没有状态码。
+pub(crate) fn upstream_status_code_from_message(message: &str) -> Option<u16> {
     let marker = "status=";
-    let Some(index) = message.find(marker) else {
-        return 500;
-    };
+    let index = message.find(marker)?;
     let digits: String = message[index + marker.len()..]
         .chars()
         .take_while(|ch| ch.is_ascii_digit())
         .collect();
-    digits.parse::<i64>().unwrap_or(500)
+    digits.parse::<u16>().ok()
 }
 
-fn resolve_warmup_model_slug(storage: &Storage) -> String {
+pub(crate) fn resolve_warmup_model_slug(storage: &Storage) -> String {
     storage
         .list_api_models_v2()
         .ok()
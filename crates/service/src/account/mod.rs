@@ -10,6 +10,10 @@ pub(crate) mod delete_many;
 pub(crate) mod export;
 #[path = "account_group.rs"]
 pub(crate) mod group;
+#[path = "account_group_policy.rs"]
+pub(crate) mod group_policy;
+#[path = "account_health_probe.rs"]
+pub(crate) mod health_probe;
 #[path = "account_import.rs"]
 pub(crate) mod import;
 #[path = "account_list.rs"]
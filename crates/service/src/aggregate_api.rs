@@ -13,10 +13,19 @@ use crate::apikey_profile::normalize_upstream_base_url;
 use crate::gateway;
 use crate::storage_helpers::{generate_aggregate_api_id, open_storage};
 
+#[path = "aggregate_api_azure.rs"]
+pub(crate) mod azure;
+#[path = "aggregate_api_bedrock.rs"]
+pub(crate) mod bedrock;
+#[path = "aggregate_api_discovery.rs"]
+pub(crate) mod discovery;
+
 pub(crate) const AGGREGATE_API_PROVIDER_CODEX: &str = "codex";
 pub(crate) const AGGREGATE_API_PROVIDER_CLAUDE: &str = "claude";
 pub(crate) const AGGREGATE_API_PROVIDER_GEMINI: &str = "gemini";
 pub(crate) const AGGREGATE_API_PROVIDER_COMPATIBLE: &str = "compatible";
+pub(crate) const AGGREGATE_API_PROVIDER_AZURE_OPENAI: &str = "azure_openai";
+pub(crate) const AGGREGATE_API_PROVIDER_BEDROCK: &str = "bedrock";
 pub(crate) const AGGREGATE_API_AUTH_APIKEY: &str = "apikey";
 pub(crate) const AGGREGATE_API_AUTH_USERPASS: &str = "userpass";
 const AGGREGATE_API_BALANCE_TEMPLATE_GENERIC: &str = "generic";
@@ -132,7 +141,7 @@ fn normalize_sort(value: Option<i64>) -> i64 {
     value.unwrap_or(0)
 }
 
-fn normalize_status(value: Option<String>) -> Result<String, String> {
+pub(crate) fn normalize_status(value: Option<String>) -> Result<String, String> {
     match value {
         Some(raw) => {
             let normalized = raw.trim().to_ascii_lowercase().replace('-', "_");
@@ -186,7 +195,7 @@ fn normalize_action(value: Option<String>) -> Result<Option<String>, String> {
     Ok(Some(with_slash))
 }
 
-fn normalize_model_override(value: Option<String>) -> Result<Option<String>, String> {
+pub(crate) fn normalize_model_override(value: Option<String>) -> Result<Option<String>, String> {
     let Some(raw) = value else {
         return Ok(None);
     };
@@ -630,7 +639,7 @@ fn apply_probe_auth(
 ///
 /// # 返回
 /// 返回函数执行结果
-fn normalize_provider_type(value: Option<String>) -> Result<String, String> {
+pub(crate) fn normalize_provider_type(value: Option<String>) -> Result<String, String> {
     match value {
         Some(raw) => {
             let normalized = raw.trim().to_ascii_lowercase().replace('-', "_");
@@ -645,6 +654,10 @@ fn normalize_provider_type(value: Option<String>) -> Result<String, String> {
                     Ok(AGGREGATE_API_PROVIDER_CLAUDE.to_string())
                 }
                 "compatible" => Ok(AGGREGATE_API_PROVIDER_COMPATIBLE.to_string()),
+                "azure_openai" | "azure" | "azure_oai" => {
+                    Ok(AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string())
+                }
+                "bedrock" | "aws_bedrock" => Ok(AGGREGATE_API_PROVIDER_BEDROCK.to_string()),
                 other => Err(format!("unsupported aggregate api provider type: {other}")),
             }
         }
@@ -673,6 +686,8 @@ fn normalize_provider_type_value(value: &str) -> String {
             AGGREGATE_API_PROVIDER_GEMINI.to_string()
         }
         "compatible" => AGGREGATE_API_PROVIDER_COMPATIBLE.to_string(),
+        "azure_openai" | "azure" | "azure_oai" => AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string(),
+        "bedrock" | "aws_bedrock" => AGGREGATE_API_PROVIDER_BEDROCK.to_string(),
         _ => AGGREGATE_API_PROVIDER_CODEX.to_string(),
     }
 }
@@ -692,6 +707,7 @@ fn provider_default_url(provider_type: &str) -> &'static str {
     match provider_type {
         AGGREGATE_API_PROVIDER_CLAUDE => "https://api.anthropic.com/v1",
         AGGREGATE_API_PROVIDER_GEMINI => "https://generativelanguage.googleapis.com",
+        AGGREGATE_API_PROVIDER_BEDROCK => bedrock::BEDROCK_DEFAULT_URL,
         _ => "https://api.openai.com/v1",
     }
 }
@@ -1527,6 +1543,120 @@ fn probe_gemini_endpoint(
     Ok(status_code)
 }
 
+fn probe_azure_openai_endpoint(
+    client: &reqwest::blocking::Client,
+    storage: &codexmanager_core::storage::Storage,
+    api: &AggregateApi,
+    secret: &str,
+    model: &str,
+) -> Result<i64, String> {
+    let deployment = azure::resolve_azure_openai_deployment(storage, api.id.as_str(), model)?;
+    let action_hint = api
+        .action
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let default_path = if action_hint.contains("responses") {
+        "/responses"
+    } else {
+        "/chat/completions"
+    };
+    let probe_path = action_path_or_default(api, default_path);
+    let url =
+        azure::build_azure_openai_url(api.url.as_str(), probe_path.as_str(), deployment.as_str())?
+            .to_string();
+    let has_auth_params = api
+        .auth_params_json
+        .as_deref()
+        .is_some_and(|value| !value.trim().is_empty());
+    let builder = if has_auth_params {
+        let builder = client.post(url.as_str());
+        let (builder, updated_url) = apply_probe_auth(builder, url.clone(), api, secret)?;
+        if updated_url != url {
+            let rebuilt = client.post(updated_url.as_str());
+            let (rebuilt, _) = apply_probe_auth(rebuilt, updated_url, api, secret)?;
+            rebuilt
+        } else {
+            builder
+        }
+    } else {
+        // 中文注释：Azure 的 API key 只认 api-key 头，不能沿用通用探测的 Bearer 组合。
+        client
+            .post(url.as_str())
+            .header(azure::AZURE_OPENAI_API_KEY_HEADER, secret.trim())
+    };
+    let request_body = if probe_path.to_ascii_lowercase().contains("responses") {
+        build_codex_probe_body(deployment.as_str())
+    } else {
+        json!({
+            "model": deployment,
+            "messages": [{"role":"user","content":"hi"}],
+            "max_tokens": 1,
+            "stream": false
+        })
+    };
+    let response = builder
+        .header("content-type", "application/json")
+        .header("accept", "application/json")
+        .header("accept-encoding", "identity")
+        .json(&request_body)
+        .send()
+        .map_err(|err| err.to_string())?;
+
+    let status_code = response.status().as_u16() as i64;
+    if !response.status().is_success() {
+        return Err(probe_http_error(
+            "azure_openai",
+            status_code as u16,
+            response,
+        ));
+    }
+    read_first_chunk(response)?;
+    Ok(status_code)
+}
+
+fn probe_bedrock_endpoint(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+    model: &str,
+) -> Result<i64, String> {
+    let (credentials, region) = bedrock::resolve_bedrock_signing(api, secret)?;
+    let url = bedrock::build_bedrock_invoke_url(api.url.as_str(), model, false)?;
+    let probe_body =
+        serde_json::to_vec(&build_claude_probe_body(model)).map_err(|err| err.to_string())?;
+    let (body, _) = bedrock::build_bedrock_invoke_body(probe_body.as_slice())?;
+    let mut builder = client.post(url.clone());
+    // 中文注释：签名覆盖最终请求体，构造完 body 后才能计算 SigV4 头。
+    for (name, value) in bedrock::sign_bedrock_request(
+        &credentials,
+        region.as_str(),
+        "POST",
+        &url,
+        body.as_ref(),
+        chrono::Utc::now(),
+    ) {
+        if name != "host" {
+            builder = builder.header(name, value);
+        }
+    }
+    let response = builder
+        .header("content-type", "application/json")
+        .header("accept", "application/json")
+        .header("accept-encoding", "identity")
+        .body(body)
+        .send()
+        .map_err(|err| err.to_string())?;
+
+    let status_code = response.status().as_u16() as i64;
+    if !response.status().is_success() {
+        return Err(probe_http_error("bedrock", status_code as u16, response));
+    }
+    read_first_chunk(response)?;
+    Ok(status_code)
+}
+
 /// 函数 `list_aggregate_apis`
 ///
 /// 作者: gaohongshun
@@ -2097,6 +2227,12 @@ pub(crate) fn test_aggregate_api_connection(
         AGGREGATE_API_PROVIDER_GEMINI => {
             probe_gemini_endpoint(&client, &api, &secret, probe_model.as_str())
         }
+        AGGREGATE_API_PROVIDER_AZURE_OPENAI => {
+            probe_azure_openai_endpoint(&client, &storage, &api, &secret, probe_model.as_str())
+        }
+        AGGREGATE_API_PROVIDER_BEDROCK => {
+            probe_bedrock_endpoint(&client, &api, &secret, probe_model.as_str())
+        }
         _ => probe_codex_endpoint(&client, &api, &secret, probe_model.as_str()),
     };
     let (ok, status_code, last_error) = match result {
@@ -2172,6 +2308,17 @@ pub(crate) fn refresh_aggregate_api_balance(
                 Some(balance_json.as_str()),
                 message.as_deref(),
             );
+            crate::event_bus::publish_event(
+                crate::event_bus::TOPIC_AGGREGATE_API_BALANCE,
+                crate::event_bus::EventAudience::Admin,
+                serde_json::json!({
+                    "aggregateApiId": api_id,
+                    "ok": ok,
+                    "balance": &snapshot,
+                    "message": message,
+                    "queriedAt": queried_at,
+                }),
+            );
             Ok(AggregateApiBalanceRefreshResult {
                 id: api_id.to_string(),
                 ok,
@@ -2185,6 +2332,17 @@ pub(crate) fn refresh_aggregate_api_balance(
             let message = format!("template={template}; {err}");
             let _ =
                 storage.update_aggregate_api_balance_result(api_id, false, None, Some(&message));
+            crate::event_bus::publish_event(
+                crate::event_bus::TOPIC_AGGREGATE_API_BALANCE,
+                crate::event_bus::EventAudience::Admin,
+                serde_json::json!({
+                    "aggregateApiId": api_id,
+                    "ok": false,
+                    "balance": null,
+                    "message": message,
+                    "queriedAt": queried_at,
+                }),
+            );
             Ok(AggregateApiBalanceRefreshResult {
                 id: api_id.to_string(),
                 ok: false,
@@ -0,0 +1,265 @@
+use codexmanager_core::rpc::types::{AggregateApiDeploymentEntry, AggregateApiDeploymentSetParams};
+use codexmanager_core::storage::{now_ts, AggregateApiSupplierModel, Storage};
+
+use super::{normalize_provider_type_value, AGGREGATE_API_PROVIDER_AZURE_OPENAI};
+use crate::storage_helpers::open_storage;
+
+/// Azure OpenAI 部署路径（chat/completions 等）默认使用的 GA api-version。
+pub(crate) const AZURE_OPENAI_DEFAULT_API_VERSION: &str = "2024-10-21";
+/// Azure Responses API 仍是 preview 版本，需单独的 api-version。
+pub(crate) const AZURE_OPENAI_RESPONSES_API_VERSION: &str = "2025-04-01-preview";
+pub(crate) const AZURE_OPENAI_API_KEY_HEADER: &str = "api-key";
+const AZURE_OPENAI_API_VERSION_PARAM: &str = "api-version";
+const DEPLOYMENT_STATUS_AVAILABLE: &str = "available";
+const DEPLOYMENT_STATUS_DISABLED: &str = "disabled";
+
+pub(crate) fn is_azure_openai_provider(provider_type: &str) -> bool {
+    normalize_provider_type_value(provider_type) == AGGREGATE_API_PROVIDER_AZURE_OPENAI
+}
+
+fn is_valid_deployment_name(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+}
+
+fn is_responses_path(path: &str) -> bool {
+    path == "responses" || path.starts_with("responses/")
+}
+
+/// 按供应商模型表把上游模型映射到部署名；未配置映射时沿用模型名（Azure 常以模型名命名部署）。
+pub(crate) fn resolve_azure_openai_deployment(
+    storage: &Storage,
+    api_id: &str,
+    upstream_model: &str,
+) -> Result<String, String> {
+    let upstream_model = upstream_model.trim();
+    if upstream_model.is_empty() {
+        return Err("azure openai deployment unresolved: missing model".to_string());
+    }
+    let mapped = storage
+        .list_aggregate_api_supplier_models(Some(api_id), Some(AGGREGATE_API_PROVIDER_AZURE_OPENAI))
+        .map_err(|err| format!("read azure openai deployments failed: {err}"))?
+        .into_iter()
+        .find(|item| item.upstream_model.eq_ignore_ascii_case(upstream_model));
+    let deployment = match mapped {
+        Some(item) if item.status == DEPLOYMENT_STATUS_DISABLED => {
+            return Err(format!(
+                "azure openai deployment disabled for model {upstream_model}"
+            ));
+        }
+        Some(item) => item
+            .deployment
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(upstream_model)
+            .to_string(),
+        None => upstream_model.to_string(),
+    };
+    if !is_valid_deployment_name(deployment.as_str()) {
+        return Err(format!("invalid azure openai deployment: {deployment}"));
+    }
+    Ok(deployment)
+}
+
+/// 把网关路径改写为 Azure 部署地址；base URL 上的 api-version 优先于内置默认值。
+pub(crate) fn build_azure_openai_url(
+    base_url: &str,
+    effective_path: &str,
+    deployment: &str,
+) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(base_url.trim())
+        .map_err(|_| "invalid aggregate api url".to_string())?;
+    if !is_valid_deployment_name(deployment) {
+        return Err(format!("invalid azure openai deployment: {deployment}"));
+    }
+    let base_query = url.query_pairs().into_owned().collect::<Vec<_>>();
+    let configured_version = base_query
+        .iter()
+        .find(|(key, value)| key == AZURE_OPENAI_API_VERSION_PARAM && !value.trim().is_empty())
+        .map(|(_, value)| value.trim().to_string());
+
+    let root = url.path().trim_end_matches('/').to_string();
+    let root_lower = root.to_ascii_lowercase();
+    let root = ["/openai/v1", "/openai"]
+        .iter()
+        .find_map(|suffix| {
+            root_lower
+                .ends_with(suffix)
+                .then(|| root[..root.len() - suffix.len()].to_string())
+        })
+        .unwrap_or(root);
+
+    let (path_part, query_part) = effective_path
+        .trim()
+        .split_once('?')
+        .map_or((effective_path.trim(), None), |(path, query)| {
+            (path, Some(query))
+        });
+    let path_part = path_part.trim_start_matches('/');
+    let path_part = path_part
+        .strip_prefix("v1/")
+        .unwrap_or(path_part)
+        .trim_matches('/');
+    let (path, default_version) = if is_responses_path(path_part) {
+        // 中文注释：Azure Responses API 不在部署路径下，部署名通过请求体 model 传递。
+        (
+            format!("{root}/openai/{path_part}"),
+            AZURE_OPENAI_RESPONSES_API_VERSION,
+        )
+    } else {
+        (
+            format!("{root}/openai/deployments/{deployment}/{path_part}"),
+            AZURE_OPENAI_DEFAULT_API_VERSION,
+        )
+    };
+    url.set_path(path.trim_end_matches('/'));
+    url.set_query(None);
+    {
+        let mut query = url.query_pairs_mut();
+        let path_query = query_part
+            .map(|raw| {
+                url::form_urlencoded::parse(raw.as_bytes())
+                    .into_owned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for (key, value) in base_query.iter().chain(path_query.iter()) {
+            if key != AZURE_OPENAI_API_VERSION_PARAM {
+                query.append_pair(key, value);
+            }
+        }
+        query.append_pair(
+            AZURE_OPENAI_API_VERSION_PARAM,
+            configured_version.as_deref().unwrap_or(default_version),
+        );
+    }
+    Ok(url)
+}
+
+fn deployment_entry(item: AggregateApiSupplierModel) -> AggregateApiDeploymentEntry {
+    AggregateApiDeploymentEntry {
+        deployment: item
+            .deployment
+            .unwrap_or_else(|| item.upstream_model.clone()),
+        api_id: item.supplier_key,
+        upstream_model: item.upstream_model,
+        status: item.status,
+        created_at: item.created_at,
+        updated_at: item.updated_at,
+    }
+}
+
+fn require_azure_openai_api(storage: &Storage, api_id: &str) -> Result<(), String> {
+    let identity = storage
+        .find_aggregate_api_supplier_identity_by_id(api_id)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "aggregate api not found".to_string())?;
+    if !is_azure_openai_provider(identity.provider_type.as_str()) {
+        return Err(format!(
+            "aggregate api {api_id} is not an {AGGREGATE_API_PROVIDER_AZURE_OPENAI} provider"
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn list_aggregate_api_deployments(
+    api_id: &str,
+) -> Result<Vec<AggregateApiDeploymentEntry>, String> {
+    let api_id = api_id.trim();
+    if api_id.is_empty() {
+        return Err("aggregate api id required".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_aggregate_api_supplier_models(Some(api_id), Some(AGGREGATE_API_PROVIDER_AZURE_OPENAI))
+        .map(|items| items.into_iter().map(deployment_entry).collect())
+        .map_err(|err| format!("list azure openai deployments failed: {err}"))
+}
+
+pub(crate) fn set_aggregate_api_deployment(
+    params: AggregateApiDeploymentSetParams,
+) -> Result<AggregateApiDeploymentEntry, String> {
+    let api_id = params.api_id.trim();
+    if api_id.is_empty() {
+        return Err("aggregate api id required".to_string());
+    }
+    let upstream_model = params.upstream_model.trim();
+    if upstream_model.is_empty() {
+        return Err("missing upstreamModel".to_string());
+    }
+    let deployment = params.deployment.trim();
+    if !is_valid_deployment_name(deployment) {
+        return Err(format!(
+            "invalid deployment: {deployment}; use letters, digits, '-', '_' or '.'"
+        ));
+    }
+    let status = match params
+        .status
+        .as_deref()
+        .map(str::trim)
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        None | Some("") | Some("active") | Some(DEPLOYMENT_STATUS_AVAILABLE) => {
+            DEPLOYMENT_STATUS_AVAILABLE
+        }
+        Some(DEPLOYMENT_STATUS_DISABLED) => DEPLOYMENT_STATUS_DISABLED,
+        Some(other) => {
+            return Err(format!(
+                "invalid status: {other}; use available or disabled"
+            ))
+        }
+    };
+
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    require_azure_openai_api(&storage, api_id)?;
+    let existing = storage
+        .list_aggregate_api_supplier_models(Some(api_id), Some(AGGREGATE_API_PROVIDER_AZURE_OPENAI))
+        .map_err(|err| format!("read azure openai deployments failed: {err}"))?
+        .into_iter()
+        .find(|item| item.upstream_model == upstream_model);
+    let now = now_ts();
+    let model = AggregateApiSupplierModel {
+        supplier_key: api_id.to_string(),
+        provider_type: AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string(),
+        upstream_model: upstream_model.to_string(),
+        display_name: existing.as_ref().and_then(|item| item.display_name.clone()),
+        deployment: Some(deployment.to_string()),
+        status: status.to_string(),
+        created_at: existing.map(|item| item.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    storage
+        .upsert_aggregate_api_supplier_model(&model)
+        .map_err(|err| format!("save azure openai deployment failed: {err}"))?;
+    Ok(deployment_entry(model))
+}
+
+pub(crate) fn delete_aggregate_api_deployment(
+    api_id: &str,
+    upstream_model: &str,
+) -> Result<(), String> {
+    let api_id = api_id.trim();
+    let upstream_model = upstream_model.trim();
+    if api_id.is_empty() {
+        return Err("aggregate api id required".to_string());
+    }
+    if upstream_model.is_empty() {
+        return Err("missing upstreamModel".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .delete_aggregate_api_supplier_model(
+            api_id,
+            AGGREGATE_API_PROVIDER_AZURE_OPENAI,
+            upstream_model,
+        )
+        .map_err(|err| format!("delete azure openai deployment failed: {err}"))
+}
+
+#[cfg(test)]
+#[path = "aggregate_api_azure_tests.rs"]
+mod tests;
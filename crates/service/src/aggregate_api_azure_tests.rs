@@ -0,0 +1,277 @@
+use super::*;
+use crate::aggregate_api::{normalize_provider_type, probe_azure_openai_endpoint};
+use codexmanager_core::storage::AggregateApi;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Response, Server};
+
+static AZURE_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = AZURE_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn azure_api(id: &str, url: &str) -> AggregateApi {
+    AggregateApi {
+        id: id.to_string(),
+        provider_type: AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string(),
+        supplier_name: Some("azure".to_string()),
+        sort: 0,
+        url: url.to_string(),
+        auth_type: "apikey".to_string(),
+        auth_params_json: None,
+        action: None,
+        model_override: None,
+        status: "active".to_string(),
+        created_at: 0,
+        updated_at: 0,
+        last_test_at: None,
+        last_test_status: None,
+        last_test_error: None,
+        balance_query_enabled: false,
+        balance_query_template: None,
+        balance_query_base_url: None,
+        balance_query_user_id: None,
+        balance_query_config_json: None,
+        last_balance_at: None,
+        last_balance_status: None,
+        last_balance_error: None,
+        last_balance_json: None,
+    }
+}
+
+fn deployment_params(
+    api_id: &str,
+    upstream_model: &str,
+    deployment: &str,
+) -> AggregateApiDeploymentSetParams {
+    AggregateApiDeploymentSetParams {
+        api_id: api_id.to_string(),
+        upstream_model: upstream_model.to_string(),
+        deployment: deployment.to_string(),
+        status: None,
+    }
+}
+
+#[test]
+fn azure_provider_type_aliases_are_normalized() {
+    for raw in ["azure_openai", "Azure-OpenAI", "azure"] {
+        assert_eq!(
+            normalize_provider_type(Some(raw.to_string())).unwrap(),
+            AGGREGATE_API_PROVIDER_AZURE_OPENAI
+        );
+        assert!(is_azure_openai_provider(raw));
+    }
+    assert!(!is_azure_openai_provider("codex"));
+}
+
+#[test]
+fn azure_url_scopes_chat_to_deployment_and_keeps_responses_global() {
+    let chat = build_azure_openai_url(
+        "https://demo.openai.azure.com/",
+        "/v1/chat/completions",
+        "gpt4o-prod",
+    )
+    .expect("chat url");
+    assert_eq!(
+        chat.as_str(),
+        "https://demo.openai.azure.com/openai/deployments/gpt4o-prod/chat/completions?api-version=2024-10-21"
+    );
+
+    let responses = build_azure_openai_url(
+        "https://demo.openai.azure.com/openai",
+        "/v1/responses?stream=true",
+        "gpt4o-prod",
+    )
+    .expect("responses url");
+    assert_eq!(
+        responses.as_str(),
+        "https://demo.openai.azure.com/openai/responses?stream=true&api-version=2025-04-01-preview"
+    );
+
+    let pinned = build_azure_openai_url(
+        "https://demo.openai.azure.com/openai/v1?api-version=2024-06-01",
+        "/v1/chat/completions",
+        "gpt4o-prod",
+    )
+    .expect("pinned url");
+    assert_eq!(
+        pinned.as_str(),
+        "https://demo.openai.azure.com/openai/deployments/gpt4o-prod/chat/completions?api-version=2024-06-01"
+    );
+
+    assert!(build_azure_openai_url(
+        "https://demo.openai.azure.com",
+        "/v1/chat/completions",
+        "a/b"
+    )
+    .is_err());
+}
+
+#[test]
+fn azure_deployments_round_trip_and_resolve_with_model_fallback() {
+    let _lock = crate::test_env_guard();
+    let dir = new_test_dir("aggregate-api-azure-deployments");
+    let db_path = dir.join("codexmanager.db");
+    let _guard = EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref());
+    let storage = Storage::open(&db_path).expect("open db");
+    storage.init().expect("init db");
+    storage
+        .insert_aggregate_api(&azure_api("agg-azure", "https://demo.openai.azure.com"))
+        .expect("insert azure api");
+    let mut codex = azure_api("agg-codex", "https://api.example.com/v1");
+    codex.provider_type = "codex".to_string();
+    storage
+        .insert_aggregate_api(&codex)
+        .expect("insert codex api");
+
+    let saved =
+        set_aggregate_api_deployment(deployment_params(" agg-azure ", "gpt-4o", " prod-4o "))
+            .expect("save deployment");
+    assert_eq!(saved.api_id, "agg-azure");
+    assert_eq!(saved.deployment, "prod-4o");
+    assert_eq!(saved.status, "available");
+
+    let listed = list_aggregate_api_deployments("agg-azure").expect("list deployments");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].upstream_model, "gpt-4o");
+
+    assert_eq!(
+        resolve_azure_openai_deployment(&storage, "agg-azure", "GPT-4o").unwrap(),
+        "prod-4o"
+    );
+    assert_eq!(
+        resolve_azure_openai_deployment(&storage, "agg-azure", "gpt-4.1-mini").unwrap(),
+        "gpt-4.1-mini"
+    );
+    assert!(resolve_azure_openai_deployment(&storage, "agg-azure", " ").is_err());
+
+    set_aggregate_api_deployment(AggregateApiDeploymentSetParams {
+        status: Some("disabled".to_string()),
+        ..deployment_params("agg-azure", "gpt-4o", "prod-4o")
+    })
+    .expect("disable deployment");
+    let err = resolve_azure_openai_deployment(&storage, "agg-azure", "gpt-4o")
+        .expect_err("disabled deployment");
+    assert!(err.contains("disabled"));
+
+    let err = set_aggregate_api_deployment(deployment_params("agg-codex", "gpt-4o", "prod-4o"))
+        .expect_err("non-azure api");
+    assert!(err.contains("azure_openai"));
+    let err = set_aggregate_api_deployment(deployment_params("agg-azure", "gpt-4o", "bad/name"))
+        .expect_err("invalid deployment");
+    assert!(err.contains("invalid deployment"));
+
+    delete_aggregate_api_deployment("agg-azure", "gpt-4o").expect("delete deployment");
+    assert!(list_aggregate_api_deployments("agg-azure")
+        .expect("list after delete")
+        .is_empty());
+}
+
+#[test]
+fn azure_probe_uses_deployment_url_and_api_key_header() {
+    let _lock = crate::test_env_guard();
+    let dir = new_test_dir("aggregate-api-azure-probe");
+    let storage = Storage::open(dir.join("codexmanager.db")).expect("open db");
+    storage.init().expect("init db");
+    let server = Server::http("127.0.0.1:0").expect("start mock server");
+    let base_url = format!("http://{}", server.server_addr());
+    let api = azure_api("agg-azure-probe", base_url.as_str());
+    storage
+        .insert_aggregate_api(&api)
+        .expect("insert azure api");
+    storage
+        .upsert_aggregate_api_supplier_model(&AggregateApiSupplierModel {
+            supplier_key: api.id.clone(),
+            provider_type: AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string(),
+            upstream_model: "gpt-4o".to_string(),
+            display_name: None,
+            deployment: Some("prod-4o".to_string()),
+            status: "available".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        })
+        .expect("save deployment");
+
+    let (tx, rx) = mpsc::channel();
+    let join = thread::spawn(move || {
+        let mut request = server
+            .recv_timeout(Duration::from_secs(2))
+            .expect("receive azure request")
+            .expect("azure request present");
+        let mut body = String::new();
+        request
+            .as_reader()
+            .read_to_string(&mut body)
+            .expect("read request body");
+        let header = |name: &str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+                .map(|header| header.value.as_str().to_string())
+        };
+        tx.send((
+            request.url().to_string(),
+            header("api-key"),
+            header("authorization"),
+            body,
+        ))
+        .expect("send azure request");
+        request
+            .respond(Response::from_string(r#"{"id":"chatcmpl_probe"}"#))
+            .expect("respond azure request");
+    });
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("build client");
+    let status = probe_azure_openai_endpoint(&client, &storage, &api, "azure-secret", "gpt-4o")
+        .expect("probe succeeds");
+
+    assert_eq!(status, 200);
+    let (url, api_key, authorization, body) = rx
+        .recv_timeout(Duration::from_secs(2))
+        .expect("captured request");
+    join.join().expect("join mock server");
+    assert_eq!(
+        url,
+        "/openai/deployments/prod-4o/chat/completions?api-version=2024-10-21"
+    );
+    assert_eq!(api_key.as_deref(), Some("azure-secret"));
+    assert_eq!(authorization, None);
+    let body: Value = serde_json::from_str(body.as_str()).expect("parse body");
+    assert_eq!(body["model"], "prod-4o");
+}
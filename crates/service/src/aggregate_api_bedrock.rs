@@ -0,0 +1,291 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use codexmanager_core::storage::AggregateApi;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{normalize_provider_type_value, AGGREGATE_API_PROVIDER_BEDROCK};
+
+/// Bedrock 上 Anthropic 模型要求的请求体版本标识。
+pub(crate) const BEDROCK_ANTHROPIC_VERSION: &str = "bedrock-2023-05-31";
+pub(crate) const BEDROCK_DEFAULT_URL: &str = "https://bedrock-runtime.us-east-1.amazonaws.com";
+const BEDROCK_SIGNING_SERVICE: &str = "bedrock";
+const SIGV4_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// 聚合 API 密钥中保存的 AWS 凭据；region 可省略并从 bedrock-runtime 域名推断。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BedrockCredentials {
+    #[serde(alias = "access_key_id")]
+    pub access_key_id: String,
+    #[serde(alias = "secret_access_key")]
+    pub secret_access_key: String,
+    #[serde(default, alias = "session_token")]
+    pub session_token: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+pub(crate) fn is_bedrock_provider(provider_type: &str) -> bool {
+    normalize_provider_type_value(provider_type) == AGGREGATE_API_PROVIDER_BEDROCK
+}
+
+/// 解析密钥：支持 JSON 对象，或 `ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN]` 简写。
+pub(crate) fn parse_bedrock_credentials(secret: &str) -> Result<BedrockCredentials, String> {
+    let secret = secret.trim();
+    let mut credentials = if secret.starts_with('{') {
+        serde_json::from_str::<BedrockCredentials>(secret)
+            .map_err(|_| "invalid bedrock credentials".to_string())?
+    } else {
+        let mut parts = secret.splitn(3, ':');
+        let access_key_id = parts.next().unwrap_or_default();
+        let secret_access_key = parts.next().unwrap_or_default();
+        BedrockCredentials {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: parts.next().map(str::to_string),
+            region: None,
+        }
+    };
+    credentials.access_key_id = credentials.access_key_id.trim().to_string();
+    credentials.secret_access_key = credentials.secret_access_key.trim().to_string();
+    credentials.session_token = credentials
+        .session_token
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    credentials.region = credentials
+        .region
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty());
+    if credentials.access_key_id.is_empty() || credentials.secret_access_key.is_empty() {
+        return Err("invalid bedrock credentials: missing access key id or secret".to_string());
+    }
+    Ok(credentials)
+}
+
+/// 凭据里显式的 region 优先，否则从 `bedrock-runtime[-fips].{region}.amazonaws.com` 推断。
+pub(crate) fn resolve_bedrock_region(
+    credentials: &BedrockCredentials,
+    base_url: &str,
+) -> Result<String, String> {
+    if let Some(region) = credentials.region.as_deref() {
+        return Ok(region.to_string());
+    }
+    let host = reqwest::Url::parse(base_url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    let mut labels = host.split('.');
+    match (labels.next(), labels.next()) {
+        (Some("bedrock-runtime" | "bedrock-runtime-fips"), Some(region))
+            if !region.is_empty() && host.contains(".amazonaws.com") =>
+        {
+            Ok(region.to_string())
+        }
+        _ => Err("bedrock region unresolved: set region in credentials".to_string()),
+    }
+}
+
+/// 拼出 InvokeModel / InvokeModelWithResponseStream 地址，模型 ID 按路径段编码。
+pub(crate) fn build_bedrock_invoke_url(
+    base_url: &str,
+    model_id: &str,
+    is_stream: bool,
+) -> Result<reqwest::Url, String> {
+    let model_id = model_id.trim();
+    if model_id.is_empty() {
+        return Err("bedrock model id unresolved: missing model".to_string());
+    }
+    let mut url = reqwest::Url::parse(base_url.trim())
+        .map_err(|_| "invalid aggregate api url".to_string())?;
+    let root = url.path().trim_end_matches('/').to_string();
+    let action = if is_stream {
+        "invoke-with-response-stream"
+    } else {
+        "invoke"
+    };
+    url.set_path(
+        format!(
+            "{root}/model/{}/{action}",
+            sigv4_uri_encode(model_id.as_bytes())
+        )
+        .as_str(),
+    );
+    url.set_query(None);
+    Ok(url)
+}
+
+/// 把 Anthropic Messages 请求体改写为 Bedrock 格式：模型与流式走 URL，补 anthropic_version。
+pub(crate) fn build_bedrock_invoke_body(body: &[u8]) -> Result<(Bytes, Option<String>), String> {
+    let mut value: Value = serde_json::from_slice(body)
+        .map_err(|_| "invalid bedrock request body: expected json object".to_string())?;
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| "invalid bedrock request body: expected json object".to_string())?;
+    let model = object
+        .remove("model")
+        .and_then(|value| value.as_str().map(str::trim).map(str::to_string))
+        .filter(|value| !value.is_empty());
+    object.remove("stream");
+    object
+        .entry("anthropic_version")
+        .or_insert_with(|| Value::String(BEDROCK_ANTHROPIC_VERSION.to_string()));
+    let body = serde_json::to_vec(&value).map_err(|err| err.to_string())?;
+    Ok((Bytes::from(body), model))
+}
+
+/// 生成 SigV4 所需请求头（host/x-amz-date/x-amz-security-token/authorization）。
+pub(crate) fn sign_bedrock_request(
+    credentials: &BedrockCredentials,
+    region: &str,
+    method: &str,
+    url: &reqwest::Url,
+    payload: &[u8],
+    signed_at: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    sigv4_sign(
+        credentials,
+        region,
+        BEDROCK_SIGNING_SERVICE,
+        method,
+        url,
+        payload,
+        signed_at,
+    )
+}
+
+fn sigv4_host(url: &reqwest::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+}
+
+fn sigv4_sign(
+    credentials: &BedrockCredentials,
+    region: &str,
+    service: &str,
+    method: &str,
+    url: &reqwest::Url,
+    payload: &[u8],
+    signed_at: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = signed_at.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = signed_at.format("%Y%m%d").to_string();
+    let mut headers = vec![("host", sigv4_host(url)), ("x-amz-date", amz_date.clone())];
+    if let Some(token) = credentials.session_token.as_deref() {
+        headers.push(("x-amz-security-token", token.to_string()));
+    }
+
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect::<String>();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{method}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        sigv4_canonical_uri(url),
+        sigv4_canonical_query(url),
+        hex_encode(Sha256::digest(payload).as_slice()),
+    );
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "{SIGV4_ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex_encode(Sha256::digest(canonical_request.as_bytes()).as_slice())
+    );
+
+    let date_key = hmac_sha256(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, service.as_bytes());
+    let signing_key = hmac_sha256(&service_key, b"aws4_request");
+    let signature = hex_encode(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    headers.push((
+        "authorization",
+        format!(
+            "{SIGV4_ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    ));
+    headers
+}
+
+/// 非 S3 服务的规范 URI 需对已编码路径再编码一次（模型 ID 中的 `:` 最终签为 `%253A`）。
+fn sigv4_canonical_uri(url: &reqwest::Url) -> String {
+    let path = url.path();
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(|segment| sigv4_uri_encode(segment.as_bytes()))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sigv4_canonical_query(url: &reqwest::Url) -> String {
+    let mut pairs = url
+        .query_pairs()
+        .map(|(key, value)| {
+            (
+                sigv4_uri_encode(key.as_bytes()),
+                sigv4_uri_encode(value.as_bytes()),
+            )
+        })
+        .collect::<Vec<_>>();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn sigv4_uri_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for byte in bytes {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(*byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push_str(&format!("{byte:02x}"));
+    }
+    out
+}
+
+/// 供连通性测试与网关共用：按候选配置解析凭据与 region。
+pub(crate) fn resolve_bedrock_signing(
+    api: &AggregateApi,
+    secret: &str,
+) -> Result<(BedrockCredentials, String), String> {
+    let credentials = parse_bedrock_credentials(secret)?;
+    let region = resolve_bedrock_region(&credentials, api.url.as_str())?;
+    Ok((credentials, region))
+}
+
+#[cfg(test)]
+#[path = "aggregate_api_bedrock_tests.rs"]
+mod tests;
@@ -0,0 +1,275 @@
+use super::*;
+use crate::aggregate_api::{normalize_provider_type, probe_bedrock_endpoint};
+use chrono::{NaiveDateTime, TimeZone};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Response, Server};
+
+fn bedrock_api(url: &str) -> AggregateApi {
+    AggregateApi {
+        id: "agg-bedrock".to_string(),
+        provider_type: AGGREGATE_API_PROVIDER_BEDROCK.to_string(),
+        supplier_name: Some("bedrock".to_string()),
+        sort: 0,
+        url: url.to_string(),
+        auth_type: "apikey".to_string(),
+        auth_params_json: None,
+        action: None,
+        model_override: None,
+        status: "active".to_string(),
+        created_at: 0,
+        updated_at: 0,
+        last_test_at: None,
+        last_test_status: None,
+        last_test_error: None,
+        balance_query_enabled: false,
+        balance_query_template: None,
+        balance_query_base_url: None,
+        balance_query_user_id: None,
+        balance_query_config_json: None,
+        last_balance_at: None,
+        last_balance_status: None,
+        last_balance_error: None,
+        last_balance_json: None,
+    }
+}
+
+fn example_credentials() -> BedrockCredentials {
+    BedrockCredentials {
+        access_key_id: "AKIDEXAMPLE".to_string(),
+        secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+        session_token: None,
+        region: None,
+    }
+}
+
+#[test]
+fn bedrock_provider_type_aliases_are_normalized() {
+    for raw in ["bedrock", "AWS-Bedrock", "aws_bedrock"] {
+        assert_eq!(
+            normalize_provider_type(Some(raw.to_string())).unwrap(),
+            AGGREGATE_API_PROVIDER_BEDROCK
+        );
+        assert!(is_bedrock_provider(raw));
+    }
+    assert!(!is_bedrock_provider("claude"));
+}
+
+#[test]
+fn hmac_sha256_matches_rfc4231_vector() {
+    assert_eq!(
+        hex_encode(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn sigv4_matches_aws_get_vanilla_vector() {
+    let url = reqwest::Url::parse("https://example.amazonaws.com/").expect("url");
+    let signed_at = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+    let headers = sigv4_sign(
+        &example_credentials(),
+        "us-east-1",
+        "service",
+        "GET",
+        &url,
+        b"",
+        signed_at,
+    );
+
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    assert_eq!(header("x-amz-date"), Some("20150830T123600Z"));
+    assert_eq!(
+        header("authorization"),
+        Some(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        )
+    );
+}
+
+#[test]
+fn sigv4_signs_session_token_and_double_encodes_model_path() {
+    let mut credentials = example_credentials();
+    credentials.session_token = Some("session-token".to_string());
+    let url = build_bedrock_invoke_url(
+        "https://bedrock-runtime.us-west-2.amazonaws.com",
+        "anthropic.claude-3-5-sonnet-20240620-v1:0",
+        true,
+    )
+    .expect("bedrock url");
+    assert_eq!(
+        url.as_str(),
+        "https://bedrock-runtime.us-west-2.amazonaws.com/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0/invoke-with-response-stream"
+    );
+    assert_eq!(
+        sigv4_canonical_uri(&url),
+        "/model/anthropic.claude-3-5-sonnet-20240620-v1%253A0/invoke-with-response-stream"
+    );
+
+    let headers = sign_bedrock_request(&credentials, "us-west-2", "POST", &url, b"{}", Utc::now());
+    let authorization = headers
+        .iter()
+        .find(|(key, _)| *key == "authorization")
+        .map(|(_, value)| value.clone())
+        .expect("authorization header");
+    assert!(authorization.contains("/us-west-2/bedrock/aws4_request"));
+    assert!(authorization.contains("SignedHeaders=host;x-amz-date;x-amz-security-token"));
+    assert!(headers
+        .iter()
+        .any(|(key, value)| *key == "x-amz-security-token" && value == "session-token"));
+}
+
+#[test]
+fn bedrock_credentials_parse_json_and_colon_forms() {
+    let parsed = parse_bedrock_credentials(
+        r#"{"accessKeyId":" AKID ","secretAccessKey":"SECRET","sessionToken":"","region":"EU-West-1"}"#,
+    )
+    .expect("json credentials");
+    assert_eq!(parsed.access_key_id, "AKID");
+    assert_eq!(parsed.session_token, None);
+    assert_eq!(parsed.region.as_deref(), Some("eu-west-1"));
+
+    let parsed = parse_bedrock_credentials("AKID:SECRET:TOKEN").expect("colon credentials");
+    assert_eq!(parsed.secret_access_key, "SECRET");
+    assert_eq!(parsed.session_token.as_deref(), Some("TOKEN"));
+
+    assert!(parse_bedrock_credentials("AKID").is_err());
+    assert!(parse_bedrock_credentials(r#"{"accessKeyId":"AKID"}"#).is_err());
+}
+
+#[test]
+fn bedrock_region_prefers_credentials_then_runtime_host() {
+    let mut credentials = example_credentials();
+    assert_eq!(
+        resolve_bedrock_region(
+            &credentials,
+            "https://bedrock-runtime.ap-northeast-1.amazonaws.com"
+        )
+        .unwrap(),
+        "ap-northeast-1"
+    );
+    assert!(resolve_bedrock_region(&credentials, "https://gateway.example.com").is_err());
+
+    credentials.region = Some("us-east-2".to_string());
+    assert_eq!(
+        resolve_bedrock_region(&credentials, "https://gateway.example.com").unwrap(),
+        "us-east-2"
+    );
+}
+
+#[test]
+fn bedrock_body_drops_model_and_stream_and_adds_anthropic_version() {
+    let (body, model) = build_bedrock_invoke_body(
+        br#"{"model":"anthropic.claude-3-haiku-20240307-v1:0","stream":true,"max_tokens":8,"messages":[]}"#,
+    )
+    .expect("bedrock body");
+    let value: Value = serde_json::from_slice(body.as_ref()).expect("parse body");
+
+    assert_eq!(
+        model.as_deref(),
+        Some("anthropic.claude-3-haiku-20240307-v1:0")
+    );
+    assert!(value.get("model").is_none());
+    assert!(value.get("stream").is_none());
+    assert_eq!(value["anthropic_version"], BEDROCK_ANTHROPIC_VERSION);
+    assert_eq!(value["max_tokens"], 8);
+    assert!(build_bedrock_invoke_body(b"[]").is_err());
+}
+
+#[test]
+fn bedrock_probe_signs_invoke_request_verifiable_by_stub_server() {
+    let server = Server::http("127.0.0.1:0").expect("start mock server");
+    let base_url = format!("http://{}", server.server_addr());
+    let api = bedrock_api(base_url.as_str());
+    let secret = r#"{"accessKeyId":"AKIDEXAMPLE","secretAccessKey":"wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY","region":"us-east-1"}"#;
+
+    let (tx, rx) = mpsc::channel();
+    let join = thread::spawn(move || {
+        let mut request = server
+            .recv_timeout(Duration::from_secs(2))
+            .expect("receive bedrock request")
+            .expect("bedrock request present");
+        let mut body = Vec::new();
+        request
+            .as_reader()
+            .read_to_end(&mut body)
+            .expect("read request body");
+        let header = |name: &str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+                .map(|header| header.value.as_str().to_string())
+        };
+        tx.send((
+            request.url().to_string(),
+            header("host"),
+            header("x-amz-date"),
+            header("authorization"),
+            body,
+        ))
+        .expect("send bedrock request");
+        request
+            .respond(Response::from_string(
+                r#"{"id":"msg_probe","type":"message","content":[]}"#,
+            ))
+            .expect("respond bedrock request");
+    });
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("build client");
+    let status = probe_bedrock_endpoint(
+        &client,
+        &api,
+        secret,
+        "anthropic.claude-3-haiku-20240307-v1:0",
+    )
+    .expect("probe succeeds");
+    assert_eq!(status, 200);
+
+    let (path, host, amz_date, authorization, body) = rx
+        .recv_timeout(Duration::from_secs(2))
+        .expect("captured request");
+    join.join().expect("join mock server");
+    assert_eq!(
+        path,
+        "/model/anthropic.claude-3-haiku-20240307-v1%3A0/invoke"
+    );
+    let value: Value = serde_json::from_slice(body.as_slice()).expect("parse body");
+    assert_eq!(value["anthropic_version"], BEDROCK_ANTHROPIC_VERSION);
+    assert!(value.get("model").is_none());
+
+    // 中文注释：服务端按收到的 host/时间/请求体独立重算签名，校验端口与请求体都被签入。
+    let host = host.expect("host header");
+    let signed_at = NaiveDateTime::parse_from_str(
+        amz_date.expect("x-amz-date header").as_str(),
+        "%Y%m%dT%H%M%SZ",
+    )
+    .expect("parse x-amz-date")
+    .and_utc();
+    let received_url =
+        reqwest::Url::parse(format!("http://{host}{path}").as_str()).expect("received url");
+    let expected = sign_bedrock_request(
+        &example_credentials(),
+        "us-east-1",
+        "POST",
+        &received_url,
+        body.as_slice(),
+        signed_at,
+    )
+    .into_iter()
+    .find(|(key, _)| *key == "authorization")
+    .map(|(_, value)| value);
+    assert_eq!(authorization, expected);
+}
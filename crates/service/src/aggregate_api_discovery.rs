@@ -0,0 +1,590 @@
+use codexmanager_core::storage::{
+    now_ts, AggregateApi, AggregateApiSupplierModel, ManagedModelV2, ModelRouteV2, Storage,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{
+    apply_probe_auth, normalize_probe_url, normalize_provider_type_value, probe_http_error,
+    with_query_param, AGGREGATE_API_PROVIDER_CLAUDE, AGGREGATE_API_PROVIDER_CODEX,
+    AGGREGATE_API_PROVIDER_COMPATIBLE, AGGREGATE_API_PROVIDER_GEMINI,
+};
+use crate::gateway;
+use crate::storage_helpers::open_storage;
+
+const MODEL_DISCOVERY_INTERVAL_ENV: &str =
+    "CODEXMANAGER_AGGREGATE_API_MODEL_DISCOVERY_INTERVAL_SECS";
+const DEFAULT_MODEL_DISCOVERY_INTERVAL_SECS: u64 = 6 * 60 * 60;
+const MIN_MODEL_DISCOVERY_INTERVAL_SECS: u64 = 5 * 60;
+const MODEL_DISCOVERY_MAX_PAGES: usize = 20;
+const AGGREGATE_API_ROUTE_SOURCE_KIND: &str = "aggregate_api";
+const SUPPLIER_MODEL_STATUS_AVAILABLE: &str = "available";
+const SUPPLIER_MODEL_STATUS_MISSING: &str = "missing";
+const SUPPLIER_MODEL_STATUS_DISABLED: &str = "disabled";
+
+static MODEL_DISCOVERY_STARTED: OnceLock<()> = OnceLock::new();
+static MODEL_DISCOVERY_INTERVAL_SECS: AtomicU64 =
+    AtomicU64::new(DEFAULT_MODEL_DISCOVERY_INTERVAL_SECS);
+
+/// 上游模型列表里的一项；display_name 仅在供应商返回时填充。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiscoveredModel {
+    pub upstream_model: String,
+    pub display_name: Option<String>,
+}
+
+/// 建议新增的路由：model_exists=false 表示模型目录里还没有同名模型，需要先建模型。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AggregateApiModelRouteProposal {
+    pub model_slug: String,
+    pub model_exists: bool,
+    pub route: ModelRouteV2,
+}
+
+/// 上游模型已不在列表中的现有路由。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AggregateApiStaleModelRoute {
+    pub model_slug: String,
+    pub route: ModelRouteV2,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AggregateApiModelDiscoveryReport {
+    pub api_id: String,
+    pub provider_type: String,
+    pub supplier_name: Option<String>,
+    pub ok: bool,
+    pub message: Option<String>,
+    pub discovered_models: Vec<String>,
+    pub new_models: Vec<String>,
+    pub missing_models: Vec<String>,
+    pub proposed_routes: Vec<AggregateApiModelRouteProposal>,
+    pub stale_routes: Vec<AggregateApiStaleModelRoute>,
+    pub discovered_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AggregateApiModelDiscoveryResult {
+    pub items: Vec<AggregateApiModelDiscoveryReport>,
+}
+
+/// Azure 按部署寻址、Bedrock 的模型列表不在 runtime 域名上，二者不参与自动发现。
+pub(crate) fn supports_model_discovery(provider_type: &str) -> bool {
+    matches!(
+        normalize_provider_type_value(provider_type).as_str(),
+        AGGREGATE_API_PROVIDER_CODEX
+            | AGGREGATE_API_PROVIDER_COMPATIBLE
+            | AGGREGATE_API_PROVIDER_CLAUDE
+            | AGGREGATE_API_PROVIDER_GEMINI
+    )
+}
+
+fn send_discovery_get(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+    url: String,
+    headers: &[(&str, &str)],
+    label: &str,
+) -> Result<Value, String> {
+    let (builder, updated_url) =
+        apply_probe_auth(client.get(url.as_str()), url.clone(), api, secret)?;
+    let mut builder = if updated_url != url {
+        let rebuilt = client.get(updated_url.as_str());
+        let (rebuilt, _) = apply_probe_auth(rebuilt, updated_url, api, secret)?;
+        rebuilt
+    } else {
+        builder
+    };
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    let response = builder
+        .header("accept", "application/json")
+        .header("accept-encoding", "identity")
+        .send()
+        .map_err(|err| err.to_string())?;
+    let status_code = response.status().as_u16();
+    if !response.status().is_success() {
+        return Err(probe_http_error(label, status_code, response));
+    }
+    response
+        .json::<Value>()
+        .map_err(|err| format!("{label} model list invalid json: {err}"))
+}
+
+fn json_text(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// OpenAI 兼容 `/v1/models`：兼容 `{data:[...]}` 与直接返回数组两种形态。
+fn list_openai_models(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+) -> Result<Vec<DiscoveredModel>, String> {
+    let url = normalize_probe_url(api.url.as_str(), "/models");
+    let value = send_discovery_get(client, api, secret, url, &[], "openai")?;
+    let items = value
+        .get("data")
+        .and_then(Value::as_array)
+        .or_else(|| value.as_array())
+        .ok_or_else(|| "openai model list missing data".to_string())?;
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            Some(DiscoveredModel {
+                upstream_model: json_text(item, "id")?,
+                display_name: None,
+            })
+        })
+        .collect())
+}
+
+/// Anthropic `/v1/models`：按 `has_more` / `last_id` 翻页。
+fn list_anthropic_models(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+) -> Result<Vec<DiscoveredModel>, String> {
+    let base = normalize_probe_url(api.url.as_str(), "/models");
+    let mut models = Vec::new();
+    let mut after_id: Option<String> = None;
+    for _ in 0..MODEL_DISCOVERY_MAX_PAGES {
+        let mut url = with_query_param(base.as_str(), "limit", "1000");
+        if let Some(after_id) = after_id.as_deref() {
+            url = with_query_param(url.as_str(), "after_id", after_id);
+        }
+        let value = send_discovery_get(
+            client,
+            api,
+            secret,
+            url,
+            &[("anthropic-version", "2023-06-01")],
+            "claude",
+        )?;
+        let items = value
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "claude model list missing data".to_string())?;
+        models.extend(items.iter().filter_map(|item| {
+            Some(DiscoveredModel {
+                upstream_model: json_text(item, "id")?,
+                display_name: json_text(item, "display_name"),
+            })
+        }));
+        after_id = value
+            .get("has_more")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+            .then(|| json_text(&value, "last_id"))
+            .flatten();
+        if after_id.is_none() {
+            break;
+        }
+    }
+    Ok(models)
+}
+
+/// Gemini 原生地址默认挂在 `/v1beta` 下；base URL 已带版本段时直接追加 `/models`。
+fn gemini_models_url(base_url: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    let lower = base.to_ascii_lowercase();
+    if lower.ends_with("/v1beta") || lower.ends_with("/v1") {
+        format!("{base}/models")
+    } else {
+        format!("{base}/v1beta/models")
+    }
+}
+
+/// Gemini `models.list`：按 `nextPageToken` 翻页，只保留支持 generateContent 的模型。
+fn list_gemini_models(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+) -> Result<Vec<DiscoveredModel>, String> {
+    let base = gemini_models_url(api.url.as_str());
+    let mut models = Vec::new();
+    let mut page_token: Option<String> = None;
+    for _ in 0..MODEL_DISCOVERY_MAX_PAGES {
+        let mut url = with_query_param(base.as_str(), "pageSize", "1000");
+        if let Some(page_token) = page_token.as_deref() {
+            url = with_query_param(url.as_str(), "pageToken", page_token);
+        }
+        let value = send_discovery_get(client, api, secret, url, &[], "gemini")?;
+        let items = value
+            .get("models")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        models.extend(items.iter().filter_map(|item| {
+            let generates_content = item
+                .get("supportedGenerationMethods")
+                .and_then(Value::as_array)
+                .is_none_or(|methods| {
+                    methods
+                        .iter()
+                        .any(|method| method.as_str() == Some("generateContent"))
+                });
+            if !generates_content {
+                return None;
+            }
+            let name = json_text(item, "name")?;
+            Some(DiscoveredModel {
+                upstream_model: name.strip_prefix("models/").unwrap_or(&name).to_string(),
+                display_name: json_text(item, "displayName"),
+            })
+        }));
+        page_token = json_text(&value, "nextPageToken");
+        if page_token.is_none() {
+            break;
+        }
+    }
+    Ok(models)
+}
+
+pub(crate) fn list_upstream_models(
+    client: &reqwest::blocking::Client,
+    api: &AggregateApi,
+    secret: &str,
+) -> Result<Vec<DiscoveredModel>, String> {
+    let provider_type = normalize_provider_type_value(api.provider_type.as_str());
+    let mut models = match provider_type.as_str() {
+        AGGREGATE_API_PROVIDER_CLAUDE => list_anthropic_models(client, api, secret)?,
+        AGGREGATE_API_PROVIDER_GEMINI => list_gemini_models(client, api, secret)?,
+        _ if supports_model_discovery(provider_type.as_str()) => {
+            list_openai_models(client, api, secret)?
+        }
+        _ => {
+            return Err(format!(
+                "model discovery unsupported for provider {provider_type}"
+            ))
+        }
+    };
+    let mut seen = HashSet::new();
+    models.retain(|model| seen.insert(model.upstream_model.to_ascii_lowercase()));
+    models.sort_by(|left, right| left.upstream_model.cmp(&right.upstream_model));
+    Ok(models)
+}
+
+/// 用发现结果刷新供应商模型表并生成路由建议；手动禁用的条目保持不动。
+pub(crate) fn apply_model_discovery(
+    storage: &Storage,
+    api: &AggregateApi,
+    discovered: &[DiscoveredModel],
+    now: i64,
+) -> Result<AggregateApiModelDiscoveryReport, String> {
+    let provider_type = normalize_provider_type_value(api.provider_type.as_str());
+    let stored = storage
+        .list_aggregate_api_supplier_models(Some(api.id.as_str()), Some(provider_type.as_str()))
+        .map_err(|err| format!("read aggregate api supplier models failed: {err}"))?;
+    let discovered_keys = discovered
+        .iter()
+        .map(|model| model.upstream_model.to_ascii_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut new_models = Vec::new();
+    for model in discovered {
+        let existing = stored.iter().find(|item| {
+            item.upstream_model
+                .eq_ignore_ascii_case(&model.upstream_model)
+        });
+        if existing.is_none() {
+            new_models.push(model.upstream_model.clone());
+        }
+        if existing.is_some_and(|item| item.status != SUPPLIER_MODEL_STATUS_MISSING) {
+            continue;
+        }
+        storage
+            .upsert_aggregate_api_supplier_model(&AggregateApiSupplierModel {
+                supplier_key: api.id.clone(),
+                provider_type: provider_type.clone(),
+                upstream_model: existing
+                    .map(|item| item.upstream_model.clone())
+                    .unwrap_or_else(|| model.upstream_model.clone()),
+                display_name: existing
+                    .and_then(|item| item.display_name.clone())
+                    .or_else(|| model.display_name.clone()),
+                deployment: existing.and_then(|item| item.deployment.clone()),
+                status: SUPPLIER_MODEL_STATUS_AVAILABLE.to_string(),
+                created_at: existing.map(|item| item.created_at).unwrap_or(now),
+                updated_at: now,
+            })
+            .map_err(|err| format!("save aggregate api supplier model failed: {err}"))?;
+    }
+
+    let mut missing_models = Vec::new();
+    for item in stored.iter().filter(|item| {
+        item.status != SUPPLIER_MODEL_STATUS_DISABLED
+            && !discovered_keys.contains(&item.upstream_model.to_ascii_lowercase())
+    }) {
+        missing_models.push(item.upstream_model.clone());
+        if item.status == SUPPLIER_MODEL_STATUS_MISSING {
+            continue;
+        }
+        storage
+            .upsert_aggregate_api_supplier_model(&AggregateApiSupplierModel {
+                status: SUPPLIER_MODEL_STATUS_MISSING.to_string(),
+                updated_at: now,
+                ..item.clone()
+            })
+            .map_err(|err| format!("save aggregate api supplier model failed: {err}"))?;
+    }
+
+    let models = storage
+        .list_managed_models_v2(true)
+        .map_err(|err| format!("read model catalog V2 routes failed: {err}"))?;
+    let (proposed_routes, stale_routes) = diff_model_routes(api, &models, discovered);
+    Ok(AggregateApiModelDiscoveryReport {
+        api_id: api.id.clone(),
+        provider_type,
+        supplier_name: api.supplier_name.clone(),
+        ok: true,
+        message: None,
+        discovered_models: discovered
+            .iter()
+            .map(|model| model.upstream_model.clone())
+            .collect(),
+        new_models,
+        missing_models,
+        proposed_routes,
+        stale_routes,
+        discovered_at: now,
+    })
+}
+
+fn is_api_route(route: &ModelRouteV2, api_id: &str) -> bool {
+    route.source_kind == AGGREGATE_API_ROUTE_SOURCE_KIND && route.source_id == api_id
+}
+
+/// 对比模型目录：未被本 API 路由覆盖的上游模型生成建议，指向已消失上游模型的路由标记为过期。
+fn diff_model_routes(
+    api: &AggregateApi,
+    models: &[ManagedModelV2],
+    discovered: &[DiscoveredModel],
+) -> (
+    Vec<AggregateApiModelRouteProposal>,
+    Vec<AggregateApiStaleModelRoute>,
+) {
+    let discovered_keys = discovered
+        .iter()
+        .map(|model| model.upstream_model.to_ascii_lowercase())
+        .collect::<HashSet<_>>();
+    let routed_keys = models
+        .iter()
+        .flat_map(|model| model.routes.iter())
+        .filter(|route| is_api_route(route, api.id.as_str()))
+        .map(|route| route.upstream_model.trim().to_ascii_lowercase())
+        .collect::<HashSet<_>>();
+
+    let proposed = discovered
+        .iter()
+        .filter(|model| !routed_keys.contains(&model.upstream_model.to_ascii_lowercase()))
+        .map(|model| {
+            let matched = models
+                .iter()
+                .find(|item| item.slug.eq_ignore_ascii_case(&model.upstream_model));
+            AggregateApiModelRouteProposal {
+                model_slug: matched
+                    .map(|item| item.slug.clone())
+                    .unwrap_or_else(|| model.upstream_model.clone()),
+                model_exists: matched.is_some(),
+                route: ModelRouteV2 {
+                    id: String::new(),
+                    source_kind: AGGREGATE_API_ROUTE_SOURCE_KIND.to_string(),
+                    source_id: api.id.clone(),
+                    upstream_model: model.upstream_model.clone(),
+                    enabled: true,
+                    priority: 0,
+                    weight: 1,
+                },
+            }
+        })
+        .collect();
+
+    let stale = models
+        .iter()
+        .flat_map(|model| {
+            model
+                .routes
+                .iter()
+                .filter(|route| {
+                    is_api_route(route, api.id.as_str())
+                        && !discovered_keys
+                            .contains(&route.upstream_model.trim().to_ascii_lowercase())
+                })
+                .map(|route| AggregateApiStaleModelRoute {
+                    model_slug: model.slug.clone(),
+                    route: route.clone(),
+                })
+        })
+        .collect();
+    (proposed, stale)
+}
+
+fn failed_report(api: &AggregateApi, message: String) -> AggregateApiModelDiscoveryReport {
+    AggregateApiModelDiscoveryReport {
+        api_id: api.id.clone(),
+        provider_type: normalize_provider_type_value(api.provider_type.as_str()),
+        supplier_name: api.supplier_name.clone(),
+        ok: false,
+        message: Some(message),
+        discovered_at: now_ts(),
+        ..Default::default()
+    }
+}
+
+fn discover_single_api(storage: &Storage, api: &AggregateApi) -> AggregateApiModelDiscoveryReport {
+    let secret = match storage.find_aggregate_api_with_secrets_by_id(api.id.as_str()) {
+        Ok(Some(found)) => found.secret_value,
+        Ok(None) => return failed_report(api, "aggregate api not found".to_string()),
+        Err(err) => return failed_report(api, err.to_string()),
+    };
+    let Some(secret) = secret else {
+        return failed_report(api, "aggregate api secret not found".to_string());
+    };
+    let client = gateway::upstream_client_for_aggregate_url(api.url.as_str());
+    let report = list_upstream_models(&client, api, secret.as_str())
+        .and_then(|discovered| apply_model_discovery(storage, api, &discovered, now_ts()));
+    match report {
+        Ok(report) => {
+            for stale in &report.stale_routes {
+                log::warn!(
+                    "event=aggregate_api_model_route_stale api_id={} model={} upstream_model={}",
+                    api.id,
+                    stale.model_slug,
+                    stale.route.upstream_model
+                );
+            }
+            report
+        }
+        Err(err) => {
+            log::warn!(
+                "event=aggregate_api_model_discovery_failed api_id={} err={err}",
+                api.id
+            );
+            failed_report(api, err)
+        }
+    }
+}
+
+/// 指定 api_id 时只发现该 API；否则遍历所有启用且支持发现的聚合 API。
+pub(crate) fn discover_aggregate_api_models(
+    api_id: Option<&str>,
+) -> Result<AggregateApiModelDiscoveryResult, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let api_id = api_id.map(str::trim).filter(|value| !value.is_empty());
+    let apis = match api_id {
+        Some(api_id) => {
+            let api = storage
+                .find_aggregate_api_with_secrets_by_id(api_id)
+                .map_err(|err| err.to_string())?
+                .ok_or_else(|| "aggregate api not found".to_string())?
+                .api;
+            if !supports_model_discovery(api.provider_type.as_str()) {
+                return Err(format!(
+                    "model discovery unsupported for provider {}",
+                    normalize_provider_type_value(api.provider_type.as_str())
+                ));
+            }
+            vec![api]
+        }
+        None => storage
+            .list_aggregate_apis()
+            .map_err(|err| err.to_string())?
+            .into_iter()
+            .filter(|api| {
+                api.status == "active" && supports_model_discovery(api.provider_type.as_str())
+            })
+            .collect(),
+    };
+    Ok(AggregateApiModelDiscoveryResult {
+        items: apis
+            .iter()
+            .map(|api| discover_single_api(&storage, api))
+            .collect(),
+    })
+}
+
+pub(crate) fn ensure_aggregate_api_model_discovery() {
+    MODEL_DISCOVERY_STARTED.get_or_init(|| {
+        if let Err(err) = thread::Builder::new()
+            .name("aggregate-api-model-discovery".to_string())
+            .spawn(model_discovery_loop)
+        {
+            log::warn!("aggregate api model discovery thread failed to start: {err}");
+        }
+    });
+}
+
+/// 从环境变量刷新自动发现间隔；设置页应用运行时覆盖后也会调用。
+pub(crate) fn reload_aggregate_api_model_discovery_from_env() {
+    MODEL_DISCOVERY_INTERVAL_SECS.store(
+        parse_model_discovery_interval_secs(std::env::var(MODEL_DISCOVERY_INTERVAL_ENV).ok()),
+        Ordering::Relaxed,
+    );
+}
+
+fn model_discovery_loop() {
+    // 中文注释：按秒轮询当前间隔，运行时改成 0 会立即停掉下一轮，改回非 0 后从改动时刻重新计时。
+    let mut last_run = Instant::now();
+    let mut was_enabled = true;
+    loop {
+        if crate::shutdown_requested() {
+            break;
+        }
+        thread::sleep(Duration::from_secs(1));
+        let interval_secs = MODEL_DISCOVERY_INTERVAL_SECS.load(Ordering::Relaxed);
+        if interval_secs == 0 {
+            was_enabled = false;
+            continue;
+        }
+        if !was_enabled {
+            was_enabled = true;
+            last_run = Instant::now();
+        }
+        if last_run.elapsed() < Duration::from_secs(interval_secs) {
+            continue;
+        }
+        last_run = Instant::now();
+        match discover_aggregate_api_models(None) {
+            Ok(result) => {
+                let failed = result.items.iter().filter(|item| !item.ok).count();
+                log::info!(
+                    "event=aggregate_api_model_discovery_done apis={} failed={failed}",
+                    result.items.len()
+                );
+            }
+            Err(err) => {
+                log::warn!("event=aggregate_api_model_discovery_failed err={err}");
+            }
+        }
+    }
+}
+
+/// 0 表示关闭定时发现；其余值不低于最小间隔，缺省或非法时使用默认间隔。
+fn parse_model_discovery_interval_secs(raw: Option<String>) -> u64 {
+    match raw.and_then(|value| value.trim().parse::<u64>().ok()) {
+        Some(0) => 0,
+        Some(value) => value.max(MIN_MODEL_DISCOVERY_INTERVAL_SECS),
+        None => DEFAULT_MODEL_DISCOVERY_INTERVAL_SECS,
+    }
+}
+
+#[cfg(test)]
+#[path = "aggregate_api_discovery_tests.rs"]
+mod tests;
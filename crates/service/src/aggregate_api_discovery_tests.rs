@@ -0,0 +1,417 @@
+use super::*;
+use codexmanager_core::storage::ManagedModelV2Upsert;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use tiny_http::{Header, Response, Server};
+
+static DISCOVERY_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = DISCOVERY_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn aggregate_api(id: &str, provider_type: &str, url: &str) -> AggregateApi {
+    AggregateApi {
+        id: id.to_string(),
+        provider_type: provider_type.to_string(),
+        supplier_name: Some(id.to_string()),
+        sort: 0,
+        url: url.to_string(),
+        auth_type: "apikey".to_string(),
+        auth_params_json: None,
+        action: None,
+        model_override: None,
+        status: "active".to_string(),
+        created_at: 0,
+        updated_at: 0,
+        last_test_at: None,
+        last_test_status: None,
+        last_test_error: None,
+        balance_query_enabled: false,
+        balance_query_template: None,
+        balance_query_base_url: None,
+        balance_query_user_id: None,
+        balance_query_config_json: None,
+        last_balance_at: None,
+        last_balance_status: None,
+        last_balance_error: None,
+        last_balance_json: None,
+    }
+}
+
+fn discovered(models: &[&str]) -> Vec<DiscoveredModel> {
+    models
+        .iter()
+        .map(|model| DiscoveredModel {
+            upstream_model: model.to_string(),
+            display_name: None,
+        })
+        .collect()
+}
+
+fn supplier_model(api_id: &str, upstream_model: &str, status: &str) -> AggregateApiSupplierModel {
+    AggregateApiSupplierModel {
+        supplier_key: api_id.to_string(),
+        provider_type: AGGREGATE_API_PROVIDER_CODEX.to_string(),
+        upstream_model: upstream_model.to_string(),
+        display_name: None,
+        deployment: None,
+        status: status.to_string(),
+        created_at: 1,
+        updated_at: 1,
+    }
+}
+
+fn seed_managed_model(storage: &Storage, slug: &str, routes: Vec<ModelRouteV2>) {
+    let mut model = storage
+        .get_managed_model_v2("gpt-5.4-mini")
+        .expect("read template model")
+        .expect("template model");
+    model.id.clear();
+    model.slug = slug.to_string();
+    model.display_name = slug.to_string();
+    model.origin = "custom".to_string();
+    model.builtin_revision = None;
+    model.user_edited = false;
+    model.routes = routes;
+    storage
+        .upsert_managed_model_v2(&ManagedModelV2Upsert {
+            previous_slug: None,
+            model,
+        })
+        .expect("seed V2 model");
+}
+
+fn api_route(api_id: &str, upstream_model: &str) -> ModelRouteV2 {
+    ModelRouteV2 {
+        id: String::new(),
+        source_kind: "aggregate_api".to_string(),
+        source_id: api_id.to_string(),
+        upstream_model: upstream_model.to_string(),
+        enabled: true,
+        priority: 0,
+        weight: 1,
+    }
+}
+
+type CapturedRequest = (String, Option<String>);
+
+/// 依次响应给定的 JSON 页面，并回传每次请求的 URL 与鉴权头。
+fn spawn_model_list_server(
+    pages: Vec<String>,
+) -> (
+    String,
+    mpsc::Receiver<CapturedRequest>,
+    thread::JoinHandle<()>,
+) {
+    let server = Server::http("127.0.0.1:0").expect("start mock server");
+    let base_url = format!("http://{}", server.server_addr());
+    let (tx, rx) = mpsc::channel();
+    let join = thread::spawn(move || {
+        for page in pages {
+            let request = server
+                .recv_timeout(Duration::from_secs(2))
+                .expect("receive model list request")
+                .expect("model list request present");
+            let auth = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("x-api-key"))
+                .map(|header| header.value.as_str().to_string());
+            tx.send((request.url().to_string(), auth))
+                .expect("send captured request");
+            let response = Response::from_string(page).with_header(
+                Header::from_bytes("Content-Type", "application/json").expect("content type"),
+            );
+            request.respond(response).expect("respond model list");
+        }
+    });
+    (base_url, rx, join)
+}
+
+#[test]
+fn discovery_is_limited_to_providers_with_model_listing() {
+    for provider in [
+        "codex",
+        "openai",
+        "compatible",
+        "claude",
+        "anthropic",
+        "gemini",
+    ] {
+        assert!(supports_model_discovery(provider), "{provider}");
+    }
+    for provider in ["azure_openai", "bedrock"] {
+        assert!(!supports_model_discovery(provider), "{provider}");
+    }
+    assert_eq!(
+        gemini_models_url("https://generativelanguage.googleapis.com/"),
+        "https://generativelanguage.googleapis.com/v1beta/models"
+    );
+    assert_eq!(
+        gemini_models_url("https://proxy.example.com/v1beta"),
+        "https://proxy.example.com/v1beta/models"
+    );
+}
+
+#[test]
+fn discovery_interval_zero_disables_and_other_values_are_clamped() {
+    assert_eq!(
+        parse_model_discovery_interval_secs(None),
+        DEFAULT_MODEL_DISCOVERY_INTERVAL_SECS
+    );
+    assert_eq!(
+        parse_model_discovery_interval_secs(Some("oops".to_string())),
+        DEFAULT_MODEL_DISCOVERY_INTERVAL_SECS
+    );
+    assert_eq!(
+        parse_model_discovery_interval_secs(Some(" 0 ".to_string())),
+        0
+    );
+    assert_eq!(
+        parse_model_discovery_interval_secs(Some("30".to_string())),
+        MIN_MODEL_DISCOVERY_INTERVAL_SECS
+    );
+    assert_eq!(
+        parse_model_discovery_interval_secs(Some("3600".to_string())),
+        3600
+    );
+}
+
+#[test]
+fn openai_model_list_is_deduplicated_and_sorted() {
+    let (base_url, rx, join) = spawn_model_list_server(vec![
+        r#"{"object":"list","data":[{"id":"gpt-4o"},{"id":"gpt-4.1"},{"id":"GPT-4o"},{"id":""}]}"#
+            .to_string(),
+    ]);
+    let api = aggregate_api("agg-openai", "codex", format!("{base_url}/v1").as_str());
+
+    let models = list_upstream_models(&reqwest::blocking::Client::new(), &api, "sk-test")
+        .expect("list openai models");
+    join.join().expect("join mock server");
+
+    assert_eq!(
+        models
+            .iter()
+            .map(|model| model.upstream_model.as_str())
+            .collect::<Vec<_>>(),
+        vec!["gpt-4.1", "gpt-4o"]
+    );
+    let (path, auth) = rx.recv().expect("captured request");
+    assert_eq!(path, "/v1/models");
+    assert_eq!(auth.as_deref(), Some("sk-test"));
+}
+
+#[test]
+fn anthropic_model_list_follows_pagination() {
+    let (base_url, rx, join) = spawn_model_list_server(vec![
+        r#"{"data":[{"id":"claude-sonnet-4-5","display_name":"Claude Sonnet 4.5"}],"has_more":true,"last_id":"claude-sonnet-4-5"}"#.to_string(),
+        r#"{"data":[{"id":"claude-haiku-4-5","display_name":"Claude Haiku 4.5"}],"has_more":false,"last_id":"claude-haiku-4-5"}"#.to_string(),
+    ]);
+    let api = aggregate_api("agg-claude", "claude", base_url.as_str());
+
+    let models = list_upstream_models(&reqwest::blocking::Client::new(), &api, "sk-ant")
+        .expect("list anthropic models");
+    join.join().expect("join mock server");
+
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].upstream_model, "claude-haiku-4-5");
+    assert_eq!(models[0].display_name.as_deref(), Some("Claude Haiku 4.5"));
+    let (first, _) = rx.recv().expect("first page");
+    let (second, _) = rx.recv().expect("second page");
+    assert_eq!(first, "/v1/models?limit=1000");
+    assert_eq!(second, "/v1/models?limit=1000&after_id=claude-sonnet-4-5");
+}
+
+#[test]
+fn gemini_model_list_strips_prefix_and_skips_non_generative_models() {
+    let (base_url, rx, join) = spawn_model_list_server(vec![
+        r#"{"models":[{"name":"models/gemini-2.5-pro","displayName":"Gemini 2.5 Pro","supportedGenerationMethods":["generateContent","countTokens"]},{"name":"models/text-embedding-004","supportedGenerationMethods":["embedContent"]}],"nextPageToken":"page-2"}"#.to_string(),
+        r#"{"models":[{"name":"models/gemini-2.5-flash"}]}"#.to_string(),
+    ]);
+    let api = aggregate_api("agg-gemini", "gemini", base_url.as_str());
+
+    let models = list_upstream_models(&reqwest::blocking::Client::new(), &api, "gm-key")
+        .expect("list gemini models");
+    join.join().expect("join mock server");
+
+    assert_eq!(
+        models
+            .iter()
+            .map(|model| model.upstream_model.as_str())
+            .collect::<Vec<_>>(),
+        vec!["gemini-2.5-flash", "gemini-2.5-pro"]
+    );
+    assert_eq!(models[1].display_name.as_deref(), Some("Gemini 2.5 Pro"));
+    let (_, _) = rx.recv().expect("first page");
+    let (second, _) = rx.recv().expect("second page");
+    assert_eq!(second, "/v1beta/models?pageSize=1000&pageToken=page-2");
+}
+
+#[test]
+fn discovery_diff_updates_supplier_models_and_reports_routes() {
+    let _lock = crate::test_env_guard();
+    let dir = new_test_dir("aggregate-api-model-discovery");
+    let db_path = dir.join("codexmanager.db");
+    let storage = Storage::open(&db_path).expect("open db");
+    storage.init().expect("init db");
+    let api = aggregate_api("agg-discovery", "codex", "https://api.example.com/v1");
+    storage.insert_aggregate_api(&api).expect("insert api");
+    for model in [
+        supplier_model("agg-discovery", "demo-alpha", "available"),
+        supplier_model("agg-discovery", "demo-legacy", "available"),
+        supplier_model("agg-discovery", "demo-retired", "disabled"),
+    ] {
+        storage
+            .upsert_aggregate_api_supplier_model(&model)
+            .expect("seed supplier model");
+    }
+    seed_managed_model(
+        &storage,
+        "demo-alpha",
+        vec![api_route("agg-discovery", "demo-alpha")],
+    );
+    seed_managed_model(
+        &storage,
+        "demo-legacy",
+        vec![api_route("agg-discovery", "demo-legacy")],
+    );
+    seed_managed_model(&storage, "demo-beta", Vec::new());
+
+    let report = apply_model_discovery(
+        &storage,
+        &api,
+        &discovered(&["demo-beta", "demo-alpha", "demo-gamma"]),
+        100,
+    )
+    .expect("apply discovery");
+
+    assert!(report.ok);
+    assert_eq!(report.new_models, vec!["demo-beta", "demo-gamma"]);
+    assert_eq!(report.missing_models, vec!["demo-legacy"]);
+    assert_eq!(report.proposed_routes.len(), 2);
+    assert_eq!(report.proposed_routes[0].model_slug, "demo-beta");
+    assert!(report.proposed_routes[0].model_exists);
+    assert_eq!(
+        report.proposed_routes[0].route,
+        api_route("agg-discovery", "demo-beta")
+    );
+    assert_eq!(report.proposed_routes[1].model_slug, "demo-gamma");
+    assert!(!report.proposed_routes[1].model_exists);
+    assert_eq!(report.stale_routes.len(), 1);
+    assert_eq!(report.stale_routes[0].model_slug, "demo-legacy");
+
+    let stored = storage
+        .list_aggregate_api_supplier_models(Some("agg-discovery"), Some("codex"))
+        .expect("list supplier models");
+    let status = |model: &str| {
+        stored
+            .iter()
+            .find(|item| item.upstream_model == model)
+            .map(|item| item.status.clone())
+    };
+    assert_eq!(status("demo-alpha").as_deref(), Some("available"));
+    assert_eq!(status("demo-beta").as_deref(), Some("available"));
+    assert_eq!(status("demo-gamma").as_deref(), Some("available"));
+    assert_eq!(status("demo-legacy").as_deref(), Some("missing"));
+    assert_eq!(status("demo-retired").as_deref(), Some("disabled"));
+
+    // 中文注释：上游恢复后 missing 条目回到 available，且不再报告为新模型。
+    let report = apply_model_discovery(
+        &storage,
+        &api,
+        &discovered(&["demo-legacy", "demo-beta", "demo-alpha", "demo-gamma"]),
+        200,
+    )
+    .expect("apply discovery again");
+    assert!(report.new_models.is_empty());
+    assert!(report.missing_models.is_empty());
+    assert!(report.stale_routes.is_empty());
+    let revived = storage
+        .list_aggregate_api_supplier_models(Some("agg-discovery"), Some("codex"))
+        .expect("list supplier models")
+        .into_iter()
+        .find(|item| item.upstream_model == "demo-legacy")
+        .expect("revived model");
+    assert_eq!(revived.status, "available");
+    assert_eq!(revived.created_at, 1);
+    assert_eq!(revived.updated_at, 200);
+}
+
+#[test]
+fn on_demand_discovery_rejects_unsupported_provider_and_reports_upstream_errors() {
+    let _lock = crate::test_env_guard();
+    let dir = new_test_dir("aggregate-api-model-discovery-rpc");
+    let db_path = dir.join("codexmanager.db");
+    let _guard = EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref());
+    let storage = Storage::open(&db_path).expect("open db");
+    storage.init().expect("init db");
+    storage
+        .insert_aggregate_api(&aggregate_api(
+            "agg-bedrock",
+            "bedrock",
+            "https://bedrock-runtime.us-east-1.amazonaws.com",
+        ))
+        .expect("insert bedrock api");
+
+    let server = Server::http("127.0.0.1:0").expect("start mock server");
+    let base_url = format!("http://{}/v1", server.server_addr());
+    storage
+        .insert_aggregate_api(&aggregate_api("agg-down", "codex", base_url.as_str()))
+        .expect("insert codex api");
+    storage
+        .upsert_aggregate_api_secret("agg-down", "sk-down")
+        .expect("save secret");
+    let join = thread::spawn(move || {
+        let request = server
+            .recv_timeout(Duration::from_secs(2))
+            .expect("receive model list request")
+            .expect("model list request present");
+        request
+            .respond(
+                Response::from_string(r#"{"error":{"message":"bad key"}}"#).with_status_code(401),
+            )
+            .expect("respond model list");
+    });
+
+    let err = discover_aggregate_api_models(Some("agg-bedrock")).expect_err("unsupported");
+    assert!(err.contains("unsupported"));
+
+    let result = discover_aggregate_api_models(None).expect("discover all");
+    join.join().expect("join mock server");
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].api_id, "agg-down");
+    assert!(!result.items[0].ok);
+    assert!(result.items[0]
+        .message
+        .as_deref()
+        .is_some_and(|message| message.contains("http_status=401")));
+}
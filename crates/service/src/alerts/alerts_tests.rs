@@ -0,0 +1,170 @@
+use super::*;
+use codexmanager_core::storage::Storage;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::test_env_guard;
+
+static ALERTS_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = ALERTS_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn init_db(prefix: &str) -> EnvGuard {
+    let dir = new_test_dir(prefix);
+    let db_path = dir.join("codexmanager.db");
+    Storage::open(&db_path)
+        .expect("open db")
+        .init()
+        .expect("init db");
+    EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref())
+}
+
+#[test]
+fn set_alert_rule_validates_and_normalizes_fields() {
+    let _lock = test_env_guard();
+    let _guard = init_db("alerts-rule-set");
+
+    let channel = set_alert_channel(AlertChannelSetParams {
+        name: "ops".to_string(),
+        kind: "slack_webhook".to_string(),
+        config: serde_json::json!({ "url": "https://hooks.slack.com/services/T/B/X" }),
+        ..Default::default()
+    })
+    .expect("save channel");
+    assert!(channel.id.starts_with("ac_"));
+    assert_eq!(channel.kind, "slack");
+
+    let rule = set_alert_rule(AlertRuleSetParams {
+        name: " low wallets ".to_string(),
+        condition: "Wallet-Low".to_string(),
+        threshold: Some(2.5),
+        channel_ids: vec![channel.id.clone(), channel.id.clone(), " ".to_string()],
+        ..Default::default()
+    })
+    .expect("save rule");
+    assert!(rule.id.starts_with("ar_"));
+    assert_eq!(rule.name, "low wallets");
+    assert_eq!(rule.condition, "wallet_low");
+    assert_eq!(rule.severity, "warning");
+    assert_eq!(rule.cooldown_secs, DEFAULT_COOLDOWN_SECS);
+    assert_eq!(rule.channel_ids, vec![channel.id.clone()]);
+
+    let invalid = |params: AlertRuleSetParams| set_alert_rule(params).expect_err("invalid rule");
+    let base = AlertRuleSetParams {
+        name: "rule".to_string(),
+        condition: "account_banned".to_string(),
+        ..Default::default()
+    };
+    assert!(invalid(AlertRuleSetParams {
+        condition: "disk_full".to_string(),
+        ..base.clone()
+    })
+    .contains("invalid condition"));
+    assert!(invalid(AlertRuleSetParams {
+        severity: Some("fatal".to_string()),
+        ..base.clone()
+    })
+    .contains("invalid severity"));
+    assert!(invalid(AlertRuleSetParams {
+        threshold: Some(-1.0),
+        ..base.clone()
+    })
+    .contains("threshold"));
+    assert!(invalid(AlertRuleSetParams {
+        channel_ids: vec!["ac_missing".to_string()],
+        ..base
+    })
+    .contains("alert channel not found"));
+
+    delete_alert_rule(rule.id.as_str()).expect("delete rule");
+    assert!(list_alert_rules().expect("list rules").is_empty());
+}
+
+#[test]
+fn set_alert_channel_masks_and_keeps_smtp_password() {
+    let _lock = test_env_guard();
+    let _guard = init_db("alerts-channel-set");
+
+    let saved = set_alert_channel(AlertChannelSetParams {
+        id: Some("mail".to_string()),
+        name: "mail".to_string(),
+        kind: "email".to_string(),
+        config: serde_json::json!({
+            "host": "smtp.example.com",
+            "username": "ops",
+            "password": "secret",
+            "from": "CodexManager <alerts@example.com>",
+            "to": ["ops@example.com"],
+        }),
+        ..Default::default()
+    })
+    .expect("save smtp channel");
+    assert_eq!(saved.kind, "smtp");
+    assert_eq!(saved.config["password"], MASKED_SECRET);
+
+    let mut config = saved.config.clone();
+    config["port"] = serde_json::json!(2525);
+    set_alert_channel(AlertChannelSetParams {
+        id: Some("mail".to_string()),
+        name: "mail".to_string(),
+        kind: "smtp".to_string(),
+        config,
+        enabled: Some(false),
+    })
+    .expect("update smtp channel");
+    let stored = open_storage()
+        .expect("storage")
+        .find_alert_channel("mail")
+        .expect("find channel")
+        .expect("channel exists");
+    let stored_config: Value = serde_json::from_str(&stored.config_json).expect("config json");
+    assert_eq!(stored_config["password"], "secret");
+    assert_eq!(stored_config["port"], 2525);
+    assert!(!stored.enabled);
+
+    let err = set_alert_channel(AlertChannelSetParams {
+        name: "bad".to_string(),
+        kind: "webhook".to_string(),
+        config: serde_json::json!({ "url": "ftp://example.com/hook" }),
+        ..Default::default()
+    })
+    .expect_err("reject non-http webhook");
+    assert!(err.contains("unsupported url scheme"));
+    let err = set_alert_channel(AlertChannelSetParams {
+        name: "pager".to_string(),
+        kind: "pagerduty".to_string(),
+        config: serde_json::json!({}),
+        ..Default::default()
+    })
+    .expect_err("reject unknown kind");
+    assert!(err.contains("invalid kind"));
+}
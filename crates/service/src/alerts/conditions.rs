@@ -0,0 +1,276 @@
+use codexmanager_core::storage::{
+    Account, AccountQuotaOverviewStats, AccountSubscription, AggregateApi, AppWallet, Storage,
+};
+use std::collections::HashMap;
+
+use crate::account_status::REFRESH_TOKEN_REGION_BLOCKED_REASON;
+use crate::gateway::bilingual_error as bilingual;
+
+const CREDIT_MICROS_PER_CREDIT: f64 = 1_000_000.0;
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// 告警规则可选的触发条件；阈值含义随条件而定。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AlertCondition {
+    AccountBanned,
+    TokenRefreshFailed,
+    AggregateBalanceLow,
+    WalletLow,
+    SubscriptionExpiring,
+    QuotaPoolExhausted,
+}
+
+impl AlertCondition {
+    pub(crate) const ALL: [Self; 6] = [
+        Self::AccountBanned,
+        Self::TokenRefreshFailed,
+        Self::AggregateBalanceLow,
+        Self::WalletLow,
+        Self::SubscriptionExpiring,
+        Self::QuotaPoolExhausted,
+    ];
+
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let normalized = raw.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|condition| condition.as_str() == normalized)
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::AccountBanned => "account_banned",
+            Self::TokenRefreshFailed => "token_refresh_failed",
+            Self::AggregateBalanceLow => "aggregate_balance_low",
+            Self::WalletLow => "wallet_low",
+            Self::SubscriptionExpiring => "subscription_expiring",
+            Self::QuotaPoolExhausted => "quota_pool_exhausted",
+        }
+    }
+
+    /// 余额类为剩余金额、钱包为 credit、订阅为天数、号池为可用账号百分比。
+    pub(crate) fn default_threshold(self) -> Option<f64> {
+        match self {
+            Self::AccountBanned | Self::TokenRefreshFailed => None,
+            Self::AggregateBalanceLow => Some(5.0),
+            Self::WalletLow => Some(1.0),
+            Self::SubscriptionExpiring => Some(3.0),
+            Self::QuotaPoolExhausted => Some(0.0),
+        }
+    }
+}
+
+/// 单次命中：`subject` 标识具体对象，冷却按 规则 + 对象 计算。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AlertFiring {
+    pub(crate) subject: String,
+    pub(crate) message: String,
+}
+
+pub(crate) fn collect_alert_firings(
+    storage: &Storage,
+    condition: AlertCondition,
+    threshold: Option<f64>,
+    now: i64,
+) -> Result<Vec<AlertFiring>, String> {
+    let threshold = threshold
+        .or_else(|| condition.default_threshold())
+        .unwrap_or(0.0);
+    match condition {
+        AlertCondition::AccountBanned => storage
+            .list_accounts_by_statuses(&["banned".to_string()])
+            .map(|accounts| banned_account_firings(&accounts))
+            .map_err(|err| format!("list banned accounts failed: {err}")),
+        AlertCondition::TokenRefreshFailed => {
+            let accounts = storage
+                .list_accounts_by_statuses(&["unavailable".to_string()])
+                .map_err(|err| format!("list unavailable accounts failed: {err}"))?;
+            let account_ids = accounts
+                .iter()
+                .map(|account| account.id.clone())
+                .collect::<Vec<_>>();
+            let reasons = storage
+                .latest_account_status_reasons(&account_ids)
+                .map_err(|err| format!("read account status reasons failed: {err}"))?;
+            Ok(token_refresh_failure_firings(&accounts, &reasons))
+        }
+        AlertCondition::AggregateBalanceLow => storage
+            .list_aggregate_apis()
+            .map(|apis| aggregate_balance_firings(&apis, threshold))
+            .map_err(|err| format!("list aggregate apis failed: {err}")),
+        AlertCondition::WalletLow => storage
+            .list_wallets()
+            .map(|wallets| wallet_low_firings(&wallets, threshold))
+            .map_err(|err| format!("list wallets failed: {err}")),
+        AlertCondition::SubscriptionExpiring => {
+            let subscriptions = storage
+                .list_account_subscriptions()
+                .map_err(|err| format!("list account subscriptions failed: {err}"))?;
+            let labels = storage
+                .list_accounts()
+                .map_err(|err| format!("list accounts failed: {err}"))?
+                .into_iter()
+                .map(|account| (account.id, account.label))
+                .collect::<HashMap<_, _>>();
+            Ok(subscription_expiring_firings(
+                &subscriptions,
+                &labels,
+                threshold,
+                now,
+            ))
+        }
+        AlertCondition::QuotaPoolExhausted => storage
+            .account_quota_overview_stats()
+            .map(|stats| quota_pool_firings(&stats, threshold))
+            .map_err(|err| format!("read account quota overview failed: {err}")),
+    }
+}
+
+pub(crate) fn banned_account_firings(accounts: &[Account]) -> Vec<AlertFiring> {
+    accounts
+        .iter()
+        .filter(|account| account.status.trim().eq_ignore_ascii_case("banned"))
+        .map(|account| AlertFiring {
+            subject: format!("account:{}", account.id),
+            message: bilingual(
+                format!("账号 {} 已被封禁", account.label),
+                format!("account {} is banned", account.label),
+            ),
+        })
+        .collect()
+}
+
+pub(crate) fn token_refresh_failure_firings(
+    accounts: &[Account],
+    reasons: &HashMap<String, String>,
+) -> Vec<AlertFiring> {
+    accounts
+        .iter()
+        .filter_map(|account| {
+            let reason = reasons.get(account.id.as_str())?;
+            let is_refresh_failure = reason.starts_with("refresh_token_invalid")
+                || reason == REFRESH_TOKEN_REGION_BLOCKED_REASON;
+            is_refresh_failure.then(|| AlertFiring {
+                subject: format!("account:{}", account.id),
+                message: bilingual(
+                    format!("账号 {} 刷新令牌失败：{reason}", account.label),
+                    format!("account {} token refresh failed: {reason}", account.label),
+                ),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn aggregate_balance_firings(apis: &[AggregateApi], threshold: f64) -> Vec<AlertFiring> {
+    apis.iter()
+        .filter(|api| api.status == "active")
+        .filter_map(|api| {
+            let (remaining, is_valid) = crate::gateway::cost_route::parse_balance(api);
+            let name = api.supplier_name.as_deref().unwrap_or(api.id.as_str());
+            let message = if !is_valid {
+                bilingual(
+                    format!("聚合 API {name} 余额查询显示不可用"),
+                    format!("aggregate api {name} balance is marked invalid"),
+                )
+            } else {
+                let remaining = remaining.filter(|value| *value < threshold)?;
+                bilingual(
+                    format!("聚合 API {name} 余额 {remaining:.2} 低于阈值 {threshold:.2}"),
+                    format!("aggregate api {name} balance {remaining:.2} is below {threshold:.2}"),
+                )
+            };
+            Some(AlertFiring {
+                subject: format!("aggregate_api:{}", api.id),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// 从未充值或扣费过的新钱包不提醒，避免每个新用户都触发告警。
+pub(crate) fn wallet_low_firings(wallets: &[AppWallet], threshold: f64) -> Vec<AlertFiring> {
+    wallets
+        .iter()
+        .filter(|wallet| wallet.status == "active" && wallet.updated_at > wallet.created_at)
+        .filter_map(|wallet| {
+            let available = (wallet.balance_credit_micros - wallet.frozen_credit_micros) as f64
+                / CREDIT_MICROS_PER_CREDIT;
+            (available < threshold).then(|| AlertFiring {
+                subject: format!("wallet:{}", wallet.id),
+                message: bilingual(
+                    format!(
+                        "{} {} 的钱包可用余额 {available:.2} 低于阈值 {threshold:.2}",
+                        wallet.owner_kind, wallet.owner_id
+                    ),
+                    format!(
+                        "wallet of {} {} has {available:.2} credits available, below {threshold:.2}",
+                        wallet.owner_kind, wallet.owner_id
+                    ),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// 已设置续订时间的订阅视为会自动续期，不提醒。
+pub(crate) fn subscription_expiring_firings(
+    subscriptions: &[AccountSubscription],
+    labels: &HashMap<String, String>,
+    threshold_days: f64,
+    now: i64,
+) -> Vec<AlertFiring> {
+    let horizon = now + (threshold_days.max(0.0) * SECS_PER_DAY) as i64;
+    subscriptions
+        .iter()
+        .filter(|subscription| subscription.has_subscription && subscription.renews_at.is_none())
+        .filter_map(|subscription| {
+            let expires_at = subscription
+                .expires_at
+                .filter(|expires_at| *expires_at > now && *expires_at <= horizon)?;
+            let label = labels
+                .get(subscription.account_id.as_str())
+                .map(String::as_str)
+                .unwrap_or(subscription.account_id.as_str());
+            let days_left = (expires_at - now) as f64 / SECS_PER_DAY;
+            Some(AlertFiring {
+                subject: format!("subscription:{}", subscription.account_id),
+                message: bilingual(
+                    format!("账号 {label} 的订阅将在 {days_left:.1} 天后到期"),
+                    format!("subscription of account {label} expires in {days_left:.1} days"),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// 可用账号占比不高于阈值百分比时触发；号池为空时不告警。
+pub(crate) fn quota_pool_firings(
+    stats: &AccountQuotaOverviewStats,
+    threshold_percent: f64,
+) -> Vec<AlertFiring> {
+    if stats.account_count <= 0 {
+        return Vec::new();
+    }
+    let available_percent =
+        stats.available_count.max(0) as f64 * 100.0 / stats.account_count as f64;
+    if available_percent > threshold_percent {
+        return Vec::new();
+    }
+    vec![AlertFiring {
+        subject: "pool".to_string(),
+        message: bilingual(
+            format!(
+                "号池可用账号 {}/{}（{available_percent:.1}%），额度接近耗尽",
+                stats.available_count, stats.account_count
+            ),
+            format!(
+                "only {}/{} accounts ({available_percent:.1}%) have quota left in the pool",
+                stats.available_count, stats.account_count
+            ),
+        ),
+    }]
+}
+
+#[cfg(test)]
+#[path = "conditions_tests.rs"]
+mod tests;
@@ -0,0 +1,171 @@
+use super::*;
+
+fn account(id: &str, status: &str) -> Account {
+    Account {
+        id: id.to_string(),
+        label: format!("label-{id}"),
+        issuer: "https://auth.openai.com".to_string(),
+        chatgpt_account_id: None,
+        workspace_id: None,
+        group_name: None,
+        sort: 0,
+        status: status.to_string(),
+        created_at: 1,
+        updated_at: 1,
+    }
+}
+
+fn aggregate_api(id: &str, status: &str, balance_json: Option<&str>) -> AggregateApi {
+    AggregateApi {
+        id: id.to_string(),
+        provider_type: "codex".to_string(),
+        supplier_name: Some(format!("supplier-{id}")),
+        sort: 0,
+        url: "https://api.example.com".to_string(),
+        auth_type: "apikey".to_string(),
+        auth_params_json: None,
+        action: None,
+        model_override: None,
+        status: status.to_string(),
+        created_at: 1,
+        updated_at: 1,
+        last_test_at: None,
+        last_test_status: None,
+        last_test_error: None,
+        balance_query_enabled: balance_json.is_some(),
+        balance_query_template: None,
+        balance_query_base_url: None,
+        balance_query_user_id: None,
+        balance_query_config_json: None,
+        last_balance_at: None,
+        last_balance_status: None,
+        last_balance_error: None,
+        last_balance_json: balance_json.map(ToString::to_string),
+    }
+}
+
+fn wallet(id: &str, balance: i64, frozen: i64, updated_at: i64) -> AppWallet {
+    AppWallet {
+        id: id.to_string(),
+        owner_kind: "user".to_string(),
+        owner_id: format!("user-{id}"),
+        balance_credit_micros: balance,
+        frozen_credit_micros: frozen,
+        status: "active".to_string(),
+        created_at: 100,
+        updated_at,
+    }
+}
+
+fn subscription(
+    account_id: &str,
+    expires_at: Option<i64>,
+    renews_at: Option<i64>,
+) -> AccountSubscription {
+    AccountSubscription {
+        account_id: account_id.to_string(),
+        has_subscription: true,
+        account_plan_type: Some("plus".to_string()),
+        plan_type: Some("plus".to_string()),
+        expires_at,
+        renews_at,
+        updated_at: 1,
+    }
+}
+
+fn subjects(firings: &[AlertFiring]) -> Vec<&str> {
+    firings.iter().map(|item| item.subject.as_str()).collect()
+}
+
+#[test]
+fn alert_condition_parse_accepts_aliases_and_round_trips() {
+    for condition in AlertCondition::ALL {
+        assert_eq!(AlertCondition::parse(condition.as_str()), Some(condition));
+    }
+    assert_eq!(
+        AlertCondition::parse(" Wallet-Low "),
+        Some(AlertCondition::WalletLow)
+    );
+    assert_eq!(AlertCondition::parse("unknown"), None);
+}
+
+#[test]
+fn account_firings_cover_banned_and_refresh_failures() {
+    let banned = banned_account_firings(&[account("a", "banned"), account("b", "active")]);
+    assert_eq!(subjects(&banned), vec!["account:a"]);
+    assert!(banned[0].message.contains("label-a"));
+
+    let reasons = HashMap::from([
+        (
+            "a".to_string(),
+            "refresh_token_invalid:invalid_grant".to_string(),
+        ),
+        (
+            "b".to_string(),
+            REFRESH_TOKEN_REGION_BLOCKED_REASON.to_string(),
+        ),
+        ("c".to_string(), "usage_http_401".to_string()),
+    ]);
+    let failed = token_refresh_failure_firings(
+        &[
+            account("a", "unavailable"),
+            account("b", "unavailable"),
+            account("c", "unavailable"),
+            account("d", "unavailable"),
+        ],
+        &reasons,
+    );
+    assert_eq!(subjects(&failed), vec!["account:a", "account:b"]);
+}
+
+#[test]
+fn balance_firings_respect_threshold_and_invalid_balance() {
+    let apis = [
+        aggregate_api("low", "active", Some(r#"{"remaining":2.5}"#)),
+        aggregate_api("enough", "active", Some(r#"{"remaining":20}"#)),
+        aggregate_api("invalid", "active", Some(r#"{"isValid":false}"#)),
+        aggregate_api("disabled", "disabled", Some(r#"{"remaining":0}"#)),
+        aggregate_api("untracked", "active", None),
+    ];
+    let firings = aggregate_balance_firings(&apis, 5.0);
+    assert_eq!(
+        subjects(&firings),
+        vec!["aggregate_api:low", "aggregate_api:invalid"]
+    );
+
+    let wallets = [
+        wallet("low", 1_500_000, 1_000_000, 200),
+        wallet("enough", 5_000_000, 0, 200),
+        wallet("fresh", 0, 0, 100),
+    ];
+    assert_eq!(
+        subjects(&wallet_low_firings(&wallets, 1.0)),
+        vec!["wallet:low"]
+    );
+}
+
+#[test]
+fn subscription_and_pool_firings_use_horizon_and_percent() {
+    let now = 1_000_000;
+    let day = 86_400;
+    let subscriptions = [
+        subscription("soon", Some(now + day), None),
+        subscription("later", Some(now + 10 * day), None),
+        subscription("renewing", Some(now + day), Some(now + day)),
+        subscription("expired", Some(now - day), None),
+    ];
+    let labels = HashMap::from([("soon".to_string(), "Soon Account".to_string())]);
+    let firings = subscription_expiring_firings(&subscriptions, &labels, 3.0, now);
+    assert_eq!(subjects(&firings), vec!["subscription:soon"]);
+    assert!(firings[0].message.contains("Soon Account"));
+
+    let stats = |available_count| AccountQuotaOverviewStats {
+        account_count: 10,
+        available_count,
+        ..Default::default()
+    };
+    assert!(quota_pool_firings(&stats(1), 0.0).is_empty());
+    assert_eq!(subjects(&quota_pool_firings(&stats(0), 0.0)), vec!["pool"]);
+    assert_eq!(subjects(&quota_pool_firings(&stats(1), 10.0)), vec!["pool"]);
+    assert!(quota_pool_firings(&AccountQuotaOverviewStats::default(), 100.0).is_empty());
+}
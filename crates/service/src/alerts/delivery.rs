@@ -0,0 +1,258 @@
+use codexmanager_core::storage::AlertChannel;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+pub(crate) const CHANNEL_KIND_WEBHOOK: &str = "webhook";
+pub(crate) const CHANNEL_KIND_SLACK: &str = "slack";
+pub(crate) const CHANNEL_KIND_SMTP: &str = "smtp";
+const DELIVERY_TIMEOUT_SECS: u64 = 15;
+const SMTP_SECURITY_STARTTLS: &str = "starttls";
+const SMTP_SECURITY_TLS: &str = "tls";
+const SMTP_SECURITY_NONE: &str = "none";
+
+static ALERT_HTTP_CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+
+/// 一次告警投递的内容；测试消息与规则触发共用。
+#[derive(Debug, Clone)]
+pub(crate) struct AlertNotification {
+    pub(crate) rule_id: String,
+    pub(crate) rule_name: String,
+    pub(crate) condition: String,
+    pub(crate) severity: String,
+    pub(crate) subject: String,
+    pub(crate) message: String,
+    pub(crate) fired_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookConfig {
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SmtpConfig {
+    host: String,
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    from: String,
+    #[serde(default)]
+    to: Vec<String>,
+    #[serde(default)]
+    security: Option<String>,
+}
+
+pub(crate) fn normalize_channel_kind(raw: &str) -> Option<&'static str> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "webhook" | "http" => Some(CHANNEL_KIND_WEBHOOK),
+        "slack" | "slack_webhook" => Some(CHANNEL_KIND_SLACK),
+        "smtp" | "email" | "mail" => Some(CHANNEL_KIND_SMTP),
+        _ => None,
+    }
+}
+
+/// 保存渠道前校验配置，避免到触发时才发现地址或收件人缺失。
+pub(crate) fn validate_channel_config(kind: &str, config: &Value) -> Result<(), String> {
+    match kind {
+        CHANNEL_KIND_WEBHOOK | CHANNEL_KIND_SLACK => {
+            let parsed = serde_json::from_value::<WebhookConfig>(config.clone())
+                .map_err(|err| format!("invalid {kind} channel config: {err}"))?;
+            validate_http_url(parsed.url.as_str())
+        }
+        CHANNEL_KIND_SMTP => {
+            let parsed = parse_smtp_config(config)?;
+            build_email(&parsed, "validation", "validation").map(|_| ())
+        }
+        other => Err(format!("unsupported alert channel kind: {other}")),
+    }
+}
+
+pub(crate) fn deliver_alert(
+    channel: &AlertChannel,
+    notification: &AlertNotification,
+) -> Result<(), String> {
+    let config = serde_json::from_str::<Value>(channel.config_json.as_str())
+        .map_err(|err| format!("invalid channel config json: {err}"))?;
+    match channel.kind.as_str() {
+        CHANNEL_KIND_WEBHOOK => {
+            let parsed = serde_json::from_value::<WebhookConfig>(config)
+                .map_err(|err| format!("invalid webhook channel config: {err}"))?;
+            post_json(
+                parsed.url.as_str(),
+                &parsed.headers,
+                &webhook_payload(notification),
+            )
+        }
+        CHANNEL_KIND_SLACK => {
+            let parsed = serde_json::from_value::<WebhookConfig>(config)
+                .map_err(|err| format!("invalid slack channel config: {err}"))?;
+            post_json(
+                parsed.url.as_str(),
+                &parsed.headers,
+                &slack_payload(notification),
+            )
+        }
+        CHANNEL_KIND_SMTP => send_email(&parse_smtp_config(&config)?, notification),
+        other => Err(format!("unsupported alert channel kind: {other}")),
+    }
+}
+
+pub(crate) fn webhook_payload(notification: &AlertNotification) -> Value {
+    json!({
+        "event": "codexmanager.alert",
+        "ruleId": notification.rule_id,
+        "ruleName": notification.rule_name,
+        "condition": notification.condition,
+        "severity": notification.severity,
+        "subject": notification.subject,
+        "message": notification.message,
+        "firedAt": notification.fired_at,
+    })
+}
+
+/// Slack 兼容的 incoming webhook 只需要 `text` 字段（飞书、Mattermost 等同样适用）。
+pub(crate) fn slack_payload(notification: &AlertNotification) -> Value {
+    json!({ "text": notification_title(notification) + "\n" + notification.message.as_str() })
+}
+
+fn notification_title(notification: &AlertNotification) -> String {
+    format!(
+        "[CodexManager][{}] {}",
+        notification.severity.to_ascii_uppercase(),
+        notification.rule_name
+    )
+}
+
+fn alert_http_client() -> &'static reqwest::blocking::Client {
+    ALERT_HTTP_CLIENT.get_or_init(|| {
+        reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_else(|_| reqwest::blocking::Client::new())
+    })
+}
+
+fn validate_http_url(raw: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(raw.trim()).map_err(|err| format!("invalid url: {err}"))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("unsupported url scheme: {}", url.scheme()));
+    }
+    Ok(())
+}
+
+fn post_json(url: &str, headers: &BTreeMap<String, String>, body: &Value) -> Result<(), String> {
+    validate_http_url(url)?;
+    let mut request = alert_http_client().post(url.trim()).json(body);
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .map_err(|err| format!("send alert webhook failed: {err}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("alert webhook responded with status {status}"));
+    }
+    Ok(())
+}
+
+fn parse_smtp_config(config: &Value) -> Result<SmtpConfig, String> {
+    let parsed = serde_json::from_value::<SmtpConfig>(config.clone())
+        .map_err(|err| format!("invalid smtp channel config: {err}"))?;
+    if parsed.host.trim().is_empty() {
+        return Err("smtp host is required".to_string());
+    }
+    if parsed.to.iter().all(|item| item.trim().is_empty()) {
+        return Err("smtp recipients are required".to_string());
+    }
+    smtp_security(&parsed)?;
+    Ok(parsed)
+}
+
+fn smtp_security(config: &SmtpConfig) -> Result<&'static str, String> {
+    match config
+        .security
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some(SMTP_SECURITY_STARTTLS) => Ok(SMTP_SECURITY_STARTTLS),
+        Some(SMTP_SECURITY_TLS) => Ok(SMTP_SECURITY_TLS),
+        Some(SMTP_SECURITY_NONE) => Ok(SMTP_SECURITY_NONE),
+        Some(other) => Err(format!(
+            "unsupported smtp security: {other}; use starttls, tls or none"
+        )),
+    }
+}
+
+fn build_email(config: &SmtpConfig, subject: &str, body: &str) -> Result<Message, String> {
+    let from = config
+        .from
+        .trim()
+        .parse::<Mailbox>()
+        .map_err(|err| format!("invalid smtp from address: {err}"))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in config.to.iter().map(|item| item.trim()) {
+        if recipient.is_empty() {
+            continue;
+        }
+        let mailbox = recipient
+            .parse::<Mailbox>()
+            .map_err(|err| format!("invalid smtp recipient {recipient}: {err}"))?;
+        builder = builder.to(mailbox);
+    }
+    builder
+        .body(body.to_string())
+        .map_err(|err| format!("build alert email failed: {err}"))
+}
+
+fn send_email(config: &SmtpConfig, notification: &AlertNotification) -> Result<(), String> {
+    let body = format!(
+        "{}\n\nrule: {}\ncondition: {}\nsubject: {}\nfiredAt: {}\n",
+        notification.message,
+        notification.rule_id,
+        notification.condition,
+        notification.subject,
+        notification.fired_at
+    );
+    let email = build_email(config, notification_title(notification).as_str(), &body)?;
+    let host = config.host.trim();
+    let builder = match smtp_security(config)? {
+        SMTP_SECURITY_TLS => SmtpTransport::relay(host),
+        SMTP_SECURITY_NONE => Ok(SmtpTransport::builder_dangerous(host)),
+        _ => SmtpTransport::starttls_relay(host),
+    }
+    .map_err(|err| format!("build smtp transport failed: {err}"))?;
+    let mut builder = builder.timeout(Some(Duration::from_secs(DELIVERY_TIMEOUT_SECS)));
+    if let Some(port) = config.port {
+        builder = builder.port(port);
+    }
+    if let Some(username) = config
+        .username
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        builder = builder.credentials(Credentials::new(
+            username.to_string(),
+            config.password.clone().unwrap_or_default(),
+        ));
+    }
+    builder
+        .build()
+        .send(&email)
+        .map(|_| ())
+        .map_err(|err| format!("send alert email failed: {err}"))
+}
@@ -0,0 +1,267 @@
+use codexmanager_core::storage::{now_ts, AlertChannel, AlertHistoryRecord, AlertRule, Storage};
+use crossbeam_channel::{bounded, Sender};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+
+use super::conditions::{collect_alert_firings, AlertCondition, AlertFiring};
+use super::delivery::{deliver_alert, AlertNotification};
+use crate::storage_helpers::open_storage;
+
+const ALERT_EVAL_INTERVAL_ENV: &str = "CODEXMANAGER_ALERT_EVAL_INTERVAL_SECS";
+const DEFAULT_ALERT_EVAL_INTERVAL_SECS: i64 = 60;
+const MIN_ALERT_EVAL_INTERVAL_SECS: i64 = 10;
+pub(crate) const DELIVERY_STATUS_SENT: &str = "sent";
+pub(crate) const DELIVERY_STATUS_PARTIAL: &str = "partial";
+pub(crate) const DELIVERY_STATUS_FAILED: &str = "failed";
+pub(crate) const DELIVERY_STATUS_NO_CHANNEL: &str = "no_channel";
+
+static LAST_ALERT_EVALUATION_AT: AtomicI64 = AtomicI64::new(0);
+static ALERT_EVALUATION_WORKER: OnceLock<Option<AlertEvaluationWorker>> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertEvaluationReport {
+    pub(crate) evaluated_rules: usize,
+    pub(crate) fired: usize,
+    pub(crate) suppressed: usize,
+    pub(crate) errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AlertDeliveryResult {
+    pub(crate) channel_id: String,
+    pub(crate) ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+/// 由现有后台轮询循环（用量轮询、令牌刷新）顺带调用；按最小间隔节流，多个循环并发时只跑一次。
+/// 评估与投递交给独立的告警线程，渠道超时不会拖慢调用方的轮询。
+pub(crate) fn evaluate_alert_rules_if_due() {
+    let now = now_ts();
+    let last = LAST_ALERT_EVALUATION_AT.load(Ordering::Relaxed);
+    if now.saturating_sub(last) < alert_eval_interval_secs() {
+        return;
+    }
+    if LAST_ALERT_EVALUATION_AT
+        .compare_exchange(last, now, Ordering::AcqRel, Ordering::Relaxed)
+        .is_err()
+    {
+        return;
+    }
+    let Some(worker) = alert_evaluation_worker() else {
+        return;
+    };
+    if !worker.trigger(now) {
+        log::debug!("event=alert_evaluation_skipped reason=worker_busy");
+    }
+}
+
+/// 单线程告警执行器：最多排队一轮评估，上一轮仍在投递时新的触发直接丢弃，等下个周期再评估。
+struct AlertEvaluationWorker {
+    sender: Sender<i64>,
+}
+
+impl AlertEvaluationWorker {
+    fn spawn<F>(run: F) -> std::io::Result<Self>
+    where
+        F: Fn(i64) + Send + 'static,
+    {
+        let (sender, receiver) = bounded::<i64>(1);
+        thread::Builder::new()
+            .name("alert-evaluator".to_string())
+            .spawn(move || {
+                while let Ok(now) = receiver.recv() {
+                    run(now);
+                }
+            })?;
+        Ok(Self { sender })
+    }
+
+    fn trigger(&self, now: i64) -> bool {
+        self.sender.try_send(now).is_ok()
+    }
+}
+
+fn alert_evaluation_worker() -> Option<&'static AlertEvaluationWorker> {
+    ALERT_EVALUATION_WORKER
+        .get_or_init(start_alert_evaluation_worker)
+        .as_ref()
+}
+
+fn start_alert_evaluation_worker() -> Option<AlertEvaluationWorker> {
+    match AlertEvaluationWorker::spawn(run_alert_evaluation) {
+        Ok(worker) => Some(worker),
+        Err(err) => {
+            log::warn!("event=alert_worker_spawn_failed err={err}");
+            None
+        }
+    }
+}
+
+fn run_alert_evaluation(now: i64) {
+    let Some(storage) = open_storage() else {
+        return;
+    };
+    match evaluate_alert_rules(&storage, now) {
+        Ok(report) => {
+            if report.fired > 0 || !report.errors.is_empty() {
+                log::info!(
+                    "event=alert_evaluation_done rules={} fired={} suppressed={} errors={}",
+                    report.evaluated_rules,
+                    report.fired,
+                    report.suppressed,
+                    report.errors.len()
+                );
+            }
+        }
+        Err(err) => log::warn!("event=alert_evaluation_failed err={err}"),
+    }
+}
+
+pub(crate) fn evaluate_alert_rules(
+    storage: &Storage,
+    now: i64,
+) -> Result<AlertEvaluationReport, String> {
+    let rules = storage
+        .list_alert_rules()
+        .map_err(|err| format!("list alert rules failed: {err}"))?
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .collect::<Vec<_>>();
+    let mut report = AlertEvaluationReport::default();
+    if rules.is_empty() {
+        return Ok(report);
+    }
+    let channels = storage
+        .list_alert_channels()
+        .map_err(|err| format!("list alert channels failed: {err}"))?
+        .into_iter()
+        .map(|channel| (channel.id.clone(), channel))
+        .collect::<HashMap<_, _>>();
+
+    for rule in rules {
+        let Some(condition) = AlertCondition::parse(rule.condition.as_str()) else {
+            report.errors.push(format!(
+                "rule {}: unknown condition {}",
+                rule.id, rule.condition
+            ));
+            continue;
+        };
+        let firings = match collect_alert_firings(storage, condition, rule.threshold, now) {
+            Ok(firings) => firings,
+            Err(err) => {
+                report.errors.push(format!("rule {}: {err}", rule.id));
+                continue;
+            }
+        };
+        report.evaluated_rules += 1;
+        for firing in firings {
+            let last_fired_at = storage
+                .latest_alert_fired_at(rule.id.as_str(), firing.subject.as_str())
+                .map_err(|err| format!("read alert history failed: {err}"))?;
+            if last_fired_at.is_some_and(|fired_at| now - fired_at < rule.cooldown_secs) {
+                report.suppressed += 1;
+                continue;
+            }
+            dispatch_alert(storage, &rule, &firing, &channels, now)?;
+            report.fired += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// 投递到规则绑定的所有启用渠道，并把投递结果写入告警历史。
+fn dispatch_alert(
+    storage: &Storage,
+    rule: &AlertRule,
+    firing: &AlertFiring,
+    channels: &HashMap<String, AlertChannel>,
+    now: i64,
+) -> Result<AlertHistoryRecord, String> {
+    let notification = AlertNotification {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        condition: rule.condition.clone(),
+        severity: rule.severity.clone(),
+        subject: firing.subject.clone(),
+        message: firing.message.clone(),
+        fired_at: now,
+    };
+    let deliveries = rule_channel_ids(rule)
+        .into_iter()
+        .filter_map(|channel_id| channels.get(channel_id.as_str()))
+        .filter(|channel| channel.enabled)
+        .map(|channel| {
+            let result = deliver_alert(channel, &notification);
+            if let Err(err) = result.as_ref() {
+                log::warn!(
+                    "event=alert_delivery_failed rule_id={} channel_id={} err={err}",
+                    rule.id,
+                    channel.id
+                );
+            }
+            AlertDeliveryResult {
+                channel_id: channel.id.clone(),
+                ok: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut record = AlertHistoryRecord {
+        id: 0,
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        condition: rule.condition.clone(),
+        severity: rule.severity.clone(),
+        subject: firing.subject.clone(),
+        message: firing.message.clone(),
+        delivery_status: delivery_status(&deliveries).to_string(),
+        delivery_json: if deliveries.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&deliveries).ok()
+        },
+        created_at: now,
+    };
+    record.id = storage
+        .insert_alert_history(&record)
+        .map_err(|err| format!("insert alert history failed: {err}"))?;
+    Ok(record)
+}
+
+pub(crate) fn rule_channel_ids(rule: &AlertRule) -> Vec<String> {
+    rule.channel_ids_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok())
+        .unwrap_or_default()
+}
+
+fn delivery_status(deliveries: &[AlertDeliveryResult]) -> &'static str {
+    let sent = deliveries.iter().filter(|item| item.ok).count();
+    if deliveries.is_empty() {
+        DELIVERY_STATUS_NO_CHANNEL
+    } else if sent == deliveries.len() {
+        DELIVERY_STATUS_SENT
+    } else if sent > 0 {
+        DELIVERY_STATUS_PARTIAL
+    } else {
+        DELIVERY_STATUS_FAILED
+    }
+}
+
+fn alert_eval_interval_secs() -> i64 {
+    std::env::var(ALERT_EVAL_INTERVAL_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(DEFAULT_ALERT_EVAL_INTERVAL_SECS)
+        .max(MIN_ALERT_EVAL_INTERVAL_SECS)
+}
+
+#[cfg(test)]
+#[path = "evaluator_tests.rs"]
+mod tests;
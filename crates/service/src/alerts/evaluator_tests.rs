@@ -0,0 +1,168 @@
+use super::*;
+use codexmanager_core::storage::Account;
+use std::time::Duration;
+use tiny_http::{Response, Server};
+
+fn seed_banned_account(storage: &Storage, id: &str) {
+    storage
+        .insert_account(&Account {
+            id: id.to_string(),
+            label: format!("label-{id}"),
+            issuer: "https://auth.openai.com".to_string(),
+            chatgpt_account_id: None,
+            workspace_id: None,
+            group_name: None,
+            sort: 0,
+            status: "banned".to_string(),
+            created_at: 1,
+            updated_at: 1,
+        })
+        .expect("insert account");
+}
+
+fn seed_channel(storage: &Storage, id: &str, url: &str) {
+    storage
+        .upsert_alert_channel(&AlertChannel {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind: "webhook".to_string(),
+            config_json: serde_json::json!({ "url": url }).to_string(),
+            enabled: true,
+            created_at: 1,
+            updated_at: 1,
+        })
+        .expect("insert channel");
+}
+
+#[test]
+fn evaluate_alert_rules_delivers_records_history_and_honors_cooldown() {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    seed_banned_account(&storage, "acc-1");
+
+    let server = Server::http("127.0.0.1:0").expect("start mock webhook");
+    let webhook_url = format!("http://{}/hook", server.server_addr());
+    let join = std::thread::spawn(move || {
+        let mut request = server
+            .recv_timeout(Duration::from_secs(5))
+            .expect("receive webhook request")
+            .expect("webhook request present");
+        let mut body = String::new();
+        request
+            .as_reader()
+            .read_to_string(&mut body)
+            .expect("read webhook body");
+        request
+            .respond(Response::from_string("ok"))
+            .expect("respond webhook");
+        body
+    });
+    seed_channel(&storage, "ok", webhook_url.as_str());
+    seed_channel(&storage, "down", "http://127.0.0.1:1/hook");
+    storage
+        .upsert_alert_rule(&AlertRule {
+            id: "ar_banned".to_string(),
+            name: "banned accounts".to_string(),
+            condition: "account_banned".to_string(),
+            severity: "critical".to_string(),
+            threshold: None,
+            cooldown_secs: 600,
+            channel_ids_json: Some(r#"["ok","down","missing"]"#.to_string()),
+            enabled: true,
+            created_at: 1,
+            updated_at: 1,
+        })
+        .expect("insert rule");
+
+    let report = evaluate_alert_rules(&storage, 1_000).expect("first evaluation");
+    assert_eq!(report.evaluated_rules, 1);
+    assert_eq!(report.fired, 1);
+    assert_eq!(report.suppressed, 0);
+
+    let body = join.join().expect("join webhook server");
+    let payload: serde_json::Value = serde_json::from_str(&body).expect("webhook json");
+    assert_eq!(payload["event"], "codexmanager.alert");
+    assert_eq!(payload["ruleId"], "ar_banned");
+    assert_eq!(payload["subject"], "account:acc-1");
+    assert_eq!(payload["severity"], "critical");
+
+    let history = storage
+        .list_alert_history(Some("ar_banned"), 10)
+        .expect("list history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].delivery_status, DELIVERY_STATUS_PARTIAL);
+    let deliveries: serde_json::Value =
+        serde_json::from_str(history[0].delivery_json.as_deref().expect("delivery json"))
+            .expect("parse deliveries");
+    assert_eq!(deliveries.as_array().map(Vec::len), Some(2));
+    assert_eq!(deliveries[0]["channelId"], "ok");
+    assert_eq!(deliveries[0]["ok"], true);
+    assert_eq!(deliveries[1]["ok"], false);
+
+    let report = evaluate_alert_rules(&storage, 1_300).expect("evaluation in cooldown");
+    assert_eq!(report.fired, 0);
+    assert_eq!(report.suppressed, 1);
+
+    let mut rule = storage
+        .find_alert_rule("ar_banned")
+        .expect("find rule")
+        .expect("rule exists");
+    rule.channel_ids_json = None;
+    storage.upsert_alert_rule(&rule).expect("update rule");
+    let report = evaluate_alert_rules(&storage, 1_600).expect("evaluation after cooldown");
+    assert_eq!(report.fired, 1);
+    let history = storage
+        .list_alert_history(Some("ar_banned"), 10)
+        .expect("list history");
+    assert_eq!(history[0].delivery_status, DELIVERY_STATUS_NO_CHANNEL);
+}
+
+#[test]
+fn delivery_status_summarizes_channel_results() {
+    let result = |ok| AlertDeliveryResult {
+        channel_id: "c".to_string(),
+        ok,
+        error: None,
+    };
+    assert_eq!(delivery_status(&[]), DELIVERY_STATUS_NO_CHANNEL);
+    assert_eq!(
+        delivery_status(&[result(true), result(true)]),
+        DELIVERY_STATUS_SENT
+    );
+    assert_eq!(
+        delivery_status(&[result(true), result(false)]),
+        DELIVERY_STATUS_PARTIAL
+    );
+    assert_eq!(delivery_status(&[result(false)]), DELIVERY_STATUS_FAILED);
+}
+
+#[test]
+fn alert_worker_queues_one_evaluation_and_drops_triggers_while_busy() {
+    let (started_tx, started_rx) = crossbeam_channel::unbounded::<i64>();
+    let (release_tx, release_rx) = crossbeam_channel::unbounded::<()>();
+    let worker = AlertEvaluationWorker::spawn(move |now| {
+        started_tx.send(now).expect("report start");
+        release_rx.recv().expect("wait for release");
+    })
+    .expect("spawn alert worker");
+
+    assert!(worker.trigger(1));
+    assert_eq!(
+        started_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("first run"),
+        1
+    );
+    // 中文注释：第一轮阻塞期间只能再排队一轮，其余触发立即返回而不是等待投递。
+    assert!(worker.trigger(2));
+    assert!(!worker.trigger(3));
+
+    release_tx.send(()).expect("release first run");
+    assert_eq!(
+        started_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("queued run"),
+        2
+    );
+    release_tx.send(()).expect("release queued run");
+}
@@ -0,0 +1,344 @@
+use codexmanager_core::rpc::types::{
+    AlertChannelEntry, AlertChannelSetParams, AlertHistoryEntry, AlertRuleEntry, AlertRuleSetParams,
+};
+use codexmanager_core::storage::{now_ts, AlertChannel, AlertHistoryRecord, AlertRule};
+use serde_json::{json, Value};
+
+use crate::storage_helpers::{generate_alert_channel_id, generate_alert_rule_id, open_storage};
+
+pub(crate) mod conditions;
+mod delivery;
+mod evaluator;
+
+use conditions::AlertCondition;
+use delivery::{
+    deliver_alert, normalize_channel_kind, validate_channel_config, AlertNotification,
+    CHANNEL_KIND_SMTP,
+};
+pub(crate) use evaluator::evaluate_alert_rules_if_due;
+use evaluator::{evaluate_alert_rules, rule_channel_ids, AlertEvaluationReport};
+
+const SEVERITIES: [&str; 3] = ["info", "warning", "critical"];
+const DEFAULT_SEVERITY: &str = "warning";
+const DEFAULT_COOLDOWN_SECS: i64 = 3600;
+const DEFAULT_HISTORY_LIMIT: i64 = 100;
+const MAX_HISTORY_LIMIT: i64 = 1000;
+const MASKED_SECRET: &str = "******";
+
+pub(crate) fn list_alert_conditions() -> Vec<Value> {
+    AlertCondition::ALL
+        .into_iter()
+        .map(|condition| {
+            json!({
+                "condition": condition.as_str(),
+                "defaultThreshold": condition.default_threshold(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn list_alert_rules() -> Result<Vec<AlertRuleEntry>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_alert_rules()
+        .map(|items| items.into_iter().map(rule_entry).collect())
+        .map_err(|err| format!("list alert rules failed: {err}"))
+}
+
+/// 未传 id 时新建规则；阈值为空表示使用条件默认阈值。
+pub(crate) fn set_alert_rule(params: AlertRuleSetParams) -> Result<AlertRuleEntry, String> {
+    let name = params.name.trim().to_string();
+    if name.is_empty() {
+        return Err("missing name".to_string());
+    }
+    let condition = AlertCondition::parse(params.condition.as_str())
+        .ok_or_else(|| format!("invalid condition: {}", params.condition.trim()))?;
+    let severity = match params
+        .severity
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+    {
+        Some(value) if SEVERITIES.contains(&value.as_str()) => value,
+        Some(value) => {
+            return Err(format!(
+                "invalid severity: {value}; use info, warning or critical"
+            ))
+        }
+        None => DEFAULT_SEVERITY.to_string(),
+    };
+    if params
+        .threshold
+        .is_some_and(|value| !value.is_finite() || value < 0.0)
+    {
+        return Err("threshold must be a finite number >= 0".to_string());
+    }
+    let cooldown_secs = params.cooldown_secs.unwrap_or(DEFAULT_COOLDOWN_SECS);
+    if cooldown_secs < 0 {
+        return Err("cooldownSecs must be >= 0".to_string());
+    }
+    let mut channel_ids: Vec<String> = Vec::new();
+    for channel_id in params.channel_ids {
+        let channel_id = channel_id.trim();
+        if !channel_id.is_empty() && !channel_ids.iter().any(|item| item == channel_id) {
+            channel_ids.push(channel_id.to_string());
+        }
+    }
+
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    for channel_id in &channel_ids {
+        let exists = storage
+            .find_alert_channel(channel_id)
+            .map_err(|err| format!("read alert channel failed: {err}"))?
+            .is_some();
+        if !exists {
+            return Err(format!("alert channel not found: {channel_id}"));
+        }
+    }
+    let id = params
+        .id
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(generate_alert_rule_id);
+    let existing = storage
+        .find_alert_rule(id.as_str())
+        .map_err(|err| format!("read alert rule failed: {err}"))?;
+    let now = now_ts();
+    let rule = AlertRule {
+        id,
+        name,
+        condition: condition.as_str().to_string(),
+        severity,
+        threshold: params.threshold,
+        cooldown_secs,
+        channel_ids_json: if channel_ids.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&channel_ids)
+                .map(Some)
+                .map_err(|err| format!("serialize alert rule failed: {err}"))?
+        },
+        enabled: params.enabled.unwrap_or(true),
+        created_at: existing.map(|item| item.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    storage
+        .upsert_alert_rule(&rule)
+        .map_err(|err| format!("save alert rule failed: {err}"))?;
+    Ok(rule_entry(rule))
+}
+
+pub(crate) fn delete_alert_rule(id: &str) -> Result<(), String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let deleted = storage
+        .delete_alert_rule(id)
+        .map_err(|err| format!("delete alert rule failed: {err}"))?;
+    if !deleted {
+        return Err(format!("alert rule not found: {id}"));
+    }
+    Ok(())
+}
+
+pub(crate) fn list_alert_channels() -> Result<Vec<AlertChannelEntry>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_alert_channels()
+        .map(|items| items.into_iter().map(channel_entry).collect())
+        .map_err(|err| format!("list alert channels failed: {err}"))
+}
+
+/// SMTP 密码在列表中脱敏；更新时未传或传回脱敏值则沿用已保存的密码。
+pub(crate) fn set_alert_channel(
+    params: AlertChannelSetParams,
+) -> Result<AlertChannelEntry, String> {
+    let name = params.name.trim().to_string();
+    if name.is_empty() {
+        return Err("missing name".to_string());
+    }
+    let kind = normalize_channel_kind(params.kind.as_str()).ok_or_else(|| {
+        format!(
+            "invalid kind: {}; use webhook, slack or smtp",
+            params.kind.trim()
+        )
+    })?;
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let id = params
+        .id
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(generate_alert_channel_id);
+    let existing = storage
+        .find_alert_channel(id.as_str())
+        .map_err(|err| format!("read alert channel failed: {err}"))?;
+    let mut config = params.config;
+    if !config.is_object() {
+        return Err("config must be an object".to_string());
+    }
+    if kind == CHANNEL_KIND_SMTP {
+        restore_smtp_password(&mut config, existing.as_ref());
+    }
+    validate_channel_config(kind, &config)?;
+    let now = now_ts();
+    let channel = AlertChannel {
+        id,
+        name,
+        kind: kind.to_string(),
+        config_json: serde_json::to_string(&config)
+            .map_err(|err| format!("serialize alert channel failed: {err}"))?,
+        enabled: params.enabled.unwrap_or(true),
+        created_at: existing.map(|item| item.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    storage
+        .upsert_alert_channel(&channel)
+        .map_err(|err| format!("save alert channel failed: {err}"))?;
+    Ok(channel_entry(channel))
+}
+
+pub(crate) fn delete_alert_channel(id: &str) -> Result<(), String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let deleted = storage
+        .delete_alert_channel(id)
+        .map_err(|err| format!("delete alert channel failed: {err}"))?;
+    if !deleted {
+        return Err(format!("alert channel not found: {id}"));
+    }
+    Ok(())
+}
+
+/// 向指定渠道发送一条测试消息，不写入告警历史。
+pub(crate) fn test_alert_channel(id: &str) -> Result<(), String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let channel = storage
+        .find_alert_channel(id)
+        .map_err(|err| format!("read alert channel failed: {err}"))?
+        .ok_or_else(|| format!("alert channel not found: {id}"))?;
+    let notification = AlertNotification {
+        rule_id: "test".to_string(),
+        rule_name: "CodexManager alert test".to_string(),
+        condition: "test".to_string(),
+        severity: "info".to_string(),
+        subject: format!("channel:{}", channel.id),
+        message: crate::gateway::bilingual_error(
+            format!("这是来自渠道 {} 的测试告警", channel.name),
+            format!("test alert from channel {}", channel.name),
+        ),
+        fired_at: now_ts(),
+    };
+    deliver_alert(&channel, &notification)
+}
+
+pub(crate) fn list_alert_history(
+    rule_id: Option<&str>,
+    limit: Option<i64>,
+) -> Result<Vec<AlertHistoryEntry>, String> {
+    let rule_id = rule_id.map(str::trim).filter(|value| !value.is_empty());
+    let limit = limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    storage
+        .list_alert_history(rule_id, limit)
+        .map(|items| items.into_iter().map(history_entry).collect())
+        .map_err(|err| format!("list alert history failed: {err}"))
+}
+
+/// 立即评估全部启用规则（仍遵守冷却），便于配置后手动验证。
+pub(crate) fn evaluate_alerts_now() -> Result<AlertEvaluationReport, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    evaluate_alert_rules(&storage, now_ts())
+}
+
+fn restore_smtp_password(config: &mut Value, existing: Option<&AlertChannel>) {
+    let keeps_existing = match config.get("password") {
+        None | Some(Value::Null) => true,
+        Some(Value::String(value)) => value == MASKED_SECRET,
+        Some(_) => false,
+    };
+    if !keeps_existing {
+        return;
+    }
+    let previous = existing
+        .filter(|channel| channel.kind == CHANNEL_KIND_SMTP)
+        .and_then(|channel| serde_json::from_str::<Value>(channel.config_json.as_str()).ok())
+        .and_then(|value| value.get("password").cloned());
+    if let Some(object) = config.as_object_mut() {
+        match previous {
+            Some(password) => {
+                object.insert("password".to_string(), password);
+            }
+            None => {
+                object.remove("password");
+            }
+        }
+    }
+}
+
+fn channel_entry(channel: AlertChannel) -> AlertChannelEntry {
+    let mut config =
+        serde_json::from_str::<Value>(channel.config_json.as_str()).unwrap_or(Value::Null);
+    if let Some(password) = config.get_mut("password") {
+        if password.as_str().is_some_and(|value| !value.is_empty()) {
+            *password = Value::String(MASKED_SECRET.to_string());
+        }
+    }
+    AlertChannelEntry {
+        id: channel.id,
+        name: channel.name,
+        kind: channel.kind,
+        config,
+        enabled: channel.enabled,
+        created_at: channel.created_at,
+        updated_at: channel.updated_at,
+    }
+}
+
+fn rule_entry(rule: AlertRule) -> AlertRuleEntry {
+    AlertRuleEntry {
+        channel_ids: rule_channel_ids(&rule),
+        id: rule.id,
+        name: rule.name,
+        condition: rule.condition,
+        severity: rule.severity,
+        threshold: rule.threshold,
+        cooldown_secs: rule.cooldown_secs,
+        enabled: rule.enabled,
+        created_at: rule.created_at,
+        updated_at: rule.updated_at,
+    }
+}
+
+fn history_entry(record: AlertHistoryRecord) -> AlertHistoryEntry {
+    AlertHistoryEntry {
+        deliveries: record
+            .delivery_json
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+            .unwrap_or_else(|| Value::Array(Vec::new())),
+        id: record.id,
+        rule_id: record.rule_id,
+        rule_name: record.rule_name,
+        condition: record.condition,
+        severity: record.severity,
+        subject: record.subject,
+        message: record.message,
+        delivery_status: record.delivery_status,
+        created_at: record.created_at,
+    }
+}
+
+#[cfg(test)]
+#[path = "alerts_tests.rs"]
+mod tests;
@@ -0,0 +1,74 @@
+use codexmanager_core::rpc::types::{ApiKeyBatchEntry, ApiKeyBatchListResult};
+use codexmanager_core::storage::{GatewayBatch, Storage};
+
+use crate::storage_helpers::open_storage;
+
+const DEFAULT_BATCH_LIST_LIMIT: i64 = 50;
+const MAX_BATCH_LIST_LIMIT: i64 = 200;
+
+fn normalize_key_id(key_id: &str) -> Result<&str, String> {
+    let normalized = key_id.trim();
+    if normalized.is_empty() {
+        return Err("missing key id".to_string());
+    }
+    Ok(normalized)
+}
+
+fn batch_entry(storage: &Storage, batch: GatewayBatch) -> Result<ApiKeyBatchEntry, String> {
+    let counts = storage
+        .gateway_batch_item_counts(batch.batch_id.as_str())
+        .map_err(|err| format!("read batch progress failed: {err}"))?;
+    let running = crate::gateway::is_batch_running(batch.batch_id.as_str());
+    Ok(ApiKeyBatchEntry {
+        finished_at: batch
+            .completed_at
+            .or(batch.failed_at)
+            .or(batch.expired_at)
+            .or(batch.cancelled_at),
+        batch_id: batch.batch_id,
+        key_id: batch.key_id,
+        endpoint: batch.endpoint,
+        status: batch.status,
+        input_file_id: batch.input_file_id,
+        output_file_id: batch.output_file_id,
+        error_file_id: batch.error_file_id,
+        total: counts.total,
+        completed: counts.completed,
+        failed: counts.failed,
+        running,
+        created_at: batch.created_at,
+        in_progress_at: batch.in_progress_at,
+        expires_at: batch.expires_at,
+        updated_at: batch.updated_at,
+    })
+}
+
+/// 列出平台 Key 最近的批任务（新到旧）及进度。
+pub(crate) fn list_batches(
+    key_id: &str,
+    limit: Option<i64>,
+) -> Result<ApiKeyBatchListResult, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_BATCH_LIST_LIMIT)
+        .clamp(1, MAX_BATCH_LIST_LIMIT) as usize;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    let items = storage
+        .list_gateway_batches(key_id, None, limit)
+        .map_err(|err| format!("list batches failed: {err}"))?
+        .into_iter()
+        .map(|batch| batch_entry(&storage, batch))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ApiKeyBatchListResult { items })
+}
+
+/// 读取单个批任务的状态与进度。
+pub(crate) fn get_batch(key_id: &str, batch_id: &str) -> Result<ApiKeyBatchEntry, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    let batch = storage
+        .find_gateway_batch(key_id, batch_id.trim())
+        .map_err(|err| format!("read batch failed: {err}"))?
+        .ok_or_else(|| format!("batch not found: {batch_id}"))?;
+    batch_entry(&storage, batch)
+}
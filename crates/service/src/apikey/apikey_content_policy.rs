@@ -0,0 +1,202 @@
+use codexmanager_core::rpc::types::{
+    ApiKeyContentPolicyEntry, ApiKeyContentPolicySetParams, ContentPolicyCustomRule,
+};
+use codexmanager_core::storage::{now_ts, ApiKeyContentPolicy};
+use std::collections::BTreeMap;
+
+use crate::storage_helpers::open_storage;
+
+pub(crate) const ACTION_BLOCK: &str = "block";
+pub(crate) const ACTION_REDACT: &str = "redact";
+pub(crate) const ACTION_LOG: &str = "log";
+/// 仅用于 `sourceActions`：对该来源关闭策略。
+pub(crate) const ACTION_OFF: &str = "off";
+pub(crate) const DEFAULT_CONTENT_POLICY_ACTION: &str = ACTION_REDACT;
+
+pub(crate) const SOURCE_KIND_OPENAI_ACCOUNT: &str = "openai_account";
+pub(crate) const SOURCE_KIND_AGGREGATE_API: &str = "aggregate_api";
+const SOURCE_KINDS: &[&str] = &[SOURCE_KIND_OPENAI_ACCOUNT, SOURCE_KIND_AGGREGATE_API];
+
+pub(crate) const BUILTIN_DETECTORS: &[&str] =
+    &["api_key", "private_key", "jwt", "email", "card_number"];
+
+const MAX_CUSTOM_RULES: usize = 32;
+const MAX_RULE_NAME_CHARS: usize = 64;
+const MAX_RULE_PATTERN_CHARS: usize = 1024;
+pub(crate) const CUSTOM_RULE_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+fn normalize_key_id(key_id: &str) -> Result<&str, String> {
+    let normalized = key_id.trim();
+    if normalized.is_empty() {
+        return Err("missing key id".to_string());
+    }
+    Ok(normalized)
+}
+
+fn normalize_action(value: &str, allow_off: bool) -> Result<String, String> {
+    let normalized = value.trim().to_ascii_lowercase();
+    match normalized.as_str() {
+        ACTION_BLOCK | ACTION_REDACT | ACTION_LOG => Ok(normalized),
+        ACTION_OFF if allow_off => Ok(normalized),
+        _ => Err(format!(
+            "invalid content policy action: {value} (expected block, redact or log{})",
+            if allow_off { " or off" } else { "" }
+        )),
+    }
+}
+
+fn normalize_source_actions(
+    source_actions: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    source_actions
+        .into_iter()
+        .map(|(source_kind, action)| {
+            let source_kind = source_kind.trim().to_ascii_lowercase();
+            if !SOURCE_KINDS.contains(&source_kind.as_str()) {
+                return Err(format!(
+                    "invalid source kind: {source_kind} (expected {})",
+                    SOURCE_KINDS.join(", ")
+                ));
+            }
+            Ok((source_kind, normalize_action(action.as_str(), true)?))
+        })
+        .collect()
+}
+
+fn normalize_detectors(detectors: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized = Vec::new();
+    for detector in detectors {
+        let detector = detector.trim().to_ascii_lowercase();
+        if !BUILTIN_DETECTORS.contains(&detector.as_str()) {
+            return Err(format!(
+                "invalid detector: {detector} (expected {})",
+                BUILTIN_DETECTORS.join(", ")
+            ));
+        }
+        if !normalized.contains(&detector) {
+            normalized.push(detector);
+        }
+    }
+    Ok(normalized)
+}
+
+fn normalize_custom_rules(
+    rules: Vec<ContentPolicyCustomRule>,
+) -> Result<Vec<ContentPolicyCustomRule>, String> {
+    if rules.len() > MAX_CUSTOM_RULES {
+        return Err(format!(
+            "too many custom rules: {} (max {MAX_CUSTOM_RULES})",
+            rules.len()
+        ));
+    }
+    let mut normalized: Vec<ContentPolicyCustomRule> = Vec::new();
+    for rule in rules {
+        let name = rule.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_RULE_NAME_CHARS {
+            return Err(format!(
+                "custom rule name must be 1..={MAX_RULE_NAME_CHARS} characters"
+            ));
+        }
+        if BUILTIN_DETECTORS.contains(&name.as_str())
+            || normalized.iter().any(|existing| existing.name == name)
+        {
+            return Err(format!("duplicate custom rule name: {name}"));
+        }
+        if rule.pattern.is_empty() || rule.pattern.chars().count() > MAX_RULE_PATTERN_CHARS {
+            return Err(format!(
+                "custom rule {name}: pattern must be 1..={MAX_RULE_PATTERN_CHARS} characters"
+            ));
+        }
+        regex::RegexBuilder::new(rule.pattern.as_str())
+            .size_limit(CUSTOM_RULE_REGEX_SIZE_LIMIT)
+            .build()
+            .map_err(|err| format!("custom rule {name}: invalid pattern: {err}"))?;
+        let action = rule
+            .action
+            .as_deref()
+            .map(|action| normalize_action(action, false))
+            .transpose()?;
+        normalized.push(ContentPolicyCustomRule {
+            name,
+            pattern: rule.pattern,
+            action,
+        });
+    }
+    Ok(normalized)
+}
+
+/// 把存储中的策略还原为 RPC 结构；未配置时返回默认关闭状态。
+pub(crate) fn content_policy_entry(
+    key_id: &str,
+    policy: Option<&ApiKeyContentPolicy>,
+) -> ApiKeyContentPolicyEntry {
+    let Some(policy) = policy else {
+        return ApiKeyContentPolicyEntry {
+            key_id: key_id.to_string(),
+            action: DEFAULT_CONTENT_POLICY_ACTION.to_string(),
+            ..Default::default()
+        };
+    };
+    ApiKeyContentPolicyEntry {
+        key_id: key_id.to_string(),
+        enabled: policy.enabled,
+        action: policy.action.clone(),
+        source_actions: serde_json::from_str(policy.source_actions_json.as_str())
+            .unwrap_or_default(),
+        detectors: serde_json::from_str(policy.detectors_json.as_str()).unwrap_or_default(),
+        custom_rules: serde_json::from_str(policy.custom_rules_json.as_str()).unwrap_or_default(),
+        updated_at: Some(policy.updated_at),
+    }
+}
+
+/// 读取平台 Key 的出站内容策略。
+pub(crate) fn get_content_policy(key_id: &str) -> Result<ApiKeyContentPolicyEntry, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    let policy = storage
+        .find_api_key_content_policy(key_id)
+        .map_err(|err| format!("read content policy failed: {err}"))?;
+    Ok(content_policy_entry(key_id, policy.as_ref()))
+}
+
+/// 写入平台 Key 的出站内容策略；省略的字段恢复默认值（`redact`、全部内置检测器、无自定义规则）。
+pub(crate) fn set_content_policy(
+    params: ApiKeyContentPolicySetParams,
+) -> Result<ApiKeyContentPolicyEntry, String> {
+    let key_id = normalize_key_id(params.id.as_str())?.to_string();
+    let action = normalize_action(
+        params
+            .action
+            .as_deref()
+            .unwrap_or(DEFAULT_CONTENT_POLICY_ACTION),
+        false,
+    )?;
+    let source_actions = normalize_source_actions(params.source_actions.unwrap_or_default())?;
+    let detectors = normalize_detectors(params.detectors.unwrap_or_default())?;
+    let custom_rules = normalize_custom_rules(params.custom_rules.unwrap_or_default())?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    if storage
+        .find_api_key_by_id(key_id.as_str())
+        .map_err(|err| format!("read api key failed: {err}"))?
+        .is_none()
+    {
+        return Err("api key not found".to_string());
+    }
+    let serialize = |value: serde_json::Result<String>| {
+        value.map_err(|err| format!("serialize content policy failed: {err}"))
+    };
+    let now = now_ts();
+    storage
+        .upsert_api_key_content_policy(&ApiKeyContentPolicy {
+            key_id: key_id.clone(),
+            enabled: params.enabled,
+            action,
+            source_actions_json: serialize(serde_json::to_string(&source_actions))?,
+            detectors_json: serialize(serde_json::to_string(&detectors))?,
+            custom_rules_json: serialize(serde_json::to_string(&custom_rules))?,
+            created_at: now,
+            updated_at: now,
+        })
+        .map_err(|err| format!("save content policy failed: {err}"))?;
+    get_content_policy(key_id.as_str())
+}
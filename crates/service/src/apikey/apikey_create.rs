@@ -48,7 +48,10 @@ fn ensure_platform_key_not_exists(storage: &Storage, key: &str) -> Result<(), St
     Ok(())
 }
 
-fn resolve_platform_key(storage: &Storage, custom_key: Option<String>) -> Result<String, String> {
+pub(super) fn resolve_platform_key(
+    storage: &Storage,
+    custom_key: Option<String>,
+) -> Result<String, String> {
     if let Some(key) = normalize_custom_key(custom_key)? {
         ensure_platform_key_not_exists(storage, &key)?;
         return Ok(key);
@@ -0,0 +1,91 @@
+use codexmanager_core::rpc::types::{ApiKeyResponseCacheEntry, ApiKeyResponseCacheSetParams};
+use codexmanager_core::storage::{now_ts, ApiKeyResponseCachePolicy};
+
+use crate::storage_helpers::open_storage;
+
+pub(crate) const DEFAULT_RESPONSE_CACHE_TTL_SECS: i64 = 3600;
+const MAX_RESPONSE_CACHE_TTL_SECS: i64 = 30 * 24 * 3600;
+
+fn normalize_key_id(key_id: &str) -> Result<&str, String> {
+    let normalized = key_id.trim();
+    if normalized.is_empty() {
+        return Err("missing key id".to_string());
+    }
+    Ok(normalized)
+}
+
+fn normalize_ttl_secs(ttl_secs: Option<i64>) -> Result<i64, String> {
+    match ttl_secs {
+        None => Ok(DEFAULT_RESPONSE_CACHE_TTL_SECS),
+        Some(value) if (1..=MAX_RESPONSE_CACHE_TTL_SECS).contains(&value) => Ok(value),
+        Some(value) => Err(format!(
+            "invalid ttlSecs: {value} (expected 1..={MAX_RESPONSE_CACHE_TTL_SECS})"
+        )),
+    }
+}
+
+/// 读取平台 Key 的响应缓存策略与占用统计；未配置时返回默认关闭状态。
+pub(crate) fn get_response_cache(key_id: &str) -> Result<ApiKeyResponseCacheEntry, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    let policy = storage
+        .find_api_key_response_cache_policy(key_id)
+        .map_err(|err| format!("read response cache policy failed: {err}"))?;
+    let stats = storage
+        .response_cache_stats(Some(key_id))
+        .map_err(|err| format!("read response cache stats failed: {err}"))?;
+    Ok(ApiKeyResponseCacheEntry {
+        key_id: key_id.to_string(),
+        enabled: policy.as_ref().is_some_and(|policy| policy.enabled),
+        ttl_secs: policy
+            .as_ref()
+            .map(|policy| policy.ttl_secs)
+            .unwrap_or(DEFAULT_RESPONSE_CACHE_TTL_SECS),
+        entries: stats.entries,
+        body_bytes: stats.body_bytes,
+        hit_count: stats.hit_count,
+        updated_at: policy.map(|policy| policy.updated_at),
+    })
+}
+
+/// 写入平台 Key 的响应缓存策略；关闭时同时清空该 Key 的已缓存响应。
+pub(crate) fn set_response_cache(
+    params: ApiKeyResponseCacheSetParams,
+) -> Result<ApiKeyResponseCacheEntry, String> {
+    let key_id = normalize_key_id(params.id.as_str())?.to_string();
+    let ttl_secs = normalize_ttl_secs(params.ttl_secs)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    if storage
+        .find_api_key_by_id(key_id.as_str())
+        .map_err(|err| format!("read api key failed: {err}"))?
+        .is_none()
+    {
+        return Err("api key not found".to_string());
+    }
+    let now = now_ts();
+    storage
+        .upsert_api_key_response_cache_policy(&ApiKeyResponseCachePolicy {
+            key_id: key_id.clone(),
+            enabled: params.enabled,
+            ttl_secs,
+            created_at: now,
+            updated_at: now,
+        })
+        .map_err(|err| format!("save response cache policy failed: {err}"))?;
+    if !params.enabled {
+        storage
+            .delete_response_cache_entries_for_key(key_id.as_str())
+            .map_err(|err| format!("clear response cache failed: {err}"))?;
+    }
+    get_response_cache(key_id.as_str())
+}
+
+/// 清空平台 Key 的已缓存响应，保留策略不变。
+pub(crate) fn clear_response_cache(key_id: &str) -> Result<ApiKeyResponseCacheEntry, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    storage
+        .delete_response_cache_entries_for_key(key_id)
+        .map_err(|err| format!("clear response cache failed: {err}"))?;
+    get_response_cache(key_id)
+}
@@ -0,0 +1,36 @@
+use codexmanager_core::rpc::types::ApiKeySecretResult;
+
+use crate::apikey::create::resolve_platform_key;
+use crate::storage_helpers::{hash_platform_key, open_storage};
+
+/// 函数 `rotate_api_key`
+///
+/// 为已有平台 Key 生成新密钥（或使用自定义密钥），旧密钥立即失效，id 与路由配置保持不变。
+pub(crate) fn rotate_api_key(
+    key_id: &str,
+    custom_key: Option<String>,
+) -> Result<ApiKeySecretResult, String> {
+    let normalized = key_id.trim();
+    if normalized.is_empty() {
+        return Err("missing key id".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    if !storage
+        .api_key_exists(normalized)
+        .map_err(|err| format!("read api key failed: {err}"))?
+    {
+        return Err("api key not found".to_string());
+    }
+    let key = resolve_platform_key(&storage, custom_key)?;
+    // 中文注释：哈希与明文同事务写入，避免出现已生效却无法回显的新密钥。
+    if !storage
+        .rotate_api_key_secret(normalized, &hash_platform_key(&key), &key)
+        .map_err(|err| format!("rotate api key failed: {err}"))?
+    {
+        return Err("api key not found".to_string());
+    }
+    Ok(ApiKeySecretResult {
+        id: normalized.to_string(),
+        key,
+    })
+}
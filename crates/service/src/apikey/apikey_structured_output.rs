@@ -0,0 +1,70 @@
+use codexmanager_core::rpc::types::{ApiKeyStructuredOutputEntry, ApiKeyStructuredOutputSetParams};
+use codexmanager_core::storage::{now_ts, ApiKeyStructuredOutputPolicy};
+
+use crate::storage_helpers::open_storage;
+
+pub(crate) const DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS: i64 = 1;
+pub(crate) const MAX_STRUCTURED_OUTPUT_MAX_REPAIRS: i64 = 3;
+
+fn normalize_key_id(key_id: &str) -> Result<&str, String> {
+    let normalized = key_id.trim();
+    if normalized.is_empty() {
+        return Err("missing key id".to_string());
+    }
+    Ok(normalized)
+}
+
+fn normalize_max_repairs(max_repairs: Option<i64>) -> Result<i64, String> {
+    match max_repairs {
+        None => Ok(DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS),
+        Some(value) if (0..=MAX_STRUCTURED_OUTPUT_MAX_REPAIRS).contains(&value) => Ok(value),
+        Some(value) => Err(format!(
+            "invalid maxRepairs: {value} (expected 0..={MAX_STRUCTURED_OUTPUT_MAX_REPAIRS})"
+        )),
+    }
+}
+
+/// 读取平台 Key 的结构化输出校验策略；未配置时返回默认关闭状态。
+pub(crate) fn get_structured_output(key_id: &str) -> Result<ApiKeyStructuredOutputEntry, String> {
+    let key_id = normalize_key_id(key_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    let policy = storage
+        .find_api_key_structured_output_policy(key_id)
+        .map_err(|err| format!("read structured output policy failed: {err}"))?;
+    Ok(ApiKeyStructuredOutputEntry {
+        key_id: key_id.to_string(),
+        enabled: policy.as_ref().is_some_and(|policy| policy.enabled),
+        max_repairs: policy
+            .as_ref()
+            .map(|policy| policy.max_repairs)
+            .unwrap_or(DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS),
+        updated_at: policy.map(|policy| policy.updated_at),
+    })
+}
+
+/// 写入平台 Key 的结构化输出校验策略；`maxRepairs` 为 `0` 时只校验并记录日志，不做修复重试。
+pub(crate) fn set_structured_output(
+    params: ApiKeyStructuredOutputSetParams,
+) -> Result<ApiKeyStructuredOutputEntry, String> {
+    let key_id = normalize_key_id(params.id.as_str())?.to_string();
+    let max_repairs = normalize_max_repairs(params.max_repairs)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    if storage
+        .find_api_key_by_id(key_id.as_str())
+        .map_err(|err| format!("read api key failed: {err}"))?
+        .is_none()
+    {
+        return Err("api key not found".to_string());
+    }
+    let now = now_ts();
+    storage
+        .upsert_api_key_structured_output_policy(&ApiKeyStructuredOutputPolicy {
+            key_id: key_id.clone(),
+            enabled: params.enabled,
+            max_repairs,
+            created_at: now,
+            updated_at: now,
+        })
+        .map_err(|err| format!("save structured output policy failed: {err}"))?;
+    get_structured_output(key_id.as_str())
+}
@@ -1,3 +1,7 @@
+#[path = "apikey_batches.rs"]
+pub(crate) mod batches;
+#[path = "apikey_content_policy.rs"]
+pub(crate) mod content_policy;
 #[path = "apikey_create.rs"]
 pub(crate) mod create;
 #[path = "apikey_delete.rs"]
@@ -12,8 +16,14 @@ pub(crate) mod list;
 pub(crate) mod profile;
 #[path = "apikey_read_secret.rs"]
 pub(crate) mod read_secret;
+#[path = "apikey_response_cache.rs"]
+pub(crate) mod response_cache;
+#[path = "apikey_rotate.rs"]
+pub(crate) mod rotate;
 #[path = "apikey_service_tier.rs"]
 pub(crate) mod service_tier;
+#[path = "apikey_structured_output.rs"]
+pub(crate) mod structured_output;
 #[path = "apikey_update_model.rs"]
 pub(crate) mod update_model;
 #[path = "apikey_usage_stats.rs"]
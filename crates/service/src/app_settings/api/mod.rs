@@ -121,5 +121,14 @@ pub fn app_settings_set(params: Option<&Value>) -> Result<Value, String> {
     let patch = patch::parse_app_settings_patch(params)?;
     let service_listen_mode = patch.service_listen_mode.clone();
     patch::apply_app_settings_patch(patch)?;
+    let changed_keys = params
+        .and_then(Value::as_object)
+        .map(|object| object.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    crate::event_bus::publish_event(
+        crate::event_bus::TOPIC_SETTINGS_CHANGED,
+        crate::event_bus::EventAudience::Admin,
+        serde_json::json!({ "keys": changed_keys }),
+    );
     current::current_app_settings_value_persisted(None, None, service_listen_mode.as_deref())
 }
@@ -35,6 +35,10 @@ pub(crate) const APP_SETTINGS_ENV_RESERVED_KEYS: &[&str] = &[
     "CODEXMANAGER_TOKEN_REFRESH_POLL_INTERVAL_SECS",
     "CODEXMANAGER_WARMUP_CRON_ENABLED",
     "CODEXMANAGER_WARMUP_CRON_EXPRESSION",
+    "CODEXMANAGER_HEALTH_PROBE_ENABLED",
+    "CODEXMANAGER_HEALTH_PROBE_INTERVAL_SECS",
+    "CODEXMANAGER_HEALTH_PROBE_FAILURE_THRESHOLD",
+    "CODEXMANAGER_HEALTH_PROBE_RECOVERY_THRESHOLD",
     "CODEXMANAGER_USAGE_REFRESH_WORKERS",
     "CODEXMANAGER_HTTP_WORKER_FACTOR",
     "CODEXMANAGER_HTTP_WORKER_MIN",
@@ -57,6 +61,20 @@ pub(crate) const ENV_OVERRIDE_CATALOG: &[EnvOverrideCatalogItem] = &[
         ENV_OVERRIDE_APPLY_MODE_RUNTIME,
         "0",
     ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_AGGREGATE_API_MODEL_DISCOVERY_INTERVAL_SECS",
+        "聚合 API 模型自动发现间隔（秒，0 关闭）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "21600",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_ALERT_EVAL_INTERVAL_SECS",
+        "告警规则评估最小间隔（秒）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "60",
+    ),
     EnvOverrideCatalogItem::new(
         "CODEXMANAGER_ALLOW_NON_LOOPBACK_LOGIN_ADDR",
         "允许非回环登录回调",
@@ -253,6 +271,13 @@ pub(crate) const ENV_OVERRIDE_CATALOG: &[EnvOverrideCatalogItem] = &[
         ENV_OVERRIDE_APPLY_MODE_RUNTIME,
         "",
     ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_PROXY_POOL_MEMBER_DOWN_SECS",
+        "代理池成员故障摘除时长（秒）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "120",
+    ),
     EnvOverrideCatalogItem::new(
         "CODEXMANAGER_REDIRECT_URI",
         "登录回调 URI",
@@ -309,7 +334,98 @@ pub(crate) const ENV_OVERRIDE_CATALOG: &[EnvOverrideCatalogItem] = &[
         ENV_OVERRIDE_APPLY_MODE_RUNTIME,
         "0",
     ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_CONTEXT_WINDOW_PREFLIGHT",
+        "上下文窗口预检",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "0",
+    ),
     EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_RESPONSE_CACHE_MAX_BYTES",
+        "响应缓存总容量（字节）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "268435456",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_RESPONSE_CACHE_MAX_ENTRY_BYTES",
+        "单条响应缓存上限（字节）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "4194304",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_RESPONSE_STORE_MAX_BYTES",
+        "Responses 存储容量（字节）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "268435456",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_BACKGROUND_RESPONSE_MAX_CONCURRENCY",
+        "后台 Responses 并发上限",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "4",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_BACKGROUND_RESPONSE_RETENTION_SECS",
+        "后台 Responses 保留时长（秒）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "86400",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_BATCH_MAX_CONCURRENCY",
+        "批处理单任务并发",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "2",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_BATCH_FILE_MAX_BYTES",
+        "批处理输入文件上限（字节）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "209715200",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_CHAT_FAN_OUT_MAX_N",
+        "Chat Completions n 拆分上限",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "8",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_CHAT_FAN_OUT_MAX_CONCURRENCY",
+        "Chat Completions n 拆分在途子请求上限",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "8",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_STRUCTURED_OUTPUT_VALIDATION_MODELS",
+        "结构化输出校验模型列表",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_STRUCTURED_OUTPUT_MAX_REPAIRS",
+        "结构化输出修复重试次数",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "1",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_SHUTDOWN_DRAIN_TIMEOUT_SECS",
+        "停机排空超时（秒）",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RUNTIME,
+        "30",
+    ),
+    EnvOverrideCatalogItem::new(
         "CODEXMANAGER_SSE_KEEPALIVE_ENABLED",
         "启用 SSE 保活",
         ENV_OVERRIDE_SCOPE_SERVICE,
@@ -337,6 +453,48 @@ pub(crate) const ENV_OVERRIDE_CATALOG: &[EnvOverrideCatalogItem] = &[
         ENV_OVERRIDE_APPLY_MODE_RUNTIME,
         "0",
     ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_ADDR",
+        "TLS 监听地址",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_CERT_FILE",
+        "TLS 证书文件",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_CLIENT_AUTH",
+        "mTLS 客户端认证模式",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "required",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_CLIENT_CA_FILE",
+        "mTLS 客户端 CA 文件",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_CLIENT_CERT_KEYS",
+        "客户端证书与平台 Key 映射",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_TLS_KEY_FILE",
+        "TLS 私钥文件",
+        ENV_OVERRIDE_SCOPE_SERVICE,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
     EnvOverrideCatalogItem::new(
         ENV_TOKEN_REFRESH_AHEAD_SECS,
         "Token 刷新提前量（秒）",
@@ -470,4 +628,11 @@ pub(crate) const ENV_OVERRIDE_CATALOG: &[EnvOverrideCatalogItem] = &[
         ENV_OVERRIDE_APPLY_MODE_RESTART,
         "",
     ),
+    EnvOverrideCatalogItem::new(
+        "CODEXMANAGER_WEB_TLS_ADDR",
+        "Web TLS 监听地址",
+        ENV_OVERRIDE_SCOPE_WEB,
+        ENV_OVERRIDE_APPLY_MODE_RESTART,
+        "",
+    ),
 ];
@@ -159,7 +159,14 @@ fn env_override_effect_scope(key: &str) -> &'static str {
     let key = normalized_env_key(key);
     match key.as_str() {
         "CODEXMANAGER_ACCOUNT_MAX_INFLIGHT"
+        | "CODEXMANAGER_BACKGROUND_RESPONSE_MAX_CONCURRENCY"
+        | "CODEXMANAGER_BACKGROUND_RESPONSE_RETENTION_SECS"
+        | "CODEXMANAGER_BATCH_FILE_MAX_BYTES"
+        | "CODEXMANAGER_BATCH_MAX_CONCURRENCY"
+        | "CODEXMANAGER_CHAT_FAN_OUT_MAX_N"
+        | "CODEXMANAGER_CHAT_FAN_OUT_MAX_CONCURRENCY"
         | "CODEXMANAGER_COMPACT_API_PATH"
+        | "CODEXMANAGER_CONTEXT_WINDOW_PREFLIGHT"
         | "CODEXMANAGER_CODEX_IMAGE_GENERATION_ENABLED"
         | "CODEXMANAGER_CODEX_IMAGE_MAIN_MODEL"
         | "CODEXMANAGER_CODEX_IMAGE_TOOL_MODEL"
@@ -172,22 +179,34 @@ fn env_override_effect_scope(key: &str) -> &'static str {
         | "CODEXMANAGER_PROMPT_CACHE_TTL_SECS"
         | "CODEXMANAGER_PROXY_LIST"
         | "CODEXMANAGER_REQUEST_GATE_WAIT_TIMEOUT_MS"
+        | "CODEXMANAGER_RESPONSE_CACHE_MAX_BYTES"
+        | "CODEXMANAGER_RESPONSE_CACHE_MAX_ENTRY_BYTES"
+        | "CODEXMANAGER_RESPONSE_STORE_MAX_BYTES"
         | "CODEXMANAGER_ROUTE_HEALTH_P2C_BALANCED_WINDOW"
         | "CODEXMANAGER_ROUTE_HEALTH_P2C_ENABLED"
         | "CODEXMANAGER_ROUTE_HEALTH_P2C_ORDERED_WINDOW"
         | "CODEXMANAGER_ROUTE_STATE_CAPACITY"
         | "CODEXMANAGER_ROUTE_STATE_TTL_SECS"
         | "CODEXMANAGER_STRICT_REQUEST_PARAM_ALLOWLIST"
+        | "CODEXMANAGER_STRUCTURED_OUTPUT_MAX_REPAIRS"
+        | "CODEXMANAGER_STRUCTURED_OUTPUT_VALIDATION_MODELS"
         | "CODEXMANAGER_UPSTREAM_BASE_URL"
         | "CODEXMANAGER_UPSTREAM_TOTAL_TIMEOUT_MS" => ENV_OVERRIDE_EFFECT_SCOPE_REQUEST_SEMANTIC,
         "CODEXMANAGER_GITHUB_TOKEN"
         | "CODEXMANAGER_NO_SERVICE"
+        | "CODEXMANAGER_TLS_ADDR"
+        | "CODEXMANAGER_TLS_CERT_FILE"
+        | "CODEXMANAGER_TLS_CLIENT_AUTH"
+        | "CODEXMANAGER_TLS_CLIENT_CA_FILE"
+        | "CODEXMANAGER_TLS_CLIENT_CERT_KEYS"
+        | "CODEXMANAGER_TLS_KEY_FILE"
         | "CODEXMANAGER_UPDATE_PRERELEASE"
         | "CODEXMANAGER_UPDATE_REPO"
         | "CODEXMANAGER_WEB_ADDR"
         | "CODEXMANAGER_WEB_NO_OPEN"
         | "CODEXMANAGER_WEB_NO_SPAWN_SERVICE"
-        | "CODEXMANAGER_WEB_ROOT" => ENV_OVERRIDE_EFFECT_SCOPE_DEPLOYMENT,
+        | "CODEXMANAGER_WEB_ROOT"
+        | "CODEXMANAGER_WEB_TLS_ADDR" => ENV_OVERRIDE_EFFECT_SCOPE_DEPLOYMENT,
         _ => ENV_OVERRIDE_EFFECT_SCOPE_RUNTIME_GLOBAL,
     }
 }
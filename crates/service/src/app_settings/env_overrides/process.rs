@@ -105,4 +105,5 @@ pub(crate) fn reload_runtime_after_env_override_apply() {
     crate::gateway::reload_runtime_config_from_env();
     crate::usage_refresh::reload_background_tasks_runtime_from_env();
     crate::usage_http::reload_usage_http_client_from_env();
+    crate::reload_aggregate_api_model_discovery_from_env();
 }
@@ -56,6 +56,10 @@ pub struct BackgroundTasksInput {
     pub http_stream_worker_min: Option<usize>,
     pub warmup_cron_enabled: Option<bool>,
     pub warmup_cron_expression: Option<String>,
+    pub health_probe_enabled: Option<bool>,
+    pub health_probe_interval_secs: Option<u64>,
+    pub health_probe_failure_threshold: Option<u64>,
+    pub health_probe_recovery_threshold: Option<u64>,
 }
 
 impl BackgroundTasksInput {
@@ -85,6 +89,10 @@ impl BackgroundTasksInput {
             http_stream_worker_min: self.http_stream_worker_min,
             warmup_cron_enabled: self.warmup_cron_enabled,
             warmup_cron_expression: self.warmup_cron_expression,
+            health_probe_enabled: self.health_probe_enabled,
+            health_probe_interval_secs: self.health_probe_interval_secs,
+            health_probe_failure_threshold: self.health_probe_failure_threshold,
+            health_probe_recovery_threshold: self.health_probe_recovery_threshold,
         }
     }
 }
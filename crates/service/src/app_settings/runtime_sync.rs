@@ -131,6 +131,8 @@ pub fn sync_runtime_settings_from_storage() {
         apply_env_overrides_to_process(&env_overrides, &env_overrides);
         reload_runtime_after_env_override_apply();
     }
+    // 中文注释：未持久化覆盖时也要按进程环境刷新一次，0 表示关闭聚合 API 模型自动发现。
+    crate::reload_aggregate_api_model_discovery_from_env();
 
     if !process_env_has_value("CODEXMANAGER_SERVICE_ADDR") {
         if let Some(mode) = settings.get(SERVICE_BIND_MODE_SETTING_KEY) {
@@ -292,6 +294,10 @@ pub fn sync_runtime_settings_from_storage() {
         "CODEXMANAGER_TOKEN_REFRESH_POLL_INTERVAL_SECS",
         "CODEXMANAGER_WARMUP_CRON_ENABLED",
         "CODEXMANAGER_WARMUP_CRON_EXPRESSION",
+        "CODEXMANAGER_HEALTH_PROBE_ENABLED",
+        "CODEXMANAGER_HEALTH_PROBE_INTERVAL_SECS",
+        "CODEXMANAGER_HEALTH_PROBE_FAILURE_THRESHOLD",
+        "CODEXMANAGER_HEALTH_PROBE_RECOVERY_THRESHOLD",
         "CODEXMANAGER_USAGE_REFRESH_WORKERS",
         "CODEXMANAGER_HTTP_WORKER_FACTOR",
         "CODEXMANAGER_HTTP_WORKER_MIN",
@@ -3,9 +3,8 @@ use super::{
     APP_SETTING_SERVICE_ADDR_KEY,
 };
 
-pub const DEFAULT_ADDR: &str = "localhost:48760";
+pub use codexmanager_core::process_env::{DEFAULT_ADDR, DEFAULT_WEB_ADDR};
 pub const DEFAULT_BIND_ADDR: &str = "0.0.0.0:48760";
-pub const DEFAULT_WEB_ADDR: &str = "localhost:48761";
 pub const DEFAULT_WEB_BIND_ADDR: &str = "0.0.0.0:48761";
 pub const SERVICE_BIND_MODE_SETTING_KEY: &str = "service.bind_mode";
 pub const SERVICE_BIND_MODE_LOOPBACK: &str = "loopback";
@@ -45,7 +45,7 @@ pub const APP_SETTING_ENV_OVERRIDES_KEY: &str = "app.env_overrides";
 pub const APP_SETTING_WEB_ACCESS_PASSWORD_HASH_KEY: &str = "web.auth.password_hash";
 pub const APP_SETTING_WEB_AUTH_MODE_KEY: &str = "web.auth.mode";
 pub const APP_SETTING_DISTRIBUTION_ENABLED_KEY: &str = "distribution.enabled";
-pub const WEB_ACCESS_SESSION_COOKIE_NAME: &str = "codexmanager_web_auth";
+pub use codexmanager_core::process_env::WEB_ACCESS_SESSION_COOKIE_NAME;
 
 /// 函数 `parse_bool_with_default`
 ///
@@ -0,0 +1,326 @@
+use codexmanager_core::rpc::types::{ModelGroupModelsSetParams, ModelGroupUpsertParams};
+use codexmanager_core::storage::{now_ts, ApiKey, Storage};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::document::DeclarativeConfig;
+use super::plan::{
+    ConfigChangeAction, ConfigResourceChange, DeclarativeConfigPlan, RESOURCE_AGGREGATE_API,
+    RESOURCE_GATEWAY, RESOURCE_MODEL_GROUP, RESOURCE_PLATFORM_KEY, RESOURCE_PROXY_PROFILE,
+};
+use crate::apikey_profile::{
+    normalize_protocol_type, normalize_rotation_strategy, profile_from_protocol,
+};
+use crate::storage_helpers::generate_key_id;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigApplyFailure {
+    pub(crate) resource: String,
+    pub(crate) key: String,
+    pub(crate) error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigApplyReport {
+    pub(crate) applied: usize,
+    pub(crate) failed: Vec<ConfigApplyFailure>,
+}
+
+/// 按差异逐项写入；单项失败不会中断其余资源，失败明细随报告返回。
+pub(crate) fn apply_declarative_config_plan(
+    storage: &Storage,
+    config: &DeclarativeConfig,
+    plan: &DeclarativeConfigPlan,
+) -> DeclarativeConfigApplyReport {
+    let mut report = DeclarativeConfigApplyReport::default();
+    for change in &plan.changes {
+        let result = match change.resource {
+            RESOURCE_GATEWAY => apply_gateway(change),
+            RESOURCE_PLATFORM_KEY => apply_platform_key(storage, change),
+            RESOURCE_AGGREGATE_API => apply_aggregate_api(config, change),
+            RESOURCE_MODEL_GROUP => apply_model_group(storage, config, change),
+            RESOURCE_PROXY_PROFILE => apply_proxy_profile(config, change),
+            other => Err(format!("unsupported declarative resource: {other}")),
+        };
+        match result {
+            Ok(()) => report.applied += 1,
+            Err(error) => {
+                log::warn!(
+                    "event=declarative_config_apply_failed resource={} key={} err={}",
+                    change.resource,
+                    change.key,
+                    error
+                );
+                report.failed.push(ConfigApplyFailure {
+                    resource: change.resource.to_string(),
+                    key: change.key.clone(),
+                    error,
+                });
+            }
+        }
+    }
+    report
+}
+
+fn desired_text(change: &ConfigResourceChange, field: &str) -> Option<String> {
+    change
+        .field(field)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn has_field(change: &ConfigResourceChange, field: &str) -> bool {
+    change.field(field).is_some()
+}
+
+fn apply_gateway(change: &ConfigResourceChange) -> Result<(), String> {
+    let patch = change
+        .fields
+        .iter()
+        .map(|item| (item.field.clone(), item.desired.clone()))
+        .collect::<Map<String, Value>>();
+    crate::app_settings_set(Some(&Value::Object(patch))).map(|_| ())
+}
+
+fn apply_platform_key(storage: &Storage, change: &ConfigResourceChange) -> Result<(), String> {
+    let model_slug = desired_text(change, "modelSlug");
+    if has_field(change, "modelSlug") {
+        crate::models_v2::ensure_text_generation_model(storage, model_slug.as_deref())?;
+    }
+    let Some(key_id) = change.target_id.as_deref() else {
+        let protocol_type = normalize_protocol_type(desired_text(change, "protocolType"))?;
+        let (client_type, protocol_type, auth_scheme) = profile_from_protocol(&protocol_type)?;
+        // 中文注释：配置文件只持有哈希，创建出的平台 Key 没有可回读的明文。
+        let record = ApiKey {
+            id: generate_key_id(),
+            name: desired_text(change, "name"),
+            model_slug,
+            reasoning_effort: desired_text(change, "reasoningEffort"),
+            service_tier: desired_text(change, "serviceTier"),
+            rotation_strategy: normalize_rotation_strategy(None)?,
+            aggregate_api_id: None,
+            account_plan_filter: None,
+            aggregate_api_url: None,
+            client_type,
+            protocol_type,
+            auth_scheme,
+            upstream_base_url: None,
+            static_headers_json: None,
+            key_hash: change.key.clone(),
+            status: desired_text(change, "status").unwrap_or_else(|| "active".to_string()),
+            created_at: now_ts(),
+            last_used_at: None,
+        };
+        return storage
+            .insert_api_key(&record)
+            .map_err(|err| format!("create platform key failed: {err}"));
+    };
+
+    if has_field(change, "name") {
+        storage
+            .update_api_key_name(key_id, desired_text(change, "name").as_deref())
+            .map_err(|err| format!("update platform key name failed: {err}"))?;
+    }
+    if ["modelSlug", "reasoningEffort", "serviceTier"]
+        .iter()
+        .any(|field| has_field(change, field))
+    {
+        let existing = storage
+            .find_api_key_by_hash(change.key.as_str())
+            .map_err(|err| format!("read platform key failed: {err}"))?
+            .ok_or_else(|| "platform key not found".to_string())?;
+        let pick = |field: &str, current: Option<String>| {
+            if has_field(change, field) {
+                desired_text(change, field)
+            } else {
+                current
+            }
+        };
+        let model_slug = pick("modelSlug", existing.model_slug);
+        let reasoning_effort = pick("reasoningEffort", existing.reasoning_effort);
+        let service_tier = pick("serviceTier", existing.service_tier);
+        storage
+            .update_api_key_model_config(
+                key_id,
+                model_slug.as_deref(),
+                reasoning_effort.as_deref(),
+                service_tier.as_deref(),
+            )
+            .map_err(|err| format!("update platform key model config failed: {err}"))?;
+    }
+    if let Some(status) = desired_text(change, "status") {
+        storage
+            .update_api_key_status(key_id, status.as_str())
+            .map_err(|err| format!("update platform key status failed: {err}"))?;
+    }
+    Ok(())
+}
+
+fn apply_aggregate_api(
+    config: &DeclarativeConfig,
+    change: &ConfigResourceChange,
+) -> Result<(), String> {
+    let spec = config
+        .aggregate_apis
+        .iter()
+        .find(|item| item.supplier_name.trim() == change.key)
+        .ok_or_else(|| format!("aggregate api `{}` missing from config", change.key))?;
+    let key = if has_field(change, "key") {
+        spec.resolved_key()?
+    } else {
+        None
+    };
+    let model_override = has_field(change, "modelOverride")
+        .then(|| desired_text(change, "modelOverride").unwrap_or_default());
+    let sort = change.field("sort").and_then(Value::as_i64);
+    let status = desired_text(change, "status");
+    let api_id = match change.target_id.clone() {
+        Some(api_id) => api_id,
+        None => {
+            let created = crate::create_aggregate_api(
+                desired_text(change, "url"),
+                key,
+                desired_text(change, "providerType"),
+                Some(change.key.clone()),
+                sort,
+                None,
+                None,
+                None,
+                None,
+                None,
+                model_override,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )?;
+            // 中文注释：创建接口固定写入 active，其余状态需要再补一次更新。
+            if status.as_deref().is_none_or(|value| value == "active") {
+                return Ok(());
+            }
+            return crate::update_aggregate_api(
+                created.id.as_str(),
+                None,
+                None,
+                None,
+                Some(change.key.clone()),
+                None,
+                status,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    crate::update_aggregate_api(
+        api_id.as_str(),
+        desired_text(change, "url"),
+        key,
+        desired_text(change, "providerType"),
+        Some(change.key.clone()),
+        sort,
+        status,
+        None,
+        None,
+        None,
+        None,
+        None,
+        model_override,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn apply_model_group(
+    storage: &Storage,
+    config: &DeclarativeConfig,
+    change: &ConfigResourceChange,
+) -> Result<(), String> {
+    let spec = config
+        .model_groups
+        .iter()
+        .find(|item| item.id.trim() == change.key)
+        .ok_or_else(|| format!("model group `{}` missing from config", change.key))?;
+    let existing = storage
+        .find_model_group(change.key.as_str())
+        .map_err(|err| format!("read model group failed: {err}"))?;
+    let group_fields = [
+        "name",
+        "description",
+        "status",
+        "sort",
+        "rateMultiplierMillis",
+    ];
+    if change.action == ConfigChangeAction::Create
+        || group_fields.iter().any(|field| has_field(change, field))
+    {
+        // 中文注释：upsert 会整体覆盖描述与状态，未声明的字段沿用数据库现值。
+        crate::upsert_model_group(ModelGroupUpsertParams {
+            id: Some(change.key.clone()),
+            name: spec.name.clone(),
+            description: spec.description.clone().or_else(|| {
+                existing
+                    .as_ref()
+                    .and_then(|group| group.description.clone())
+            }),
+            status: spec
+                .status
+                .clone()
+                .or_else(|| existing.as_ref().map(|group| group.status.clone())),
+            sort: spec.sort,
+            is_default: None,
+            rate_multiplier_millis: spec.rate_multiplier_millis,
+        })?;
+    }
+    if let (true, Some(models)) = (has_field(change, "models"), spec.models.clone()) {
+        crate::set_model_group_models(ModelGroupModelsSetParams {
+            group_id: change.key.clone(),
+            models: models.into_iter().map(|item| item.into_params()).collect(),
+        })?;
+    }
+    Ok(())
+}
+
+fn apply_proxy_profile(
+    config: &DeclarativeConfig,
+    change: &ConfigResourceChange,
+) -> Result<(), String> {
+    let spec = config
+        .proxy_profiles
+        .iter()
+        .find(|item| item.name.trim() == change.key)
+        .ok_or_else(|| format!("proxy profile `{}` missing from config", change.key))?;
+    let proxy_url = has_field(change, "proxyUrl").then(|| spec.proxy_url.clone());
+    let enabled = change.field("enabled").and_then(Value::as_bool);
+    let notes = desired_text(change, "notes");
+    match change.target_id.as_deref() {
+        Some(id) => crate::update_proxy_profile(id, None, proxy_url, enabled, None, notes),
+        None => {
+            crate::create_proxy_profile(Some(change.key.clone()), proxy_url, enabled, None, notes)
+        }
+    }
+    .map(|_| ())
+}
@@ -0,0 +1,247 @@
+use codexmanager_core::rpc::types::ModelGroupModelUpsertParams;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 声明式配置允许覆盖的网关设置项，键名与 `appSettings/set` 保持一致。
+pub(crate) const GATEWAY_SETTING_KEYS: &[&str] = &[
+    "accountMaxInflight",
+    "backgroundTasks",
+    "compactModelForwardRules",
+    "envOverrides",
+    "freeAccountMaxModel",
+    "gatewayOriginator",
+    "gatewayResidencyRequirement",
+    "gatewayUserAgentVersion",
+    "modelForwardRules",
+    "quotaGuard",
+    "routeStrategy",
+    "sseKeepaliveEnabled",
+    "sseKeepaliveIntervalMs",
+    "threadAwareAccountDistributionEnabled",
+    "upstreamProxyBypassHosts",
+    "upstreamProxyUrl",
+    "upstreamStreamTimeoutMs",
+    "upstreamTotalTimeoutMs",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DocumentFormat {
+    Toml,
+    Yaml,
+}
+
+impl DocumentFormat {
+    pub(crate) fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|value| value.to_str())
+            .unwrap_or_default();
+        Self::parse(extension)
+            .map_err(|_| format!("unsupported config file extension: {}", path.display()))
+    }
+
+    pub(crate) fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            other => Err(format!("unsupported config format: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct DeclarativeConfig {
+    #[serde(default)]
+    pub(crate) gateway: Map<String, Value>,
+    #[serde(default)]
+    pub(crate) platform_keys: Vec<PlatformKeySpec>,
+    #[serde(default)]
+    pub(crate) aggregate_apis: Vec<AggregateApiSpec>,
+    #[serde(default)]
+    pub(crate) model_groups: Vec<ModelGroupSpec>,
+    #[serde(default)]
+    pub(crate) proxy_profiles: Vec<ProxyProfileSpec>,
+}
+
+/// 平台 Key 只按 SHA-256 哈希声明，明文不进入配置文件。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct PlatformKeySpec {
+    pub(crate) key_hash: String,
+    pub(crate) name: Option<String>,
+    pub(crate) model_slug: Option<String>,
+    pub(crate) reasoning_effort: Option<String>,
+    pub(crate) service_tier: Option<String>,
+    pub(crate) protocol_type: Option<String>,
+    pub(crate) status: Option<String>,
+}
+
+/// 聚合 API 以供应商名称作为稳定标识；密钥可直接写入或通过 `keyEnv` 引用环境变量。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct AggregateApiSpec {
+    pub(crate) supplier_name: String,
+    pub(crate) provider_type: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) sort: Option<i64>,
+    pub(crate) status: Option<String>,
+    pub(crate) model_override: Option<String>,
+    pub(crate) key: Option<String>,
+    pub(crate) key_env: Option<String>,
+}
+
+impl AggregateApiSpec {
+    pub(crate) fn resolved_key(&self) -> Result<Option<String>, String> {
+        if let Some(key) = self
+            .key
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            return Ok(Some(key.to_string()));
+        }
+        let Some(env_name) = self
+            .key_env
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        else {
+            return Ok(None);
+        };
+        std::env::var(env_name)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "aggregate api `{}` keyEnv `{env_name}` is not set",
+                    self.supplier_name
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ModelGroupSpec {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) status: Option<String>,
+    pub(crate) sort: Option<i64>,
+    pub(crate) rate_multiplier_millis: Option<i64>,
+    pub(crate) models: Option<Vec<ModelGroupModelSpec>>,
+}
+
+/// 模型组成员既可以写成模型 slug，也可以写成带倍率等字段的完整对象。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ModelGroupModelSpec {
+    Slug(String),
+    Detailed(ModelGroupModelUpsertParams),
+}
+
+impl ModelGroupModelSpec {
+    pub(crate) fn into_params(self) -> ModelGroupModelUpsertParams {
+        match self {
+            Self::Slug(slug) => ModelGroupModelUpsertParams {
+                platform_model_slug: slug,
+                ..Default::default()
+            },
+            Self::Detailed(params) => params,
+        }
+    }
+}
+
+/// 代理配置以名称作为稳定标识。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ProxyProfileSpec {
+    pub(crate) name: String,
+    pub(crate) proxy_url: String,
+    pub(crate) enabled: Option<bool>,
+    pub(crate) notes: Option<String>,
+}
+
+pub(crate) fn parse_declarative_config(
+    raw: &str,
+    format: DocumentFormat,
+) -> Result<DeclarativeConfig, String> {
+    let value = match format {
+        DocumentFormat::Toml => toml_edit::de::from_str::<Value>(raw)
+            .map_err(|err| format!("invalid toml config: {err}"))?,
+        DocumentFormat::Yaml => serde_norway::from_str::<Value>(raw)
+            .map_err(|err| format!("invalid yaml config: {err}"))?,
+    };
+    // 中文注释：空 YAML 文档解析为 null，按空配置处理，便于先落一个占位文件。
+    let value = if value.is_null() {
+        Value::Object(Map::new())
+    } else {
+        value
+    };
+    let config = serde_json::from_value::<DeclarativeConfig>(value)
+        .map_err(|err| format!("invalid declarative config: {err}"))?;
+    validate_declarative_config(&config)?;
+    Ok(config)
+}
+
+fn validate_declarative_config(config: &DeclarativeConfig) -> Result<(), String> {
+    for key in config.gateway.keys() {
+        if !GATEWAY_SETTING_KEYS.contains(&key.as_str()) {
+            return Err(format!("unsupported gateway setting: {key}"));
+        }
+    }
+    ensure_unique(
+        "platformKeys.keyHash",
+        config
+            .platform_keys
+            .iter()
+            .map(|item| item.key_hash.as_str()),
+    )?;
+    for item in &config.platform_keys {
+        let hash = item.key_hash.as_str();
+        if hash.len() != 64 || !hash.chars().all(|ch| matches!(ch, '0'..='9' | 'a'..='f')) {
+            return Err(format!(
+                "platformKeys.keyHash must be a lowercase sha256 hex digest: {hash}"
+            ));
+        }
+    }
+    ensure_unique(
+        "aggregateApis.supplierName",
+        config
+            .aggregate_apis
+            .iter()
+            .map(|item| item.supplier_name.as_str()),
+    )?;
+    ensure_unique(
+        "modelGroups.id",
+        config.model_groups.iter().map(|item| item.id.as_str()),
+    )?;
+    ensure_unique(
+        "proxyProfiles.name",
+        config.proxy_profiles.iter().map(|item| item.name.as_str()),
+    )?;
+    Ok(())
+}
+
+fn ensure_unique<'a>(field: &str, values: impl Iterator<Item = &'a str>) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("{field} must not be empty"));
+        }
+        if !seen.insert(value) {
+            return Err(format!("duplicate {field}: {value}"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "document_tests.rs"]
+mod tests;
@@ -0,0 +1,115 @@
+use super::*;
+
+const KEY_HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+#[test]
+fn toml_document_parses_all_sections() {
+    let raw = format!(
+        r#"
+[gateway]
+routeStrategy = "balanced"
+accountMaxInflight = 4
+
+[[platformKeys]]
+keyHash = "{KEY_HASH}"
+name = "ci"
+modelSlug = "gpt-5"
+
+[[aggregateApis]]
+supplierName = "relay"
+providerType = "claude"
+url = "https://relay.example.com/v1"
+keyEnv = "RELAY_KEY"
+
+[[modelGroups]]
+id = "mg_team"
+name = "Team"
+models = ["gpt-5", {{ platformModelSlug = "gpt-5-mini", rateMultiplierMillis = 500 }}]
+
+[[proxyProfiles]]
+name = "hk"
+proxyUrl = "socks5://127.0.0.1:1080"
+"#
+    );
+
+    let config = parse_declarative_config(&raw, DocumentFormat::Toml).expect("parse toml");
+
+    assert_eq!(
+        config.gateway.get("routeStrategy"),
+        Some(&Value::from("balanced"))
+    );
+    assert_eq!(config.platform_keys[0].model_slug.as_deref(), Some("gpt-5"));
+    assert_eq!(
+        config.aggregate_apis[0].key_env.as_deref(),
+        Some("RELAY_KEY")
+    );
+    assert_eq!(config.proxy_profiles[0].name, "hk");
+    let models = config.model_groups[0]
+        .models
+        .clone()
+        .expect("models")
+        .into_iter()
+        .map(ModelGroupModelSpec::into_params)
+        .collect::<Vec<_>>();
+    assert_eq!(models[0].platform_model_slug, "gpt-5");
+    assert_eq!(models[1].platform_model_slug, "gpt-5-mini");
+    assert_eq!(models[1].rate_multiplier_millis, Some(500));
+}
+
+#[test]
+fn yaml_document_parses_and_empty_document_is_empty_config() {
+    let raw = format!(
+        "gateway:\n  sseKeepaliveEnabled: false\nplatformKeys:\n  - keyHash: {KEY_HASH}\n    status: disabled\n"
+    );
+    let config = parse_declarative_config(&raw, DocumentFormat::Yaml).expect("parse yaml");
+    assert_eq!(
+        config.gateway.get("sseKeepaliveEnabled"),
+        Some(&Value::Bool(false))
+    );
+    assert_eq!(config.platform_keys[0].status.as_deref(), Some("disabled"));
+
+    let empty = parse_declarative_config("", DocumentFormat::Yaml).expect("parse empty");
+    assert!(empty.gateway.is_empty());
+    assert!(empty.platform_keys.is_empty());
+}
+
+#[test]
+fn invalid_documents_are_rejected() {
+    let err = parse_declarative_config(
+        "[gateway]\nserviceAddr = \"0.0.0.0:1\"\n",
+        DocumentFormat::Toml,
+    )
+    .expect_err("unknown gateway key");
+    assert!(err.contains("unsupported gateway setting: serviceAddr"));
+
+    let err = parse_declarative_config(
+        "[[platformKeys]]\nkeyHash = \"ABC\"\n",
+        DocumentFormat::Toml,
+    )
+    .expect_err("bad hash");
+    assert!(err.contains("keyHash"));
+
+    let err = parse_declarative_config(
+        "proxyProfiles:\n  - name: hk\n    proxyUrl: http://a:1\n  - name: hk\n    proxyUrl: http://b:1\n",
+        DocumentFormat::Yaml,
+    )
+    .expect_err("duplicate proxy");
+    assert!(err.contains("duplicate proxyProfiles.name: hk"));
+
+    let err = parse_declarative_config("accounts = []\n", DocumentFormat::Toml)
+        .expect_err("unknown section");
+    assert!(err.contains("invalid declarative config"));
+}
+
+#[test]
+fn format_is_detected_from_extension() {
+    assert_eq!(
+        DocumentFormat::from_path(Path::new("/etc/codexmanager/config.yml")),
+        Ok(DocumentFormat::Yaml)
+    );
+    assert_eq!(
+        DocumentFormat::from_path(Path::new("config.TOML")),
+        Ok(DocumentFormat::Toml)
+    );
+    assert!(DocumentFormat::from_path(Path::new("config.json")).is_err());
+}
@@ -0,0 +1,285 @@
+mod apply;
+mod document;
+mod plan;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use codexmanager_core::storage::now_ts;
+
+use apply::{apply_declarative_config_plan, ConfigApplyFailure, DeclarativeConfigApplyReport};
+use document::{parse_declarative_config, DeclarativeConfig};
+use plan::{plan_declarative_config, DeclarativeConfigPlan};
+
+pub(crate) use document::DocumentFormat;
+
+pub(crate) const ENV_CONFIG_FILE: &str = "CODEXMANAGER_CONFIG_FILE";
+const ENV_CONFIG_WATCH_INTERVAL_SECS: &str = "CODEXMANAGER_CONFIG_WATCH_INTERVAL_SECS";
+const DEFAULT_CONFIG_WATCH_INTERVAL_SECS: u64 = 5;
+const INLINE_SOURCE: &str = "inline";
+
+static CONFIG_WATCH_STARTED: OnceLock<()> = OnceLock::new();
+static LAST_APPLY: OnceLock<Mutex<Option<DeclarativeConfigApplyRecord>>> = OnceLock::new();
+
+/// 声明式配置来源：默认读取 `CODEXMANAGER_CONFIG_FILE`，RPC 也可以直接提交文本做预览或应用。
+pub(crate) enum DeclarativeConfigSource {
+    ConfiguredFile,
+    Inline {
+        content: String,
+        format: DocumentFormat,
+    },
+}
+
+struct LoadedConfig {
+    source: String,
+    digest: String,
+    config: DeclarativeConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigDiff {
+    pub(crate) source: String,
+    pub(crate) digest: String,
+    #[serde(flatten)]
+    pub(crate) plan: DeclarativeConfigPlan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigApplyResult {
+    pub(crate) source: String,
+    pub(crate) digest: String,
+    #[serde(flatten)]
+    pub(crate) plan: DeclarativeConfigPlan,
+    #[serde(flatten)]
+    pub(crate) report: DeclarativeConfigApplyReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigApplyRecord {
+    pub(crate) source: String,
+    pub(crate) digest: Option<String>,
+    pub(crate) applied_at: i64,
+    pub(crate) changes: usize,
+    pub(crate) applied: usize,
+    pub(crate) failed: Vec<ConfigApplyFailure>,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigStatus {
+    pub(crate) config_file: Option<String>,
+    pub(crate) watching: bool,
+    pub(crate) last_apply: Option<DeclarativeConfigApplyRecord>,
+    pub(crate) drift: Option<DeclarativeConfigDiff>,
+    pub(crate) drift_error: Option<String>,
+}
+
+fn configured_config_path() -> Option<PathBuf> {
+    std::env::var(ENV_CONFIG_FILE)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+fn config_watch_interval() -> Duration {
+    let secs = std::env::var(ENV_CONFIG_WATCH_INTERVAL_SECS)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_CONFIG_WATCH_INTERVAL_SECS)
+        .max(1);
+    Duration::from_secs(secs)
+}
+
+fn content_digest(raw: &str) -> String {
+    Sha256::digest(raw.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn read_configured_file() -> Result<(PathBuf, String), String> {
+    let path =
+        configured_config_path().ok_or_else(|| format!("{ENV_CONFIG_FILE} is not configured"))?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|err| format!("read config file {} failed: {err}", path.display()))?;
+    Ok((path, raw))
+}
+
+fn load_config(source: DeclarativeConfigSource) -> Result<LoadedConfig, String> {
+    let (source, raw, format) = match source {
+        DeclarativeConfigSource::ConfiguredFile => {
+            let (path, raw) = read_configured_file()?;
+            let format = DocumentFormat::from_path(&path)?;
+            (path.display().to_string(), raw, format)
+        }
+        DeclarativeConfigSource::Inline { content, format } => {
+            (INLINE_SOURCE.to_string(), content, format)
+        }
+    };
+    let config = parse_declarative_config(&raw, format)?;
+    Ok(LoadedConfig {
+        source,
+        digest: content_digest(&raw),
+        config,
+    })
+}
+
+fn plan_loaded_config(loaded: &LoadedConfig) -> Result<DeclarativeConfigPlan, String> {
+    let storage =
+        crate::storage_helpers::open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let current_settings = crate::app_settings_get()?;
+    plan_declarative_config(&storage, &current_settings, &loaded.config)
+}
+
+fn last_apply_slot() -> &'static Mutex<Option<DeclarativeConfigApplyRecord>> {
+    LAST_APPLY.get_or_init(|| Mutex::new(None))
+}
+
+fn record_last_apply(record: DeclarativeConfigApplyRecord) {
+    *crate::lock_utils::lock_recover(last_apply_slot(), "declarative_config_last_apply") =
+        Some(record);
+}
+
+/// 只计算差异，不落库。
+pub(crate) fn diff_declarative_config(
+    source: DeclarativeConfigSource,
+) -> Result<DeclarativeConfigDiff, String> {
+    let loaded = load_config(source)?;
+    let plan = plan_loaded_config(&loaded)?;
+    Ok(DeclarativeConfigDiff {
+        source: loaded.source,
+        digest: loaded.digest,
+        plan,
+    })
+}
+
+/// 计算差异并写入；重复应用同一份配置不会产生任何变更。
+pub(crate) fn apply_declarative_config(
+    source: DeclarativeConfigSource,
+) -> Result<DeclarativeConfigApplyResult, String> {
+    let loaded = load_config(source)?;
+    let plan = plan_loaded_config(&loaded)?;
+    let report = if plan.is_empty() {
+        DeclarativeConfigApplyReport::default()
+    } else {
+        let storage = crate::storage_helpers::open_storage()
+            .ok_or_else(|| "storage unavailable".to_string())?;
+        apply_declarative_config_plan(&storage, &loaded.config, &plan)
+    };
+    record_last_apply(DeclarativeConfigApplyRecord {
+        source: loaded.source.clone(),
+        digest: Some(loaded.digest.clone()),
+        applied_at: now_ts(),
+        changes: plan.changes.len(),
+        applied: report.applied,
+        failed: report.failed.clone(),
+        error: None,
+    });
+    Ok(DeclarativeConfigApplyResult {
+        source: loaded.source,
+        digest: loaded.digest,
+        plan,
+        report,
+    })
+}
+
+/// 返回最近一次应用记录，并对配置文件与数据库做一次实时漂移检测。
+pub(crate) fn declarative_config_status() -> DeclarativeConfigStatus {
+    let config_file = configured_config_path().map(|path| path.display().to_string());
+    let last_apply =
+        crate::lock_utils::lock_recover(last_apply_slot(), "declarative_config_last_apply").clone();
+    let (drift, drift_error) = if config_file.is_some() {
+        match diff_declarative_config(DeclarativeConfigSource::ConfiguredFile) {
+            Ok(diff) => (Some(diff), None),
+            Err(err) => (None, Some(err)),
+        }
+    } else {
+        (None, None)
+    };
+    DeclarativeConfigStatus {
+        config_file,
+        watching: CONFIG_WATCH_STARTED.get().is_some(),
+        last_apply,
+        drift,
+        drift_error,
+    }
+}
+
+/// 启动时同步应用一次配置文件，随后在后台轮询文件内容，变化后重新应用。
+pub(crate) fn ensure_declarative_config_sync() {
+    if configured_config_path().is_none() {
+        return;
+    }
+    let initial_digest = sync_configured_file(None);
+    CONFIG_WATCH_STARTED.get_or_init(|| {
+        if let Err(err) = thread::Builder::new()
+            .name("declarative-config-watch".to_string())
+            .spawn(move || declarative_config_watch_loop(initial_digest))
+        {
+            log::warn!("declarative config watch thread failed to start: {err}");
+        }
+    });
+}
+
+fn declarative_config_watch_loop(mut last_digest: Option<String>) {
+    loop {
+        thread::sleep(config_watch_interval());
+        last_digest = sync_configured_file(last_digest);
+    }
+}
+
+/// 文件内容未变化时跳过；返回本轮之后应记住的内容摘要。
+fn sync_configured_file(last_digest: Option<String>) -> Option<String> {
+    let raw = match read_configured_file() {
+        Ok((_, raw)) => raw,
+        Err(err) => {
+            log::warn!("event=declarative_config_read_failed err={}", err);
+            return last_digest;
+        }
+    };
+    let digest = content_digest(&raw);
+    if last_digest.as_deref() == Some(digest.as_str()) {
+        return last_digest;
+    }
+    match apply_declarative_config(DeclarativeConfigSource::ConfiguredFile) {
+        Ok(result) => {
+            log::info!(
+                "event=declarative_config_applied source={} digest={} changes={} applied={} failed={}",
+                result.source,
+                result.digest,
+                result.plan.changes.len(),
+                result.report.applied,
+                result.report.failed.len()
+            );
+        }
+        Err(err) => {
+            log::warn!("event=declarative_config_apply_failed err={}", err);
+            record_last_apply(DeclarativeConfigApplyRecord {
+                source: configured_config_path()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                digest: Some(digest.clone()),
+                applied_at: now_ts(),
+                changes: 0,
+                applied: 0,
+                failed: Vec::new(),
+                error: Some(err),
+            });
+        }
+    }
+    // 中文注释：解析失败也记住摘要，避免坏文件在每个轮询周期重复报错；修正文件后摘要变化即会重试。
+    Some(digest)
+}
+
+#[cfg(test)]
+#[path = "mod_tests.rs"]
+mod tests;
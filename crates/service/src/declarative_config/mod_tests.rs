@@ -0,0 +1,125 @@
+use super::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const KEY_HASH: &str = "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae";
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        match &self.original {
+            Some(value) => std::env::set_var(self.key, value),
+            None => std::env::remove_var(self.key),
+        }
+    }
+}
+
+fn isolated_path(label: &str, extension: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "codexmanager-declarative-{label}-{}-{nanos}.{extension}",
+        std::process::id()
+    ))
+}
+
+#[test]
+fn configured_file_is_applied_idempotently_and_drift_is_reported() {
+    let _guard = crate::test_env_guard();
+    let db_path = isolated_path("db", "db");
+    let config_path = isolated_path("config", "yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "platformKeys:\n  - keyHash: {KEY_HASH}\n    name: deploy\nproxyProfiles:\n  - name: egress\n    proxyUrl: http://127.0.0.1:3128\n    enabled: false\n"
+        ),
+    )
+    .expect("write config");
+    let _db = EnvGuard::set("CODEXMANAGER_DB_PATH", &db_path.to_string_lossy());
+    let _config = EnvGuard::set(ENV_CONFIG_FILE, &config_path.to_string_lossy());
+    crate::storage_helpers::initialize_storage().expect("init storage");
+
+    let first = apply_declarative_config(DeclarativeConfigSource::ConfiguredFile).expect("apply");
+    assert_eq!(first.plan.changes.len(), 2);
+    assert_eq!(first.report.applied, 2);
+    assert!(first.report.failed.is_empty());
+
+    let second =
+        apply_declarative_config(DeclarativeConfigSource::ConfiguredFile).expect("reapply");
+    assert!(second.plan.is_empty());
+    assert_eq!(second.plan.unchanged, 2);
+    assert_eq!(second.digest, first.digest);
+
+    let storage = crate::storage_helpers::open_storage().expect("open storage");
+    let key = storage
+        .find_api_key_by_hash(KEY_HASH)
+        .expect("read key")
+        .expect("key exists");
+    storage
+        .update_api_key_name(&key.id, Some("renamed"))
+        .expect("rename key");
+
+    let status = declarative_config_status();
+    assert_eq!(
+        status.last_apply.as_ref().map(|record| record.changes),
+        Some(0)
+    );
+    let drift = status.drift.expect("drift");
+    assert_eq!(drift.plan.changes.len(), 1);
+    assert_eq!(drift.plan.changes[0].key, KEY_HASH);
+    assert_eq!(drift.plan.changes[0].field("name"), Some(&"deploy".into()));
+
+    let _ = std::fs::remove_file(&config_path);
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[test]
+fn configured_file_sync_skips_unchanged_content_and_inline_diff_does_not_write() {
+    let _guard = crate::test_env_guard();
+    let db_path = isolated_path("sync-db", "db");
+    let config_path = isolated_path("sync-config", "toml");
+    std::fs::write(
+        &config_path,
+        "[[proxyProfiles]]\nname = \"sync\"\nproxyUrl = \"http://127.0.0.1:3129\"\n",
+    )
+    .expect("write config");
+    let _db = EnvGuard::set("CODEXMANAGER_DB_PATH", &db_path.to_string_lossy());
+    let _config = EnvGuard::set(ENV_CONFIG_FILE, &config_path.to_string_lossy());
+    crate::storage_helpers::initialize_storage().expect("init storage");
+
+    let digest = sync_configured_file(None).expect("digest");
+    assert_eq!(sync_configured_file(Some(digest.clone())), Some(digest));
+
+    let diff = diff_declarative_config(DeclarativeConfigSource::Inline {
+        content: "[[proxyProfiles]]\nname = \"preview\"\nproxyUrl = \"http://127.0.0.1:3130\"\n"
+            .to_string(),
+        format: DocumentFormat::Toml,
+    })
+    .expect("diff");
+    assert_eq!(diff.source, INLINE_SOURCE);
+    assert_eq!(diff.plan.changes.len(), 1);
+    let names = crate::storage_helpers::open_storage()
+        .expect("open storage")
+        .list_proxy_profiles()
+        .expect("list proxies")
+        .into_iter()
+        .map(|item| item.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["sync".to_string()]);
+
+    let _ = std::fs::remove_file(&config_path);
+    let _ = std::fs::remove_file(&db_path);
+}
@@ -0,0 +1,578 @@
+use codexmanager_core::storage::{
+    derive_proxy_profile_url_metadata, AggregateApi, ModelGroupModel, ProxyProfile, Storage,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::document::{
+    AggregateApiSpec, DeclarativeConfig, ModelGroupSpec, PlatformKeySpec, ProxyProfileSpec,
+};
+use crate::aggregate_api::{normalize_model_override, normalize_provider_type};
+use crate::apikey::service_tier::normalize_service_tier_owned;
+use crate::apikey_profile::normalize_upstream_base_url;
+use crate::reasoning_effort::normalize_reasoning_effort_owned;
+
+pub(crate) const RESOURCE_GATEWAY: &str = "gateway";
+pub(crate) const RESOURCE_PLATFORM_KEY: &str = "platformKey";
+pub(crate) const RESOURCE_AGGREGATE_API: &str = "aggregateApi";
+pub(crate) const RESOURCE_MODEL_GROUP: &str = "modelGroup";
+pub(crate) const RESOURCE_PROXY_PROFILE: &str = "proxyProfile";
+const REDACTED_SECRET: &str = "***";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConfigChangeAction {
+    Create,
+    Update,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigFieldChange {
+    pub(crate) field: String,
+    pub(crate) current: Value,
+    pub(crate) desired: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigResourceChange {
+    pub(crate) resource: &'static str,
+    pub(crate) key: String,
+    pub(crate) action: ConfigChangeAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) target_id: Option<String>,
+    pub(crate) fields: Vec<ConfigFieldChange>,
+}
+
+impl ConfigResourceChange {
+    pub(crate) fn field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|item| item.field == name)
+            .map(|item| &item.desired)
+    }
+}
+
+/// 声明式配置与当前数据库状态的差异；`changes` 为空即表示没有漂移。
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeclarativeConfigPlan {
+    pub(crate) changes: Vec<ConfigResourceChange>,
+    pub(crate) unchanged: usize,
+}
+
+impl DeclarativeConfigPlan {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn record(&mut self, change: Option<ConfigResourceChange>) {
+        match change {
+            Some(change) => self.changes.push(change),
+            None => self.unchanged += 1,
+        }
+    }
+}
+
+/// 逐项对比声明式配置与当前状态；文件中未出现的资源和字段不参与对比。
+pub(crate) fn plan_declarative_config(
+    storage: &Storage,
+    current_settings: &Value,
+    config: &DeclarativeConfig,
+) -> Result<DeclarativeConfigPlan, String> {
+    let mut plan = DeclarativeConfigPlan::default();
+    if !config.gateway.is_empty() {
+        plan.record(plan_gateway(current_settings, &config.gateway));
+    }
+    for spec in &config.platform_keys {
+        plan.record(plan_platform_key(storage, spec)?);
+    }
+    if !config.aggregate_apis.is_empty() {
+        let existing = storage
+            .list_aggregate_apis()
+            .map_err(|err| format!("list aggregate apis failed: {err}"))?;
+        for spec in &config.aggregate_apis {
+            plan.record(plan_aggregate_api(storage, &existing, spec)?);
+        }
+    }
+    if !config.model_groups.is_empty() {
+        let group_models = storage
+            .list_model_group_models_v2()
+            .map_err(|err| format!("list model group models failed: {err}"))?;
+        for spec in &config.model_groups {
+            plan.record(plan_model_group(storage, &group_models, spec)?);
+        }
+    }
+    if !config.proxy_profiles.is_empty() {
+        let existing = storage
+            .list_proxy_profiles()
+            .map_err(|err| format!("list proxy profiles failed: {err}"))?;
+        for spec in &config.proxy_profiles {
+            plan.record(plan_proxy_profile(&existing, spec)?);
+        }
+    }
+    Ok(plan)
+}
+
+fn resource_change(
+    resource: &'static str,
+    key: &str,
+    target_id: Option<String>,
+    fields: Vec<ConfigFieldChange>,
+) -> Option<ConfigResourceChange> {
+    if target_id.is_some() && fields.is_empty() {
+        return None;
+    }
+    Some(ConfigResourceChange {
+        resource,
+        key: key.to_string(),
+        action: if target_id.is_some() {
+            ConfigChangeAction::Update
+        } else {
+            ConfigChangeAction::Create
+        },
+        target_id,
+        fields,
+    })
+}
+
+fn compare_field(
+    fields: &mut Vec<ConfigFieldChange>,
+    field: &str,
+    current: Value,
+    desired: Option<Value>,
+) {
+    let Some(desired) = desired else {
+        return;
+    };
+    if current != desired {
+        fields.push(ConfigFieldChange {
+            field: field.to_string(),
+            current,
+            desired,
+        });
+    }
+}
+
+fn normalize_text(value: &str) -> Value {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        Value::Null
+    } else {
+        Value::from(trimmed)
+    }
+}
+
+/// 期望值只覆盖声明的键：对象按子键递归比较，标量允许与字符串形式的当前值相等。
+fn value_covers(current: &Value, desired: &Value) -> bool {
+    match (current, desired) {
+        (Value::Object(current), Value::Object(desired)) => desired.iter().all(|(key, value)| {
+            current
+                .get(key)
+                .is_some_and(|current| value_covers(current, value))
+        }),
+        (Value::Number(current), Value::Number(desired)) => current.as_f64() == desired.as_f64(),
+        (Value::String(current), Value::Number(_) | Value::Bool(_)) => {
+            serde_json::from_str::<Value>(current.trim())
+                .is_ok_and(|parsed| value_covers(&parsed, desired))
+        }
+        _ => current == desired,
+    }
+}
+
+fn plan_gateway(
+    current_settings: &Value,
+    desired: &Map<String, Value>,
+) -> Option<ConfigResourceChange> {
+    let fields = desired
+        .iter()
+        .filter_map(|(key, desired)| {
+            let current = current_settings.get(key).cloned().unwrap_or(Value::Null);
+            (!value_covers(&current, desired)).then(|| ConfigFieldChange {
+                field: key.clone(),
+                current,
+                desired: desired.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    resource_change(
+        RESOURCE_GATEWAY,
+        RESOURCE_GATEWAY,
+        Some(RESOURCE_GATEWAY.to_string()),
+        fields,
+    )
+}
+
+fn normalize_api_key_status(value: &str) -> Result<String, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "active" | "enabled" => Ok("active".to_string()),
+        "disabled" => Ok("disabled".to_string()),
+        other => Err(format!("unsupported platform key status: {other}")),
+    }
+}
+
+fn plan_platform_key(
+    storage: &Storage,
+    spec: &PlatformKeySpec,
+) -> Result<Option<ConfigResourceChange>, String> {
+    let existing = storage
+        .find_api_key_by_hash(spec.key_hash.as_str())
+        .map_err(|err| format!("read platform key failed: {err}"))?;
+    let service_tier = match spec.service_tier.clone() {
+        Some(raw) => Some(Value::from(normalize_service_tier_owned(Some(raw))?)),
+        None => None,
+    };
+    let status = match spec.status.as_deref() {
+        Some(raw) => Some(Value::from(normalize_api_key_status(raw)?)),
+        None => None,
+    };
+    let reasoning_effort = spec
+        .reasoning_effort
+        .clone()
+        .map(|raw| Value::from(normalize_reasoning_effort_owned(Some(raw))));
+    let (current, target_id) = match existing {
+        Some(key) => (Some(key.clone()), Some(key.id)),
+        None => (None, None),
+    };
+    let current_value = |read: fn(&codexmanager_core::storage::ApiKey) -> Value| {
+        current.as_ref().map(read).unwrap_or(Value::Null)
+    };
+
+    let mut fields = Vec::new();
+    compare_field(
+        &mut fields,
+        "name",
+        current_value(|key| Value::from(key.name.clone())),
+        spec.name.as_deref().map(normalize_text),
+    );
+    compare_field(
+        &mut fields,
+        "modelSlug",
+        current_value(|key| Value::from(key.model_slug.clone())),
+        spec.model_slug.as_deref().map(normalize_text),
+    );
+    compare_field(
+        &mut fields,
+        "reasoningEffort",
+        current_value(|key| Value::from(key.reasoning_effort.clone())),
+        reasoning_effort,
+    );
+    compare_field(
+        &mut fields,
+        "serviceTier",
+        current_value(|key| Value::from(key.service_tier.clone())),
+        service_tier,
+    );
+    compare_field(
+        &mut fields,
+        "status",
+        current_value(|key| Value::from(key.status.clone())),
+        status,
+    );
+    if current.is_none() {
+        // 中文注释：协议类型决定客户端画像，只在创建时生效，避免覆盖后台手动调整过的路由配置。
+        compare_field(
+            &mut fields,
+            "protocolType",
+            Value::Null,
+            spec.protocol_type.as_deref().map(normalize_text),
+        );
+    }
+    Ok(resource_change(
+        RESOURCE_PLATFORM_KEY,
+        spec.key_hash.as_str(),
+        target_id,
+        fields,
+    ))
+}
+
+fn plan_aggregate_api(
+    storage: &Storage,
+    existing: &[AggregateApi],
+    spec: &AggregateApiSpec,
+) -> Result<Option<ConfigResourceChange>, String> {
+    let supplier_name = spec.supplier_name.trim();
+    let current = existing
+        .iter()
+        .find(|item| item.supplier_name.as_deref().map(str::trim) == Some(supplier_name));
+    let provider_type = match spec.provider_type.clone() {
+        Some(raw) => Some(Value::from(normalize_provider_type(Some(raw))?)),
+        None => None,
+    };
+    let url = match spec.url.clone() {
+        Some(raw) => Some(Value::from(
+            normalize_upstream_base_url(Some(raw))?
+                .ok_or_else(|| format!("aggregate api `{supplier_name}` url is empty"))?,
+        )),
+        None => None,
+    };
+    let status = match spec.status.clone() {
+        Some(raw) => Some(Value::from(crate::aggregate_api::normalize_status(Some(
+            raw,
+        ))?)),
+        None => None,
+    };
+    let model_override = match spec.model_override.clone() {
+        Some(raw) => Some(Value::from(normalize_model_override(Some(raw))?)),
+        None => None,
+    };
+    let key = spec.resolved_key()?;
+    if current.is_none() && key.is_none() {
+        return Err(format!(
+            "aggregate api `{supplier_name}` requires key or keyEnv to be created"
+        ));
+    }
+
+    let mut fields = Vec::new();
+    compare_field(
+        &mut fields,
+        "providerType",
+        current
+            .map(|item| Value::from(item.provider_type.clone()))
+            .unwrap_or(Value::Null),
+        provider_type,
+    );
+    compare_field(
+        &mut fields,
+        "url",
+        current
+            .map(|item| Value::from(item.url.clone()))
+            .unwrap_or(Value::Null),
+        url,
+    );
+    compare_field(
+        &mut fields,
+        "sort",
+        current
+            .map(|item| Value::from(item.sort))
+            .unwrap_or(Value::Null),
+        spec.sort.map(Value::from),
+    );
+    compare_field(
+        &mut fields,
+        "status",
+        current
+            .map(|item| Value::from(item.status.clone()))
+            .unwrap_or(Value::Null),
+        status,
+    );
+    compare_field(
+        &mut fields,
+        "modelOverride",
+        current
+            .map(|item| Value::from(item.model_override.clone()))
+            .unwrap_or(Value::Null),
+        model_override,
+    );
+    if let Some(key) = key {
+        let current_secret = match current {
+            Some(item) => storage
+                .find_aggregate_api_secret_by_id(item.id.as_str())
+                .map_err(|err| format!("read aggregate api secret failed: {err}"))?,
+            None => None,
+        };
+        // 中文注释：密钥只比较是否一致，差异里统一遮蔽，避免 diff 结果泄露明文。
+        if current_secret.as_deref() != Some(key.as_str()) {
+            fields.push(ConfigFieldChange {
+                field: "key".to_string(),
+                current: current_secret
+                    .map(|_| Value::from(REDACTED_SECRET))
+                    .unwrap_or(Value::Null),
+                desired: Value::from(REDACTED_SECRET),
+            });
+        }
+    }
+    Ok(resource_change(
+        RESOURCE_AGGREGATE_API,
+        supplier_name,
+        current.map(|item| item.id.clone()),
+        fields,
+    ))
+}
+
+fn model_group_members_value(members: impl Iterator<Item = (String, bool, Option<i64>)>) -> Value {
+    let mut members = members.collect::<Vec<_>>();
+    members.sort_by(|left, right| left.0.cmp(&right.0));
+    members.dedup_by(|left, right| left.0 == right.0);
+    Value::Array(
+        members
+            .into_iter()
+            .map(|(slug, enabled, rate)| {
+                serde_json::json!({
+                    "platformModelSlug": slug,
+                    "enabled": enabled,
+                    "rateMultiplierMillis": rate,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// 模型组成员的规范化形式，供对比与写入共用。
+pub(crate) fn desired_model_group_members(spec: &ModelGroupSpec) -> Option<Value> {
+    let members = spec.models.clone()?;
+    Some(model_group_members_value(
+        members
+            .into_iter()
+            .map(|member| member.into_params())
+            .filter_map(|params| {
+                let slug = params.platform_model_slug.trim().to_string();
+                (!slug.is_empty()).then(|| {
+                    (
+                        slug,
+                        params.enabled.unwrap_or(true),
+                        params
+                            .rate_multiplier_millis
+                            .map(|value| value.clamp(0, 100_000)),
+                    )
+                })
+            }),
+    ))
+}
+
+fn plan_model_group(
+    storage: &Storage,
+    group_models: &[ModelGroupModel],
+    spec: &ModelGroupSpec,
+) -> Result<Option<ConfigResourceChange>, String> {
+    let id = spec.id.trim();
+    let current = storage
+        .find_model_group(id)
+        .map_err(|err| format!("read model group failed: {err}"))?;
+    let status = match spec.status.as_deref() {
+        Some(raw) => Some(Value::from(crate::model_groups::normalize_status(Some(
+            raw,
+        ))?)),
+        None => None,
+    };
+    let current_members = || {
+        model_group_members_value(
+            group_models
+                .iter()
+                .filter(|member| member.group_id == id)
+                .map(|member| {
+                    (
+                        member.platform_model_slug.clone(),
+                        member.enabled,
+                        member.rate_multiplier_millis,
+                    )
+                }),
+        )
+    };
+
+    let mut fields = Vec::new();
+    compare_field(
+        &mut fields,
+        "name",
+        current
+            .as_ref()
+            .map(|group| Value::from(group.name.clone()))
+            .unwrap_or(Value::Null),
+        Some(normalize_text(spec.name.as_str())),
+    );
+    compare_field(
+        &mut fields,
+        "description",
+        current
+            .as_ref()
+            .map(|group| Value::from(group.description.clone()))
+            .unwrap_or(Value::Null),
+        spec.description.as_deref().map(normalize_text),
+    );
+    compare_field(
+        &mut fields,
+        "status",
+        current
+            .as_ref()
+            .map(|group| Value::from(group.status.clone()))
+            .unwrap_or(Value::Null),
+        status,
+    );
+    compare_field(
+        &mut fields,
+        "sort",
+        current
+            .as_ref()
+            .map(|group| Value::from(group.sort))
+            .unwrap_or(Value::Null),
+        spec.sort.map(Value::from),
+    );
+    compare_field(
+        &mut fields,
+        "rateMultiplierMillis",
+        current
+            .as_ref()
+            .map(|group| Value::from(group.rate_multiplier_millis))
+            .unwrap_or(Value::Null),
+        spec.rate_multiplier_millis
+            .map(|value| Value::from(crate::model_groups::normalize_rate(Some(value)))),
+    );
+    compare_field(
+        &mut fields,
+        "models",
+        if current.is_some() {
+            current_members()
+        } else {
+            Value::Null
+        },
+        desired_model_group_members(spec),
+    );
+    Ok(resource_change(
+        RESOURCE_MODEL_GROUP,
+        id,
+        current.map(|group| group.id),
+        fields,
+    ))
+}
+
+fn plan_proxy_profile(
+    existing: &[ProxyProfile],
+    spec: &ProxyProfileSpec,
+) -> Result<Option<ConfigResourceChange>, String> {
+    let name = spec.name.trim();
+    let current = existing.iter().find(|item| item.name.trim() == name);
+    let proxy_url =
+        crate::proxy_registry::validation::normalize_proxy_profile_url(spec.proxy_url.trim())?;
+
+    let mut fields = Vec::new();
+    if current.map(|item| item.proxy_url.as_str()) != Some(proxy_url.as_str()) {
+        fields.push(ConfigFieldChange {
+            field: "proxyUrl".to_string(),
+            current: current
+                .map(|item| Value::from(item.proxy_url_redacted.clone()))
+                .unwrap_or(Value::Null),
+            desired: Value::from(derive_proxy_profile_url_metadata(&proxy_url).proxy_url_redacted),
+        });
+    }
+    compare_field(
+        &mut fields,
+        "enabled",
+        current
+            .map(|item| Value::from(item.enabled))
+            .unwrap_or(Value::Null),
+        spec.enabled.map(Value::from),
+    );
+    // 中文注释：代理更新接口无法清空备注，空备注视为不托管，避免每次都报告漂移。
+    compare_field(
+        &mut fields,
+        "notes",
+        current
+            .map(|item| Value::from(item.notes.clone()))
+            .unwrap_or(Value::Null),
+        spec.notes
+            .as_deref()
+            .map(normalize_text)
+            .filter(|value| !value.is_null()),
+    );
+    Ok(resource_change(
+        RESOURCE_PROXY_PROFILE,
+        name,
+        current.map(|item| item.id.clone()),
+        fields,
+    ))
+}
+
+#[cfg(test)]
+#[path = "plan_tests.rs"]
+mod tests;
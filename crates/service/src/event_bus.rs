@@ -0,0 +1,239 @@
+use codexmanager_core::storage::now_ts;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::RpcActor;
+
+pub(crate) const TOPIC_ACCOUNT_STATUS: &str = "account.status";
+pub(crate) const TOPIC_ACCOUNT_COOLDOWN: &str = "account.cooldown";
+pub(crate) const TOPIC_REQUEST_COMPLETED: &str = "request.completed";
+pub(crate) const TOPIC_AGGREGATE_API_BALANCE: &str = "aggregate_api.balance";
+pub(crate) const TOPIC_SETTINGS_CHANGED: &str = "settings.changed";
+pub(crate) const TOPIC_USAGE_REFRESH: &str = "usage.refresh";
+pub(crate) const EVENT_TOPICS: [&str; 6] = [
+    TOPIC_ACCOUNT_STATUS,
+    TOPIC_ACCOUNT_COOLDOWN,
+    TOPIC_REQUEST_COMPLETED,
+    TOPIC_AGGREGATE_API_BALANCE,
+    TOPIC_SETTINGS_CHANGED,
+    TOPIC_USAGE_REFRESH,
+];
+
+const EVENT_BACKLOG_CAPACITY: usize = 512;
+const SUBSCRIBER_BUFFER_CAPACITY: usize = 256;
+
+static EVENT_BUS: OnceLock<Mutex<EventBusState>> = OnceLock::new();
+static EVENT_EPOCH: OnceLock<u64> = OnceLock::new();
+
+/// 事件可见范围：管理员可见全部；成员只能看到归属自己的事件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum EventAudience {
+    Admin,
+    User(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServerEvent {
+    /// 进程内递增序号；对外序列化为 `<boot_ts>-<seq>`。
+    #[serde(serialize_with = "serialize_event_id")]
+    pub(crate) id: u64,
+    pub(crate) topic: &'static str,
+    pub(crate) at: i64,
+    pub(crate) data: Value,
+    #[serde(skip)]
+    pub(crate) audience: EventAudience,
+}
+
+impl ServerEvent {
+    pub(crate) fn event_id(&self) -> String {
+        format_event_id(self.id)
+    }
+
+    pub(crate) fn visible_to(&self, actor: &RpcActor) -> bool {
+        if actor.is_admin() {
+            return true;
+        }
+        match &self.audience {
+            EventAudience::Admin => false,
+            EventAudience::User(user_id) => actor.user_id.as_deref() == Some(user_id.as_str()),
+        }
+    }
+}
+
+/// 逗号分隔的主题过滤；支持 `*` 与 `account.*` 这类前缀通配，为空表示全部主题。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TopicFilter {
+    patterns: Vec<String>,
+}
+
+impl TopicFilter {
+    pub(crate) fn parse(raw: Option<&str>) -> Result<Self, String> {
+        let mut patterns: Vec<String> = Vec::new();
+        for item in raw.unwrap_or_default().split(',') {
+            let item = item.trim().to_ascii_lowercase();
+            if item.is_empty() {
+                continue;
+            }
+            if item == "*" {
+                return Ok(Self::default());
+            }
+            if !EVENT_TOPICS
+                .iter()
+                .any(|topic| topic_matches_pattern(topic, item.as_str()))
+            {
+                return Err(format!("unknown event topic: {item}"));
+            }
+            if !patterns.contains(&item) {
+                patterns.push(item);
+            }
+        }
+        Ok(Self { patterns })
+    }
+
+    pub(crate) fn matches(&self, topic: &str) -> bool {
+        self.patterns.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| topic_matches_pattern(topic, pattern.as_str()))
+    }
+}
+
+/// 本进程的事件纪元（首次使用时的毫秒时间戳），服务重启后随之变化。
+fn event_epoch() -> u64 {
+    *EVENT_EPOCH.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default()
+    })
+}
+
+pub(crate) fn format_event_id(seq: u64) -> String {
+    format!("{}-{seq}", event_epoch())
+}
+
+/// 解析 `<boot_ts>-<seq>`；只有纪元与当前进程一致时才返回序号。
+fn current_epoch_seq(event_id: &str) -> Option<u64> {
+    let (epoch, seq) = event_id.trim().split_once('-')?;
+    if epoch.parse::<u64>().ok()? != event_epoch() {
+        return None;
+    }
+    seq.parse::<u64>().ok()
+}
+
+fn serialize_event_id<S: Serializer>(seq: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(format_event_id(*seq).as_str())
+}
+
+fn topic_matches_pattern(topic: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => topic == pattern,
+    }
+}
+
+/// 单个订阅连接的过滤条件：主题 + 调用者可见范围。
+#[derive(Debug, Clone)]
+pub(crate) struct EventStreamFilter {
+    pub(crate) topics: TopicFilter,
+    pub(crate) actor: RpcActor,
+}
+
+impl EventStreamFilter {
+    pub(crate) fn accepts(&self, event: &ServerEvent) -> bool {
+        self.topics.matches(event.topic) && event.visible_to(&self.actor)
+    }
+}
+
+pub(crate) struct EventSubscription {
+    /// 断线重连时补发的事件（序号大于 Last-Event-ID）。
+    pub(crate) replay: Vec<Arc<ServerEvent>>,
+    /// 请求的 Last-Event-ID 已滚出缓冲区或来自上一个进程，客户端需要全量刷新。
+    pub(crate) missed: bool,
+    pub(crate) receiver: Receiver<Arc<ServerEvent>>,
+}
+
+#[derive(Default)]
+struct EventBusState {
+    last_id: u64,
+    backlog: VecDeque<Arc<ServerEvent>>,
+    subscribers: Vec<Sender<Arc<ServerEvent>>>,
+}
+
+fn event_bus() -> &'static Mutex<EventBusState> {
+    EVENT_BUS.get_or_init(|| Mutex::new(EventBusState::default()))
+}
+
+pub(crate) fn publish_event(topic: &'static str, audience: EventAudience, data: Value) {
+    let mut guard = crate::lock_utils::lock_recover(event_bus(), "event_bus");
+    let state = &mut *guard;
+    state.last_id += 1;
+    let event = Arc::new(ServerEvent {
+        id: state.last_id,
+        topic,
+        at: now_ts(),
+        data,
+        audience,
+    });
+    if state.backlog.len() >= EVENT_BACKLOG_CAPACITY {
+        state.backlog.pop_front();
+    }
+    state.backlog.push_back(event.clone());
+    // 中文注释：消费过慢的订阅直接断开，客户端带 Last-Event-ID 重连即可从缓冲区补齐。
+    state
+        .subscribers
+        .retain(|sender| sender.try_send(event.clone()).is_ok());
+}
+
+/// 当前是否有活跃订阅；发布方据此跳过只为事件准备数据的额外查询。
+pub(crate) fn has_event_subscribers() -> bool {
+    !crate::lock_utils::lock_recover(event_bus(), "event_bus")
+        .subscribers
+        .is_empty()
+}
+
+/// 注册订阅并在同一把锁内取出补发事件，保证补发与实时推送之间不丢不重。
+pub(crate) fn subscribe_events(last_event_id: Option<&str>) -> EventSubscription {
+    let (sender, receiver) = bounded(SUBSCRIBER_BUFFER_CAPACITY);
+    // 中文注释：纪元不同（服务已重启）或格式无法识别时序号不可比较，直接要求全量刷新。
+    let last_seq = last_event_id.map(current_epoch_seq);
+    let mut guard = crate::lock_utils::lock_recover(event_bus(), "event_bus");
+    let state = &mut *guard;
+    let (replay, missed) = match last_seq {
+        None => (Vec::new(), false),
+        Some(None) => (Vec::new(), true),
+        Some(Some(last_event_id)) if last_event_id < state.last_id => {
+            let oldest = state
+                .backlog
+                .front()
+                .map(|event| event.id)
+                .unwrap_or(state.last_id + 1);
+            let replay = state
+                .backlog
+                .iter()
+                .filter(|event| event.id > last_event_id)
+                .cloned()
+                .collect();
+            (replay, last_event_id + 1 < oldest)
+        }
+        Some(Some(last_event_id)) => (Vec::new(), last_event_id > state.last_id),
+    };
+    state.subscribers.push(sender);
+    EventSubscription {
+        replay,
+        missed,
+        receiver,
+    }
+}
+
+#[cfg(test)]
+#[path = "event_bus_tests.rs"]
+mod tests;
@@ -0,0 +1,99 @@
+use super::*;
+use serde_json::json;
+
+fn member(user_id: &str) -> RpcActor {
+    RpcActor::from_parts(Some(crate::ROLE_MEMBER), Some(user_id))
+}
+
+#[test]
+fn topic_filter_supports_exact_prefix_and_wildcard() {
+    let all = TopicFilter::parse(Some(" * ")).expect("wildcard");
+    assert!(all.matches(TOPIC_SETTINGS_CHANGED));
+    assert_eq!(
+        TopicFilter::parse(None).expect("empty"),
+        TopicFilter::default()
+    );
+
+    let filter = TopicFilter::parse(Some("account.*, request.completed")).expect("parse");
+    assert!(filter.matches(TOPIC_ACCOUNT_STATUS));
+    assert!(filter.matches(TOPIC_ACCOUNT_COOLDOWN));
+    assert!(filter.matches(TOPIC_REQUEST_COMPLETED));
+    assert!(!filter.matches(TOPIC_AGGREGATE_API_BALANCE));
+
+    let err = TopicFilter::parse(Some("account.*,billing.*")).expect_err("unknown topic");
+    assert!(err.contains("billing.*"));
+    assert!(TopicFilter::parse(Some("acc.*")).is_err());
+}
+
+#[test]
+fn member_only_sees_own_user_scoped_events() {
+    let event = |audience| ServerEvent {
+        id: 1,
+        topic: TOPIC_REQUEST_COMPLETED,
+        at: 0,
+        data: json!({}),
+        audience,
+    };
+    let own = event(EventAudience::User("user-1".to_string()));
+    let other = event(EventAudience::User("user-2".to_string()));
+    let admin_only = event(EventAudience::Admin);
+
+    assert!(own.visible_to(&member("user-1")));
+    assert!(!other.visible_to(&member("user-1")));
+    assert!(!admin_only.visible_to(&member("user-1")));
+    assert!(other.visible_to(&RpcActor::system_admin()));
+    assert!(admin_only.visible_to(&RpcActor::from_parts(Some(crate::ROLE_ADMIN), None)));
+}
+
+#[test]
+fn subscribe_replays_after_last_event_id_and_streams_live_events() {
+    let checkpoint = subscribe_events(None);
+    assert!(checkpoint.replay.is_empty());
+    assert!(!checkpoint.missed);
+    assert!(has_event_subscribers());
+
+    // 中文注释：其他并行测试也会向全局总线发布事件，按标记找到本测试的事件。
+    publish_event(
+        TOPIC_SETTINGS_CHANGED,
+        EventAudience::Admin,
+        json!({ "keys": ["event-bus-live-marker"] }),
+    );
+    let live = std::iter::from_fn(|| checkpoint.receiver.recv().ok())
+        .find(|event| event.data["keys"][0] == "event-bus-live-marker")
+        .expect("live event");
+
+    let resumed = subscribe_events(Some(format_event_id(live.id - 1).as_str()));
+    assert!(!resumed.missed);
+    assert_eq!(resumed.replay.first().map(|event| event.id), Some(live.id));
+
+    let ahead = subscribe_events(Some(format_event_id(u64::MAX).as_str()));
+    assert!(ahead.missed);
+    assert!(ahead.replay.is_empty());
+}
+
+#[test]
+fn event_ids_carry_process_epoch_and_force_resync_across_restarts() {
+    let event_id = format_event_id(7);
+    let (epoch, seq) = event_id.split_once('-').expect("epoch separator");
+    assert_eq!(epoch.parse::<u64>().expect("epoch"), event_epoch());
+    assert_eq!(seq, "7");
+    assert_eq!(current_epoch_seq(event_id.as_str()), Some(7));
+
+    // 中文注释：上一个进程的编号即使序号更小也不能拿来补发，必须全量刷新。
+    let previous_process = format!("{}-1", event_epoch() - 1);
+    for stale in [previous_process.as_str(), "1", "bogus"] {
+        let subscription = subscribe_events(Some(stale));
+        assert!(subscription.missed, "{stale}");
+        assert!(subscription.replay.is_empty(), "{stale}");
+    }
+
+    let event = ServerEvent {
+        id: 3,
+        topic: TOPIC_USAGE_REFRESH,
+        at: 0,
+        data: json!({}),
+        audience: EventAudience::Admin,
+    };
+    let value = serde_json::to_value(&event).expect("event json");
+    assert_eq!(value["id"], format_event_id(3));
+}
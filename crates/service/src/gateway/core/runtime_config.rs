@@ -47,6 +47,22 @@ static THREAD_AWARE_ACCOUNT_DISTRIBUTION: AtomicBool =
     AtomicBool::new(DEFAULT_THREAD_AWARE_ACCOUNT_DISTRIBUTION);
 static STRICT_REQUEST_PARAM_ALLOWLIST: AtomicBool =
     AtomicBool::new(DEFAULT_STRICT_REQUEST_PARAM_ALLOWLIST);
+static CONTEXT_WINDOW_PREFLIGHT: AtomicBool = AtomicBool::new(DEFAULT_CONTEXT_WINDOW_PREFLIGHT);
+static RESPONSE_CACHE_MAX_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_RESPONSE_CACHE_MAX_BYTES);
+static RESPONSE_CACHE_MAX_ENTRY_BYTES: AtomicUsize =
+    AtomicUsize::new(DEFAULT_RESPONSE_CACHE_MAX_ENTRY_BYTES);
+static RESPONSE_STORE_MAX_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_RESPONSE_STORE_MAX_BYTES);
+static BACKGROUND_RESPONSE_MAX_CONCURRENCY: AtomicUsize =
+    AtomicUsize::new(DEFAULT_BACKGROUND_RESPONSE_MAX_CONCURRENCY);
+static BACKGROUND_RESPONSE_RETENTION_SECS: AtomicU64 =
+    AtomicU64::new(DEFAULT_BACKGROUND_RESPONSE_RETENTION_SECS);
+static BATCH_MAX_CONCURRENCY: AtomicUsize = AtomicUsize::new(DEFAULT_BATCH_MAX_CONCURRENCY);
+static BATCH_FILE_MAX_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_BATCH_FILE_MAX_BYTES);
+static STRUCTURED_OUTPUT_MAX_REPAIRS: AtomicUsize =
+    AtomicUsize::new(DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS);
+static CHAT_FAN_OUT_MAX_N: AtomicUsize = AtomicUsize::new(DEFAULT_CHAT_FAN_OUT_MAX_N);
+static CHAT_FAN_OUT_MAX_CONCURRENCY: AtomicUsize =
+    AtomicUsize::new(DEFAULT_CHAT_FAN_OUT_MAX_CONCURRENCY);
 static ENABLE_REQUEST_COMPRESSION: AtomicBool = AtomicBool::new(DEFAULT_ENABLE_REQUEST_COMPRESSION);
 static USE_WEBSOCKET_UPSTREAM: AtomicBool = AtomicBool::new(DEFAULT_USE_WEBSOCKET_UPSTREAM);
 static CODEX_IMAGE_GENERATION_ENABLED: AtomicBool =
@@ -65,6 +81,7 @@ static CODEX_USER_AGENT_VERSION: OnceLock<RwLock<String>> = OnceLock::new();
 static RESIDENCY_REQUIREMENT: OnceLock<RwLock<Option<String>>> = OnceLock::new();
 static TOKEN_EXCHANGE_CLIENT_ID: OnceLock<RwLock<String>> = OnceLock::new();
 static TOKEN_EXCHANGE_ISSUER: OnceLock<RwLock<String>> = OnceLock::new();
+static STRUCTURED_OUTPUT_VALIDATION_MODELS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
 
 pub(crate) const DEFAULT_GATEWAY_DEBUG: bool = false;
 const DEFAULT_UPSTREAM_CONNECT_TIMEOUT_SECS: u64 = 15;
@@ -75,6 +92,18 @@ const DEFAULT_SSE_KEEPALIVE_INTERVAL_MS: u64 = 15_000;
 const DEFAULT_ACCOUNT_MAX_INFLIGHT: usize = 0;
 const DEFAULT_THREAD_AWARE_ACCOUNT_DISTRIBUTION: bool = true;
 const DEFAULT_STRICT_REQUEST_PARAM_ALLOWLIST: bool = false;
+const DEFAULT_CONTEXT_WINDOW_PREFLIGHT: bool = false;
+const DEFAULT_RESPONSE_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+const DEFAULT_RESPONSE_CACHE_MAX_ENTRY_BYTES: usize = 4 * 1024 * 1024;
+const DEFAULT_RESPONSE_STORE_MAX_BYTES: usize = 256 * 1024 * 1024;
+const DEFAULT_BACKGROUND_RESPONSE_MAX_CONCURRENCY: usize = 4;
+const DEFAULT_BACKGROUND_RESPONSE_RETENTION_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_BATCH_MAX_CONCURRENCY: usize = 2;
+const DEFAULT_BATCH_FILE_MAX_BYTES: usize = 200 * 1024 * 1024;
+const DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS: usize = 1;
+const MAX_STRUCTURED_OUTPUT_MAX_REPAIRS: usize = 3;
+const DEFAULT_CHAT_FAN_OUT_MAX_N: usize = 8;
+const DEFAULT_CHAT_FAN_OUT_MAX_CONCURRENCY: usize = 8;
 const DEFAULT_ENABLE_REQUEST_COMPRESSION: bool = true;
 const DEFAULT_USE_WEBSOCKET_UPSTREAM: bool = false;
 const DEFAULT_CODEX_IMAGE_GENERATION_ENABLED: bool = true;
@@ -104,6 +133,21 @@ const ENV_SSE_KEEPALIVE_ENABLED: &str = "CODEXMANAGER_SSE_KEEPALIVE_ENABLED";
 const ENV_SSE_KEEPALIVE_INTERVAL_MS: &str = "CODEXMANAGER_SSE_KEEPALIVE_INTERVAL_MS";
 const ENV_ACCOUNT_MAX_INFLIGHT: &str = "CODEXMANAGER_ACCOUNT_MAX_INFLIGHT";
 const ENV_STRICT_REQUEST_PARAM_ALLOWLIST: &str = "CODEXMANAGER_STRICT_REQUEST_PARAM_ALLOWLIST";
+const ENV_CONTEXT_WINDOW_PREFLIGHT: &str = "CODEXMANAGER_CONTEXT_WINDOW_PREFLIGHT";
+const ENV_RESPONSE_CACHE_MAX_BYTES: &str = "CODEXMANAGER_RESPONSE_CACHE_MAX_BYTES";
+const ENV_RESPONSE_CACHE_MAX_ENTRY_BYTES: &str = "CODEXMANAGER_RESPONSE_CACHE_MAX_ENTRY_BYTES";
+const ENV_RESPONSE_STORE_MAX_BYTES: &str = "CODEXMANAGER_RESPONSE_STORE_MAX_BYTES";
+const ENV_BACKGROUND_RESPONSE_MAX_CONCURRENCY: &str =
+    "CODEXMANAGER_BACKGROUND_RESPONSE_MAX_CONCURRENCY";
+const ENV_BACKGROUND_RESPONSE_RETENTION_SECS: &str =
+    "CODEXMANAGER_BACKGROUND_RESPONSE_RETENTION_SECS";
+const ENV_BATCH_MAX_CONCURRENCY: &str = "CODEXMANAGER_BATCH_MAX_CONCURRENCY";
+const ENV_BATCH_FILE_MAX_BYTES: &str = "CODEXMANAGER_BATCH_FILE_MAX_BYTES";
+const ENV_STRUCTURED_OUTPUT_VALIDATION_MODELS: &str =
+    "CODEXMANAGER_STRUCTURED_OUTPUT_VALIDATION_MODELS";
+const ENV_STRUCTURED_OUTPUT_MAX_REPAIRS: &str = "CODEXMANAGER_STRUCTURED_OUTPUT_MAX_REPAIRS";
+const ENV_CHAT_FAN_OUT_MAX_N: &str = "CODEXMANAGER_CHAT_FAN_OUT_MAX_N";
+const ENV_CHAT_FAN_OUT_MAX_CONCURRENCY: &str = "CODEXMANAGER_CHAT_FAN_OUT_MAX_CONCURRENCY";
 const ENV_ENABLE_REQUEST_COMPRESSION: &str = "CODEXMANAGER_ENABLE_REQUEST_COMPRESSION";
 const ENV_USE_WEBSOCKET_UPSTREAM: &str = "CODEXMANAGER_USE_WEBSOCKET_UPSTREAM";
 const ENV_CODEX_IMAGE_GENERATION_ENABLED: &str = "CODEXMANAGER_CODEX_IMAGE_GENERATION_ENABLED";
@@ -1001,6 +1045,82 @@ pub(crate) fn strict_request_param_allowlist_enabled() -> bool {
     STRICT_REQUEST_PARAM_ALLOWLIST.load(Ordering::Relaxed)
 }
 
+/// 是否在转发前按模型目录 `context_window` 预检输入 token。
+pub(crate) fn context_window_preflight_enabled() -> bool {
+    ensure_runtime_config_loaded();
+    CONTEXT_WINDOW_PREFLIGHT.load(Ordering::Relaxed)
+}
+
+/// 响应缓存总字节预算；超出后按最近使用时间淘汰。
+pub(crate) fn response_cache_max_bytes() -> usize {
+    ensure_runtime_config_loaded();
+    RESPONSE_CACHE_MAX_BYTES.load(Ordering::Relaxed)
+}
+
+/// 单条响应缓存的字节上限；更大的响应照常转发但不入缓存。
+pub(crate) fn response_cache_max_entry_bytes() -> usize {
+    ensure_runtime_config_loaded();
+    RESPONSE_CACHE_MAX_ENTRY_BYTES.load(Ordering::Relaxed)
+}
+
+/// 网关侧 Responses 存储总字节预算；为 0 时不记录也不本地展开 `previous_response_id`。
+pub(crate) fn response_store_max_bytes() -> usize {
+    ensure_runtime_config_loaded();
+    RESPONSE_STORE_MAX_BYTES.load(Ordering::Relaxed)
+}
+
+/// 按模型开启结构化输出校验的模型列表；`*` 表示全部模型。
+pub(crate) fn structured_output_validation_models() -> Vec<String> {
+    ensure_runtime_config_loaded();
+    crate::lock_utils::read_recover(
+        structured_output_validation_models_cell(),
+        "structured_output_validation_models",
+    )
+    .clone()
+}
+
+/// 按模型开启校验时的修复重试次数上限。
+pub(crate) fn structured_output_max_repairs() -> usize {
+    ensure_runtime_config_loaded();
+    STRUCTURED_OUTPUT_MAX_REPAIRS.load(Ordering::Relaxed)
+}
+
+/// Chat Completions `n` 拆分的上限；为 0 时不拆分，`n` 原样透传。
+pub(crate) fn chat_fan_out_max_n() -> usize {
+    ensure_runtime_config_loaded();
+    CHAT_FAN_OUT_MAX_N.load(Ordering::Relaxed)
+}
+
+/// 全部 `n` 拆分请求同时在途的子请求上限。
+pub(crate) fn chat_fan_out_max_concurrency() -> usize {
+    ensure_runtime_config_loaded();
+    CHAT_FAN_OUT_MAX_CONCURRENCY.load(Ordering::Relaxed)
+}
+
+/// 同时运行的后台 Responses 任务上限；为 0 时不接受 `background: true`。
+pub(crate) fn background_response_max_concurrency() -> usize {
+    ensure_runtime_config_loaded();
+    BACKGROUND_RESPONSE_MAX_CONCURRENCY.load(Ordering::Relaxed)
+}
+
+/// 已结束的后台 Responses 任务保留时长（秒）。
+pub(crate) fn background_response_retention_secs() -> u64 {
+    ensure_runtime_config_loaded();
+    BACKGROUND_RESPONSE_RETENTION_SECS.load(Ordering::Relaxed)
+}
+
+/// 单个批任务同时执行的请求数；为 0 时关闭 `/v1/files` 与 `/v1/batches` 模拟。
+pub(crate) fn batch_max_concurrency() -> usize {
+    ensure_runtime_config_loaded();
+    BATCH_MAX_CONCURRENCY.load(Ordering::Relaxed)
+}
+
+/// 批处理输入文件的大小上限（字节）。
+pub(crate) fn batch_file_max_bytes() -> usize {
+    ensure_runtime_config_loaded();
+    BATCH_FILE_MAX_BYTES.load(Ordering::Relaxed)
+}
+
 /// 函数 `request_gate_wait_timeout`
 ///
 /// 作者: gaohongshun
@@ -1674,6 +1794,81 @@ pub(super) fn reload_from_env() {
         ),
         Ordering::Relaxed,
     );
+    CONTEXT_WINDOW_PREFLIGHT.store(
+        env_bool_or(
+            ENV_CONTEXT_WINDOW_PREFLIGHT,
+            DEFAULT_CONTEXT_WINDOW_PREFLIGHT,
+        ),
+        Ordering::Relaxed,
+    );
+    RESPONSE_CACHE_MAX_BYTES.store(
+        env_usize_or(
+            ENV_RESPONSE_CACHE_MAX_BYTES,
+            DEFAULT_RESPONSE_CACHE_MAX_BYTES,
+        ),
+        Ordering::Relaxed,
+    );
+    RESPONSE_CACHE_MAX_ENTRY_BYTES.store(
+        env_usize_or(
+            ENV_RESPONSE_CACHE_MAX_ENTRY_BYTES,
+            DEFAULT_RESPONSE_CACHE_MAX_ENTRY_BYTES,
+        ),
+        Ordering::Relaxed,
+    );
+    RESPONSE_STORE_MAX_BYTES.store(
+        env_usize_or(
+            ENV_RESPONSE_STORE_MAX_BYTES,
+            DEFAULT_RESPONSE_STORE_MAX_BYTES,
+        ),
+        Ordering::Relaxed,
+    );
+    BACKGROUND_RESPONSE_MAX_CONCURRENCY.store(
+        env_usize_or(
+            ENV_BACKGROUND_RESPONSE_MAX_CONCURRENCY,
+            DEFAULT_BACKGROUND_RESPONSE_MAX_CONCURRENCY,
+        ),
+        Ordering::Relaxed,
+    );
+    BACKGROUND_RESPONSE_RETENTION_SECS.store(
+        env_u64_or(
+            ENV_BACKGROUND_RESPONSE_RETENTION_SECS,
+            DEFAULT_BACKGROUND_RESPONSE_RETENTION_SECS,
+        ),
+        Ordering::Relaxed,
+    );
+    BATCH_MAX_CONCURRENCY.store(
+        env_usize_or(ENV_BATCH_MAX_CONCURRENCY, DEFAULT_BATCH_MAX_CONCURRENCY),
+        Ordering::Relaxed,
+    );
+    STRUCTURED_OUTPUT_MAX_REPAIRS.store(
+        env_usize_or(
+            ENV_STRUCTURED_OUTPUT_MAX_REPAIRS,
+            DEFAULT_STRUCTURED_OUTPUT_MAX_REPAIRS,
+        )
+        .min(MAX_STRUCTURED_OUTPUT_MAX_REPAIRS),
+        Ordering::Relaxed,
+    );
+    CHAT_FAN_OUT_MAX_N.store(
+        env_usize_or(ENV_CHAT_FAN_OUT_MAX_N, DEFAULT_CHAT_FAN_OUT_MAX_N),
+        Ordering::Relaxed,
+    );
+    CHAT_FAN_OUT_MAX_CONCURRENCY.store(
+        env_usize_or(
+            ENV_CHAT_FAN_OUT_MAX_CONCURRENCY,
+            DEFAULT_CHAT_FAN_OUT_MAX_CONCURRENCY,
+        ),
+        Ordering::Relaxed,
+    );
+    *crate::lock_utils::write_recover(
+        structured_output_validation_models_cell(),
+        "structured_output_validation_models",
+    ) = parse_structured_output_validation_models(
+        env_non_empty(ENV_STRUCTURED_OUTPUT_VALIDATION_MODELS).as_deref(),
+    );
+    BATCH_FILE_MAX_BYTES.store(
+        env_usize_or(ENV_BATCH_FILE_MAX_BYTES, DEFAULT_BATCH_FILE_MAX_BYTES),
+        Ordering::Relaxed,
+    );
     ENABLE_REQUEST_COMPRESSION.store(
         env_bool_or(
             ENV_ENABLE_REQUEST_COMPRESSION,
@@ -2125,6 +2320,10 @@ fn upstream_proxy_bypass_hosts_cell() -> &'static RwLock<Vec<String>> {
     UPSTREAM_PROXY_BYPASS_HOSTS.get_or_init(|| RwLock::new(Vec::new()))
 }
 
+fn structured_output_validation_models_cell() -> &'static RwLock<Vec<String>> {
+    STRUCTURED_OUTPUT_VALIDATION_MODELS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
 /// 函数 `free_account_max_model_cell`
 ///
 /// 作者: gaohongshun
@@ -2933,6 +3132,20 @@ fn normalize_upstream_proxy_url(proxy_url: Option<&str>) -> Result<Option<String
     Ok(normalized)
 }
 
+fn parse_structured_output_validation_models(raw: Option<&str>) -> Vec<String> {
+    raw.unwrap_or_default()
+        .split([',', ';', '\n', '\r'])
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_ascii_lowercase)
+        .fold(Vec::new(), |mut models, model| {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+            models
+        })
+}
+
 fn parse_upstream_proxy_bypass_hosts(raw: &str) -> Vec<String> {
     raw.split(|ch| matches!(ch, ',' | ';' | '\n' | '\r'))
         .filter_map(normalize_upstream_proxy_bypass_host)
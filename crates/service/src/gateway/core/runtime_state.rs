@@ -0,0 +1,793 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub(crate) const ENV_RUNTIME_STATE_URL: &str = "CODEXMANAGER_RUNTIME_STATE_URL";
+const REDIS_KEY_PREFIX: &str = "codexmanager:";
+const REDIS_DEFAULT_PORT: u16 = 6379;
+const REDIS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REDIS_IO_TIMEOUT: Duration = Duration::from_secs(2);
+const REDIS_RECONNECT_BACKOFF_BASE: Duration = Duration::from_millis(500);
+const REDIS_RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(30);
+const REDIS_MAX_IDLE_CONNECTIONS: usize = 8;
+// 中文注释：清理过期租约、判断上限与写入新租约必须在一次脚本里完成，
+// 否则多副本同时看到“未满”后各自写入，上限会被突破。
+const REDIS_ACQUIRE_LEASE_SCRIPT: &str = r"
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', ARGV[1])
+local limit = tonumber(ARGV[4])
+if limit > 0 and redis.call('ZCARD', KEYS[1]) >= limit then return 0 end
+redis.call('ZADD', KEYS[1], ARGV[2], ARGV[3])
+if tonumber(ARGV[5]) > 0 then redis.call('PEXPIRE', KEYS[1], ARGV[5]) end
+return 1
+";
+
+pub(crate) const SCOPE_ACCOUNT_INFLIGHT: &str = "account_inflight";
+pub(crate) const SCOPE_ACCOUNT_COOLDOWN: &str = "account_cooldown";
+pub(crate) const SCOPE_ROUTE_CURSOR: &str = "route_cursor";
+pub(crate) const SCOPE_ROUTE_ACCOUNT_CURSOR: &str = "route_account_cursor";
+
+/// 网关运行时状态后端：进程内实现仅服务单实例，共享实现让多副本看到同一份租约、冷却与游标。
+pub(crate) trait RuntimeStateBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// 是否跨进程共享；为 false 时调用方继续以进程内状态为准。
+    fn is_shared(&self) -> bool;
+
+    /// 申请一个带 TTL 的租约，返回租约 id；进程崩溃后租约在 TTL 到期时自动失效。
+    /// `limit` 大于 0 且未过期租约已达上限时返回 None，计数与写入在后端内原子完成。
+    fn acquire_lease(
+        &self,
+        scope: &str,
+        key: &str,
+        ttl: Duration,
+        limit: usize,
+    ) -> Result<Option<String>, String>;
+
+    fn release_lease(&self, scope: &str, key: &str, lease_id: &str) -> Result<(), String>;
+
+    /// 当前未过期的租约数量。
+    fn lease_count(&self, scope: &str, key: &str) -> Result<usize, String>;
+
+    /// 把截止时间（unix 秒）推迟到 `until`；已有更晚的截止时间时保持不变。
+    fn extend_deadline(&self, scope: &str, key: &str, until: i64) -> Result<(), String>;
+
+    /// 读取仍未到期的截止时间。
+    fn deadline(&self, scope: &str, key: &str, now: i64) -> Result<Option<i64>, String>;
+
+    fn clear_deadline(&self, scope: &str, key: &str) -> Result<(), String>;
+
+    /// 原子递增游标并返回递增前的值；`ttl` 为零时不过期。
+    fn next_cursor(&self, scope: &str, key: &str, ttl: Duration) -> Result<u64, String>;
+
+    /// 读取下一次 `next_cursor` 将返回的值，不推进游标。
+    fn peek_cursor(&self, scope: &str, key: &str) -> Result<u64, String>;
+
+    fn get_value(&self, scope: &str, key: &str) -> Result<Option<String>, String>;
+
+    fn set_value(&self, scope: &str, key: &str, value: &str, ttl: Duration) -> Result<(), String>;
+}
+
+static RUNTIME_STATE_BACKEND: OnceLock<RwLock<ConfiguredBackend>> = OnceLock::new();
+
+struct ConfiguredBackend {
+    url: Option<String>,
+    backend: Arc<dyn RuntimeStateBackend>,
+}
+
+impl Default for ConfiguredBackend {
+    fn default() -> Self {
+        Self {
+            url: None,
+            backend: Arc::new(InMemoryRuntimeState::default()),
+        }
+    }
+}
+
+fn backend_slot() -> &'static RwLock<ConfiguredBackend> {
+    RUNTIME_STATE_BACKEND.get_or_init(|| RwLock::new(configured_backend_from_env()))
+}
+
+/// 当前生效的运行时状态后端。
+pub(crate) fn runtime_state_backend() -> Arc<dyn RuntimeStateBackend> {
+    let slot = crate::lock_utils::read_recover(backend_slot(), "runtime_state_backend");
+    slot.backend.clone()
+}
+
+/// 共享后端已启用时返回它，否则返回 None，调用方据此决定是否额外同步进程内状态。
+pub(crate) fn shared_runtime_state_backend() -> Option<Arc<dyn RuntimeStateBackend>> {
+    let backend = runtime_state_backend();
+    backend.is_shared().then_some(backend)
+}
+
+pub(super) fn reload_from_env() {
+    let next_url = runtime_state_url_from_env();
+    let mut slot = crate::lock_utils::write_recover(backend_slot(), "runtime_state_backend");
+    // 中文注释：URL 未变化时保留原后端，避免热重载把进程内在途租约清零。
+    if slot.url == next_url {
+        return;
+    }
+    *slot = build_backend(next_url);
+}
+
+fn runtime_state_url_from_env() -> Option<String> {
+    std::env::var(ENV_RUNTIME_STATE_URL)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty() && !value.eq_ignore_ascii_case("memory"))
+}
+
+fn configured_backend_from_env() -> ConfiguredBackend {
+    build_backend(runtime_state_url_from_env())
+}
+
+fn build_backend(url: Option<String>) -> ConfiguredBackend {
+    let Some(raw) = url.clone() else {
+        return ConfiguredBackend::default();
+    };
+    match RedisRuntimeState::from_url(raw.as_str()) {
+        Ok(backend) => ConfiguredBackend {
+            url,
+            backend: Arc::new(backend),
+        },
+        Err(err) => {
+            log::warn!("event=runtime_state_config_invalid fallback=memory err={err}");
+            ConfiguredBackend {
+                url,
+                backend: Arc::new(InMemoryRuntimeState::default()),
+            }
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn new_lease_id() -> String {
+    format!("{}-{:016x}", std::process::id(), rand::random::<u64>())
+}
+
+fn scoped_key(scope: &str, key: &str) -> String {
+    format!("{scope}:{key}")
+}
+
+#[derive(Default)]
+struct InMemoryState {
+    leases: HashMap<String, HashMap<String, i64>>,
+    deadlines: HashMap<String, i64>,
+    cursors: HashMap<String, (u64, Option<i64>)>,
+    values: HashMap<String, (String, Option<i64>)>,
+}
+
+/// 进程内后端，语义与 Redis 实现一致，单实例部署时的默认选择。
+#[derive(Default)]
+pub(crate) struct InMemoryRuntimeState {
+    state: Mutex<InMemoryState>,
+}
+
+fn expires_at(now_ms: i64, ttl: Duration) -> Option<i64> {
+    (!ttl.is_zero()).then(|| now_ms.saturating_add(ttl.as_millis() as i64))
+}
+
+fn is_live(expires_at: Option<i64>, now_ms: i64) -> bool {
+    expires_at.is_none_or(|value| value > now_ms)
+}
+
+impl InMemoryRuntimeState {
+    fn lock(&self) -> std::sync::MutexGuard<'_, InMemoryState> {
+        crate::lock_utils::lock_recover(&self.state, "runtime_state_memory")
+    }
+}
+
+impl RuntimeStateBackend for InMemoryRuntimeState {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn is_shared(&self) -> bool {
+        false
+    }
+
+    fn acquire_lease(
+        &self,
+        scope: &str,
+        key: &str,
+        ttl: Duration,
+        limit: usize,
+    ) -> Result<Option<String>, String> {
+        let now_ms = now_millis();
+        let expires = expires_at(now_ms, ttl).unwrap_or(i64::MAX);
+        let mut state = self.lock();
+        let leases = state.leases.entry(scoped_key(scope, key)).or_default();
+        leases.retain(|_, expires_at| *expires_at > now_ms);
+        if limit > 0 && leases.len() >= limit {
+            return Ok(None);
+        }
+        let lease_id = new_lease_id();
+        leases.insert(lease_id.clone(), expires);
+        Ok(Some(lease_id))
+    }
+
+    fn release_lease(&self, scope: &str, key: &str, lease_id: &str) -> Result<(), String> {
+        let mut state = self.lock();
+        let entry_key = scoped_key(scope, key);
+        if let Some(leases) = state.leases.get_mut(entry_key.as_str()) {
+            leases.remove(lease_id);
+            if leases.is_empty() {
+                state.leases.remove(entry_key.as_str());
+            }
+        }
+        Ok(())
+    }
+
+    fn lease_count(&self, scope: &str, key: &str) -> Result<usize, String> {
+        let now_ms = now_millis();
+        let state = self.lock();
+        Ok(state
+            .leases
+            .get(scoped_key(scope, key).as_str())
+            .map(|leases| leases.values().filter(|value| **value > now_ms).count())
+            .unwrap_or(0))
+    }
+
+    fn extend_deadline(&self, scope: &str, key: &str, until: i64) -> Result<(), String> {
+        let mut state = self.lock();
+        let current = state
+            .deadlines
+            .entry(scoped_key(scope, key))
+            .or_insert(until);
+        if until > *current {
+            *current = until;
+        }
+        Ok(())
+    }
+
+    fn deadline(&self, scope: &str, key: &str, now: i64) -> Result<Option<i64>, String> {
+        let mut state = self.lock();
+        let entry_key = scoped_key(scope, key);
+        match state.deadlines.get(entry_key.as_str()).copied() {
+            Some(until) if until > now => Ok(Some(until)),
+            Some(_) => {
+                state.deadlines.remove(entry_key.as_str());
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn clear_deadline(&self, scope: &str, key: &str) -> Result<(), String> {
+        self.lock()
+            .deadlines
+            .remove(scoped_key(scope, key).as_str());
+        Ok(())
+    }
+
+    fn next_cursor(&self, scope: &str, key: &str, ttl: Duration) -> Result<u64, String> {
+        let now_ms = now_millis();
+        let mut state = self.lock();
+        let entry = state
+            .cursors
+            .entry(scoped_key(scope, key))
+            .or_insert((0, None));
+        if !is_live(entry.1, now_ms) {
+            entry.0 = 0;
+        }
+        let current = entry.0;
+        entry.0 = current.wrapping_add(1);
+        entry.1 = expires_at(now_ms, ttl);
+        Ok(current)
+    }
+
+    fn peek_cursor(&self, scope: &str, key: &str) -> Result<u64, String> {
+        let now_ms = now_millis();
+        let state = self.lock();
+        Ok(state
+            .cursors
+            .get(scoped_key(scope, key).as_str())
+            .filter(|(_, expires_at)| is_live(*expires_at, now_ms))
+            .map(|(value, _)| *value)
+            .unwrap_or(0))
+    }
+
+    fn get_value(&self, scope: &str, key: &str) -> Result<Option<String>, String> {
+        let now_ms = now_millis();
+        let state = self.lock();
+        Ok(state
+            .values
+            .get(scoped_key(scope, key).as_str())
+            .filter(|(_, expires_at)| is_live(*expires_at, now_ms))
+            .map(|(value, _)| value.clone()))
+    }
+
+    fn set_value(&self, scope: &str, key: &str, value: &str, ttl: Duration) -> Result<(), String> {
+        let expires = expires_at(now_millis(), ttl);
+        self.lock()
+            .values
+            .insert(scoped_key(scope, key), (value.to_string(), expires));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RedisEndpoint {
+    host: String,
+    port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    database: u32,
+}
+
+impl RedisEndpoint {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let url =
+            url::Url::parse(raw).map_err(|err| format!("invalid runtime state url: {err}"))?;
+        if url.scheme() != "redis" {
+            return Err(format!(
+                "unsupported runtime state url scheme: {}",
+                url.scheme()
+            ));
+        }
+        let host = url
+            .host_str()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| "runtime state url is missing a host".to_string())?
+            .to_string();
+        let decode = |value: &str| {
+            urlencoding::decode(value)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| value.to_string())
+        };
+        let username = Some(url.username())
+            .filter(|value| !value.is_empty())
+            .map(decode);
+        let password = url.password().map(decode);
+        let database = match url.path().trim_matches('/') {
+            "" => 0,
+            value => value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid runtime state redis database: {value}"))?,
+        };
+        Ok(Self {
+            host,
+            port: url.port().unwrap_or(REDIS_DEFAULT_PORT),
+            username,
+            password,
+            database,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RespValue {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<RespValue>>),
+}
+
+impl RespValue {
+    fn into_result(self) -> Result<RespValue, String> {
+        match self {
+            RespValue::Error(message) => Err(format!("redis error: {message}")),
+            other => Ok(other),
+        }
+    }
+
+    fn as_integer(&self) -> Result<i64, String> {
+        match self {
+            RespValue::Integer(value) => Ok(*value),
+            other => Err(format!("unexpected redis reply: {other:?}")),
+        }
+    }
+
+    fn into_string(self) -> Result<Option<String>, String> {
+        match self {
+            RespValue::Bulk(None) | RespValue::Array(None) => Ok(None),
+            RespValue::Bulk(Some(bytes)) => Ok(Some(String::from_utf8_lossy(&bytes).into_owned())),
+            RespValue::Simple(value) => Ok(Some(value)),
+            other => Err(format!("unexpected redis reply: {other:?}")),
+        }
+    }
+}
+
+fn encode_command(args: &[&str]) -> Vec<u8> {
+    let mut out = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg.as_bytes());
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, String> {
+    let mut line = String::new();
+    let read = reader
+        .read_line(&mut line)
+        .map_err(|err| format!("redis read failed: {err}"))?;
+    if read == 0 {
+        return Err("redis connection closed".to_string());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_reply<R: BufRead>(reader: &mut R) -> Result<RespValue, String> {
+    let line = read_line(reader)?;
+    let (kind, rest) = line.split_at(line.len().min(1));
+    let parse_len = |value: &str| {
+        value
+            .parse::<i64>()
+            .map_err(|_| format!("invalid redis reply length: {value}"))
+    };
+    match kind {
+        "+" => Ok(RespValue::Simple(rest.to_string())),
+        "-" => Ok(RespValue::Error(rest.to_string())),
+        ":" => Ok(RespValue::Integer(parse_len(rest)?)),
+        "$" => {
+            let len = parse_len(rest)?;
+            if len < 0 {
+                return Ok(RespValue::Bulk(None));
+            }
+            let mut buf = vec![0u8; len as usize + 2];
+            reader
+                .read_exact(&mut buf)
+                .map_err(|err| format!("redis read failed: {err}"))?;
+            buf.truncate(len as usize);
+            Ok(RespValue::Bulk(Some(buf)))
+        }
+        "*" => {
+            let len = parse_len(rest)?;
+            if len < 0 {
+                return Ok(RespValue::Array(None));
+            }
+            let mut items = Vec::with_capacity(len as usize);
+            for _ in 0..len {
+                items.push(read_reply(reader)?);
+            }
+            Ok(RespValue::Array(Some(items)))
+        }
+        _ => Err(format!("unexpected redis reply: {line}")),
+    }
+}
+
+struct RedisConnection {
+    reader: BufReader<TcpStream>,
+}
+
+impl RedisConnection {
+    fn open(endpoint: &RedisEndpoint) -> Result<Self, String> {
+        let addr = (endpoint.host.as_str(), endpoint.port)
+            .to_socket_addrs()
+            .map_err(|err| format!("redis resolve failed: {err}"))?
+            .next()
+            .ok_or_else(|| format!("redis host not resolvable: {}", endpoint.host))?;
+        let stream = TcpStream::connect_timeout(&addr, REDIS_CONNECT_TIMEOUT)
+            .map_err(|err| format!("redis connect failed: {err}"))?;
+        let _ = stream.set_read_timeout(Some(REDIS_IO_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(REDIS_IO_TIMEOUT));
+        let _ = stream.set_nodelay(true);
+        let mut conn = Self {
+            reader: BufReader::new(stream),
+        };
+        if let Some(password) = endpoint.password.as_deref() {
+            match endpoint.username.as_deref() {
+                Some(username) => conn.command(&["AUTH", username, password])?,
+                None => conn.command(&["AUTH", password])?,
+            };
+        }
+        if endpoint.database != 0 {
+            conn.command(&["SELECT", endpoint.database.to_string().as_str()])?;
+        }
+        Ok(conn)
+    }
+
+    fn pipeline(&mut self, commands: &[Vec<&str>]) -> Result<Vec<RespValue>, String> {
+        let mut payload = Vec::new();
+        for command in commands {
+            payload.extend_from_slice(&encode_command(command));
+        }
+        self.reader
+            .get_mut()
+            .write_all(&payload)
+            .map_err(|err| format!("redis write failed: {err}"))?;
+        let mut replies = Vec::with_capacity(commands.len());
+        for _ in commands {
+            replies.push(read_reply(&mut self.reader)?.into_result()?);
+        }
+        Ok(replies)
+    }
+
+    fn command(&mut self, args: &[&str]) -> Result<RespValue, String> {
+        let mut replies = self.pipeline(&[args.to_vec()])?;
+        replies
+            .pop()
+            .ok_or_else(|| "redis returned no reply".to_string())
+    }
+}
+
+#[derive(Default)]
+struct RedisConnectionPool {
+    idle: Vec<RedisConnection>,
+    consecutive_failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl RedisConnectionPool {
+    /// 连接级失败后按指数退避打开断路器，退避期内的调用直接失败，不再逐个请求等待连接超时。
+    fn record_failure(&mut self, err: &str) {
+        self.idle.clear();
+        // 中文注释：并发请求会同时撞上同一次故障，断路器已打开时不再重复叠加退避。
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return;
+        }
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let backoff = REDIS_RECONNECT_BACKOFF_BASE
+            .saturating_mul(1u32 << self.consecutive_failures.saturating_sub(1).min(16))
+            .min(REDIS_RECONNECT_BACKOFF_MAX);
+        self.retry_at = Some(Instant::now() + backoff);
+        log::warn!(
+            "event=runtime_state_redis_unavailable failures={} retry_in_ms={} err={}",
+            self.consecutive_failures,
+            backoff.as_millis(),
+            err
+        );
+    }
+
+    fn record_success(&mut self) {
+        if self.consecutive_failures > 0 {
+            log::info!(
+                "event=runtime_state_redis_recovered failures={}",
+                self.consecutive_failures
+            );
+        }
+        self.consecutive_failures = 0;
+        self.retry_at = None;
+    }
+
+    fn check_in(&mut self, conn: RedisConnection) {
+        if self.idle.len() < REDIS_MAX_IDLE_CONNECTIONS {
+            self.idle.push(conn);
+        }
+    }
+}
+
+/// 走 Redis 协议的共享后端：租约用有序集合按过期时间计分，截止时间依赖 `ZADD GT`（Redis 6.2+）。
+/// 连接池只在借还连接时加锁，网络往返期间并发请求各用各的连接，空闲连接最多保留
+/// `REDIS_MAX_IDLE_CONNECTIONS` 条。
+pub(crate) struct RedisRuntimeState {
+    endpoint: RedisEndpoint,
+    pool: Mutex<RedisConnectionPool>,
+}
+
+impl RedisRuntimeState {
+    pub(crate) fn from_url(raw: &str) -> Result<Self, String> {
+        Ok(Self {
+            endpoint: RedisEndpoint::parse(raw)?,
+            pool: Mutex::new(RedisConnectionPool::default()),
+        })
+    }
+
+    fn key(kind: &str, scope: &str, key: &str) -> String {
+        format!("{REDIS_KEY_PREFIX}{kind}:{scope}:{key}")
+    }
+
+    fn deadline_key(scope: &str) -> String {
+        format!("{REDIS_KEY_PREFIX}deadline:{scope}")
+    }
+
+    fn lock_pool(&self) -> std::sync::MutexGuard<'_, RedisConnectionPool> {
+        crate::lock_utils::lock_recover(&self.pool, "runtime_state_redis")
+    }
+
+    /// 借出一条空闲连接；断路器打开时直接返回错误。
+    fn check_out(&self) -> Result<Option<RedisConnection>, String> {
+        let mut pool = self.lock_pool();
+        if let Some(retry_at) = pool.retry_at {
+            let now = Instant::now();
+            if now < retry_at {
+                return Err(format!(
+                    "redis circuit open, retry in {}ms",
+                    (retry_at - now).as_millis()
+                ));
+            }
+        }
+        Ok(pool.idle.pop())
+    }
+
+    fn pipeline(&self, commands: &[Vec<&str>]) -> Result<Vec<RespValue>, String> {
+        let mut idle = self.check_out()?;
+        // 中文注释：空闲连接可能已被服务端关闭，失败后换一条新连接重试一次；仍失败则打开断路器，
+        // 并把错误交给调用方回退到本地状态。
+        for attempt in 0..2 {
+            let mut conn = match idle.take() {
+                Some(conn) => conn,
+                None => match RedisConnection::open(&self.endpoint) {
+                    Ok(conn) => conn,
+                    Err(err) => {
+                        self.lock_pool().record_failure(&err);
+                        return Err(err);
+                    }
+                },
+            };
+            match conn.pipeline(commands) {
+                Ok(replies) => {
+                    let mut pool = self.lock_pool();
+                    pool.record_success();
+                    pool.check_in(conn);
+                    return Ok(replies);
+                }
+                Err(err) if err.starts_with("redis error:") => {
+                    self.lock_pool().check_in(conn);
+                    return Err(err);
+                }
+                Err(err) if attempt == 1 => {
+                    self.lock_pool().record_failure(&err);
+                    return Err(err);
+                }
+                Err(_) => {}
+            }
+        }
+        Err("redis connection unavailable".to_string())
+    }
+
+    fn last_reply(&self, commands: &[Vec<&str>]) -> Result<RespValue, String> {
+        self.pipeline(commands)?
+            .pop()
+            .ok_or_else(|| "redis returned no reply".to_string())
+    }
+}
+
+impl RuntimeStateBackend for RedisRuntimeState {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn acquire_lease(
+        &self,
+        scope: &str,
+        key: &str,
+        ttl: Duration,
+        limit: usize,
+    ) -> Result<Option<String>, String> {
+        let lease_id = new_lease_id();
+        let now_ms = now_millis();
+        let redis_key = Self::key("lease", scope, key);
+        let expires = expires_at(now_ms, ttl).unwrap_or(i64::MAX).to_string();
+        let now_text = now_ms.to_string();
+        let limit_text = limit.to_string();
+        let key_ttl_ms = ttl.as_millis().to_string();
+        let acquired = self
+            .last_reply(&[vec![
+                "EVAL",
+                REDIS_ACQUIRE_LEASE_SCRIPT,
+                "1",
+                redis_key.as_str(),
+                now_text.as_str(),
+                expires.as_str(),
+                lease_id.as_str(),
+                limit_text.as_str(),
+                key_ttl_ms.as_str(),
+            ]])?
+            .as_integer()?;
+        Ok((acquired == 1).then_some(lease_id))
+    }
+
+    fn release_lease(&self, scope: &str, key: &str, lease_id: &str) -> Result<(), String> {
+        let redis_key = Self::key("lease", scope, key);
+        self.pipeline(&[vec!["ZREM", redis_key.as_str(), lease_id]])?;
+        Ok(())
+    }
+
+    fn lease_count(&self, scope: &str, key: &str) -> Result<usize, String> {
+        let redis_key = Self::key("lease", scope, key);
+        let min = format!("({}", now_millis());
+        let count = self
+            .last_reply(&[vec!["ZCOUNT", redis_key.as_str(), min.as_str(), "+inf"]])?
+            .as_integer()?;
+        Ok(count.max(0) as usize)
+    }
+
+    fn extend_deadline(&self, scope: &str, key: &str, until: i64) -> Result<(), String> {
+        let redis_key = Self::deadline_key(scope);
+        let until_text = until.to_string();
+        let now_text = codexmanager_core::storage::now_ts().to_string();
+        self.pipeline(&[
+            vec![
+                "ZREMRANGEBYSCORE",
+                redis_key.as_str(),
+                "-inf",
+                now_text.as_str(),
+            ],
+            vec!["ZADD", redis_key.as_str(), "GT", until_text.as_str(), key],
+        ])?;
+        Ok(())
+    }
+
+    fn deadline(&self, scope: &str, key: &str, now: i64) -> Result<Option<i64>, String> {
+        let redis_key = Self::deadline_key(scope);
+        let score = self
+            .last_reply(&[vec!["ZSCORE", redis_key.as_str(), key]])?
+            .into_string()?;
+        Ok(score
+            .and_then(|value| value.parse::<f64>().ok())
+            .map(|value| value as i64)
+            .filter(|until| *until > now))
+    }
+
+    fn clear_deadline(&self, scope: &str, key: &str) -> Result<(), String> {
+        let redis_key = Self::deadline_key(scope);
+        self.pipeline(&[vec!["ZREM", redis_key.as_str(), key]])?;
+        Ok(())
+    }
+
+    fn next_cursor(&self, scope: &str, key: &str, ttl: Duration) -> Result<u64, String> {
+        let redis_key = Self::key("cursor", scope, key);
+        let ttl_ms = ttl.as_millis().to_string();
+        let mut commands = vec![vec!["INCR", redis_key.as_str()]];
+        if !ttl.is_zero() {
+            commands.push(vec!["PEXPIRE", redis_key.as_str(), ttl_ms.as_str()]);
+        }
+        let replies = self.pipeline(&commands)?;
+        let value = replies
+            .first()
+            .ok_or_else(|| "redis returned no reply".to_string())?
+            .as_integer()?;
+        Ok(value.saturating_sub(1).max(0) as u64)
+    }
+
+    fn peek_cursor(&self, scope: &str, key: &str) -> Result<u64, String> {
+        let redis_key = Self::key("cursor", scope, key);
+        Ok(self
+            .last_reply(&[vec!["GET", redis_key.as_str()]])?
+            .into_string()?
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(0))
+    }
+
+    fn get_value(&self, scope: &str, key: &str) -> Result<Option<String>, String> {
+        let redis_key = Self::key("value", scope, key);
+        self.last_reply(&[vec!["GET", redis_key.as_str()]])?
+            .into_string()
+    }
+
+    fn set_value(&self, scope: &str, key: &str, value: &str, ttl: Duration) -> Result<(), String> {
+        let redis_key = Self::key("value", scope, key);
+        let ttl_ms = ttl.as_millis().to_string();
+        let command = if ttl.is_zero() {
+            vec!["SET", redis_key.as_str(), value]
+        } else {
+            vec!["SET", redis_key.as_str(), value, "PX", ttl_ms.as_str()]
+        };
+        self.pipeline(&[command])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) fn set_runtime_state_backend_for_tests(backend: Option<Arc<dyn RuntimeStateBackend>>) {
+    let mut slot = crate::lock_utils::write_recover(backend_slot(), "runtime_state_backend");
+    *slot = match backend {
+        Some(backend) => ConfiguredBackend {
+            url: Some(format!("test:{}", backend.name())),
+            backend,
+        },
+        None => ConfiguredBackend::default(),
+    };
+}
+
+#[cfg(test)]
+#[path = "tests/runtime_state_tests.rs"]
+mod tests;
@@ -1108,6 +1108,7 @@ fn account_proxy_set_and_clear_invalidate_gateway_cache() {
         true,
         None,
         None,
+        None,
         Some("http://127.0.0.1:7101"),
         None,
         None,
@@ -1131,6 +1132,7 @@ fn account_proxy_set_and_clear_invalidate_gateway_cache() {
         true,
         None,
         None,
+        None,
         Some("http://127.0.0.1:7102"),
         None,
         None,
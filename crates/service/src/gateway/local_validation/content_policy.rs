@@ -0,0 +1,504 @@
+use codexmanager_core::rpc::types::ApiKeyContentPolicyEntry;
+use codexmanager_core::storage::{now_ts, ApiKey, Storage};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::apikey_content_policy::{
+    ACTION_BLOCK, ACTION_LOG, ACTION_OFF, ACTION_REDACT, BUILTIN_DETECTORS,
+    CUSTOM_RULE_REGEX_SIZE_LIMIT, SOURCE_KIND_AGGREGATE_API, SOURCE_KIND_OPENAI_ACCOUNT,
+};
+use crate::apikey_profile::{ROTATION_AGGREGATE_API, ROTATION_HYBRID};
+
+use super::LocalValidationError;
+
+const MAX_MATCH_PATHS: usize = 5;
+const PENDING_MATCHES_TTL_SECS: i64 = 600;
+/// 请求体顶层承载用户内容的字段（Responses / Chat / Anthropic / Gemini / Completions）。
+const USER_CONTENT_ROOT_FIELDS: &[&str] = &[
+    "input",
+    "instructions",
+    "messages",
+    "prompt",
+    "system",
+    "systemInstruction",
+    "system_instruction",
+    "contents",
+];
+/// 消息与内容块里继续下钻的字段；id、call_id、encrypted_content、参数、工具定义与
+/// 图片/文件数据等不透明字段不扫描，避免 `redact` 改坏上游校验的内容。
+const USER_CONTENT_FIELDS: &[&str] = &["content", "text", "parts"];
+/// 工具输出整体都是调用方提供的内容，其下所有字符串都扫描。
+const TOOL_OUTPUT_FIELDS: &[&str] = &["output", "functionResponse", "function_response"];
+
+type CompiledPolicyCache = HashMap<String, (i64, Arc<CompiledContentPolicy>)>;
+
+static BUILTIN_DETECTOR_REGEXES: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+static COMPILED_POLICIES: OnceLock<Mutex<CompiledPolicyCache>> = OnceLock::new();
+static PENDING_MATCHES: OnceLock<Mutex<HashMap<String, (i64, String)>>> = OnceLock::new();
+
+fn builtin_detector_pattern(name: &str) -> &'static str {
+    match name {
+        "api_key" => {
+            r"\b(?:sk-(?:proj-|ant-)?[A-Za-z0-9_-]{20,}|(?:AKIA|ASIA)[0-9A-Z]{16}|gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,}|xox[abprs]-[A-Za-z0-9-]{10,}|AIza[0-9A-Za-z_-]{35}|glpat-[A-Za-z0-9_-]{20,})"
+        }
+        "private_key" => {
+            r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY-----(?s:.*?)(?:-----END (?:[A-Z0-9]+ )*PRIVATE KEY-----|\z)"
+        }
+        "jwt" => r"\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}",
+        "email" => r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b",
+        "card_number" => r"\b\d(?:[ -]?\d){12,18}\b",
+        _ => r"[^\s\S]",
+    }
+}
+
+fn builtin_detector_regexes() -> &'static [(&'static str, Regex)] {
+    BUILTIN_DETECTOR_REGEXES.get_or_init(|| {
+        BUILTIN_DETECTORS
+            .iter()
+            .filter_map(|name| {
+                Regex::new(builtin_detector_pattern(name))
+                    .ok()
+                    .map(|regex| (*name, regex))
+            })
+            .collect()
+    })
+}
+
+/// 卡号需通过 Luhn 校验，避免把普通长数字（订单号、时间戳）当成卡号。
+fn passes_luhn(candidate: &str) -> bool {
+    let digits = candidate
+        .chars()
+        .filter_map(|ch| ch.to_digit(10))
+        .collect::<Vec<_>>();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            if index % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *digit
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+struct ContentDetector {
+    name: String,
+    regex: Regex,
+    /// 自定义规则自带的动作；为空时按来源与默认动作决定。
+    action: Option<String>,
+    luhn: bool,
+}
+
+struct CompiledContentPolicy {
+    action: String,
+    source_actions: HashMap<String, String>,
+    detectors: Vec<ContentDetector>,
+}
+
+fn compile_content_policy(entry: &ApiKeyContentPolicyEntry) -> CompiledContentPolicy {
+    let mut detectors = builtin_detector_regexes()
+        .iter()
+        .filter(|(name, _)| {
+            entry.detectors.is_empty() || entry.detectors.iter().any(|item| item == name)
+        })
+        .map(|(name, regex)| ContentDetector {
+            name: name.to_string(),
+            regex: regex.clone(),
+            action: None,
+            luhn: *name == "card_number",
+        })
+        .collect::<Vec<_>>();
+    for rule in &entry.custom_rules {
+        match RegexBuilder::new(rule.pattern.as_str())
+            .size_limit(CUSTOM_RULE_REGEX_SIZE_LIMIT)
+            .build()
+        {
+            Ok(regex) => detectors.push(ContentDetector {
+                name: rule.name.clone(),
+                regex,
+                action: rule.action.clone(),
+                luhn: false,
+            }),
+            Err(err) => log::warn!(
+                "event=gateway_content_policy_rule_invalid rule={} err={}",
+                rule.name,
+                err
+            ),
+        }
+    }
+    CompiledContentPolicy {
+        action: entry.action.clone(),
+        source_actions: entry
+            .source_actions
+            .iter()
+            .map(|(kind, action)| (kind.clone(), action.clone()))
+            .collect(),
+        detectors,
+    }
+}
+
+/// 按 `(key_id, updated_at)` 缓存编译后的策略，避免每个请求重复编译正则。
+fn load_content_policy(
+    storage: &Storage,
+    key_id: &str,
+) -> Result<Option<Arc<CompiledContentPolicy>>, String> {
+    let Some(policy) = storage
+        .find_api_key_content_policy(key_id)
+        .map_err(|err| format!("read content policy failed: {err}"))?
+    else {
+        return Ok(None);
+    };
+    if !policy.enabled {
+        return Ok(None);
+    }
+    let cache = COMPILED_POLICIES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = crate::lock_utils::lock_recover(cache, "content_policy_cache");
+    if let Some((updated_at, compiled)) = cache.get(key_id) {
+        if *updated_at == policy.updated_at {
+            return Ok(Some(compiled.clone()));
+        }
+    }
+    let entry = crate::apikey_content_policy::content_policy_entry(key_id, Some(&policy));
+    let compiled = Arc::new(compile_content_policy(&entry));
+    cache.insert(key_id.to_string(), (policy.updated_at, compiled.clone()));
+    Ok(Some(compiled))
+}
+
+/// 单个检测器在本次请求中的命中摘要；不保存命中原文。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ContentPolicyMatch {
+    detector: String,
+    action: String,
+    count: usize,
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct ContentPolicyScan {
+    matches: Vec<ContentPolicyMatch>,
+    redacted: bool,
+}
+
+impl ContentPolicyScan {
+    fn record(&mut self, detector: &str, action: &str, count: usize, path: &str) {
+        let existing = self
+            .matches
+            .iter_mut()
+            .find(|item| item.detector == detector);
+        let item = match existing {
+            Some(item) => item,
+            None => {
+                self.matches.push(ContentPolicyMatch {
+                    detector: detector.to_string(),
+                    action: action.to_string(),
+                    count: 0,
+                    paths: Vec::new(),
+                });
+                self.matches.last_mut().expect("match just pushed")
+            }
+        };
+        item.count += count;
+        if item.paths.len() < MAX_MATCH_PATHS && !item.paths.iter().any(|item| item == path) {
+            item.paths.push(path.to_string());
+        }
+    }
+
+    fn blocked_detectors(&self) -> Vec<&str> {
+        self.matches
+            .iter()
+            .filter(|item| item.action == ACTION_BLOCK)
+            .map(|item| item.detector.as_str())
+            .collect()
+    }
+}
+
+fn scan_text(
+    policy: &CompiledContentPolicy,
+    source_action: &str,
+    text: &mut String,
+    path: &str,
+    scan: &mut ContentPolicyScan,
+) {
+    for detector in &policy.detectors {
+        let count = detector
+            .regex
+            .find_iter(text.as_str())
+            .filter(|found| !detector.luhn || passes_luhn(found.as_str()))
+            .count();
+        if count == 0 {
+            continue;
+        }
+        let action = detector.action.as_deref().unwrap_or(source_action);
+        scan.record(detector.name.as_str(), action, count, path);
+        if action != ACTION_REDACT {
+            continue;
+        }
+        let placeholder = format!("[REDACTED:{}]", detector.name);
+        let replaced = detector
+            .regex
+            .replace_all(text.as_str(), |found: &regex::Captures<'_>| {
+                if !detector.luhn || passes_luhn(&found[0]) {
+                    placeholder.clone()
+                } else {
+                    found[0].to_string()
+                }
+            });
+        *text = replaced.into_owned();
+        scan.redacted = true;
+    }
+}
+
+fn scan_string(
+    policy: &CompiledContentPolicy,
+    source_action: &str,
+    text: &mut String,
+    path: &str,
+    scan: &mut ContentPolicyScan,
+) {
+    // 中文注释：内联文件/图片（data URL）是 base64，逐字扫描只会产生误报。
+    if !text.starts_with("data:") {
+        scan_text(policy, source_action, text, path, scan);
+    }
+}
+
+/// 扫描工具输出子树中的全部字符串。
+fn scan_tool_output(
+    policy: &CompiledContentPolicy,
+    source_action: &str,
+    value: &mut Value,
+    path: &mut String,
+    scan: &mut ContentPolicyScan,
+) {
+    match value {
+        Value::String(text) => scan_string(policy, source_action, text, path.as_str(), scan),
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let len = path.len();
+                path.push_str(format!("[{index}]").as_str());
+                scan_tool_output(policy, source_action, item, path, scan);
+                path.truncate(len);
+            }
+        }
+        Value::Object(object) => {
+            for (key, item) in object.iter_mut() {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                scan_tool_output(policy, source_action, item, path, scan);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 扫描消息、内容块与工具输出；对象只下钻 `USER_CONTENT_FIELDS` 与 `TOOL_OUTPUT_FIELDS`。
+fn scan_user_content(
+    policy: &CompiledContentPolicy,
+    source_action: &str,
+    value: &mut Value,
+    path: &mut String,
+    scan: &mut ContentPolicyScan,
+) {
+    match value {
+        Value::String(text) => scan_string(policy, source_action, text, path.as_str(), scan),
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let len = path.len();
+                path.push_str(format!("[{index}]").as_str());
+                scan_user_content(policy, source_action, item, path, scan);
+                path.truncate(len);
+            }
+        }
+        Value::Object(object) => {
+            for (key, item) in object.iter_mut() {
+                let tool_output = TOOL_OUTPUT_FIELDS.contains(&key.as_str());
+                if !tool_output && !USER_CONTENT_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                if tool_output {
+                    scan_tool_output(policy, source_action, item, path, scan);
+                } else {
+                    scan_user_content(policy, source_action, item, path, scan);
+                }
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 平台 Key 可能把请求发往的上游来源。混合轮转在扫描时还不知道最终走账号池还是
+/// 模型路由里的聚合 API 供应商，两种来源都要算上。
+fn upstream_source_kinds(rotation_strategy: &str) -> &'static [&'static str] {
+    match rotation_strategy {
+        ROTATION_AGGREGATE_API => &[SOURCE_KIND_AGGREGATE_API],
+        ROTATION_HYBRID => &[SOURCE_KIND_OPENAI_ACCOUNT, SOURCE_KIND_AGGREGATE_API],
+        _ => &[SOURCE_KIND_OPENAI_ACCOUNT],
+    }
+}
+
+fn action_strictness(action: &str) -> u8 {
+    match action {
+        ACTION_BLOCK => 3,
+        ACTION_REDACT => 2,
+        ACTION_LOG => 1,
+        _ => 0,
+    }
+}
+
+/// 多个候选来源时取最严格的来源动作（block > redact > log > off）。
+fn resolve_source_action<'a>(policy: &'a CompiledContentPolicy, source_kinds: &[&str]) -> &'a str {
+    source_kinds
+        .iter()
+        .map(|source_kind| {
+            policy
+                .source_actions
+                .get(*source_kind)
+                .map(String::as_str)
+                .unwrap_or(policy.action.as_str())
+        })
+        .max_by_key(|action| action_strictness(action))
+        .unwrap_or(policy.action.as_str())
+}
+
+/// 函数 `scan_request_body`
+///
+/// 只扫描用户内容（消息文本、instructions/system、工具输出）；`redact` 动作就地替换为
+/// `[REDACTED:<检测器>]`。来源动作为 `off` 或请求体不是 JSON 对象时返回 `None`。
+fn scan_request_body(
+    policy: &CompiledContentPolicy,
+    source_kinds: &[&str],
+    body: &[u8],
+) -> Option<(ContentPolicyScan, Value)> {
+    let source_action = resolve_source_action(policy, source_kinds);
+    if source_action == ACTION_OFF {
+        return None;
+    }
+    let mut value = serde_json::from_slice::<Value>(body).ok()?;
+    let mut scan = ContentPolicyScan::default();
+    let mut path = "$".to_string();
+    for (key, item) in value.as_object_mut()?.iter_mut() {
+        if !USER_CONTENT_ROOT_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        path.push('.');
+        path.push_str(key);
+        scan_user_content(policy, source_action, item, &mut path, &mut scan);
+        path.truncate(1);
+    }
+    Some((scan, value))
+}
+
+fn pending_matches() -> &'static Mutex<HashMap<String, (i64, String)>> {
+    PENDING_MATCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_content_policy_matches(trace_id: &str, matches: &[ContentPolicyMatch]) {
+    let Ok(matches_json) = serde_json::to_string(matches) else {
+        return;
+    };
+    let now = now_ts();
+    let mut pending = crate::lock_utils::lock_recover(pending_matches(), "content_policy_matches");
+    // 中文注释：请求异常中断时不会写日志，顺带清理过期条目防止常驻增长。
+    pending.retain(|_, (created_at, _)| now - *created_at < PENDING_MATCHES_TTL_SECS);
+    pending.insert(trace_id.to_string(), (now, matches_json));
+}
+
+/// 取出（并移除）该请求的内容策略命中 JSON，供写请求日志时落库。
+pub(crate) fn take_content_policy_matches(trace_id: &str) -> Option<String> {
+    let pending = PENDING_MATCHES.get()?;
+    crate::lock_utils::lock_recover(pending, "content_policy_matches")
+        .remove(trace_id)
+        .map(|(_, matches_json)| matches_json)
+}
+
+/// 函数 `enforce_content_policy`
+///
+/// 平台 Key 开启出站内容策略时扫描请求体：`block` 命中直接拒绝，`redact` 命中替换后继续，
+/// `log` 只记录。命中摘要随请求日志写入 `request_content_policy_matches`。
+pub(crate) fn enforce_content_policy(
+    storage: &Storage,
+    trace_id: &str,
+    api_key: &ApiKey,
+    body: &mut Vec<u8>,
+) -> Result<(), LocalValidationError> {
+    if body.is_empty() {
+        return Ok(());
+    }
+    let policy = match load_content_policy(storage, api_key.id.as_str()) {
+        Ok(Some(policy)) => policy,
+        Ok(None) => return Ok(()),
+        Err(err) => {
+            return Err(LocalValidationError::new(
+                500,
+                crate::gateway::bilingual_error("读取出站内容策略失败", err),
+            ))
+        }
+    };
+    let source_kinds = upstream_source_kinds(api_key.rotation_strategy.as_str());
+    let Some((scan, value)) = scan_request_body(&policy, source_kinds, body) else {
+        return Ok(());
+    };
+    if scan.matches.is_empty() {
+        return Ok(());
+    }
+    record_content_policy_matches(trace_id, &scan.matches);
+    let summary = scan
+        .matches
+        .iter()
+        .map(|item| format!("{}:{}x{}", item.detector, item.action, item.count))
+        .collect::<Vec<_>>()
+        .join(",");
+    log::info!(
+        "event=gateway_content_policy trace_id={} key_id={} source_kind={} matches={}",
+        trace_id,
+        api_key.id,
+        source_kinds.join("+"),
+        summary
+    );
+    let blocked = scan.blocked_detectors();
+    if !blocked.is_empty() {
+        return Err(LocalValidationError::new(
+            400,
+            crate::gateway::bilingual_error(
+                "请求内容触发出站内容策略，已拒绝发送",
+                format!("request blocked by content policy: {}", blocked.join(", ")),
+            ),
+        ));
+    }
+    if scan.redacted {
+        *body = serde_json::to_vec(&value).map_err(|err| {
+            LocalValidationError::new(
+                500,
+                crate::gateway::bilingual_error(
+                    "出站内容脱敏失败",
+                    format!("serialize redacted request body failed: {err}"),
+                ),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/content_policy_tests.rs"]
+mod tests;
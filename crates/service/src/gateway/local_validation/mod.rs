@@ -6,9 +6,13 @@ use tiny_http::Request;
 use super::conversation_binding::RouteConversationSource;
 
 mod auth;
+mod content_policy;
 mod io;
 mod request;
 
+pub(super) use content_policy::{enforce_content_policy, take_content_policy_matches};
+pub(super) use request::{multipart_boundary, parse_multipart_form};
+
 pub(super) struct LocalValidationResult {
     pub(super) trace_id: String,
     pub(super) incoming_headers: super::IncomingHeaderSnapshot,
@@ -18,6 +22,8 @@ pub(super) struct LocalValidationResult {
     pub(super) path: String,
     pub(super) passthrough_body: Bytes,
     pub(super) body: Bytes,
+    pub(super) background_request_body: Option<Bytes>,
+    pub(super) fan_out_request_body: Option<Bytes>,
     pub(super) is_stream: bool,
     pub(super) has_prompt_cache_key: bool,
     pub(super) request_shape: Option<String>,
@@ -1117,13 +1117,14 @@ fn adapt_openai_images_edits_json_body_to_responses(
 }
 
 #[derive(Debug)]
-struct MultipartPart {
-    name: String,
-    content_type: Option<String>,
-    data: Vec<u8>,
+pub(in crate::gateway) struct MultipartPart {
+    pub(in crate::gateway) name: String,
+    pub(in crate::gateway) filename: Option<String>,
+    pub(in crate::gateway) content_type: Option<String>,
+    pub(in crate::gateway) data: Vec<u8>,
 }
 
-fn multipart_boundary(content_type: &str) -> Option<String> {
+pub(in crate::gateway) fn multipart_boundary(content_type: &str) -> Option<String> {
     content_type.split(';').find_map(|part| {
         let part = part.trim();
         let value = part.strip_prefix("boundary=")?;
@@ -1131,15 +1132,18 @@ fn multipart_boundary(content_type: &str) -> Option<String> {
     })
 }
 
-fn parse_content_disposition_name(value: &str) -> Option<String> {
+fn parse_content_disposition_param(value: &str, param: &str) -> Option<String> {
     value.split(';').find_map(|part| {
-        let part = part.trim();
-        let value = part.strip_prefix("name=")?;
-        Some(value.trim_matches('"').to_string())
+        let (key, value) = part.trim().split_once('=')?;
+        key.eq_ignore_ascii_case(param)
+            .then(|| value.trim_matches('"').to_string())
     })
 }
 
-fn parse_multipart_form(body: &[u8], boundary: &str) -> Result<Vec<MultipartPart>, String> {
+pub(in crate::gateway) fn parse_multipart_form(
+    body: &[u8],
+    boundary: &str,
+) -> Result<Vec<MultipartPart>, String> {
     let marker = format!("--{boundary}").into_bytes();
     let mut parts = Vec::new();
     for raw_section in split_bytes(body, marker.as_slice()).into_iter().skip(1) {
@@ -1155,12 +1159,14 @@ fn parse_multipart_form(body: &[u8], boundary: &str) -> Result<Vec<MultipartPart
         data_raw = trim_suffix_bytes(data_raw, b"\r\n");
         data_raw = trim_suffix_bytes(data_raw, b"--");
         let mut name = None;
+        let mut filename = None;
         let mut content_type = None;
         let headers_text = String::from_utf8_lossy(headers_raw);
         for header in headers_text.lines() {
             if let Some((field, value)) = header.split_once(':') {
                 if field.trim().eq_ignore_ascii_case("content-disposition") {
-                    name = parse_content_disposition_name(value.trim());
+                    name = parse_content_disposition_param(value.trim(), "name");
+                    filename = parse_content_disposition_param(value.trim(), "filename");
                 } else if field.trim().eq_ignore_ascii_case("content-type") {
                     let value = value.trim();
                     if !value.is_empty() {
@@ -1174,6 +1180,7 @@ fn parse_multipart_form(body: &[u8], boundary: &str) -> Result<Vec<MultipartPart
         };
         parts.push(MultipartPart {
             name,
+            filename,
             content_type,
             data: data_raw.to_vec(),
         });
@@ -1877,7 +1884,28 @@ pub(super) fn build_local_validation_result(
             crate::gateway::bilingual_error("不支持的请求方法", "unsupported method"),
         )
     })?;
+    // 出站内容策略先于所有改写执行，后台与 `n > 1` 回环重放拿到的也是脱敏后的请求体。
+    super::content_policy::enforce_content_policy(
+        &storage,
+        trace_id.as_str(),
+        &api_key,
+        &mut body,
+    )?;
     let initial_request_value = super::super::parse_request_json_value(&body);
+    // 后台模式需要未经网关改写的原始请求体，回环重放时再走完整链路。
+    let background_request_body = initial_request_value
+        .as_ref()
+        .and_then(|value| value.get("background"))
+        .and_then(Value::as_bool)
+        .filter(|background| *background && request_method == "POST")
+        .map(|_| Bytes::from(body.clone()));
+    // `n > 1` 拆分同样按原始请求体回环重放，避免重复套用改写策略。
+    let fan_out_request_body = initial_request_value
+        .as_ref()
+        .and_then(|value| value.get("n"))
+        .and_then(Value::as_u64)
+        .filter(|n| *n > 1 && request_method == "POST")
+        .map(|_| Bytes::from(body.clone()));
     let initial_service_tier_diagnostic = initial_request_value
         .as_ref()
         .map(|value| super::super::inspect_service_tier_value(value.get("service_tier")))
@@ -2026,6 +2054,8 @@ pub(super) fn build_local_validation_result(
             path: logical_path.clone(),
             passthrough_body: Bytes::from(rewritten_body.clone()),
             body: Bytes::from(rewritten_body),
+            background_request_body,
+            fan_out_request_body,
             is_stream,
             has_prompt_cache_key,
             request_shape,
@@ -2404,6 +2434,8 @@ pub(super) fn build_local_validation_result(
         path,
         passthrough_body: Bytes::from(passthrough_body),
         body: Bytes::from(body),
+        background_request_body,
+        fan_out_request_body,
         is_stream,
         has_prompt_cache_key,
         request_shape,
@@ -69,6 +69,17 @@ pub(crate) fn load_active_gateway_api_key_by_id(
         .map_err(|err| (err.status_code, err.message))
 }
 
+/// 对 Responses WebSocket 的 response.create 载荷执行平台 Key 的出站内容策略。
+pub(crate) fn gateway_enforce_content_policy(
+    storage: &codexmanager_core::storage::Storage,
+    trace_id: &str,
+    api_key: &codexmanager_core::storage::ApiKey,
+    body: &mut Vec<u8>,
+) -> Result<(), (u16, String)> {
+    local_validation::enforce_content_policy(storage, trace_id, api_key, body)
+        .map_err(|err| (err.status_code, err.message))
+}
+
 pub(crate) fn error_message_for_client(
     _prefers_raw_errors: bool,
     message: impl Into<String>,
@@ -81,14 +92,24 @@ pub(crate) fn error_message_for_client(
 }
 
 mod anchor_fingerprint;
+#[path = "request/background_responses.rs"]
+mod background_responses;
+#[path = "request/batches.rs"]
+mod batches;
+#[path = "request/chat_fan_out.rs"]
+mod chat_fan_out;
 mod concurrency;
 #[path = "routing/conversation_binding.rs"]
 pub(crate) mod conversation_binding;
 #[path = "routing/cooldown.rs"]
 mod cooldown;
+#[path = "routing/cost_route.rs"]
+pub(crate) mod cost_route;
 mod error_response;
 #[path = "routing/failover.rs"]
 mod failover;
+#[path = "routing/group_policy.rs"]
+mod group_policy;
 #[path = "observability/http_bridge/mod.rs"]
 mod http_bridge;
 #[path = "request/incoming_headers.rs"]
@@ -117,32 +138,43 @@ mod request_helpers;
 mod request_log;
 #[path = "request/request_rewrite.rs"]
 mod request_rewrite;
+#[path = "request/response_cache.rs"]
+mod response_cache;
+#[path = "request/response_store.rs"]
+mod response_store;
 #[path = "routing/route_hint.rs"]
 mod route_hint;
 #[path = "routing/route_quality.rs"]
 mod route_quality;
 #[path = "core/runtime_config.rs"]
 mod runtime_config;
+#[path = "core/runtime_state.rs"]
+mod runtime_state;
 #[path = "routing/selection.rs"]
 mod selection;
 #[path = "request/session_affinity.rs"]
 mod session_affinity;
+#[path = "request/structured_output.rs"]
+mod structured_output;
 #[path = "request/thread_anchor.rs"]
 mod thread_anchor;
+#[path = "request/token_counter.rs"]
+mod token_counter;
 #[path = "auth/token_exchange.rs"]
 mod token_exchange;
 #[path = "observability/trace_log.rs"]
 mod trace_log;
 mod upstream;
 
+pub(crate) use batches::{is_batch_running, resume_unfinished_batches};
 pub(crate) use concurrency::current_gateway_concurrency_recommendation;
 use metrics::{
     account_inflight_count, acquire_account_inflight, begin_gateway_request,
     record_gateway_candidate_skip, record_gateway_cooldown_mark, record_gateway_failover_attempt,
-    record_gateway_request_outcome, AccountInFlightGuard,
+    record_gateway_request_outcome, try_acquire_account_inflight, AccountInFlightGuard,
 };
 pub(crate) use metrics::{
-    begin_rpc_request, duration_to_millis, gateway_metrics_prometheus,
+    begin_rpc_request, duration_to_millis, gateway_active_requests, gateway_metrics_prometheus,
     record_usage_refresh_outcome, GatewayCandidateSkipReason,
 };
 pub(super) use official_responses_http::normalize_official_responses_http_body_with_value;
@@ -243,6 +275,17 @@ pub(crate) fn record_http_queue_dequeue(is_stream_queue: bool) {
 pub(crate) fn record_http_queue_enqueue_failure() {
     metrics::record_http_queue_enqueue_failure();
 }
+
+/// 停机排空结束后落盘：清空 trace 写入缓冲，并把 SQLite WAL 中的请求日志与 token 统计合并回主库。
+pub(crate) fn flush_gateway_state_for_shutdown() {
+    trace_log::flush_trace_writer();
+    let Some(storage) = open_storage() else {
+        return;
+    };
+    if let Err(err) = storage.checkpoint_wal() {
+        log::warn!("event=shutdown_wal_checkpoint_failed err={err}");
+    }
+}
 #[cfg(test)]
 use cooldown::cooldown_reason_for_status;
 use cooldown::{
@@ -254,6 +297,7 @@ pub(super) use failover::should_failover_after_refresh;
 use failover::{
     should_failover_from_cached_snapshot_value, should_failover_from_low_quota_snapshot_value,
 };
+pub(crate) use group_policy::invalidate_group_policy_cache as invalidate_account_group_policy_cache;
 use http_bridge::respond_with_upstream;
 pub(crate) use http_bridge::summarize_upstream_error_hint_from_body;
 pub(crate) use http_bridge::PassthroughSseProtocol;
@@ -403,14 +447,22 @@ fn decode_base64_header_value(input: &[u8]) -> Option<Vec<u8>> {
 
     Some(output)
 }
+use background_responses::maybe_respond_background_response;
+use batches::{maybe_respond_batch_request, prefer_batch_candidates};
+use chat_fan_out::{bind_fan_out_child, maybe_respond_chat_fan_out};
 pub(super) use incoming_headers::IncomingHeaderSnapshot;
-use local_count_tokens::maybe_respond_local_count_tokens;
+use local_count_tokens::{maybe_reject_context_window_overflow, maybe_respond_local_count_tokens};
 use local_models::maybe_respond_local_models;
 use openai_fallback::try_openai_fallback;
 pub(crate) use request_entry::handle_gateway_request;
 use request_gate::{request_gate_lock, RequestGateAcquireError};
 pub(crate) use request_log::write_request_log;
-use route_hint::{apply_route_strategy, apply_route_strategy_with_source};
+use response_store::maybe_respond_local_stored_response;
+pub(crate) use response_store::{
+    collect_stored_response_history, record_completed_response, ResponseStoreTurn,
+};
+use route_hint::apply_route_strategy_with_source;
+pub(crate) use route_hint::normalize_route_strategy;
 use route_quality::record_route_quality;
 pub(crate) use runtime_config::invalidate_account_proxy_client_cache as invalidate_account_proxy_cache;
 pub(crate) use runtime_config::upstream_client;
@@ -453,6 +505,7 @@ use upstream::proxy::proxy_validated_request;
 /// 无
 pub(crate) fn reload_runtime_config_from_env() {
     runtime_config::reload_from_env();
+    runtime_state::reload_from_env();
     selection::reload_from_env();
     request_gate::clear_runtime_state();
     cooldown::clear_runtime_state();
@@ -811,6 +864,66 @@ pub(crate) fn strict_request_param_allowlist_enabled() -> bool {
     runtime_config::strict_request_param_allowlist_enabled()
 }
 
+/// 是否开启上下文窗口预检。
+pub(crate) fn context_window_preflight_enabled() -> bool {
+    runtime_config::context_window_preflight_enabled()
+}
+
+/// 响应缓存总字节预算。
+pub(crate) fn response_cache_max_bytes() -> usize {
+    runtime_config::response_cache_max_bytes()
+}
+
+/// 单条响应缓存字节上限。
+pub(crate) fn response_cache_max_entry_bytes() -> usize {
+    runtime_config::response_cache_max_entry_bytes()
+}
+
+/// 网关侧 Responses 存储总字节预算。
+pub(crate) fn response_store_max_bytes() -> usize {
+    runtime_config::response_store_max_bytes()
+}
+
+/// 同时运行的后台 Responses 任务上限。
+pub(crate) fn background_response_max_concurrency() -> usize {
+    runtime_config::background_response_max_concurrency()
+}
+
+/// 已结束的后台 Responses 任务保留时长（秒）。
+pub(crate) fn background_response_retention_secs() -> u64 {
+    runtime_config::background_response_retention_secs()
+}
+
+/// 单个批任务同时执行的请求数。
+pub(crate) fn batch_max_concurrency() -> usize {
+    runtime_config::batch_max_concurrency()
+}
+
+/// 批处理输入文件的大小上限（字节）。
+pub(crate) fn batch_file_max_bytes() -> usize {
+    runtime_config::batch_file_max_bytes()
+}
+
+/// Chat Completions `n` 拆分的上限。
+pub(crate) fn chat_fan_out_max_n() -> usize {
+    runtime_config::chat_fan_out_max_n()
+}
+
+/// `n` 拆分子请求的全局在途上限。
+pub(crate) fn chat_fan_out_max_concurrency() -> usize {
+    runtime_config::chat_fan_out_max_concurrency()
+}
+
+/// 按模型开启结构化输出校验的模型列表。
+pub(crate) fn structured_output_validation_models() -> Vec<String> {
+    runtime_config::structured_output_validation_models()
+}
+
+/// 按模型开启校验时的修复重试次数上限。
+pub(crate) fn structured_output_max_repairs() -> usize {
+    runtime_config::structured_output_max_repairs()
+}
+
 /// 函数 `current_upstream_proxy_url`
 ///
 /// 作者: gaohongshun
@@ -7,9 +7,9 @@ pub(super) use output_text::append_output_text;
 pub(super) use output_text::{
     append_output_text_raw, collect_output_text_from_event_fields, collect_response_output_text,
     collect_response_reasoning_summary_text, extract_error_hint_from_body,
-    extract_error_message_from_json, merge_usage, parse_usage_from_json,
-    reload_from_env as reload_output_text_from_env, usage_has_signal, UpstreamResponseBridgeResult,
-    UpstreamResponseUsage,
+    extract_error_message_from_json, extract_final_message_text, merge_usage,
+    parse_usage_from_json, reload_from_env as reload_output_text_from_env, usage_has_signal,
+    UpstreamResponseBridgeResult, UpstreamResponseUsage,
 };
 #[cfg(test)]
 pub(super) use output_text::{output_text_limit_bytes, OUTPUT_TEXT_TRUNCATED_MARKER};
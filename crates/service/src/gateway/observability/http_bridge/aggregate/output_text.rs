@@ -650,6 +650,44 @@ fn extract_output_text_from_json(value: &Value) -> Option<String> {
     }
 }
 
+/// 函数 `extract_final_message_text`
+///
+/// 只拼接最终 assistant 消息里的 `output_text` 片段，不含推理摘要与工具调用；
+/// 片段之间不插入分隔符，保证 JSON 输出可以原样解析。
+pub(in super::super) fn extract_final_message_text(value: &Value) -> Option<String> {
+    let response = value.get("response").unwrap_or(value);
+    let mut output = String::new();
+    let mut found = false;
+    let messages = response
+        .get("output")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("type").and_then(Value::as_str) == Some("message"));
+    for message in messages {
+        let parts = message
+            .get("content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|part| part.get("type").and_then(Value::as_str) == Some("output_text"));
+        for part in parts {
+            if let Some(text) = part.get("text").and_then(Value::as_str) {
+                output.push_str(text);
+                found = true;
+            }
+        }
+    }
+    if found {
+        return Some(output);
+    }
+    value
+        .pointer("/choices/0/message/content")
+        .and_then(Value::as_str)
+        .or_else(|| response.get("output_text").and_then(Value::as_str))
+        .map(str::to_string)
+}
+
 /// 函数 `parse_usage_from_json`
 ///
 /// 作者: gaohongshun
@@ -1,7 +1,7 @@
 use super::{
-    extract_error_hint_from_body, limit_upstream_error_hint, output_text_limit_bytes,
-    reload_from_env, summarize_upstream_error_hint, UpstreamResponseBridgeResult,
-    UPSTREAM_ERROR_HINT_LIMIT_BYTES,
+    extract_error_hint_from_body, extract_final_message_text, limit_upstream_error_hint,
+    output_text_limit_bytes, reload_from_env, summarize_upstream_error_hint,
+    UpstreamResponseBridgeResult, UPSTREAM_ERROR_HINT_LIMIT_BYTES,
 };
 
 struct EnvGuard {
@@ -237,3 +237,24 @@ fn output_text_limit_invalid_value_falls_back_to_unbounded() {
 
     assert_eq!(output_text_limit_bytes(), 0);
 }
+
+#[test]
+fn extract_final_message_text_joins_output_text_parts_without_separator() {
+    let value = serde_json::json!({
+        "output": [
+            {"type": "reasoning", "summary": [{"type": "summary_text", "text": "thinking"}]},
+            {"type": "message", "content": [
+                {"type": "output_text", "text": "{\"a\":"},
+                {"type": "output_text", "text": "1}"}
+            ]}
+        ]
+    });
+    assert_eq!(
+        extract_final_message_text(&value).as_deref(),
+        Some("{\"a\":1}")
+    );
+
+    let chat = serde_json::json!({"choices": [{"message": {"content": "{}"}}]});
+    assert_eq!(extract_final_message_text(&chat).as_deref(), Some("{}"));
+    assert_eq!(extract_final_message_text(&serde_json::json!({})), None);
+}
@@ -174,7 +174,7 @@ fn respond_passthrough_collector_stream(
 pub(crate) fn respond_with_upstream(
     request: Request,
     upstream: reqwest::blocking::Response,
-    _inflight_guard: super::super::AccountInFlightGuard,
+    _inflight_guard: Option<super::super::AccountInFlightGuard>,
     response_adapter: ResponseAdapter,
     passthrough_sse_protocol: Option<PassthroughSseProtocol>,
     gemini_stream_output_mode: Option<GeminiStreamOutputMode>,
@@ -900,9 +900,9 @@ pub(crate) fn respond_with_upstream(
 pub(crate) fn respond_with_stream_upstream(
     request: Request,
     upstream: GatewayStreamResponse,
-    _inflight_guard: super::super::AccountInFlightGuard,
+    _inflight_guard: Option<super::super::AccountInFlightGuard>,
     response_adapter: ResponseAdapter,
-    _passthrough_sse_protocol: Option<PassthroughSseProtocol>,
+    passthrough_sse_protocol: Option<PassthroughSseProtocol>,
     gemini_stream_output_mode: Option<GeminiStreamOutputMode>,
     request_path: &str,
     tool_name_restore_map: Option<&ToolNameRestoreMap>,
@@ -1480,6 +1480,14 @@ pub(crate) fn respond_with_stream_upstream(
                             keepalive_frame,
                             request_started_at,
                         ))
+                    } else if let Some(protocol) = passthrough_sse_protocol {
+                        Box::new(PassthroughSseUsageReader::from_stream_response(
+                            upstream,
+                            Arc::clone(&usage_collector),
+                            keepalive_frame,
+                            protocol,
+                            request_started_at,
+                        ))
                     } else {
                         return Err(format!(
                             "stream upstream response is not supported for path {request_path}"
@@ -70,6 +70,47 @@ pub(crate) fn summarize_upstream_error_hint_from_body(
     aggregate::extract_error_hint_from_body(status_code, body)
 }
 
+/// 函数 `extract_final_output_text_from_body`
+///
+/// 从非流式 JSON 或完整 SSE 上游响应体中取出最终消息文本，供结构化输出校验使用。
+pub(crate) fn extract_final_output_text_from_body(body: &[u8]) -> Option<String> {
+    if looks_like_sse_payload(body) {
+        let (aggregated, _) = collect_non_stream_json_from_sse_bytes(body);
+        let value = serde_json::from_slice::<serde_json::Value>(aggregated?.as_slice()).ok()?;
+        return aggregate::extract_final_message_text(&value);
+    }
+    let value = serde_json::from_slice::<serde_json::Value>(body).ok()?;
+    aggregate::extract_final_message_text(&value)
+}
+
+/// 函数 `extract_usage_from_body`
+///
+/// 从非流式 JSON 或完整 SSE 上游响应体中取出用量，供结构化输出修复累计被替换响应的消耗。
+pub(crate) fn extract_usage_from_body(body: &[u8]) -> crate::gateway::RequestLogUsage {
+    let usage = if looks_like_sse_payload(body) {
+        let (synthesized, mut usage) = collect_non_stream_json_from_sse_bytes(body);
+        if let Some(synthesized) = synthesized {
+            body_conversion::merge_usage_from_body_without_output_text(&mut usage, &synthesized);
+        }
+        usage
+    } else {
+        serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .map(|value| parse_usage_from_json(&value))
+            .unwrap_or_default()
+    };
+    crate::gateway::RequestLogUsage {
+        input_tokens: usage.input_tokens,
+        cached_input_tokens: usage.cached_input_tokens,
+        cache_write_tokens: usage.cache_write_tokens,
+        output_tokens: usage.output_tokens,
+        total_tokens: usage.total_tokens,
+        reasoning_output_tokens: usage.reasoning_output_tokens,
+        first_response_ms: None,
+        estimated_input_tokens: None,
+    }
+}
+
 mod delivery;
 mod stream_readers;
 /// 函数 `respond_with_upstream`
@@ -86,7 +127,7 @@ mod stream_readers;
 pub(super) fn respond_with_upstream(
     request: Request,
     upstream: GatewayUpstreamResponse,
-    inflight_guard: super::AccountInFlightGuard,
+    inflight_guard: Option<super::AccountInFlightGuard>,
     response_adapter: super::ResponseAdapter,
     passthrough_sse_protocol: Option<PassthroughSseProtocol>,
     gemini_stream_output_mode: Option<super::GeminiStreamOutputMode>,
@@ -7,6 +7,7 @@ use super::{
     UpstreamSseFramePumpItem,
 };
 use crate::gateway::http_bridge::{extract_error_hint_from_body, extract_error_message_from_json};
+use crate::gateway::upstream::GatewayStreamResponse;
 use std::time::Instant;
 
 pub(crate) struct PassthroughSseUsageReader {
@@ -53,6 +54,26 @@ impl PassthroughSseUsageReader {
         }
     }
 
+    pub(crate) fn from_stream_response(
+        upstream: GatewayStreamResponse,
+        usage_collector: Arc<Mutex<PassthroughSseCollector>>,
+        keepalive_frame: SseKeepAliveFrame,
+        protocol: PassthroughSseProtocol,
+        request_started_at: Instant,
+    ) -> Self {
+        Self {
+            upstream: UpstreamSseFramePump::from_reader(upstream.into_body()),
+            out_cursor: Cursor::new(Vec::new()),
+            usage_collector,
+            keepalive_frame,
+            protocol,
+            request_started_at,
+            last_upstream_activity: Instant::now(),
+            saw_upstream_frame: false,
+            finished: false,
+        }
+    }
+
     /// 函数 `update_usage_from_frame`
     ///
     /// 作者: gaohongshun
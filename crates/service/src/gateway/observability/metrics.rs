@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
+use super::runtime_state::RuntimeStateBackend;
+
+// 中文注释：共享租约的 TTL 只兜底副本崩溃未释放的情况，需长于最长的流式请求。
+const ACCOUNT_INFLIGHT_LEASE_TTL: Duration = Duration::from_secs(60 * 60);
+
 static ACCOUNT_INFLIGHT: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
 static GATEWAY_REQUEST_LABELS: OnceLock<Mutex<HashMap<GatewayRequestLabelKey, usize>>> =
     OnceLock::new();
@@ -151,6 +156,11 @@ pub(crate) fn begin_gateway_request() -> GatewayRequestGuard {
     GatewayRequestGuard
 }
 
+/// 当前正在处理的网关请求数（含流式响应），停机排空据此判断是否可以退出。
+pub(crate) fn gateway_active_requests() -> usize {
+    GATEWAY_ACTIVE_REQUESTS.load(Ordering::Relaxed)
+}
+
 /// 函数 `begin_rpc_request`
 ///
 /// 作者: gaohongshun
@@ -658,6 +668,21 @@ fn classify_protocol(protocol_type: Option<&str>) -> &'static str {
 /// # 返回
 /// 返回函数执行结果
 pub(crate) fn account_inflight_count(account_id: &str) -> usize {
+    if let Some(backend) = super::runtime_state::shared_runtime_state_backend() {
+        match backend.lease_count(super::runtime_state::SCOPE_ACCOUNT_INFLIGHT, account_id) {
+            Ok(count) => return count,
+            Err(err) => log::warn!(
+                "event=account_inflight_shared_count_failed backend={} account_id={} err={}",
+                backend.name(),
+                account_id,
+                err
+            ),
+        }
+    }
+    local_account_inflight_count(account_id)
+}
+
+fn local_account_inflight_count(account_id: &str) -> usize {
     let lock = ACCOUNT_INFLIGHT.get_or_init(|| Mutex::new(HashMap::new()));
     let map = crate::lock_utils::lock_recover(lock, "account_inflight");
     map.get(account_id).copied().unwrap_or(0)
@@ -665,6 +690,7 @@ pub(crate) fn account_inflight_count(account_id: &str) -> usize {
 
 pub(crate) struct AccountInFlightGuard {
     account_id: String,
+    shared_lease: Option<(Arc<dyn RuntimeStateBackend>, String)>,
 }
 
 impl Drop for AccountInFlightGuard {
@@ -680,6 +706,20 @@ impl Drop for AccountInFlightGuard {
     /// # 返回
     /// 无
     fn drop(&mut self) {
+        if let Some((backend, lease_id)) = self.shared_lease.take() {
+            if let Err(err) = backend.release_lease(
+                super::runtime_state::SCOPE_ACCOUNT_INFLIGHT,
+                &self.account_id,
+                &lease_id,
+            ) {
+                log::warn!(
+                    "event=account_inflight_shared_release_failed backend={} account_id={} err={}",
+                    backend.name(),
+                    self.account_id,
+                    err
+                );
+            }
+        }
         let lock = ACCOUNT_INFLIGHT.get_or_init(|| Mutex::new(HashMap::new()));
         let mut map = crate::lock_utils::lock_recover(lock, "account_inflight");
         if let Some(value) = map.get_mut(&self.account_id) {
@@ -704,12 +744,74 @@ impl Drop for AccountInFlightGuard {
 /// # 返回
 /// 返回函数执行结果
 pub(crate) fn acquire_account_inflight(account_id: &str) -> AccountInFlightGuard {
+    let lock = ACCOUNT_INFLIGHT.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut map = crate::lock_utils::lock_recover(lock, "account_inflight");
+    *map.entry(account_id.to_string()).or_insert(0) += 1;
+    drop(map);
+    let shared_lease = match acquire_shared_inflight_lease(account_id, 0) {
+        SharedInflightLease::Acquired(backend, lease_id) => Some((backend, lease_id)),
+        SharedInflightLease::Full | SharedInflightLease::Unavailable => None,
+    };
+    AccountInFlightGuard {
+        account_id: account_id.to_string(),
+        shared_lease,
+    }
+}
+
+/// 函数 `try_acquire_account_inflight`
+///
+/// 按并发上限原子地占用一个在途名额；`limit` 为 0 表示不限，已满时返回 None。
+/// 共享后端可用时以共享租约计数为准，不可用时退回进程内计数。
+pub(crate) fn try_acquire_account_inflight(
+    account_id: &str,
+    limit: usize,
+) -> Option<AccountInFlightGuard> {
+    let shared_lease = match acquire_shared_inflight_lease(account_id, limit) {
+        SharedInflightLease::Acquired(backend, lease_id) => Some((backend, lease_id)),
+        SharedInflightLease::Full => return None,
+        SharedInflightLease::Unavailable => None,
+    };
     let lock = ACCOUNT_INFLIGHT.get_or_init(|| Mutex::new(HashMap::new()));
     let mut map = crate::lock_utils::lock_recover(lock, "account_inflight");
     let entry = map.entry(account_id.to_string()).or_insert(0);
+    if shared_lease.is_none() && limit > 0 && *entry >= limit {
+        return None;
+    }
     *entry += 1;
-    AccountInFlightGuard {
+    drop(map);
+    Some(AccountInFlightGuard {
         account_id: account_id.to_string(),
+        shared_lease,
+    })
+}
+
+enum SharedInflightLease {
+    Acquired(Arc<dyn RuntimeStateBackend>, String),
+    Full,
+    Unavailable,
+}
+
+fn acquire_shared_inflight_lease(account_id: &str, limit: usize) -> SharedInflightLease {
+    let Some(backend) = super::runtime_state::shared_runtime_state_backend() else {
+        return SharedInflightLease::Unavailable;
+    };
+    match backend.acquire_lease(
+        super::runtime_state::SCOPE_ACCOUNT_INFLIGHT,
+        account_id,
+        ACCOUNT_INFLIGHT_LEASE_TTL,
+        limit,
+    ) {
+        Ok(Some(lease_id)) => SharedInflightLease::Acquired(backend, lease_id),
+        Ok(None) => SharedInflightLease::Full,
+        Err(err) => {
+            log::warn!(
+                "event=account_inflight_shared_acquire_failed backend={} account_id={} err={}",
+                backend.name(),
+                account_id,
+                err
+            );
+            SharedInflightLease::Unavailable
+        }
     }
 }
 
@@ -1,4 +1,7 @@
-use codexmanager_core::storage::{now_ts, RequestLog, RequestTokenStat, Storage};
+use codexmanager_core::storage::{
+    now_ts, RequestContentPolicyMatches, RequestLog, RequestRouteDecision,
+    RequestStructuredOutputResult, RequestTokenStat, Storage,
+};
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
 
@@ -50,6 +53,7 @@ pub(crate) struct RequestLogTraceContext<'a> {
     pub upstream_model: Option<&'a str>,
     pub actual_source_kind: Option<&'a str>,
     pub actual_source_id: Option<&'a str>,
+    pub route_decision: Option<&'a str>,
 }
 
 /// 函数 `normalize_token`
@@ -303,6 +307,54 @@ pub(crate) fn write_request_log(
     );
 }
 
+/// 推送请求完成摘要；归属成员的 API Key 产生的事件对该成员可见。
+/// 没有订阅者时整条跳过，避免每个请求都多一次 API Key 归属查询。
+#[allow(clippy::too_many_arguments)]
+fn publish_request_completed_event(
+    storage: &Storage,
+    request_log_id: i64,
+    trace_context: &RequestLogTraceContext<'_>,
+    key_id: Option<&str>,
+    account_id: Option<&str>,
+    request_path: &str,
+    method: &str,
+    model: Option<&str>,
+    status_code: Option<u16>,
+    total_tokens: Option<i64>,
+    duration_ms: Option<i64>,
+    error: Option<&str>,
+    created_at: i64,
+) {
+    if !crate::event_bus::has_event_subscribers() {
+        return;
+    }
+    let owner_user_id = key_id
+        .and_then(|key_id| storage.find_api_key_owner(key_id).ok().flatten())
+        .and_then(|owner| owner.owner_user_id);
+    let audience = match owner_user_id {
+        Some(user_id) => crate::event_bus::EventAudience::User(user_id),
+        None => crate::event_bus::EventAudience::Admin,
+    };
+    crate::event_bus::publish_event(
+        crate::event_bus::TOPIC_REQUEST_COMPLETED,
+        audience,
+        serde_json::json!({
+            "requestLogId": request_log_id,
+            "traceId": trace_context.trace_id,
+            "keyId": key_id,
+            "accountId": account_id,
+            "requestPath": request_path,
+            "method": method,
+            "model": model,
+            "statusCode": status_code,
+            "totalTokens": total_tokens,
+            "durationMs": duration_ms,
+            "error": error,
+            "createdAt": created_at,
+        }),
+    );
+}
+
 /// 函数 `write_request_log_with_attempts`
 ///
 /// 作者: gaohongshun
@@ -331,6 +383,23 @@ pub(crate) fn write_request_log_with_attempts(
     duration_ms: Option<u128>,
     attempted_account_ids: Option<&[String]>,
 ) {
+    let mut usage = usage;
+    // 中文注释：结构化输出修复替换掉的响应同样计费，先并入用量（`n > 1` 子请求也随之汇总到父请求）。
+    let structured_output = trace_context
+        .trace_id
+        .and_then(super::structured_output::take_structured_output_report);
+    if let Some(report) = structured_output.as_ref() {
+        super::structured_output::merge_repair_usage(&mut usage, &report.repair_usage);
+    }
+    if super::chat_fan_out::capture_fan_out_child_log(
+        trace_context.trace_id,
+        account_id,
+        upstream_url,
+        attempted_account_ids,
+        usage,
+    ) {
+        return;
+    }
     let original_path = trace_context.original_path.unwrap_or(request_path);
     let adapted_path = trace_context.adapted_path.unwrap_or(request_path);
     let initial_account_id = attempted_account_ids
@@ -522,6 +591,71 @@ pub(crate) fn write_request_log_with_attempts(
         }
     };
 
+    if let Some(decision_json) = trace_context
+        .route_decision
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        if let Err(err) = storage.insert_request_route_decision(&RequestRouteDecision {
+            request_log_id,
+            trace_id: trace_context.trace_id.map(str::to_string),
+            strategy: normalize_log_text(trace_context.route_strategy)
+                .unwrap_or_else(|| "unknown".to_string()),
+            decision_json: decision_json.to_string(),
+            created_at,
+        }) {
+            log::warn!(
+                "event=gateway_route_decision_insert_failed request_log_id={} err={}",
+                request_log_id,
+                err
+            );
+        }
+    }
+
+    if let Some(matches_json) = trace_context
+        .trace_id
+        .and_then(super::local_validation::take_content_policy_matches)
+    {
+        if let Err(err) =
+            storage.insert_request_content_policy_matches(&RequestContentPolicyMatches {
+                request_log_id,
+                trace_id: trace_context.trace_id.map(str::to_string),
+                matches_json,
+                created_at,
+            })
+        {
+            log::warn!(
+                "event=gateway_content_policy_matches_insert_failed request_log_id={} err={}",
+                request_log_id,
+                err
+            );
+        }
+    }
+
+    if let Some(report) = structured_output {
+        if let Err(err) =
+            storage.insert_request_structured_output_result(&RequestStructuredOutputResult {
+                request_log_id,
+                trace_id: trace_context.trace_id.map(str::to_string),
+                outcome: report.outcome.as_str().to_string(),
+                repairs: i64::try_from(report.repairs).unwrap_or(i64::MAX),
+                skip_reason: report.skip_reason.map(str::to_string),
+                errors_json: serde_json::to_string(&report.errors)
+                    .unwrap_or_else(|_| "[]".to_string()),
+                repair_input_tokens: report.repair_usage.input_tokens,
+                repair_output_tokens: report.repair_usage.output_tokens,
+                repair_total_tokens: report.repair_usage.total_tokens,
+                created_at,
+            })
+        {
+            log::warn!(
+                "event=gateway_structured_output_result_insert_failed request_log_id={} err={}",
+                request_log_id,
+                err
+            );
+        }
+    }
+
     if let Some(err) = token_stat_error {
         let err_text = err.to_string();
         super::metrics::record_db_error(err_text.as_str());
@@ -536,6 +670,22 @@ pub(crate) fn write_request_log_with_attempts(
         );
     }
 
+    publish_request_completed_event(
+        storage,
+        request_log_id,
+        &trace_context,
+        key_id,
+        account_id,
+        request_path,
+        method,
+        model,
+        status_code,
+        total_tokens,
+        duration_ms,
+        error,
+        created_at,
+    );
+
     if success {
         touch_api_key_last_used_after_success(storage, key_id, created_at);
     }
@@ -12,6 +12,7 @@ use serde_json::Value;
 
 const DEFAULT_TRACE_QUEUE_CAPACITY: usize = 0;
 const TRACE_FLUSH_WAIT_TIMEOUT_MS: u64 = 200;
+const TRACE_SHUTDOWN_FLUSH_TIMEOUT_MS: u64 = 2_000;
 const ENV_TRACE_QUEUE_CAPACITY: &str = "CODEXMANAGER_TRACE_QUEUE_CAPACITY";
 const ENV_GEMINI_TRACE_DIAGNOSTICS: &str = "CODEXMANAGER_GEMINI_TRACE_DIAGNOSTICS";
 const ENV_GATEWAY_TRACE_STDOUT: &str = "CODEXMANAGER_GATEWAY_TRACE_STDOUT";
@@ -30,6 +31,7 @@ enum TraceCommand {
         ack: Option<SyncSender<()>>,
     },
     ResetPath(PathBuf),
+    Flush(SyncSender<()>),
 }
 
 enum TraceCommandSender {
@@ -719,6 +721,18 @@ fn trace_writer() -> &'static TraceAsyncWriter {
     TRACE_WRITER.get_or_init(|| TraceAsyncWriter::new(trace_file_path_from_env()))
 }
 
+/// 停机前等待写入线程处理完已入队的 trace 行并刷新文件缓冲；写入线程未启动时直接返回。
+pub(crate) fn flush_trace_writer() {
+    let Some(writer) = TRACE_WRITER.get() else {
+        return;
+    };
+    let (ack_tx, ack_rx) = mpsc::sync_channel(1);
+    if writer.tx.send(TraceCommand::Flush(ack_tx)).is_err() {
+        return;
+    }
+    let _ = ack_rx.recv_timeout(Duration::from_millis(TRACE_SHUTDOWN_FLUSH_TIMEOUT_MS));
+}
+
 /// 函数 `trace_writer_loop`
 ///
 /// 作者: gaohongshun
@@ -748,6 +762,18 @@ fn trace_writer_loop(rx: Receiver<TraceCommand>, mut writer: TraceFileWriter) {
                 }
             }
             TraceCommand::ResetPath(path) => writer.reset_path(path),
+            TraceCommand::Flush(ack) => {
+                if let Some(file) = writer.writer.as_mut() {
+                    if let Err(err) = file.flush() {
+                        log::warn!(
+                            "gateway trace flush failed: path={}, err={}",
+                            writer.path.display(),
+                            err
+                        );
+                    }
+                }
+                let _ = ack.send(());
+            }
         }
     }
 }
@@ -0,0 +1,878 @@
+use codexmanager_core::storage::{
+    now_ts, GatewayBackgroundResponse, GatewayBackgroundResponseEvent, Storage,
+};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Request, Response, StatusCode};
+
+use super::local_response::LocalResponseContext;
+
+const RESPONSES_PATH: &str = "/v1/responses";
+const BACKGROUND_RESPONSE_ID_PREFIX: &str = "resp_bg_";
+const EVENT_PAGE_SIZE: usize = 256;
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const LOOPBACK_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ERROR_BODY_CHARS: usize = 2048;
+const SKIPPED_FORWARD_HEADERS: &[&str] = &[
+    "accept",
+    "accept-encoding",
+    "connection",
+    "content-length",
+    "host",
+    "keep-alive",
+    "te",
+    "transfer-encoding",
+    "upgrade",
+];
+
+const STATUS_QUEUED: &str = "queued";
+const STATUS_IN_PROGRESS: &str = "in_progress";
+const STATUS_FAILED: &str = "failed";
+const STATUS_CANCELLED: &str = "cancelled";
+
+static RUNNING_BACKGROUND_RESPONSES: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    OnceLock::new();
+static LOOPBACK_CLIENT: OnceLock<Client> = OnceLock::new();
+
+fn running_background_responses() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    RUNNING_BACKGROUND_RESPONSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 并发未满时登记任务并返回取消标记；满额返回 `None`。
+fn try_register_background_response(response_id: &str, limit: usize) -> Option<Arc<AtomicBool>> {
+    let mut running = crate::lock_utils::lock_recover(
+        running_background_responses(),
+        "running_background_responses",
+    );
+    if running.len() >= limit {
+        return None;
+    }
+    let cancelled = Arc::new(AtomicBool::new(false));
+    running.insert(response_id.to_string(), cancelled.clone());
+    Some(cancelled)
+}
+
+fn unregister_background_response(response_id: &str) {
+    crate::lock_utils::lock_recover(
+        running_background_responses(),
+        "running_background_responses",
+    )
+    .remove(response_id);
+}
+
+fn background_cancel_flag(response_id: &str) -> Option<Arc<AtomicBool>> {
+    crate::lock_utils::lock_recover(
+        running_background_responses(),
+        "running_background_responses",
+    )
+    .get(response_id)
+    .cloned()
+}
+
+/// 网关内部回环重放共用的 HTTP 客户端（后台 Responses、批处理）。
+pub(super) fn loopback_client() -> Result<&'static Client, String> {
+    if let Some(client) = LOOPBACK_CLIENT.get() {
+        return Ok(client);
+    }
+    // 中文注释：请求只发往本进程 loopback 后端，不能走上游代理，也不能设置整体超时（长任务可能持续数十分钟）。
+    let client = Client::builder()
+        .no_proxy()
+        .connect_timeout(LOOPBACK_CONNECT_TIMEOUT)
+        .timeout(None::<Duration>)
+        .build()
+        .map_err(|err| format!("build gateway loopback client failed: {err}"))?;
+    Ok(LOOPBACK_CLIENT.get_or_init(|| client))
+}
+
+fn is_active_status(status: &str) -> bool {
+    status == STATUS_QUEUED || status == STATUS_IN_PROGRESS
+}
+
+fn new_background_response_id() -> String {
+    format!(
+        "{BACKGROUND_RESPONSE_ID_PREFIX}{:032x}",
+        rand::random::<u128>()
+    )
+}
+
+enum BackgroundRoute<'a> {
+    Create,
+    Retrieve {
+        response_id: &'a str,
+        query: Option<&'a str>,
+    },
+    Cancel {
+        response_id: &'a str,
+    },
+    Delete {
+        response_id: &'a str,
+    },
+}
+
+fn resolve_background_route<'a>(method: &str, path: &'a str) -> Option<BackgroundRoute<'a>> {
+    let (path_only, query) = match path.split_once('?') {
+        Some((path_only, query)) => (path_only, Some(query)),
+        None => (path, None),
+    };
+    if path_only == RESPONSES_PATH {
+        return method
+            .eq_ignore_ascii_case("POST")
+            .then_some(BackgroundRoute::Create);
+    }
+    let rest = path_only.strip_prefix("/v1/responses/")?;
+    let (response_id, action) = match rest.split_once('/') {
+        Some((response_id, action)) => (response_id, Some(action)),
+        None => (rest, None),
+    };
+    // 中文注释：只接管网关生成的后台 id，其余 id 交给 Responses 存储或上游处理。
+    if !response_id.starts_with(BACKGROUND_RESPONSE_ID_PREFIX) {
+        return None;
+    }
+    match (method.to_ascii_uppercase().as_str(), action) {
+        ("GET", None) => Some(BackgroundRoute::Retrieve { response_id, query }),
+        ("POST", Some("cancel")) => Some(BackgroundRoute::Cancel { response_id }),
+        ("DELETE", None) => Some(BackgroundRoute::Delete { response_id }),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RetrieveOptions {
+    stream: bool,
+    starting_after: Option<i64>,
+}
+
+fn parse_retrieve_options(query: Option<&str>) -> RetrieveOptions {
+    let mut options = RetrieveOptions::default();
+    for pair in query.unwrap_or_default().split('&') {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match name {
+            "stream" => options.stream = value.eq_ignore_ascii_case("true") || value == "1",
+            "starting_after" => options.starting_after = value.parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+    options
+}
+
+/// 生成排队状态的 response 快照；提交后立即返回给客户端。
+fn queued_response_snapshot(response_id: &str, created_at: i64, request: &Value) -> Value {
+    json!({
+        "id": response_id,
+        "object": "response",
+        "created_at": created_at,
+        "status": STATUS_QUEUED,
+        "background": true,
+        "model": request.get("model").cloned().unwrap_or(Value::Null),
+        "previous_response_id": request
+            .get("previous_response_id")
+            .cloned()
+            .unwrap_or(Value::Null),
+        "metadata": request.get("metadata").cloned().unwrap_or(Value::Null),
+        "output": [],
+        "error": null,
+        "incomplete_details": null,
+    })
+}
+
+/// 去掉 `background` 并强制流式，由网关在后台消费上游事件。
+fn build_background_upstream_body(request: &Value) -> Result<Vec<u8>, String> {
+    let mut body = request.clone();
+    let object = body
+        .as_object_mut()
+        .ok_or_else(|| "background request body must be a JSON object".to_string())?;
+    object.remove("background");
+    object.insert("stream".to_string(), Value::Bool(true));
+    serde_json::to_vec(&body)
+        .map_err(|err| format!("serialize background request body failed: {err}"))
+}
+
+fn with_status(snapshot: &Value, status: &str) -> Value {
+    let mut snapshot = snapshot.clone();
+    if let Some(object) = snapshot.as_object_mut() {
+        object.insert("status".to_string(), Value::String(status.to_string()));
+    }
+    snapshot
+}
+
+fn failed_snapshot(snapshot: &Value, message: &str) -> Value {
+    let mut snapshot = with_status(snapshot, STATUS_FAILED);
+    if let Some(object) = snapshot.as_object_mut() {
+        object.insert(
+            "error".to_string(),
+            json!({ "code": "server_error", "message": message }),
+        );
+    }
+    snapshot
+}
+
+/// 把上游事件改写成后台 id 与网关序号；返回事件类型。
+fn rewrite_background_event(event: &mut Value, response_id: &str, sequence_number: i64) -> String {
+    let event_type = event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if let Some(object) = event.as_object_mut() {
+        object.insert("sequence_number".to_string(), json!(sequence_number));
+        if let Some(response) = object.get_mut("response").and_then(Value::as_object_mut) {
+            response.insert("id".to_string(), Value::String(response_id.to_string()));
+            response.insert("background".to_string(), Value::Bool(true));
+        }
+    }
+    event_type
+}
+
+fn terminal_status_for_event(event_type: &str) -> Option<&'static str> {
+    match event_type {
+        "response.completed" => Some("completed"),
+        "response.failed" | "error" => Some(STATUS_FAILED),
+        "response.incomplete" => Some("incomplete"),
+        _ => None,
+    }
+}
+
+fn sse_frame(event_json: &str) -> Vec<u8> {
+    let event_type = serde_json::from_str::<Value>(event_json)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("type")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+    let mut frame = String::new();
+    if let Some(event_type) = event_type {
+        frame.push_str("event: ");
+        frame.push_str(event_type.as_str());
+        frame.push('\n');
+    }
+    frame.push_str("data: ");
+    frame.push_str(event_json);
+    frame.push_str("\n\n");
+    frame.into_bytes()
+}
+
+struct BackgroundResponseJob {
+    response_id: String,
+    backend_addr: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    snapshot: Value,
+    store_turn: Option<super::response_store::ResponseStoreTurn>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl BackgroundResponseJob {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+struct BackgroundEventSink<'a> {
+    storage: &'a Storage,
+    job: &'a BackgroundResponseJob,
+    next_sequence_number: i64,
+}
+
+impl BackgroundEventSink<'_> {
+    fn append(&mut self, event: &Value) -> Result<(), String> {
+        let event_json = serde_json::to_string(event)
+            .map_err(|err| format!("serialize background event failed: {err}"))?;
+        self.storage
+            .append_gateway_background_response_event(
+                &GatewayBackgroundResponseEvent {
+                    response_id: self.job.response_id.clone(),
+                    sequence_number: self.next_sequence_number,
+                    event_json,
+                },
+                now_ts(),
+            )
+            .map_err(|err| format!("append background event failed: {err}"))?;
+        self.next_sequence_number += 1;
+        Ok(())
+    }
+
+    /// 处理一条上游 SSE 事件；进入终态时返回 `true`。
+    fn observe(&mut self, payload: &str) -> Result<bool, String> {
+        let Ok(mut event) = serde_json::from_str::<Value>(payload) else {
+            return Ok(false);
+        };
+        let event_type = rewrite_background_event(
+            &mut event,
+            self.job.response_id.as_str(),
+            self.next_sequence_number,
+        );
+        self.append(&event)?;
+        let snapshot = event.get("response").cloned();
+        if let Some(status) = terminal_status_for_event(event_type.as_str()) {
+            let snapshot = snapshot.unwrap_or_else(|| {
+                let message = event
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("upstream reported an error");
+                failed_snapshot(&self.job.snapshot, message)
+            });
+            self.finish(status, &snapshot)?;
+            return Ok(true);
+        }
+        if let Some(snapshot) = snapshot {
+            self.update(STATUS_IN_PROGRESS, &snapshot)?;
+        }
+        Ok(false)
+    }
+
+    fn update(&self, status: &str, snapshot: &Value) -> Result<bool, String> {
+        let response_json = serde_json::to_string(snapshot)
+            .map_err(|err| format!("serialize background response failed: {err}"))?;
+        self.storage
+            .update_gateway_background_response(
+                self.job.response_id.as_str(),
+                status,
+                response_json.as_str(),
+                now_ts(),
+            )
+            .map_err(|err| format!("update background response failed: {err}"))
+    }
+
+    fn finish(&self, status: &str, snapshot: &Value) -> Result<(), String> {
+        let snapshot = with_status(snapshot, status);
+        if !self.update(status, &snapshot)? {
+            return Ok(());
+        }
+        if status == "completed" {
+            if let Some(turn) = self.job.store_turn.as_ref() {
+                // 中文注释：以后台 id 写入 Responses 存储，后续 previous_response_id 才能续接。
+                super::response_store::record_completed_response(
+                    self.storage,
+                    turn,
+                    None,
+                    &snapshot,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+fn execute_background_response(sink: &mut BackgroundEventSink<'_>) -> Result<(), String> {
+    let job = sink.job;
+    sink.update(
+        STATUS_IN_PROGRESS,
+        &with_status(&job.snapshot, STATUS_IN_PROGRESS),
+    )?;
+    let mut request = loopback_client()?
+        .post(format!("http://{}{RESPONSES_PATH}", job.backend_addr))
+        .header("Accept", "text/event-stream")
+        .body(job.body.clone());
+    for (name, value) in &job.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .map_err(|err| format!("background request failed: {err}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(format!(
+            "background request returned {}: {}",
+            status.as_u16(),
+            truncate_chars(body.trim(), MAX_ERROR_BODY_CHARS)
+        ));
+    }
+
+    let mut reader = BufReader::new(response);
+    let mut line = String::new();
+    let mut data = String::new();
+    loop {
+        if job.is_cancelled() {
+            return Ok(());
+        }
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|err| format!("read background stream failed: {err}"))?;
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if let Some(chunk) = trimmed.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(chunk.trim_start());
+        } else if (trimmed.is_empty() || read == 0) && !data.is_empty() {
+            let payload = std::mem::take(&mut data);
+            if payload != "[DONE]" && sink.observe(payload.as_str())? {
+                return Ok(());
+            }
+        }
+        if read == 0 {
+            return Err("background stream closed before the response completed".to_string());
+        }
+    }
+}
+
+/// 函数 `run_background_response`
+///
+/// 后台线程入口：经 loopback 后端走完整代理链路，把事件与最终快照写入存储。
+fn run_background_response(job: BackgroundResponseJob) {
+    let Some(storage) = crate::storage_helpers::open_storage() else {
+        log::warn!(
+            "event=gateway_background_response_failed response_id={} err=open storage failed",
+            job.response_id
+        );
+        unregister_background_response(job.response_id.as_str());
+        return;
+    };
+    let mut sink = BackgroundEventSink {
+        storage: &storage,
+        job: &job,
+        next_sequence_number: 0,
+    };
+    if let Err(err) = execute_background_response(&mut sink) {
+        if !job.is_cancelled() {
+            log::warn!(
+                "event=gateway_background_response_failed response_id={} err={}",
+                job.response_id,
+                err
+            );
+            let snapshot = failed_snapshot(&job.snapshot, err.as_str());
+            let _ = sink.append(&json!({ "type": "response.failed", "response": snapshot }));
+            if let Err(err) = sink.finish(STATUS_FAILED, &snapshot) {
+                log::warn!(
+                    "event=gateway_background_response_finish_failed response_id={} err={}",
+                    job.response_id,
+                    err
+                );
+            }
+        }
+    }
+    unregister_background_response(job.response_id.as_str());
+}
+
+/// 进程重启等原因导致任务不再运行时，把残留的进行中状态收敛为失败。
+fn reconcile_background_response(
+    storage: &Storage,
+    record: GatewayBackgroundResponse,
+) -> Result<GatewayBackgroundResponse, String> {
+    if !is_active_status(record.status.as_str())
+        || background_cancel_flag(record.response_id.as_str()).is_some()
+    {
+        return Ok(record);
+    }
+    let snapshot = serde_json::from_str::<Value>(record.response_json.as_str())
+        .map_err(|err| format!("parse background response failed: {err}"))?;
+    let snapshot = failed_snapshot(
+        &snapshot,
+        "background response was interrupted before completion",
+    );
+    let response_json = serde_json::to_string(&snapshot)
+        .map_err(|err| format!("serialize background response failed: {err}"))?;
+    storage
+        .update_gateway_background_response(
+            record.response_id.as_str(),
+            STATUS_FAILED,
+            response_json.as_str(),
+            now_ts(),
+        )
+        .map_err(|err| format!("update background response failed: {err}"))?;
+    storage
+        .find_gateway_background_response(record.key_id.as_str(), record.response_id.as_str())
+        .map_err(|err| format!("read background response failed: {err}"))?
+        .ok_or_else(|| "background response disappeared".to_string())
+}
+
+fn find_background_response(
+    storage: &Storage,
+    key_id: &str,
+    response_id: &str,
+) -> Result<Option<GatewayBackgroundResponse>, String> {
+    storage
+        .find_gateway_background_response(key_id, response_id)
+        .map_err(|err| format!("read background response failed: {err}"))?
+        .map(|record| reconcile_background_response(storage, record))
+        .transpose()
+}
+
+/// 函数 `cancel_background_response`
+///
+/// 进行中的任务立即标记为 `cancelled` 并通知后台线程断开；已结束的任务原样返回。
+fn cancel_background_response(
+    storage: &Storage,
+    key_id: &str,
+    response_id: &str,
+) -> Result<Option<GatewayBackgroundResponse>, String> {
+    let Some(record) = find_background_response(storage, key_id, response_id)? else {
+        return Ok(None);
+    };
+    if !is_active_status(record.status.as_str()) {
+        return Ok(Some(record));
+    }
+    if let Some(cancelled) = background_cancel_flag(response_id) {
+        cancelled.store(true, Ordering::Relaxed);
+    }
+    let snapshot = serde_json::from_str::<Value>(record.response_json.as_str())
+        .map_err(|err| format!("parse background response failed: {err}"))?;
+    let response_json = serde_json::to_string(&with_status(&snapshot, STATUS_CANCELLED))
+        .map_err(|err| format!("serialize background response failed: {err}"))?;
+    storage
+        .update_gateway_background_response(
+            response_id,
+            STATUS_CANCELLED,
+            response_json.as_str(),
+            now_ts(),
+        )
+        .map_err(|err| format!("update background response failed: {err}"))?;
+    find_background_response(storage, key_id, response_id)
+}
+
+/// 按游标回放后台事件；任务仍在运行时轮询存储等待新事件，结束后关闭流。
+struct BackgroundEventReader {
+    key_id: String,
+    response_id: String,
+    cursor: i64,
+    pending: Cursor<Vec<u8>>,
+    finished: bool,
+}
+
+impl BackgroundEventReader {
+    fn new(key_id: &str, response_id: &str, starting_after: Option<i64>) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            response_id: response_id.to_string(),
+            cursor: starting_after.unwrap_or(-1),
+            pending: Cursor::new(Vec::new()),
+            finished: false,
+        }
+    }
+
+    fn load_events(&mut self, storage: &Storage) -> std::io::Result<bool> {
+        let events = storage
+            .list_gateway_background_response_events(
+                self.response_id.as_str(),
+                self.cursor,
+                EVENT_PAGE_SIZE,
+            )
+            .map_err(std::io::Error::other)?;
+        if events.is_empty() {
+            return Ok(false);
+        }
+        let mut buffer = Vec::new();
+        for event in events {
+            self.cursor = event.sequence_number;
+            buffer.extend(sse_frame(event.event_json.as_str()));
+        }
+        self.pending = Cursor::new(buffer);
+        Ok(true)
+    }
+
+    fn fill(&mut self) -> std::io::Result<()> {
+        let Some(storage) = crate::storage_helpers::open_storage() else {
+            self.finished = true;
+            return Ok(());
+        };
+        if self.load_events(&storage)? {
+            return Ok(());
+        }
+        let active =
+            find_background_response(&storage, self.key_id.as_str(), self.response_id.as_str())
+                .map_err(std::io::Error::other)?
+                .is_some_and(|record| is_active_status(record.status.as_str()));
+        if active {
+            thread::sleep(EVENT_POLL_INTERVAL);
+            return Ok(());
+        }
+        // 中文注释：状态落定前可能刚追加了终态事件，结束前再补读一次。
+        self.load_events(&storage)?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Read for BackgroundEventReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.pending.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            if self.finished {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+    }
+}
+
+fn respond_background_events(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    response_id: &str,
+    starting_after: Option<i64>,
+) -> Result<(), String> {
+    super::local_response::record_local_result(
+        context,
+        200,
+        super::request_log::RequestLogUsage::default(),
+        None,
+    );
+    let mut headers = Vec::new();
+    for (name, value) in [
+        ("Content-Type", "text/event-stream"),
+        ("Cache-Control", "no-cache"),
+    ] {
+        if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            headers.push(header);
+        }
+    }
+    let response = Response::new(
+        StatusCode(200),
+        headers,
+        BackgroundEventReader::new(context.key_id, response_id, starting_after),
+        None,
+        None,
+    );
+    let _ = request.respond(response);
+    Ok(())
+}
+
+/// 回环重放时透传的客户端请求头（剔除逐跳与长度相关头）。
+pub(super) fn forward_headers(request: &Request) -> Vec<(String, String)> {
+    request
+        .headers()
+        .iter()
+        .filter(|header| {
+            !SKIPPED_FORWARD_HEADERS
+                .iter()
+                .any(|skipped| header.field.equiv(skipped))
+        })
+        .map(|header| {
+            (
+                header.field.as_str().as_str().to_string(),
+                header.value.as_str().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn start_background_response(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    payload: Value,
+    client_body: &[u8],
+) -> Result<(), String> {
+    let Some(backend_addr) = crate::http::backend_runtime::current_backend_addr() else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            503,
+            "background responses are unavailable: gateway backend is not running".to_string(),
+        );
+    };
+    let response_id = new_background_response_id();
+    let Some(cancelled) = try_register_background_response(
+        response_id.as_str(),
+        super::background_response_max_concurrency(),
+    ) else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            429,
+            "too many background responses are running; retry later".to_string(),
+        );
+    };
+    let created_at = now_ts();
+    let snapshot = queued_response_snapshot(response_id.as_str(), created_at, &payload);
+    let prepared = build_background_upstream_body(&payload).and_then(|body| {
+        let response_json = serde_json::to_string(&snapshot)
+            .map_err(|err| format!("serialize background response failed: {err}"))?;
+        context
+            .storage
+            .insert_gateway_background_response(&GatewayBackgroundResponse {
+                response_id: response_id.clone(),
+                key_id: context.key_id.to_string(),
+                status: STATUS_QUEUED.to_string(),
+                response_json,
+                last_sequence_number: -1,
+                created_at,
+                updated_at: created_at,
+            })
+            .map_err(|err| format!("save background response failed: {err}"))?;
+        Ok(body)
+    });
+    let body = match prepared {
+        Ok(body) => body,
+        Err(err) => {
+            unregister_background_response(response_id.as_str());
+            return super::local_response::respond_local_terminal_error(request, context, 500, err);
+        }
+    };
+    let retention_secs = super::background_response_retention_secs();
+    let updated_before =
+        created_at.saturating_sub(i64::try_from(retention_secs).unwrap_or(i64::MAX));
+    if let Err(err) = context
+        .storage
+        .prune_gateway_background_responses(updated_before)
+    {
+        log::warn!(
+            "event=gateway_background_response_prune_failed trace_id={} err={}",
+            context.trace_id,
+            err
+        );
+    }
+
+    let job = BackgroundResponseJob {
+        response_id: response_id.clone(),
+        backend_addr,
+        headers: forward_headers(&request),
+        body,
+        snapshot: snapshot.clone(),
+        store_turn: super::response_store::resolve_response_store_turn(
+            context.key_id,
+            RESPONSES_PATH,
+            RESPONSES_PATH,
+            "POST",
+            client_body,
+        ),
+        cancelled,
+    };
+    if let Err(err) = thread::Builder::new()
+        .name("background-response".to_string())
+        .spawn(move || run_background_response(job))
+    {
+        unregister_background_response(response_id.as_str());
+        let _ = context.storage.update_gateway_background_response(
+            response_id.as_str(),
+            STATUS_FAILED,
+            failed_snapshot(&snapshot, "spawn background worker failed")
+                .to_string()
+                .as_str(),
+            now_ts(),
+        );
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            500,
+            format!("spawn background worker failed: {err}"),
+        );
+    }
+    log::info!(
+        "event=gateway_background_response_started trace_id={} response_id={}",
+        context.trace_id,
+        response_id
+    );
+
+    if payload.get("stream").and_then(Value::as_bool) == Some(true) {
+        return respond_background_events(request, context, response_id.as_str(), None);
+    }
+    super::local_response::respond_local_json(
+        request,
+        context,
+        snapshot.to_string(),
+        super::request_log::RequestLogUsage::default(),
+    )
+}
+
+fn respond_background_record(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    record: GatewayBackgroundResponse,
+) -> Result<(), String> {
+    super::local_response::respond_local_json(
+        request,
+        context,
+        record.response_json,
+        super::request_log::RequestLogUsage::default(),
+    )
+}
+
+/// 函数 `maybe_respond_background_response`
+///
+/// 接管 `background: true` 的 `POST /v1/responses`，以及后台 id 的查询、流式回放、取消与删除；
+/// 其余请求原样返回给后续链路。
+pub(super) fn maybe_respond_background_response(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    background_body: Option<&[u8]>,
+) -> Result<Option<Request>, String> {
+    let Some(route) = resolve_background_route(context.request_method, context.path) else {
+        return Ok(Some(request));
+    };
+    match route {
+        BackgroundRoute::Create => {
+            if super::background_response_max_concurrency() == 0 {
+                return Ok(Some(request));
+            }
+            let Some(client_body) = background_body else {
+                return Ok(Some(request));
+            };
+            let Some(payload) = serde_json::from_slice::<Value>(client_body)
+                .ok()
+                .filter(|payload| payload.get("background").and_then(Value::as_bool) == Some(true))
+            else {
+                return Ok(Some(request));
+            };
+            start_background_response(request, context, payload, client_body)?;
+            Ok(None)
+        }
+        BackgroundRoute::Retrieve { response_id, query } => {
+            let Some(record) =
+                find_background_response(context.storage, context.key_id, response_id)?
+            else {
+                return Ok(Some(request));
+            };
+            let options = parse_retrieve_options(query);
+            if options.stream {
+                respond_background_events(request, context, response_id, options.starting_after)?;
+            } else {
+                respond_background_record(request, context, record)?;
+            }
+            Ok(None)
+        }
+        BackgroundRoute::Cancel { response_id } => {
+            let Some(record) =
+                cancel_background_response(context.storage, context.key_id, response_id)?
+            else {
+                return Ok(Some(request));
+            };
+            respond_background_record(request, context, record)?;
+            Ok(None)
+        }
+        BackgroundRoute::Delete { response_id } => {
+            if cancel_background_response(context.storage, context.key_id, response_id)?.is_none() {
+                return Ok(Some(request));
+            }
+            context
+                .storage
+                .delete_gateway_background_response(context.key_id, response_id)
+                .map_err(|err| format!("delete background response failed: {err}"))?;
+            let _ = context
+                .storage
+                .delete_gateway_response(context.key_id, response_id);
+            let body = json!({
+                "id": response_id,
+                "object": "response",
+                "deleted": true,
+            });
+            super::local_response::respond_local_json(
+                request,
+                context,
+                body.to_string(),
+                super::request_log::RequestLogUsage::default(),
+            )?;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/background_responses_tests.rs"]
+mod tests;
@@ -0,0 +1,1259 @@
+use codexmanager_core::storage::{
+    now_ts, Account, GatewayBatch, GatewayBatchItem, GatewayBatchItemCounts, GatewayFile, Storage,
+    Token,
+};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Request, Response};
+
+use super::incoming_headers::BATCH_ID_HEADER_NAME;
+use super::local_response::LocalResponseContext;
+
+const FILES_PATH: &str = "/v1/files";
+const BATCHES_PATH: &str = "/v1/batches";
+const FILE_ID_PREFIX: &str = "file-";
+const BATCH_ID_PREFIX: &str = "batch_";
+const BATCH_REQUEST_ID_PREFIX: &str = "batch_req_";
+const PURPOSE_BATCH: &str = "batch";
+const PURPOSE_BATCH_OUTPUT: &str = "batch_output";
+const SUPPORTED_BATCH_ENDPOINTS: &[&str] =
+    &["/v1/responses", "/v1/chat/completions", "/v1/embeddings"];
+const COMPLETION_WINDOW: &str = "24h";
+const COMPLETION_WINDOW_SECS: i64 = 24 * 60 * 60;
+const MAX_BATCH_REQUESTS: usize = 50_000;
+const MAX_REPORTED_LINE_ERRORS: usize = 100;
+const DEFAULT_LIST_LIMIT: usize = 20;
+const MAX_LIST_LIMIT: usize = 100;
+const ITEM_MAX_ATTEMPTS: u32 = 3;
+const ITEM_RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+const AUTH_HEADERS: &[&str] = &["authorization", "x-api-key", "x-goog-api-key"];
+
+const STATUS_VALIDATING: &str = "validating";
+const STATUS_IN_PROGRESS: &str = "in_progress";
+const STATUS_FINALIZING: &str = "finalizing";
+const STATUS_COMPLETED: &str = "completed";
+const STATUS_FAILED: &str = "failed";
+const STATUS_EXPIRED: &str = "expired";
+const STATUS_CANCELLING: &str = "cancelling";
+const STATUS_CANCELLED: &str = "cancelled";
+
+const ITEM_PENDING: &str = "pending";
+const ITEM_RUNNING: &str = "running";
+const ITEM_COMPLETED: &str = "completed";
+const ITEM_FAILED: &str = "failed";
+
+/// 本进程内执行中的批任务：取消标记与 loopback 回放令牌。
+struct RunningBatch {
+    cancelled: Arc<AtomicBool>,
+    /// 批任务 loopback 子请求携带的随机令牌；客户端无法伪造出登记过的值。
+    loopback_token: String,
+}
+
+static RUNNING_BATCHES: OnceLock<Mutex<HashMap<String, RunningBatch>>> = OnceLock::new();
+
+fn running_batches() -> &'static Mutex<HashMap<String, RunningBatch>> {
+    RUNNING_BATCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 登记批任务执行线程并返回取消标记与 loopback 令牌；已在执行时返回 `None`，避免重复拉起。
+fn try_register_batch(batch_id: &str) -> Option<(Arc<AtomicBool>, String)> {
+    let mut running = crate::lock_utils::lock_recover(running_batches(), "running_batches");
+    if running.contains_key(batch_id) {
+        return None;
+    }
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let loopback_token = format!("{:032x}", rand::random::<u128>());
+    running.insert(
+        batch_id.to_string(),
+        RunningBatch {
+            cancelled: cancelled.clone(),
+            loopback_token: loopback_token.clone(),
+        },
+    );
+    Some((cancelled, loopback_token))
+}
+
+fn unregister_batch(batch_id: &str) {
+    crate::lock_utils::lock_recover(running_batches(), "running_batches").remove(batch_id);
+}
+
+fn batch_cancel_flag(batch_id: &str) -> Option<Arc<AtomicBool>> {
+    crate::lock_utils::lock_recover(running_batches(), "running_batches")
+        .get(batch_id)
+        .map(|running| running.cancelled.clone())
+}
+
+/// 函数 `resolve_batch_loopback_token`
+///
+/// 把批任务 loopback 请求头里的令牌解析回批任务 ID；
+/// 只有执行中批任务登记过的令牌才会命中，客户端自带的头按未命中处理。
+pub(super) fn resolve_batch_loopback_token(token: &str) -> Option<String> {
+    crate::lock_utils::lock_recover(running_batches(), "running_batches")
+        .iter()
+        .find(|(_, running)| running.loopback_token == token)
+        .map(|(batch_id, _)| batch_id.clone())
+}
+
+/// 批任务是否有执行线程在本进程内运行。
+pub(crate) fn is_batch_running(batch_id: &str) -> bool {
+    batch_cancel_flag(batch_id).is_some()
+}
+
+fn is_terminal_batch_status(status: &str) -> bool {
+    matches!(
+        status,
+        STATUS_COMPLETED | STATUS_FAILED | STATUS_EXPIRED | STATUS_CANCELLED
+    )
+}
+
+fn new_prefixed_id(prefix: &str) -> String {
+    format!("{prefix}{:032x}", rand::random::<u128>())
+}
+
+enum BatchRoute<'a> {
+    UploadFile,
+    ListFiles { query: Option<&'a str> },
+    RetrieveFile { file_id: &'a str },
+    FileContent { file_id: &'a str },
+    DeleteFile { file_id: &'a str },
+    CreateBatch,
+    ListBatches { query: Option<&'a str> },
+    RetrieveBatch { batch_id: &'a str },
+    CancelBatch { batch_id: &'a str },
+}
+
+fn resolve_batch_route<'a>(method: &str, path: &'a str) -> Option<BatchRoute<'a>> {
+    let (path_only, query) = match path.split_once('?') {
+        Some((path_only, query)) => (path_only, Some(query)),
+        None => (path, None),
+    };
+    let method = method.to_ascii_uppercase();
+    if path_only == FILES_PATH {
+        return match method.as_str() {
+            "POST" => Some(BatchRoute::UploadFile),
+            "GET" => Some(BatchRoute::ListFiles { query }),
+            _ => None,
+        };
+    }
+    if path_only == BATCHES_PATH {
+        return match method.as_str() {
+            "POST" => Some(BatchRoute::CreateBatch),
+            "GET" => Some(BatchRoute::ListBatches { query }),
+            _ => None,
+        };
+    }
+    if let Some(rest) = path_only.strip_prefix("/v1/files/") {
+        let (file_id, action) = match rest.split_once('/') {
+            Some((file_id, action)) => (file_id, Some(action)),
+            None => (rest, None),
+        };
+        if !file_id.starts_with(FILE_ID_PREFIX) {
+            return None;
+        }
+        return match (method.as_str(), action) {
+            ("GET", None) => Some(BatchRoute::RetrieveFile { file_id }),
+            ("GET", Some("content")) => Some(BatchRoute::FileContent { file_id }),
+            ("DELETE", None) => Some(BatchRoute::DeleteFile { file_id }),
+            _ => None,
+        };
+    }
+    let rest = path_only.strip_prefix("/v1/batches/")?;
+    let (batch_id, action) = match rest.split_once('/') {
+        Some((batch_id, action)) => (batch_id, Some(action)),
+        None => (rest, None),
+    };
+    if !batch_id.starts_with(BATCH_ID_PREFIX) {
+        return None;
+    }
+    match (method.as_str(), action) {
+        ("GET", None) => Some(BatchRoute::RetrieveBatch { batch_id }),
+        ("POST", Some("cancel")) => Some(BatchRoute::CancelBatch { batch_id }),
+        _ => None,
+    }
+}
+
+fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+fn list_limit(query: Option<&str>) -> usize {
+    query_param(query, "limit")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .clamp(1, MAX_LIST_LIMIT)
+}
+
+fn file_object(file: &GatewayFile) -> Value {
+    json!({
+        "id": file.file_id,
+        "object": "file",
+        "bytes": file.bytes,
+        "created_at": file.created_at,
+        "filename": file.filename,
+        "purpose": file.purpose,
+        "status": "processed",
+    })
+}
+
+fn list_object(data: Vec<Value>, has_more: bool) -> Value {
+    let first_id = data.first().and_then(|item| item.get("id")).cloned();
+    let last_id = data.last().and_then(|item| item.get("id")).cloned();
+    json!({
+        "object": "list",
+        "data": data,
+        "first_id": first_id,
+        "last_id": last_id,
+        "has_more": has_more,
+    })
+}
+
+fn parse_optional_json(raw: Option<&str>) -> Value {
+    raw.and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn batch_object(batch: &GatewayBatch, counts: GatewayBatchItemCounts) -> Value {
+    json!({
+        "id": batch.batch_id,
+        "object": "batch",
+        "endpoint": batch.endpoint,
+        "errors": parse_optional_json(batch.errors_json.as_deref()),
+        "input_file_id": batch.input_file_id,
+        "completion_window": batch.completion_window,
+        "status": batch.status,
+        "output_file_id": batch.output_file_id,
+        "error_file_id": batch.error_file_id,
+        "created_at": batch.created_at,
+        "in_progress_at": batch.in_progress_at,
+        "expires_at": batch.expires_at,
+        "finalizing_at": batch.finalizing_at,
+        "completed_at": batch.completed_at,
+        "failed_at": batch.failed_at,
+        "expired_at": batch.expired_at,
+        "cancelling_at": batch.cancelling_at,
+        "cancelled_at": batch.cancelled_at,
+        "request_counts": {
+            "total": counts.total,
+            "completed": counts.completed,
+            "failed": counts.failed,
+        },
+        "metadata": parse_optional_json(batch.metadata_json.as_deref()),
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BatchLineError {
+    code: &'static str,
+    message: String,
+    line: usize,
+}
+
+fn batch_errors_json(errors: &[BatchLineError]) -> String {
+    let data = errors
+        .iter()
+        .take(MAX_REPORTED_LINE_ERRORS)
+        .map(|error| {
+            json!({
+                "code": error.code,
+                "message": error.message,
+                "param": null,
+                "line": error.line,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "object": "list", "data": data }).to_string()
+}
+
+/// 逐行校验批处理输入文件，返回 `(custom_id, body)`；任一行不合法时返回全部行错误。
+fn parse_batch_input(
+    content: &[u8],
+    endpoint: &str,
+) -> Result<Vec<(String, Value)>, Vec<BatchLineError>> {
+    let Ok(text) = std::str::from_utf8(content) else {
+        return Err(vec![BatchLineError {
+            code: "invalid_json_line",
+            message: "batch input file must be UTF-8 encoded JSONL".to_string(),
+            line: 1,
+        }]);
+    };
+    let mut requests = Vec::new();
+    let mut errors = Vec::new();
+    let mut custom_ids = HashSet::new();
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = index + 1;
+        let raw_line = raw_line.trim();
+        if raw_line.is_empty() {
+            continue;
+        }
+        let mut error = |code: &'static str, message: String| {
+            errors.push(BatchLineError {
+                code,
+                message,
+                line,
+            })
+        };
+        let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(raw_line) else {
+            error(
+                "invalid_json_line",
+                "line is not a valid JSON object".to_string(),
+            );
+            continue;
+        };
+        let custom_id = object
+            .get("custom_id")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|custom_id| !custom_id.is_empty())
+            .map(str::to_string);
+        let Some(custom_id) = custom_id else {
+            error(
+                "missing_required_parameter",
+                "missing custom_id".to_string(),
+            );
+            continue;
+        };
+        if !custom_ids.insert(custom_id.clone()) {
+            error(
+                "duplicate_custom_id",
+                format!("duplicate custom_id: {custom_id}"),
+            );
+            continue;
+        }
+        let method = object.get("method").and_then(Value::as_str).unwrap_or("");
+        if !method.eq_ignore_ascii_case("POST") {
+            error(
+                "invalid_method",
+                format!("unsupported method: {method} (expected POST)"),
+            );
+            continue;
+        }
+        let url = object.get("url").and_then(Value::as_str).unwrap_or("");
+        if url != endpoint {
+            error(
+                "mismatched_endpoint",
+                format!("url {url} does not match batch endpoint {endpoint}"),
+            );
+            continue;
+        }
+        match object.remove("body") {
+            Some(body @ Value::Object(_)) => requests.push((custom_id, body)),
+            _ => error(
+                "missing_required_parameter",
+                "body must be a JSON object".to_string(),
+            ),
+        }
+    }
+    if requests.len() > MAX_BATCH_REQUESTS {
+        errors.push(BatchLineError {
+            code: "too_many_requests",
+            message: format!("batch input exceeds {MAX_BATCH_REQUESTS} requests"),
+            line: MAX_BATCH_REQUESTS + 1,
+        });
+    }
+    if requests.is_empty() && errors.is_empty() {
+        errors.push(BatchLineError {
+            code: "empty_file",
+            message: "batch input file has no requests".to_string(),
+            line: 1,
+        });
+    }
+    if errors.is_empty() {
+        Ok(requests)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 批处理请求一律走非流式，网关拿到完整响应体后写入输出文件。
+fn build_batch_item_body(endpoint: &str, body_json: &str) -> Result<Vec<u8>, String> {
+    let mut body = serde_json::from_str::<Value>(body_json)
+        .map_err(|err| format!("parse batch request body failed: {err}"))?;
+    if endpoint != "/v1/embeddings" {
+        if let Some(object) = body.as_object_mut() {
+            object.insert("stream".to_string(), Value::Bool(false));
+            object.remove("stream_options");
+        }
+    }
+    serde_json::to_vec(&body).map_err(|err| format!("serialize batch request body failed: {err}"))
+}
+
+fn batch_output_line(
+    custom_id: &str,
+    status_code: u16,
+    request_id: Option<&str>,
+    body: &str,
+) -> Value {
+    let body = serde_json::from_str::<Value>(body).unwrap_or_else(|_| json!(body));
+    json!({
+        "id": new_prefixed_id(BATCH_REQUEST_ID_PREFIX),
+        "custom_id": custom_id,
+        "response": {
+            "status_code": status_code,
+            "request_id": request_id,
+            "body": body,
+        },
+        "error": null,
+    })
+}
+
+fn batch_error_line(custom_id: &str, code: &str, message: &str) -> Value {
+    json!({
+        "id": new_prefixed_id(BATCH_REQUEST_ID_PREFIX),
+        "custom_id": custom_id,
+        "response": null,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn should_retry_batch_status(status_code: u16) -> bool {
+    matches!(status_code, 429 | 502 | 503 | 504)
+}
+
+/// 函数 `prefer_batch_candidates`
+///
+/// 批处理回环请求的候选排序：没有在途请求的账号优先，其次是额度窗口更早重置的账号
+/// （剩余额度在重置前不用就会作废）；同档内保持轮转策略给出的原顺序。
+pub(super) fn prefer_batch_candidates(storage: &Storage, candidates: &mut [(Account, Token)]) {
+    if candidates.len() < 2 {
+        return;
+    }
+    let account_ids = candidates
+        .iter()
+        .map(|(account, _)| account.id.clone())
+        .collect::<Vec<_>>();
+    let next_reset_at = storage
+        .latest_usage_snapshots_for_accounts(&account_ids)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|snapshot| {
+            let reset_at = [snapshot.resets_at, snapshot.secondary_resets_at]
+                .into_iter()
+                .flatten()
+                .min()?;
+            Some((snapshot.account_id, reset_at))
+        })
+        .collect::<HashMap<_, _>>();
+    candidates.sort_by_cached_key(|(account, _)| {
+        (
+            super::account_inflight_count(account.id.as_str()) > 0,
+            next_reset_at
+                .get(account.id.as_str())
+                .copied()
+                .unwrap_or(i64::MAX),
+        )
+    });
+}
+
+struct BatchRun {
+    batch_id: String,
+    key_id: String,
+    endpoint: String,
+    expires_at: i64,
+    backend_addr: String,
+    auth_headers: Vec<(String, String)>,
+    loopback_token: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl BatchRun {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn is_expired(&self) -> bool {
+        now_ts() >= self.expires_at
+    }
+
+    fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.is_expired()
+    }
+}
+
+struct BatchItemOutcome {
+    status: &'static str,
+    status_code: Option<i64>,
+    line: Value,
+}
+
+fn execute_batch_item(run: &BatchRun, item: &GatewayBatchItem) -> BatchItemOutcome {
+    let body = match build_batch_item_body(run.endpoint.as_str(), item.body_json.as_str()) {
+        Ok(body) => body,
+        Err(err) => {
+            return BatchItemOutcome {
+                status: ITEM_FAILED,
+                status_code: None,
+                line: batch_error_line(item.custom_id.as_str(), "invalid_request", &err),
+            }
+        }
+    };
+    let client = match super::background_responses::loopback_client() {
+        Ok(client) => client,
+        Err(err) => {
+            return BatchItemOutcome {
+                status: ITEM_FAILED,
+                status_code: None,
+                line: batch_error_line(item.custom_id.as_str(), "batch_request_failed", &err),
+            }
+        }
+    };
+    let mut last_error = String::new();
+    for attempt in 1..=ITEM_MAX_ATTEMPTS {
+        let mut request = client
+            .post(format!("http://{}{}", run.backend_addr, item.url))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .header(BATCH_ID_HEADER_NAME, run.loopback_token.as_str())
+            .body(body.clone());
+        for (name, value) in &run.auth_headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        match request.send() {
+            Ok(response) => {
+                let status_code = response.status().as_u16();
+                let request_id = response
+                    .headers()
+                    .get(crate::error_codes::TRACE_ID_HEADER_NAME)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let text = response.text().unwrap_or_default();
+                if should_retry_batch_status(status_code)
+                    && attempt < ITEM_MAX_ATTEMPTS
+                    && !run.should_stop()
+                {
+                    thread::sleep(ITEM_RETRY_BASE_DELAY * 2u32.pow(attempt - 1));
+                    continue;
+                }
+                let status = if (200..300).contains(&status_code) {
+                    ITEM_COMPLETED
+                } else {
+                    ITEM_FAILED
+                };
+                return BatchItemOutcome {
+                    status,
+                    status_code: Some(i64::from(status_code)),
+                    line: batch_output_line(
+                        item.custom_id.as_str(),
+                        status_code,
+                        request_id.as_deref(),
+                        text.as_str(),
+                    ),
+                };
+            }
+            Err(err) => {
+                last_error = format!("batch request failed: {err}");
+                if attempt < ITEM_MAX_ATTEMPTS && !run.should_stop() {
+                    thread::sleep(ITEM_RETRY_BASE_DELAY * 2u32.pow(attempt - 1));
+                }
+            }
+        }
+    }
+    BatchItemOutcome {
+        status: ITEM_FAILED,
+        status_code: None,
+        line: batch_error_line(
+            item.custom_id.as_str(),
+            "batch_request_failed",
+            last_error.as_str(),
+        ),
+    }
+}
+
+fn run_batch_worker(run: &BatchRun, queue: &Mutex<VecDeque<GatewayBatchItem>>) {
+    let Some(storage) = crate::storage_helpers::open_storage() else {
+        return;
+    };
+    loop {
+        // 中文注释：停机排空时不再派发新行，未执行的行保持 pending，重启后自动恢复。
+        if run.should_stop() || crate::draining() {
+            return;
+        }
+        let Some(item) = crate::lock_utils::lock_recover(queue, "batch_queue").pop_front() else {
+            return;
+        };
+        let _ = storage.update_gateway_batch_item(
+            run.batch_id.as_str(),
+            item.line_index,
+            ITEM_RUNNING,
+            None,
+            None,
+            now_ts(),
+        );
+        let outcome = execute_batch_item(run, &item);
+        if let Err(err) = storage.update_gateway_batch_item(
+            run.batch_id.as_str(),
+            item.line_index,
+            outcome.status,
+            outcome.status_code,
+            Some(outcome.line.to_string().as_str()),
+            now_ts(),
+        ) {
+            log::warn!(
+                "event=gateway_batch_item_save_failed batch_id={} line={} err={}",
+                run.batch_id,
+                item.line_index,
+                err
+            );
+        }
+    }
+}
+
+fn write_batch_output_file(
+    storage: &Storage,
+    run: &BatchRun,
+    suffix: &str,
+    lines: &[String],
+) -> Result<Option<String>, String> {
+    if lines.is_empty() {
+        return Ok(None);
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    let file = GatewayFile {
+        file_id: new_prefixed_id(FILE_ID_PREFIX),
+        key_id: run.key_id.clone(),
+        purpose: PURPOSE_BATCH_OUTPUT.to_string(),
+        filename: format!("{}_{suffix}.jsonl", run.batch_id),
+        bytes: content.len() as i64,
+        created_at: now_ts(),
+    };
+    storage
+        .insert_gateway_file(&file, content.as_bytes())
+        .map_err(|err| format!("save batch {suffix} file failed: {err}"))?;
+    Ok(Some(file.file_id))
+}
+
+/// 汇总已结束的请求行，生成 OpenAI 格式的输出与错误 JSONL，并写入最终状态。
+fn finalize_batch(storage: &Storage, run: &BatchRun) -> Result<(), String> {
+    let current = storage
+        .find_gateway_batch(run.key_id.as_str(), run.batch_id.as_str())
+        .map_err(|err| format!("read batch failed: {err}"))?
+        .ok_or_else(|| "batch disappeared".to_string())?;
+    if is_terminal_batch_status(current.status.as_str()) {
+        return Ok(());
+    }
+    let cancelled = run.is_cancelled() || current.status == STATUS_CANCELLING;
+    let pending = storage
+        .list_gateway_batch_items(run.batch_id.as_str(), ITEM_PENDING)
+        .map_err(|err| format!("read batch items failed: {err}"))?;
+    let expired = !cancelled && !pending.is_empty() && run.is_expired();
+    if !cancelled && !expired {
+        storage
+            .set_gateway_batch_status(run.batch_id.as_str(), STATUS_FINALIZING, now_ts())
+            .map_err(|err| format!("update batch status failed: {err}"))?;
+    }
+    if expired {
+        for item in &pending {
+            let line = batch_error_line(
+                item.custom_id.as_str(),
+                "batch_expired",
+                "This request could not be executed before the completion window expired.",
+            );
+            storage
+                .update_gateway_batch_item(
+                    run.batch_id.as_str(),
+                    item.line_index,
+                    ITEM_FAILED,
+                    None,
+                    Some(line.to_string().as_str()),
+                    now_ts(),
+                )
+                .map_err(|err| format!("save batch item failed: {err}"))?;
+        }
+    }
+
+    let collect_lines = |status: &str| -> Result<Vec<String>, String> {
+        Ok(storage
+            .list_gateway_batch_items(run.batch_id.as_str(), status)
+            .map_err(|err| format!("read batch items failed: {err}"))?
+            .into_iter()
+            .filter_map(|item| item.result_json)
+            .collect())
+    };
+    let output_file_id = write_batch_output_file(
+        storage,
+        run,
+        "output",
+        collect_lines(ITEM_COMPLETED)?.as_slice(),
+    )?;
+    let error_file_id = write_batch_output_file(
+        storage,
+        run,
+        "error",
+        collect_lines(ITEM_FAILED)?.as_slice(),
+    )?;
+    storage
+        .set_gateway_batch_results(
+            run.batch_id.as_str(),
+            output_file_id.as_deref(),
+            error_file_id.as_deref(),
+            None,
+            now_ts(),
+        )
+        .map_err(|err| format!("save batch results failed: {err}"))?;
+    let status = if cancelled {
+        STATUS_CANCELLED
+    } else if expired {
+        STATUS_EXPIRED
+    } else {
+        STATUS_COMPLETED
+    };
+    storage
+        .set_gateway_batch_status(run.batch_id.as_str(), status, now_ts())
+        .map_err(|err| format!("update batch status failed: {err}"))?;
+    Ok(())
+}
+
+fn execute_batch(storage: &Storage, run: &BatchRun) -> Result<(), String> {
+    storage
+        .reset_running_gateway_batch_items(run.batch_id.as_str(), now_ts())
+        .map_err(|err| format!("reset batch items failed: {err}"))?;
+    let started = storage
+        .set_gateway_batch_status(run.batch_id.as_str(), STATUS_IN_PROGRESS, now_ts())
+        .map_err(|err| format!("update batch status failed: {err}"))?;
+    if started {
+        let pending = storage
+            .list_gateway_batch_items(run.batch_id.as_str(), ITEM_PENDING)
+            .map_err(|err| format!("read batch items failed: {err}"))?;
+        let workers = super::batch_max_concurrency().max(1).min(pending.len());
+        let queue = Mutex::new(VecDeque::from(pending));
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| run_batch_worker(run, &queue));
+            }
+        });
+    }
+    if crate::draining() && !run.should_stop() {
+        log::info!(
+            "event=gateway_batch_paused_for_drain batch_id={}",
+            run.batch_id
+        );
+        return Ok(());
+    }
+    finalize_batch(storage, run)
+}
+
+/// 函数 `run_batch`
+///
+/// 批任务线程入口：按并发预算经 loopback 后端执行每一行请求，结束后生成结果文件。
+fn run_batch(run: BatchRun) {
+    log::info!(
+        "event=gateway_batch_started batch_id={} key_id={}",
+        run.batch_id,
+        run.key_id
+    );
+    let result = crate::storage_helpers::open_storage()
+        .ok_or_else(|| "open storage failed".to_string())
+        .and_then(|storage| {
+            execute_batch(&storage, &run).inspect_err(|err| {
+                let errors = batch_errors_json(&[BatchLineError {
+                    code: "batch_failed",
+                    message: err.clone(),
+                    line: 0,
+                }]);
+                let _ = storage.set_gateway_batch_results(
+                    run.batch_id.as_str(),
+                    None,
+                    None,
+                    Some(errors.as_str()),
+                    now_ts(),
+                );
+                let _ = storage.set_gateway_batch_status(
+                    run.batch_id.as_str(),
+                    STATUS_FAILED,
+                    now_ts(),
+                );
+            })
+        });
+    if let Err(err) = result {
+        log::warn!(
+            "event=gateway_batch_failed batch_id={} err={}",
+            run.batch_id,
+            err
+        );
+    }
+    unregister_batch(run.batch_id.as_str());
+}
+
+fn start_batch_runner(
+    batch: &GatewayBatch,
+    auth_headers: Vec<(String, String)>,
+) -> Result<(), String> {
+    let backend_addr = crate::http::backend_runtime::current_backend_addr()
+        .ok_or_else(|| "gateway backend is not running".to_string())?;
+    let Some((cancelled, loopback_token)) = try_register_batch(batch.batch_id.as_str()) else {
+        return Ok(());
+    };
+    let run = BatchRun {
+        batch_id: batch.batch_id.clone(),
+        key_id: batch.key_id.clone(),
+        endpoint: batch.endpoint.clone(),
+        expires_at: batch.expires_at,
+        backend_addr,
+        auth_headers,
+        loopback_token,
+        cancelled,
+    };
+    thread::Builder::new()
+        .name("gateway-batch".to_string())
+        .spawn(move || run_batch(run))
+        .map(|_| ())
+        .map_err(|err| {
+            unregister_batch(batch.batch_id.as_str());
+            format!("spawn batch worker failed: {err}")
+        })
+}
+
+/// 恢复执行时没有原始请求头，改用已保存的平台 Key 明文。
+fn stored_auth_headers(storage: &Storage, key_id: &str) -> Result<Vec<(String, String)>, String> {
+    let secret = storage
+        .find_api_key_secret_by_id(key_id)
+        .map_err(|err| format!("read api key secret failed: {err}"))?
+        .ok_or_else(|| "platform key secret is unavailable; batch cannot resume".to_string())?;
+    Ok(vec![(
+        "Authorization".to_string(),
+        format!("Bearer {secret}"),
+    )])
+}
+
+fn resume_batch(storage: &Storage, batch: &GatewayBatch) {
+    if is_terminal_batch_status(batch.status.as_str()) || is_batch_running(batch.batch_id.as_str())
+    {
+        return;
+    }
+    let result = stored_auth_headers(storage, batch.key_id.as_str())
+        .and_then(|auth_headers| start_batch_runner(batch, auth_headers));
+    if let Err(err) = result {
+        log::warn!(
+            "event=gateway_batch_resume_failed batch_id={} err={}",
+            batch.batch_id,
+            err
+        );
+    }
+}
+
+/// 函数 `resume_unfinished_batches`
+///
+/// 服务启动后恢复上次未结束的批任务；中断时正在执行的请求行会重新排队。
+pub(crate) fn resume_unfinished_batches() {
+    if super::batch_max_concurrency() == 0 {
+        return;
+    }
+    let Some(storage) = crate::storage_helpers::open_storage() else {
+        return;
+    };
+    match storage.list_unfinished_gateway_batches() {
+        Ok(batches) => {
+            for batch in &batches {
+                resume_batch(&storage, batch);
+            }
+        }
+        Err(err) => log::warn!("event=gateway_batch_resume_list_failed err={err}"),
+    }
+}
+
+fn load_batch_object(storage: &Storage, batch: &GatewayBatch) -> Result<Value, String> {
+    let counts = storage
+        .gateway_batch_item_counts(batch.batch_id.as_str())
+        .map_err(|err| format!("read batch progress failed: {err}"))?;
+    Ok(batch_object(batch, counts))
+}
+
+fn find_batch(
+    storage: &Storage,
+    key_id: &str,
+    batch_id: &str,
+) -> Result<Option<GatewayBatch>, String> {
+    storage
+        .find_gateway_batch(key_id, batch_id)
+        .map_err(|err| format!("read batch failed: {err}"))
+}
+
+/// 函数 `cancel_batch`
+///
+/// 标记为 `cancelling` 并通知执行线程停止派发；没有执行线程时直接收尾为 `cancelled`。
+fn cancel_batch(
+    storage: &Storage,
+    key_id: &str,
+    batch_id: &str,
+) -> Result<Option<GatewayBatch>, String> {
+    let Some(batch) = find_batch(storage, key_id, batch_id)? else {
+        return Ok(None);
+    };
+    if is_terminal_batch_status(batch.status.as_str()) {
+        return Ok(Some(batch));
+    }
+    storage
+        .set_gateway_batch_status(batch_id, STATUS_CANCELLING, now_ts())
+        .map_err(|err| format!("update batch status failed: {err}"))?;
+    match batch_cancel_flag(batch_id) {
+        Some(cancelled) => cancelled.store(true, Ordering::Relaxed),
+        None => {
+            let run = BatchRun {
+                batch_id: batch.batch_id.clone(),
+                key_id: batch.key_id.clone(),
+                endpoint: batch.endpoint.clone(),
+                expires_at: batch.expires_at,
+                backend_addr: String::new(),
+                auth_headers: Vec::new(),
+                loopback_token: String::new(),
+                cancelled: Arc::new(AtomicBool::new(true)),
+            };
+            finalize_batch(storage, &run)?;
+        }
+    }
+    find_batch(storage, key_id, batch_id)
+}
+
+fn request_auth_headers(request: &Request) -> Vec<(String, String)> {
+    request
+        .headers()
+        .iter()
+        .filter(|header| AUTH_HEADERS.iter().any(|name| header.field.equiv(name)))
+        .map(|header| {
+            (
+                header.field.as_str().as_str().to_string(),
+                header.value.as_str().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn respond_json(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    body: Value,
+) -> Result<(), String> {
+    super::local_response::respond_local_json(
+        request,
+        context,
+        body.to_string(),
+        super::request_log::RequestLogUsage::default(),
+    )
+}
+
+fn respond_file_content(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    content: Vec<u8>,
+) -> Result<(), String> {
+    super::local_response::record_local_result(
+        context,
+        200,
+        super::request_log::RequestLogUsage::default(),
+        None,
+    );
+    let header = Header::from_bytes(
+        b"content-type".as_slice(),
+        b"application/octet-stream".as_slice(),
+    )
+    .map_err(|_| "build content-type header failed".to_string())?;
+    let response = super::error_response::with_trace_id_header(
+        Response::from_data(content).with_header(header),
+        Some(context.trace_id),
+    );
+    let _ = request.respond(response);
+    Ok(())
+}
+
+fn upload_batch_file(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    body: &[u8],
+) -> Result<Option<Request>, String> {
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Content-Type"))
+        .map(|header| header.value.as_str().to_string())
+        .unwrap_or_default();
+    let parts = super::local_validation::multipart_boundary(content_type.as_str())
+        .and_then(|boundary| {
+            super::local_validation::parse_multipart_form(body, boundary.as_str()).ok()
+        })
+        .unwrap_or_default();
+    let purpose = parts
+        .iter()
+        .find(|part| part.name == "purpose")
+        .and_then(|part| std::str::from_utf8(part.data.as_slice()).ok())
+        .map(str::trim);
+    // 中文注释：只接管批处理输入文件，其余用途（assistants、fine-tune 等）继续转发上游。
+    if purpose != Some(PURPOSE_BATCH) {
+        return Ok(Some(request));
+    }
+    let Some(file_part) = parts.iter().find(|part| part.name == "file") else {
+        super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            400,
+            "missing file part".to_string(),
+        )?;
+        return Ok(None);
+    };
+    let max_bytes = super::batch_file_max_bytes();
+    if max_bytes > 0 && file_part.data.len() > max_bytes {
+        super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            413,
+            format!("batch file exceeds {max_bytes} bytes"),
+        )?;
+        return Ok(None);
+    }
+    let file = GatewayFile {
+        file_id: new_prefixed_id(FILE_ID_PREFIX),
+        key_id: context.key_id.to_string(),
+        purpose: PURPOSE_BATCH.to_string(),
+        filename: file_part
+            .filename
+            .clone()
+            .filter(|filename| !filename.trim().is_empty())
+            .unwrap_or_else(|| "batch.jsonl".to_string()),
+        bytes: file_part.data.len() as i64,
+        created_at: now_ts(),
+    };
+    context
+        .storage
+        .insert_gateway_file(&file, file_part.data.as_slice())
+        .map_err(|err| format!("save batch file failed: {err}"))?;
+    respond_json(request, context, file_object(&file))?;
+    Ok(None)
+}
+
+fn create_batch(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    body: &[u8],
+) -> Result<(), String> {
+    let Ok(Value::Object(payload)) = serde_json::from_slice::<Value>(body) else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            400,
+            "batch request body must be a JSON object".to_string(),
+        );
+    };
+    let endpoint = payload
+        .get("endpoint")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !SUPPORTED_BATCH_ENDPOINTS.contains(&endpoint) {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            400,
+            format!(
+                "unsupported batch endpoint: {endpoint} (expected one of {})",
+                SUPPORTED_BATCH_ENDPOINTS.join(", ")
+            ),
+        );
+    }
+    let completion_window = payload
+        .get("completion_window")
+        .and_then(Value::as_str)
+        .unwrap_or(COMPLETION_WINDOW);
+    if completion_window != COMPLETION_WINDOW {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            400,
+            format!("unsupported completion_window: {completion_window} (expected 24h)"),
+        );
+    }
+    let input_file_id = payload
+        .get("input_file_id")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let input_file = context
+        .storage
+        .find_gateway_file(context.key_id, input_file_id)
+        .map_err(|err| format!("read batch file failed: {err}"))?
+        .filter(|file| file.purpose == PURPOSE_BATCH);
+    let content = match input_file {
+        Some(_) => context
+            .storage
+            .find_gateway_file_content(context.key_id, input_file_id)
+            .map_err(|err| format!("read batch file failed: {err}"))?,
+        None => None,
+    };
+    let Some(content) = content else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            404,
+            format!("batch input file not found: {input_file_id}"),
+        );
+    };
+
+    let created_at = now_ts();
+    let mut batch = GatewayBatch {
+        batch_id: new_prefixed_id(BATCH_ID_PREFIX),
+        key_id: context.key_id.to_string(),
+        endpoint: endpoint.to_string(),
+        input_file_id: input_file_id.to_string(),
+        completion_window: COMPLETION_WINDOW.to_string(),
+        status: STATUS_VALIDATING.to_string(),
+        metadata_json: payload
+            .get("metadata")
+            .filter(|metadata| !metadata.is_null())
+            .map(Value::to_string),
+        created_at,
+        expires_at: created_at + COMPLETION_WINDOW_SECS,
+        updated_at: created_at,
+        ..Default::default()
+    };
+    let items = match parse_batch_input(&content, endpoint) {
+        Ok(requests) => requests
+            .into_iter()
+            .enumerate()
+            .map(|(index, (custom_id, body))| GatewayBatchItem {
+                batch_id: batch.batch_id.clone(),
+                line_index: index as i64,
+                custom_id,
+                url: endpoint.to_string(),
+                body_json: body.to_string(),
+                status: ITEM_PENDING.to_string(),
+                status_code: None,
+                result_json: None,
+                updated_at: created_at,
+            })
+            .collect::<Vec<_>>(),
+        Err(errors) => {
+            batch.status = STATUS_FAILED.to_string();
+            batch.failed_at = Some(created_at);
+            batch.errors_json = Some(batch_errors_json(&errors));
+            Vec::new()
+        }
+    };
+    context
+        .storage
+        .insert_gateway_batch(&batch, &items)
+        .map_err(|err| format!("save batch failed: {err}"))?;
+    if batch.status == STATUS_VALIDATING {
+        if let Err(err) = start_batch_runner(&batch, request_auth_headers(&request)) {
+            log::warn!(
+                "event=gateway_batch_start_failed trace_id={} batch_id={} err={}",
+                context.trace_id,
+                batch.batch_id,
+                err
+            );
+        }
+    }
+    let body = load_batch_object(context.storage, &batch)?;
+    respond_json(request, context, body)
+}
+
+/// 函数 `maybe_respond_batch_request`
+///
+/// 在网关本地模拟 OpenAI Files（`purpose=batch`）与 Batch API；
+/// 未命中本地文件或批任务的请求原样返回给后续链路。
+pub(super) fn maybe_respond_batch_request(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    body: &[u8],
+) -> Result<Option<Request>, String> {
+    if super::batch_max_concurrency() == 0 {
+        return Ok(Some(request));
+    }
+    let Some(route) = resolve_batch_route(context.request_method, context.path) else {
+        return Ok(Some(request));
+    };
+    let storage = context.storage;
+    match route {
+        BatchRoute::UploadFile => return upload_batch_file(request, context, body),
+        BatchRoute::ListFiles { query } => {
+            let files = storage
+                .list_gateway_files(
+                    context.key_id,
+                    query_param(query, "purpose"),
+                    list_limit(query),
+                )
+                .map_err(|err| format!("list files failed: {err}"))?;
+            let data = files.iter().map(file_object).collect();
+            respond_json(request, context, list_object(data, false))?;
+        }
+        BatchRoute::RetrieveFile { file_id } => {
+            let Some(file) = storage
+                .find_gateway_file(context.key_id, file_id)
+                .map_err(|err| format!("read file failed: {err}"))?
+            else {
+                return Ok(Some(request));
+            };
+            respond_json(request, context, file_object(&file))?;
+        }
+        BatchRoute::FileContent { file_id } => {
+            let Some(content) = storage
+                .find_gateway_file_content(context.key_id, file_id)
+                .map_err(|err| format!("read file failed: {err}"))?
+            else {
+                return Ok(Some(request));
+            };
+            respond_file_content(request, context, content)?;
+        }
+        BatchRoute::DeleteFile { file_id } => {
+            if !storage
+                .delete_gateway_file(context.key_id, file_id)
+                .map_err(|err| format!("delete file failed: {err}"))?
+            {
+                return Ok(Some(request));
+            }
+            let body = json!({ "id": file_id, "object": "file", "deleted": true });
+            respond_json(request, context, body)?;
+        }
+        BatchRoute::CreateBatch => create_batch(request, context, body)?,
+        BatchRoute::ListBatches { query } => {
+            let limit = list_limit(query);
+            let mut batches = storage
+                .list_gateway_batches(context.key_id, query_param(query, "after"), limit + 1)
+                .map_err(|err| format!("list batches failed: {err}"))?;
+            let has_more = batches.len() > limit;
+            batches.truncate(limit);
+            let data = batches
+                .iter()
+                .map(|batch| load_batch_object(storage, batch))
+                .collect::<Result<Vec<_>, _>>()?;
+            respond_json(request, context, list_object(data, has_more))?;
+        }
+        BatchRoute::RetrieveBatch { batch_id } => {
+            let Some(batch) = find_batch(storage, context.key_id, batch_id)? else {
+                return super::local_response::respond_local_terminal_error(
+                    request,
+                    context,
+                    404,
+                    format!("batch not found: {batch_id}"),
+                )
+                .map(|_| None);
+            };
+            resume_batch(storage, &batch);
+            let body = load_batch_object(storage, &batch)?;
+            respond_json(request, context, body)?;
+        }
+        BatchRoute::CancelBatch { batch_id } => {
+            let Some(batch) = cancel_batch(storage, context.key_id, batch_id)? else {
+                return super::local_response::respond_local_terminal_error(
+                    request,
+                    context,
+                    404,
+                    format!("batch not found: {batch_id}"),
+                )
+                .map(|_| None);
+            };
+            let body = load_batch_object(storage, &batch)?;
+            respond_json(request, context, body)?;
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+#[path = "tests/batches_tests.rs"]
+mod tests;
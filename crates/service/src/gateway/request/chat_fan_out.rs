@@ -0,0 +1,894 @@
+use bytes::Bytes;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Request, Response, StatusCode};
+
+use super::incoming_headers::FAN_OUT_CHILD_HEADER_NAME;
+use super::local_response::LocalResponseContext;
+use super::request_log::RequestLogUsage;
+
+const CHAT_COMPLETIONS_PATH: &str = "/v1/chat/completions";
+const CHILD_REPORT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ERROR_BODY_CHARS: usize = 2048;
+
+/// 子请求写日志时交给父请求汇总的信息。
+#[derive(Debug, Clone, Default)]
+struct FanOutChildReport {
+    account_id: Option<String>,
+    upstream_url: Option<String>,
+    attempted_account_ids: Vec<String>,
+    usage: RequestLogUsage,
+}
+
+#[derive(Default)]
+struct FanOutRegistry {
+    /// 子请求令牌 -> 捕获到的日志；`None` 表示子请求仍在途。
+    reports: HashMap<String, Option<FanOutChildReport>>,
+    /// 子请求 trace_id -> 令牌。
+    traces: HashMap<String, String>,
+}
+
+static FAN_OUT_REGISTRY: OnceLock<(Mutex<FanOutRegistry>, Condvar)> = OnceLock::new();
+
+fn fan_out_registry() -> &'static (Mutex<FanOutRegistry>, Condvar) {
+    FAN_OUT_REGISTRY.get_or_init(|| (Mutex::new(FanOutRegistry::default()), Condvar::new()))
+}
+
+/// 在途子请求数未超过上限时登记 `count` 个令牌；超限返回 `None`。
+fn register_fan_out_children(count: usize, limit: usize) -> Option<Vec<String>> {
+    let (registry, _) = fan_out_registry();
+    let mut registry = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+    if registry.reports.len().saturating_add(count) > limit {
+        return None;
+    }
+    let tokens = (0..count)
+        .map(|_| format!("{:032x}", rand::random::<u128>()))
+        .collect::<Vec<_>>();
+    for token in &tokens {
+        registry.reports.insert(token.clone(), None);
+    }
+    Some(tokens)
+}
+
+/// 函数 `bind_fan_out_child`
+///
+/// 请求携带网关登记过的子请求令牌时，把本次 trace_id 绑定到该令牌；
+/// 客户端伪造的令牌不会命中登记表，按普通请求处理。
+pub(super) fn bind_fan_out_child(request: &Request, trace_id: &str) {
+    let Some(token) = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(FAN_OUT_CHILD_HEADER_NAME))
+        .map(|header| header.value.as_str().trim().to_string())
+    else {
+        return;
+    };
+    let (registry, _) = fan_out_registry();
+    let mut registry = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+    let pending = matches!(registry.reports.get(token.as_str()), Some(None));
+    if pending && !registry.traces.values().any(|bound| *bound == token) {
+        registry.traces.insert(trace_id.to_string(), token);
+    }
+}
+
+/// 函数 `capture_fan_out_child_log`
+///
+/// 子请求的日志交给父请求合并成一条，避免用量被重复计入；返回 `true` 表示已接管。
+pub(super) fn capture_fan_out_child_log(
+    trace_id: Option<&str>,
+    account_id: Option<&str>,
+    upstream_url: Option<&str>,
+    attempted_account_ids: Option<&[String]>,
+    usage: RequestLogUsage,
+) -> bool {
+    let (Some(trace_id), Some((registry, signal))) = (trace_id, FAN_OUT_REGISTRY.get()) else {
+        return false;
+    };
+    let mut registry = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+    let Some(token) = registry.traces.remove(trace_id) else {
+        return false;
+    };
+    registry.reports.insert(
+        token,
+        Some(FanOutChildReport {
+            account_id: account_id.map(str::to_string),
+            upstream_url: upstream_url.map(str::to_string),
+            attempted_account_ids: attempted_account_ids.unwrap_or_default().to_vec(),
+            usage,
+        }),
+    );
+    signal.notify_all();
+    true
+}
+
+/// 等待已到达后端的子请求写完日志（带超时），随后注销全部令牌。
+fn collect_child_reports(tokens: &[String], awaited: &[bool]) -> Vec<FanOutChildReport> {
+    let (registry, signal) = fan_out_registry();
+    let deadline = Instant::now() + CHILD_REPORT_TIMEOUT;
+    let mut guard = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+    loop {
+        let pending = tokens
+            .iter()
+            .zip(awaited)
+            .any(|(token, awaited)| *awaited && matches!(guard.reports.get(token), Some(None)));
+        let now = Instant::now();
+        if !pending || now >= deadline {
+            break;
+        }
+        guard = match signal.wait_timeout(guard, deadline - now) {
+            Ok((guard, _)) => guard,
+            Err(poisoned) => poisoned.into_inner().0,
+        };
+    }
+    let reports = tokens
+        .iter()
+        .filter_map(|token| guard.reports.remove(token).flatten())
+        .collect();
+    guard.traces.retain(|_, token| !tokens.contains(token));
+    reports
+}
+
+/// 读取 `n`；仅 JSON 对象且 `n > 1` 时返回请求体与份数。
+fn requested_choice_count(body: &[u8]) -> Option<(Value, usize)> {
+    let payload = serde_json::from_slice::<Value>(body).ok()?;
+    let n = payload.as_object()?.get("n")?.as_u64()?;
+    let n = usize::try_from(n).ok().filter(|n| *n > 1)?;
+    Some((payload, n))
+}
+
+/// 去掉 `n`，每个子请求只生成一个选项。
+fn build_child_body(payload: &Value) -> Result<Vec<u8>, String> {
+    let mut body = payload.clone();
+    if let Some(object) = body.as_object_mut() {
+        object.remove("n");
+    }
+    serde_json::to_vec(&body).map_err(|err| format!("serialize fan-out request body failed: {err}"))
+}
+
+/// 第 `child` 个子请求的第 `local` 个选项在合并结果中的下标。
+fn global_choice_index(child: usize, local: u64, child_count: usize) -> u64 {
+    local
+        .saturating_mul(child_count as u64)
+        .saturating_add(child as u64)
+}
+
+fn reindex_choices(choices: &mut [Value], child: usize, child_count: usize) {
+    for choice in choices {
+        let local = choice.get("index").and_then(Value::as_u64).unwrap_or(0);
+        if let Some(object) = choice.as_object_mut() {
+            object.insert(
+                "index".to_string(),
+                json!(global_choice_index(child, local, child_count)),
+            );
+        }
+    }
+}
+
+/// 逐字段累加 usage 里的数值（含嵌套的 details 对象）。
+fn sum_usage_values(total: &mut Value, usage: &Value) {
+    match (total, usage) {
+        (Value::Object(total), Value::Object(usage)) => {
+            for (key, value) in usage {
+                match total.get_mut(key) {
+                    Some(existing) => sum_usage_values(existing, value),
+                    None => {
+                        total.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (total @ Value::Number(_), Value::Number(value)) => {
+            let sum = match (total.as_i64(), value.as_i64()) {
+                (Some(left), Some(right)) => json!(left.saturating_add(right)),
+                _ => json!(total.as_f64().unwrap_or(0.0) + value.as_f64().unwrap_or(0.0)),
+            };
+            *total = sum;
+        }
+        (total @ Value::Null, value) => *total = value.clone(),
+        _ => {}
+    }
+}
+
+/// 函数 `merge_chat_completion_choices`
+///
+/// 以第一个子响应为骨架，按子请求顺序合并 `choices[]` 并重排下标，`usage` 求和。
+fn merge_chat_completion_choices(responses: Vec<Value>) -> Value {
+    let child_count = responses.len();
+    let mut merged = Value::Null;
+    let mut choices = Vec::new();
+    let mut usage: Option<Value> = None;
+    for (child, mut response) in responses.into_iter().enumerate() {
+        if let Some(mut items) = response
+            .get_mut("choices")
+            .and_then(Value::as_array_mut)
+            .map(std::mem::take)
+        {
+            reindex_choices(&mut items, child, child_count);
+            choices.extend(items);
+        }
+        if let Some(child_usage) = response.get("usage").filter(|value| value.is_object()) {
+            match usage.as_mut() {
+                Some(total) => sum_usage_values(total, child_usage),
+                None => usage = Some(child_usage.clone()),
+            }
+        }
+        if child == 0 {
+            merged = response;
+        }
+    }
+    choices.sort_by_key(|choice| choice.get("index").and_then(Value::as_u64).unwrap_or(0));
+    if let Some(object) = merged.as_object_mut() {
+        object.insert("choices".to_string(), Value::Array(choices));
+        if let Some(usage) = usage {
+            object.insert("usage".to_string(), usage);
+        }
+    }
+    merged
+}
+
+fn sse_data_frame(payload: &Value) -> Vec<u8> {
+    format!("data: {payload}\n\n").into_bytes()
+}
+
+/// 流式合并状态：统一 chunk id、改写选项下标，并把各子流的 usage 攒到结尾。
+struct FanOutStreamState {
+    child_count: usize,
+    include_usage: bool,
+    id: Option<Value>,
+    template: Option<Map<String, Value>>,
+    usage: Option<Value>,
+    error: Option<String>,
+}
+
+impl FanOutStreamState {
+    fn new(child_count: usize, include_usage: bool) -> Self {
+        Self {
+            child_count,
+            include_usage,
+            id: None,
+            template: None,
+            usage: None,
+            error: None,
+        }
+    }
+
+    /// 改写一条子流 chunk；只带 usage 的 chunk 暂存到结尾，返回 `None`。
+    fn rewrite_chunk(&mut self, child: usize, mut chunk: Value) -> Option<Vec<u8>> {
+        let object = chunk.as_object_mut()?;
+        if let Some(usage) = object.remove("usage").filter(|value| value.is_object()) {
+            match self.usage.as_mut() {
+                Some(total) => sum_usage_values(total, &usage),
+                None => self.usage = Some(usage),
+            }
+        }
+        let choices = object.get_mut("choices").and_then(Value::as_array_mut)?;
+        if choices.is_empty() {
+            return None;
+        }
+        reindex_choices(choices, child, self.child_count);
+        match self.id.as_ref() {
+            Some(id) => {
+                object.insert("id".to_string(), id.clone());
+            }
+            None => self.id = object.get("id").cloned(),
+        }
+        if self.template.is_none() {
+            let mut template = object.clone();
+            template.insert("choices".to_string(), json!([]));
+            self.template = Some(template);
+        }
+        Some(sse_data_frame(&chunk))
+    }
+
+    /// 子流中途失败时发出错误帧并结束整个流。
+    fn fail(&mut self, message: String) -> Vec<u8> {
+        let mut frames = sse_data_frame(&json!({
+            "error": { "message": message, "type": "server_error" }
+        }));
+        frames.extend_from_slice(b"data: [DONE]\n\n");
+        self.error = Some(message);
+        frames
+    }
+
+    /// 全部子流结束：按需补发汇总 usage，再发 `[DONE]`。
+    fn finish(&self) -> Vec<u8> {
+        let mut frames = Vec::new();
+        if let (true, Some(usage), Some(template)) = (
+            self.include_usage,
+            self.usage.as_ref(),
+            self.template.as_ref(),
+        ) {
+            let mut chunk = template.clone();
+            chunk.insert("usage".to_string(), usage.clone());
+            frames.extend(sse_data_frame(&Value::Object(chunk)));
+        }
+        frames.extend_from_slice(b"data: [DONE]\n\n");
+        frames
+    }
+}
+
+fn sum_optional(left: Option<i64>, right: Option<i64>) -> Option<i64> {
+    match (left, right) {
+        (None, None) => None,
+        (left, right) => Some(left.unwrap_or(0).saturating_add(right.unwrap_or(0))),
+    }
+}
+
+/// 合并子请求用量；首包耗时取最快的一路。
+fn sum_request_log_usage(reports: &[FanOutChildReport]) -> RequestLogUsage {
+    reports
+        .iter()
+        .fold(RequestLogUsage::default(), |total, report| {
+            RequestLogUsage {
+                input_tokens: sum_optional(total.input_tokens, report.usage.input_tokens),
+                cached_input_tokens: sum_optional(
+                    total.cached_input_tokens,
+                    report.usage.cached_input_tokens,
+                ),
+                cache_write_tokens: sum_optional(
+                    total.cache_write_tokens,
+                    report.usage.cache_write_tokens,
+                ),
+                output_tokens: sum_optional(total.output_tokens, report.usage.output_tokens),
+                total_tokens: sum_optional(total.total_tokens, report.usage.total_tokens),
+                reasoning_output_tokens: sum_optional(
+                    total.reasoning_output_tokens,
+                    report.usage.reasoning_output_tokens,
+                ),
+                first_response_ms: match (total.first_response_ms, report.usage.first_response_ms) {
+                    (Some(left), Some(right)) => Some(left.min(right)),
+                    (left, right) => left.or(right),
+                },
+                estimated_input_tokens: sum_optional(
+                    total.estimated_input_tokens,
+                    report.usage.estimated_input_tokens,
+                ),
+            }
+        })
+}
+
+enum ChildEvent {
+    Started,
+    Json(Value),
+    Chunk(Value),
+    Done,
+    Failed {
+        status: u16,
+        body: String,
+        responded: bool,
+    },
+}
+
+struct FanOutChildJob {
+    child: usize,
+    url: String,
+    headers: Vec<(String, String)>,
+    token: String,
+    body: Vec<u8>,
+    stream: bool,
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+fn run_fan_out_child(job: FanOutChildJob, events: Sender<(usize, ChildEvent)>) {
+    let send = |event| events.send((job.child, event)).is_ok();
+    let client = match super::background_responses::loopback_client() {
+        Ok(client) => client,
+        Err(body) => {
+            send(ChildEvent::Failed {
+                status: 500,
+                body,
+                responded: false,
+            });
+            return;
+        }
+    };
+    // 中文注释：子请求不带 `Accept: text/event-stream`，走普通队列，避免父请求占满流式 worker 后互相等待。
+    let mut request = client
+        .post(job.url.as_str())
+        .header(FAN_OUT_CHILD_HEADER_NAME, job.token.as_str())
+        .body(job.body);
+    for (name, value) in &job.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(err) => {
+            send(ChildEvent::Failed {
+                status: 502,
+                body: format!("fan-out request failed: {err}"),
+                responded: false,
+            });
+            return;
+        }
+    };
+    let status = response.status().as_u16();
+    if !response.status().is_success() {
+        let body = response.text().unwrap_or_default();
+        send(ChildEvent::Failed {
+            status,
+            body: truncate_chars(body.as_str(), MAX_ERROR_BODY_CHARS),
+            responded: true,
+        });
+        return;
+    }
+    if !job.stream {
+        let event = match response.json::<Value>() {
+            Ok(value) => ChildEvent::Json(value),
+            Err(err) => ChildEvent::Failed {
+                status: 502,
+                body: format!("parse fan-out response failed: {err}"),
+                responded: true,
+            },
+        };
+        send(event);
+        return;
+    }
+    if !send(ChildEvent::Started) {
+        return;
+    }
+    let mut reader = BufReader::new(response);
+    let mut line = String::new();
+    let mut data = String::new();
+    loop {
+        line.clear();
+        let read = match reader.read_line(&mut line) {
+            Ok(read) => read,
+            Err(err) => {
+                send(ChildEvent::Failed {
+                    status: 502,
+                    body: format!("read fan-out stream failed: {err}"),
+                    responded: true,
+                });
+                return;
+            }
+        };
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if let Some(chunk) = trimmed.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(chunk.trim_start());
+        } else if (trimmed.is_empty() || read == 0) && !data.is_empty() {
+            let payload = std::mem::take(&mut data);
+            if payload == "[DONE]" {
+                continue;
+            }
+            let event = match serde_json::from_str::<Value>(payload.as_str()) {
+                Ok(value) if value.get("error").is_some() => ChildEvent::Failed {
+                    status: 502,
+                    body: value["error"]["message"]
+                        .as_str()
+                        .unwrap_or("upstream stream reported an error")
+                        .to_string(),
+                    responded: true,
+                },
+                Ok(value) => ChildEvent::Chunk(value),
+                Err(_) => continue,
+            };
+            let failed = matches!(event, ChildEvent::Failed { .. });
+            if !send(event) || failed {
+                return;
+            }
+        }
+        if read == 0 {
+            send(ChildEvent::Done);
+            return;
+        }
+    }
+}
+
+/// 按子请求顺序回放事件的流式响应体；结束或失败后停止读取。
+struct FanOutStreamReader {
+    events: Receiver<(usize, ChildEvent)>,
+    backlog: VecDeque<(usize, ChildEvent)>,
+    state: Arc<Mutex<FanOutStreamState>>,
+    pending: Cursor<Vec<u8>>,
+    running: usize,
+    finished: bool,
+}
+
+impl FanOutStreamReader {
+    fn fill(&mut self) {
+        let next = self.backlog.pop_front().or_else(|| self.events.recv().ok());
+        let mut state = crate::lock_utils::lock_recover(&self.state, "chat_fan_out_stream");
+        let frames = match next {
+            Some((child, ChildEvent::Chunk(chunk))) => {
+                state.rewrite_chunk(child, chunk).unwrap_or_default()
+            }
+            Some((_, ChildEvent::Done)) => {
+                self.running = self.running.saturating_sub(1);
+                Vec::new()
+            }
+            Some((_, ChildEvent::Failed { body, .. })) => {
+                self.finished = true;
+                state.fail(body)
+            }
+            Some(_) => Vec::new(),
+            None => {
+                self.finished = true;
+                state.fail("fan-out stream ended unexpectedly".to_string())
+            }
+        };
+        let mut frames = frames;
+        if !self.finished && self.running == 0 {
+            self.finished = true;
+            frames.extend(state.finish());
+        }
+        self.pending = Cursor::new(frames);
+    }
+}
+
+impl Read for FanOutStreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.pending.read(buf)?;
+            if read > 0 || buf.is_empty() || self.finished {
+                return Ok(read);
+            }
+            self.fill();
+        }
+    }
+}
+
+struct FanOutOutcome {
+    status_code: u16,
+    error: Option<String>,
+}
+
+/// 父请求汇总一条请求日志：用量为各子请求之和，尝试过的账号为各子请求账号的并集。
+fn record_fan_out_result(
+    context: &LocalResponseContext<'_>,
+    tokens: &[String],
+    awaited: &[bool],
+    outcome: FanOutOutcome,
+    started_at: Instant,
+) {
+    let reports = collect_child_reports(tokens, awaited);
+    let usage = sum_request_log_usage(&reports);
+    let mut attempted_account_ids = Vec::new();
+    for account_id in reports.iter().flat_map(|report| {
+        report
+            .attempted_account_ids
+            .iter()
+            .chain(report.account_id.iter())
+    }) {
+        if !attempted_account_ids.contains(account_id) {
+            attempted_account_ids.push(account_id.clone());
+        }
+    }
+    let account_id = reports
+        .iter()
+        .find_map(|report| report.account_id.as_deref());
+    let upstream_url = reports
+        .iter()
+        .find_map(|report| report.upstream_url.as_deref());
+    let elapsed_ms = started_at.elapsed().as_millis();
+    let error = outcome.error.as_deref();
+    super::trace_log::log_request_final(
+        context.trace_id,
+        outcome.status_code,
+        account_id,
+        upstream_url,
+        error,
+        elapsed_ms,
+    );
+    super::record_gateway_request_outcome(
+        context.path,
+        outcome.status_code,
+        Some(context.protocol_type),
+    );
+    super::request_log::write_request_log_with_attempts(
+        context.storage,
+        super::request_log::RequestLogTraceContext {
+            trace_id: Some(context.trace_id),
+            original_path: Some(context.original_path),
+            adapted_path: Some(context.path),
+            response_adapter: Some(context.response_adapter),
+            route_source: Some("chat_fan_out"),
+            actual_source_kind: account_id.map(|_| "openai_account"),
+            actual_source_id: account_id,
+            ..Default::default()
+        },
+        Some(context.key_id),
+        account_id,
+        context.path,
+        context.request_method,
+        context.model_for_log,
+        context.reasoning_for_log,
+        upstream_url,
+        Some(outcome.status_code),
+        usage,
+        error,
+        Some(elapsed_ms),
+        Some(attempted_account_ids.as_slice()),
+    );
+}
+
+fn respond_child_error(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    status: u16,
+    body: String,
+) {
+    let content_type = if serde_json::from_str::<Value>(body.as_str()).is_ok() {
+        "application/json"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    let mut response = Response::from_string(body).with_status_code(status);
+    if let Ok(header) = Header::from_bytes(b"Content-Type".as_slice(), content_type.as_bytes()) {
+        response = response.with_header(header);
+    }
+    let response = super::error_response::with_trace_id_header(response, Some(context.trace_id));
+    let _ = request.respond(response);
+}
+
+fn respond_fan_out_json(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    events: Receiver<(usize, ChildEvent)>,
+    child_count: usize,
+    awaited: &mut [bool],
+) -> FanOutOutcome {
+    let mut responses = vec![Value::Null; child_count];
+    let mut received = 0;
+    let mut failure = None;
+    for (child, event) in events.iter().take(child_count) {
+        received += 1;
+        match event {
+            ChildEvent::Json(value) => {
+                awaited[child] = true;
+                responses[child] = value;
+            }
+            ChildEvent::Failed {
+                status,
+                body,
+                responded,
+            } => {
+                awaited[child] = responded;
+                failure.get_or_insert((status, body));
+            }
+            _ => {}
+        }
+    }
+    if received < child_count {
+        failure.get_or_insert((502, "fan-out worker exited unexpectedly".to_string()));
+    }
+    if let Some((status, body)) = failure {
+        respond_child_error(request, context, status, body.clone());
+        return FanOutOutcome {
+            status_code: status,
+            error: Some(body),
+        };
+    }
+    let merged = merge_chat_completion_choices(responses);
+    let mut response = Response::from_string(merged.to_string()).with_status_code(200);
+    if let Ok(header) = Header::from_bytes(b"Content-Type".as_slice(), b"application/json") {
+        response = response.with_header(header);
+    }
+    let response = super::error_response::with_trace_id_header(response, Some(context.trace_id));
+    let _ = request.respond(response);
+    FanOutOutcome {
+        status_code: 200,
+        error: None,
+    }
+}
+
+fn respond_fan_out_stream(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    events: Receiver<(usize, ChildEvent)>,
+    child_count: usize,
+    include_usage: bool,
+    awaited: &mut [bool],
+) -> FanOutOutcome {
+    // 中文注释：全部子流都拿到 2xx 响应后才开始回写，任一路提前失败时整体按该错误返回。
+    let mut backlog = VecDeque::new();
+    let mut started = 0;
+    while started < child_count {
+        let Ok((child, event)) = events.recv() else {
+            break;
+        };
+        match event {
+            ChildEvent::Started => {
+                awaited[child] = true;
+                started += 1;
+            }
+            ChildEvent::Failed {
+                status,
+                body,
+                responded,
+            } => {
+                awaited[child] = responded;
+                respond_child_error(request, context, status, body.clone());
+                return FanOutOutcome {
+                    status_code: status,
+                    error: Some(body),
+                };
+            }
+            event => backlog.push_back((child, event)),
+        }
+    }
+    let state = Arc::new(Mutex::new(FanOutStreamState::new(
+        child_count,
+        include_usage,
+    )));
+    let reader = FanOutStreamReader {
+        events,
+        backlog,
+        state: state.clone(),
+        pending: Cursor::new(Vec::new()),
+        running: child_count,
+        finished: false,
+    };
+    let mut headers = Vec::new();
+    for (name, value) in [
+        ("Content-Type", "text/event-stream"),
+        ("Cache-Control", "no-cache"),
+    ] {
+        if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            headers.push(header);
+        }
+    }
+    let response = super::error_response::with_trace_id_header(
+        Response::new(StatusCode(200), headers, reader, None, None),
+        Some(context.trace_id),
+    );
+    let _ = request.respond(response);
+    let error = crate::lock_utils::lock_recover(&state, "chat_fan_out_stream")
+        .error
+        .clone();
+    FanOutOutcome {
+        status_code: if error.is_some() { 502 } else { 200 },
+        error,
+    }
+}
+
+fn spawn_fan_out_children(
+    backend_addr: &str,
+    headers: &[(String, String)],
+    tokens: &[String],
+    body: &[u8],
+    stream: bool,
+    events: &Sender<(usize, ChildEvent)>,
+) {
+    for (child, token) in tokens.iter().enumerate() {
+        let job = FanOutChildJob {
+            child,
+            url: format!("http://{backend_addr}{CHAT_COMPLETIONS_PATH}"),
+            headers: headers.to_vec(),
+            token: token.clone(),
+            body: body.to_vec(),
+            stream,
+        };
+        let sender = events.clone();
+        if let Err(err) = thread::Builder::new()
+            .name("chat-fan-out".to_string())
+            .spawn(move || run_fan_out_child(job, sender))
+        {
+            let _ = events.send((
+                child,
+                ChildEvent::Failed {
+                    status: 500,
+                    body: format!("spawn fan-out worker failed: {err}"),
+                    responded: false,
+                },
+            ));
+        }
+    }
+}
+
+/// 函数 `maybe_respond_chat_fan_out`
+///
+/// 接管 `n > 1` 的 `POST /v1/chat/completions`：拆成 `n` 个单选项子请求经 loopback 后端并行执行
+/// （各自走正常路由，可落到不同账号），再把选项按下标合并成一个 JSON 或 SSE 响应。
+pub(super) fn maybe_respond_chat_fan_out(
+    request: Request,
+    context: &LocalResponseContext<'_>,
+    fan_out_body: Option<&Bytes>,
+) -> Result<Option<Request>, String> {
+    if !context.request_method.eq_ignore_ascii_case("POST")
+        || context.original_path != CHAT_COMPLETIONS_PATH
+    {
+        return Ok(Some(request));
+    }
+    let max_n = super::chat_fan_out_max_n();
+    if max_n == 0 {
+        return Ok(Some(request));
+    }
+    let Some((payload, child_count)) =
+        fan_out_body.and_then(|body| requested_choice_count(body.as_ref()))
+    else {
+        return Ok(Some(request));
+    };
+    if child_count > max_n {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            400,
+            format!("n must be at most {max_n}"),
+        )
+        .map(|_| None);
+    }
+    let Some(backend_addr) = crate::http::backend_runtime::current_backend_addr() else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            503,
+            "n > 1 is unavailable: gateway backend is not running".to_string(),
+        )
+        .map(|_| None);
+    };
+    let body = match build_child_body(&payload) {
+        Ok(body) => body,
+        Err(err) => {
+            return super::local_response::respond_local_terminal_error(request, context, 500, err)
+                .map(|_| None)
+        }
+    };
+    let Some(tokens) =
+        register_fan_out_children(child_count, super::chat_fan_out_max_concurrency())
+    else {
+        return super::local_response::respond_local_terminal_error(
+            request,
+            context,
+            429,
+            "too many n > 1 chat completions are running; retry later".to_string(),
+        )
+        .map(|_| None);
+    };
+    let stream = payload.get("stream").and_then(Value::as_bool) == Some(true);
+    let include_usage = payload
+        .pointer("/stream_options/include_usage")
+        .and_then(Value::as_bool)
+        == Some(true);
+    log::info!(
+        "event=gateway_chat_fan_out trace_id={} n={} stream={}",
+        context.trace_id,
+        child_count,
+        stream
+    );
+
+    let started_at = Instant::now();
+    let headers = super::background_responses::forward_headers(&request)
+        .into_iter()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case(FAN_OUT_CHILD_HEADER_NAME))
+        .collect::<Vec<_>>();
+    let (sender, events) = mpsc::channel();
+    spawn_fan_out_children(
+        backend_addr.as_str(),
+        &headers,
+        &tokens,
+        &body,
+        stream,
+        &sender,
+    );
+    drop(sender);
+    let mut awaited = vec![false; child_count];
+    let outcome = if stream {
+        respond_fan_out_stream(
+            request,
+            context,
+            events,
+            child_count,
+            include_usage,
+            &mut awaited,
+        )
+    } else {
+        respond_fan_out_json(request, context, events, child_count, &mut awaited)
+    };
+    record_fan_out_result(context, &tokens, &awaited, outcome, started_at);
+    Ok(None)
+}
+
+#[cfg(test)]
+#[path = "tests/chat_fan_out_tests.rs"]
+mod tests;
@@ -2,6 +2,8 @@ use tiny_http::Request;
 
 const X_OPENAI_INTERNAL_CODEX_RESPONSES_LITE_HEADER_NAME: &str =
     "x-openai-internal-codex-responses-lite";
+pub(crate) const BATCH_ID_HEADER_NAME: &str = "x-codexmanager-batch-id";
+pub(crate) const FAN_OUT_CHILD_HEADER_NAME: &str = "x-codexmanager-fan-out-child";
 
 #[derive(Clone, Default)]
 pub(crate) struct IncomingHeaderSnapshot {
@@ -25,6 +27,7 @@ pub(crate) struct IncomingHeaderSnapshot {
     responsesapi_include_timing_metrics: Option<String>,
     codex_inference_call_id: Option<String>,
     oai_attestation: Option<String>,
+    batch_id: Option<String>,
     passthrough_codex_headers: Vec<(String, String)>,
     conversation_id: Option<String>,
 }
@@ -178,6 +181,10 @@ impl IncomingHeaderSnapshot {
                 }
                 continue;
             }
+            if snapshot.batch_id.is_none() && name.eq_ignore_ascii_case(BATCH_ID_HEADER_NAME) {
+                snapshot.batch_id = resolve_batch_loopback_header(value);
+                continue;
+            }
             if should_capture_passthrough_codex_header(name) && !value.is_empty() {
                 remember_passthrough_header(&mut snapshot.passthrough_codex_headers, name, value);
                 continue;
@@ -345,6 +352,10 @@ impl IncomingHeaderSnapshot {
                 }
                 continue;
             }
+            if snapshot.batch_id.is_none() && header.field.equiv(BATCH_ID_HEADER_NAME) {
+                snapshot.batch_id = resolve_batch_loopback_header(header.value.as_str().trim());
+                continue;
+            }
             let header_name = header.field.to_string();
             if should_capture_passthrough_codex_header(header_name.as_str()) {
                 let value = header.value.as_str().trim();
@@ -648,6 +659,11 @@ impl IncomingHeaderSnapshot {
         self.oai_attestation.as_deref()
     }
 
+    /// 网关批处理回环请求经令牌解析出的 batch id；选路时据此偏向空闲账号。
+    pub(crate) fn batch_id(&self) -> Option<&str> {
+        self.batch_id.as_deref()
+    }
+
     /// 函数 `passthrough_codex_headers`
     ///
     /// 作者: gaohongshun
@@ -740,6 +756,14 @@ fn remember_passthrough_header(headers: &mut Vec<(String, String)>, name: &str,
     headers.push((name.to_string(), value.to_string()));
 }
 
+/// 批处理回环头只接受执行中批任务登记的令牌；客户端自带的值直接丢弃，既不偏向选路也不转发上游。
+fn resolve_batch_loopback_header(value: &str) -> Option<String> {
+    if value.is_empty() {
+        return None;
+    }
+    super::batches::resolve_batch_loopback_token(value)
+}
+
 fn session_id_from_turn_metadata(turn_metadata: &str) -> Option<String> {
     let payload = serde_json::from_str::<serde_json::Value>(turn_metadata).ok()?;
     let session_id = payload.get("session_id")?.as_str()?.trim();
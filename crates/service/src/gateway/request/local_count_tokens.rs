@@ -3,107 +3,63 @@ use crate::apikey_profile::{
 };
 use serde_json::{json, Value};
 
-/// 函数 `accumulate_text_len`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - value: 参数 value
-///
-/// # 返回
-/// 返回函数执行结果
-fn accumulate_text_len(value: &Value) -> usize {
-    match value {
-        Value::String(text) => text.chars().count(),
-        Value::Array(items) => items.iter().map(accumulate_text_len).sum(),
-        Value::Object(map) => {
-            if let Some(text) = map.get("text").and_then(Value::as_str) {
-                return text.chars().count();
-            }
-            if let Some(content) = map.get("content") {
-                return accumulate_text_len(content);
-            }
-            if let Some(input) = map.get("input") {
-                return accumulate_text_len(input);
-            }
-            map.values().map(accumulate_text_len).sum()
-        }
-        _ => 0,
-    }
-}
+use super::token_counter::{
+    count_request_input_tokens, resolve_tokenizer_encoding, tokenizer_encoding_for_model,
+    TokenizerEncoding,
+};
 
-/// 函数 `estimate_input_tokens_from_anthropic_messages`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - body: 参数 body
-///
-/// # 返回
-/// 返回函数执行结果
-fn estimate_input_tokens_from_anthropic_messages(body: &[u8]) -> Result<u64, String> {
+fn parse_anthropic_messages(body: &[u8]) -> Result<Value, String> {
     let payload: Value = serde_json::from_slice(body).map_err(|_| {
         crate::gateway::bilingual_error("Claude 请求 JSON 无效", "invalid claude request json")
     })?;
-    let Some(object) = payload.as_object() else {
+    if !payload.is_object() {
         return Err(crate::gateway::bilingual_error(
             "Claude 请求体必须是对象",
             "claude request body must be an object",
         ));
-    };
-
-    let mut char_count = 0usize;
-    if let Some(system) = object.get("system") {
-        char_count += accumulate_text_len(system);
-    }
-    if let Some(messages) = object.get("messages").and_then(Value::as_array) {
-        for message in messages {
-            if let Some(content) = message.get("content") {
-                char_count += accumulate_text_len(content);
-            }
-        }
     }
-
-    // 中文注释：count_tokens 仅用于本地预算估计，采用稳定的轻量估算（约 4 chars/token）。
-    let estimated = ((char_count as u64) / 4).max(1);
-    Ok(estimated)
+    Ok(payload)
 }
 
-fn estimate_input_tokens_from_gemini_request(body: &[u8]) -> Result<u64, String> {
+fn parse_gemini_request(body: &[u8]) -> Result<Value, String> {
     let payload: Value = serde_json::from_slice(body).map_err(|_| {
         crate::gateway::bilingual_error("Gemini 请求 JSON 无效", "invalid gemini request json")
     })?;
-    let Some(root) = payload.as_object() else {
+    if !payload.is_object() {
         return Err(crate::gateway::bilingual_error(
             "Gemini 请求体必须是对象",
             "gemini request body must be an object",
         ));
-    };
-    let object = root
-        .get("request")
-        .and_then(Value::as_object)
-        .unwrap_or(root);
-
-    let mut char_count = 0usize;
-    if let Some(system_instruction) = object
-        .get("systemInstruction")
-        .or_else(|| object.get("system_instruction"))
-    {
-        char_count += accumulate_text_len(system_instruction);
-    }
-    if let Some(contents) = object.get("contents").and_then(Value::as_array) {
-        for content in contents {
-            if let Some(parts) = content.get("parts") {
-                char_count += accumulate_text_len(parts);
-            }
-        }
     }
+    Ok(payload)
+}
+
+/// 函数 `count_input_tokens_from_anthropic_messages`
+///
+/// 作者: gaohongshun
+///
+/// 时间: 2026-04-02
+///
+/// # 参数
+/// - encoding: 参数 encoding
+/// - body: 参数 body
+///
+/// # 返回
+/// 返回函数执行结果
+fn count_input_tokens_from_anthropic_messages(
+    encoding: TokenizerEncoding,
+    body: &[u8],
+) -> Result<u64, String> {
+    let payload = parse_anthropic_messages(body)?;
+    Ok(count_request_input_tokens(encoding, &payload).max(1))
+}
 
-    Ok(((char_count as u64) / 4).max(1))
+fn count_input_tokens_from_gemini_request(
+    encoding: TokenizerEncoding,
+    body: &[u8],
+) -> Result<u64, String> {
+    let payload = parse_gemini_request(body)?;
+    Ok(count_request_input_tokens(encoding, &payload).max(1))
 }
 
 fn is_anthropic_count_tokens_request_path(path: &str) -> bool {
@@ -166,12 +122,13 @@ pub(super) fn maybe_respond_local_count_tokens(
         storage,
     };
 
-    let estimate_result = if is_gemini_count_tokens {
-        estimate_input_tokens_from_gemini_request(body)
+    let encoding = resolve_tokenizer_encoding(storage, model_for_log);
+    let count_result = if is_gemini_count_tokens {
+        count_input_tokens_from_gemini_request(encoding, body)
     } else {
-        estimate_input_tokens_from_anthropic_messages(body)
+        count_input_tokens_from_anthropic_messages(encoding, body)
     };
-    match estimate_result {
+    match count_result {
         Ok(input_tokens) => {
             let output = if is_gemini_count_tokens {
                 json!({ "totalTokens": input_tokens }).to_string()
@@ -202,6 +159,98 @@ pub(super) fn maybe_respond_local_count_tokens(
     }
 }
 
+/// 开启上下文窗口预检时，输入 token 超过模型目录 `context_window` 的请求直接在本地拒绝。
+#[allow(clippy::too_many_arguments)]
+pub(super) fn maybe_reject_context_window_overflow(
+    request: tiny_http::Request,
+    trace_id: &str,
+    key_id: &str,
+    protocol_type: &str,
+    original_path: &str,
+    path: &str,
+    request_method: &str,
+    body: &[u8],
+    model_for_log: Option<&str>,
+    reasoning_for_log: Option<&str>,
+    storage: &codexmanager_core::storage::Storage,
+) -> Result<Option<tiny_http::Request>, String> {
+    if !super::context_window_preflight_enabled()
+        || !request_method.eq_ignore_ascii_case("POST")
+        || is_anthropic_count_tokens_request_path(original_path)
+        || is_gemini_count_tokens_request_path(original_path)
+    {
+        return Ok(Some(request));
+    }
+    let Some(model) = model_for_log
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(Some(request));
+    };
+    let managed_model = match storage.get_managed_model_v2(model) {
+        Ok(Some(item)) => item,
+        Ok(None) => return Ok(Some(request)),
+        Err(err) => {
+            log::warn!(
+                "event=gateway_context_window_preflight_lookup_failed trace_id={} model={} err={}",
+                trace_id,
+                model,
+                err
+            );
+            return Ok(Some(request));
+        }
+    };
+    let Some(context_window) = managed_model
+        .context_window
+        .and_then(|value| u64::try_from(value).ok())
+        .filter(|value| *value > 0)
+    else {
+        return Ok(Some(request));
+    };
+    // 中文注释：multipart 等非 JSON 请求无法本地计数，直接放行交给上游判断。
+    let Ok(payload) = serde_json::from_slice::<Value>(body) else {
+        return Ok(Some(request));
+    };
+    let encoding = tokenizer_encoding_for_model(&managed_model);
+    let input_tokens = count_request_input_tokens(encoding, &payload);
+    if input_tokens <= context_window {
+        return Ok(Some(request));
+    }
+    log::warn!(
+        "event=gateway_context_window_preflight_rejected trace_id={} key_id={} model={} encoding={} input_tokens={} context_window={}",
+        trace_id,
+        key_id,
+        model,
+        encoding.as_str(),
+        input_tokens,
+        context_window
+    );
+    let context = super::local_response::LocalResponseContext {
+        trace_id,
+        key_id,
+        protocol_type,
+        original_path,
+        path,
+        response_adapter: super::ResponseAdapter::Passthrough,
+        request_method,
+        model_for_log,
+        reasoning_for_log,
+        storage,
+    };
+    super::local_response::respond_local_terminal_error(
+        request,
+        &context,
+        400,
+        crate::gateway::bilingual_error(
+            format!("请求输入约 {input_tokens} tokens，超过模型 {model} 的上下文窗口 {context_window}"),
+            format!(
+                "context_length_exceeded: input is about {input_tokens} tokens, exceeding the {context_window}-token context window of model {model}"
+            ),
+        ),
+    )?;
+    Ok(None)
+}
+
 #[cfg(test)]
 #[path = "tests/local_count_tokens_tests.rs"]
 mod tests;
@@ -1,4 +1,4 @@
-use tiny_http::{Request, Response};
+use tiny_http::{Header, Request, Response};
 
 /// 函数 `handle_gateway_request`
 ///
@@ -21,13 +21,23 @@ pub(crate) fn handle_gateway_request(mut request: Request) -> Result<(), String>
     }
 
     if request.url() == "/health" {
-        let response = Response::from_string("ok");
+        let response = if crate::draining() {
+            Response::from_string("draining").with_status_code(503)
+        } else {
+            Response::from_string("ok")
+        };
         let _ = request.respond(response);
         return Ok(());
     }
 
+    if crate::draining() {
+        let _ = request.respond(draining_response());
+        return Ok(());
+    }
+
     let _request_guard = super::begin_gateway_request();
     let trace_id = super::trace_log::next_trace_id();
+    super::bind_fan_out_child(&request, trace_id.as_str());
     let request_path_for_log = super::normalize_models_path(request.url());
     let request_method_for_log = request.method().as_str().to_string();
     let validated =
@@ -114,6 +124,55 @@ pub(crate) fn handle_gateway_request(mut request: Request) -> Result<(), String>
         None => return Ok(()),
     };
 
+    let responses_context = super::local_response::LocalResponseContext {
+        trace_id: validated.trace_id.as_str(),
+        key_id: validated.key_id.as_str(),
+        protocol_type: validated.protocol_type.as_str(),
+        original_path: validated.original_path.as_str(),
+        path: validated.path.as_str(),
+        response_adapter: validated.response_adapter,
+        request_method: validated.request_method.as_str(),
+        model_for_log: validated.model_for_log.as_deref(),
+        reasoning_for_log: validated.reasoning_for_log.as_deref(),
+        storage: &validated.storage,
+    };
+    let request = match super::maybe_respond_background_response(
+        request,
+        &responses_context,
+        validated.background_request_body.as_deref(),
+    )? {
+        Some(request) => request,
+        None => return Ok(()),
+    };
+    let request = match super::maybe_respond_batch_request(
+        request,
+        &responses_context,
+        validated.passthrough_body.as_ref(),
+    )? {
+        Some(request) => request,
+        None => return Ok(()),
+    };
+    let request = if validated.rotation_strategy == crate::apikey_profile::ROTATION_AGGREGATE_API {
+        request
+    } else {
+        match super::maybe_respond_chat_fan_out(
+            request,
+            &responses_context,
+            validated.fan_out_request_body.as_ref(),
+        )? {
+            Some(request) => request,
+            None => return Ok(()),
+        }
+    };
+    let request = if validated.rotation_strategy == crate::apikey_profile::ROTATION_AGGREGATE_API {
+        request
+    } else {
+        match super::maybe_respond_local_stored_response(request, &responses_context)? {
+            Some(request) => request,
+            None => return Ok(()),
+        }
+    };
+
     let trace_id_for_count_tokens = validated.trace_id.clone();
     let key_id_for_count_tokens = validated.key_id.clone();
     let protocol_type_for_count_tokens = validated.protocol_type.clone();
@@ -143,5 +202,36 @@ pub(crate) fn handle_gateway_request(mut request: Request) -> Result<(), String>
         }
     };
 
+    let request = match super::maybe_reject_context_window_overflow(
+        request,
+        validated.trace_id.as_str(),
+        validated.key_id.as_str(),
+        validated.protocol_type.as_str(),
+        validated.original_path.as_str(),
+        validated.path.as_str(),
+        validated.request_method.as_str(),
+        validated.passthrough_body.as_ref(),
+        validated.model_for_log.as_deref(),
+        validated.reasoning_for_log.as_deref(),
+        &validated.storage,
+    )? {
+        Some(request) => request,
+        None => return Ok(()),
+    };
+
     super::proxy_validated_request(request, validated, debug)
 }
+
+/// 停机排空期间拒绝新的网关请求，按剩余排空时间给出 `Retry-After`。
+fn draining_response() -> Response<std::io::Cursor<Vec<u8>>> {
+    let mut response = super::error_response::terminal_text_response(
+        503,
+        crate::lifecycle::drain::draining_error_message(),
+        None,
+    );
+    let retry_after = crate::lifecycle::drain::drain_retry_after_secs().to_string();
+    if let Ok(header) = Header::from_bytes(b"Retry-After".as_slice(), retry_after.as_bytes()) {
+        response.add_header(header);
+    }
+    response
+}
@@ -0,0 +1,423 @@
+use bytes::Bytes;
+use codexmanager_core::storage::{now_ts, ResponseCacheEntry, Storage};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use tiny_http::Request;
+
+use super::request_log::{RequestLogTraceContext, RequestLogUsage};
+use super::upstream::{
+    GatewayBodyCaptureCommit, GatewayByteStream, GatewayStreamResponse, GatewayUpstreamResponse,
+};
+
+const RESPONSE_CACHE_SOURCE_KIND: &str = "response_cache";
+const RESPONSE_CACHE_KEY_PREFIX: &str = "rc_";
+/// 不影响上游输出、但每次请求都可能不同的字段，参与缓存键前剔除。
+const VOLATILE_BODY_FIELDS: &[&str] = &[
+    "metadata",
+    "prompt_cache_key",
+    "safety_identifier",
+    "store",
+    "stream",
+    "stream_options",
+    "user",
+];
+
+/// 一次可缓存请求的缓存目标；只有平台 Key 开启缓存且请求体可规范化时才会生成。
+#[derive(Debug, Clone)]
+pub(super) struct ResponseCacheTarget {
+    cache_key: String,
+    key_id: String,
+    model: Option<String>,
+    request_path: String,
+    ttl_secs: i64,
+}
+
+impl ResponseCacheTarget {
+    pub(super) fn cache_key(&self) -> &str {
+        self.cache_key.as_str()
+    }
+}
+
+/// 缓存命中回放所需的请求上下文，字段与上游真实响应走同一条 bridge 链路。
+pub(super) struct ResponseCacheReplay<'a> {
+    pub(super) storage: &'a Storage,
+    pub(super) trace_id: &'a str,
+    pub(super) key_id: &'a str,
+    pub(super) original_path: &'a str,
+    pub(super) path: &'a str,
+    pub(super) request_method: &'a str,
+    pub(super) response_adapter: super::ResponseAdapter,
+    pub(super) gemini_stream_output_mode: Option<super::GeminiStreamOutputMode>,
+    pub(super) tool_name_restore_map: &'a super::ToolNameRestoreMap,
+    pub(super) client_is_stream: bool,
+    pub(super) gateway_mode_for_log: Option<&'a str>,
+    pub(super) client_model_for_log: Option<&'a str>,
+    pub(super) model_for_log: Option<&'a str>,
+    pub(super) model_source_for_log: Option<&'a str>,
+    pub(super) client_reasoning_for_log: Option<&'a str>,
+    pub(super) reasoning_for_log: Option<&'a str>,
+    pub(super) reasoning_source_for_log: Option<&'a str>,
+    pub(super) service_tier_for_log: Option<&'a str>,
+    pub(super) effective_service_tier_for_log: Option<&'a str>,
+    pub(super) service_tier_source_for_log: Option<&'a str>,
+    pub(super) started_at: Instant,
+}
+
+fn is_cacheable_payload(payload: &Map<String, Value>) -> bool {
+    // 中文注释：依赖服务端会话状态或异步执行的请求，同样的请求体并不代表同样的输出。
+    if payload
+        .get("previous_response_id")
+        .is_some_and(|value| !value.is_null())
+        || payload
+            .get("conversation")
+            .is_some_and(|value| !value.is_null())
+    {
+        return false;
+    }
+    !payload
+        .get("background")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys = map.keys().collect::<Vec<_>>();
+            keys.sort();
+            out.push('{');
+            for (idx, key) in keys.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// 函数 `compute_response_cache_key`
+///
+/// 按平台 Key、路由、生效模型与规范化请求体计算缓存键；请求不可缓存时返回 `None`。
+#[allow(clippy::too_many_arguments)]
+pub(super) fn compute_response_cache_key(
+    key_id: &str,
+    protocol_type: &str,
+    original_path: &str,
+    path: &str,
+    client_is_stream: bool,
+    model: Option<&str>,
+    reasoning: Option<&str>,
+    body: &[u8],
+) -> Option<String> {
+    let mut payload = serde_json::from_slice::<Value>(body).ok()?;
+    let object = payload.as_object_mut()?;
+    if !is_cacheable_payload(object) {
+        return None;
+    }
+    for field in VOLATILE_BODY_FIELDS {
+        object.remove(*field);
+    }
+    let mut canonical = String::with_capacity(body.len());
+    write_canonical_json(&payload, &mut canonical);
+
+    let mut hasher = Sha256::new();
+    for part in [
+        key_id,
+        protocol_type,
+        original_path,
+        path,
+        if client_is_stream {
+            "stream"
+        } else {
+            "blocking"
+        },
+        model.unwrap_or_default(),
+        reasoning.unwrap_or_default(),
+        canonical.as_str(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0_u8]);
+    }
+    let digest = hasher.finalize();
+    let mut cache_key = String::with_capacity(RESPONSE_CACHE_KEY_PREFIX.len() + digest.len() * 2);
+    cache_key.push_str(RESPONSE_CACHE_KEY_PREFIX);
+    for byte in digest {
+        cache_key.push_str(&format!("{byte:02x}"));
+    }
+    Some(cache_key)
+}
+
+/// 函数 `resolve_response_cache_target`
+///
+/// 读取平台 Key 的缓存策略；未开启、读取失败或请求不可缓存时都按未开启处理。
+#[allow(clippy::too_many_arguments)]
+pub(super) fn resolve_response_cache_target(
+    storage: &Storage,
+    trace_id: &str,
+    key_id: &str,
+    protocol_type: &str,
+    original_path: &str,
+    path: &str,
+    request_method: &str,
+    client_is_stream: bool,
+    model: Option<&str>,
+    reasoning: Option<&str>,
+    body: &[u8],
+) -> Option<ResponseCacheTarget> {
+    if !request_method.eq_ignore_ascii_case("POST") {
+        return None;
+    }
+    let policy = match storage.find_api_key_response_cache_policy(key_id) {
+        Ok(policy) => policy?,
+        Err(err) => {
+            log::warn!(
+                "event=gateway_response_cache_policy_read_failed trace_id={} key_id={} err={}",
+                trace_id,
+                key_id,
+                err
+            );
+            return None;
+        }
+    };
+    if !policy.enabled || policy.ttl_secs <= 0 {
+        return None;
+    }
+    let cache_key = compute_response_cache_key(
+        key_id,
+        protocol_type,
+        original_path,
+        path,
+        client_is_stream,
+        model,
+        reasoning,
+        body,
+    )?;
+    Some(ResponseCacheTarget {
+        cache_key,
+        key_id: key_id.to_string(),
+        model: model.map(str::to_string),
+        request_path: path.to_string(),
+        ttl_secs: policy.ttl_secs,
+    })
+}
+
+fn cached_response_headers(entry: &ResponseCacheEntry) -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(value) = entry
+        .content_type
+        .as_deref()
+        .and_then(|value| reqwest::header::HeaderValue::from_str(value).ok())
+    {
+        headers.insert(reqwest::header::CONTENT_TYPE, value);
+    }
+    headers
+}
+
+/// 函数 `maybe_respond_from_response_cache`
+///
+/// 命中时把缓存的上游响应交给 `respond_with_upstream` 回放，并记录不计费的请求日志；
+/// 未命中时原样交还 `request`。
+pub(super) fn maybe_respond_from_response_cache(
+    request: Request,
+    target: &ResponseCacheTarget,
+    replay: ResponseCacheReplay<'_>,
+) -> Result<Option<Request>, String> {
+    let now = now_ts();
+    let entry = match replay
+        .storage
+        .find_response_cache_entry(target.cache_key(), now)
+    {
+        Ok(Some(entry)) => entry,
+        Ok(None) => return Ok(Some(request)),
+        Err(err) => {
+            log::warn!(
+                "event=gateway_response_cache_read_failed trace_id={} err={}",
+                replay.trace_id,
+                err
+            );
+            return Ok(Some(request));
+        }
+    };
+    let Ok(status) = reqwest::StatusCode::from_u16(entry.status_code as u16) else {
+        return Ok(Some(request));
+    };
+    if let Err(err) = replay
+        .storage
+        .record_response_cache_hit(target.cache_key(), now)
+    {
+        log::warn!(
+            "event=gateway_response_cache_hit_record_failed trace_id={} err={}",
+            replay.trace_id,
+            err
+        );
+    }
+    let headers = cached_response_headers(&entry);
+    let upstream = GatewayUpstreamResponse::Stream(GatewayStreamResponse::new(
+        status,
+        headers,
+        GatewayByteStream::from_bytes(Bytes::from(entry.body)),
+    ));
+    let bridge = super::respond_with_upstream(
+        request,
+        upstream,
+        // 中文注释：缓存命中不占用任何账号，不登记账号在途计数。
+        None,
+        replay.response_adapter,
+        None,
+        replay.gemini_stream_output_mode,
+        replay.path,
+        Some(replay.tool_name_restore_map),
+        replay.client_is_stream,
+        false,
+        Some(replay.trace_id),
+        replay.model_for_log,
+        replay.started_at,
+    )?;
+    let bridge_ok = bridge.is_ok(replay.client_is_stream);
+    let final_error = (!bridge_ok).then(|| {
+        bridge
+            .error_message(replay.client_is_stream)
+            .unwrap_or_else(|| "cached response incomplete".to_string())
+    });
+    let status_code = bridge.delivered_status_code.unwrap_or(status.as_u16());
+    let status_code = if final_error.is_some() && status_code < 400 {
+        499
+    } else {
+        status_code
+    };
+    let cache_key_for_log = &target.cache_key()[..target.cache_key().len().min(19)];
+    log::info!(
+        "event=gateway_response_cache_hit trace_id={} key_id={} cache_key={} status={}",
+        replay.trace_id,
+        replay.key_id,
+        cache_key_for_log,
+        status_code
+    );
+    super::record_gateway_request_outcome(
+        replay.path,
+        status_code,
+        Some(RESPONSE_CACHE_SOURCE_KIND),
+    );
+    super::trace_log::log_request_final(
+        replay.trace_id,
+        status_code,
+        Some(replay.key_id),
+        None,
+        final_error.as_deref(),
+        replay.started_at.elapsed().as_millis(),
+    );
+    // 中文注释：upstream_url 为空时请求日志不会触发计费；来源标记为 response_cache 供日志筛选。
+    super::write_request_log(
+        replay.storage,
+        RequestLogTraceContext {
+            trace_id: Some(replay.trace_id),
+            original_path: Some(replay.original_path),
+            adapted_path: Some(replay.path),
+            gateway_mode: replay.gateway_mode_for_log,
+            route_source: Some(RESPONSE_CACHE_SOURCE_KIND),
+            client_model: replay.client_model_for_log,
+            model_source: replay.model_source_for_log,
+            client_reasoning_effort: replay.client_reasoning_for_log,
+            reasoning_source: replay.reasoning_source_for_log,
+            response_adapter: Some(replay.response_adapter),
+            service_tier: replay.service_tier_for_log,
+            effective_service_tier: replay.effective_service_tier_for_log,
+            service_tier_source: replay.service_tier_source_for_log,
+            actual_source_kind: Some(RESPONSE_CACHE_SOURCE_KIND),
+            actual_source_id: Some(cache_key_for_log),
+            ..Default::default()
+        },
+        Some(replay.key_id),
+        None,
+        replay.path,
+        replay.request_method,
+        replay.model_for_log,
+        replay.reasoning_for_log,
+        None,
+        Some(status_code),
+        RequestLogUsage {
+            first_response_ms: bridge.usage.first_response_ms,
+            ..RequestLogUsage::default()
+        },
+        final_error.as_deref(),
+        Some(replay.started_at.elapsed().as_millis()),
+    );
+    Ok(None)
+}
+
+/// 函数 `capture_upstream_response`
+///
+/// 对 2xx 上游响应分流一份副本，等 bridge 确认完整送达后再写入缓存。
+pub(super) fn capture_upstream_response(
+    target: &ResponseCacheTarget,
+    response: GatewayUpstreamResponse,
+) -> (GatewayUpstreamResponse, Option<GatewayBodyCaptureCommit>) {
+    let max_entry_bytes = super::response_cache_max_entry_bytes();
+    if !response.status().is_success() || max_entry_bytes == 0 {
+        return (response, None);
+    }
+    let status_code = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let target = target.clone();
+    let (response, commit) = response.capture_body(max_entry_bytes, move |body| {
+        store_captured_response(target, status_code, content_type, body);
+    });
+    (response, Some(commit))
+}
+
+fn store_captured_response(
+    target: ResponseCacheTarget,
+    status_code: u16,
+    content_type: Option<String>,
+    body: Vec<u8>,
+) {
+    let Some(storage) = crate::storage_helpers::open_storage() else {
+        return;
+    };
+    let now = now_ts();
+    let entry = ResponseCacheEntry {
+        cache_key: target.cache_key,
+        key_id: target.key_id,
+        model: target.model,
+        request_path: target.request_path,
+        status_code: i64::from(status_code),
+        content_type,
+        body,
+        hit_count: 0,
+        created_at: now,
+        expires_at: now.saturating_add(target.ttl_secs),
+        last_hit_at: None,
+    };
+    if let Err(err) = storage.upsert_response_cache_entry(&entry) {
+        log::warn!("event=gateway_response_cache_store_failed err={}", err);
+        return;
+    }
+    let max_total_bytes = i64::try_from(super::response_cache_max_bytes()).unwrap_or(i64::MAX);
+    if let Err(err) = storage.prune_response_cache(now, max_total_bytes) {
+        log::warn!("event=gateway_response_cache_prune_failed err={}", err);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/response_cache_tests.rs"]
+mod tests;
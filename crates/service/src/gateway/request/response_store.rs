@@ -0,0 +1,414 @@
+use bytes::Bytes;
+use codexmanager_core::storage::{now_ts, GatewayStoredResponse, Storage};
+use serde_json::Value;
+use std::collections::HashSet;
+use tiny_http::Request;
+
+use super::upstream::{GatewayBodyCaptureCommit, GatewayUpstreamResponse};
+
+const MAX_STORED_RESPONSE_CHAIN: usize = 256;
+const MAX_CAPTURED_RESPONSE_BYTES: usize = 32 * 1024 * 1024;
+const RESPONSES_PATH: &str = "/v1/responses";
+
+/// 一次 `/v1/responses` 请求在网关存储里的记录草稿：只保存本轮输入与上一轮指针，
+/// 完整上下文在需要时沿 `previous_response_id` 链重建。
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseStoreTurn {
+    key_id: String,
+    previous_response_id: Option<String>,
+    model: Option<String>,
+    input: Vec<Value>,
+}
+
+impl ResponseStoreTurn {
+    pub(crate) fn new(
+        key_id: &str,
+        previous_response_id: Option<&str>,
+        model: Option<&str>,
+        input: &Value,
+    ) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            previous_response_id: previous_response_id
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            model: model.map(str::to_string),
+            input: normalize_response_input(Some(input)),
+        }
+    }
+}
+
+/// 把 `previous_response_id` 链展开成完整 input 的请求体，供不持有该 response 的账号使用。
+#[derive(Debug, Clone)]
+pub(super) struct PreviousResponseExpansion {
+    owner_account_id: Option<String>,
+    upstream_keeps_previous_response_id: bool,
+    expanded_body: Bytes,
+}
+
+impl PreviousResponseExpansion {
+    /// 上游请求体仍携带 `previous_response_id` 且目标账号就是原响应所属账号时，交给上游续接；
+    /// 其他情况都改发本地展开后的请求体。
+    pub(super) fn should_expand_for(&self, account_id: &str) -> bool {
+        !self.upstream_keeps_previous_response_id
+            || self.owner_account_id.as_deref() != Some(account_id)
+    }
+
+    pub(super) fn expanded_body(&self) -> &Bytes {
+        &self.expanded_body
+    }
+}
+
+fn response_store_enabled() -> bool {
+    super::response_store_max_bytes() > 0
+}
+
+fn is_store_path(path: &str) -> bool {
+    path.split('?').next().unwrap_or(path) == RESPONSES_PATH
+}
+
+fn non_empty_str(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn normalize_response_input(input: Option<&Value>) -> Vec<Value> {
+    match input {
+        Some(Value::Array(items)) => items.clone(),
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(text)) => vec![serde_json::json!({
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": text }]
+        })],
+        Some(item) => vec![item.clone()],
+    }
+}
+
+/// 函数 `resolve_response_store_turn`
+///
+/// 客户端显式 `store: false` 时不落库，与上游 Responses API 的语义保持一致。
+pub(super) fn resolve_response_store_turn(
+    key_id: &str,
+    original_path: &str,
+    path: &str,
+    request_method: &str,
+    client_body: &[u8],
+) -> Option<ResponseStoreTurn> {
+    if !response_store_enabled()
+        || !request_method.eq_ignore_ascii_case("POST")
+        || !is_store_path(original_path)
+        || !is_store_path(path)
+    {
+        return None;
+    }
+    let payload = serde_json::from_slice::<Value>(client_body).ok()?;
+    let object = payload.as_object()?;
+    if object.get("store").and_then(Value::as_bool) == Some(false) {
+        return None;
+    }
+    Some(ResponseStoreTurn {
+        key_id: key_id.to_string(),
+        previous_response_id: non_empty_str(object.get("previous_response_id")),
+        model: non_empty_str(object.get("model")),
+        input: normalize_response_input(object.get("input")),
+    })
+}
+
+/// 从上游响应体里找出完成态的 response 对象；兼容 SSE 与非流式 JSON。
+fn completed_response_from_body(body: &[u8]) -> Option<Value> {
+    if let Ok(value) = serde_json::from_slice::<Value>(body) {
+        return (value.get("status").and_then(Value::as_str) == Some("completed")).then_some(value);
+    }
+    let text = std::str::from_utf8(body).ok()?;
+    text.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+        .filter(|event| event.get("type").and_then(Value::as_str) == Some("response.completed"))
+        .filter_map(|mut event| event.get_mut("response").map(Value::take))
+        .next_back()
+}
+
+/// 函数 `record_completed_response`
+///
+/// 写入一条已完成的 response 并按容量预算淘汰最旧记录。
+pub(crate) fn record_completed_response(
+    storage: &Storage,
+    turn: &ResponseStoreTurn,
+    account_id: Option<&str>,
+    response: &Value,
+) -> Result<bool, String> {
+    let Some(response_id) = non_empty_str(response.get("id")) else {
+        return Ok(false);
+    };
+    let Some(output) = response.get("output").and_then(Value::as_array) else {
+        return Ok(false);
+    };
+    let input_json = serde_json::to_string(&turn.input)
+        .map_err(|err| format!("serialize stored response input failed: {err}"))?;
+    let output_json = serde_json::to_string(output)
+        .map_err(|err| format!("serialize stored response output failed: {err}"))?;
+    let response_json = serde_json::to_string(response)
+        .map_err(|err| format!("serialize stored response failed: {err}"))?;
+    let retained_bytes = input_json.len() + output_json.len() + response_json.len();
+    storage
+        .upsert_gateway_response(&GatewayStoredResponse {
+            response_id,
+            key_id: turn.key_id.clone(),
+            account_id: account_id.map(str::to_string),
+            previous_response_id: turn.previous_response_id.clone(),
+            model: non_empty_str(response.get("model")).or_else(|| turn.model.clone()),
+            input_json,
+            output_json,
+            response_json,
+            retained_bytes: retained_bytes as i64,
+            created_at: now_ts(),
+        })
+        .map_err(|err| format!("save stored response failed: {err}"))?;
+    let max_total_bytes = i64::try_from(super::response_store_max_bytes()).unwrap_or(i64::MAX);
+    storage
+        .prune_gateway_responses(max_total_bytes)
+        .map_err(|err| format!("prune stored responses failed: {err}"))?;
+    Ok(true)
+}
+
+/// 函数 `capture_completed_response`
+///
+/// 对 2xx 上游响应分流一份副本，bridge 确认完整送达后解析出完成态 response 并落库。
+pub(super) fn capture_completed_response(
+    turn: &ResponseStoreTurn,
+    account_id: &str,
+    response: GatewayUpstreamResponse,
+) -> (GatewayUpstreamResponse, Option<GatewayBodyCaptureCommit>) {
+    if !response.status().is_success() {
+        return (response, None);
+    }
+    let turn = turn.clone();
+    let account_id = account_id.to_string();
+    let (response, commit) = response.capture_body(MAX_CAPTURED_RESPONSE_BYTES, move |body| {
+        let Some(response) = completed_response_from_body(body.as_slice()) else {
+            return;
+        };
+        let Some(storage) = crate::storage_helpers::open_storage() else {
+            return;
+        };
+        if let Err(err) =
+            record_completed_response(&storage, &turn, Some(account_id.as_str()), &response)
+        {
+            log::warn!("event=gateway_response_store_record_failed err={}", err);
+        }
+    });
+    (response, Some(commit))
+}
+
+/// 函数 `collect_stored_response_history`
+///
+/// 沿 `previous_response_id` 链回溯，按时间顺序返回每轮 input 与 output；
+/// 同时返回链头 response 的所属账号。
+pub(crate) fn collect_stored_response_history(
+    storage: &Storage,
+    key_id: &str,
+    response_id: &str,
+) -> Result<(Vec<Value>, Option<String>), String> {
+    let mut current_response_id = response_id.to_string();
+    let mut visited = HashSet::new();
+    let mut chain = Vec::new();
+    loop {
+        if !visited.insert(current_response_id.clone()) {
+            return Err(format!(
+                "stored response history contains a cycle at {current_response_id}"
+            ));
+        }
+        if chain.len() >= MAX_STORED_RESPONSE_CHAIN {
+            return Err(format!(
+                "stored response history for {response_id} exceeds the {MAX_STORED_RESPONSE_CHAIN} response limit"
+            ));
+        }
+        let stored = storage
+            .find_gateway_response(key_id, current_response_id.as_str())
+            .map_err(|err| format!("read stored response failed: {err}"))?
+            .ok_or_else(|| {
+                format!("previous_response_id {current_response_id} is not available in the gateway response store")
+            })?;
+        let previous_response_id = stored.previous_response_id.clone();
+        chain.push(stored);
+        match previous_response_id {
+            Some(previous_response_id) => current_response_id = previous_response_id,
+            None => break,
+        }
+    }
+
+    let owner_account_id = chain.first().and_then(|stored| stored.account_id.clone());
+    let mut history = Vec::new();
+    for stored in chain.into_iter().rev() {
+        for json in [stored.input_json, stored.output_json] {
+            let items = serde_json::from_str::<Vec<Value>>(json.as_str())
+                .map_err(|err| format!("parse stored response items failed: {err}"))?;
+            history.extend(items);
+        }
+    }
+    Ok((history, owner_account_id))
+}
+
+/// 用历史 items 替换 `previous_response_id`，生成可发给任意账号的完整请求体。
+pub(crate) fn expand_previous_response_body(
+    body: &[u8],
+    history: Vec<Value>,
+) -> Result<Vec<u8>, String> {
+    let mut payload = serde_json::from_slice::<Value>(body)
+        .map_err(|err| format!("parse request for response history expansion failed: {err}"))?;
+    let object = payload.as_object_mut().ok_or_else(|| {
+        "request for response history expansion must be a JSON object".to_string()
+    })?;
+    let mut input = history;
+    input.extend(normalize_response_input(object.get("input")));
+    object.remove("previous_response_id");
+    object.insert("input".to_string(), Value::Array(input));
+    let serialized = serde_json::to_vec(&payload)
+        .map_err(|err| format!("serialize request after history expansion failed: {err}"))?;
+    // 中文注释：历史里的加密推理内容只对原账号有效，展开后统一剥离。
+    Ok(super::strip_cross_account_encrypted_content(&serialized).unwrap_or(serialized))
+}
+
+/// 函数 `resolve_previous_response_expansion`
+///
+/// 客户端请求带 `previous_response_id` 且网关存储里有完整链路时，预先生成展开后的请求体；
+/// 链路缺失时返回 `None`，沿用原有透传行为。
+pub(super) fn resolve_previous_response_expansion(
+    storage: &Storage,
+    trace_id: &str,
+    key_id: &str,
+    original_path: &str,
+    path: &str,
+    client_body: &[u8],
+    upstream_body: &[u8],
+) -> Option<PreviousResponseExpansion> {
+    if !response_store_enabled() || !is_store_path(original_path) || !is_store_path(path) {
+        return None;
+    }
+    let client_payload = serde_json::from_slice::<Value>(client_body).ok()?;
+    let previous_response_id = non_empty_str(client_payload.get("previous_response_id"))?;
+    let (history, owner_account_id) = match collect_stored_response_history(
+        storage,
+        key_id,
+        previous_response_id.as_str(),
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            log::debug!(
+                    "event=gateway_response_store_expansion_skipped trace_id={} previous_response_id={} err={}",
+                    trace_id,
+                    previous_response_id,
+                    err
+                );
+            return None;
+        }
+    };
+    let upstream_keeps_previous_response_id = serde_json::from_slice::<Value>(upstream_body)
+        .ok()
+        .and_then(|value| non_empty_str(value.get("previous_response_id")))
+        .is_some();
+    let expanded_body = match expand_previous_response_body(upstream_body, history) {
+        Ok(body) => Bytes::from(body),
+        Err(err) => {
+            log::warn!(
+                "event=gateway_response_store_expansion_failed trace_id={} previous_response_id={} err={}",
+                trace_id,
+                previous_response_id,
+                err
+            );
+            return None;
+        }
+    };
+    Some(PreviousResponseExpansion {
+        owner_account_id,
+        upstream_keeps_previous_response_id,
+        expanded_body,
+    })
+}
+
+fn stored_response_id_from_path(path: &str) -> Option<&str> {
+    let normalized = path.split('?').next().unwrap_or(path);
+    let response_id = normalized.strip_prefix("/v1/responses/")?.trim();
+    (!response_id.is_empty() && !response_id.contains('/') && response_id != "compact")
+        .then_some(response_id)
+}
+
+/// 函数 `maybe_respond_local_stored_response`
+///
+/// `GET/DELETE /v1/responses/{id}` 命中网关存储时本地响应；未命中则继续转发上游。
+pub(super) fn maybe_respond_local_stored_response(
+    request: Request,
+    context: &super::local_response::LocalResponseContext<'_>,
+) -> Result<Option<Request>, String> {
+    let is_get = context.request_method.eq_ignore_ascii_case("GET");
+    let is_delete = context.request_method.eq_ignore_ascii_case("DELETE");
+    if !(is_get || is_delete) {
+        return Ok(Some(request));
+    }
+    let Some(response_id) = stored_response_id_from_path(context.path) else {
+        return Ok(Some(request));
+    };
+    let usage = super::request_log::RequestLogUsage::default();
+    if is_delete {
+        return match context
+            .storage
+            .delete_gateway_response(context.key_id, response_id)
+        {
+            Ok(true) => {
+                let body = serde_json::json!({
+                    "id": response_id,
+                    "object": "response",
+                    "deleted": true,
+                });
+                super::local_response::respond_local_json(
+                    request,
+                    context,
+                    body.to_string(),
+                    usage,
+                )?;
+                Ok(None)
+            }
+            Ok(false) => Ok(Some(request)),
+            Err(err) => {
+                log::warn!(
+                    "event=gateway_response_store_delete_failed trace_id={} err={}",
+                    context.trace_id,
+                    err
+                );
+                Ok(Some(request))
+            }
+        };
+    }
+    match context
+        .storage
+        .find_gateway_response(context.key_id, response_id)
+    {
+        Ok(Some(stored)) => {
+            super::local_response::respond_local_json(
+                request,
+                context,
+                stored.response_json,
+                usage,
+            )?;
+            Ok(None)
+        }
+        Ok(None) => Ok(Some(request)),
+        Err(err) => {
+            log::warn!(
+                "event=gateway_response_store_read_failed trace_id={} err={}",
+                context.trace_id,
+                err
+            );
+            Ok(Some(request))
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/response_store_tests.rs"]
+mod tests;
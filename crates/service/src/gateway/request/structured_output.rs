@@ -0,0 +1,626 @@
+use bytes::Bytes;
+use codexmanager_core::storage::{now_ts, Storage};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use super::request_log::RequestLogUsage;
+use super::upstream::GatewayUpstreamResponse;
+
+/// 回传给模型与写入日志的校验错误条数上限。
+const MAX_REPORTED_ERRORS: usize = 8;
+/// `$ref` 与嵌套 schema 的递归深度上限，防止自引用 schema 无限展开。
+const MAX_SCHEMA_DEPTH: usize = 64;
+const PENDING_REPORTS_TTL_SECS: i64 = 600;
+/// 不含消息文本（如工具调用）的响应不做校验。
+pub(super) const SKIP_REASON_NO_MESSAGE_TEXT: &str = "no_message_text";
+/// 聚合 API 路由不做结构化输出校验。
+pub(super) const SKIP_REASON_AGGREGATE_API: &str = "aggregate_api";
+
+/// trace_id -> (登记时间, 校验结果)，写请求日志时取出落库。
+static PENDING_REPORTS: OnceLock<Mutex<HashMap<String, (i64, StructuredOutputReport)>>> =
+    OnceLock::new();
+
+/// 一次请求的结构化输出校验目标；只有平台 Key 或模型开启校验且请求声明了 json_schema 时才会生成。
+#[derive(Debug, Clone)]
+pub(super) struct StructuredOutputTarget {
+    schema: Value,
+    max_repairs: usize,
+}
+
+/// 结构化输出校验的最终结果，用于日志。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StructuredOutputOutcome {
+    Valid,
+    Repaired,
+    Invalid,
+    Skipped,
+}
+
+impl StructuredOutputOutcome {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Repaired => "repaired",
+            Self::Invalid => "invalid",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// 一次请求的结构化输出校验结果；`repair_usage` 是被修复替换掉的响应消耗，写日志时计入请求用量。
+#[derive(Debug, Clone)]
+pub(super) struct StructuredOutputReport {
+    pub(super) outcome: StructuredOutputOutcome,
+    pub(super) repairs: usize,
+    pub(super) skip_reason: Option<&'static str>,
+    pub(super) errors: Vec<String>,
+    pub(super) repair_usage: RequestLogUsage,
+}
+
+fn pending_reports() -> &'static Mutex<HashMap<String, (i64, StructuredOutputReport)>> {
+    PENDING_REPORTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_structured_output_report(trace_id: &str, report: StructuredOutputReport) {
+    let now = now_ts();
+    let mut pending =
+        crate::lock_utils::lock_recover(pending_reports(), "structured_output_reports");
+    // 中文注释：请求异常中断时不会写日志，顺带清理过期条目防止常驻增长。
+    pending.retain(|_, (created_at, _)| now - *created_at < PENDING_REPORTS_TTL_SECS);
+    pending.insert(trace_id.to_string(), (now, report));
+}
+
+/// 取出（并移除）该请求的结构化输出校验结果，供写请求日志时落库。
+pub(super) fn take_structured_output_report(trace_id: &str) -> Option<StructuredOutputReport> {
+    let pending = PENDING_REPORTS.get()?;
+    crate::lock_utils::lock_recover(pending, "structured_output_reports")
+        .remove(trace_id)
+        .map(|(_, report)| report)
+}
+
+/// 函数 `record_aggregate_route_skipped`
+///
+/// 聚合 API 路由不做结构化输出校验（候选供应商协议各异，无法在同一上游上发起修复重试）；
+/// 请求需要校验时记一条 `skipped` 结果，让请求日志能看出这次未校验。
+pub(super) fn record_aggregate_route_skipped(trace_id: &str) {
+    finish(
+        trace_id,
+        StructuredOutputReport {
+            outcome: StructuredOutputOutcome::Skipped,
+            repairs: 0,
+            skip_reason: Some(SKIP_REASON_AGGREGATE_API),
+            errors: Vec::new(),
+            repair_usage: RequestLogUsage::default(),
+        },
+    );
+}
+
+/// 函数 `requested_json_schema`
+///
+/// 读取上游请求体声明的 JSON schema：Responses 的 `text.format`，或 Chat 的 `response_format`。
+pub(super) fn requested_json_schema(body: &[u8]) -> Option<Value> {
+    let payload = serde_json::from_slice::<Value>(body).ok()?;
+    if let Some(format) = payload.pointer("/text/format") {
+        if format.get("type").and_then(Value::as_str) != Some("json_schema") {
+            return None;
+        }
+        return format
+            .get("schema")
+            .filter(|schema| is_schema(schema))
+            .cloned();
+    }
+    let format = payload.get("response_format")?;
+    if format.get("type").and_then(Value::as_str) != Some("json_schema") {
+        return None;
+    }
+    format
+        .pointer("/json_schema/schema")
+        .filter(|schema| is_schema(schema))
+        .cloned()
+}
+
+fn is_schema(value: &Value) -> bool {
+    value.is_object() || value.is_boolean()
+}
+
+fn model_enabled_by_env(models: &[String], model: Option<&str>) -> bool {
+    if models.iter().any(|item| item == "*") {
+        return true;
+    }
+    let Some(model) = model.map(str::trim).filter(|value| !value.is_empty()) else {
+        return false;
+    };
+    let model = model.to_ascii_lowercase();
+    models.contains(&model)
+}
+
+/// 函数 `resolve_structured_output_target`
+///
+/// 平台 Key 策略开启时按其修复次数执行；否则按 `CODEXMANAGER_STRUCTURED_OUTPUT_VALIDATION_MODELS`
+/// 匹配模型。流式请求无法在交付前整体校验，直接跳过。
+pub(super) fn resolve_structured_output_target(
+    storage: &Storage,
+    trace_id: &str,
+    key_id: &str,
+    model: Option<&str>,
+    request_method: &str,
+    client_is_stream: bool,
+    body: &[u8],
+) -> Option<StructuredOutputTarget> {
+    if client_is_stream || !request_method.eq_ignore_ascii_case("POST") {
+        return None;
+    }
+    let schema = requested_json_schema(body)?;
+    let policy = match storage.find_api_key_structured_output_policy(key_id) {
+        Ok(policy) => policy,
+        Err(err) => {
+            log::warn!(
+                "event=gateway_structured_output_policy_read_failed trace_id={} key_id={} err={}",
+                trace_id,
+                key_id,
+                err
+            );
+            None
+        }
+    };
+    let max_repairs = match policy {
+        Some(policy) if policy.enabled => usize::try_from(policy.max_repairs).unwrap_or(0),
+        _ if model_enabled_by_env(&super::structured_output_validation_models(), model) => {
+            super::structured_output_max_repairs()
+        }
+        _ => return None,
+    };
+    Some(StructuredOutputTarget {
+        schema,
+        max_repairs,
+    })
+}
+
+/// 函数 `validate_output_text`
+///
+/// 把输出文本按 JSON 解析后对照 schema 校验，返回不超过上限条数的错误描述；空列表表示通过。
+pub(super) fn validate_output_text(schema: &Value, text: &str) -> Vec<String> {
+    let instance = match serde_json::from_str::<Value>(text.trim()) {
+        Ok(value) => value,
+        Err(err) => return vec![format!("output is not valid JSON: {err}")],
+    };
+    let mut errors = Vec::new();
+    validate_value(schema, schema, &instance, "$", 0, &mut errors);
+    errors.truncate(MAX_REPORTED_ERRORS);
+    errors
+}
+
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    if reference == "#" {
+        return Some(root);
+    }
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}.{key}")
+}
+
+fn validate_value(
+    root: &Value,
+    schema: &Value,
+    instance: &Value,
+    path: &str,
+    depth: usize,
+    errors: &mut Vec<String>,
+) {
+    if errors.len() >= MAX_REPORTED_ERRORS || depth > MAX_SCHEMA_DEPTH {
+        return;
+    }
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{path}: no value is allowed here"));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve_ref(root, reference) {
+            Some(target) => validate_value(root, target, instance, path, depth + 1, errors),
+            None => errors.push(format!("{path}: unresolved schema reference {reference}")),
+        }
+    }
+    if let Some(expected) = schema.get("type") {
+        let allowed = match expected {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|kind| matches_type(kind, instance)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                json_type_name(instance)
+            ));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(instance) {
+            errors.push(format!(
+                "{path}: value is not one of the allowed enum values"
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            errors.push(format!("{path}: value must equal {expected}"));
+        }
+    }
+    validate_combinators(root, schema, instance, path, depth, errors);
+    match instance {
+        Value::Object(object) => validate_object(root, schema, object, path, depth, errors),
+        Value::Array(items) => validate_array(root, schema, items, path, depth, errors),
+        Value::String(text) => validate_string(schema, text, path, errors),
+        Value::Number(_) => validate_number(schema, instance, path, errors),
+        _ => {}
+    }
+}
+
+fn count_valid(
+    root: &Value,
+    schemas: &[Value],
+    instance: &Value,
+    path: &str,
+    depth: usize,
+) -> usize {
+    schemas
+        .iter()
+        .filter(|schema| {
+            let mut branch_errors = Vec::new();
+            validate_value(root, schema, instance, path, depth + 1, &mut branch_errors);
+            branch_errors.is_empty()
+        })
+        .count()
+}
+
+fn validate_combinators(
+    root: &Value,
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    depth: usize,
+    errors: &mut Vec<String>,
+) {
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        for branch in all_of {
+            validate_value(root, branch, instance, path, depth + 1, errors);
+        }
+    }
+    if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array) {
+        if count_valid(root, any_of, instance, path, depth) == 0 {
+            errors.push(format!("{path}: value does not match any allowed schema"));
+        }
+    }
+    if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+        let matched = count_valid(root, one_of, instance, path, depth);
+        if matched != 1 {
+            errors.push(format!(
+                "{path}: value must match exactly one schema, matched {matched}"
+            ));
+        }
+    }
+}
+
+fn validate_object(
+    root: &Value,
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    depth: usize,
+    errors: &mut Vec<String>,
+) {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!("{path}: missing required property \"{key}\""));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, value) in object {
+        let value_path = child_path(path, key);
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property) => {
+                validate_value(root, property, value, &value_path, depth + 1, errors);
+            }
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    errors.push(format!("{path}: unexpected property \"{key}\""));
+                }
+                Some(additional @ Value::Object(_)) => {
+                    validate_value(root, additional, value, &value_path, depth + 1, errors);
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+fn validate_array(
+    root: &Value,
+    schema: &Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    depth: usize,
+    errors: &mut Vec<String>,
+) {
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if (items.len() as u64) < min {
+            errors.push(format!("{path}: expected at least {min} items"));
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if items.len() as u64 > max {
+            errors.push(format!("{path}: expected at most {max} items"));
+        }
+    }
+    if let Some(item_schema) = schema.get("items").filter(|value| is_schema(value)) {
+        for (index, item) in items.iter().enumerate() {
+            let item_path = format!("{path}[{index}]");
+            validate_value(root, item_schema, item, &item_path, depth + 1, errors);
+        }
+    }
+}
+
+fn validate_string(schema: &Map<String, Value>, text: &str, path: &str, errors: &mut Vec<String>) {
+    let length = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            errors.push(format!("{path}: expected at least {min} characters"));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            errors.push(format!("{path}: expected at most {max} characters"));
+        }
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(number) = instance.as_f64() else {
+        return;
+    };
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if number < min {
+            errors.push(format!("{path}: must be >= {min}"));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if number > max {
+            errors.push(format!("{path}: must be <= {max}"));
+        }
+    }
+    if let Some(min) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+        if number <= min {
+            errors.push(format!("{path}: must be > {min}"));
+        }
+    }
+    if let Some(max) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+        if number >= max {
+            errors.push(format!("{path}: must be < {max}"));
+        }
+    }
+}
+
+fn repair_instruction(errors: &[String]) -> String {
+    let mut text =
+        String::from("The previous response does not conform to the required JSON schema:\n");
+    for error in errors {
+        text.push_str("- ");
+        text.push_str(error);
+        text.push('\n');
+    }
+    text.push_str(
+        "Respond again with only the corrected JSON value that satisfies the schema, without any other text.",
+    );
+    text
+}
+
+/// 函数 `build_repair_body`
+///
+/// 在原请求的对话末尾追加上一轮无效输出与校验错误，生成修复重试的请求体。
+pub(super) fn build_repair_body(
+    body: &[u8],
+    invalid_output: &str,
+    errors: &[String],
+) -> Option<Bytes> {
+    let mut payload = serde_json::from_slice::<Value>(body).ok()?;
+    let object = payload.as_object_mut()?;
+    let instruction = repair_instruction(errors);
+    if let Some(messages) = object.get_mut("messages").and_then(Value::as_array_mut) {
+        messages.push(serde_json::json!({ "role": "assistant", "content": invalid_output }));
+        messages.push(serde_json::json!({ "role": "user", "content": instruction }));
+    } else {
+        let mut input = match object.remove("input") {
+            Some(Value::Array(items)) => items,
+            Some(Value::String(text)) => vec![serde_json::json!({
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": text }],
+            })],
+            Some(Value::Null) | None => Vec::new(),
+            Some(_) => return None,
+        };
+        input.push(serde_json::json!({
+            "type": "message",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": invalid_output }],
+        }));
+        input.push(serde_json::json!({
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": instruction }],
+        }));
+        object.insert("input".to_string(), Value::Array(input));
+    }
+    serde_json::to_vec(&payload).ok().map(Bytes::from)
+}
+
+/// 函数 `merge_repair_usage`
+///
+/// 把被修复替换掉的响应消耗累加到 `total`；首包耗时与估算值仍以交付的响应为准。
+pub(super) fn merge_repair_usage(total: &mut RequestLogUsage, usage: &RequestLogUsage) {
+    fn add(left: Option<i64>, right: Option<i64>) -> Option<i64> {
+        match (left, right) {
+            (None, None) => None,
+            (left, right) => Some(left.unwrap_or(0).saturating_add(right.unwrap_or(0))),
+        }
+    }
+    total.input_tokens = add(total.input_tokens, usage.input_tokens);
+    total.cached_input_tokens = add(total.cached_input_tokens, usage.cached_input_tokens);
+    total.cache_write_tokens = add(total.cache_write_tokens, usage.cache_write_tokens);
+    total.output_tokens = add(total.output_tokens, usage.output_tokens);
+    total.total_tokens = add(total.total_tokens, usage.total_tokens);
+    total.reasoning_output_tokens =
+        add(total.reasoning_output_tokens, usage.reasoning_output_tokens);
+}
+
+fn finish(trace_id: &str, report: StructuredOutputReport) -> StructuredOutputOutcome {
+    let outcome = report.outcome;
+    let repair_tokens = report.repair_usage.total_tokens.unwrap_or(0);
+    if report.errors.is_empty() {
+        log::info!(
+            "event=gateway_structured_output trace_id={} outcome={} repairs={} repair_tokens={} skip_reason={}",
+            trace_id,
+            outcome.as_str(),
+            report.repairs,
+            repair_tokens,
+            report.skip_reason.unwrap_or("-")
+        );
+    } else {
+        log::warn!(
+            "event=gateway_structured_output trace_id={} outcome={} repairs={} repair_tokens={} errors={}",
+            trace_id,
+            outcome.as_str(),
+            report.repairs,
+            repair_tokens,
+            report.errors.join("; ")
+        );
+    }
+    record_structured_output_report(trace_id, report);
+    outcome
+}
+
+/// 函数 `enforce_structured_output`
+///
+/// 缓冲 2xx 上游响应并校验最终输出；不符合 schema 时通过 `send_repair` 在同一账号上
+/// 最多重试 `max_repairs` 次，返回最后一次收到的响应及校验结果。
+/// 不含消息文本的响应（如工具调用）不做校验。
+pub(super) fn enforce_structured_output<F>(
+    target: &StructuredOutputTarget,
+    trace_id: &str,
+    body: &Bytes,
+    response: GatewayUpstreamResponse,
+    mut send_repair: F,
+) -> Result<(GatewayUpstreamResponse, StructuredOutputOutcome), String>
+where
+    F: FnMut(&Bytes) -> Option<GatewayUpstreamResponse>,
+{
+    let (mut buffered, mut current) = response.into_buffered()?;
+    let mut request_body = body.clone();
+    let mut report = StructuredOutputReport {
+        outcome: StructuredOutputOutcome::Invalid,
+        repairs: 0,
+        skip_reason: None,
+        errors: Vec::new(),
+        repair_usage: RequestLogUsage::default(),
+    };
+    loop {
+        let Some(output) = super::http_bridge::extract_final_output_text_from_body(&buffered)
+        else {
+            report.outcome = StructuredOutputOutcome::Skipped;
+            report.skip_reason = Some(SKIP_REASON_NO_MESSAGE_TEXT);
+            return Ok((current, finish(trace_id, report)));
+        };
+        let errors = validate_output_text(&target.schema, &output);
+        if errors.is_empty() {
+            report.outcome = if report.repairs == 0 {
+                StructuredOutputOutcome::Valid
+            } else {
+                StructuredOutputOutcome::Repaired
+            };
+            return Ok((current, finish(trace_id, report)));
+        }
+        report.errors = errors;
+        if report.repairs >= target.max_repairs {
+            return Ok((current, finish(trace_id, report)));
+        }
+        let Some(repair_body) = build_repair_body(&request_body, &output, &report.errors) else {
+            return Ok((current, finish(trace_id, report)));
+        };
+        report.repairs += 1;
+        let repaired = send_repair(&repair_body)
+            .filter(|response| response.status().is_success())
+            .and_then(|response| match response.into_buffered() {
+                Ok(buffered) => Some(buffered),
+                Err(err) => {
+                    log::warn!(
+                        "event=gateway_structured_output_repair_read_failed trace_id={} err={}",
+                        trace_id,
+                        err
+                    );
+                    None
+                }
+            });
+        let Some((next_buffered, next)) = repaired else {
+            // 中文注释：修复请求本身失败时保留上一轮的完整响应交付给客户端。
+            return Ok((current, finish(trace_id, report)));
+        };
+        // 中文注释：被替换的这一轮不会交付，但上游已计费，用量要并入请求日志。
+        merge_repair_usage(
+            &mut report.repair_usage,
+            &super::http_bridge::extract_usage_from_body(&buffered),
+        );
+        buffered = next_buffered;
+        current = next;
+        request_body = repair_body;
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/structured_output_tests.rs"]
+mod tests;
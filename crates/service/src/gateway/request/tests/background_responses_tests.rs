@@ -0,0 +1,146 @@
+use super::*;
+
+fn open_test_storage() -> Storage {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    storage
+}
+
+fn insert_queued(storage: &Storage, response_id: &str) -> Value {
+    let snapshot = queued_response_snapshot(response_id, 100, &json!({ "model": "gpt-5" }));
+    storage
+        .insert_gateway_background_response(&GatewayBackgroundResponse {
+            response_id: response_id.to_string(),
+            key_id: "key-a".to_string(),
+            status: STATUS_QUEUED.to_string(),
+            response_json: snapshot.to_string(),
+            last_sequence_number: -1,
+            created_at: 100,
+            updated_at: 100,
+        })
+        .expect("insert background response");
+    snapshot
+}
+
+fn test_job(response_id: &str, snapshot: Value) -> BackgroundResponseJob {
+    BackgroundResponseJob {
+        response_id: response_id.to_string(),
+        backend_addr: "127.0.0.1:1".to_string(),
+        headers: Vec::new(),
+        body: Vec::new(),
+        snapshot,
+        store_turn: None,
+        cancelled: Arc::new(AtomicBool::new(false)),
+    }
+}
+
+#[test]
+fn background_routes_only_claim_gateway_ids() {
+    assert!(matches!(
+        resolve_background_route("POST", "/v1/responses"),
+        Some(BackgroundRoute::Create)
+    ));
+    assert!(matches!(
+        resolve_background_route("GET", "/v1/responses/resp_bg_1?stream=true"),
+        Some(BackgroundRoute::Retrieve {
+            response_id: "resp_bg_1",
+            query: Some("stream=true")
+        })
+    ));
+    assert!(matches!(
+        resolve_background_route("POST", "/v1/responses/resp_bg_1/cancel"),
+        Some(BackgroundRoute::Cancel {
+            response_id: "resp_bg_1"
+        })
+    ));
+    assert!(resolve_background_route("GET", "/v1/responses/resp_upstream").is_none());
+    assert!(resolve_background_route("GET", "/v1/responses").is_none());
+    assert!(resolve_background_route("POST", "/v1/responses/compact").is_none());
+
+    assert_eq!(
+        parse_retrieve_options(Some("stream=true&starting_after=7")),
+        RetrieveOptions {
+            stream: true,
+            starting_after: Some(7)
+        }
+    );
+    assert_eq!(parse_retrieve_options(None), RetrieveOptions::default());
+}
+
+#[test]
+fn background_upstream_body_drops_background_and_forces_stream() {
+    let body = build_background_upstream_body(
+        &json!({ "model": "gpt-5", "input": "hi", "background": true, "stream": false }),
+    )
+    .expect("body");
+    let value: Value = serde_json::from_slice(&body).expect("json");
+    assert!(value.get("background").is_none());
+    assert_eq!(value["stream"], true);
+    assert_eq!(value["input"], "hi");
+}
+
+#[test]
+fn background_sink_persists_rewritten_events_until_terminal() {
+    let storage = open_test_storage();
+    let snapshot = insert_queued(&storage, "resp_bg_1");
+    let job = test_job("resp_bg_1", snapshot);
+    let mut sink = BackgroundEventSink {
+        storage: &storage,
+        job: &job,
+        next_sequence_number: 0,
+    };
+
+    assert!(!sink
+        .observe(
+            r#"{"type":"response.created","response":{"id":"resp_up","status":"in_progress"}}"#
+        )
+        .expect("created"));
+    assert!(!sink
+        .observe(r#"{"type":"response.output_text.delta","delta":"hi"}"#)
+        .expect("delta"));
+    assert!(sink
+        .observe(r#"{"type":"response.completed","response":{"id":"resp_up","status":"completed","output":[]}}"#)
+        .expect("completed"));
+
+    let record = storage
+        .find_gateway_background_response("key-a", "resp_bg_1")
+        .expect("find")
+        .expect("record");
+    assert_eq!(record.status, "completed");
+    assert_eq!(record.last_sequence_number, 2);
+    let final_snapshot: Value = serde_json::from_str(&record.response_json).expect("snapshot");
+    assert_eq!(final_snapshot["id"], "resp_bg_1");
+    assert_eq!(final_snapshot["background"], true);
+
+    let events = storage
+        .list_gateway_background_response_events("resp_bg_1", 0, 10)
+        .expect("events");
+    assert_eq!(events.len(), 2);
+    let delta: Value = serde_json::from_str(&events[0].event_json).expect("delta json");
+    assert_eq!(delta["sequence_number"], 1);
+}
+
+#[test]
+fn cancel_and_orphan_reconcile_settle_background_status() {
+    let storage = open_test_storage();
+    insert_queued(&storage, "resp_bg_running");
+    let cancelled = try_register_background_response("resp_bg_running", usize::MAX)
+        .expect("register running job");
+    let record = cancel_background_response(&storage, "key-a", "resp_bg_running")
+        .expect("cancel")
+        .expect("record");
+    assert_eq!(record.status, STATUS_CANCELLED);
+    assert!(cancelled.load(Ordering::Relaxed));
+    unregister_background_response("resp_bg_running");
+
+    insert_queued(&storage, "resp_bg_orphan");
+    let record = find_background_response(&storage, "key-a", "resp_bg_orphan")
+        .expect("find")
+        .expect("record");
+    assert_eq!(record.status, STATUS_FAILED);
+    assert!(
+        find_background_response(&storage, "key-b", "resp_bg_orphan")
+            .expect("find other key")
+            .is_none()
+    );
+}
@@ -0,0 +1,294 @@
+use super::*;
+
+fn open_test_storage() -> Storage {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    storage
+}
+
+fn insert_running_batch(storage: &Storage, batch_id: &str, expires_at: i64) {
+    let batch = GatewayBatch {
+        batch_id: batch_id.to_string(),
+        key_id: "key-a".to_string(),
+        endpoint: "/v1/responses".to_string(),
+        input_file_id: "file-in".to_string(),
+        completion_window: COMPLETION_WINDOW.to_string(),
+        status: STATUS_VALIDATING.to_string(),
+        created_at: 100,
+        expires_at,
+        updated_at: 100,
+        ..Default::default()
+    };
+    let items = (0..3)
+        .map(|line_index| GatewayBatchItem {
+            batch_id: batch_id.to_string(),
+            line_index,
+            custom_id: format!("req-{line_index}"),
+            url: "/v1/responses".to_string(),
+            body_json: "{}".to_string(),
+            status: ITEM_PENDING.to_string(),
+            status_code: None,
+            result_json: None,
+            updated_at: 100,
+        })
+        .collect::<Vec<_>>();
+    storage
+        .insert_gateway_batch(&batch, &items)
+        .expect("insert batch");
+    storage
+        .set_gateway_batch_status(batch_id, STATUS_IN_PROGRESS, 101)
+        .expect("start batch");
+    let ok_line = batch_output_line("req-0", 200, Some("trc-1"), r#"{"id":"resp_1"}"#);
+    storage
+        .update_gateway_batch_item(
+            batch_id,
+            0,
+            ITEM_COMPLETED,
+            Some(200),
+            Some(ok_line.to_string().as_str()),
+            102,
+        )
+        .expect("complete item");
+    let failed_line = batch_output_line("req-1", 400, None, r#"{"error":{"message":"bad"}}"#);
+    storage
+        .update_gateway_batch_item(
+            batch_id,
+            1,
+            ITEM_FAILED,
+            Some(400),
+            Some(failed_line.to_string().as_str()),
+            102,
+        )
+        .expect("fail item");
+}
+
+fn test_run(batch_id: &str, expires_at: i64, cancelled: bool) -> BatchRun {
+    BatchRun {
+        batch_id: batch_id.to_string(),
+        key_id: "key-a".to_string(),
+        endpoint: "/v1/responses".to_string(),
+        expires_at,
+        backend_addr: "127.0.0.1:1".to_string(),
+        auth_headers: Vec::new(),
+        loopback_token: String::new(),
+        cancelled: Arc::new(AtomicBool::new(cancelled)),
+    }
+}
+
+fn file_lines(storage: &Storage, file_id: Option<&str>) -> Vec<Value> {
+    let content = storage
+        .find_gateway_file_content("key-a", file_id.expect("file id"))
+        .expect("read file")
+        .expect("file exists");
+    String::from_utf8(content)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("json line"))
+        .collect()
+}
+
+#[test]
+fn batch_routes_only_claim_gateway_ids() {
+    assert!(matches!(
+        resolve_batch_route("POST", "/v1/files"),
+        Some(BatchRoute::UploadFile)
+    ));
+    assert!(matches!(
+        resolve_batch_route("GET", "/v1/files?purpose=batch&limit=5"),
+        Some(BatchRoute::ListFiles {
+            query: Some("purpose=batch&limit=5")
+        })
+    ));
+    assert!(matches!(
+        resolve_batch_route("GET", "/v1/files/file-abc/content"),
+        Some(BatchRoute::FileContent {
+            file_id: "file-abc"
+        })
+    ));
+    assert!(matches!(
+        resolve_batch_route("delete", "/v1/files/file-abc"),
+        Some(BatchRoute::DeleteFile {
+            file_id: "file-abc"
+        })
+    ));
+    assert!(matches!(
+        resolve_batch_route("POST", "/v1/batches/batch_abc/cancel"),
+        Some(BatchRoute::CancelBatch {
+            batch_id: "batch_abc"
+        })
+    ));
+    assert!(resolve_batch_route("GET", "/v1/files/upload_abc").is_none());
+    assert!(resolve_batch_route("GET", "/v1/batches/other").is_none());
+    assert!(resolve_batch_route("PUT", "/v1/batches").is_none());
+    assert!(resolve_batch_route("POST", "/v1/responses").is_none());
+
+    assert_eq!(
+        query_param(Some("purpose=batch&limit=5"), "limit"),
+        Some("5")
+    );
+    assert_eq!(list_limit(Some("limit=1000")), MAX_LIST_LIMIT);
+    assert_eq!(list_limit(None), DEFAULT_LIST_LIMIT);
+}
+
+#[test]
+fn parse_batch_input_reports_every_invalid_line() {
+    let content = concat!(
+        r#"{"custom_id":"a","method":"POST","url":"/v1/responses","body":{"model":"gpt-5"}}"#,
+        "\n\n",
+        r#"{"custom_id":"a","method":"POST","url":"/v1/responses","body":{}}"#,
+        "\n",
+        r#"{"custom_id":"b","method":"GET","url":"/v1/responses","body":{}}"#,
+        "\n",
+        r#"{"custom_id":"c","method":"POST","url":"/v1/embeddings","body":{}}"#,
+        "\n",
+        r#"{"custom_id":"d","method":"POST","url":"/v1/responses","body":"x"}"#,
+        "\n",
+        "not json\n",
+    );
+
+    let errors =
+        parse_batch_input(content.as_bytes(), "/v1/responses").expect_err("invalid batch input");
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| (error.line, error.code))
+            .collect::<Vec<_>>(),
+        vec![
+            (3, "duplicate_custom_id"),
+            (4, "invalid_method"),
+            (5, "mismatched_endpoint"),
+            (6, "missing_required_parameter"),
+            (7, "invalid_json_line"),
+        ]
+    );
+    let errors_json: Value =
+        serde_json::from_str(batch_errors_json(&errors).as_str()).expect("errors json");
+    assert_eq!(errors_json["data"][0]["line"], 3);
+}
+
+#[test]
+fn parse_batch_input_keeps_request_bodies_in_order() {
+    let content = concat!(
+        r#"{"custom_id":"a","method":"POST","url":"/v1/chat/completions","body":{"n":1}}"#,
+        "\n",
+        r#"{"custom_id":"b","method":"post","url":"/v1/chat/completions","body":{"n":2}}"#,
+    );
+
+    let requests =
+        parse_batch_input(content.as_bytes(), "/v1/chat/completions").expect("valid input");
+
+    assert_eq!(
+        requests,
+        vec![
+            ("a".to_string(), json!({ "n": 1 })),
+            ("b".to_string(), json!({ "n": 2 })),
+        ]
+    );
+    assert_eq!(
+        parse_batch_input(b"\n\n", "/v1/responses")
+            .expect_err("empty input")
+            .first()
+            .map(|error| error.code),
+        Some("empty_file")
+    );
+}
+
+#[test]
+fn batch_item_body_forces_non_streaming_except_embeddings() {
+    let body = build_batch_item_body(
+        "/v1/responses",
+        r#"{"model":"gpt-5","stream":true,"stream_options":{"include_usage":true}}"#,
+    )
+    .expect("build body");
+    let body: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(body, json!({ "model": "gpt-5", "stream": false }));
+
+    let body = build_batch_item_body("/v1/embeddings", r#"{"input":"hi"}"#).expect("build body");
+    let body: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(body, json!({ "input": "hi" }));
+}
+
+#[test]
+fn finalize_batch_writes_output_and_error_files_on_cancel() {
+    let storage = open_test_storage();
+    insert_running_batch(&storage, "batch_cancel", now_ts() + 3600);
+
+    finalize_batch(&storage, &test_run("batch_cancel", now_ts() + 3600, true))
+        .expect("finalize batch");
+
+    let batch = storage
+        .find_gateway_batch("key-a", "batch_cancel")
+        .expect("read batch")
+        .expect("batch exists");
+    assert_eq!(batch.status, STATUS_CANCELLED);
+    let output = file_lines(&storage, batch.output_file_id.as_deref());
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0]["custom_id"], "req-0");
+    assert_eq!(output[0]["response"]["status_code"], 200);
+    assert_eq!(output[0]["response"]["request_id"], "trc-1");
+    assert_eq!(output[0]["response"]["body"]["id"], "resp_1");
+    let errors = file_lines(&storage, batch.error_file_id.as_deref());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["custom_id"], "req-1");
+
+    let object = load_batch_object(&storage, &batch).expect("batch object");
+    assert_eq!(object["object"], "batch");
+    assert_eq!(
+        object["request_counts"],
+        json!({ "total": 3, "completed": 1, "failed": 1 })
+    );
+}
+
+#[test]
+fn finalize_batch_fails_pending_items_after_expiry() {
+    let storage = open_test_storage();
+    insert_running_batch(&storage, "batch_expired", 1);
+
+    finalize_batch(&storage, &test_run("batch_expired", 1, false)).expect("finalize batch");
+
+    let batch = storage
+        .find_gateway_batch("key-a", "batch_expired")
+        .expect("read batch")
+        .expect("batch exists");
+    assert_eq!(batch.status, STATUS_EXPIRED);
+    assert!(batch.expired_at.is_some());
+    let errors = file_lines(&storage, batch.error_file_id.as_deref());
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1]["custom_id"], "req-2");
+    assert_eq!(errors[1]["error"]["code"], "batch_expired");
+    assert!(errors[1]["response"].is_null());
+}
+
+#[test]
+fn batch_header_only_binds_registered_loopback_tokens() {
+    use super::super::incoming_headers::IncomingHeaderSnapshot;
+
+    let snapshot_for = |value: &str| {
+        let request: Request = tiny_http::TestRequest::new()
+            .with_header(Header::from_bytes(BATCH_ID_HEADER_NAME, value).expect("batch header"))
+            .into();
+        IncomingHeaderSnapshot::from_request(&request)
+    };
+    let batch_id = "batch_loopback_token_test";
+
+    // 中文注释：客户端直接带上真实 batch id 也不能触发批处理选路，且该头不会被透传。
+    let (_, token) = try_register_batch(batch_id).expect("register batch");
+    let forged = snapshot_for(batch_id);
+    assert_eq!(forged.batch_id(), None);
+    assert!(forged.passthrough_codex_headers().is_empty());
+
+    assert_eq!(snapshot_for(token.as_str()).batch_id(), Some(batch_id));
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        BATCH_ID_HEADER_NAME,
+        axum::http::HeaderValue::from_str(token.as_str()).expect("header value"),
+    );
+    assert_eq!(
+        IncomingHeaderSnapshot::from_http_headers(&headers).batch_id(),
+        Some(batch_id)
+    );
+
+    unregister_batch(batch_id);
+    assert_eq!(snapshot_for(token.as_str()).batch_id(), None);
+}
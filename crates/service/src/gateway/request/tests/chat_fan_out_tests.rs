@@ -0,0 +1,158 @@
+use super::*;
+
+fn data_frames(bytes: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(bytes)
+        .split("\n\n")
+        .filter_map(|frame| frame.strip_prefix("data: "))
+        .filter(|payload| *payload != "[DONE]")
+        .map(|payload| serde_json::from_str(payload).expect("frame json"))
+        .collect()
+}
+
+#[test]
+fn requested_choice_count_only_accepts_n_above_one() {
+    let (payload, n) =
+        requested_choice_count(br#"{"model":"gpt-5","n":3,"messages":[]}"#).expect("fan-out");
+    assert_eq!(n, 3);
+
+    let body: Value = serde_json::from_slice(&build_child_body(&payload).expect("child body"))
+        .expect("child json");
+    assert_eq!(body, json!({ "model": "gpt-5", "messages": [] }));
+
+    assert!(requested_choice_count(br#"{"n":1}"#).is_none());
+    assert!(requested_choice_count(br#"{"n":"2"}"#).is_none());
+    assert!(requested_choice_count(br#"[{"n":2}]"#).is_none());
+}
+
+#[test]
+fn merge_chat_completion_choices_reindexes_and_sums_usage() {
+    let child = |id: &str, text: &str, tokens: i64| {
+        json!({
+            "id": id,
+            "object": "chat.completion",
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": text } }],
+            "usage": {
+                "prompt_tokens": 10,
+                "completion_tokens": tokens,
+                "completion_tokens_details": { "reasoning_tokens": 1 }
+            }
+        })
+    };
+    let merged = merge_chat_completion_choices(vec![
+        child("chatcmpl_a", "first", 3),
+        child("chatcmpl_b", "second", 4),
+        child("chatcmpl_c", "third", 5),
+    ]);
+
+    assert_eq!(merged["id"], "chatcmpl_a");
+    let choices = merged["choices"].as_array().expect("choices");
+    assert_eq!(choices.len(), 3);
+    for (index, text) in ["first", "second", "third"].iter().enumerate() {
+        assert_eq!(choices[index]["index"], index);
+        assert_eq!(choices[index]["message"]["content"], *text);
+    }
+    assert_eq!(merged["usage"]["prompt_tokens"], 30);
+    assert_eq!(merged["usage"]["completion_tokens"], 12);
+    assert_eq!(
+        merged["usage"]["completion_tokens_details"]["reasoning_tokens"],
+        3
+    );
+}
+
+#[test]
+fn stream_state_rewrites_chunks_and_emits_summed_usage() {
+    let mut state = FanOutStreamState::new(2, true);
+    let chunk = |id: &str, content: &str| {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "choices": [{ "index": 0, "delta": { "content": content } }]
+        })
+    };
+    let mut output = Vec::new();
+    output.extend(state.rewrite_chunk(1, chunk("b", "x")).expect("frame"));
+    output.extend(state.rewrite_chunk(0, chunk("a", "y")).expect("frame"));
+    let usage_only = json!({
+        "id": "a",
+        "choices": [],
+        "usage": { "prompt_tokens": 5, "completion_tokens": 2 }
+    });
+    assert!(state.rewrite_chunk(0, usage_only.clone()).is_none());
+    assert!(state.rewrite_chunk(1, usage_only).is_none());
+    output.extend(state.finish());
+
+    let frames = data_frames(&output);
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0]["choices"][0]["index"], 1);
+    assert_eq!(frames[1]["choices"][0]["index"], 0);
+    assert!(frames.iter().all(|frame| frame["id"] == "b"));
+    assert_eq!(frames[2]["choices"], json!([]));
+    assert_eq!(frames[2]["usage"]["prompt_tokens"], 10);
+    assert_eq!(frames[2]["usage"]["completion_tokens"], 4);
+    assert!(String::from_utf8_lossy(&output).ends_with("data: [DONE]\n\n"));
+
+    let mut state = FanOutStreamState::new(2, false);
+    let _ = state.rewrite_chunk(0, chunk("a", "y"));
+    let failed = state.fail("boom".to_string());
+    assert_eq!(data_frames(&failed)[0]["error"]["message"], "boom");
+    assert_eq!(state.error.as_deref(), Some("boom"));
+    assert_eq!(state.finish(), b"data: [DONE]\n\n".to_vec());
+}
+
+#[test]
+fn captured_child_logs_are_summed_once_per_registered_token() {
+    let tokens = register_fan_out_children(2, usize::MAX).expect("register");
+    assert!(register_fan_out_children(1, 0).is_none());
+    {
+        let (registry, _) = fan_out_registry();
+        let mut registry = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+        registry
+            .traces
+            .insert("trc_fan_child_0".to_string(), tokens[0].clone());
+        registry
+            .traces
+            .insert("trc_fan_child_1".to_string(), tokens[1].clone());
+    }
+    let usage = |input: i64, first_response_ms: i64| RequestLogUsage {
+        input_tokens: Some(input),
+        output_tokens: Some(2),
+        first_response_ms: Some(first_response_ms),
+        ..Default::default()
+    };
+    let accounts = ["acc_a".to_string()];
+    assert!(capture_fan_out_child_log(
+        Some("trc_fan_child_0"),
+        Some("acc_a"),
+        Some("https://upstream/a"),
+        Some(&accounts),
+        usage(5, 40),
+    ));
+    assert!(capture_fan_out_child_log(
+        Some("trc_fan_child_1"),
+        Some("acc_b"),
+        None,
+        None,
+        usage(7, 25),
+    ));
+    assert!(!capture_fan_out_child_log(
+        Some("trc_fan_child_1"),
+        None,
+        None,
+        None,
+        usage(1, 1),
+    ));
+
+    let reports = collect_child_reports(&tokens, &[true, true]);
+    assert_eq!(reports.len(), 2);
+    let total = sum_request_log_usage(&reports);
+    assert_eq!(total.input_tokens, Some(12));
+    assert_eq!(total.output_tokens, Some(4));
+    assert_eq!(total.cached_input_tokens, None);
+    assert_eq!(total.first_response_ms, Some(25));
+
+    let (registry, _) = fan_out_registry();
+    let registry = crate::lock_utils::lock_recover(registry, "chat_fan_out_registry");
+    assert!(tokens
+        .iter()
+        .all(|token| !registry.reports.contains_key(token)));
+}
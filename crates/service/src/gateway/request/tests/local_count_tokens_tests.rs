@@ -1,6 +1,6 @@
 use super::*;
 
-/// 函数 `estimate_input_tokens_uses_messages_and_system_text`
+/// 函数 `count_input_tokens_uses_messages_and_system_text`
 ///
 /// 作者: gaohongshun
 ///
@@ -12,17 +12,18 @@ use super::*;
 /// # 返回
 /// 无
 #[test]
-fn estimate_input_tokens_uses_messages_and_system_text() {
+fn count_input_tokens_uses_messages_and_system_text() {
     let body = br#"{
         "model":"gpt-5.3-codex",
-        "system":"abcdabcd",
+        "system":"You are a helpful assistant.",
         "messages":[
-            {"role":"user","content":"abcd"},
-            {"role":"assistant","content":[{"type":"text","text":"abcdabcd"}]}
+            {"role":"user","content":"hello world"},
+            {"role":"assistant","content":[{"type":"text","text":"hello there"}]}
         ]
     }"#;
-    let count = estimate_input_tokens_from_anthropic_messages(body).expect("estimate failed");
-    assert_eq!(count, 5);
+    let count = count_input_tokens_from_anthropic_messages(TokenizerEncoding::O200kBase, body)
+        .expect("count failed");
+    assert_eq!(count, 24);
 }
 
 #[test]
@@ -33,7 +34,7 @@ fn anthropic_count_tokens_matches_original_path_after_protocol_adaptation() {
     assert!(!is_anthropic_count_tokens_request_path("/v1/responses"));
 }
 
-/// 函数 `estimate_input_tokens_rejects_invalid_json`
+/// 函数 `count_input_tokens_rejects_invalid_json`
 ///
 /// 作者: gaohongshun
 ///
@@ -45,13 +46,16 @@ fn anthropic_count_tokens_matches_original_path_after_protocol_adaptation() {
 /// # 返回
 /// 无
 #[test]
-fn estimate_input_tokens_rejects_invalid_json() {
-    let err = estimate_input_tokens_from_anthropic_messages(br#"{"messages":["#)
-        .expect_err("should reject invalid json");
+fn count_input_tokens_rejects_invalid_json() {
+    let err = count_input_tokens_from_anthropic_messages(
+        TokenizerEncoding::O200kBase,
+        br#"{"messages":["#,
+    )
+    .expect_err("should reject invalid json");
     assert_eq!(err, "Claude 请求 JSON 无效(invalid claude request json)");
 }
 
-/// 函数 `estimate_input_tokens_rejects_non_object_payload`
+/// 函数 `count_input_tokens_rejects_non_object_payload`
 ///
 /// 作者: gaohongshun
 ///
@@ -63,9 +67,10 @@ fn estimate_input_tokens_rejects_invalid_json() {
 /// # 返回
 /// 无
 #[test]
-fn estimate_input_tokens_rejects_non_object_payload() {
-    let err = estimate_input_tokens_from_anthropic_messages(br#"["bad"]"#)
-        .expect_err("should reject non-object payload");
+fn count_input_tokens_rejects_non_object_payload() {
+    let err =
+        count_input_tokens_from_anthropic_messages(TokenizerEncoding::O200kBase, br#"["bad"]"#)
+            .expect_err("should reject non-object payload");
     assert_eq!(
         err,
         "Claude 请求体必须是对象(claude request body must be an object)"
@@ -73,37 +78,40 @@ fn estimate_input_tokens_rejects_non_object_payload() {
 }
 
 #[test]
-fn estimate_gemini_input_tokens_uses_contents_and_system_instruction() {
+fn count_gemini_input_tokens_uses_contents_and_system_instruction() {
     let body = br#"{
-        "systemInstruction":{"parts":[{"text":"abcdabcd"}]},
+        "systemInstruction":{"parts":[{"text":"You are a helpful assistant."}]},
         "contents":[
-            {"role":"user","parts":[{"text":"abcd"}]},
-            {"role":"model","parts":[{"text":"abcdabcd"}]}
+            {"role":"user","parts":[{"text":"hello world"}]},
+            {"role":"model","parts":[{"text":"hello there"}]}
         ]
     }"#;
-    let count = estimate_input_tokens_from_gemini_request(body).expect("estimate failed");
-    assert_eq!(count, 5);
+    let count = count_input_tokens_from_gemini_request(TokenizerEncoding::O200kBase, body)
+        .expect("count failed");
+    assert_eq!(count, 10);
 }
 
 #[test]
-fn estimate_gemini_input_tokens_rejects_invalid_json() {
-    let err = estimate_input_tokens_from_gemini_request(br#"{"contents":["#)
-        .expect_err("should reject invalid json");
+fn count_gemini_input_tokens_rejects_invalid_json() {
+    let err =
+        count_input_tokens_from_gemini_request(TokenizerEncoding::O200kBase, br#"{"contents":["#)
+            .expect_err("should reject invalid json");
     assert_eq!(err, "Gemini 请求 JSON 无效(invalid gemini request json)");
 }
 
 #[test]
-fn estimate_gemini_cli_wrapped_input_tokens_uses_nested_request() {
+fn count_gemini_cli_wrapped_input_tokens_uses_nested_request() {
     let body = br#"{
         "model":"gemini-2.5-pro",
         "request":{
-            "system_instruction":{"parts":[{"text":"abcdabcd"}]},
+            "system_instruction":{"parts":[{"text":"You are a helpful assistant."}]},
             "contents":[
-                {"parts":[{"text":"abcd"}]},
-                {"parts":[{"text":"abcdabcd"}]}
+                {"parts":[{"text":"hello world"}]},
+                {"parts":[{"text":"hello there"}]}
             ]
         }
     }"#;
-    let count = estimate_input_tokens_from_gemini_request(body).expect("estimate failed");
-    assert_eq!(count, 5);
+    let count = count_input_tokens_from_gemini_request(TokenizerEncoding::O200kBase, body)
+        .expect("count failed");
+    assert_eq!(count, 10);
 }
@@ -0,0 +1,72 @@
+use super::*;
+
+fn key_for(key_id: &str, client_is_stream: bool, body: &[u8]) -> Option<String> {
+    compute_response_cache_key(
+        key_id,
+        "openai_compat",
+        "/v1/responses",
+        "/v1/responses",
+        client_is_stream,
+        Some("gpt-5"),
+        Some("medium"),
+        body,
+    )
+}
+
+#[test]
+fn response_cache_key_ignores_field_order_and_volatile_fields() {
+    let left = br#"{"model":"gpt-5","input":[{"role":"user","content":"hi"}],"temperature":0,"tools":[{"type":"function","name":"a","parameters":{"type":"object","properties":{}}}]}"#;
+    let right = br#"{"tools":[{"parameters":{"properties":{},"type":"object"},"name":"a","type":"function"}],"temperature":0,"user":"ci-42","metadata":{"run":"7"},"prompt_cache_key":"abc","input":[{"content":"hi","role":"user"}],"model":"gpt-5"}"#;
+
+    let left_key = key_for("key-a", false, left).expect("left cacheable");
+    let right_key = key_for("key-a", false, right).expect("right cacheable");
+    assert_eq!(left_key, right_key);
+    assert!(left_key.starts_with("rc_"));
+    assert_eq!(left_key.len(), 3 + 64);
+}
+
+#[test]
+fn response_cache_key_separates_inputs_sampling_keys_and_stream_mode() {
+    let base = br#"{"model":"gpt-5","input":"hi","temperature":0}"#;
+    let warmer = br#"{"model":"gpt-5","input":"hi","temperature":1}"#;
+    let other_input = br#"{"model":"gpt-5","input":"hello","temperature":0}"#;
+
+    let base_key = key_for("key-a", false, base).expect("base cacheable");
+    assert_ne!(base_key, key_for("key-a", false, warmer).expect("warmer"));
+    assert_ne!(
+        base_key,
+        key_for("key-a", false, other_input).expect("input")
+    );
+    assert_ne!(base_key, key_for("key-b", false, base).expect("other key"));
+    assert_ne!(base_key, key_for("key-a", true, base).expect("stream"));
+}
+
+#[test]
+fn response_cache_key_skips_stateful_and_non_json_requests() {
+    assert!(key_for(
+        "key-a",
+        false,
+        br#"{"model":"gpt-5","input":"hi","previous_response_id":"resp_1"}"#
+    )
+    .is_none());
+    assert!(key_for(
+        "key-a",
+        false,
+        br#"{"model":"gpt-5","input":"hi","conversation":"conv_1"}"#
+    )
+    .is_none());
+    assert!(key_for(
+        "key-a",
+        false,
+        br#"{"model":"gpt-5","input":"hi","background":true}"#
+    )
+    .is_none());
+    assert!(key_for("key-a", false, b"not json").is_none());
+    assert!(key_for("key-a", false, br#"["model"]"#).is_none());
+    assert!(key_for(
+        "key-a",
+        false,
+        br#"{"model":"gpt-5","input":"hi","background":false,"previous_response_id":null}"#
+    )
+    .is_some());
+}
@@ -0,0 +1,121 @@
+use super::*;
+use serde_json::json;
+
+fn open_test_storage() -> Storage {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    storage
+}
+
+#[test]
+fn response_store_turn_requires_post_to_responses_and_honors_store_flag() {
+    let body = br#"{"model":"gpt-5","input":"hi","previous_response_id":"resp_0"}"#;
+    let turn = resolve_response_store_turn("key-a", "/v1/responses", "/v1/responses", "POST", body)
+        .expect("turn");
+    assert_eq!(turn.previous_response_id.as_deref(), Some("resp_0"));
+    assert_eq!(turn.model.as_deref(), Some("gpt-5"));
+    assert_eq!(
+        turn.input,
+        vec![json!({
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": "hi" }]
+        })]
+    );
+
+    let unstored = br#"{"model":"gpt-5","input":"hi","store":false}"#;
+    assert!(resolve_response_store_turn(
+        "key-a",
+        "/v1/responses",
+        "/v1/responses",
+        "POST",
+        unstored
+    )
+    .is_none());
+    assert!(
+        resolve_response_store_turn("key-a", "/v1/responses", "/v1/responses", "GET", body)
+            .is_none()
+    );
+    assert!(resolve_response_store_turn(
+        "key-a",
+        "/v1/responses/compact",
+        "/v1/responses/compact",
+        "POST",
+        body
+    )
+    .is_none());
+    assert!(resolve_response_store_turn(
+        "key-a",
+        "/v1/chat/completions",
+        "/v1/responses",
+        "POST",
+        body
+    )
+    .is_none());
+}
+
+#[test]
+fn completed_response_is_parsed_from_sse_and_json_bodies() {
+    let sse = concat!(
+        "event: response.created\n",
+        "data: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_1\",\"status\":\"in_progress\"}}\n\n",
+        "event: response.completed\n",
+        "data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_1\",\"status\":\"completed\",\"output\":[]}}\n\n",
+    );
+    let parsed = completed_response_from_body(sse.as_bytes()).expect("sse response");
+    assert_eq!(parsed["id"], "resp_1");
+
+    let json_body = br#"{"id":"resp_2","object":"response","status":"completed","output":[]}"#;
+    let parsed = completed_response_from_body(json_body).expect("json response");
+    assert_eq!(parsed["id"], "resp_2");
+
+    let incomplete = br#"{"id":"resp_3","object":"response","status":"incomplete","output":[]}"#;
+    assert!(completed_response_from_body(incomplete).is_none());
+}
+
+#[test]
+fn stored_history_chain_expands_into_full_request_body() {
+    let storage = open_test_storage();
+    let first = ResponseStoreTurn::new("key-a", None, Some("gpt-5"), &json!("first"));
+    let first_output = json!({
+        "id": "resp_1",
+        "status": "completed",
+        "output": [{"type":"message","role":"assistant","content":[{"type":"output_text","text":"one"}]}]
+    });
+    assert!(record_completed_response(&storage, &first, Some("acc-1"), &first_output).unwrap());
+    let second = ResponseStoreTurn::new("key-a", Some("resp_1"), Some("gpt-5"), &json!("second"));
+    let second_output = json!({
+        "id": "resp_2",
+        "status": "completed",
+        "output": [{"type":"message","role":"assistant","content":[{"type":"output_text","text":"two"}]}]
+    });
+    assert!(record_completed_response(&storage, &second, Some("acc-2"), &second_output).unwrap());
+
+    let (history, owner) =
+        collect_stored_response_history(&storage, "key-a", "resp_2").expect("history");
+    assert_eq!(owner.as_deref(), Some("acc-2"));
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[0]["content"][0]["text"], "first");
+    assert_eq!(history[3]["content"][0]["text"], "two");
+    assert!(collect_stored_response_history(&storage, "key-b", "resp_2").is_err());
+
+    let upstream_body = br#"{"model":"gpt-5","input":"third","previous_response_id":"resp_2"}"#;
+    let expansion = resolve_previous_response_expansion(
+        &storage,
+        "trace-1",
+        "key-a",
+        "/v1/responses",
+        "/v1/responses",
+        upstream_body,
+        upstream_body,
+    )
+    .expect("expansion");
+    assert!(!expansion.should_expand_for("acc-2"));
+    assert!(expansion.should_expand_for("acc-1"));
+    let expanded: Value =
+        serde_json::from_slice(expansion.expanded_body()).expect("expanded body json");
+    assert!(expanded.get("previous_response_id").is_none());
+    let input = expanded["input"].as_array().expect("input array");
+    assert_eq!(input.len(), 5);
+    assert_eq!(input[4]["content"][0]["text"], "third");
+}
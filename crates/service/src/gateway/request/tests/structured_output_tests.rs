@@ -0,0 +1,259 @@
+use super::*;
+use crate::gateway::upstream::{GatewayByteStream, GatewayStreamResponse};
+use serde_json::json;
+
+fn person_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "age": { "type": "integer", "minimum": 0 },
+            "tags": { "type": "array", "items": { "$ref": "#/$defs/tag" }, "maxItems": 2 },
+            "role": { "anyOf": [{ "enum": ["admin", "member"] }, { "type": "null" }] }
+        },
+        "required": ["name", "age"],
+        "additionalProperties": false,
+        "$defs": { "tag": { "type": "string" } }
+    })
+}
+
+fn target(max_repairs: usize) -> StructuredOutputTarget {
+    StructuredOutputTarget {
+        schema: person_schema(),
+        max_repairs,
+    }
+}
+
+fn responses_body(text: &str) -> GatewayUpstreamResponse {
+    responses_body_with_usage(text, json!(null))
+}
+
+fn responses_body_with_usage(text: &str, usage: Value) -> GatewayUpstreamResponse {
+    let body = json!({
+        "id": "resp_1",
+        "object": "response",
+        "status": "completed",
+        "output": [{
+            "type": "message",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": text }]
+        }],
+        "usage": usage
+    });
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::HeaderValue::from_static("application/json"),
+    );
+    GatewayUpstreamResponse::Stream(GatewayStreamResponse::new(
+        reqwest::StatusCode::OK,
+        headers,
+        GatewayByteStream::from_bytes(Bytes::from(body.to_string())),
+    ))
+}
+
+#[test]
+fn requested_json_schema_reads_responses_and_chat_formats() {
+    let responses =
+        br#"{"text":{"format":{"type":"json_schema","name":"p","schema":{"type":"object"}}}}"#;
+    let chat = br#"{"response_format":{"type":"json_schema","json_schema":{"name":"p","schema":{"type":"object"}}}}"#;
+    let plain = br#"{"text":{"format":{"type":"json_object"}}}"#;
+
+    assert_eq!(
+        requested_json_schema(responses),
+        Some(json!({"type": "object"}))
+    );
+    assert_eq!(requested_json_schema(chat), Some(json!({"type": "object"})));
+    assert_eq!(requested_json_schema(plain), None);
+    assert_eq!(requested_json_schema(b"not json"), None);
+}
+
+#[test]
+fn model_enabled_by_env_matches_case_insensitively_and_wildcard() {
+    let models = vec!["gpt-5".to_string()];
+    assert!(model_enabled_by_env(&models, Some("GPT-5")));
+    assert!(!model_enabled_by_env(&models, Some("gpt-5-mini")));
+    assert!(!model_enabled_by_env(&models, None));
+    assert!(model_enabled_by_env(&["*".to_string()], None));
+}
+
+#[test]
+fn validate_output_text_accepts_conforming_output() {
+    let schema = person_schema();
+    assert!(validate_output_text(&schema, r#"{"name":"a","age":3}"#).is_empty());
+    assert!(validate_output_text(
+        &schema,
+        r#" {"name":"a","age":3.0,"tags":["x"],"role":null} "#
+    )
+    .is_empty());
+}
+
+#[test]
+fn validate_output_text_reports_paths_for_each_violation() {
+    let schema = person_schema();
+    let errors = validate_output_text(
+        &schema,
+        r#"{"name":"","age":-1,"tags":["x",2,"z"],"role":"owner","extra":true}"#,
+    );
+
+    assert!(errors.contains(&"$.name: expected at least 1 characters".to_string()));
+    assert!(errors.contains(&"$.age: must be >= 0".to_string()));
+    assert!(errors.contains(&"$.tags: expected at most 2 items".to_string()));
+    assert!(errors.contains(&"$.tags[1]: expected string, got integer".to_string()));
+    assert!(errors.contains(&"$.role: value does not match any allowed schema".to_string()));
+    assert!(errors.contains(&"$: unexpected property \"extra\"".to_string()));
+
+    let missing = validate_output_text(&schema, r#"{"age":"3"}"#);
+    assert_eq!(
+        missing,
+        vec![
+            "$: missing required property \"name\"".to_string(),
+            "$.age: expected integer, got string".to_string(),
+        ]
+    );
+    assert!(
+        validate_output_text(&schema, "```json\n{}```")[0].starts_with("output is not valid JSON")
+    );
+}
+
+#[test]
+fn build_repair_body_appends_invalid_output_and_errors() {
+    let body = br#"{"model":"gpt-5","input":"who?","text":{"format":{"type":"json_schema"}}}"#;
+    let repaired = build_repair_body(body, "{}", &["$: missing".to_string()]).expect("repair body");
+    let value: Value = serde_json::from_slice(&repaired).expect("json");
+    let input = value["input"].as_array().expect("input array");
+
+    assert_eq!(input.len(), 3);
+    assert_eq!(input[0]["content"][0]["text"], "who?");
+    assert_eq!(input[1]["role"], "assistant");
+    assert_eq!(input[1]["content"][0]["text"], "{}");
+    assert_eq!(input[2]["role"], "user");
+    assert!(input[2]["content"][0]["text"]
+        .as_str()
+        .is_some_and(|text| text.contains("- $: missing")));
+    assert_eq!(value["text"]["format"]["type"], "json_schema");
+
+    let chat = br#"{"messages":[{"role":"user","content":"who?"}]}"#;
+    let repaired = build_repair_body(chat, "{}", &[]).expect("chat repair body");
+    let value: Value = serde_json::from_slice(&repaired).expect("json");
+    assert_eq!(value["messages"].as_array().map(Vec::len), Some(3));
+}
+
+#[test]
+fn enforce_structured_output_repairs_until_valid() {
+    let body = Bytes::from_static(br#"{"model":"gpt-5","input":"who?"}"#);
+    let mut sent = Vec::new();
+    let (response, outcome) = enforce_structured_output(
+        &target(2),
+        "trc_1",
+        &body,
+        responses_body(r#"{"name":"a"}"#),
+        |repair_body| {
+            sent.push(repair_body.clone());
+            Some(responses_body(r#"{"name":"a","age":1}"#))
+        },
+    )
+    .expect("enforce");
+
+    assert_eq!(outcome, StructuredOutputOutcome::Repaired);
+    assert_eq!(sent.len(), 1);
+    let (bytes, _) = response.into_buffered().expect("buffer");
+    assert!(String::from_utf8_lossy(&bytes).contains(r#"\"age\":1"#));
+}
+
+#[test]
+fn enforce_structured_output_stops_after_max_repairs() {
+    let body = Bytes::from_static(br#"{"model":"gpt-5","input":[]}"#);
+    let mut calls = 0;
+    let (_, outcome) = enforce_structured_output(
+        &target(2),
+        "trc_2",
+        &body,
+        responses_body("not json"),
+        |repair_body| {
+            calls += 1;
+            let value: Value = serde_json::from_slice(repair_body).expect("json");
+            assert_eq!(value["input"].as_array().map(Vec::len), Some(calls * 2));
+            Some(responses_body("still not json"))
+        },
+    )
+    .expect("enforce");
+
+    assert_eq!(outcome, StructuredOutputOutcome::Invalid);
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn enforce_structured_output_validates_only_when_repairs_disabled() {
+    let body = Bytes::from_static(br#"{"input":"x"}"#);
+    let (_, outcome) =
+        enforce_structured_output(&target(0), "trc_3", &body, responses_body("{}"), |_| {
+            panic!("repair must not be sent")
+        })
+        .expect("enforce");
+    assert_eq!(outcome, StructuredOutputOutcome::Invalid);
+
+    let (_, outcome) = enforce_structured_output(
+        &target(1),
+        "trc_4",
+        &body,
+        responses_body(r#"{"name":"a","age":2}"#),
+        |_| panic!("repair must not be sent"),
+    )
+    .expect("enforce");
+    assert_eq!(outcome, StructuredOutputOutcome::Valid);
+}
+
+#[test]
+fn enforce_structured_output_records_outcome_and_replaced_response_usage() {
+    let body = Bytes::from_static(br#"{"model":"gpt-5","input":"who?"}"#);
+    let (_, outcome) = enforce_structured_output(
+        &target(2),
+        "trc_structured_report",
+        &body,
+        responses_body_with_usage(
+            r#"{"name":"a"}"#,
+            json!({ "input_tokens": 30, "output_tokens": 10, "total_tokens": 40 }),
+        ),
+        |_| {
+            Some(responses_body_with_usage(
+                r#"{"name":"a","age":1}"#,
+                json!({ "input_tokens": 50, "output_tokens": 8, "total_tokens": 58 }),
+            ))
+        },
+    )
+    .expect("enforce");
+    assert_eq!(outcome, StructuredOutputOutcome::Repaired);
+
+    let report = take_structured_output_report("trc_structured_report").expect("report");
+    assert_eq!(report.outcome, StructuredOutputOutcome::Repaired);
+    assert_eq!(report.repairs, 1);
+    assert!(report.errors.iter().any(|error| error.contains("\"age\"")));
+    assert_eq!(report.repair_usage.input_tokens, Some(30));
+    assert_eq!(report.repair_usage.output_tokens, Some(10));
+    assert_eq!(report.repair_usage.total_tokens, Some(40));
+    assert!(take_structured_output_report("trc_structured_report").is_none());
+
+    // 中文注释：交付的修复响应用量加上被替换响应的用量，才是这次请求的真实消耗。
+    let mut usage = RequestLogUsage {
+        input_tokens: Some(50),
+        output_tokens: Some(8),
+        total_tokens: Some(58),
+        first_response_ms: Some(120),
+        ..RequestLogUsage::default()
+    };
+    merge_repair_usage(&mut usage, &report.repair_usage);
+    assert_eq!(usage.input_tokens, Some(80));
+    assert_eq!(usage.total_tokens, Some(98));
+    assert_eq!(usage.cached_input_tokens, None);
+    assert_eq!(usage.first_response_ms, Some(120));
+}
+
+#[test]
+fn aggregate_route_records_skipped_outcome() {
+    record_aggregate_route_skipped("trc_structured_aggregate");
+    let report = take_structured_output_report("trc_structured_aggregate").expect("report");
+    assert_eq!(report.outcome, StructuredOutputOutcome::Skipped);
+    assert_eq!(report.skip_reason, Some(SKIP_REASON_AGGREGATE_API));
+    assert_eq!(report.repairs, 0);
+}
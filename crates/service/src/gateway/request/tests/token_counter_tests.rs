@@ -0,0 +1,196 @@
+use super::*;
+use serde_json::json;
+
+fn tokens(encoding: TokenizerEncoding, text: &str) -> u64 {
+    encoding.bpe().encode_ordinary(text).len() as u64
+}
+
+#[test]
+fn cjk_and_code_are_counted_by_bpe_instead_of_characters() {
+    let cjk = "请帮我把这段日志按时间排序，并找出所有超时的请求。".repeat(20);
+    let code =
+        "fn main() { let v: Vec<u8> = (0..=255).collect(); println!(\"{:?}\", v); }\n".repeat(10);
+    let encoding = TokenizerEncoding::O200kBase;
+
+    let cjk_count = count_request_input_tokens(encoding, &json!({ "instructions": cjk }));
+    let code_count = count_request_input_tokens(encoding, &json!({ "instructions": code }));
+
+    assert_eq!(cjk_count, tokens(encoding, cjk.as_str()));
+    assert_eq!(code_count, tokens(encoding, code.as_str()));
+    // 中文注释：旧实现按 4 字符 1 token 估算，CJK 会被严重低估。
+    assert!(
+        cjk_count > (cjk.chars().count() as u64) / 4 * 2,
+        "{cjk_count}"
+    );
+}
+
+#[test]
+fn tokenizer_encoding_follows_model_family_and_catalog_capability() {
+    assert_eq!(
+        infer_tokenizer_encoding("gpt-5.4"),
+        TokenizerEncoding::O200kBase
+    );
+    assert_eq!(
+        infer_tokenizer_encoding("gpt-4o-mini"),
+        TokenizerEncoding::O200kBase
+    );
+    assert_eq!(
+        infer_tokenizer_encoding("gpt-4.1"),
+        TokenizerEncoding::O200kBase
+    );
+    assert_eq!(
+        infer_tokenizer_encoding("GPT-4-turbo"),
+        TokenizerEncoding::Cl100kBase
+    );
+    assert_eq!(
+        infer_tokenizer_encoding("gpt-3.5-turbo"),
+        TokenizerEncoding::Cl100kBase
+    );
+    assert_eq!(
+        infer_tokenizer_encoding("claude-sonnet-4-5"),
+        TokenizerEncoding::O200kBase
+    );
+
+    let mut model = ManagedModelV2 {
+        slug: "gpt-5.4".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        tokenizer_encoding_for_model(&model),
+        TokenizerEncoding::O200kBase
+    );
+    model.capabilities = json!({ "tokenizer": "cl100k_base" });
+    assert_eq!(
+        tokenizer_encoding_for_model(&model),
+        TokenizerEncoding::Cl100kBase
+    );
+    model.capabilities = json!({ "tokenizerEncoding": "unknown" });
+    assert_eq!(
+        tokenizer_encoding_for_model(&model),
+        TokenizerEncoding::O200kBase
+    );
+}
+
+#[test]
+fn responses_body_counts_instructions_input_items_and_tool_schemas() {
+    let encoding = TokenizerEncoding::O200kBase;
+    let tool = json!({
+        "type": "function",
+        "name": "read_file",
+        "parameters": {"type": "object", "properties": {"path": {"type": "string"}}}
+    });
+    let body = json!({
+        "model": "gpt-5.4",
+        "instructions": "Be terse.",
+        "input": [
+            {"role": "user", "content": [{"type": "input_text", "text": "open main.rs"}]},
+            {"type": "function_call", "name": "read_file", "arguments": "{\"path\":\"main.rs\"}"},
+            {"type": "function_call_output", "output": "fn main() {}"}
+        ],
+        "tools": [tool.clone()]
+    });
+
+    let expected = tokens(encoding, "Be terse.")
+        + TOKENS_PER_MESSAGE
+        + tokens(encoding, "user")
+        + tokens(encoding, "open main.rs")
+        + tokens(encoding, "read_file")
+        + tokens(encoding, "{\"path\":\"main.rs\"}")
+        + tokens(encoding, "fn main() {}")
+        + tokens(encoding, tool.to_string().as_str())
+        + TOKENS_PER_REPLY_PRIMING;
+    assert_eq!(count_request_input_tokens(encoding, &body), expected);
+}
+
+#[test]
+fn chat_body_counts_tool_calls_and_images_by_detail() {
+    let encoding = TokenizerEncoding::Cl100kBase;
+    let body = json!({
+        "model": "gpt-4-turbo",
+        "messages": [
+            {"role": "user", "content": [
+                {"type": "text", "text": "what is this?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA", "detail": "low"}},
+                {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+            ]},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_1", "type": "function", "function": {"name": "lookup", "arguments": "{\"q\":\"cat\"}"}}
+            ]}
+        ]
+    });
+
+    let expected = TOKENS_PER_MESSAGE
+        + tokens(encoding, "user")
+        + tokens(encoding, "what is this?")
+        + IMAGE_TOKENS_LOW_DETAIL
+        + IMAGE_TOKENS_DEFAULT
+        + TOKENS_PER_MESSAGE
+        + tokens(encoding, "assistant")
+        + tokens(encoding, "lookup")
+        + tokens(encoding, "{\"q\":\"cat\"}")
+        + TOKENS_PER_REPLY_PRIMING;
+    assert_eq!(count_request_input_tokens(encoding, &body), expected);
+}
+
+#[test]
+fn anthropic_body_counts_tool_use_tool_results_and_skips_signatures() {
+    let encoding = TokenizerEncoding::O200kBase;
+    let body = json!({
+        "system": [{"type": "text", "text": "You are a coding agent."}],
+        "messages": [
+            {"role": "assistant", "content": [
+                {"type": "thinking", "thinking": "check the file", "signature": "c2lnbmF0dXJl"},
+                {"type": "tool_use", "id": "toolu_1", "name": "read", "input": {"path": "a.rs"}}
+            ]},
+            {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "toolu_1", "content": [{"type": "text", "text": "mod a;"}]},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}}
+            ]}
+        ],
+        "tools": [{"name": "read", "input_schema": {"type": "object"}}]
+    });
+
+    let expected = TOKENS_PER_MESSAGE
+        + tokens(encoding, "You are a coding agent.")
+        + TOKENS_PER_MESSAGE
+        + tokens(encoding, "assistant")
+        + tokens(encoding, "check the file")
+        + tokens(encoding, "read")
+        + tokens(encoding, "{\"path\":\"a.rs\"}")
+        + TOKENS_PER_MESSAGE
+        + tokens(encoding, "user")
+        + tokens(encoding, "mod a;")
+        + IMAGE_TOKENS_DEFAULT
+        + tokens(
+            encoding,
+            "{\"input_schema\":{\"type\":\"object\"},\"name\":\"read\"}",
+        )
+        + TOKENS_PER_REPLY_PRIMING;
+    assert_eq!(count_request_input_tokens(encoding, &body), expected);
+}
+
+#[test]
+fn gemini_body_counts_parts_inline_data_and_function_calls() {
+    let encoding = TokenizerEncoding::O200kBase;
+    let body = json!({
+        "request": {
+            "systemInstruction": {"parts": [{"text": "Answer in Chinese."}]},
+            "contents": [
+                {"role": "user", "parts": [
+                    {"text": "describe"},
+                    {"inlineData": {"mimeType": "image/png", "data": "AAAA"}}
+                ]},
+                {"role": "model", "parts": [
+                    {"functionCall": {"name": "search", "args": {"q": "cat"}}}
+                ]}
+            ]
+        }
+    });
+
+    let expected = tokens(encoding, "Answer in Chinese.")
+        + tokens(encoding, "describe")
+        + GEMINI_IMAGE_TOKENS
+        + tokens(encoding, "search")
+        + tokens(encoding, "{\"q\":\"cat\"}");
+    assert_eq!(count_request_input_tokens(encoding, &body), expected);
+}
@@ -0,0 +1,335 @@
+use codexmanager_core::storage::{ManagedModelV2, Storage};
+use serde_json::{Map, Value};
+use tiktoken_rs::CoreBPE;
+
+const TOKENIZER_CAPABILITY_KEYS: &[&str] =
+    &["tokenizer", "tokenizerEncoding", "tokenizer_encoding"];
+// 中文注释：对话格式的固定开销沿用 OpenAI cookbook 的计法：每条消息 3 个 token，回复引导再加 3 个。
+const TOKENS_PER_MESSAGE: u64 = 3;
+const TOKENS_PER_REPLY_PRIMING: u64 = 3;
+// 中文注释：本地拿不到图片尺寸，low detail 按固定 85 计，其余按 1024x1024 high detail（85 + 4 * 170）计；
+// Gemini 官方按每张图片 258 token 计费。
+const IMAGE_TOKENS_LOW_DETAIL: u64 = 85;
+const IMAGE_TOKENS_DEFAULT: u64 = 765;
+const GEMINI_IMAGE_TOKENS: u64 = 258;
+
+/// 本地计数使用的 BPE 编码族。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenizerEncoding {
+    O200kBase,
+    Cl100kBase,
+}
+
+impl TokenizerEncoding {
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "o200k_base" | "o200k" => Some(Self::O200kBase),
+            "cl100k_base" | "cl100k" => Some(Self::Cl100kBase),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::O200kBase => "o200k_base",
+            Self::Cl100kBase => "cl100k_base",
+        }
+    }
+
+    fn bpe(self) -> &'static CoreBPE {
+        match self {
+            Self::O200kBase => tiktoken_rs::o200k_base_singleton(),
+            Self::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+        }
+    }
+}
+
+/// 按模型名推断编码族：GPT-4 / GPT-3.5 代用 cl100k，其余（含非 OpenAI 模型的近似）用 o200k。
+pub(crate) fn infer_tokenizer_encoding(model: &str) -> TokenizerEncoding {
+    let normalized = model.trim().to_ascii_lowercase();
+    let legacy = (normalized.starts_with("gpt-4") && !is_o200k_gpt4_variant(normalized.as_str()))
+        || normalized.starts_with("gpt-3.5")
+        || normalized.starts_with("text-embedding");
+    if legacy {
+        TokenizerEncoding::Cl100kBase
+    } else {
+        TokenizerEncoding::O200kBase
+    }
+}
+
+fn is_o200k_gpt4_variant(model: &str) -> bool {
+    ["gpt-4o", "gpt-4.1", "gpt-4.5"]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+/// 模型目录 `capabilities.tokenizer` 优先，缺省时按模型名推断。
+pub(crate) fn tokenizer_encoding_for_model(model: &ManagedModelV2) -> TokenizerEncoding {
+    TOKENIZER_CAPABILITY_KEYS
+        .iter()
+        .find_map(|key| model.capabilities.get(*key))
+        .and_then(Value::as_str)
+        .and_then(TokenizerEncoding::parse)
+        .unwrap_or_else(|| infer_tokenizer_encoding(model.slug.as_str()))
+}
+
+pub(crate) fn resolve_tokenizer_encoding(
+    storage: &Storage,
+    model: Option<&str>,
+) -> TokenizerEncoding {
+    let Some(model) = model.map(str::trim).filter(|value| !value.is_empty()) else {
+        return TokenizerEncoding::O200kBase;
+    };
+    match storage.get_managed_model_v2(model) {
+        Ok(Some(item)) => tokenizer_encoding_for_model(&item),
+        Ok(None) => infer_tokenizer_encoding(model),
+        Err(err) => {
+            log::warn!(
+                "event=tokenizer_model_lookup_failed model={} err={}",
+                model,
+                err
+            );
+            infer_tokenizer_encoding(model)
+        }
+    }
+}
+
+/// 统计请求体的输入 token；同时识别 Responses / Chat / Messages / Gemini 四种请求形态。
+pub(crate) fn count_request_input_tokens(encoding: TokenizerEncoding, payload: &Value) -> u64 {
+    let counter = TokenCounter {
+        bpe: encoding.bpe(),
+    };
+    let Some(root) = payload.as_object() else {
+        return counter.content(payload);
+    };
+    let object = root
+        .get("request")
+        .and_then(Value::as_object)
+        .filter(|inner| inner.contains_key("contents"))
+        .unwrap_or(root);
+    if object.contains_key("contents") {
+        counter.gemini_request(object)
+    } else {
+        counter.conversation_request(object)
+    }
+}
+
+struct TokenCounter {
+    bpe: &'static CoreBPE,
+}
+
+impl TokenCounter {
+    fn text(&self, text: &str) -> u64 {
+        if text.is_empty() {
+            return 0;
+        }
+        self.bpe.encode_ordinary(text).len() as u64
+    }
+
+    fn json(&self, value: &Value) -> u64 {
+        match value {
+            Value::Null => 0,
+            Value::String(text) => self.text(text),
+            other => self.text(other.to_string().as_str()),
+        }
+    }
+
+    fn conversation_request(&self, object: &Map<String, Value>) -> u64 {
+        let mut total = 0;
+        if let Some(instructions) = object.get("instructions") {
+            total += self.content(instructions);
+        }
+        if let Some(system) = object.get("system") {
+            total += TOKENS_PER_MESSAGE + self.content(system);
+        }
+        let mut has_messages = false;
+        match object.get("input") {
+            Some(Value::String(text)) => {
+                has_messages = true;
+                total += TOKENS_PER_MESSAGE + self.text(text);
+            }
+            Some(Value::Array(items)) => {
+                has_messages |= !items.is_empty();
+                total += items
+                    .iter()
+                    .map(|item| self.conversation_item(item))
+                    .sum::<u64>();
+            }
+            _ => {}
+        }
+        if let Some(messages) = object.get("messages").and_then(Value::as_array) {
+            has_messages |= !messages.is_empty();
+            total += messages
+                .iter()
+                .map(|item| self.conversation_item(item))
+                .sum::<u64>();
+        }
+        if let Some(tools) = object.get("tools").and_then(Value::as_array) {
+            total += tools.iter().map(|tool| self.json(tool)).sum::<u64>();
+        }
+        if has_messages {
+            total += TOKENS_PER_REPLY_PRIMING;
+        }
+        total
+    }
+
+    /// Responses `input[]` 条目与 Chat / Messages 的 `messages[]` 条目。
+    fn conversation_item(&self, item: &Value) -> u64 {
+        let Some(map) = item.as_object() else {
+            return self.content(item);
+        };
+        match map.get("type").and_then(Value::as_str) {
+            Some("function_call") | Some("custom_tool_call") => {
+                return self.tool_call(map);
+            }
+            Some("function_call_output") | Some("custom_tool_call_output") => {
+                return map.get("output").map_or(0, |output| self.content(output));
+            }
+            Some("reasoning") => {
+                return map
+                    .get("summary")
+                    .map_or(0, |summary| self.content(summary));
+            }
+            _ => {}
+        }
+        let mut total = TOKENS_PER_MESSAGE;
+        if let Some(role) = map.get("role").and_then(Value::as_str) {
+            total += self.text(role);
+        }
+        if let Some(name) = map.get("name").and_then(Value::as_str) {
+            total += self.text(name);
+        }
+        if let Some(content) = map.get("content") {
+            total += self.content(content);
+        }
+        if let Some(tool_calls) = map.get("tool_calls").and_then(Value::as_array) {
+            total += tool_calls
+                .iter()
+                .filter_map(Value::as_object)
+                .map(|call| {
+                    call.get("function")
+                        .and_then(Value::as_object)
+                        .map_or_else(|| self.tool_call(call), |function| self.tool_call(function))
+                })
+                .sum::<u64>();
+        }
+        total
+    }
+
+    fn tool_call(&self, map: &Map<String, Value>) -> u64 {
+        let name = map.get("name").and_then(Value::as_str).unwrap_or_default();
+        let arguments = map
+            .get("arguments")
+            .or_else(|| map.get("input"))
+            .or_else(|| map.get("args"))
+            .map_or(0, |arguments| self.json(arguments));
+        self.text(name) + arguments
+    }
+
+    /// 内容块：文本、图片、工具调用与工具结果；base64 数据与签名等元数据不计入。
+    fn content(&self, value: &Value) -> u64 {
+        match value {
+            Value::String(text) => self.text(text),
+            Value::Array(items) => items.iter().map(|item| self.content(item)).sum(),
+            Value::Object(map) => self.content_block(map),
+            _ => 0,
+        }
+    }
+
+    fn content_block(&self, map: &Map<String, Value>) -> u64 {
+        let block_type = map.get("type").and_then(Value::as_str).unwrap_or_default();
+        match block_type {
+            "input_image" | "image" | "image_url" => return image_tokens(map),
+            "tool_use" | "server_tool_use" | "function_call" => return self.tool_call(map),
+            "thinking" => {
+                return map
+                    .get("thinking")
+                    .and_then(Value::as_str)
+                    .map_or(0, |text| self.text(text));
+            }
+            "redacted_thinking" => return 0,
+            _ => {}
+        }
+        if let Some(text) = map.get("text").and_then(Value::as_str) {
+            return self.text(text);
+        }
+        if map.contains_key("image_url") {
+            return image_tokens(map);
+        }
+        map.get("content")
+            .or_else(|| map.get("output"))
+            .map_or(0, |content| self.content(content))
+    }
+
+    fn gemini_request(&self, object: &Map<String, Value>) -> u64 {
+        let mut total = 0;
+        if let Some(system) = object
+            .get("systemInstruction")
+            .or_else(|| object.get("system_instruction"))
+        {
+            total += self.gemini_parts(system.get("parts").unwrap_or(system));
+        }
+        if let Some(contents) = object.get("contents").and_then(Value::as_array) {
+            total += contents
+                .iter()
+                .map(|content| {
+                    content
+                        .get("parts")
+                        .map_or(0, |parts| self.gemini_parts(parts))
+                })
+                .sum::<u64>();
+        }
+        if let Some(tools) = object.get("tools").and_then(Value::as_array) {
+            total += tools.iter().map(|tool| self.json(tool)).sum::<u64>();
+        }
+        total
+    }
+
+    fn gemini_parts(&self, parts: &Value) -> u64 {
+        let Some(parts) = parts.as_array() else {
+            return self.content(parts);
+        };
+        parts
+            .iter()
+            .filter_map(Value::as_object)
+            .map(|part| {
+                if let Some(text) = part.get("text").and_then(Value::as_str) {
+                    return self.text(text);
+                }
+                if ["inlineData", "inline_data", "fileData", "file_data"]
+                    .iter()
+                    .any(|key| part.contains_key(*key))
+                {
+                    return GEMINI_IMAGE_TOKENS;
+                }
+                if let Some(call) = part
+                    .get("functionCall")
+                    .or_else(|| part.get("function_call"))
+                    .and_then(Value::as_object)
+                {
+                    return self.tool_call(call);
+                }
+                part.get("functionResponse")
+                    .or_else(|| part.get("function_response"))
+                    .map_or(0, |response| self.json(response))
+            })
+            .sum()
+    }
+}
+
+fn image_tokens(map: &Map<String, Value>) -> u64 {
+    let detail = map.get("detail").or_else(|| {
+        map.get("image_url")
+            .and_then(Value::as_object)
+            .and_then(|image_url| image_url.get("detail"))
+    });
+    if detail.and_then(Value::as_str) == Some("low") {
+        IMAGE_TOKENS_LOW_DETAIL
+    } else {
+        IMAGE_TOKENS_DEFAULT
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/token_counter_tests.rs"]
+mod tests;
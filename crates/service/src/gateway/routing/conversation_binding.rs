@@ -383,7 +383,7 @@ pub(crate) fn apply_candidate_rotation(
         };
     }
     let manual_preferred_account_id = super::manual_preferred_account();
-    super::apply_route_strategy(candidates, key_id, model_for_log);
+    let application = super::apply_route_strategy_with_source(candidates, key_id, model_for_log);
     if manual_preferred_account_id
         .as_deref()
         .is_some_and(|account_id| {
@@ -409,7 +409,7 @@ pub(crate) fn apply_candidate_rotation(
     }
     CandidateRotationPlan {
         source: CandidateRotationSource::RouteStrategy,
-        strategy_label: super::current_route_strategy(),
+        strategy_label: application.strategy_label,
         strategy_applied: true,
     }
 }
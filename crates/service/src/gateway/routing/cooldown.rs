@@ -38,6 +38,20 @@ pub(super) enum CooldownReason {
     AnthropicChallenge,
 }
 
+impl CooldownReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Network => "network",
+            Self::RateLimited => "rate_limited",
+            Self::Upstream5xx => "upstream_5xx",
+            Self::Upstream4xx => "upstream_4xx",
+            Self::Challenge => "challenge",
+            Self::AnthropicChallenge => "anthropic_challenge",
+        }
+    }
+}
+
 /// 函数 `cooldown_secs_for_reason`
 ///
 /// 作者: gaohongshun
@@ -187,13 +201,38 @@ pub(super) fn is_account_in_cooldown(account_id: &str) -> bool {
     let lock = ACCOUNT_COOLDOWN_UNTIL.get_or_init(|| Mutex::new(AccountCooldownState::default()));
     let mut state = crate::lock_utils::lock_recover(lock, "account_cooldown_until");
     let now = now_ts();
-    match state.entries.get(account_id).copied() {
+    let locally_cooling = match state.entries.get(account_id).copied() {
         Some(until) if until > now => true,
         Some(_) => {
             state.entries.remove(account_id);
             false
         }
         None => false,
+    };
+    drop(state);
+    locally_cooling || is_account_in_shared_cooldown(account_id, now)
+}
+
+// 中文注释：共享后端只同步冷却截止时间；429 阶梯计数仍按副本各自累计，避免每次失败都多一次往返。
+fn is_account_in_shared_cooldown(account_id: &str, now: i64) -> bool {
+    let Some(backend) = super::runtime_state::shared_runtime_state_backend() else {
+        return false;
+    };
+    match backend.deadline(
+        super::runtime_state::SCOPE_ACCOUNT_COOLDOWN,
+        account_id,
+        now,
+    ) {
+        Ok(until) => until.is_some(),
+        Err(err) => {
+            log::warn!(
+                "event=account_cooldown_shared_read_failed backend={} account_id={} err={}",
+                backend.name(),
+                account_id,
+                err
+            );
+            false
+        }
     }
 }
 
@@ -209,6 +248,8 @@ pub(super) fn is_account_in_cooldown(account_id: &str) -> bool {
 /// # 返回
 /// 无
 pub(super) fn mark_account_cooldown(account_id: &str, reason: CooldownReason) {
+    // 中文注释：先于冷却锁读取分组策略，避免持锁期间触发策略缓存的存储加载。
+    let multiplier_millis = super::group_policy::cooldown_multiplier_millis_for_account(account_id);
     let lock = ACCOUNT_COOLDOWN_UNTIL.get_or_init(|| Mutex::new(AccountCooldownState::default()));
     let mut guard = crate::lock_utils::lock_recover(lock, "account_cooldown_until");
     let state = &mut *guard;
@@ -216,12 +257,15 @@ pub(super) fn mark_account_cooldown(account_id: &str, reason: CooldownReason) {
     let now = now_ts();
     maybe_cleanup_expired_cooldowns(state, now);
     let cooldown_until = now
-        + cooldown_secs_for_mark(
-            &mut state.offense_counts,
-            &mut state.offense_last_at,
-            account_id,
-            reason,
-            now,
+        + super::group_policy::scale_cooldown_secs(
+            cooldown_secs_for_mark(
+                &mut state.offense_counts,
+                &mut state.offense_last_at,
+                account_id,
+                reason,
+                now,
+            ),
+            multiplier_millis,
         );
     // 中文注释：同账号短时间内可能触发不同失败类型；保留更晚的 until 可避免被较短冷却覆盖。
     match state.entries.get_mut(account_id) {
@@ -234,6 +278,35 @@ pub(super) fn mark_account_cooldown(account_id: &str, reason: CooldownReason) {
             state.entries.insert(account_id.to_string(), cooldown_until);
         }
     }
+    drop(guard);
+    crate::event_bus::publish_event(
+        crate::event_bus::TOPIC_ACCOUNT_COOLDOWN,
+        crate::event_bus::EventAudience::Admin,
+        serde_json::json!({
+            "accountId": account_id,
+            "state": "started",
+            "reason": reason.as_str(),
+            "until": cooldown_until,
+        }),
+    );
+    // 中文注释：传输层失败计入代理池成员健康度，连续失败时账号会切到池内其他出口。
+    if reason == CooldownReason::Network {
+        crate::proxy_pools::report_account_proxy_transport_failure(account_id);
+    }
+    if let Some(backend) = super::runtime_state::shared_runtime_state_backend() {
+        if let Err(err) = backend.extend_deadline(
+            super::runtime_state::SCOPE_ACCOUNT_COOLDOWN,
+            account_id,
+            cooldown_until,
+        ) {
+            log::warn!(
+                "event=account_cooldown_shared_mark_failed backend={} account_id={} err={}",
+                backend.name(),
+                account_id,
+                err
+            );
+        }
+    }
 }
 
 /// 函数 `mark_account_cooldown_for_status`
@@ -266,12 +339,36 @@ pub(super) fn clear_account_cooldown(account_id: &str) {
     let lock = ACCOUNT_COOLDOWN_UNTIL.get_or_init(|| Mutex::new(AccountCooldownState::default()));
     let mut guard = crate::lock_utils::lock_recover(lock, "account_cooldown_until");
     let state = &mut *guard;
-    state.entries.remove(account_id);
+    let was_cooling_down = state.entries.remove(account_id).is_some();
     decay_offense_count_for_success(
         &mut state.offense_counts,
         &mut state.offense_last_at,
         account_id,
     );
+    drop(guard);
+    // 中文注释：成功请求每次都会走到这里，只在确实解除冷却时推送事件。
+    if was_cooling_down {
+        crate::event_bus::publish_event(
+            crate::event_bus::TOPIC_ACCOUNT_COOLDOWN,
+            crate::event_bus::EventAudience::Admin,
+            serde_json::json!({
+                "accountId": account_id,
+                "state": "cleared",
+            }),
+        );
+    }
+    if let Some(backend) = super::runtime_state::shared_runtime_state_backend() {
+        if let Err(err) =
+            backend.clear_deadline(super::runtime_state::SCOPE_ACCOUNT_COOLDOWN, account_id)
+        {
+            log::warn!(
+                "event=account_cooldown_shared_clear_failed backend={} account_id={} err={}",
+                backend.name(),
+                account_id,
+                err
+            );
+        }
+    }
 }
 
 /// 函数 `maybe_cleanup_expired_cooldowns`
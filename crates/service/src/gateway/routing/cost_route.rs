@@ -0,0 +1,355 @@
+use super::route_hint::ROUTE_STRATEGY_CHEAPEST;
+use crate::quota::model_pricing::{
+    load_catalog_prices, resolve_model_price_from_catalog, wildcard_matches, CatalogModelPrice,
+};
+use codexmanager_core::storage::{AggregateApi, ModelPriceRule, RecentTokenMix, Storage};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+// 中文注释：缓存命中率与输出长度只看最近一段请求，避免很久以前的用量拖偏估算。
+const TOKEN_MIX_SAMPLE_LIMIT: i64 = 50;
+const DEFAULT_EXPECTED_OUTPUT_TOKENS: i64 = 1024;
+const PRICE_SOURCE_SUPPLIER_RULE: &str = "supplier_rule";
+const PRICE_SOURCE_CATALOG: &str = "catalog";
+const PRICE_SOURCE_MISSING: &str = "missing";
+
+/// 成本估算所需的请求侧输入。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CostRouteInputs {
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_ratio: f64,
+    pub(crate) expected_output_tokens: i64,
+    pub(crate) token_mix_samples: i64,
+}
+
+impl CostRouteInputs {
+    /// 由请求体估算输入长度，再用最近用量推导缓存命中率与期望输出长度。
+    pub(crate) fn from_request(body: &[u8], mix: RecentTokenMix) -> Self {
+        let cached_input_ratio = if mix.input_tokens > 0 {
+            (mix.cached_input_tokens.max(0) as f64 / mix.input_tokens as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let observed_output = (mix.sample_count > 0)
+            .then(|| mix.output_tokens.max(0) / mix.sample_count)
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_EXPECTED_OUTPUT_TOKENS);
+        let expected_output_tokens = match request_max_output_tokens(body) {
+            Some(limit) => observed_output.min(limit),
+            None => observed_output,
+        };
+        Self {
+            input_tokens: super::request_log::estimate_input_tokens_from_body(body),
+            cached_input_ratio,
+            expected_output_tokens,
+            token_mix_samples: mix.sample_count,
+        }
+    }
+
+    fn cached_input_tokens(&self) -> i64 {
+        (self.input_tokens as f64 * self.cached_input_ratio).round() as i64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SupplierPrice {
+    source: &'static str,
+    input_price_per_1m: f64,
+    cached_input_price_per_1m: f64,
+    output_price_per_1m: f64,
+}
+
+/// 单个聚合 API 候选的价格输入与估算结果，原样写入请求日志的选路决策。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CostRouteCandidate {
+    pub(crate) aggregate_api_id: String,
+    pub(crate) supplier_name: Option<String>,
+    pub(crate) upstream_model: Option<String>,
+    pub(crate) price_source: &'static str,
+    pub(crate) input_price_per_1m: Option<f64>,
+    pub(crate) cached_input_price_per_1m: Option<f64>,
+    pub(crate) output_price_per_1m: Option<f64>,
+    pub(crate) estimated_cost_usd: Option<f64>,
+    pub(crate) balance_remaining_usd: Option<f64>,
+    pub(crate) balance_capped: bool,
+}
+
+/// `cheapest` 策略的一次选路决策；候选顺序即实际尝试顺序。
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CostRouteDecision {
+    pub(crate) strategy: &'static str,
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_ratio: f64,
+    pub(crate) expected_output_tokens: i64,
+    pub(crate) token_mix_samples: i64,
+    pub(crate) preferred_aggregate_api_id: Option<String>,
+    pub(crate) candidates: Vec<CostRouteCandidate>,
+}
+
+/// 当前网关策略为 `cheapest` 时按估算成本重排聚合 API 候选，并返回决策 JSON 供请求日志记录。
+pub(crate) fn apply_cheapest_route_to_aggregate_candidates(
+    storage: &Storage,
+    candidates: &mut [AggregateApi],
+    key_id: &str,
+    model: Option<&str>,
+    body: &[u8],
+    preferred_aggregate_api_id: Option<&str>,
+) -> Option<String> {
+    if candidates.is_empty() || super::current_route_strategy() != ROUTE_STRATEGY_CHEAPEST {
+        return None;
+    }
+    let price_rules = storage
+        .list_enabled_model_price_rules()
+        .unwrap_or_else(|err| {
+            log::warn!("event=cost_route_price_rules_load_failed err={err}");
+            Vec::new()
+        });
+    let catalog = load_catalog_prices(storage).unwrap_or_else(|err| {
+        log::warn!("event=cost_route_catalog_load_failed err={err}");
+        Vec::new()
+    });
+    let mix = model
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|model| {
+            storage
+                .summarize_recent_token_mix(Some(key_id), model, TOKEN_MIX_SAMPLE_LIMIT)
+                .unwrap_or_else(|err| {
+                    log::warn!(
+                        "event=cost_route_token_mix_load_failed key_id={} model={} err={}",
+                        key_id,
+                        model,
+                        err
+                    );
+                    RecentTokenMix::default()
+                })
+        })
+        .unwrap_or_default();
+    let inputs = CostRouteInputs::from_request(body, mix);
+    let decision = rank_aggregate_candidates_by_cost(
+        candidates,
+        model,
+        inputs,
+        price_rules.as_slice(),
+        catalog.as_slice(),
+        preferred_aggregate_api_id,
+    );
+    serde_json::to_string(&decision).ok()
+}
+
+/// 按估算成本升序稳定排序；余额不足或余额快照失效的候选降到末尾，无价格的候选排在有价格的之后。
+pub(crate) fn rank_aggregate_candidates_by_cost(
+    candidates: &mut [AggregateApi],
+    model: Option<&str>,
+    inputs: CostRouteInputs,
+    price_rules: &[ModelPriceRule],
+    catalog: &[CatalogModelPrice],
+    preferred_aggregate_api_id: Option<&str>,
+) -> CostRouteDecision {
+    let preferred_id = preferred_aggregate_api_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let preserves_head = preferred_id
+        .zip(candidates.first())
+        .is_some_and(|(preferred_id, first)| first.id == preferred_id);
+    let start = usize::from(preserves_head);
+
+    let mut ranked = candidates
+        .iter()
+        .cloned()
+        .map(|api| {
+            let estimate = estimate_candidate(&api, model, inputs, price_rules, catalog);
+            (api, estimate)
+        })
+        .collect::<Vec<_>>();
+    ranked[start..].sort_by(|(_, left), (_, right)| compare_estimates(left, right));
+
+    let mut decision_candidates = Vec::with_capacity(ranked.len());
+    for (slot, (api, estimate)) in candidates.iter_mut().zip(ranked) {
+        *slot = api;
+        decision_candidates.push(estimate);
+    }
+    CostRouteDecision {
+        strategy: ROUTE_STRATEGY_CHEAPEST,
+        input_tokens: inputs.input_tokens,
+        cached_input_ratio: inputs.cached_input_ratio,
+        expected_output_tokens: inputs.expected_output_tokens,
+        token_mix_samples: inputs.token_mix_samples,
+        preferred_aggregate_api_id: preferred_id.map(str::to_string),
+        candidates: decision_candidates,
+    }
+}
+
+fn compare_estimates(left: &CostRouteCandidate, right: &CostRouteCandidate) -> Ordering {
+    left.balance_capped
+        .cmp(&right.balance_capped)
+        .then_with(
+            || match (left.estimated_cost_usd, right.estimated_cost_usd) {
+                (Some(left), Some(right)) => left.total_cmp(&right),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        )
+}
+
+fn estimate_candidate(
+    api: &AggregateApi,
+    model: Option<&str>,
+    inputs: CostRouteInputs,
+    price_rules: &[ModelPriceRule],
+    catalog: &[CatalogModelPrice],
+) -> CostRouteCandidate {
+    // 中文注释：模型过滤已把供应商侧模型名写入 model_override，价格按真正发往上游的模型计算。
+    let upstream_model = api
+        .model_override
+        .as_deref()
+        .or(model)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    let price = upstream_model.as_deref().and_then(|upstream_model| {
+        resolve_supplier_price(api, upstream_model, inputs.input_tokens, price_rules).or_else(
+            || {
+                resolve_model_price_from_catalog(catalog, upstream_model, inputs.input_tokens).map(
+                    |matched| SupplierPrice {
+                        source: PRICE_SOURCE_CATALOG,
+                        input_price_per_1m: matched.input_price_per_1m,
+                        cached_input_price_per_1m: matched.cached_input_price_per_1m,
+                        output_price_per_1m: matched.output_price_per_1m,
+                    },
+                )
+            },
+        )
+    });
+    let estimated_cost_usd = price.map(|price| estimate_cost_usd(price, inputs));
+    let (balance_remaining_usd, balance_valid) = parse_balance(api);
+    let balance_capped = !balance_valid
+        || balance_remaining_usd
+            .zip(estimated_cost_usd)
+            .is_some_and(|(remaining, cost)| remaining < cost);
+    CostRouteCandidate {
+        aggregate_api_id: api.id.clone(),
+        supplier_name: api.supplier_name.clone(),
+        upstream_model,
+        price_source: price.map_or(PRICE_SOURCE_MISSING, |price| price.source),
+        input_price_per_1m: price.map(|price| price.input_price_per_1m),
+        cached_input_price_per_1m: price.map(|price| price.cached_input_price_per_1m),
+        output_price_per_1m: price.map(|price| price.output_price_per_1m),
+        estimated_cost_usd,
+        balance_remaining_usd,
+        balance_capped,
+    }
+}
+
+/// 供应商专属价格：价格规则的 provider 填写聚合 API 的 id 或供应商名称时视为该供应商的报价。
+fn resolve_supplier_price(
+    api: &AggregateApi,
+    upstream_model: &str,
+    input_tokens: i64,
+    price_rules: &[ModelPriceRule],
+) -> Option<SupplierPrice> {
+    let supplier_name = api
+        .supplier_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let upstream_model = upstream_model.to_ascii_lowercase();
+    price_rules
+        .iter()
+        .filter(|rule| {
+            let provider = rule.provider.trim();
+            provider.eq_ignore_ascii_case(api.id.as_str())
+                || supplier_name.is_some_and(|name| provider.eq_ignore_ascii_case(name))
+        })
+        .filter(|rule| {
+            let pattern = rule.model_pattern.trim().to_ascii_lowercase();
+            if rule.match_type == "exact" {
+                pattern == upstream_model
+            } else {
+                wildcard_matches(pattern.as_str(), upstream_model.as_str())
+            }
+        })
+        .find_map(|rule| {
+            let long_context = rule
+                .long_context_threshold_tokens
+                .is_some_and(|threshold| input_tokens > threshold);
+            let pick = |regular: Option<f64>, long: Option<f64>| {
+                if long_context {
+                    long.or(regular)
+                } else {
+                    regular
+                }
+            };
+            let input = pick(
+                rule.input_price_per_1m,
+                rule.long_context_input_price_per_1m,
+            )?;
+            let output = pick(
+                rule.output_price_per_1m,
+                rule.long_context_output_price_per_1m,
+            )?;
+            let cached = pick(
+                rule.cached_input_price_per_1m,
+                rule.long_context_cached_input_price_per_1m,
+            )
+            .unwrap_or(input);
+            Some(SupplierPrice {
+                source: PRICE_SOURCE_SUPPLIER_RULE,
+                input_price_per_1m: input,
+                cached_input_price_per_1m: cached,
+                output_price_per_1m: output,
+            })
+        })
+}
+
+fn estimate_cost_usd(price: SupplierPrice, inputs: CostRouteInputs) -> f64 {
+    let input_total = inputs.input_tokens.max(0) as f64;
+    let cached_input = (inputs.cached_input_tokens().max(0) as f64).min(input_total);
+    let billable_input = input_total - cached_input;
+    let output = inputs.expected_output_tokens.max(0) as f64;
+    let cost = (billable_input / 1_000_000.0) * price.input_price_per_1m
+        + (cached_input / 1_000_000.0) * price.cached_input_price_per_1m
+        + (output / 1_000_000.0) * price.output_price_per_1m;
+    cost.max(0.0)
+}
+
+/// 返回 (剩余余额, 快照是否有效)；未开启余额查询或没有快照时视为不限额。
+pub(crate) fn parse_balance(api: &AggregateApi) -> (Option<f64>, bool) {
+    if !api.balance_query_enabled {
+        return (None, true);
+    }
+    let Some(value) = api
+        .last_balance_json
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+    else {
+        return (None, true);
+    };
+    let remaining = value
+        .get("remaining")
+        .and_then(Value::as_f64)
+        .filter(|value| value.is_finite());
+    let is_valid = value
+        .get("isValid")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    (remaining, is_valid)
+}
+
+fn request_max_output_tokens(body: &[u8]) -> Option<i64> {
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    ["max_output_tokens", "max_completion_tokens", "max_tokens"]
+        .iter()
+        .find_map(|field| value.get(*field).and_then(Value::as_i64))
+        .filter(|value| *value > 0)
+}
+
+#[cfg(test)]
+#[path = "tests/cost_route_tests.rs"]
+mod tests;
@@ -0,0 +1,278 @@
+use codexmanager_core::storage::{Account, AccountGroupPolicy, Token};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// 中文注释：策略在后台 RPC 写入时会主动失效缓存；TTL 只兜底多进程共享数据库时的外部改动。
+const GROUP_POLICY_CACHE_TTL: Duration = Duration::from_secs(5);
+const COOLDOWN_MULTIPLIER_BASE_MILLIS: i64 = 1000;
+const MAX_FALLBACK_CHAIN_LEN: usize = 8;
+
+type GroupPolicyMap = Arc<HashMap<String, Arc<GroupRoutingPolicy>>>;
+
+/// 解析后的分组选路策略；未设置的字段沿用全局网关配置。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct GroupRoutingPolicy {
+    pub(crate) route_strategy: Option<&'static str>,
+    pub(crate) account_max_inflight: Option<usize>,
+    pub(crate) allowed_models: Vec<String>,
+    pub(crate) fallback_groups: Vec<String>,
+    pub(crate) cooldown_multiplier_millis: Option<i64>,
+}
+
+impl GroupRoutingPolicy {
+    pub(crate) fn from_record(record: &AccountGroupPolicy) -> Self {
+        Self {
+            route_strategy: record
+                .route_strategy
+                .as_deref()
+                .and_then(super::route_hint::normalize_route_strategy),
+            account_max_inflight: record
+                .account_max_inflight
+                .and_then(|value| usize::try_from(value).ok()),
+            allowed_models: parse_string_list(
+                record.group_name.as_str(),
+                record.allowed_models_json.as_deref(),
+            ),
+            fallback_groups: parse_string_list(
+                record.group_name.as_str(),
+                record.fallback_groups_json.as_deref(),
+            ),
+            cooldown_multiplier_millis: record.cooldown_multiplier_millis.map(|value| value.max(0)),
+        }
+    }
+
+    /// 未配置模型白名单时放行全部模型。
+    pub(crate) fn allows_model(&self, model: &str) -> bool {
+        self.allowed_models.is_empty()
+            || self
+                .allowed_models
+                .iter()
+                .any(|pattern| model_pattern_matches(pattern, model))
+    }
+}
+
+/// 支持精确匹配（忽略大小写）与 `gpt-5*` 形式的前缀匹配。
+fn model_pattern_matches(pattern: &str, model: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let model = model.trim().to_ascii_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+fn parse_string_list(group_name: &str, raw: Option<&str>) -> Vec<String> {
+    let Some(raw) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Vec<String>>(raw) {
+        Ok(items) => items
+            .into_iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        Err(err) => {
+            log::warn!(
+                "event=account_group_policy_invalid_list group={} err={}",
+                group_name,
+                err
+            );
+            Vec::new()
+        }
+    }
+}
+
+struct GroupPolicyCache {
+    expires_at: Option<Instant>,
+    policies: GroupPolicyMap,
+}
+
+static GROUP_POLICY_CACHE: OnceLock<Mutex<GroupPolicyCache>> = OnceLock::new();
+static ACCOUNT_GROUP_INDEX: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn group_policy_cache() -> &'static Mutex<GroupPolicyCache> {
+    GROUP_POLICY_CACHE.get_or_init(|| {
+        Mutex::new(GroupPolicyCache {
+            expires_at: None,
+            policies: Arc::new(HashMap::new()),
+        })
+    })
+}
+
+fn load_group_policies() -> GroupPolicyMap {
+    let Some(storage) = crate::storage_helpers::open_storage() else {
+        return Arc::new(HashMap::new());
+    };
+    match storage.list_account_group_policies() {
+        Ok(records) => Arc::new(
+            records
+                .iter()
+                .map(|record| {
+                    (
+                        record.group_name.clone(),
+                        Arc::new(GroupRoutingPolicy::from_record(record)),
+                    )
+                })
+                .collect(),
+        ),
+        Err(err) => {
+            log::warn!("event=account_group_policy_load_failed err={}", err);
+            Arc::new(HashMap::new())
+        }
+    }
+}
+
+fn group_policies() -> GroupPolicyMap {
+    let mut cache = crate::lock_utils::lock_recover(group_policy_cache(), "account_group_policy");
+    let now = Instant::now();
+    if cache.expires_at.is_some_and(|expires_at| expires_at > now) {
+        return cache.policies.clone();
+    }
+    cache.policies = load_group_policies();
+    cache.expires_at = Some(now + GROUP_POLICY_CACHE_TTL);
+    cache.policies.clone()
+}
+
+/// 策略变更后调用，下一次选路会重新从存储加载。
+pub(crate) fn invalidate_group_policy_cache() {
+    let mut cache = crate::lock_utils::lock_recover(group_policy_cache(), "account_group_policy");
+    cache.expires_at = None;
+}
+
+fn account_group_name(account: &Account) -> Option<&str> {
+    account
+        .group_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn policy_for_account(account: &Account) -> Option<Arc<GroupRoutingPolicy>> {
+    let group_name = account_group_name(account)?;
+    group_policies().get(group_name).cloned()
+}
+
+/// 剔除所在分组不允许当前模型的账号；没有任何分组策略时不做处理。
+pub(crate) fn retain_candidates_allowed_for_model(
+    candidates: &mut Vec<(Account, Token)>,
+    request_model: Option<&str>,
+) {
+    let Some(model) = request_model
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        return;
+    };
+    let policies = group_policies();
+    if policies.is_empty() {
+        return;
+    }
+    candidates.retain(|(account, _)| {
+        account_group_name(account)
+            .and_then(|group_name| policies.get(group_name))
+            .is_none_or(|policy| policy.allows_model(model))
+    });
+}
+
+/// 返回以 `group_name` 开头、按广度优先展开的回退分组链，自动去重并限制长度防止环路。
+pub(crate) fn group_fallback_chain(group_name: &str) -> Vec<String> {
+    let policies = group_policies();
+    let mut chain = vec![group_name.to_string()];
+    let mut idx = 0;
+    while idx < chain.len() && chain.len() < MAX_FALLBACK_CHAIN_LEN {
+        if let Some(policy) = policies.get(chain[idx].as_str()) {
+            for fallback in &policy.fallback_groups {
+                if chain.len() >= MAX_FALLBACK_CHAIN_LEN {
+                    break;
+                }
+                if !chain.iter().any(|item| item == fallback) {
+                    chain.push(fallback.clone());
+                }
+            }
+        }
+        idx += 1;
+    }
+    chain
+}
+
+/// 记住候选账号所属分组，供只拿得到账号 ID 的冷却逻辑查找分组策略。
+pub(crate) fn remember_candidate_groups(candidates: &[(Account, Token)]) {
+    if group_policies().is_empty() {
+        return;
+    }
+    let index = ACCOUNT_GROUP_INDEX.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut index = crate::lock_utils::lock_recover(index, "account_group_index");
+    for (account, _) in candidates {
+        match account_group_name(account) {
+            Some(group_name) => {
+                if index.get(account.id.as_str()).map(String::as_str) != Some(group_name) {
+                    index.insert(account.id.clone(), group_name.to_string());
+                }
+            }
+            None => {
+                index.remove(account.id.as_str());
+            }
+        }
+    }
+}
+
+/// 候选全部来自同一分组且该分组配置了选路策略时，返回该策略。
+pub(super) fn shared_group_route_strategy(candidates: &[(Account, Token)]) -> Option<&'static str> {
+    let (first, rest) = candidates.split_first()?;
+    let group_name = account_group_name(&first.0)?;
+    if rest
+        .iter()
+        .any(|(account, _)| account_group_name(account) != Some(group_name))
+    {
+        return None;
+    }
+    group_policies().get(group_name)?.route_strategy
+}
+
+/// 分组配置了并发上限时覆盖全局 `account_max_inflight`；0 表示不限。
+pub(crate) fn account_max_inflight_for(account: &Account, default_limit: usize) -> usize {
+    policy_for_account(account)
+        .and_then(|policy| policy.account_max_inflight)
+        .unwrap_or(default_limit)
+}
+
+/// 账号所在分组配置的冷却倍率（千分比，1000 = 1x）；账号未出现在候选池或分组无策略时返回 `None`。
+pub(super) fn cooldown_multiplier_millis_for_account(account_id: &str) -> Option<i64> {
+    let group_name = ACCOUNT_GROUP_INDEX.get().and_then(|index| {
+        crate::lock_utils::lock_recover(index, "account_group_index")
+            .get(account_id)
+            .cloned()
+    })?;
+    group_policies()
+        .get(group_name.as_str())
+        .and_then(|policy| policy.cooldown_multiplier_millis)
+}
+
+pub(super) fn scale_cooldown_secs(cooldown_secs: i64, multiplier_millis: Option<i64>) -> i64 {
+    match multiplier_millis {
+        Some(multiplier) => {
+            cooldown_secs.saturating_mul(multiplier) / COOLDOWN_MULTIPLIER_BASE_MILLIS
+        }
+        None => cooldown_secs,
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn set_group_policies_for_tests(policies: Vec<(&str, GroupRoutingPolicy)>) {
+    let mut cache = crate::lock_utils::lock_recover(group_policy_cache(), "account_group_policy");
+    cache.policies = Arc::new(
+        policies
+            .into_iter()
+            .map(|(group_name, policy)| (group_name.to_string(), Arc::new(policy)))
+            .collect(),
+    );
+    cache.expires_at = Some(Instant::now() + Duration::from_secs(3600));
+    if let Some(index) = ACCOUNT_GROUP_INDEX.get() {
+        crate::lock_utils::lock_recover(index, "account_group_index").clear();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/group_policy_tests.rs"]
+mod tests;
@@ -8,8 +8,11 @@ use std::time::{Duration, Instant};
 const ROUTE_STRATEGY_ENV: &str = "CODEXMANAGER_ROUTE_STRATEGY";
 const ROUTE_MODE_ORDERED: u8 = 0;
 const ROUTE_MODE_BALANCED_ROUND_ROBIN: u8 = 1;
+// 中文注释：cheapest 只对聚合 API 候选按成本重排；账号候选没有单价，按 ordered 处理。
+const ROUTE_MODE_CHEAPEST: u8 = 2;
 const ROUTE_STRATEGY_ORDERED: &str = "ordered";
 const ROUTE_STRATEGY_BALANCED: &str = "balanced";
+pub(super) const ROUTE_STRATEGY_CHEAPEST: &str = "cheapest";
 const ROUTE_HEALTH_P2C_ENABLED_ENV: &str = "CODEXMANAGER_ROUTE_HEALTH_P2C_ENABLED";
 const ROUTE_HEALTH_P2C_ORDERED_WINDOW_ENV: &str = "CODEXMANAGER_ROUTE_HEALTH_P2C_ORDERED_WINDOW";
 const ROUTE_HEALTH_P2C_BALANCED_WINDOW_ENV: &str = "CODEXMANAGER_ROUTE_HEALTH_P2C_BALANCED_WINDOW";
@@ -86,6 +89,7 @@ struct RouteRoundRobinState {
 ///
 /// # 返回
 /// 无
+#[cfg(test)]
 pub(crate) fn apply_route_strategy(
     candidates: &mut [(Account, Token)],
     key_id: &str,
@@ -105,10 +109,17 @@ pub(crate) fn apply_route_strategy_with_source(
     model: Option<&str>,
 ) -> RouteStrategyApplication {
     ensure_route_config_loaded();
-    let mode = route_mode();
+    // 中文注释：候选全部来自同一个配置了选路策略的账号分组时，以分组策略覆盖全局模式。
+    let group_mode =
+        super::group_policy::shared_group_route_strategy(candidates).and_then(parse_route_mode);
+    let mode = group_mode.unwrap_or_else(route_mode);
     let default_application = RouteStrategyApplication {
         strategy_label: route_mode_label(mode),
-        source: "route_strategy",
+        source: if group_mode.is_some() {
+            "group_policy"
+        } else {
+            "route_strategy"
+        },
     };
     if rotate_to_manual_preferred_account(candidates) {
         return RouteStrategyApplication {
@@ -243,10 +254,10 @@ fn route_mode() -> u8 {
 /// # 返回
 /// 返回函数执行结果
 fn route_mode_label(mode: u8) -> &'static str {
-    if mode == ROUTE_MODE_BALANCED_ROUND_ROBIN {
-        ROUTE_STRATEGY_BALANCED
-    } else {
-        ROUTE_STRATEGY_ORDERED
+    match mode {
+        ROUTE_MODE_BALANCED_ROUND_ROBIN => ROUTE_STRATEGY_BALANCED,
+        ROUTE_MODE_CHEAPEST => ROUTE_STRATEGY_CHEAPEST,
+        _ => ROUTE_STRATEGY_ORDERED,
     }
 }
 
@@ -267,10 +278,18 @@ fn parse_route_mode(raw: &str) -> Option<u8> {
         ROUTE_STRATEGY_BALANCED | "round_robin" | "round-robin" | "rr" => {
             Some(ROUTE_MODE_BALANCED_ROUND_ROBIN)
         }
+        ROUTE_STRATEGY_CHEAPEST | "cost" | "lowest_cost" | "lowest-cost" => {
+            Some(ROUTE_MODE_CHEAPEST)
+        }
         _ => None,
     }
 }
 
+/// 把策略别名归一为 `ordered` / `balanced` / `cheapest`，无法识别时返回 `None`。
+pub(crate) fn normalize_route_strategy(raw: &str) -> Option<&'static str> {
+    parse_route_mode(raw).map(route_mode_label)
+}
+
 /// 函数 `current_route_strategy`
 ///
 /// 作者: gaohongshun
@@ -302,7 +321,7 @@ pub(crate) fn set_route_strategy(strategy: &str) -> Result<&'static str, String>
     ensure_route_config_loaded();
     let Some(mode) = parse_route_mode(strategy) else {
         return Err(
-            "invalid strategy; use ordered or balanced (aliases: round_robin/round-robin/rr)"
+            "invalid strategy; use ordered, balanced or cheapest (aliases: round_robin/round-robin/rr, cost/lowest_cost)"
                 .to_string(),
         );
     };
@@ -389,6 +408,9 @@ pub(crate) fn clear_manual_preferred_account() {
 /// # 返回
 /// 返回函数执行结果
 fn next_start_index(key_id: &str, model: Option<&str>, candidate_count: usize) -> usize {
+    if let Some(start) = next_shared_start_index(key_id, model, candidate_count) {
+        return start;
+    }
     let lock = ROUTE_STATE.get_or_init(|| Mutex::new(RouteRoundRobinState::default()));
     let mut state_guard = crate::lock_utils::lock_recover(lock, "route_state");
     let state = &mut *state_guard;
@@ -417,11 +439,96 @@ fn next_start_index(key_id: &str, model: Option<&str>, candidate_count: usize) -
     start
 }
 
+// 中文注释：配置了共享运行时状态时游标落在共享后端，多副本轮询才不会各自从头开始；后端异常时回退进程内游标。
+fn next_shared_start_index(
+    key_id: &str,
+    model: Option<&str>,
+    candidate_count: usize,
+) -> Option<usize> {
+    let backend = super::runtime_state::shared_runtime_state_backend()?;
+    let key = key_model_key(key_id, model);
+    match backend.next_cursor(
+        super::runtime_state::SCOPE_ROUTE_CURSOR,
+        key.as_str(),
+        route_state_ttl(),
+    ) {
+        Ok(cursor) => Some((cursor % candidate_count as u64) as usize),
+        Err(err) => {
+            log::warn!(
+                "event=route_cursor_shared_failed backend={} key={} err={}",
+                backend.name(),
+                key,
+                err
+            );
+            None
+        }
+    }
+}
+
+fn next_shared_account_start_index(
+    candidates: &[(Account, Token)],
+    key_id: &str,
+    model: Option<&str>,
+) -> Option<usize> {
+    let backend = super::runtime_state::shared_runtime_state_backend()?;
+    let key = key_model_key(key_id, model);
+    let scope = super::runtime_state::SCOPE_ROUTE_ACCOUNT_CURSOR;
+    let result = backend.get_value(scope, key.as_str()).and_then(|raw| {
+        let previous = raw.as_deref().and_then(|value| {
+            let (last, next) = value.split_once('\n')?;
+            Some(AccountRoundRobinCursor {
+                last_selected_account_id: last.to_string(),
+                next_account_id: next.to_string(),
+            })
+        });
+        let start = account_cursor_start(candidates, previous.as_ref());
+        let next = (start + 1) % candidates.len();
+        let value = format!("{}\n{}", candidates[start].0.id, candidates[next].0.id);
+        backend
+            .set_value(scope, key.as_str(), value.as_str(), route_state_ttl())
+            .map(|_| start)
+    });
+    match result {
+        Ok(start) => Some(start),
+        Err(err) => {
+            log::warn!(
+                "event=route_account_cursor_shared_failed backend={} key={} err={}",
+                backend.name(),
+                key,
+                err
+            );
+            None
+        }
+    }
+}
+
+fn account_cursor_start(
+    candidates: &[(Account, Token)],
+    previous_cursor: Option<&AccountRoundRobinCursor>,
+) -> usize {
+    previous_cursor
+        .and_then(|cursor| {
+            candidates
+                .iter()
+                .position(|(account, _)| account.id == cursor.last_selected_account_id)
+                .map(|index| (index + 1) % candidates.len())
+                .or_else(|| {
+                    candidates
+                        .iter()
+                        .position(|(account, _)| account.id == cursor.next_account_id)
+                })
+        })
+        .unwrap_or(0)
+}
+
 fn next_account_start_index(
     candidates: &[(Account, Token)],
     key_id: &str,
     model: Option<&str>,
 ) -> usize {
+    if let Some(start) = next_shared_account_start_index(candidates, key_id, model) {
+        return start;
+    }
     let lock = ROUTE_STATE.get_or_init(|| Mutex::new(RouteRoundRobinState::default()));
     let mut state_guard = crate::lock_utils::lock_recover(lock, "route_state");
     let state = &mut *state_guard;
@@ -442,20 +549,7 @@ fn next_account_start_index(
         .account_cursor_by_key_model
         .get(key.as_str())
         .map(|entry| entry.value.clone());
-    let start = previous_cursor
-        .as_ref()
-        .and_then(|cursor| {
-            candidates
-                .iter()
-                .position(|(account, _)| account.id == cursor.last_selected_account_id)
-                .map(|index| (index + 1) % candidates.len())
-                .or_else(|| {
-                    candidates
-                        .iter()
-                        .position(|(account, _)| account.id == cursor.next_account_id)
-                })
-        })
-        .unwrap_or(0);
+    let start = account_cursor_start(candidates, previous_cursor.as_ref());
     let next = (start + 1) % candidates.len();
     state.account_cursor_by_key_model.insert(
         key,
@@ -475,6 +569,18 @@ fn current_start_index(key_id: &str, model: Option<&str>, candidate_count: usize
     if candidate_count == 0 {
         return 0;
     }
+    if let Some(backend) = super::runtime_state::shared_runtime_state_backend() {
+        let key = key_model_key(key_id, model);
+        match backend.peek_cursor(super::runtime_state::SCOPE_ROUTE_CURSOR, key.as_str()) {
+            Ok(cursor) => return (cursor % candidate_count as u64) as usize,
+            Err(err) => log::warn!(
+                "event=route_cursor_shared_failed backend={} key={} err={}",
+                backend.name(),
+                key,
+                err
+            ),
+        }
+    }
     let lock = ROUTE_STATE.get_or_init(|| Mutex::new(RouteRoundRobinState::default()));
     let state_guard = crate::lock_utils::lock_recover(lock, "route_state");
     let state = &*state_guard;
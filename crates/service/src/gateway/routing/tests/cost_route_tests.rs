@@ -0,0 +1,249 @@
+use super::*;
+use codexmanager_core::storage::ModelPriceTierV2;
+
+fn candidate(id: &str, supplier_name: Option<&str>) -> AggregateApi {
+    AggregateApi {
+        id: id.to_string(),
+        provider_type: "codex".to_string(),
+        supplier_name: supplier_name.map(str::to_string),
+        sort: 0,
+        url: format!("https://{id}.example.com"),
+        auth_type: "apikey".to_string(),
+        auth_params_json: None,
+        action: None,
+        model_override: None,
+        status: "active".to_string(),
+        created_at: 0,
+        updated_at: 0,
+        last_test_at: None,
+        last_test_status: None,
+        last_test_error: None,
+        balance_query_enabled: false,
+        balance_query_template: None,
+        balance_query_base_url: None,
+        balance_query_user_id: None,
+        balance_query_config_json: None,
+        last_balance_at: None,
+        last_balance_status: None,
+        last_balance_error: None,
+        last_balance_json: None,
+    }
+}
+
+fn supplier_rule(provider: &str, model_pattern: &str, input: f64, output: f64) -> ModelPriceRule {
+    ModelPriceRule {
+        id: format!("{provider}-{model_pattern}"),
+        provider: provider.to_string(),
+        model_pattern: model_pattern.to_string(),
+        match_type: if model_pattern.contains('*') {
+            "wildcard".to_string()
+        } else {
+            "exact".to_string()
+        },
+        billing_mode: "standard".to_string(),
+        currency: "USD".to_string(),
+        unit: "per_1m_tokens".to_string(),
+        input_price_per_1m: Some(input),
+        cached_input_price_per_1m: None,
+        output_price_per_1m: Some(output),
+        reasoning_output_price_per_1m: None,
+        cache_write_5m_price_per_1m: None,
+        cache_write_1h_price_per_1m: None,
+        cache_hit_price_per_1m: None,
+        long_context_threshold_tokens: None,
+        long_context_input_price_per_1m: None,
+        long_context_cached_input_price_per_1m: None,
+        long_context_output_price_per_1m: None,
+        source: "custom".to_string(),
+        source_url: None,
+        seed_version: None,
+        enabled: true,
+        priority: 0,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+fn catalog_price(model_slug: &str, input_usd: f64, output_usd: f64) -> CatalogModelPrice {
+    CatalogModelPrice {
+        model_slug: model_slug.to_string(),
+        provider: "openai".to_string(),
+        price_status: "ok".to_string(),
+        tiers: vec![ModelPriceTierV2 {
+            min_input_tokens: 0,
+            input_microusd_per_1m: (input_usd * 1_000_000.0) as i64,
+            cached_input_microusd_per_1m: (input_usd * 100_000.0) as i64,
+            cache_write_microusd_per_1m: None,
+            output_microusd_per_1m: (output_usd * 1_000_000.0) as i64,
+        }],
+    }
+}
+
+fn inputs(input_tokens: i64, cached_input_ratio: f64) -> CostRouteInputs {
+    CostRouteInputs {
+        input_tokens,
+        cached_input_ratio,
+        expected_output_tokens: 1_000,
+        token_mix_samples: 0,
+    }
+}
+
+fn ids(candidates: &[AggregateApi]) -> Vec<&str> {
+    candidates.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[test]
+fn cheapest_prefers_supplier_rules_then_catalog_then_unknown_prices() {
+    let mut candidates = vec![
+        candidate("ag-unknown", None),
+        candidate("ag-catalog", None),
+        candidate("ag-discount", Some("Discount")),
+    ];
+    candidates[0].model_override = Some("vendor-private".to_string());
+    let rules = vec![supplier_rule("discount", "gpt-5*", 0.5, 1.0)];
+    let catalog = vec![catalog_price("gpt-5.4", 2.0, 8.0)];
+
+    let decision = rank_aggregate_candidates_by_cost(
+        &mut candidates,
+        Some("gpt-5.4"),
+        inputs(10_000, 0.0),
+        &rules,
+        &catalog,
+        None,
+    );
+
+    assert_eq!(
+        ids(&candidates),
+        vec!["ag-discount", "ag-catalog", "ag-unknown"]
+    );
+    assert_eq!(decision.strategy, "cheapest");
+    assert_eq!(decision.candidates[0].price_source, "supplier_rule");
+    assert_eq!(decision.candidates[1].price_source, "catalog");
+    assert_eq!(decision.candidates[2].price_source, "missing");
+    assert_eq!(
+        decision.candidates[2].upstream_model.as_deref(),
+        Some("vendor-private")
+    );
+    let discount_cost = decision.candidates[0].estimated_cost_usd.expect("cost");
+    assert!((discount_cost - 0.006).abs() < 1e-9, "{discount_cost}");
+}
+
+#[test]
+fn cheapest_cached_ratio_can_flip_ranking_between_suppliers() {
+    // 中文注释：a 输入便宜但没有缓存折扣，b 输入贵但缓存价极低；高缓存命中率下 b 更便宜。
+    let mut cached_rule = supplier_rule("ag-b", "gpt-5.4", 3.0, 1.0);
+    cached_rule.cached_input_price_per_1m = Some(0.1);
+    let rules = vec![supplier_rule("ag-a", "gpt-5.4", 1.0, 1.0), cached_rule];
+
+    let mut cold = vec![candidate("ag-a", None), candidate("ag-b", None)];
+    rank_aggregate_candidates_by_cost(
+        &mut cold,
+        Some("gpt-5.4"),
+        inputs(100_000, 0.0),
+        &rules,
+        &[],
+        None,
+    );
+    assert_eq!(ids(&cold), vec!["ag-a", "ag-b"]);
+
+    let mut warm = vec![candidate("ag-a", None), candidate("ag-b", None)];
+    rank_aggregate_candidates_by_cost(
+        &mut warm,
+        Some("gpt-5.4"),
+        inputs(100_000, 0.9),
+        &rules,
+        &[],
+        None,
+    );
+    assert_eq!(ids(&warm), vec!["ag-b", "ag-a"]);
+}
+
+#[test]
+fn cheapest_demotes_candidates_whose_balance_cannot_cover_the_request() {
+    let mut broke = candidate("ag-broke", None);
+    broke.balance_query_enabled = true;
+    broke.last_balance_json = Some(r#"{"isValid":true,"remaining":0.0001}"#.to_string());
+    let mut invalid = candidate("ag-invalid", None);
+    invalid.balance_query_enabled = true;
+    invalid.last_balance_json = Some(r#"{"isValid":false,"remaining":100.0}"#.to_string());
+    let mut funded = candidate("ag-funded", None);
+    funded.balance_query_enabled = true;
+    funded.last_balance_json = Some(r#"{"isValid":true,"remaining":50.0}"#.to_string());
+    let rules = vec![
+        supplier_rule("ag-broke", "gpt-5.4", 0.1, 0.1),
+        supplier_rule("ag-invalid", "gpt-5.4", 0.2, 0.2),
+        supplier_rule("ag-funded", "gpt-5.4", 5.0, 5.0),
+    ];
+    let mut candidates = vec![broke, invalid, funded];
+
+    let decision = rank_aggregate_candidates_by_cost(
+        &mut candidates,
+        Some("gpt-5.4"),
+        inputs(10_000, 0.0),
+        &rules,
+        &[],
+        None,
+    );
+
+    assert_eq!(
+        ids(&candidates),
+        vec!["ag-funded", "ag-broke", "ag-invalid"]
+    );
+    assert!(!decision.candidates[0].balance_capped);
+    assert!(decision.candidates[1].balance_capped);
+    assert_eq!(decision.candidates[1].balance_remaining_usd, Some(0.0001));
+    assert!(decision.candidates[2].balance_capped);
+}
+
+#[test]
+fn cheapest_keeps_preferred_head_and_uses_long_context_prices() {
+    let mut long_rule = supplier_rule("ag-long", "gpt-5.4", 0.5, 0.5);
+    long_rule.long_context_threshold_tokens = Some(50_000);
+    long_rule.long_context_input_price_per_1m = Some(10.0);
+    let rules = vec![
+        long_rule,
+        supplier_rule("ag-mid", "gpt-5.4", 2.0, 2.0),
+        supplier_rule("ag-preferred", "gpt-5.4", 20.0, 20.0),
+    ];
+    let mut candidates = vec![
+        candidate("ag-preferred", None),
+        candidate("ag-long", None),
+        candidate("ag-mid", None),
+    ];
+
+    let decision = rank_aggregate_candidates_by_cost(
+        &mut candidates,
+        Some("gpt-5.4"),
+        inputs(100_000, 0.0),
+        &rules,
+        &[],
+        Some("ag-preferred"),
+    );
+
+    assert_eq!(ids(&candidates), vec!["ag-preferred", "ag-mid", "ag-long"]);
+    assert_eq!(
+        decision.preferred_aggregate_api_id.as_deref(),
+        Some("ag-preferred")
+    );
+    assert_eq!(decision.candidates[2].input_price_per_1m, Some(10.0));
+}
+
+#[test]
+fn cost_route_inputs_use_recent_mix_and_request_output_cap() {
+    let body = br#"{"model":"gpt-5.4","input":"hello","max_output_tokens":200}"#;
+    let mix = RecentTokenMix {
+        sample_count: 4,
+        input_tokens: 4_000,
+        cached_input_tokens: 1_000,
+        output_tokens: 2_000,
+    };
+
+    let capped = CostRouteInputs::from_request(body, mix);
+    assert_eq!(capped.cached_input_ratio, 0.25);
+    assert_eq!(capped.expected_output_tokens, 200);
+    assert_eq!(capped.token_mix_samples, 4);
+
+    let fresh = CostRouteInputs::from_request(br#"{"input":"hello"}"#, RecentTokenMix::default());
+    assert_eq!(fresh.cached_input_ratio, 0.0);
+    assert_eq!(fresh.expected_output_tokens, 1024);
+}
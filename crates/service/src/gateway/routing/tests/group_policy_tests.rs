@@ -0,0 +1,140 @@
+use super::*;
+
+fn candidate(account_id: &str, group_name: Option<&str>) -> (Account, Token) {
+    (
+        Account {
+            id: account_id.to_string(),
+            label: String::new(),
+            issuer: String::new(),
+            chatgpt_account_id: None,
+            workspace_id: None,
+            group_name: group_name.map(str::to_string),
+            sort: 0,
+            status: "active".to_string(),
+            created_at: 0,
+            updated_at: 0,
+        },
+        Token {
+            account_id: account_id.to_string(),
+            id_token: String::new(),
+            access_token: String::new(),
+            refresh_token: String::new(),
+            api_key_access_token: None,
+            last_refresh: 0,
+        },
+    )
+}
+
+fn record(group_name: &str) -> AccountGroupPolicy {
+    AccountGroupPolicy {
+        group_name: group_name.to_string(),
+        route_strategy: None,
+        account_max_inflight: None,
+        allowed_models_json: None,
+        fallback_groups_json: None,
+        cooldown_multiplier_millis: None,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+#[test]
+fn policy_record_is_parsed_with_normalized_strategy_and_lists() {
+    let policy = GroupRoutingPolicy::from_record(&AccountGroupPolicy {
+        route_strategy: Some("round_robin".to_string()),
+        account_max_inflight: Some(-3),
+        allowed_models_json: Some("[\" gpt-5* \", \"\"]".to_string()),
+        fallback_groups_json: Some("not-json".to_string()),
+        cooldown_multiplier_millis: Some(-1),
+        ..record("gp-parse")
+    });
+
+    assert_eq!(policy.route_strategy, Some("balanced"));
+    assert_eq!(policy.account_max_inflight, None);
+    assert_eq!(policy.allowed_models, vec!["gpt-5*".to_string()]);
+    assert!(policy.fallback_groups.is_empty());
+    assert_eq!(policy.cooldown_multiplier_millis, Some(0));
+}
+
+#[test]
+fn allowed_models_support_exact_and_prefix_patterns() {
+    let policy = GroupRoutingPolicy {
+        allowed_models: vec!["GPT-5*".to_string(), "o3".to_string()],
+        ..Default::default()
+    };
+
+    assert!(policy.allows_model("gpt-5.3-codex"));
+    assert!(policy.allows_model("O3"));
+    assert!(!policy.allows_model("o3-mini"));
+    assert!(!policy.allows_model("claude-sonnet"));
+    assert!(GroupRoutingPolicy::default().allows_model("anything"));
+}
+
+#[test]
+fn group_policy_hooks_follow_cached_policies() {
+    set_group_policies_for_tests(vec![
+        (
+            "gp-premium",
+            GroupRoutingPolicy {
+                route_strategy: Some("balanced"),
+                account_max_inflight: Some(8),
+                allowed_models: vec!["gpt-5*".to_string()],
+                fallback_groups: vec!["gp-shared".to_string(), "gp-premium".to_string()],
+                cooldown_multiplier_millis: Some(500),
+            },
+        ),
+        (
+            "gp-shared",
+            GroupRoutingPolicy {
+                fallback_groups: vec!["gp-batch".to_string(), "gp-premium".to_string()],
+                cooldown_multiplier_millis: Some(3000),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    assert_eq!(
+        group_fallback_chain("gp-premium"),
+        vec![
+            "gp-premium".to_string(),
+            "gp-shared".to_string(),
+            "gp-batch".to_string()
+        ]
+    );
+
+    let mut candidates = vec![
+        candidate("gp-acc-premium", Some("gp-premium")),
+        candidate("gp-acc-shared", Some("gp-shared")),
+        candidate("gp-acc-ungrouped", None),
+    ];
+    retain_candidates_allowed_for_model(&mut candidates, Some("o3"));
+    let remaining = candidates
+        .iter()
+        .map(|(account, _)| account.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(remaining, vec!["gp-acc-shared", "gp-acc-ungrouped"]);
+
+    let premium = vec![
+        candidate("gp-acc-p1", Some("gp-premium")),
+        candidate("gp-acc-p2", Some(" gp-premium ")),
+    ];
+    assert_eq!(shared_group_route_strategy(&premium), Some("balanced"));
+    assert_eq!(shared_group_route_strategy(&candidates), None);
+    assert_eq!(account_max_inflight_for(&premium[0].0, 2), 8);
+    assert_eq!(account_max_inflight_for(&candidates[0].0, 2), 2);
+
+    remember_candidate_groups(&premium);
+    remember_candidate_groups(&candidates);
+    let premium_multiplier = cooldown_multiplier_millis_for_account("gp-acc-p1");
+    let shared_multiplier = cooldown_multiplier_millis_for_account("gp-acc-shared");
+    assert_eq!(premium_multiplier, Some(500));
+    assert_eq!(
+        cooldown_multiplier_millis_for_account("gp-acc-ungrouped"),
+        None
+    );
+    assert_eq!(scale_cooldown_secs(45, premium_multiplier), 22);
+    assert_eq!(scale_cooldown_secs(45, shared_multiplier), 135);
+    assert_eq!(scale_cooldown_secs(45, None), 45);
+
+    invalidate_group_policy_cache();
+}
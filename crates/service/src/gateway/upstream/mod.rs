@@ -10,5 +10,6 @@ pub(super) mod support;
 
 pub(super) use attempt_flow::transport::send_async_stream_request;
 pub(super) use response::{
-    GatewayByteStream, GatewayByteStreamItem, GatewayStreamResponse, GatewayUpstreamResponse,
+    GatewayBodyCaptureCommit, GatewayByteStream, GatewayByteStreamItem, GatewayStreamResponse,
+    GatewayUpstreamResponse,
 };
@@ -7,9 +7,19 @@ use std::time::Instant;
 use tiny_http::Request;
 
 use super::super::GatewayUpstreamResponse;
+use super::bedrock::bedrock_stream_response;
+use crate::aggregate_api::azure::{
+    build_azure_openai_url, is_azure_openai_provider, resolve_azure_openai_deployment,
+    AZURE_OPENAI_API_KEY_HEADER,
+};
+use crate::aggregate_api::bedrock::{
+    build_bedrock_invoke_body, build_bedrock_invoke_url, is_bedrock_provider,
+    resolve_bedrock_signing, sign_bedrock_request, BedrockCredentials,
+};
 use crate::aggregate_api::{
-    AGGREGATE_API_AUTH_APIKEY, AGGREGATE_API_AUTH_USERPASS, AGGREGATE_API_PROVIDER_CLAUDE,
-    AGGREGATE_API_PROVIDER_CODEX, AGGREGATE_API_PROVIDER_COMPATIBLE, AGGREGATE_API_PROVIDER_GEMINI,
+    AGGREGATE_API_AUTH_APIKEY, AGGREGATE_API_AUTH_USERPASS, AGGREGATE_API_PROVIDER_AZURE_OPENAI,
+    AGGREGATE_API_PROVIDER_BEDROCK, AGGREGATE_API_PROVIDER_CLAUDE, AGGREGATE_API_PROVIDER_CODEX,
+    AGGREGATE_API_PROVIDER_COMPATIBLE, AGGREGATE_API_PROVIDER_GEMINI,
 };
 use crate::gateway::protocol_adapter::adapt_openai_responses_to_anthropic_messages;
 use crate::gateway::request_log::RequestLogUsage;
@@ -304,8 +314,10 @@ fn aggregate_upstream_model_for_log<'a>(
 }
 
 fn should_bridge_responses_to_anthropic(candidate: &AggregateApi, path: &str) -> bool {
-    normalize_provider_type_value(candidate.provider_type.as_str()) == AGGREGATE_API_PROVIDER_CLAUDE
-        && (path == "/v1/responses" || path.starts_with("/v1/responses?"))
+    matches!(
+        normalize_provider_type_value(candidate.provider_type.as_str()).as_str(),
+        AGGREGATE_API_PROVIDER_CLAUDE | AGGREGATE_API_PROVIDER_BEDROCK
+    ) && (path == "/v1/responses" || path.starts_with("/v1/responses?"))
 }
 
 fn responses_to_anthropic_messages_action_path(candidate: &AggregateApi, path: &str) -> String {
@@ -360,6 +372,17 @@ fn parse_auth_config(
         if auth_type == AGGREGATE_API_AUTH_USERPASS {
             return Ok((AggregateApiAuthConfig::UserPassBasic, injected_headers));
         }
+        if is_azure_openai_provider(candidate.provider_type.as_str()) {
+            // 中文注释：Azure OpenAI 的 API key 走 api-key 头原样传递，而不是 Bearer。
+            injected_headers.insert(AZURE_OPENAI_API_KEY_HEADER.to_string());
+            return Ok((
+                AggregateApiAuthConfig::ApiKeyHeader {
+                    name: AZURE_OPENAI_API_KEY_HEADER.to_string(),
+                    format: "raw".to_string(),
+                },
+                injected_headers,
+            ));
+        }
         return Ok((
             AggregateApiAuthConfig::ApiKeyDefaultBearer,
             injected_headers,
@@ -497,6 +520,8 @@ fn should_skip_forward_header(name: &str) -> bool {
             | "transfer-encoding"
             | "upgrade"
             | "host"
+            | "x-codexmanager-batch-id"
+            | "x-codexmanager-fan-out-child"
     )
 }
 
@@ -724,6 +749,8 @@ fn normalize_provider_type_value(value: &str) -> String {
             AGGREGATE_API_PROVIDER_GEMINI.to_string()
         }
         "compatible" => AGGREGATE_API_PROVIDER_COMPATIBLE.to_string(),
+        "azure_openai" | "azure" | "azure_oai" => AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string(),
+        "bedrock" | "aws_bedrock" => AGGREGATE_API_PROVIDER_BEDROCK.to_string(),
         _ => AGGREGATE_API_PROVIDER_CODEX.to_string(),
     }
 }
@@ -962,6 +989,56 @@ fn build_anthropic_bridge_aggregate_api_request(
     Ok(builder)
 }
 
+/// Bedrock 不走通用鉴权头：按最终请求体做 SigV4 签名，每次重试重新计算签名时间。
+fn build_bedrock_aggregate_api_request(
+    client: &reqwest::blocking::Client,
+    url: reqwest::Url,
+    body: &Bytes,
+    credentials: &BedrockCredentials,
+    region: &str,
+    request_deadline: Option<Instant>,
+    is_stream: bool,
+) -> Result<reqwest::blocking::RequestBuilder, String> {
+    let mut builder = client.post(url.clone());
+    if let Some(timeout) =
+        super::super::support::deadline::send_timeout(request_deadline, is_stream)
+    {
+        builder = builder.timeout(timeout);
+    }
+    for (name, value) in sign_bedrock_request(
+        credentials,
+        region,
+        "POST",
+        &url,
+        body.as_ref(),
+        chrono::Utc::now(),
+    ) {
+        if name == "host" {
+            continue;
+        }
+        builder = builder.header(
+            HeaderName::from_static(name),
+            HeaderValue::from_str(value.as_str())
+                .map_err(|_| "invalid bedrock credentials".to_string())?,
+        );
+    }
+    let accept = if is_stream {
+        "application/vnd.amazon.eventstream"
+    } else {
+        "application/json"
+    };
+    Ok(builder
+        .header(
+            HeaderName::from_static("content-type"),
+            HeaderValue::from_static("application/json"),
+        )
+        .header(
+            HeaderName::from_static("accept"),
+            HeaderValue::from_static(accept),
+        )
+        .body(body.clone()))
+}
+
 /// 函数 `resolve_aggregate_api_rotation_candidates`
 ///
 /// 作者: gaohongshun
@@ -1033,6 +1110,7 @@ pub(in super::super) struct AggregateProxyRequest<'a> {
     pub gateway_mode_for_log: Option<&'a str>,
     pub route_strategy_for_log: Option<&'a str>,
     pub route_source_for_log: Option<&'a str>,
+    pub route_decision_for_log: Option<&'a str>,
     pub client_model_for_log: Option<&'a str>,
     pub model_for_log: Option<&'a str>,
     pub model_source_for_log: Option<&'a str>,
@@ -1065,6 +1143,7 @@ pub(in super::super) fn proxy_aggregate_request(
         gateway_mode_for_log,
         route_strategy_for_log,
         route_source_for_log,
+        route_decision_for_log,
         client_model_for_log,
         model_for_log,
         model_source_for_log,
@@ -1149,6 +1228,20 @@ pub(in super::super) fn proxy_aggregate_request(
         } else {
             response_adapter
         };
+        let bedrock_signing = if is_bedrock_provider(candidate.provider_type.as_str()) {
+            match resolve_bedrock_signing(&candidate, secret.as_str()) {
+                Ok(signing) => Some(signing),
+                Err(err) => {
+                    last_attempt_url = Some(candidate_url.clone());
+                    last_attempt_supplier_name = candidate_supplier_name.clone();
+                    last_attempt_error = Some(err);
+                    last_failure_status = 502;
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
         let (auth_config, injected_headers) = match parse_auth_config(&candidate) {
             Ok(value) => value,
             Err(err) => {
@@ -1160,23 +1253,50 @@ pub(in super::super) fn proxy_aggregate_request(
             }
         };
 
-        let base_upstream_url =
-            match build_upstream_url(candidate_url.as_str(), effective_path.as_str()) {
-                Ok(url) => url,
-                Err(_) => {
+        let azure_deployment = if is_azure_openai_provider(candidate.provider_type.as_str()) {
+            match resolve_azure_openai_deployment(
+                storage,
+                candidate_id.as_str(),
+                candidate_upstream_model.as_deref().unwrap_or(""),
+            ) {
+                Ok(deployment) => Some(deployment),
+                Err(err) => {
                     last_attempt_url = Some(candidate_url.clone());
                     last_attempt_supplier_name = candidate_supplier_name.clone();
-                    last_attempt_error = Some("invalid aggregate api url".to_string());
+                    last_attempt_error = Some(err);
                     last_failure_status = 502;
                     continue;
                 }
-            };
+            }
+        } else {
+            None
+        };
+        let base_upstream_url = match azure_deployment.as_deref() {
+            Some(deployment) => {
+                build_azure_openai_url(candidate_url.as_str(), effective_path.as_str(), deployment)
+            }
+            None => build_upstream_url(candidate_url.as_str(), effective_path.as_str())
+                .map_err(|_| "invalid aggregate api url".to_string()),
+        };
+        let base_upstream_url = match base_upstream_url {
+            Ok(url) => url,
+            Err(err) => {
+                last_attempt_url = Some(candidate_url.clone());
+                last_attempt_supplier_name = candidate_supplier_name.clone();
+                last_attempt_error = Some(err);
+                last_failure_status = 502;
+                continue;
+            }
+        };
         let candidate_body = rewrite_body_for_candidate_transport(
             body,
             &candidate,
             path,
             base_upstream_url.as_str(),
         );
+        // 中文注释：Azure 以部署名寻址，请求体 model 同步改成部署名，Responses API 依赖它选部署。
+        let candidate_body =
+            rewrite_body_model_override(&candidate_body, azure_deployment.as_deref());
         let candidate_body = rewrite_minimax_responses_body(
             &candidate_body,
             candidate.url.as_str(),
@@ -1200,6 +1320,30 @@ pub(in super::super) fn proxy_aggregate_request(
         } else {
             candidate_body
         };
+        // 中文注释：Bedrock 的模型与流式模式都编码在 URL 中，需要在请求体定稿后再确定地址。
+        let (base_upstream_url, upstream_body) = if bedrock_signing.is_some() {
+            let bedrock_request = build_bedrock_invoke_body(upstream_body.as_ref()).and_then(
+                |(bedrock_body, body_model)| {
+                    let model_id = body_model
+                        .or_else(|| candidate_upstream_model.clone())
+                        .unwrap_or_default();
+                    build_bedrock_invoke_url(candidate_url.as_str(), model_id.as_str(), is_stream)
+                        .map(|url| (url, bedrock_body))
+                },
+            );
+            match bedrock_request {
+                Ok(value) => value,
+                Err(err) => {
+                    last_attempt_url = Some(candidate_url.clone());
+                    last_attempt_supplier_name = candidate_supplier_name.clone();
+                    last_attempt_error = Some(err);
+                    last_failure_status = 502;
+                    continue;
+                }
+            }
+        } else {
+            (base_upstream_url, upstream_body)
+        };
 
         let mut succeeded = false;
         for attempt_idx in 0..=AGGREGATE_API_RETRY_ATTEMPTS_PER_CHANNEL {
@@ -1241,6 +1385,7 @@ pub(in super::super) fn proxy_aggregate_request(
                         aggregate_api_supplier_name: candidate_supplier_name.as_deref(),
                         aggregate_api_url: Some(candidate_url.as_str()),
                         attempted_aggregate_api_ids: Some(attempted_aggregate_api_ids.as_slice()),
+                        route_decision: route_decision_for_log,
                         upstream_model: candidate_upstream_model.as_deref(),
                         actual_source_kind: Some("aggregate_api"),
                         actual_source_id: Some(candidate_id.as_str()),
@@ -1288,7 +1433,17 @@ pub(in super::super) fn proxy_aggregate_request(
             let request_ref = request.as_ref().ok_or_else(|| {
                 "aggregate api request already consumed before upstream attempt".to_string()
             })?;
-            let builder = if bridge_responses_to_anthropic {
+            let builder = if let Some((credentials, region)) = bedrock_signing.as_ref() {
+                build_bedrock_aggregate_api_request(
+                    &client,
+                    url.clone(),
+                    &upstream_body,
+                    credentials,
+                    region.as_str(),
+                    request_deadline,
+                    is_stream,
+                )?
+            } else if bridge_responses_to_anthropic {
                 build_anthropic_bridge_aggregate_api_request(
                     &client,
                     request_ref,
@@ -1384,10 +1539,15 @@ pub(in super::super) fn proxy_aggregate_request(
             let request = request.take().ok_or_else(|| {
                 "aggregate api request already consumed before bridge".to_string()
             })?;
+            let upstream = if bedrock_signing.is_some() && is_stream {
+                GatewayUpstreamResponse::Stream(bedrock_stream_response(upstream))
+            } else {
+                GatewayUpstreamResponse::Blocking(upstream)
+            };
             let bridge = super::super::super::respond_with_upstream(
                 request,
-                GatewayUpstreamResponse::Blocking(upstream),
-                inflight_guard,
+                upstream,
+                Some(inflight_guard),
                 response_adapter_for_candidate,
                 passthrough_sse_protocol,
                 None,
@@ -1480,6 +1640,7 @@ pub(in super::super) fn proxy_aggregate_request(
                     aggregate_api_supplier_name: candidate_supplier_name.as_deref(),
                     aggregate_api_url: Some(candidate_url.as_str()),
                     attempted_aggregate_api_ids: Some(attempted_aggregate_api_ids.as_slice()),
+                    route_decision: route_decision_for_log,
                     upstream_model: candidate_upstream_model.as_deref(),
                     actual_source_kind: Some("aggregate_api"),
                     actual_source_id: Some(candidate_id.as_str()),
@@ -1554,6 +1715,7 @@ pub(in super::super) fn proxy_aggregate_request(
             aggregate_api_supplier_name: last_attempt_supplier_name.as_deref(),
             aggregate_api_url: last_attempt_url.as_deref(),
             attempted_aggregate_api_ids: Some(attempted_aggregate_api_ids.as_slice()),
+            route_decision: route_decision_for_log,
             upstream_model: last_attempt_upstream_model.as_deref(),
             actual_source_kind: last_attempt_id.as_deref().map(|_| "aggregate_api"),
             actual_source_id: last_attempt_id.as_deref(),
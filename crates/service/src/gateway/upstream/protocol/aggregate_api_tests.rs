@@ -2,13 +2,14 @@ use codexmanager_core::storage::{now_ts, AggregateApi, Storage};
 
 use super::{
     build_anthropic_bridge_aggregate_api_request, build_upstream_url, effective_action_path,
-    resolve_aggregate_api_rotation_candidates, resolve_passthrough_sse_protocol,
+    parse_auth_config, resolve_aggregate_api_rotation_candidates, resolve_passthrough_sse_protocol,
     responses_to_anthropic_messages_action_path, rewrite_body_model_override,
-    should_bridge_responses_to_anthropic,
+    should_bridge_responses_to_anthropic, AggregateApiAuthConfig,
 };
 use crate::aggregate_api::{
-    AGGREGATE_API_AUTH_APIKEY, AGGREGATE_API_PROVIDER_CLAUDE, AGGREGATE_API_PROVIDER_CODEX,
-    AGGREGATE_API_PROVIDER_COMPATIBLE, AGGREGATE_API_PROVIDER_GEMINI,
+    AGGREGATE_API_AUTH_APIKEY, AGGREGATE_API_PROVIDER_AZURE_OPENAI, AGGREGATE_API_PROVIDER_BEDROCK,
+    AGGREGATE_API_PROVIDER_CLAUDE, AGGREGATE_API_PROVIDER_CODEX, AGGREGATE_API_PROVIDER_COMPATIBLE,
+    AGGREGATE_API_PROVIDER_GEMINI,
 };
 use crate::gateway::{PassthroughSseProtocol, ResponseAdapter};
 use bytes::Bytes;
@@ -256,6 +257,68 @@ fn gemini_native_candidates_resolve_to_gemini_provider_only() {
     assert_eq!(candidate_ids, vec!["agg-gemini"]);
 }
 
+#[test]
+fn azure_openai_candidate_joins_codex_pool_with_api_key_header_auth() {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    let mut azure = aggregate_api_with_action(None);
+    azure.id = "agg-azure".to_string();
+    azure.provider_type = AGGREGATE_API_PROVIDER_AZURE_OPENAI.to_string();
+    azure.url = "https://demo.openai.azure.com".to_string();
+    storage
+        .insert_aggregate_api(&azure)
+        .expect("insert azure aggregate api");
+
+    let codex_ids = resolve_aggregate_api_rotation_candidates(&storage, "openai", None)
+        .expect("resolve codex candidates")
+        .into_iter()
+        .map(|item| item.id)
+        .collect::<Vec<_>>();
+    assert_eq!(codex_ids, vec!["agg-azure".to_string()]);
+    assert!(resolve_aggregate_api_rotation_candidates(&storage, "anthropic_native", None).is_err());
+
+    let (auth, injected) = parse_auth_config(&azure).expect("parse azure auth");
+    assert!(matches!(
+        auth,
+        AggregateApiAuthConfig::ApiKeyHeader { ref name, ref format }
+            if name == "api-key" && format == "raw"
+    ));
+    assert!(injected.contains("api-key"));
+
+    azure.auth_params_json = Some(r#"{"location":"header","name":"Authorization"}"#.to_string());
+    let (auth, _) = parse_auth_config(&azure).expect("parse custom azure auth");
+    assert!(matches!(
+        auth,
+        AggregateApiAuthConfig::ApiKeyHeader { ref name, ref format }
+            if name == "Authorization" && format == "bearer"
+    ));
+}
+
+#[test]
+fn bedrock_candidate_joins_claude_pool_and_bridges_responses() {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    let mut bedrock = aggregate_api_with_action(None);
+    bedrock.id = "agg-bedrock".to_string();
+    bedrock.provider_type = AGGREGATE_API_PROVIDER_BEDROCK.to_string();
+    bedrock.url = "https://bedrock-runtime.us-east-1.amazonaws.com".to_string();
+    storage
+        .insert_aggregate_api(&bedrock)
+        .expect("insert bedrock aggregate api");
+
+    let claude_ids = resolve_aggregate_api_rotation_candidates(&storage, "anthropic_native", None)
+        .expect("resolve claude candidates")
+        .into_iter()
+        .map(|item| item.id)
+        .collect::<Vec<_>>();
+    assert_eq!(claude_ids, vec!["agg-bedrock".to_string()]);
+    assert!(resolve_aggregate_api_rotation_candidates(&storage, "openai", None).is_err());
+    assert!(should_bridge_responses_to_anthropic(
+        &bedrock,
+        "/v1/responses"
+    ));
+}
+
 #[test]
 fn compatible_candidate_resolves_for_codex_and_anthropic_without_protocol_bridge() {
     let storage = Storage::open_in_memory().expect("open storage");
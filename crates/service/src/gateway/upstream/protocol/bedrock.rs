@@ -0,0 +1,320 @@
+use base64::Engine;
+use bytes::Bytes;
+use reqwest::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc;
+use std::thread;
+
+use super::super::response::{GatewayByteStream, GatewayByteStreamItem, GatewayStreamResponse};
+
+const EVENT_STREAM_PRELUDE_BYTES: usize = 12;
+const EVENT_STREAM_MESSAGE_CRC_BYTES: usize = 4;
+const EVENT_STREAM_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+const EVENT_STREAM_READ_CHUNK_BYTES: usize = 8 * 1024;
+const EVENT_STREAM_CHANNEL_CAPACITY: usize = 128;
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// `application/vnd.amazon.eventstream` 的单条消息；只保留字符串头，其余类型按长度跳过。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EventStreamMessage {
+    headers: HashMap<String, String>,
+    payload: Vec<u8>,
+}
+
+impl EventStreamMessage {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    pub(crate) fn payload(&self) -> &[u8] {
+        self.payload.as_slice()
+    }
+}
+
+/// 增量解码 AWS event-stream 二进制帧，prelude 与整帧 CRC 都会校验。
+#[derive(Debug, Default)]
+pub(crate) struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn has_pending_bytes(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    pub(crate) fn next_message(&mut self) -> Result<Option<EventStreamMessage>, String> {
+        if self.buffer.len() < EVENT_STREAM_PRELUDE_BYTES {
+            return Ok(None);
+        }
+        let total_len = read_u32(&self.buffer[0..4]) as usize;
+        let headers_len = read_u32(&self.buffer[4..8]) as usize;
+        let prelude_crc = read_u32(&self.buffer[8..12]);
+        if crc32(&self.buffer[0..8]) != prelude_crc {
+            return Err("bedrock event stream prelude crc mismatch".to_string());
+        }
+        if total_len > EVENT_STREAM_MAX_MESSAGE_BYTES
+            || total_len < EVENT_STREAM_PRELUDE_BYTES + headers_len + EVENT_STREAM_MESSAGE_CRC_BYTES
+        {
+            return Err(format!(
+                "bedrock event stream invalid message length: {total_len}"
+            ));
+        }
+        if self.buffer.len() < total_len {
+            return Ok(None);
+        }
+        let frame = self.buffer.drain(..total_len).collect::<Vec<_>>();
+        let crc_offset = total_len - EVENT_STREAM_MESSAGE_CRC_BYTES;
+        if crc32(&frame[..crc_offset]) != read_u32(&frame[crc_offset..]) {
+            return Err("bedrock event stream message crc mismatch".to_string());
+        }
+        let headers_end = EVENT_STREAM_PRELUDE_BYTES + headers_len;
+        let headers = parse_headers(&frame[EVENT_STREAM_PRELUDE_BYTES..headers_end])?;
+        Ok(Some(EventStreamMessage {
+            headers,
+            payload: frame[headers_end..crc_offset].to_vec(),
+        }))
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn parse_headers(mut raw: &[u8]) -> Result<HashMap<String, String>, String> {
+    let invalid = || "bedrock event stream invalid header block".to_string();
+    let mut headers = HashMap::new();
+    while !raw.is_empty() {
+        let name_len = raw[0] as usize;
+        let name = raw.get(1..1 + name_len).ok_or_else(invalid)?;
+        let name = String::from_utf8_lossy(name).to_string();
+        let value_type = *raw.get(1 + name_len).ok_or_else(invalid)?;
+        raw = &raw[2 + name_len..];
+        let value_len = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let len = raw.get(..2).ok_or_else(invalid)?;
+                raw = &raw[2..];
+                u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            _ => return Err(invalid()),
+        };
+        let value = raw.get(..value_len).ok_or_else(invalid)?;
+        if value_type == 7 {
+            headers.insert(name, String::from_utf8_lossy(value).to_string());
+        }
+        raw = &raw[value_len..];
+    }
+    Ok(headers)
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0_u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                0xEDB8_8320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0_u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+fn anthropic_sse_frame(event_type: &str, data: &str) -> Vec<u8> {
+    format!("event: {event_type}\ndata: {data}\n\n").into_bytes()
+}
+
+fn anthropic_error_frame(error_type: &str, message: &str) -> Vec<u8> {
+    let payload = json!({
+        "type": "error",
+        "error": {
+            "type": error_type,
+            "message": message,
+        }
+    });
+    anthropic_sse_frame("error", payload.to_string().as_str())
+}
+
+/// 把一条 Bedrock 消息转成 Anthropic SSE 帧；返回值第二项表示流是否已因异常终止。
+pub(crate) fn anthropic_sse_from_bedrock_message(message: &EventStreamMessage) -> (Vec<u8>, bool) {
+    match message.header(":message-type").unwrap_or("event") {
+        "event" => {
+            if message.header(":event-type").unwrap_or("chunk") != "chunk" {
+                return (Vec::new(), false);
+            }
+            let decoded = serde_json::from_slice::<Value>(message.payload())
+                .ok()
+                .and_then(|value| {
+                    value
+                        .get("bytes")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .and_then(|encoded| {
+                    base64::engine::general_purpose::STANDARD
+                        .decode(encoded.as_bytes())
+                        .ok()
+                })
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok());
+            let Some(event) = decoded else {
+                return (
+                    anthropic_error_frame("api_error", "bedrock event stream chunk undecodable"),
+                    true,
+                );
+            };
+            let event_type = event
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("message_delta")
+                .to_string();
+            (
+                anthropic_sse_frame(event_type.as_str(), event.to_string().as_str()),
+                false,
+            )
+        }
+        "exception" => {
+            let exception_type = message
+                .header(":exception-type")
+                .unwrap_or("internalServerException");
+            let detail = serde_json::from_slice::<Value>(message.payload())
+                .ok()
+                .and_then(|value| {
+                    value
+                        .get("message")
+                        .or_else(|| value.get("Message"))
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| String::from_utf8_lossy(message.payload()).to_string());
+            (
+                anthropic_error_frame(
+                    bedrock_exception_error_type(exception_type),
+                    format!("bedrock {exception_type}: {detail}").as_str(),
+                ),
+                true,
+            )
+        }
+        _ => {
+            let code = message.header(":error-code").unwrap_or("error");
+            let detail = message.header(":error-message").unwrap_or_default();
+            (
+                anthropic_error_frame("api_error", format!("bedrock {code}: {detail}").as_str()),
+                true,
+            )
+        }
+    }
+}
+
+fn bedrock_exception_error_type(exception_type: &str) -> &'static str {
+    match exception_type {
+        "throttlingException" | "serviceQuotaExceededException" => "rate_limit_error",
+        "validationException" => "invalid_request_error",
+        "accessDeniedException" => "permission_error",
+        "modelStreamErrorException" | "modelTimeoutException" => "overloaded_error",
+        _ => "api_error",
+    }
+}
+
+/// 后台线程读取 Bedrock 二进制流并逐帧转成 Anthropic SSE 字节流。
+pub(crate) fn bedrock_event_stream_to_anthropic_sse<R>(mut upstream: R) -> GatewayByteStream
+where
+    R: Read + Send + 'static,
+{
+    let (tx, rx) = mpsc::sync_channel::<GatewayByteStreamItem>(EVENT_STREAM_CHANNEL_CAPACITY);
+    thread::spawn(move || {
+        let mut decoder = EventStreamDecoder::default();
+        let mut buffer = vec![0_u8; EVENT_STREAM_READ_CHUNK_BYTES];
+        loop {
+            let read = match upstream.read(&mut buffer) {
+                Ok(0) => {
+                    let item = if decoder.has_pending_bytes() {
+                        GatewayByteStreamItem::Error(
+                            "bedrock event stream truncated mid-message".to_string(),
+                        )
+                    } else {
+                        GatewayByteStreamItem::Eof
+                    };
+                    let _ = tx.send(item);
+                    return;
+                }
+                Ok(read) => read,
+                Err(err) => {
+                    let _ = tx.send(GatewayByteStreamItem::Error(err.to_string()));
+                    return;
+                }
+            };
+            decoder.push(&buffer[..read]);
+            loop {
+                match decoder.next_message() {
+                    Ok(Some(message)) => {
+                        let (frame, terminal) = anthropic_sse_from_bedrock_message(&message);
+                        if !frame.is_empty()
+                            && tx
+                                .send(GatewayByteStreamItem::Chunk(Bytes::from(frame)))
+                                .is_err()
+                        {
+                            return;
+                        }
+                        if terminal {
+                            let _ = tx.send(GatewayByteStreamItem::Eof);
+                            return;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        log::warn!("event=bedrock_event_stream_decode_failed err={err}");
+                        let _ = tx.send(GatewayByteStreamItem::Chunk(Bytes::from(
+                            anthropic_error_frame("api_error", err.as_str()),
+                        )));
+                        let _ = tx.send(GatewayByteStreamItem::Eof);
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    GatewayByteStream::from_receiver(rx)
+}
+
+/// 成功的 InvokeModelWithResponseStream 响应改包成 text/event-stream，交给 Anthropic SSE 链路。
+pub(crate) fn bedrock_stream_response(
+    upstream: reqwest::blocking::Response,
+) -> GatewayStreamResponse {
+    let status = upstream.status();
+    let mut headers = upstream.headers().clone();
+    headers.remove(CONTENT_LENGTH);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    GatewayStreamResponse::new(
+        status,
+        headers,
+        bedrock_event_stream_to_anthropic_sse(upstream),
+    )
+}
+
+#[cfg(test)]
+#[path = "bedrock_tests.rs"]
+mod tests;
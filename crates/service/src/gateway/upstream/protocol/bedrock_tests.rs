@@ -0,0 +1,178 @@
+use super::*;
+use std::io::Cursor;
+use std::time::Duration;
+use tiny_http::{Header, Response, Server};
+
+fn encode_message(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut header_bytes = Vec::new();
+    for (name, value) in headers {
+        header_bytes.push(name.len() as u8);
+        header_bytes.extend_from_slice(name.as_bytes());
+        header_bytes.push(7);
+        header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        header_bytes.extend_from_slice(value.as_bytes());
+    }
+    let total_len = 12 + header_bytes.len() + payload.len() + 4;
+    let mut message = Vec::with_capacity(total_len);
+    message.extend_from_slice(&(total_len as u32).to_be_bytes());
+    message.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+    let prelude_crc = crc32(&message);
+    message.extend_from_slice(&prelude_crc.to_be_bytes());
+    message.extend_from_slice(&header_bytes);
+    message.extend_from_slice(payload);
+    let message_crc = crc32(&message);
+    message.extend_from_slice(&message_crc.to_be_bytes());
+    message
+}
+
+fn chunk_message(event: Value) -> Vec<u8> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(event.to_string());
+    encode_message(
+        &[
+            (":message-type", "event"),
+            (":event-type", "chunk"),
+            (":content-type", "application/json"),
+        ],
+        json!({ "bytes": encoded, "p": "abcd" })
+            .to_string()
+            .as_bytes(),
+    )
+}
+
+fn sample_stream() -> Vec<u8> {
+    let mut stream = Vec::new();
+    for event in [
+        json!({"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":7,"output_tokens":0}}}),
+        json!({"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"hi"}}),
+        json!({"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":2}}),
+        json!({"type":"message_stop"}),
+    ] {
+        stream.extend(chunk_message(event));
+    }
+    stream
+}
+
+#[test]
+fn crc32_matches_ieee_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+#[test]
+fn decoder_reassembles_messages_split_across_reads() {
+    let stream = sample_stream();
+    let mut decoder = EventStreamDecoder::default();
+    let mut messages = Vec::new();
+    for piece in stream.chunks(7) {
+        decoder.push(piece);
+        while let Some(message) = decoder.next_message().expect("decode message") {
+            messages.push(message);
+        }
+    }
+
+    assert_eq!(messages.len(), 4);
+    assert!(!decoder.has_pending_bytes());
+    assert_eq!(messages[0].header(":event-type"), Some("chunk"));
+    let (frame, terminal) = anthropic_sse_from_bedrock_message(&messages[1]);
+    assert!(!terminal);
+    let frame = String::from_utf8(frame).expect("utf8 frame");
+    assert!(frame.starts_with("event: content_block_delta\ndata: "));
+    assert!(frame.ends_with("\n\n"));
+    assert!(frame.contains(r#""text":"hi""#));
+}
+
+#[test]
+fn decoder_rejects_corrupted_message_crc() {
+    let mut message = chunk_message(json!({"type":"message_stop"}));
+    let last = message.len() - 1;
+    message[last] ^= 0xFF;
+    let mut decoder = EventStreamDecoder::default();
+    decoder.push(&message);
+
+    let err = decoder.next_message().expect_err("crc mismatch");
+    assert!(err.contains("crc"));
+}
+
+#[test]
+fn exception_message_becomes_terminal_anthropic_error_event() {
+    let message = encode_message(
+        &[
+            (":message-type", "exception"),
+            (":exception-type", "throttlingException"),
+        ],
+        br#"{"message":"Too many requests"}"#,
+    );
+    let sse = bedrock_event_stream_to_anthropic_sse(Cursor::new(
+        [message, chunk_message(json!({"type":"message_stop"}))].concat(),
+    ))
+    .read_all_bytes()
+    .expect("read converted stream");
+    let sse = String::from_utf8(sse.to_vec()).expect("utf8 sse");
+
+    assert!(sse.starts_with("event: error\ndata: "));
+    assert!(sse.contains("rate_limit_error"));
+    assert!(sse.contains("bedrock throttlingException: Too many requests"));
+    assert!(!sse.contains("message_stop"));
+}
+
+#[test]
+fn truncated_stream_surfaces_error_after_complete_frames() {
+    let stream = sample_stream();
+    let truncated = stream[..stream.len() - 3].to_vec();
+    let err = bedrock_event_stream_to_anthropic_sse(Cursor::new(truncated))
+        .read_all_bytes()
+        .expect_err("truncated stream");
+    assert!(err.contains("truncated"));
+}
+
+#[test]
+fn stub_server_event_stream_is_delivered_as_anthropic_sse() {
+    let server = Server::http("127.0.0.1:0").expect("start mock server");
+    let url = format!(
+        "http://{}/model/demo/invoke-with-response-stream",
+        server.server_addr()
+    );
+    let join = std::thread::spawn(move || {
+        let request = server
+            .recv_timeout(Duration::from_secs(2))
+            .expect("receive bedrock request")
+            .expect("bedrock request present");
+        let response = Response::from_data(sample_stream()).with_header(
+            Header::from_bytes("Content-Type", "application/vnd.amazon.eventstream")
+                .expect("content type header"),
+        );
+        request.respond(response).expect("respond bedrock stream");
+    });
+
+    let upstream = reqwest::blocking::Client::new()
+        .post(url.as_str())
+        .body("{}")
+        .send()
+        .expect("send bedrock request");
+    let response = bedrock_stream_response(upstream);
+    join.join().expect("join mock server");
+
+    assert_eq!(
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+        Some("text/event-stream")
+    );
+    assert!(response.headers().get(CONTENT_LENGTH).is_none());
+    let sse =
+        String::from_utf8(response.read_all_bytes().expect("read sse").to_vec()).expect("utf8 sse");
+    let events = sse
+        .lines()
+        .filter_map(|line| line.strip_prefix("event: "))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            "message_start",
+            "content_block_delta",
+            "message_delta",
+            "message_stop"
+        ]
+    );
+    assert!(sse.contains(r#""input_tokens":7"#));
+}
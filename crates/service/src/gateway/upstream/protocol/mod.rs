@@ -1 +1,2 @@
 pub(super) mod aggregate_api;
+pub(super) mod bedrock;
@@ -565,6 +565,15 @@ fn proxy_with_aggregate_candidates(
         model_for_log,
         aggregate_api_id,
     );
+    let route_decision_for_log =
+        super::super::cost_route::apply_cheapest_route_to_aggregate_candidates(
+            storage,
+            &mut aggregate_api_candidates,
+            key_id,
+            model_for_log,
+            body.as_ref(),
+            aggregate_api_id,
+        );
     super::protocol::aggregate_api::prepare_first_aggregate_candidate_client(
         aggregate_api_candidates.as_slice(),
         trace_id,
@@ -590,6 +599,7 @@ fn proxy_with_aggregate_candidates(
             } else {
                 "route_strategy"
             }),
+            route_decision_for_log: route_decision_for_log.as_deref(),
             client_model_for_log,
             model_for_log,
             model_source_for_log,
@@ -672,6 +682,8 @@ pub(in super::super) fn proxy_validated_request(
         path,
         passthrough_body,
         body,
+        background_request_body: _background_request_body,
+        fan_out_request_body: _fan_out_request_body,
         is_stream,
         has_prompt_cache_key,
         request_shape,
@@ -780,7 +792,73 @@ pub(in super::super) fn proxy_validated_request(
         }
     };
 
-    if should_try_provider_executor_aggregate_route(execution_plan, configured_model.as_ref()) {
+    // 中文注释：聚合 API 按候选供应商切换响应适配，缓存条目无法据此回放，只覆盖账号池路径；
+    // 直接走聚合 API 的路由既不查也不写缓存。
+    let aggregate_route_first =
+        should_try_provider_executor_aggregate_route(execution_plan, configured_model.as_ref());
+    let response_cache = if aggregate_route_first {
+        None
+    } else {
+        super::super::response_cache::resolve_response_cache_target(
+            &storage,
+            trace_id.as_str(),
+            key_id.as_str(),
+            protocol_type.as_str(),
+            original_path.as_str(),
+            path.as_str(),
+            request_method.as_str(),
+            client_is_stream,
+            model_for_log.as_deref(),
+            reasoning_for_log.as_deref(),
+            passthrough_body.as_ref(),
+        )
+    };
+    let request = match response_cache.as_ref() {
+        Some(target) => match super::super::response_cache::maybe_respond_from_response_cache(
+            request,
+            target,
+            super::super::response_cache::ResponseCacheReplay {
+                storage: &storage,
+                trace_id: trace_id.as_str(),
+                key_id: key_id.as_str(),
+                original_path: original_path.as_str(),
+                path: path.as_str(),
+                request_method: request_method.as_str(),
+                response_adapter,
+                gemini_stream_output_mode,
+                tool_name_restore_map: &tool_name_restore_map,
+                client_is_stream,
+                gateway_mode_for_log: gateway_mode_for_log.as_deref(),
+                client_model_for_log: client_model_for_log.as_deref(),
+                model_for_log: model_for_log.as_deref(),
+                model_source_for_log: model_source_for_log.as_deref(),
+                client_reasoning_for_log: client_reasoning_for_log.as_deref(),
+                reasoning_for_log: reasoning_for_log.as_deref(),
+                reasoning_source_for_log: reasoning_source_for_log.as_deref(),
+                service_tier_for_log: service_tier_for_log.as_deref(),
+                effective_service_tier_for_log: effective_service_tier_for_log.as_deref(),
+                service_tier_source_for_log: service_tier_source_for_log.as_deref(),
+                started_at,
+            },
+        )? {
+            Some(request) => request,
+            None => return Ok(()),
+        },
+        None => request,
+    };
+
+    // 中文注释：结构化输出校验只覆盖账号池路径，聚合 API 路由记一条 skipped 结果。
+    let structured_output = super::super::structured_output::resolve_structured_output_target(
+        &storage,
+        trace_id.as_str(),
+        key_id.as_str(),
+        model_for_log.as_deref(),
+        request_method.as_str(),
+        client_is_stream,
+        body.as_ref(),
+    );
+
+    if aggregate_route_first {
         let (aggregate_path, aggregate_body) = if is_hybrid_account_first_route(execution_plan) {
             (passthrough_path.as_str(), &passthrough_body)
         } else {
@@ -793,6 +871,11 @@ pub(in super::super) fn proxy_validated_request(
             model_for_log.as_deref(),
         ) {
             Ok(aggregate_api_candidates) => {
+                if structured_output.is_some() {
+                    super::super::structured_output::record_aggregate_route_skipped(
+                        trace_id.as_str(),
+                    );
+                }
                 return proxy_with_aggregate_candidates(
                     request,
                     &storage,
@@ -973,6 +1056,23 @@ pub(in super::super) fn proxy_validated_request(
         }
     }
 
+    let response_store_turn = super::super::response_store::resolve_response_store_turn(
+        key_id.as_str(),
+        original_path.as_str(),
+        path.as_str(),
+        request_method.as_str(),
+        passthrough_body.as_ref(),
+    );
+    let previous_response_expansion =
+        super::super::response_store::resolve_previous_response_expansion(
+            &storage,
+            trace_id.as_str(),
+            key_id.as_str(),
+            original_path.as_str(),
+            path.as_str(),
+            passthrough_body.as_ref(),
+            body.as_ref(),
+        );
     let context = GatewayUpstreamExecutionContext::new(
         &trace_id,
         &storage,
@@ -997,7 +1097,11 @@ pub(in super::super) fn proxy_validated_request(
         super::super::request_log::estimate_input_tokens_from_body(body.as_ref()),
         setup.candidate_count,
         setup.account_max_inflight,
-    );
+    )
+    .with_response_cache(response_cache.as_ref())
+    .with_response_store(response_store_turn.as_ref())
+    .with_previous_response_expansion(previous_response_expansion.as_ref())
+    .with_structured_output(structured_output.as_ref());
     let allow_openai_fallback = setup.upstream_fallback_base.is_some();
     let disable_challenge_stateless_retry = !(protocol_type == PROTOCOL_ANTHROPIC_NATIVE
         && body.len() <= 2 * 1024)
@@ -1078,6 +1182,11 @@ pub(in super::super) fn proxy_validated_request(
             model_for_log.as_deref(),
         ) {
             Ok(aggregate_api_candidates) => {
+                if structured_output.is_some() {
+                    super::super::structured_output::record_aggregate_route_skipped(
+                        trace_id.as_str(),
+                    );
+                }
                 return proxy_with_aggregate_candidates(
                     request,
                     &storage,
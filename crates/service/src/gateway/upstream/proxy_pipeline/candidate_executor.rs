@@ -251,6 +251,8 @@ pub(in super::super) fn execute_candidate_sequence(
     } = params;
     let mut request = Some(request);
     let mut state = CandidateExecutionState::default();
+    // 中文注释：展开 previous_response_id 后的请求体单独缓存，避免与原请求体的改写结果串用。
+    let mut expanded_state = CandidateExecutionState::default();
     let mut attempted_account_ids = Vec::new();
     let mut skipped_cooldown = 0usize;
     let mut skipped_inflight = 0usize;
@@ -322,16 +324,24 @@ pub(in super::super) fn execute_candidate_sequence(
             } else {
                 None
             };
-        let body_for_attempt = state.body_for_attempt(
+        let expanded_body = context
+            .previous_response_expansion()
+            .filter(|expansion| expansion.should_expand_for(account.id.as_str()))
+            .map(|expansion| expansion.expanded_body());
+        let (attempt_state, attempt_body) = match expanded_body {
+            Some(expanded_body) => (&mut expanded_state, expanded_body),
+            None => (&mut state, body),
+        };
+        let body_for_attempt = attempt_state.body_for_attempt(
             path,
-            body,
+            attempt_body,
             strip_session_affinity,
             setup,
             attempt_model_override,
             attempt_prompt_cache_key,
         );
         context.log_candidate_start(&account.id, idx, strip_session_affinity);
-        if let Some(skip_reason) = context.should_skip_candidate(&account.id, idx) {
+        if let Some(skip_reason) = context.should_skip_candidate(&account, idx) {
             context.log_candidate_skip(&account.id, idx, skip_reason);
             match skip_reason {
                 super::super::support::candidates::CandidateSkipReason::Cooldown => {
@@ -343,6 +353,22 @@ pub(in super::super) fn execute_candidate_sequence(
             }
             continue;
         }
+        // 中文注释：上限判断与占用名额在同一次获取里完成，多副本并发时不会同时越过上限。
+        let Some(acquired_inflight) = super::super::super::try_acquire_account_inflight(
+            &account.id,
+            context.inflight_limit_for_candidate(&account, idx),
+        ) else {
+            super::super::super::record_gateway_candidate_skip(
+                super::super::super::GatewayCandidateSkipReason::Inflight,
+            );
+            context.log_candidate_skip(
+                &account.id,
+                idx,
+                super::super::support::candidates::CandidateSkipReason::Inflight,
+            );
+            skipped_inflight += 1;
+            continue;
+        };
         prepare_next_account_candidate_client(ordered_account_ids.as_slice(), idx, trace_id);
         attempted_account_ids.push(account.id.clone());
 
@@ -375,7 +401,7 @@ pub(in super::super) fn execute_candidate_sequence(
             },
         );
 
-        let mut inflight_guard = Some(super::super::super::acquire_account_inflight(&account.id));
+        let mut inflight_guard = Some(acquired_inflight);
         let mut attempt_trace = CandidateAttemptTrace::default();
         let mut same_account_retry_count = 0u8;
         let mut decision = run_candidate_attempt(CandidateAttemptParams {
@@ -503,9 +529,13 @@ pub(in super::super) fn execute_candidate_sequence(
                     && !strip_session_affinity
                     && (incoming_turn_state.is_some() || setup.has_body_encrypted_content)
                 {
-                    let retry_body = state.retry_body(
+                    let (retry_state, retry_source_body) = match expanded_body {
+                        Some(expanded_body) => (&mut expanded_state, expanded_body),
+                        None => (&mut state, body),
+                    };
+                    let retry_body = retry_state.retry_body(
                         path,
-                        body,
+                        retry_source_body,
                         setup,
                         attempt_model_override,
                         attempt_prompt_cache_key,
@@ -655,6 +685,64 @@ pub(in super::super) fn execute_candidate_sequence(
                         continue;
                     }
                 }
+                if let Some(target) = context
+                    .structured_output()
+                    .filter(|_| !client_is_stream && resp.status().is_success())
+                {
+                    let enforced =
+                        super::super::super::structured_output::enforce_structured_output(
+                            target,
+                            trace_id,
+                            &body_for_attempt,
+                            resp,
+                            |repair_body| {
+                                let mut repair_trace = CandidateAttemptTrace::default();
+                                match run_candidate_attempt(CandidateAttemptParams {
+                                    storage,
+                                    method,
+                                    request_ctx,
+                                    incoming_headers: &attempt_headers,
+                                    body: repair_body,
+                                    upstream_is_stream,
+                                    path,
+                                    request_deadline,
+                                    account: &account,
+                                    token: &mut token,
+                                    strip_session_affinity,
+                                    debug,
+                                    allow_openai_fallback: attempt_allow_openai_fallback,
+                                    disable_challenge_stateless_retry,
+                                    has_more_candidates: false,
+                                    context,
+                                    setup,
+                                    trace: &mut repair_trace,
+                                }) {
+                                    CandidateUpstreamDecision::RespondUpstream(response) => {
+                                        Some(response)
+                                    }
+                                    _ => None,
+                                }
+                            },
+                        );
+                    match enforced {
+                        Ok((response, _)) => {
+                            resp = response;
+                        }
+                        Err(message) => {
+                            super::super::super::mark_account_cooldown(
+                                &account.id,
+                                super::super::super::CooldownReason::Network,
+                            );
+                            attempt_trace.last_attempt_error = Some(message);
+                            record_failover_attempt(
+                                &mut attempt_trace,
+                                &mut last_attempt_url,
+                                &mut last_attempt_error,
+                            );
+                            continue;
+                        }
+                    }
+                }
                 let request = request.take().ok_or_else(|| {
                     "request already consumed before upstream response".to_string()
                 })?;
@@ -1,5 +1,5 @@
 use super::super::support::candidates;
-use codexmanager_core::storage::Storage;
+use codexmanager_core::storage::{Account, Storage};
 
 pub(in super::super) struct GatewayUpstreamExecutionContext<'a> {
     trace_id: &'a str,
@@ -25,6 +25,11 @@ pub(in super::super) struct GatewayUpstreamExecutionContext<'a> {
     estimated_input_tokens: i64,
     candidate_count: usize,
     account_max_inflight: usize,
+    response_cache: Option<&'a super::super::super::response_cache::ResponseCacheTarget>,
+    response_store: Option<&'a super::super::super::response_store::ResponseStoreTurn>,
+    previous_response_expansion:
+        Option<&'a super::super::super::response_store::PreviousResponseExpansion>,
+    structured_output: Option<&'a super::super::super::structured_output::StructuredOutputTarget>,
 }
 
 impl<'a> GatewayUpstreamExecutionContext<'a> {
@@ -89,9 +94,75 @@ impl<'a> GatewayUpstreamExecutionContext<'a> {
             estimated_input_tokens,
             candidate_count,
             account_max_inflight,
+            response_cache: None,
+            response_store: None,
+            previous_response_expansion: None,
+            structured_output: None,
         }
     }
 
+    /// 挂上响应缓存目标；候选成功返回的上游响应会被分流写入缓存。
+    pub(in super::super) fn with_response_cache(
+        mut self,
+        response_cache: Option<&'a super::super::super::response_cache::ResponseCacheTarget>,
+    ) -> Self {
+        self.response_cache = response_cache;
+        self
+    }
+
+    pub(in super::super) fn response_cache(
+        &self,
+    ) -> Option<&super::super::super::response_cache::ResponseCacheTarget> {
+        self.response_cache
+    }
+
+    /// 挂上 Responses 存储草稿；成功完成的响应会写入网关侧存储。
+    pub(in super::super) fn with_response_store(
+        mut self,
+        response_store: Option<&'a super::super::super::response_store::ResponseStoreTurn>,
+    ) -> Self {
+        self.response_store = response_store;
+        self
+    }
+
+    pub(in super::super) fn response_store(
+        &self,
+    ) -> Option<&super::super::super::response_store::ResponseStoreTurn> {
+        self.response_store
+    }
+
+    /// 挂上 `previous_response_id` 的本地展开结果；切换到非原账号时改发展开后的请求体。
+    pub(in super::super) fn with_previous_response_expansion(
+        mut self,
+        expansion: Option<&'a super::super::super::response_store::PreviousResponseExpansion>,
+    ) -> Self {
+        self.previous_response_expansion = expansion;
+        self
+    }
+
+    pub(in super::super) fn previous_response_expansion(
+        &self,
+    ) -> Option<&super::super::super::response_store::PreviousResponseExpansion> {
+        self.previous_response_expansion
+    }
+
+    /// 挂上结构化输出校验目标；非流式 2xx 响应交付前会按 schema 校验并按需修复重试。
+    pub(in super::super) fn with_structured_output(
+        mut self,
+        structured_output: Option<
+            &'a super::super::super::structured_output::StructuredOutputTarget,
+        >,
+    ) -> Self {
+        self.structured_output = structured_output;
+        self
+    }
+
+    pub(in super::super) fn structured_output(
+        &self,
+    ) -> Option<&super::super::super::structured_output::StructuredOutputTarget> {
+        self.structured_output
+    }
+
     /// 函数 `has_more_candidates`
     ///
     /// 作者: gaohongshun
@@ -124,18 +195,35 @@ impl<'a> GatewayUpstreamExecutionContext<'a> {
     /// 返回函数执行结果
     pub(in super::super) fn should_skip_candidate(
         &self,
-        account_id: &str,
+        account: &Account,
         idx: usize,
     ) -> Option<candidates::CandidateSkipReason> {
         candidates::candidate_skip_reason_for_proxy(
-            account_id,
+            account.id.as_str(),
             idx,
             self.candidate_count,
-            self.account_max_inflight,
             self.protocol_type == crate::apikey_profile::PROTOCOL_ANTHROPIC_NATIVE,
         )
     }
 
+    /// 函数 `inflight_limit_for_candidate`
+    ///
+    /// 返回该候选占用在途名额时使用的上限，0 表示不限。
+    pub(in super::super) fn inflight_limit_for_candidate(
+        &self,
+        account: &Account,
+        idx: usize,
+    ) -> usize {
+        candidates::candidate_inflight_limit_for_proxy(
+            idx,
+            self.candidate_count,
+            super::super::super::group_policy::account_max_inflight_for(
+                account,
+                self.account_max_inflight,
+            ),
+        )
+    }
+
     /// 函数 `log_candidate_start`
     ///
     /// 作者: gaohongshun
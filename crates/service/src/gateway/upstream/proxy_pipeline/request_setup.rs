@@ -85,6 +85,10 @@ pub(in super::super) fn prepare_request_setup(
         model_for_log,
         account_binding_counts.as_ref(),
     );
+    if incoming_headers.batch_id().is_some() {
+        // 中文注释：批处理回环请求让路给交互流量：优先空闲账号与额度即将重置的账号。
+        super::super::super::prefer_batch_candidates(storage, candidates);
+    }
     let candidate_order = candidates
         .iter()
         .map(|(account, _)| format!("{}#sort={}", account.id, account.sort))
@@ -199,11 +199,23 @@ pub(super) fn finalize_upstream_response(
     has_more_candidates: bool,
 ) -> Result<FinalizeUpstreamResponseOutcome, String> {
     let status_code = response.status().as_u16();
+    let (response, response_cache_commit) = match context.response_cache() {
+        Some(target) => {
+            super::super::super::response_cache::capture_upstream_response(target, response)
+        }
+        None => (response, None),
+    };
+    let (response, response_store_commit) = match context.response_store() {
+        Some(turn) => super::super::super::response_store::capture_completed_response(
+            turn, account_id, response,
+        ),
+        None => (response, None),
+    };
 
     let bridge = super::super::super::respond_with_upstream(
         request,
         response,
-        inflight_guard,
+        Some(inflight_guard),
         response_adapter,
         None,
         gemini_stream_output_mode,
@@ -279,6 +291,14 @@ pub(super) fn finalize_upstream_response(
         client_delivery_failed,
     );
 
+    let delivered = status_for_log < 400 && bridge_ok && final_error.is_none();
+    if let Some(commit) = response_cache_commit {
+        commit.finish(delivered);
+    }
+    if let Some(commit) = response_store_commit {
+        commit.finish(delivered);
+    }
+
     if upstream_stream_failed {
         super::super::super::mark_account_cooldown(
             account_id,
@@ -311,6 +311,39 @@ impl GatewayStreamResponse {
     }
 }
 
+/// 以 `Read` 方式消费字节流，未读完的块放回 replay 队首，供按行解析的 SSE 读取器复用。
+impl Read for GatewayByteStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            match self.recv() {
+                Ok(GatewayByteStreamItem::Chunk(bytes)) => {
+                    if bytes.is_empty() {
+                        continue;
+                    }
+                    let read = bytes.len().min(buf.len());
+                    buf[..read].copy_from_slice(&bytes[..read]);
+                    if read < bytes.len() {
+                        self.replay
+                            .push_front(GatewayByteStreamItem::Chunk(bytes.slice(read..)));
+                    }
+                    return Ok(read);
+                }
+                Ok(item @ GatewayByteStreamItem::Eof) => {
+                    self.replay.push_front(item);
+                    return Ok(0);
+                }
+                Ok(GatewayByteStreamItem::Error(err)) => {
+                    return Err(std::io::Error::other(err));
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+    }
+}
+
 impl Drop for GatewayByteStream {
     fn drop(&mut self) {
         if let Some(consumers) = self.tee_consumers.take() {
@@ -323,6 +356,18 @@ impl Drop for GatewayByteStream {
     }
 }
 
+/// 响应体副本的提交句柄；bridge 结束后由调用方决定副本是否交给采集回调。
+#[derive(Debug)]
+pub(crate) struct GatewayBodyCaptureCommit {
+    tx: mpsc::Sender<bool>,
+}
+
+impl GatewayBodyCaptureCommit {
+    pub(crate) fn finish(self, should_commit: bool) {
+        let _ = self.tx.send(should_commit);
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum GatewayUpstreamResponse {
     Blocking(reqwest::blocking::Response),
@@ -379,6 +424,52 @@ impl GatewayUpstreamResponse {
             response.prefetch_until(max_bytes, idle_timeout, wall_clock_timeout, should_stop);
         (prefix, Self::Stream(response), terminal)
     }
+
+    /// 分流一份响应体副本：客户端照常消费原始流，后台线程最多累积 `max_bytes` 字节，
+    /// 读完且收到 `finish(true)` 后调用 `on_commit`；超限或上游出错时直接放弃副本。
+    pub(crate) fn capture_body<F>(
+        self,
+        max_bytes: usize,
+        on_commit: F,
+    ) -> (Self, GatewayBodyCaptureCommit)
+    where
+        F: FnOnce(Vec<u8>) + Send + 'static,
+    {
+        let response = match self {
+            Self::Blocking(response) => GatewayStreamResponse::from_blocking_response(response),
+            Self::Stream(response) => response,
+        };
+        let GatewayStreamResponse {
+            status,
+            headers,
+            body,
+        } = response;
+        let (client_body, mut capture_body) = body.tee();
+        let (tx, rx) = mpsc::channel::<bool>();
+        thread::spawn(move || {
+            let mut captured = Vec::new();
+            loop {
+                match capture_body.recv() {
+                    Ok(GatewayByteStreamItem::Chunk(chunk)) => {
+                        if captured.len() + chunk.len() > max_bytes {
+                            return;
+                        }
+                        captured.extend_from_slice(chunk.as_ref());
+                    }
+                    Ok(GatewayByteStreamItem::Eof) => break,
+                    Ok(GatewayByteStreamItem::Error(_)) | Err(_) => return,
+                }
+            }
+            drop(capture_body);
+            if rx.recv().unwrap_or(false) {
+                on_commit(captured);
+            }
+        });
+        (
+            Self::Stream(GatewayStreamResponse::new(status, headers, client_body)),
+            GatewayBodyCaptureCommit { tx },
+        )
+    }
 }
 
 impl From<reqwest::blocking::Response> for GatewayUpstreamResponse {
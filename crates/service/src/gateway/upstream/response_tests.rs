@@ -183,3 +183,52 @@ fn dropping_openai_responses_reader_cancels_sidecar_and_silent_upstream() {
         }
     }
 }
+
+#[test]
+fn capture_body_delivers_copy_only_after_commit() {
+    let body = Bytes::from_static(b"data: {\"type\":\"response.completed\"}\n\n");
+    let response = GatewayUpstreamResponse::Stream(GatewayStreamResponse::new(
+        reqwest::StatusCode::OK,
+        reqwest::header::HeaderMap::new(),
+        GatewayByteStream::from_bytes(body.clone()),
+    ));
+    let (tx, rx) = mpsc::channel();
+    let (response, commit) = response.capture_body(1024, move |captured| {
+        let _ = tx.send(captured);
+    });
+    let GatewayUpstreamResponse::Stream(response) = response else {
+        panic!("captured response should be a stream");
+    };
+    assert_eq!(response.read_all_bytes().expect("client body"), body);
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+    commit.finish(true);
+    let captured = rx
+        .recv_timeout(Duration::from_secs(2))
+        .expect("captured body");
+    assert_eq!(captured, body.to_vec());
+}
+
+#[test]
+fn capture_body_drops_copy_over_limit_or_without_commit() {
+    for (max_bytes, should_commit) in [(4, true), (1024, false)] {
+        let response = GatewayUpstreamResponse::Stream(GatewayStreamResponse::new(
+            reqwest::StatusCode::OK,
+            reqwest::header::HeaderMap::new(),
+            GatewayByteStream::from_bytes(Bytes::from_static(b"0123456789")),
+        ));
+        let (tx, rx) = mpsc::channel();
+        let (response, commit) = response.capture_body(max_bytes, move |captured| {
+            let _ = tx.send(captured);
+        });
+        let GatewayUpstreamResponse::Stream(response) = response else {
+            panic!("captured response should be a stream");
+        };
+        assert_eq!(
+            response.read_all_bytes().expect("client body").as_ref(),
+            b"0123456789"
+        );
+        commit.finish(should_commit);
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}
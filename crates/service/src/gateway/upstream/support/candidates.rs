@@ -39,22 +39,85 @@ pub(crate) fn prepare_gateway_candidates(
         && normalized_plan_filter.is_none()
         && !exclude_free_accounts
     {
-        return super::super::super::collect_gateway_candidates_with_low_quota_mode(
+        let mut candidates = super::super::super::collect_gateway_candidates_with_low_quota_mode(
             storage,
             low_quota_mode,
+        )?;
+        super::super::super::group_policy::retain_candidates_allowed_for_model(
+            &mut candidates,
+            request_model,
         );
+        super::super::super::group_policy::remember_candidate_groups(&candidates);
+        return Ok(candidates);
     }
 
+    let authorized_candidates =
+        list_authorized_gateway_candidates(storage, normalized_plan_filter, exclude_free_accounts)?;
+    let Some(group_filter) = normalized_group_filter else {
+        let mut candidates = collect_group_gateway_candidates(
+            storage,
+            &authorized_candidates,
+            None,
+            low_quota_mode,
+        )?;
+        super::super::super::group_policy::retain_candidates_allowed_for_model(
+            &mut candidates,
+            request_model,
+        );
+        super::super::super::group_policy::remember_candidate_groups(&candidates);
+        return Ok(candidates);
+    };
+
+    // 中文注释：按分组策略的回退链依次尝试；首个存在非冷却账号的分组胜出，
+    // 全部冷却时退回第一个非空分组，让后续冷却判定照常处理最后一个候选。
+    // 账号列表只查询一次，各分组在同一份授权候选上分区。
+    let mut first_non_empty = None;
+    for group_name in super::super::super::group_policy::group_fallback_chain(group_filter) {
+        let mut candidates = collect_group_gateway_candidates(
+            storage,
+            &authorized_candidates,
+            Some(group_name.as_str()),
+            low_quota_mode,
+        )?;
+        super::super::super::group_policy::retain_candidates_allowed_for_model(
+            &mut candidates,
+            request_model,
+        );
+        if candidates.is_empty() {
+            continue;
+        }
+        if candidates
+            .iter()
+            .any(|(account, _)| !super::super::super::is_account_in_cooldown(&account.id))
+        {
+            if group_name != group_filter {
+                log::info!(
+                    "event=account_group_fallback group={} fallback={} candidates={}",
+                    group_filter,
+                    group_name,
+                    candidates.len()
+                );
+            }
+            super::super::super::group_policy::remember_candidate_groups(&candidates);
+            return Ok(candidates);
+        }
+        first_non_empty.get_or_insert(candidates);
+    }
+    let candidates = first_non_empty.unwrap_or_default();
+    super::super::super::group_policy::remember_candidate_groups(&candidates);
+    Ok(candidates)
+}
+
+fn list_authorized_gateway_candidates(
+    storage: &Storage,
+    plan_filter: Option<&str>,
+    exclude_free_accounts: bool,
+) -> Result<Vec<(Account, Token)>, String> {
     let mut authorized_candidates = storage
         .list_gateway_candidates()
         .map_err(|err| format!("list gateway candidates failed: {err}"))?;
-    if let Some(group_filter) = normalized_group_filter {
-        authorized_candidates.retain(|(account, _)| {
-            crate::account_group::account_matches_group_filter(account, Some(group_filter))
-        });
-    }
 
-    if normalized_plan_filter.is_some() || exclude_free_accounts {
+    if plan_filter.is_some() || exclude_free_accounts {
         let account_ids = authorized_candidates
             .iter()
             .map(|(account, _)| account.id.clone())
@@ -65,7 +128,7 @@ pub(crate) fn prepare_gateway_candidates(
             .into_iter()
             .map(|snapshot| (snapshot.account_id.clone(), snapshot))
             .collect::<HashMap<_, _>>();
-        if let Some(plan_filter) = normalized_plan_filter {
+        if let Some(plan_filter) = plan_filter {
             authorized_candidates.retain(|(account, token)| {
                 crate::account_plan::account_matches_plan_filter_with_snapshot(
                     token,
@@ -94,10 +157,25 @@ pub(crate) fn prepare_gateway_candidates(
         }
     }
 
+    Ok(authorized_candidates)
+}
+
+fn collect_group_gateway_candidates(
+    storage: &Storage,
+    authorized_candidates: &[(Account, Token)],
+    group_filter: Option<&str>,
+    low_quota_mode: super::super::super::LowQuotaCandidateMode,
+) -> Result<Vec<(Account, Token)>, String> {
     let authorized_account_ids = authorized_candidates
-        .into_iter()
-        .map(|(account, _)| account.id)
+        .iter()
+        .filter(|(account, _)| {
+            crate::account_group::account_matches_group_filter(account, group_filter)
+        })
+        .map(|(account, _)| account.id.clone())
         .collect::<Vec<_>>();
+    if authorized_account_ids.is_empty() {
+        return Ok(Vec::new());
+    }
     // 中文注释：保持账号原始顺序（按账户排序字段）作为候选顺序，失败时再依次切下一个。
     super::super::super::collect_gateway_candidates_for_account_ids_with_low_quota_mode(
         storage,
@@ -185,7 +263,6 @@ pub(in super::super) fn candidate_skip_reason_for_proxy(
     account_id: &str,
     idx: usize,
     candidate_count: usize,
-    account_max_inflight: usize,
     skip_last_cooldown: bool,
 ) -> Option<CandidateSkipReason> {
     let has_more_candidates = idx + 1 < candidate_count;
@@ -198,20 +275,24 @@ pub(in super::super) fn candidate_skip_reason_for_proxy(
         return Some(CandidateSkipReason::Cooldown);
     }
 
-    if account_max_inflight > 0
-        && super::super::super::account_inflight_count(account_id) >= account_max_inflight
-        && has_more_candidates
-    {
-        // 中文注释：并发上限是软约束，最后一个候选仍要尝试，避免把可恢复抖动直接放大成全局不可用。
-        super::super::super::record_gateway_candidate_skip(
-            super::super::super::GatewayCandidateSkipReason::Inflight,
-        );
-        return Some(CandidateSkipReason::Inflight);
-    }
-
     None
 }
 
+/// 函数 `candidate_inflight_limit_for_proxy`
+///
+/// 并发上限是软约束：最后一个候选返回 0（不限），避免把可恢复抖动直接放大成全局不可用。
+pub(in super::super) fn candidate_inflight_limit_for_proxy(
+    idx: usize,
+    candidate_count: usize,
+    account_max_inflight: usize,
+) -> usize {
+    if idx + 1 < candidate_count {
+        account_max_inflight
+    } else {
+        0
+    }
+}
+
 #[cfg(test)]
 #[path = "candidates_tests.rs"]
 mod tests;
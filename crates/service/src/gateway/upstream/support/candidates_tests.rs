@@ -1,6 +1,6 @@
 use super::{
-    allow_openai_fallback_for_account_with_snapshot, candidate_skip_reason_for_proxy,
-    CandidateSkipReason,
+    allow_openai_fallback_for_account_with_snapshot, candidate_inflight_limit_for_proxy,
+    candidate_skip_reason_for_proxy, CandidateSkipReason,
 };
 use codexmanager_core::storage::{
     now_ts, Account, ModelSourceMapping, ModelSourceModel, Storage, Token, UsageSnapshotRecord,
@@ -703,10 +703,14 @@ fn allow_openai_fallback_for_account_rejects_workspace_plans() {
 }
 
 #[test]
-fn candidate_skip_reason_for_proxy_allows_failover_when_head_account_is_inflight_limited() {
+fn candidate_inflight_limit_allows_failover_when_head_account_is_inflight_limited() {
     let _guard = crate::gateway::acquire_account_inflight("acc-preferred");
-    let actual = candidate_skip_reason_for_proxy("acc-preferred", 0, 2, 1, false);
-    assert_eq!(actual, Some(CandidateSkipReason::Inflight));
+    let head_limit = candidate_inflight_limit_for_proxy(0, 2, 1);
+    let last_limit = candidate_inflight_limit_for_proxy(1, 2, 1);
+
+    assert!(crate::gateway::try_acquire_account_inflight("acc-preferred", head_limit).is_none());
+    assert_eq!(last_limit, 0);
+    assert!(crate::gateway::try_acquire_account_inflight("acc-preferred", last_limit).is_some());
 }
 
 #[test]
@@ -714,8 +718,8 @@ fn candidate_skip_reason_for_proxy_can_skip_last_cooldown_candidate() {
     let account_id = "acc-cooldown-last-skip-test";
     crate::gateway::gateway_mark_account_cooldown_for_status(account_id, 403);
 
-    let default_last = candidate_skip_reason_for_proxy(account_id, 0, 1, 0, false);
-    let strict_last = candidate_skip_reason_for_proxy(account_id, 0, 1, 0, true);
+    let default_last = candidate_skip_reason_for_proxy(account_id, 0, 1, false);
+    let strict_last = candidate_skip_reason_for_proxy(account_id, 0, 1, true);
 
     assert_eq!(default_last, None);
     assert_eq!(strict_last, Some(CandidateSkipReason::Cooldown));
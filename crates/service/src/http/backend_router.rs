@@ -5,6 +5,7 @@ pub(crate) enum BackendRoute {
     Rpc,
     AuthCallback,
     UsageRefreshEvents,
+    ServerEvents,
     Metrics,
     Gateway,
 }
@@ -30,6 +31,11 @@ pub(crate) fn resolve_backend_route(method: &str, path: &str) -> BackendRoute {
     if method == "GET" && path == "/events/usage-refresh" {
         return BackendRoute::UsageRefreshEvents;
     }
+    if method == "GET"
+        && path.split('?').next() == Some(crate::http::server_events::SERVER_EVENTS_PATH)
+    {
+        return BackendRoute::ServerEvents;
+    }
     if method == "GET" && path == "/metrics" {
         return BackendRoute::Metrics;
     }
@@ -55,6 +61,7 @@ pub(crate) fn handle_backend_request(request: Request) {
         BackendRoute::UsageRefreshEvents => {
             crate::http::usage_events::handle_usage_refresh_events(request)
         }
+        BackendRoute::ServerEvents => crate::http::server_events::handle_server_events(request),
         BackendRoute::Metrics => crate::http::gateway_endpoint::handle_metrics(request),
         BackendRoute::Gateway => crate::http::gateway_endpoint::handle_gateway(request),
     }
@@ -2,6 +2,7 @@ use std::io;
 use std::io::Write;
 use std::net::TcpStream;
 use std::panic::AssertUnwindSafe;
+use std::sync::RwLock;
 use std::thread;
 use std::time::Duration;
 
@@ -33,6 +34,8 @@ const ENV_HTTP_QUEUE_MIN: &str = "CODEXMANAGER_HTTP_QUEUE_MIN";
 const ENV_HTTP_STREAM_QUEUE_FACTOR: &str = "CODEXMANAGER_HTTP_STREAM_QUEUE_FACTOR";
 const ENV_HTTP_STREAM_QUEUE_MIN: &str = "CODEXMANAGER_HTTP_STREAM_QUEUE_MIN";
 
+static BACKEND_ADDR: RwLock<Option<String>> = RwLock::new(None);
+
 pub(crate) struct BackendServer {
     pub(crate) addr: String,
     pub(crate) join: thread::JoinHandle<()>,
@@ -362,6 +365,12 @@ fn run_backend_server(server: Server) {
     spawn_request_workers(stream_worker_count, stream_rx, true);
 
     for request in server.incoming_requests() {
+        if request.url() == "/__shutdown" && !crate::shutdown_requested() {
+            // 中文注释：外部停机请求先进入排空阶段，排空线程结束后会再次唤醒本循环退出。
+            crate::lifecycle::drain::begin_drain();
+            let _ = request.respond(tiny_http::Response::from_string("draining"));
+            continue;
+        }
         if crate::shutdown_requested() || request.url() == "/__shutdown" {
             let _ = request.respond(tiny_http::Response::from_string("shutdown"));
             break;
@@ -415,10 +424,16 @@ pub(crate) fn start_backend_server() -> io::Result<BackendServer> {
         .to_ip()
         .map(|address| address.to_string())
         .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "backend addr missing"))?;
+    *crate::lock_utils::write_recover(&BACKEND_ADDR, "backend_addr") = Some(addr.clone());
     let join = thread::spawn(move || run_backend_server(server));
     Ok(BackendServer { addr, join })
 }
 
+/// 当前进程内 tiny_http 后端的 loopback 地址；网关内部重放请求（后台 Responses 等）时使用。
+pub(crate) fn current_backend_addr() -> Option<String> {
+    crate::lock_utils::read_recover(&BACKEND_ADDR, "backend_addr").clone()
+}
+
 /// 函数 `wake_backend_shutdown`
 ///
 /// 作者: gaohongshun
@@ -29,7 +29,8 @@ pub fn handle_gateway(request: Request) {
 /// # 返回
 /// 无
 pub fn handle_metrics(request: Request) {
-    let body = crate::gateway::gateway_metrics_prometheus();
+    let mut body = crate::gateway::gateway_metrics_prometheus();
+    body.push_str(crate::lifecycle::drain::drain_metrics_prometheus().as_str());
     let mut response = Response::from_string(body);
     if let Ok(content_type) = Header::from_bytes(b"Content-Type", b"text/plain; version=0.0.4") {
         response = response.with_header(content_type);
@@ -2,6 +2,7 @@ pub mod callback_endpoint;
 pub mod gateway_endpoint;
 pub mod rpc_endpoint;
 pub mod server;
+pub(crate) mod server_events;
 pub(crate) mod usage_events;
 
 pub(crate) mod backend_router;
@@ -14,3 +15,4 @@ pub(crate) mod proxy_request;
 pub(crate) mod proxy_response;
 pub(crate) mod proxy_runtime;
 pub(crate) mod responses_websocket;
+pub(crate) mod tls_listener;
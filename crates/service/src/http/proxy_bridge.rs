@@ -1,8 +1,12 @@
+use std::future::IntoFuture;
 use std::io;
 use std::time::Duration;
 
 use axum::Router;
 
+// 中文注释：停机标记在排空结束（或超时）后才置位，此时残留连接只再保留很短的收尾时间。
+const SHUTDOWN_FORCE_CLOSE_GRACE: Duration = Duration::from_secs(1);
+
 /// 函数 `wait_for_shutdown_signal`
 ///
 /// 作者: gaohongshun
@@ -20,6 +24,16 @@ async fn wait_for_shutdown_signal() {
     }
 }
 
+/// 停机标记置位后仍未结束的长连接不再等待，避免阻塞进程退出。
+async fn wait_for_forced_close() {
+    wait_for_shutdown_signal().await;
+    tokio::time::sleep(SHUTDOWN_FORCE_CLOSE_GRACE).await;
+    log::warn!(
+        "event=front_proxy_forced_close grace_ms={}",
+        SHUTDOWN_FORCE_CLOSE_GRACE.as_millis()
+    );
+}
+
 /// 函数 `serve_proxy_on_listener`
 ///
 /// 作者: gaohongshun
@@ -33,10 +47,13 @@ async fn wait_for_shutdown_signal() {
 /// # 返回
 /// 返回函数执行结果
 async fn serve_proxy_on_listener(listener: tokio::net::TcpListener, app: Router) -> io::Result<()> {
-    axum::serve(listener, app)
+    let serve = axum::serve(listener, app)
         .with_graceful_shutdown(wait_for_shutdown_signal())
-        .await
-        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+        .into_future();
+    tokio::select! {
+        result = serve => result.map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+        _ = wait_for_forced_close() => Ok(()),
+    }
 }
 
 /// 函数 `run_proxy_server`
@@ -76,3 +93,16 @@ pub(crate) async fn run_proxy_server(addr: &str, app: Router) -> io::Result<()>
     let listener = tokio::net::TcpListener::bind(addr_trimmed).await?;
     serve_proxy_on_listener(listener, app).await
 }
+
+/// 函数 `run_tls_proxy_server`
+///
+/// 在独立地址上提供 TLS 版前置代理；明文监听保持不变，供本机 web/桌面端继续使用。
+pub(crate) async fn run_tls_proxy_server(addr: &str, app: Router) -> io::Result<()> {
+    let app = crate::http::tls_listener::with_client_cert_auth(app)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let serve = crate::http::tls_listener::serve_tls_router(addr, app, wait_for_shutdown_signal());
+    tokio::select! {
+        result = serve => result,
+        _ = wait_for_forced_close() => Ok(()),
+    }
+}
@@ -8,7 +8,7 @@ use reqwest::Client;
 use std::io;
 use std::io::Read;
 
-use crate::http::proxy_bridge::run_proxy_server;
+use crate::http::proxy_bridge::{run_proxy_server, run_tls_proxy_server};
 use crate::http::proxy_request::{build_target_url, filter_request_headers};
 use crate::http::proxy_response::{merge_upstream_headers, text_error_response};
 
@@ -437,6 +437,10 @@ fn build_front_proxy_app(state: ProxyState) -> Router {
             "/events/usage-refresh",
             get(crate::http::usage_events::handle_usage_refresh_events_http),
         )
+        .route(
+            crate::http::server_events::SERVER_EVENTS_PATH,
+            get(crate::http::server_events::handle_server_events_http),
+        )
         .route("/v1/responses", any(responses_handler))
         .route("/proxy-test-upload", post(proxy_test_upload))
         .fallback(any(proxy_handler))
@@ -470,7 +474,15 @@ pub(crate) fn run_front_proxy(addr: &str, backend_addr: &str) -> io::Result<()>
             client,
         };
         let app = build_front_proxy_app(state);
-        run_proxy_server(addr, app).await
+        match crate::http::tls_listener::tls_listener_addr(crate::http::tls_listener::ENV_TLS_ADDR)
+        {
+            Some(tls_addr) => tokio::try_join!(
+                run_proxy_server(addr, app.clone()),
+                run_tls_proxy_server(tls_addr.as_str(), app)
+            )
+            .map(|_| ()),
+            None => run_proxy_server(addr, app).await,
+        }
     })
 }
 
@@ -51,6 +51,7 @@ const RESPONSES_WS_MAX_MESSAGE_BYTES: usize = 256 * 1024 * 1024;
 const RESPONSES_WS_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 const WEBSOCKET_CONNECTION_LIMIT_REACHED_CODE: &str = "websocket_connection_limit_reached";
 const RESPONSES_WS_REQUEST_IN_FLIGHT_CODE: &str = "response_in_flight";
+const RESPONSES_WS_CONTENT_POLICY_CODE: &str = "content_policy_violation";
 const WEBSOCKET_CONNECTION_LIMIT_REACHED_MESSAGE: &str =
     "Responses websocket connection limit reached (60 minutes). Create a new websocket connection to continue.";
 // A freshly handshaken socket can still be reset before its first client frame reaches the
@@ -307,6 +308,9 @@ pub(super) fn is_websocket_upgrade_request(headers: &HeaderMap) -> bool {
 }
 
 pub(super) async fn upgrade_responses_websocket(request: HttpRequest<Body>) -> Response<Body> {
+    if crate::draining() {
+        return draining_response();
+    }
     let (mut parts, _) = request.into_parts();
 
     let context = match authorize_websocket_request(&parts.headers) {
@@ -338,6 +342,7 @@ pub(super) async fn upgrade_responses_websocket(request: HttpRequest<Body>) -> R
 }
 
 async fn run_responses_websocket_session(mut socket: WebSocket, context: WsRequestContext) {
+    let _stream_session = crate::lifecycle::drain::begin_stream_session();
     let first_text = match receive_initial_request(&mut socket).await {
         Ok(Some(text)) => text,
         Ok(None) => return,
@@ -347,17 +352,24 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
         }
     };
 
-    if let Err(err) = validate_ws_api_key_for_new_request(&context).await {
-        record_rejected_ws_request(&context, &err);
-        send_ws_error_and_close(&mut socket, err, context.prefer_raw_errors).await;
-        return;
-    }
+    let first_trace_id = crate::gateway::next_trace_id();
+    let first_text =
+        match validate_ws_api_key_for_new_request(&context, first_trace_id.as_str(), first_text)
+            .await
+        {
+            Ok(text) => text,
+            Err(err) => {
+                record_rejected_ws_request(&context, first_trace_id.as_str(), &err);
+                send_ws_error_and_close(&mut socket, err, context.prefer_raw_errors).await;
+                return;
+            }
+        };
 
     let prepared_first =
         match rewrite_client_frame_with_model_fast_policy(first_text.as_str(), &context).await {
             Ok(prepared) => prepared,
             Err(err) => {
-                record_rejected_ws_request(&context, &err);
+                record_rejected_ws_request(&context, first_trace_id.as_str(), &err);
                 send_ws_error_and_close(&mut socket, err, context.prefer_raw_errors).await;
                 return;
             }
@@ -365,6 +377,7 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
 
     let mut first_log = begin_ws_request_log(
         &context,
+        first_trace_id,
         &prepared_first,
         "unresolved",
         "initial_upstream_connect",
@@ -414,7 +427,8 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
     };
 
     let mut completed_tool_calls = CompletedWsToolCallCache::default();
-    let mut completed_responses = CompletedWsResponseCache::default();
+    let mut completed_responses =
+        CompletedWsResponseCache::for_api_key(context.api_key.id.as_str());
     if let Err(err) = upstream
         .stream
         .send(UpstreamMessage::Text(
@@ -475,6 +489,16 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
 
     loop {
         tokio::select! {
+            _ = crate::lifecycle::drain::wait_for_drain(), if pending_request.is_none() => {
+                // 中文注释：停机排空时只在两次响应之间关闭会话，不打断正在生成的响应。
+                log::info!(
+                    "event=responses_ws_closed_for_drain account_id={}",
+                    upstream.account_id,
+                );
+                let _ = socket.close().await;
+                let _ = upstream.stream.close(None).await;
+                break;
+            }
             _ = heartbeat.tick() => {
                 if let Err(err) = socket.send(Message::Ping(Vec::new().into())).await {
                     log::info!("event=responses_ws_client_heartbeat_failed err={err}");
@@ -543,44 +567,51 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
                 };
                 match client_result {
                     Ok(Message::Text(text)) => {
+                        if pending_request.is_some() {
+                            log::warn!(
+                                "event=responses_ws_request_rejected_while_in_flight account_id={} upstream_url={}",
+                                upstream.account_id,
+                                upstream.upstream_url,
+                            );
+                            send_ws_error(
+                                &mut socket,
+                                WsSessionError::new(
+                                    400,
+                                    RESPONSES_WS_REQUEST_IN_FLIGHT_CODE,
+                                    crate::gateway::bilingual_error(
+                                        "当前 WebSocket 响应尚未完成",
+                                        "another response is already in flight on this websocket connection",
+                                    ),
+                                ),
+                                context.prefer_raw_errors,
+                            )
+                            .await;
+                            continue;
+                        }
+                        // 中文注释：出站内容策略要在网关改写之前作用于客户端原始载荷，与 HTTP 链路一致。
+                        let trace_id = crate::gateway::next_trace_id();
+                        let text = match validate_ws_api_key_for_new_request(
+                            &context,
+                            trace_id.as_str(),
+                            text.to_string(),
+                        )
+                        .await
+                        {
+                            Ok(text) => text,
+                            Err(err) => {
+                                record_rejected_ws_request(&context, trace_id.as_str(), &err);
+                                send_ws_error_and_close(&mut socket, err, context.prefer_raw_errors)
+                                    .await;
+                                let _ = upstream.stream.close(None).await;
+                                break;
+                            }
+                        };
                         match rewrite_client_frame(text.as_str(), &context) {
                             Ok(prepared) => {
-                                if pending_request.is_some() {
-                                    log::warn!(
-                                        "event=responses_ws_request_rejected_while_in_flight account_id={} upstream_url={}",
-                                        upstream.account_id,
-                                        upstream.upstream_url,
-                                    );
-                                    send_ws_error(
-                                        &mut socket,
-                                        WsSessionError::new(
-                                            400,
-                                            RESPONSES_WS_REQUEST_IN_FLIGHT_CODE,
-                                            crate::gateway::bilingual_error(
-                                                "当前 WebSocket 响应尚未完成",
-                                                "another response is already in flight on this websocket connection",
-                                            ),
-                                        ),
-                                        context.prefer_raw_errors,
-                                    )
-                                    .await;
-                                    continue;
-                                }
-                                if let Err(err) = validate_ws_api_key_for_new_request(&context).await {
-                                    record_rejected_ws_request(&context, &err);
-                                    send_ws_error_and_close(
-                                        &mut socket,
-                                        err,
-                                        context.prefer_raw_errors,
-                                    )
-                                    .await;
-                                    let _ = upstream.stream.close(None).await;
-                                    break;
-                                }
                                 let prepared = match apply_model_fast_policy(prepared).await {
                                     Ok(prepared) => prepared,
                                     Err(err) => {
-                                        record_rejected_ws_request(&context, &err);
+                                        record_rejected_ws_request(&context, trace_id.as_str(), &err);
                                         send_ws_error_and_close(
                                             &mut socket,
                                             err,
@@ -602,6 +633,7 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
                                 let mut current_pending = PendingWsRequestState {
                                     log: begin_ws_request_log(
                                         &context,
+                                        trace_id,
                                         &prepared,
                                         upstream.route_strategy,
                                         upstream.route_source,
@@ -1023,6 +1055,8 @@ async fn run_responses_websocket_session(mut socket: WebSocket, context: WsReque
                                         &mut completed_responses,
                                         &pending.prepared,
                                         text.as_str(),
+                                        context.api_key.id.as_str(),
+                                        upstream.account_id.as_str(),
                                     );
                                 }
                                 if let Err(err) = flush_ws_upstream_preamble(&mut socket, &mut pending).await {
@@ -1367,10 +1401,16 @@ fn authorize_websocket_request(headers: &HeaderMap) -> Result<WsRequestContext,
     })
 }
 
+/// 函数 `validate_ws_api_key_for_new_request`
+///
+/// 每个新的 response.create 都重新校验平台 Key，并执行出站内容策略；返回可能已脱敏的载荷。
 async fn validate_ws_api_key_for_new_request(
     context: &WsRequestContext,
-) -> Result<(), WsSessionError> {
+    trace_id: &str,
+    text: String,
+) -> Result<String, WsSessionError> {
     let key_id = context.api_key.id.clone();
+    let trace_id = trace_id.to_string();
     tokio::task::spawn_blocking(move || {
         let storage = open_storage().ok_or_else(|| {
             WsSessionError::new(
@@ -1379,13 +1419,15 @@ async fn validate_ws_api_key_for_new_request(
                 crate::gateway::bilingual_error("存储不可用", "storage unavailable"),
             )
         })?;
-        crate::gateway::load_active_gateway_api_key_by_id(
+        let api_key = crate::gateway::load_active_gateway_api_key_by_id(
             &storage,
             key_id.as_str(),
             RESPONSES_ENDPOINT,
         )
-        .map(|_| ())
-        .map_err(|(status, message)| WsSessionError::new(status, RESPONSES_WS_ERROR_CODE, message))
+        .map_err(|(status, message)| {
+            WsSessionError::new(status, RESPONSES_WS_ERROR_CODE, message)
+        })?;
+        apply_content_policy_with_storage(&storage, trace_id.as_str(), &api_key, text)
     })
     .await
     .map_err(|err| {
@@ -1449,6 +1491,24 @@ fn responses_ws_heartbeat_interval() -> tokio::time::Interval {
     )
 }
 
+fn apply_content_policy_with_storage(
+    storage: &codexmanager_core::storage::Storage,
+    trace_id: &str,
+    api_key: &codexmanager_core::storage::ApiKey,
+    text: String,
+) -> Result<String, WsSessionError> {
+    let mut body = text.into_bytes();
+    crate::gateway::gateway_enforce_content_policy(storage, trace_id, api_key, &mut body).map_err(
+        |(status, message)| WsSessionError::new(status, RESPONSES_WS_CONTENT_POLICY_CODE, message),
+    )?;
+    String::from_utf8(body).map_err(|err| {
+        WsSessionError::bad_gateway_bilingual(
+            "序列化出站内容策略结果失败",
+            format!("serialize content policy result failed: {err}"),
+        )
+    })
+}
+
 async fn rewrite_client_frame_with_model_fast_policy(
     text: &str,
     context: &WsRequestContext,
@@ -2148,16 +2208,29 @@ async fn wait_for_client_request_and_reconnect_upstream(
     let Some(text) = receive_initial_request(socket).await? else {
         return Ok(None);
     };
-    validate_ws_api_key_for_new_request(context).await?;
+    let trace_id = crate::gateway::next_trace_id();
+    let text = match validate_ws_api_key_for_new_request(context, trace_id.as_str(), text).await {
+        Ok(text) => text,
+        Err(err) => {
+            record_rejected_ws_request(context, trace_id.as_str(), &err);
+            return Err(err);
+        }
+    };
     let prepared = match rewrite_client_frame_with_model_fast_policy(text.as_str(), context).await {
         Ok(prepared) => prepared,
         Err(err) => {
-            record_rejected_ws_request(context, &err);
+            record_rejected_ws_request(context, trace_id.as_str(), &err);
             return Err(err);
         }
     };
     let mut pending = PendingWsRequestState {
-        log: begin_ws_request_log(context, &prepared, "unresolved", "upstream_reconnect"),
+        log: begin_ws_request_log(
+            context,
+            trace_id,
+            &prepared,
+            "unresolved",
+            "upstream_reconnect",
+        ),
         prepared,
         conversation_routing: None,
         forwarded_upstream_event: false,
@@ -2966,11 +3039,11 @@ fn build_upstream_websocket_request(
 
 fn begin_ws_request_log(
     context: &WsRequestContext,
+    trace_id: String,
     prepared: &PreparedClientFrame,
     route_strategy: &'static str,
     route_source: &'static str,
 ) -> PendingWsRequestLog {
-    let trace_id = crate::gateway::next_trace_id();
     let effective_protocol_type = crate::apikey_profile::resolve_gateway_protocol_type(
         context.api_key.protocol_type.as_str(),
         RESPONSES_ENDPOINT,
@@ -3016,14 +3089,13 @@ fn begin_ws_request_log(
     }
 }
 
-fn record_rejected_ws_request(context: &WsRequestContext, err: &WsSessionError) {
-    let trace_id = crate::gateway::next_trace_id();
+fn record_rejected_ws_request(context: &WsRequestContext, trace_id: &str, err: &WsSessionError) {
     let effective_protocol_type = crate::apikey_profile::resolve_gateway_protocol_type(
         context.api_key.protocol_type.as_str(),
         RESPONSES_ENDPOINT,
     );
     crate::gateway::log_request_start(
-        trace_id.as_str(),
+        trace_id,
         context.api_key.id.as_str(),
         "GET",
         RESPONSES_ENDPOINT,
@@ -3039,7 +3111,7 @@ fn record_rejected_ws_request(context: &WsRequestContext, err: &WsSessionError)
         crate::gateway::write_request_log(
             &storage,
             crate::gateway::RequestLogTraceContext {
-                trace_id: Some(trace_id.as_str()),
+                trace_id: Some(trace_id),
                 original_path: Some(RESPONSES_ENDPOINT),
                 adapted_path: Some(RESPONSES_ENDPOINT),
                 request_type: Some("ws"),
@@ -3061,7 +3133,7 @@ fn record_rejected_ws_request(context: &WsRequestContext, err: &WsSessionError)
         );
     }
     crate::gateway::log_request_final(
-        trace_id.as_str(),
+        trace_id,
         err.status,
         None,
         None,
@@ -3278,6 +3350,8 @@ fn cache_completed_ws_response(
     completed_responses: &mut CompletedWsResponseCache,
     prepared: &PreparedClientFrame,
     terminal_text: &str,
+    key_id: &str,
+    account_id: &str,
 ) {
     if let Err(err) = completed_responses.observe_completed_response(
         terminal_text,
@@ -3290,6 +3364,48 @@ fn cache_completed_ws_response(
             err,
         );
     }
+    if prepared.store {
+        store_completed_ws_response(prepared, terminal_text, key_id, account_id);
+    }
+}
+
+/// 函数 `store_completed_ws_response`
+///
+/// 与 HTTP 通道共用网关侧 Responses 存储，使 WebSocket 产生的 response 也能跨连接、跨账号续接。
+fn store_completed_ws_response(
+    prepared: &PreparedClientFrame,
+    terminal_text: &str,
+    key_id: &str,
+    account_id: &str,
+) {
+    if crate::gateway::response_store_max_bytes() == 0 {
+        return;
+    }
+    let Some(response) = serde_json::from_str::<Value>(terminal_text)
+        .ok()
+        .filter(|event| event.get("type").and_then(Value::as_str) == Some("response.completed"))
+        .and_then(|mut event| event.get_mut("response").map(Value::take))
+    else {
+        return;
+    };
+    let turn = crate::gateway::ResponseStoreTurn::new(
+        key_id,
+        prepared.previous_response_id.as_deref(),
+        prepared.model.as_deref(),
+        &prepared.input,
+    );
+    let Some(storage) = open_storage() else {
+        return;
+    };
+    if let Err(err) =
+        crate::gateway::record_completed_response(&storage, &turn, Some(account_id), &response)
+    {
+        log::warn!(
+            "event=responses_ws_response_store_record_failed account_id={} err={}",
+            account_id,
+            err
+        );
+    }
 }
 
 fn rebase_ws_request_for_account_change(
@@ -3918,6 +4034,21 @@ async fn send_ws_error_and_close(
     let _ = socket.close().await;
 }
 
+fn draining_response() -> Response<Body> {
+    let mut response = text_error_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        crate::lifecycle::drain::draining_error_message(),
+    );
+    if let Ok(value) = HeaderValue::from_str(
+        crate::lifecycle::drain::drain_retry_after_secs()
+            .to_string()
+            .as_str(),
+    ) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
 fn upgrade_required_response(message: impl Into<String>) -> Response<Body> {
     let mut response = text_response(StatusCode::UPGRADE_REQUIRED, message.into());
     response
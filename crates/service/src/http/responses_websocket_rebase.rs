@@ -60,6 +60,7 @@ pub(super) struct CompletedWsResponseCache {
     responses: HashMap<String, CompletedWsResponse>,
     insertion_order: VecDeque<String>,
     retained_bytes: usize,
+    key_id: Option<String>,
 }
 
 impl WsToolCallKind {
@@ -132,6 +133,14 @@ impl CompletedWsToolCallCache {
 }
 
 impl CompletedWsResponseCache {
+    /// 绑定平台 Key；会话缓存未命中时回落到网关侧 Responses 存储。
+    pub(super) fn for_api_key(key_id: &str) -> Self {
+        Self {
+            key_id: Some(key_id.to_string()),
+            ..Self::default()
+        }
+    }
+
     pub(super) fn observe_completed_response(
         &mut self,
         terminal_text: &str,
@@ -235,17 +244,17 @@ impl CompletedWsResponseCache {
         let mut current_response_id = response_id.to_string();
         let mut visited = HashSet::new();
         let mut chain = Vec::new();
+        let mut stored_prefix = None;
         loop {
             if !visited.insert(current_response_id.clone()) {
                 return Err(format!(
                     "cached websocket response history contains a cycle at {current_response_id}"
                 ));
             }
-            let response = self.responses.get(&current_response_id).ok_or_else(|| {
-                format!(
-                    "previous_response_id {current_response_id} is not available in this websocket session recovery cache"
-                )
-            })?;
+            let Some(response) = self.responses.get(&current_response_id) else {
+                stored_prefix = Some(self.collect_stored_history_items(&current_response_id)?);
+                break;
+            };
             chain.push(response);
             let Some(previous_response_id) = response.previous_response_id.as_deref() else {
                 break;
@@ -263,13 +272,29 @@ impl CompletedWsResponseCache {
                 .saturating_add(response.input.len())
                 .saturating_add(response.output.len())
         });
-        let mut history = Vec::with_capacity(capacity);
+        let mut history = stored_prefix.unwrap_or_default();
+        history.reserve(capacity);
         for response in chain.into_iter().rev() {
             history.extend(response.input.iter().cloned());
             history.extend(response.output.iter().cloned());
         }
         Ok(history)
     }
+
+    fn collect_stored_history_items(&self, response_id: &str) -> Result<Vec<Value>, String> {
+        let missing = || {
+            format!(
+                "previous_response_id {response_id} is not available in this websocket session recovery cache"
+            )
+        };
+        let key_id = self.key_id.as_deref().ok_or_else(missing)?;
+        if crate::gateway::response_store_max_bytes() == 0 {
+            return Err(missing());
+        }
+        let storage = crate::storage_helpers::open_storage().ok_or_else(missing)?;
+        crate::gateway::collect_stored_response_history(&storage, key_id, response_id)
+            .map(|(history, _)| history)
+    }
 }
 
 fn normalize_ws_response_input(input: &Value) -> Vec<Value> {
@@ -1,7 +1,7 @@
 use super::{
-    apply_model_fast_policy_with_storage, build_socks5_connect_request,
-    build_upstream_websocket_request, infer_ws_terminal_status, inspect_ws_terminal_event,
-    is_previous_response_not_found_terminal, merge_client_metadata,
+    apply_content_policy_with_storage, apply_model_fast_policy_with_storage,
+    build_socks5_connect_request, build_upstream_websocket_request, infer_ws_terminal_status,
+    inspect_ws_terminal_event, is_previous_response_not_found_terminal, merge_client_metadata,
     missing_ws_tool_call_from_terminal, parse_websocket_target, parse_ws_usage,
     prepare_missing_ws_tool_call_retry, proxy_basic_auth_header,
     rebase_ws_request_for_account_change, rewrite_client_frame, should_buffer_ws_upstream_preamble,
@@ -11,8 +11,8 @@ use super::{
 };
 use axum::http::{HeaderMap, HeaderValue};
 use codexmanager_core::storage::{
-    now_ts, Account, ApiKey, ConversationBinding, ManagedModelV2Upsert, ModelFastPolicyV2, Storage,
-    Token,
+    now_ts, Account, ApiKey, ApiKeyContentPolicy, ConversationBinding, ManagedModelV2Upsert,
+    ModelFastPolicyV2, Storage, Token,
 };
 use serde_json::{json, Value};
 
@@ -178,6 +178,57 @@ fn websocket_frame_applies_model_fast_policy() {
     assert!(value.get("service_tier").is_none());
 }
 
+#[test]
+fn websocket_response_create_payload_runs_content_policy_before_forwarding() {
+    let storage = Storage::open_in_memory().expect("open storage");
+    storage.init().expect("init storage");
+    let mut api_key = sample_api_key();
+    api_key.id = "gk_ws_content_policy".to_string();
+    storage.insert_api_key(&api_key).expect("insert api key");
+    let upsert_policy = |action: &str, updated_at: i64| {
+        storage
+            .upsert_api_key_content_policy(&ApiKeyContentPolicy {
+                key_id: api_key.id.clone(),
+                enabled: true,
+                action: action.to_string(),
+                source_actions_json: "{}".to_string(),
+                detectors_json: r#"["email"]"#.to_string(),
+                custom_rules_json: "[]".to_string(),
+                created_at: 1,
+                updated_at,
+            })
+            .expect("upsert content policy");
+    };
+    let frame = json!({
+        "type": "response.create",
+        "model": "gpt-5.4",
+        "previous_response_id": "resp_ops@example.com",
+        "input": [{ "role": "user", "content": "mail ops@example.com" }]
+    })
+    .to_string();
+
+    upsert_policy("redact", 1);
+    let text = apply_content_policy_with_storage(
+        &storage,
+        "trc_ws_content_policy_redact",
+        &api_key,
+        frame.clone(),
+    )
+    .expect("redact websocket frame");
+    let value: Value = serde_json::from_str(&text).expect("parse redacted frame");
+    assert_eq!(value["input"][0]["content"], "mail [REDACTED:email]");
+    assert_eq!(value["previous_response_id"], "resp_ops@example.com");
+    assert_eq!(value["type"], "response.create");
+
+    upsert_policy("block", 2);
+    let err =
+        apply_content_policy_with_storage(&storage, "trc_ws_content_policy_block", &api_key, frame)
+            .expect_err("block websocket frame");
+    assert_eq!(err.status, 400);
+    assert_eq!(err.code, "content_policy_violation");
+    assert!(err.message.contains("email"));
+}
+
 fn sample_account() -> Account {
     Account {
         id: "acc-test".to_string(),
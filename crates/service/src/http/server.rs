@@ -14,6 +14,7 @@ use crate::http::proxy_runtime::run_front_proxy;
 /// 返回函数执行结果
 pub fn start_http(addr: &str) -> std::io::Result<()> {
     let backend = start_backend_server()?;
+    crate::gateway::resume_unfinished_batches();
     let result = run_front_proxy(addr, &backend.addr);
     wake_backend_shutdown(&backend.addr);
     let _ = backend.join.join();
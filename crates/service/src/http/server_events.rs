@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use axum::extract::FromRequestParts;
+use axum::http::{
+    HeaderMap as AxumHeaderMap, HeaderValue as AxumHeaderValue, Request as HttpRequest,
+    StatusCode as AxumStatusCode,
+};
+use axum::response::{IntoResponse, Response as AxumResponse};
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use futures_util::stream;
+use tiny_http::{Header, Request, Response, StatusCode};
+
+use crate::event_bus::{
+    subscribe_events, EventStreamFilter, EventSubscription, ServerEvent, TopicFilter,
+};
+
+pub(crate) const SERVER_EVENTS_PATH: &str = "/events";
+const RESYNC_EVENT_NAME: &str = "resync";
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const WS_FORWARD_BUFFER: usize = 64;
+
+#[derive(Debug, Clone)]
+enum EventFrame {
+    Event(Arc<ServerEvent>),
+    Resync,
+    KeepAlive,
+}
+
+/// 订阅参数：`topics` 主题过滤，`lastEventId`（或 `Last-Event-ID` 头）断线续传。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ServerEventsQuery {
+    topics: Option<String>,
+    last_event_id: Option<String>,
+}
+
+fn parse_server_events_query(
+    query: Option<&str>,
+    last_event_id_header: Option<&str>,
+) -> ServerEventsQuery {
+    let mut parsed = ServerEventsQuery::default();
+    for (key, value) in url::form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+        match key.as_ref() {
+            "topics" | "topic" => parsed.topics = Some(value.into_owned()),
+            "lastEventId" | "last_event_id" => {
+                parsed.last_event_id =
+                    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+            }
+            _ => {}
+        }
+    }
+    // 中文注释：EventSource 自动重连只会带请求头，优先使用头里的最新进度。
+    if let Some(last_event_id) = last_event_id_header {
+        parsed.last_event_id = Some(last_event_id.to_string());
+    }
+    parsed
+}
+
+struct ServerEventCursor {
+    filter: EventStreamFilter,
+    replay: VecDeque<Arc<ServerEvent>>,
+    resync_pending: bool,
+    receiver: Receiver<Arc<ServerEvent>>,
+}
+
+impl ServerEventCursor {
+    fn new(filter: EventStreamFilter, subscription: EventSubscription) -> Self {
+        Self {
+            filter,
+            replay: subscription.replay.into(),
+            resync_pending: subscription.missed,
+            receiver: subscription.receiver,
+        }
+    }
+
+    /// 阻塞等待下一帧；订阅被总线断开时返回 None。
+    fn next_frame(&mut self) -> Option<EventFrame> {
+        if std::mem::take(&mut self.resync_pending) {
+            return Some(EventFrame::Resync);
+        }
+        while let Some(event) = self.replay.pop_front() {
+            if self.filter.accepts(&event) {
+                return Some(EventFrame::Event(event));
+            }
+        }
+        loop {
+            match self.receiver.recv_timeout(KEEPALIVE_INTERVAL) {
+                Ok(event) if self.filter.accepts(&event) => return Some(EventFrame::Event(event)),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Some(EventFrame::KeepAlive),
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+fn event_json(event: &ServerEvent) -> String {
+    serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string())
+}
+
+fn sse_frame(frame: &EventFrame) -> Vec<u8> {
+    match frame {
+        EventFrame::Event(event) => format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            event.event_id(),
+            event.topic,
+            event_json(event)
+        )
+        .into_bytes(),
+        EventFrame::Resync => format!("event: {RESYNC_EVENT_NAME}\ndata: {{}}\n\n").into_bytes(),
+        EventFrame::KeepAlive => b": keep-alive\n\n".to_vec(),
+    }
+}
+
+fn ws_frame(frame: &EventFrame) -> WsMessage {
+    match frame {
+        EventFrame::Event(event) => WsMessage::Text(event_json(event).into()),
+        EventFrame::Resync => WsMessage::Text(
+            serde_json::json!({ "topic": RESYNC_EVENT_NAME })
+                .to_string()
+                .into(),
+        ),
+        EventFrame::KeepAlive => WsMessage::Ping(Bytes::new()),
+    }
+}
+
+fn open_event_cursor(
+    query: &ServerEventsQuery,
+    actor: crate::RpcActor,
+) -> Result<ServerEventCursor, String> {
+    let topics = TopicFilter::parse(query.topics.as_deref())?;
+    let filter = EventStreamFilter { topics, actor };
+    Ok(ServerEventCursor::new(
+        filter,
+        subscribe_events(query.last_event_id.as_deref()),
+    ))
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn request_header_value<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str().trim())
+        .filter(|value| !value.is_empty())
+}
+
+fn axum_header_value<'a>(headers: &'a AxumHeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn response_header(name: &'static str, value: &'static str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid static header")
+}
+
+struct ServerEventSseStream {
+    cursor: ServerEventCursor,
+    pending: Vec<u8>,
+    pending_offset: usize,
+    opened: bool,
+}
+
+impl ServerEventSseStream {
+    fn new(cursor: ServerEventCursor) -> Self {
+        Self {
+            cursor,
+            pending: Vec::new(),
+            pending_offset: 0,
+            opened: false,
+        }
+    }
+
+    fn refill(&mut self) -> bool {
+        if !self.opened {
+            self.opened = true;
+            self.pending = b": connected\n\n".to_vec();
+        } else {
+            let Some(frame) = self.cursor.next_frame() else {
+                return false;
+            };
+            self.pending = sse_frame(&frame);
+        }
+        self.pending_offset = 0;
+        true
+    }
+}
+
+impl Read for ServerEventSseStream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+        if self.pending_offset >= self.pending.len() && !self.refill() {
+            return Ok(0);
+        }
+        let remaining = &self.pending[self.pending_offset..];
+        let count = remaining.len().min(out.len());
+        out[..count].copy_from_slice(&remaining[..count]);
+        self.pending_offset += count;
+        Ok(count)
+    }
+}
+
+pub(crate) fn handle_server_events(request: Request) {
+    if request.method().as_str() != "GET" {
+        let _ = request.respond(Response::from_string("{}").with_status_code(405));
+        return;
+    }
+    if !request_header_value(&request, "X-CodexManager-Rpc-Token")
+        .is_some_and(crate::rpc_auth_token_matches)
+    {
+        let _ = request.respond(Response::from_string("{}").with_status_code(401));
+        return;
+    }
+    let actor = crate::RpcActor::from_parts(
+        request_header_value(&request, "X-CodexManager-Rpc-Actor-Role"),
+        request_header_value(&request, "X-CodexManager-Rpc-Actor-User-Id"),
+    );
+    let query = parse_server_events_query(
+        request.url().split_once('?').map(|(_, query)| query),
+        request_header_value(&request, "Last-Event-ID"),
+    );
+    let cursor = match open_event_cursor(&query, actor) {
+        Ok(cursor) => cursor,
+        Err(err) => {
+            let _ = request.respond(Response::from_string(error_body(&err)).with_status_code(400));
+            return;
+        }
+    };
+    let headers = vec![
+        response_header("Content-Type", "text/event-stream"),
+        response_header("Cache-Control", "no-cache"),
+        response_header("Connection", "keep-alive"),
+        response_header("X-Accel-Buffering", "no"),
+    ];
+    let response = Response::new(
+        StatusCode(200),
+        headers,
+        ServerEventSseStream::new(cursor),
+        None,
+        None,
+    );
+    let _ = request.respond(response);
+}
+
+/// 同一路径同时提供 SSE 与 WebSocket：带 Upgrade 头时升级为 WebSocket，否则返回事件流。
+pub(crate) async fn handle_server_events_http(request: HttpRequest<Body>) -> AxumResponse {
+    let (mut parts, _) = request.into_parts();
+    if !axum_header_value(&parts.headers, "X-CodexManager-Rpc-Token")
+        .is_some_and(crate::rpc_auth_token_matches)
+    {
+        return (AxumStatusCode::UNAUTHORIZED, "{}").into_response();
+    }
+    let actor = crate::RpcActor::from_parts(
+        axum_header_value(&parts.headers, "X-CodexManager-Rpc-Actor-Role"),
+        axum_header_value(&parts.headers, "X-CodexManager-Rpc-Actor-User-Id"),
+    );
+    let query = parse_server_events_query(
+        parts.uri.query(),
+        axum_header_value(&parts.headers, "Last-Event-ID"),
+    );
+    let cursor = match open_event_cursor(&query, actor) {
+        Ok(cursor) => cursor,
+        Err(err) => return (AxumStatusCode::BAD_REQUEST, error_body(&err)).into_response(),
+    };
+
+    if crate::http::responses_websocket::is_websocket_upgrade_request(&parts.headers) {
+        return match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+            Ok(ws) => ws.on_upgrade(move |socket| run_server_events_websocket(socket, cursor)),
+            Err(err) => (
+                AxumStatusCode::BAD_REQUEST,
+                error_body(&format!("websocket upgrade rejected: {err}")),
+            )
+                .into_response(),
+        };
+    }
+
+    let event_stream = stream::unfold((Some(cursor), false), |(cursor, opened)| async move {
+        if !opened {
+            return Some((
+                Ok::<Bytes, Infallible>(Bytes::from_static(b": connected\n\n")),
+                (cursor, true),
+            ));
+        }
+        let mut cursor = cursor?;
+        let (cursor, frame) = tokio::task::spawn_blocking(move || {
+            let frame = cursor.next_frame();
+            (cursor, frame)
+        })
+        .await
+        .ok()?;
+        let frame = frame?;
+        Some((Ok(Bytes::from(sse_frame(&frame))), (Some(cursor), true)))
+    });
+
+    let mut response = AxumResponse::new(Body::from_stream(event_stream));
+    *response.status_mut() = AxumStatusCode::OK;
+    response.headers_mut().insert(
+        "content-type",
+        AxumHeaderValue::from_static("text/event-stream"),
+    );
+    response
+        .headers_mut()
+        .insert("cache-control", AxumHeaderValue::from_static("no-cache"));
+    response
+        .headers_mut()
+        .insert("x-accel-buffering", AxumHeaderValue::from_static("no"));
+    response
+}
+
+async fn run_server_events_websocket(mut socket: WebSocket, mut cursor: ServerEventCursor) {
+    // 中文注释：总线是阻塞通道，用独立阻塞任务转发到异步通道，便于同时监听客户端关闭。
+    let (sender, mut receiver) = tokio::sync::mpsc::channel::<EventFrame>(WS_FORWARD_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        while let Some(frame) = cursor.next_frame() {
+            if sender.blocking_send(frame).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            frame = receiver.recv() => {
+                let Some(frame) = frame else {
+                    let _ = socket.send(WsMessage::Close(None)).await;
+                    break;
+                };
+                if socket.send(ws_frame(&frame)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                // 中文注释：客户端消息（含 pong）不需要处理，订阅条件在握手时已确定。
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "server_events_tests.rs"]
+mod tests;
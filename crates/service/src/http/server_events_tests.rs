@@ -0,0 +1,109 @@
+use super::*;
+use crate::event_bus::{EventAudience, TOPIC_ACCOUNT_STATUS, TOPIC_REQUEST_COMPLETED};
+use crossbeam_channel::bounded;
+
+fn event(id: u64, topic: &'static str, audience: EventAudience) -> Arc<ServerEvent> {
+    Arc::new(ServerEvent {
+        id,
+        topic,
+        at: 1775900000,
+        data: serde_json::json!({ "id": id }),
+        audience,
+    })
+}
+
+#[test]
+fn server_events_query_prefers_last_event_id_header() {
+    let parsed = parse_server_events_query(
+        Some("topics=account.*%2Crequest.completed&lastEventId=7"),
+        None,
+    );
+    assert_eq!(
+        parsed.topics.as_deref(),
+        Some("account.*,request.completed")
+    );
+    assert_eq!(parsed.last_event_id.as_deref(), Some("7"));
+
+    let parsed = parse_server_events_query(
+        Some("lastEventId=1775900000000-7"),
+        Some("1775900000000-12"),
+    );
+    assert_eq!(parsed.last_event_id.as_deref(), Some("1775900000000-12"));
+    assert_eq!(
+        parse_server_events_query(Some("lastEventId=%20"), None),
+        ServerEventsQuery::default()
+    );
+}
+
+#[test]
+fn event_cursor_emits_resync_then_filtered_replay_and_live_events() {
+    let (sender, receiver) = bounded(8);
+    let filter = EventStreamFilter {
+        topics: TopicFilter::parse(Some("request.completed")).expect("topics"),
+        actor: crate::RpcActor::from_parts(Some(crate::ROLE_MEMBER), Some("user-1")),
+    };
+    let mut cursor = ServerEventCursor::new(
+        filter,
+        EventSubscription {
+            replay: vec![
+                event(1, TOPIC_ACCOUNT_STATUS, EventAudience::Admin),
+                event(
+                    2,
+                    TOPIC_REQUEST_COMPLETED,
+                    EventAudience::User("user-2".to_string()),
+                ),
+                event(
+                    3,
+                    TOPIC_REQUEST_COMPLETED,
+                    EventAudience::User("user-1".to_string()),
+                ),
+            ],
+            missed: true,
+            receiver,
+        },
+    );
+    sender
+        .send(event(4, TOPIC_REQUEST_COMPLETED, EventAudience::Admin))
+        .expect("send hidden");
+    sender
+        .send(event(
+            5,
+            TOPIC_REQUEST_COMPLETED,
+            EventAudience::User("user-1".to_string()),
+        ))
+        .expect("send visible");
+    drop(sender);
+
+    let frames = std::iter::from_fn(|| cursor.next_frame()).collect::<Vec<_>>();
+    assert!(matches!(frames[0], EventFrame::Resync));
+    let ids = frames[1..]
+        .iter()
+        .map(|frame| match frame {
+            EventFrame::Event(event) => event.id,
+            other => panic!("unexpected frame: {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![3, 5]);
+}
+
+#[test]
+fn server_event_sse_frame_carries_id_and_topic() {
+    let frame = String::from_utf8(sse_frame(&EventFrame::Event(event(
+        42,
+        TOPIC_ACCOUNT_STATUS,
+        EventAudience::Admin,
+    ))))
+    .expect("utf8 frame");
+
+    assert!(frame.starts_with(&format!(
+        "id: {}\nevent: account.status\ndata: {{",
+        crate::event_bus::format_event_id(42)
+    )));
+    assert!(frame.contains("\"topic\":\"account.status\""));
+    assert!(!frame.contains("audience"));
+    assert!(frame.ends_with("\n\n"));
+    assert_eq!(
+        String::from_utf8(sse_frame(&EventFrame::Resync)).expect("utf8"),
+        "event: resync\ndata: {}\n\n"
+    );
+}
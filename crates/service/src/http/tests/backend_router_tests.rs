@@ -43,6 +43,18 @@ fn resolves_usage_refresh_events_route() {
     );
 }
 
+#[test]
+fn resolves_server_events_route_with_query() {
+    assert_eq!(
+        resolve_backend_route("GET", "/events?topics=account.*&lastEventId=3"),
+        BackendRoute::ServerEvents
+    );
+    assert_eq!(
+        resolve_backend_route("GET", "/events/unknown"),
+        BackendRoute::Gateway
+    );
+}
+
 /// 函数 `resolves_metrics_route`
 ///
 /// 作者: gaohongshun
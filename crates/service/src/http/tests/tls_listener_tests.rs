@@ -0,0 +1,216 @@
+use super::*;
+use axum::routing::get;
+use rcgen::{BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+static TEST_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+struct TestCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+struct TestCert {
+    cert_pem: String,
+    key_pem: String,
+    der: Vec<u8>,
+}
+
+fn new_test_dir() -> PathBuf {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("unix ts")
+        .as_nanos();
+    let counter = TEST_DIR_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!(
+        "codexmanager-tls-{}-{counter}-{nonce}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).expect("create test dir");
+    dir
+}
+
+fn test_ca() -> TestCa {
+    let mut params = CertificateParams::new(Vec::<String>::new()).expect("ca params");
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "codexmanager test ca");
+    let key = KeyPair::generate().expect("ca key");
+    let cert = params.self_signed(&key).expect("ca cert");
+    TestCa { cert, key }
+}
+
+fn signed_cert(ca: &TestCa, name: &str, usage: ExtendedKeyUsagePurpose) -> TestCert {
+    let mut params = CertificateParams::new(vec![name.to_string()]).expect("leaf params");
+    params.extended_key_usages = vec![usage];
+    let key = KeyPair::generate().expect("leaf key");
+    let cert = params
+        .signed_by(&key, &ca.cert, &ca.key)
+        .expect("sign leaf cert");
+    TestCert {
+        cert_pem: cert.pem(),
+        key_pem: key.serialize_pem(),
+        der: cert.der().to_vec(),
+    }
+}
+
+fn write_settings(
+    dir: &Path,
+    ca: &TestCa,
+    server: &TestCert,
+    client_auth_required: bool,
+) -> TlsSettings {
+    let settings = TlsSettings {
+        cert_path: dir.join("server.pem"),
+        key_path: dir.join("server.key"),
+        client_ca_path: Some(dir.join("client-ca.pem")),
+        client_auth_required,
+    };
+    std::fs::write(&settings.cert_path, &server.cert_pem).expect("write cert");
+    std::fs::write(&settings.key_path, &server.key_pem).expect("write key");
+    std::fs::write(
+        settings.client_ca_path.as_ref().expect("ca path"),
+        ca.cert.pem(),
+    )
+    .expect("write ca");
+    settings
+}
+
+#[test]
+fn client_cert_key_map_normalizes_fingerprints() {
+    let fingerprint = "AB".repeat(32);
+    let colon_fingerprint = vec!["cd"; 32].join(":");
+    let mapping = parse_client_cert_key_map(&format!(
+        "sha256:{fingerprint}=key-a; {colon_fingerprint} = key-b ,"
+    ))
+    .expect("parse mapping");
+
+    assert_eq!(
+        mapping.get(&"ab".repeat(32)).map(String::as_str),
+        Some("key-a")
+    );
+    assert_eq!(
+        mapping.get(&"cd".repeat(32)).map(String::as_str),
+        Some("key-b")
+    );
+    assert!(parse_client_cert_key_map("abc=key-a").is_err());
+    assert!(parse_client_cert_key_map(&format!("{fingerprint}=")).is_err());
+    assert!(parse_client_cert_key_map(&fingerprint).is_err());
+    assert!(parse_client_cert_key_map("").expect("empty").is_empty());
+}
+
+#[test]
+fn client_auth_mode_defaults_to_required() {
+    assert_eq!(parse_client_auth_required(None), Ok(true));
+    assert_eq!(parse_client_auth_required(Some(" Optional ")), Ok(false));
+    assert_eq!(parse_client_auth_required(Some("required")), Ok(true));
+    assert!(parse_client_auth_required(Some("sometimes")).is_err());
+}
+
+#[test]
+fn reloader_swaps_config_when_certificate_changes() {
+    let dir = new_test_dir();
+    let ca = test_ca();
+    let server = signed_cert(&ca, "localhost", ExtendedKeyUsagePurpose::ServerAuth);
+    let settings = write_settings(&dir, &ca, &server, true);
+    let reloader = TlsConfigReloader::load(settings.clone()).expect("load tls config");
+    let initial = reloader.current();
+
+    assert_eq!(reloader.reload_if_changed(), Ok(false));
+
+    std::fs::write(&settings.cert_path, "not a certificate").expect("break cert");
+    std::fs::File::options()
+        .write(true)
+        .open(&settings.cert_path)
+        .and_then(|file| file.set_modified(SystemTime::now() + Duration::from_secs(10)))
+        .expect("touch cert");
+    assert!(reloader.reload_if_changed().is_err());
+    assert!(Arc::ptr_eq(&initial, &reloader.current()));
+
+    let renewed = signed_cert(&ca, "localhost", ExtendedKeyUsagePurpose::ServerAuth);
+    std::fs::write(&settings.cert_path, &renewed.cert_pem).expect("write renewed cert");
+    std::fs::write(&settings.key_path, &renewed.key_pem).expect("write renewed key");
+    std::fs::File::options()
+        .write(true)
+        .open(&settings.cert_path)
+        .and_then(|file| file.set_modified(SystemTime::now() + Duration::from_secs(20)))
+        .expect("touch cert");
+    assert_eq!(reloader.reload_if_changed(), Ok(true));
+    assert!(!Arc::ptr_eq(&initial, &reloader.current()));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn mtls_listener_requires_client_cert_and_exposes_fingerprint() {
+    let dir = new_test_dir();
+    let ca = test_ca();
+    let server = signed_cert(&ca, "localhost", ExtendedKeyUsagePurpose::ServerAuth);
+    let client = signed_cert(&ca, "client-a", ExtendedKeyUsagePurpose::ClientAuth);
+    let settings = write_settings(&dir, &ca, &server, true);
+    let reloader = TlsConfigReloader::load(settings).expect("load tls config");
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime");
+    runtime.block_on(async move {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let app = Router::new().route(
+            "/peer",
+            get(|ConnectInfo(peer): ConnectInfo<TlsPeer>| async move {
+                peer.client_cert_sha256.unwrap_or_default()
+            }),
+        );
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_task =
+            tokio::spawn(serve_tls_on_listener(listener, reloader, app, async move {
+                let _ = shutdown_rx.await;
+            }));
+
+        let client_builder = || {
+            reqwest::Client::builder()
+                .use_rustls_tls()
+                .no_proxy()
+                .resolve("localhost", addr)
+                .add_root_certificate(
+                    reqwest::Certificate::from_pem(ca.cert.pem().as_bytes()).expect("ca pem"),
+                )
+        };
+        let url = format!("https://localhost:{}/peer", addr.port());
+
+        let anonymous = client_builder().build().expect("anonymous client");
+        assert!(anonymous.get(url.as_str()).send().await.is_err());
+
+        let identity = reqwest::Identity::from_pem(
+            format!("{}{}", client.cert_pem, client.key_pem).as_bytes(),
+        )
+        .expect("client identity");
+        let authenticated = client_builder()
+            .identity(identity)
+            .build()
+            .expect("mtls client");
+        let body = authenticated
+            .get(url.as_str())
+            .send()
+            .await
+            .expect("mtls request")
+            .text()
+            .await
+            .expect("response body");
+        assert_eq!(body, client_cert_sha256(&client.der));
+
+        drop(anonymous);
+        drop(authenticated);
+        let _ = shutdown_tx.send(());
+        server_task
+            .await
+            .expect("join server")
+            .expect("server result");
+    });
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use axum::extract::connect_info::{ConnectInfo, Connected};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::serve::{IncomingStream, Listener};
+use axum::Router;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use sha2::{Digest, Sha256};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+pub const ENV_TLS_ADDR: &str = "CODEXMANAGER_TLS_ADDR";
+pub const ENV_WEB_TLS_ADDR: &str = "CODEXMANAGER_WEB_TLS_ADDR";
+const ENV_TLS_CERT_FILE: &str = "CODEXMANAGER_TLS_CERT_FILE";
+const ENV_TLS_KEY_FILE: &str = "CODEXMANAGER_TLS_KEY_FILE";
+const ENV_TLS_CLIENT_CA_FILE: &str = "CODEXMANAGER_TLS_CLIENT_CA_FILE";
+const ENV_TLS_CLIENT_AUTH: &str = "CODEXMANAGER_TLS_CLIENT_AUTH";
+const ENV_TLS_CLIENT_CERT_KEYS: &str = "CODEXMANAGER_TLS_CLIENT_CERT_KEYS";
+
+const CLIENT_AUTH_REQUIRED: &str = "required";
+const CLIENT_AUTH_OPTIONAL: &str = "optional";
+const TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const TLS_ACCEPT_QUEUE: usize = 64;
+const SHA256_HEX_LEN: usize = 64;
+const API_KEY_HEADERS: &[&str] = &["authorization", "x-api-key", "x-goog-api-key"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TlsSettings {
+    pub(crate) cert_path: PathBuf,
+    pub(crate) key_path: PathBuf,
+    pub(crate) client_ca_path: Option<PathBuf>,
+    pub(crate) client_auth_required: bool,
+}
+
+fn read_env_trim(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 读取 TLS 监听地址；未配置时不启用 TLS 监听。
+pub fn tls_listener_addr(env_name: &str) -> Option<String> {
+    read_env_trim(env_name)
+}
+
+fn parse_client_auth_required(raw: Option<&str>) -> Result<bool, String> {
+    match raw.map(str::trim).filter(|value| !value.is_empty()) {
+        None => Ok(true),
+        Some(value) if value.eq_ignore_ascii_case(CLIENT_AUTH_REQUIRED) => Ok(true),
+        Some(value) if value.eq_ignore_ascii_case(CLIENT_AUTH_OPTIONAL) => Ok(false),
+        Some(value) => Err(format!(
+            "invalid {ENV_TLS_CLIENT_AUTH}: {value} (expected {CLIENT_AUTH_REQUIRED} or {CLIENT_AUTH_OPTIONAL})"
+        )),
+    }
+}
+
+/// 函数 `tls_settings_from_env`
+///
+/// 证书与私钥必须同时配置；配置客户端 CA 后开启 mTLS，默认要求客户端出示证书。
+pub(crate) fn tls_settings_from_env() -> Result<TlsSettings, String> {
+    let cert_path = read_env_trim(ENV_TLS_CERT_FILE)
+        .ok_or_else(|| format!("{ENV_TLS_CERT_FILE} is required when TLS is enabled"))?;
+    let key_path = read_env_trim(ENV_TLS_KEY_FILE)
+        .ok_or_else(|| format!("{ENV_TLS_KEY_FILE} is required when TLS is enabled"))?;
+    Ok(TlsSettings {
+        cert_path: PathBuf::from(cert_path),
+        key_path: PathBuf::from(key_path),
+        client_ca_path: read_env_trim(ENV_TLS_CLIENT_CA_FILE).map(PathBuf::from),
+        client_auth_required: parse_client_auth_required(
+            read_env_trim(ENV_TLS_CLIENT_AUTH).as_deref(),
+        )?,
+    })
+}
+
+/// 证书指纹统一为小写十六进制；兼容 `sha256:` 前缀与冒号分隔写法。
+pub(crate) fn normalize_cert_fingerprint(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let raw = raw
+        .get(..7)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("sha256:"))
+        .map(|_| &raw[7..])
+        .unwrap_or(raw);
+    let normalized = raw
+        .chars()
+        .filter(|ch| *ch != ':')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    (normalized.len() == SHA256_HEX_LEN && normalized.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .then_some(normalized)
+}
+
+/// 函数 `parse_client_cert_key_map`
+///
+/// 解析 `指纹=平台 Key ID` 列表（逗号或分号分隔），用于把已验证的客户端证书映射到平台 Key。
+pub(crate) fn parse_client_cert_key_map(raw: &str) -> Result<HashMap<String, String>, String> {
+    let mut mapping = HashMap::new();
+    for entry in raw
+        .split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let (fingerprint, key_id) = entry.split_once('=').ok_or_else(|| {
+            format!("invalid client cert mapping: {entry} (expected fingerprint=keyId)")
+        })?;
+        let fingerprint = normalize_cert_fingerprint(fingerprint)
+            .ok_or_else(|| format!("invalid client cert sha256 fingerprint: {fingerprint}"))?;
+        let key_id = key_id.trim();
+        if key_id.is_empty() {
+            return Err(format!(
+                "missing platform key id for client cert {fingerprint}"
+            ));
+        }
+        mapping.insert(fingerprint, key_id.to_string());
+    }
+    Ok(mapping)
+}
+
+pub(crate) fn client_cert_sha256(der: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(der))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|err| format!("read certificate {} failed: {err}", path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("parse certificate {} failed: {err}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("no certificate found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// 函数 `load_server_config`
+///
+/// 按当前文件内容构建 rustls 服务端配置；仅协商 HTTP/1.1，与 axum 监听能力一致。
+pub(crate) fn load_server_config(settings: &TlsSettings) -> Result<Arc<ServerConfig>, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let certs = load_certs(settings.cert_path.as_path())?;
+    let key = PrivateKeyDer::from_pem_file(settings.key_path.as_path()).map_err(|err| {
+        format!(
+            "read private key {} failed: {err}",
+            settings.key_path.display()
+        )
+    })?;
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| format!("build tls config failed: {err}"))?;
+    let builder = match settings.client_ca_path.as_deref() {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .map_err(|err| format!("load client ca {} failed: {err}", ca_path.display()))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = if settings.client_auth_required {
+                verifier
+            } else {
+                verifier.allow_unauthenticated()
+            };
+            builder.with_client_cert_verifier(
+                verifier
+                    .build()
+                    .map_err(|err| format!("build client cert verifier failed: {err}"))?,
+            )
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .map_err(|err| format!("load tls certificate failed: {err}"))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+type FileStamp = Vec<Option<(SystemTime, u64)>>;
+
+fn settings_file_stamp(settings: &TlsSettings) -> FileStamp {
+    [
+        Some(settings.cert_path.as_path()),
+        Some(settings.key_path.as_path()),
+        settings.client_ca_path.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    })
+    .collect()
+}
+
+/// 持有当前生效的 TLS 配置；证书文件变化后在下次握手时切换到新配置。
+pub(crate) struct TlsConfigReloader {
+    settings: TlsSettings,
+    current: RwLock<Arc<ServerConfig>>,
+    stamp: Mutex<FileStamp>,
+}
+
+impl TlsConfigReloader {
+    pub(crate) fn load(settings: TlsSettings) -> Result<Arc<Self>, String> {
+        let stamp = settings_file_stamp(&settings);
+        let config = load_server_config(&settings)?;
+        Ok(Arc::new(Self {
+            settings,
+            current: RwLock::new(config),
+            stamp: Mutex::new(stamp),
+        }))
+    }
+
+    fn current(&self) -> Arc<ServerConfig> {
+        match self.current.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// 文件时间戳或大小变化时重新加载；加载失败时保留旧配置并返回错误。
+    pub(crate) fn reload_if_changed(&self) -> Result<bool, String> {
+        let stamp = settings_file_stamp(&self.settings);
+        {
+            let mut last = crate::lock_utils::lock_recover(&self.stamp, "tls_file_stamp");
+            if *last == stamp {
+                return Ok(false);
+            }
+            *last = stamp;
+        }
+        let config = load_server_config(&self.settings)?;
+        match self.current.write() {
+            Ok(mut guard) => *guard = config,
+            Err(poisoned) => *poisoned.into_inner() = config,
+        }
+        Ok(true)
+    }
+}
+
+/// TLS 连接的对端信息；`client_cert_sha256` 为已通过校验的客户端证书指纹。
+#[derive(Debug, Clone)]
+pub struct TlsPeer {
+    pub remote_addr: SocketAddr,
+    pub client_cert_sha256: Option<String>,
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for TlsPeer {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        stream.remote_addr().clone()
+    }
+}
+
+/// 包装 TCP 监听：握手在独立任务中完成，慢握手不会阻塞后续连接的 accept。
+pub struct TlsListener {
+    accepted: mpsc::Receiver<(TlsStream<TcpStream>, TlsPeer)>,
+    local_addr: SocketAddr,
+    accept_task: JoinHandle<()>,
+}
+
+impl TlsListener {
+    pub(crate) fn new(listener: TcpListener, reloader: Arc<TlsConfigReloader>) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (sender, accepted) = mpsc::channel(TLS_ACCEPT_QUEUE);
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (stream, remote_addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        log::warn!("event=tls_accept_failed err={err}");
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        continue;
+                    }
+                };
+                let acceptor = TlsAcceptor::from(reloader.current());
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let tls_stream =
+                        match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream))
+                            .await
+                        {
+                            Ok(Ok(tls_stream)) => tls_stream,
+                            Ok(Err(err)) => {
+                                log::debug!(
+                                    "event=tls_handshake_failed remote_addr={remote_addr} err={err}"
+                                );
+                                return;
+                            }
+                            Err(_) => {
+                                log::debug!(
+                                    "event=tls_handshake_timeout remote_addr={remote_addr}"
+                                );
+                                return;
+                            }
+                        };
+                    let client_cert_sha256 = tls_stream
+                        .get_ref()
+                        .1
+                        .peer_certificates()
+                        .and_then(|certs| certs.first())
+                        .map(|cert| client_cert_sha256(cert.as_ref()));
+                    let peer = TlsPeer {
+                        remote_addr,
+                        client_cert_sha256,
+                    };
+                    let _ = sender.send((tls_stream, peer)).await;
+                });
+            }
+        });
+        Ok(Self {
+            accepted,
+            local_addr,
+            accept_task,
+        })
+    }
+}
+
+impl Drop for TlsListener {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = TlsPeer;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.accepted.recv().await {
+            Some(accepted) => accepted,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(TlsPeer {
+            remote_addr: self.local_addr,
+            client_cert_sha256: None,
+        })
+    }
+}
+
+/// 函数 `serve_tls_on_listener`
+///
+/// 在已绑定的 TCP 监听上提供 TLS 服务，并定期检查证书文件以热加载。
+pub(crate) async fn serve_tls_on_listener<F>(
+    listener: TcpListener,
+    reloader: Arc<TlsConfigReloader>,
+    app: Router,
+    shutdown: F,
+) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let watcher = {
+        let reloader = reloader.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TLS_RELOAD_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match reloader.reload_if_changed() {
+                    Ok(true) => log::info!("event=tls_config_reloaded"),
+                    Ok(false) => {}
+                    Err(err) => log::warn!("event=tls_config_reload_failed err={err}"),
+                }
+            }
+        })
+    };
+    let listener = TlsListener::new(listener, reloader)?;
+    let result = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<TlsPeer>(),
+    )
+    .with_graceful_shutdown(shutdown)
+    .await
+    .map_err(io::Error::other);
+    watcher.abort();
+    result
+}
+
+/// 函数 `serve_tls_router`
+///
+/// 按 `CODEXMANAGER_TLS_*` 配置在 `addr` 上启动 TLS 监听；service 与 web 共用。
+pub async fn serve_tls_router<F>(addr: &str, app: Router, shutdown: F) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let reloader = tls_settings_from_env()
+        .and_then(TlsConfigReloader::load)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let listener = TcpListener::bind(addr.trim()).await?;
+    log::info!(
+        "event=tls_listener_started addr={} client_ca={}",
+        addr.trim(),
+        reloader.settings.client_ca_path.is_some()
+    );
+    serve_tls_on_listener(listener, reloader, app, shutdown).await
+}
+
+fn has_api_key_header(request: &Request) -> bool {
+    API_KEY_HEADERS
+        .iter()
+        .any(|name| request.headers().contains_key(*name))
+}
+
+async fn client_cert_auth_middleware(
+    State(mapping): State<Arc<HashMap<String, String>>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let mapped =
+        request
+            .extensions()
+            .get::<ConnectInfo<TlsPeer>>()
+            .and_then(|ConnectInfo(peer)| {
+                let fingerprint = peer.client_cert_sha256.as_deref()?;
+                Some((peer.remote_addr, mapping.get(fingerprint)?.clone()))
+            });
+    if let Some((remote_addr, key_id)) = mapped.filter(|_| !has_api_key_header(&request)) {
+        log::debug!("event=tls_client_cert_auth remote_addr={remote_addr} key_id={key_id}");
+        let secret = tokio::task::spawn_blocking(move || {
+            crate::storage_helpers::open_storage()
+                .and_then(|storage| storage.find_api_key_secret_by_id(key_id.as_str()).ok())
+                .flatten()
+        })
+        .await
+        .ok()
+        .flatten();
+        if let Some(value) =
+            secret.and_then(|secret| HeaderValue::from_str(&format!("Bearer {secret}")).ok())
+        {
+            request.headers_mut().insert(header::AUTHORIZATION, value);
+        }
+    }
+    next.run(request).await
+}
+
+/// 函数 `with_client_cert_auth`
+///
+/// 未携带 API Key 的请求若出示了已映射的客户端证书，则以对应平台 Key 身份转发。
+pub(crate) fn with_client_cert_auth(app: Router) -> Result<Router, String> {
+    let mapping = read_env_trim(ENV_TLS_CLIENT_CERT_KEYS)
+        .map(|raw| parse_client_cert_key_map(raw.as_str()))
+        .transpose()?
+        .unwrap_or_default();
+    if mapping.is_empty() {
+        return Ok(app);
+    }
+    Ok(app.layer(axum::middleware::from_fn_with_state(
+        Arc::new(mapping),
+        client_cert_auth_middleware,
+    )))
+}
+
+#[cfg(test)]
+#[path = "tests/tls_listener_tests.rs"]
+mod tests;
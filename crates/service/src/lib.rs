@@ -6,6 +6,7 @@ mod account;
 mod account_identity;
 mod agent_identity;
 mod aggregate_api;
+mod alerts;
 mod apikey;
 pub(crate) mod app_settings;
 mod auth;
@@ -16,7 +17,9 @@ mod codex_skill_repositories;
 mod codex_skills;
 mod codex_skills_marketplace;
 mod dashboard;
+mod declarative_config;
 mod errors;
+mod event_bus;
 mod gateway;
 mod http;
 mod lifecycle;
@@ -41,6 +44,8 @@ pub(crate) use account::delete as account_delete;
 pub(crate) use account::delete_many as account_delete_many;
 pub(crate) use account::export as account_export;
 pub(crate) use account::group as account_group;
+pub(crate) use account::group_policy as account_group_policy;
+pub(crate) use account::health_probe as account_health_probe;
 pub(crate) use account::import as account_import;
 pub(crate) use account::list as account_list;
 pub(crate) use account::plan as account_plan;
@@ -49,10 +54,19 @@ pub(crate) use account::proxy_testing::presets::proxy_test_presets;
 pub(crate) use account::status as account_status;
 pub(crate) use account::update as account_update;
 pub(crate) use account::warmup as account_warmup;
+pub(crate) use aggregate_api::azure::{
+    delete_aggregate_api_deployment, list_aggregate_api_deployments, set_aggregate_api_deployment,
+};
+pub(crate) use aggregate_api::discovery::{
+    discover_aggregate_api_models, ensure_aggregate_api_model_discovery,
+    reload_aggregate_api_model_discovery_from_env,
+};
 pub(crate) use aggregate_api::{
     create_aggregate_api, delete_aggregate_api, list_aggregate_apis, read_aggregate_api_secret,
     refresh_aggregate_api_balance, test_aggregate_api_connection, update_aggregate_api,
 };
+pub(crate) use apikey::batches as apikey_batches;
+pub(crate) use apikey::content_policy as apikey_content_policy;
 pub(crate) use apikey::create as apikey_create;
 pub(crate) use apikey::delete as apikey_delete;
 pub(crate) use apikey::disable as apikey_disable;
@@ -60,6 +74,9 @@ pub(crate) use apikey::enable as apikey_enable;
 pub(crate) use apikey::list as apikey_list;
 pub(crate) use apikey::profile as apikey_profile;
 pub(crate) use apikey::read_secret as apikey_read_secret;
+pub(crate) use apikey::response_cache as apikey_response_cache;
+pub(crate) use apikey::rotate as apikey_rotate;
+pub(crate) use apikey::structured_output as apikey_structured_output;
 pub(crate) use apikey::update_model as apikey_update_model;
 pub(crate) use apikey::usage_stats as apikey_usage_stats;
 pub(crate) use auth::account as auth_account;
@@ -73,6 +90,8 @@ pub(crate) use model_groups::{
     resolve_api_key_model_group_access, set_model_group_models, set_model_group_users,
     upsert_model_group,
 };
+pub(crate) use proxy_registry::pools as proxy_pools;
+pub(crate) use proxy_registry::subscriptions as proxy_subscriptions;
 pub(crate) use proxy_registry::{
     cancel_proxy_test_job, create_proxy_profile, delete_proxy_profile,
     get_proxy_profile_diagnostics_history, get_proxy_profile_latency_test_history,
@@ -82,7 +101,10 @@ pub(crate) use proxy_registry::{
 };
 
 pub(crate) use requestlog::clear as requestlog_clear;
+pub(crate) use requestlog::content_policy as requestlog_content_policy;
 pub(crate) use requestlog::list as requestlog_list;
+pub(crate) use requestlog::route_decision as requestlog_route_decision;
+pub(crate) use requestlog::structured_output as requestlog_structured_output;
 pub(crate) use requestlog::summary as requestlog_summary;
 pub(crate) use requestlog::today_summary as requestlog_today_summary;
 pub(crate) use runtime::lock_utils;
@@ -168,7 +190,9 @@ pub use auth::{
     AppUserUpdateInput, AppWalletResult, BillingModeLockResult,
 };
 pub use auth::{rpc_auth_token, rpc_auth_token_matches};
+pub use http::tls_listener::{serve_tls_router, tls_listener_addr, ENV_TLS_ADDR, ENV_WEB_TLS_ADDR};
 pub use lifecycle::bootstrap::{initialize_storage_if_needed, portable};
+pub use lifecycle::drain::{draining, install_drain_signal_handler, shutdown_drain_timeout};
 pub use lifecycle::shutdown::{clear_shutdown_flag, request_shutdown, shutdown_requested};
 pub use lifecycle::startup::{start_one_shot_server, start_server, ServerHandle};
 pub use logging::init_logging;
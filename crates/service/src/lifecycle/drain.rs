@@ -0,0 +1,266 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicI64, AtomicU8, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const ENV_SHUTDOWN_DRAIN_TIMEOUT_SECS: &str = "CODEXMANAGER_SHUTDOWN_DRAIN_TIMEOUT_SECS";
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_SECS: u64 = 30;
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const PHASE_RUNNING: u8 = 0;
+const PHASE_DRAINING: u8 = 1;
+const PHASE_FINALIZING: u8 = 2;
+
+static DRAIN_PHASE: AtomicU8 = AtomicU8::new(PHASE_RUNNING);
+static DRAIN_STARTED_AT_MS: AtomicI64 = AtomicI64::new(0);
+static DRAIN_DEADLINE_AT_MS: AtomicI64 = AtomicI64::new(0);
+static ACTIVE_STREAM_SESSIONS: AtomicUsize = AtomicUsize::new(0);
+static DRAIN_NOTIFY: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DrainStatus {
+    pub state: &'static str,
+    pub started_at_ms: Option<i64>,
+    pub deadline_at_ms: Option<i64>,
+    pub remaining_ms: Option<i64>,
+    pub inflight_requests: usize,
+    pub inflight_streams: usize,
+}
+
+/// 前置代理直接承载的长连接（如 `/v1/responses` WebSocket）不经过 gateway 计数，单独登记。
+pub(crate) struct StreamSessionGuard;
+
+impl Drop for StreamSessionGuard {
+    fn drop(&mut self) {
+        ACTIVE_STREAM_SESSIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn begin_stream_session() -> StreamSessionGuard {
+    ACTIVE_STREAM_SESSIONS.fetch_add(1, Ordering::Relaxed);
+    StreamSessionGuard
+}
+
+/// 函数 `draining`
+///
+/// 排空开始后（含收尾阶段）返回 `true`，此时不再接收新的网关请求。
+pub fn draining() -> bool {
+    DRAIN_PHASE.load(Ordering::SeqCst) != PHASE_RUNNING
+}
+
+/// 等待进入排空阶段；长连接会话在空闲时据此主动关闭。
+pub(crate) async fn wait_for_drain() {
+    let notified = DRAIN_NOTIFY.notified();
+    if draining() {
+        return;
+    }
+    notified.await;
+}
+
+/// 函数 `shutdown_drain_timeout`
+///
+/// 停机排空等待在途请求的最长时间，`0` 表示不等待。
+pub fn shutdown_drain_timeout() -> Duration {
+    let secs = std::env::var(ENV_SHUTDOWN_DRAIN_TIMEOUT_SECS)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+/// 函数 `install_drain_signal_handler`
+///
+/// 独立服务进程收到 Ctrl+C / SIGTERM（如 `docker stop`）时先排空；排空中再次收到信号则立即退出。
+pub fn install_drain_signal_handler() {
+    let result = ctrlc::set_handler(|| {
+        if !begin_drain() {
+            log::warn!("event=service_drain_interrupted");
+            std::process::exit(130);
+        }
+    });
+    if let Err(err) = result {
+        log::warn!("event=service_drain_signal_handler_failed err={err}");
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn inflight_counts() -> (usize, usize) {
+    (
+        crate::gateway::gateway_active_requests(),
+        ACTIVE_STREAM_SESSIONS.load(Ordering::Relaxed),
+    )
+}
+
+/// 函数 `begin_drain`
+///
+/// 进入排空阶段并启动后台线程；已在排空或收尾时返回 `false`。
+pub(crate) fn begin_drain() -> bool {
+    if DRAIN_PHASE
+        .compare_exchange(
+            PHASE_RUNNING,
+            PHASE_DRAINING,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        )
+        .is_err()
+    {
+        return false;
+    }
+    let timeout = shutdown_drain_timeout();
+    let started_at_ms = now_millis();
+    DRAIN_STARTED_AT_MS.store(started_at_ms, Ordering::SeqCst);
+    DRAIN_DEADLINE_AT_MS.store(
+        started_at_ms.saturating_add(timeout.as_millis() as i64),
+        Ordering::SeqCst,
+    );
+    DRAIN_NOTIFY.notify_waiters();
+    let (requests, streams) = inflight_counts();
+    log::info!(
+        "event=service_drain_started timeout_secs={} inflight_requests={} inflight_streams={}",
+        timeout.as_secs(),
+        requests,
+        streams
+    );
+    if let Err(err) = thread::Builder::new()
+        .name("service-drain".to_string())
+        .spawn(run_drain)
+    {
+        log::warn!("event=service_drain_spawn_failed err={err}");
+        finish_drain();
+    }
+    true
+}
+
+fn drain_settled(
+    inflight_requests: usize,
+    inflight_streams: usize,
+    now_ms: i64,
+    deadline_ms: i64,
+) -> bool {
+    inflight_requests.saturating_add(inflight_streams) == 0 || now_ms >= deadline_ms
+}
+
+fn run_drain() {
+    loop {
+        let (requests, streams) = inflight_counts();
+        if drain_settled(
+            requests,
+            streams,
+            now_millis(),
+            DRAIN_DEADLINE_AT_MS.load(Ordering::SeqCst),
+        ) {
+            break;
+        }
+        thread::sleep(DRAIN_POLL_INTERVAL);
+    }
+    finish_drain();
+}
+
+/// 在途请求结束或到达截止时间后：落盘日志与统计，置位停机标记并唤醒监听循环。
+fn finish_drain() {
+    DRAIN_PHASE.store(PHASE_FINALIZING, Ordering::SeqCst);
+    let (requests, streams) = inflight_counts();
+    if requests > 0 || streams > 0 {
+        log::warn!(
+            "event=service_drain_deadline_reached abandoned_requests={} abandoned_streams={}",
+            requests,
+            streams
+        );
+    }
+    crate::gateway::flush_gateway_state_for_shutdown();
+    log::info!(
+        "event=service_drain_finished elapsed_ms={}",
+        now_millis().saturating_sub(DRAIN_STARTED_AT_MS.load(Ordering::SeqCst))
+    );
+    super::shutdown::mark_shutdown_requested();
+    if let Some(addr) = crate::http::backend_runtime::current_backend_addr() {
+        crate::http::backend_runtime::wake_backend_shutdown(addr.as_str());
+    }
+}
+
+pub(crate) fn reset_drain_state() {
+    DRAIN_PHASE.store(PHASE_RUNNING, Ordering::SeqCst);
+    DRAIN_STARTED_AT_MS.store(0, Ordering::SeqCst);
+    DRAIN_DEADLINE_AT_MS.store(0, Ordering::SeqCst);
+}
+
+fn phase_label(phase: u8) -> &'static str {
+    match phase {
+        PHASE_DRAINING => "draining",
+        PHASE_FINALIZING => "finalizing",
+        _ => "running",
+    }
+}
+
+fn build_drain_status(
+    phase: u8,
+    started_at_ms: i64,
+    deadline_at_ms: i64,
+    inflight: (usize, usize),
+    now_ms: i64,
+) -> DrainStatus {
+    let active = phase != PHASE_RUNNING;
+    DrainStatus {
+        state: phase_label(phase),
+        started_at_ms: active.then_some(started_at_ms),
+        deadline_at_ms: active.then_some(deadline_at_ms),
+        remaining_ms: active.then(|| deadline_at_ms.saturating_sub(now_ms).max(0)),
+        inflight_requests: inflight.0,
+        inflight_streams: inflight.1,
+    }
+}
+
+pub(crate) fn drain_status() -> DrainStatus {
+    build_drain_status(
+        DRAIN_PHASE.load(Ordering::SeqCst),
+        DRAIN_STARTED_AT_MS.load(Ordering::SeqCst),
+        DRAIN_DEADLINE_AT_MS.load(Ordering::SeqCst),
+        inflight_counts(),
+        now_millis(),
+    )
+}
+
+fn retry_after_secs_for(status: &DrainStatus) -> u64 {
+    // 中文注释：排空结束前本实例不会恢复，按剩余排空时间提示客户端，至少 1 秒。
+    let remaining_ms = status.remaining_ms.unwrap_or(0).max(0) as u64;
+    remaining_ms.div_ceil(1000).max(1)
+}
+
+/// 函数 `drain_retry_after_secs`
+///
+/// 排空期间拒绝新请求时 `Retry-After` 头的取值（秒）。
+pub(crate) fn drain_retry_after_secs() -> u64 {
+    retry_after_secs_for(&drain_status())
+}
+
+pub(crate) fn draining_error_message() -> String {
+    crate::gateway::bilingual_error(
+        "服务正在停机排空，暂不接收新请求",
+        "service is draining for shutdown; retry later",
+    )
+}
+
+pub(crate) fn drain_metrics_prometheus() -> String {
+    let status = drain_status();
+    format!(
+        "codexmanager_service_draining {}\n\
+codexmanager_service_drain_inflight_requests {}\n\
+codexmanager_service_drain_inflight_streams {}\n\
+codexmanager_service_drain_remaining_seconds {}\n",
+        u8::from(status.state != "running"),
+        status.inflight_requests,
+        status.inflight_streams,
+        status.remaining_ms.unwrap_or(0) / 1000,
+    )
+}
+
+#[cfg(test)]
+#[path = "tests/drain_tests.rs"]
+mod tests;
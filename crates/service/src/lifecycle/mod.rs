@@ -1,3 +1,4 @@
 pub mod bootstrap;
+pub mod drain;
 pub mod shutdown;
 pub mod startup;
@@ -33,6 +33,12 @@ pub fn shutdown_requested() -> bool {
 /// 无
 pub fn clear_shutdown_flag() {
     SHUTDOWN_REQUESTED.store(false, Ordering::SeqCst);
+    super::drain::reset_drain_state();
+}
+
+/// 排空结束后由排空线程置位，监听循环据此退出。
+pub(crate) fn mark_shutdown_requested() {
+    SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
 }
 
 /// 函数 `request_shutdown`
@@ -72,11 +72,14 @@ pub fn start_server(addr: &str) -> std::io::Result<()> {
     crate::storage_helpers::initialize_storage()
         .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
     crate::sync_runtime_settings_from_storage();
+    crate::declarative_config::ensure_declarative_config_sync();
     crate::app_settings::ensure_codex_latest_version_sync();
+    crate::ensure_aggregate_api_model_discovery();
     crate::usage_refresh::ensure_usage_polling();
     crate::usage_refresh::ensure_gateway_keepalive();
     crate::usage_refresh::ensure_token_refresh_polling();
     crate::usage_refresh::ensure_warmup_cron();
+    crate::usage_refresh::ensure_health_probe();
     crate::plugin::ensure_plugin_scheduler();
     crate::http::server::start_http(addr)
 }
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn drain_settles_when_idle_or_past_deadline() {
+    assert!(drain_settled(0, 0, 1_000, 5_000));
+    assert!(!drain_settled(1, 0, 1_000, 5_000));
+    assert!(!drain_settled(0, 2, 4_999, 5_000));
+    assert!(drain_settled(3, 1, 5_000, 5_000));
+}
+
+#[test]
+fn drain_status_reports_remaining_time_only_while_draining() {
+    let running = build_drain_status(PHASE_RUNNING, 0, 0, (2, 1), 10_000);
+    assert_eq!(running.state, "running");
+    assert_eq!(running.remaining_ms, None);
+    assert_eq!(running.inflight_requests, 2);
+    assert_eq!(retry_after_secs_for(&running), 1);
+
+    let draining = build_drain_status(PHASE_DRAINING, 10_000, 40_000, (1, 1), 15_500);
+    assert_eq!(draining.state, "draining");
+    assert_eq!(draining.started_at_ms, Some(10_000));
+    assert_eq!(draining.remaining_ms, Some(24_500));
+    assert_eq!(retry_after_secs_for(&draining), 25);
+
+    let overdue = build_drain_status(PHASE_FINALIZING, 10_000, 40_000, (0, 0), 41_000);
+    assert_eq!(overdue.state, "finalizing");
+    assert_eq!(overdue.remaining_ms, Some(0));
+    assert_eq!(retry_after_secs_for(&overdue), 1);
+
+    let value = serde_json::to_value(&draining).expect("serialize status");
+    assert_eq!(value["deadlineAtMs"], 40_000);
+    assert_eq!(value["inflightStreams"], 1);
+}
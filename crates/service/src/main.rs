@@ -16,6 +16,7 @@ fn main() {
         .unwrap_or_else(|_| codexmanager_service::default_listener_bind_addr());
     let addr = codexmanager_service::listener_bind_addr(&configured_addr);
     println!("codexmanager-service listening on {addr}");
+    codexmanager_service::install_drain_signal_handler();
     if let Err(err) = codexmanager_service::start_server(&addr) {
         eprintln!("service stopped: {err}");
         std::process::exit(1);
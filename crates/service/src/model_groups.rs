@@ -30,7 +30,7 @@ fn normalize_optional_text(value: Option<&str>) -> Option<String> {
         .map(str::to_string)
 }
 
-fn normalize_status(value: Option<&str>) -> Result<String, String> {
+pub(crate) fn normalize_status(value: Option<&str>) -> Result<String, String> {
     match value
         .map(str::trim)
         .filter(|value| !value.is_empty())
@@ -42,7 +42,7 @@ fn normalize_status(value: Option<&str>) -> Result<String, String> {
     }
 }
 
-fn normalize_rate(value: Option<i64>) -> i64 {
+pub(crate) fn normalize_rate(value: Option<i64>) -> i64 {
     value.unwrap_or(1000).clamp(0, 100_000)
 }
 
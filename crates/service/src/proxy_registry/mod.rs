@@ -10,6 +10,9 @@ use serde_json::Value;
 
 use crate::storage_helpers::{generate_proxy_profile_id, open_storage};
 
+pub(crate) mod pools;
+mod subscription_parser;
+pub(crate) mod subscriptions;
 pub(crate) mod validation;
 
 pub(crate) fn list_proxy_profiles() -> Result<Vec<ProxyProfileEntry>, String> {
@@ -80,6 +83,7 @@ pub(crate) fn update_proxy_profile(
         normalize_optional_text(notes),
         url_changed,
     )?;
+    pools::forget_proxy_profile_assignments(updated.id.as_str());
     let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
     let accounts_count = storage
         .list_account_ids_bound_to_proxy_profile(&updated.id)
@@ -106,6 +110,7 @@ pub(crate) fn delete_proxy_profile(id: &str) -> Result<(), String> {
         .delete_proxy_profile(normalized_id)
         .map_err(|err| format!("delete proxy profile failed: {err}"))?;
     if deleted {
+        pools::forget_proxy_profile_assignments(normalized_id);
         Ok(())
     } else {
         Err("proxy profile not found".to_string())
@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use codexmanager_core::rpc::types::{ProxyPoolEntry, ProxyPoolMemberEntry, ProxyPoolSetParams};
+use codexmanager_core::storage::{
+    now_ts, AccountProxyPoolBinding, ProxyPool, ProxyProfile, Storage,
+};
+
+use crate::storage_helpers::{generate_proxy_pool_id, open_storage};
+
+pub(crate) const POLICY_STICKY: &str = "sticky";
+pub(crate) const POLICY_ROUND_ROBIN: &str = "round_robin";
+pub(crate) const POLICY_LEAST_LATENCY: &str = "least_latency";
+const POOL_MEMBER_DOWN_ENV: &str = "CODEXMANAGER_PROXY_POOL_MEMBER_DOWN_SECS";
+const DEFAULT_POOL_MEMBER_DOWN_SECS: i64 = 120;
+const MIN_POOL_MEMBER_DOWN_SECS: i64 = 10;
+/// 同一成员在观察窗口内累计的传输错误次数达到该值才判定代理故障，避免单次上游抖动触发切换。
+const POOL_MEMBER_FAILURE_THRESHOLD: u32 = 2;
+const LATENCY_SAMPLE_LIMIT: usize = 5;
+
+static POOL_HEALTH: OnceLock<Mutex<PoolHealthState>> = OnceLock::new();
+static ROUND_ROBIN_CURSOR: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProxyPoolPolicy {
+    Sticky,
+    RoundRobin,
+    LeastLatency,
+}
+
+impl ProxyPoolPolicy {
+    pub(crate) fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "" | POLICY_STICKY => Ok(Self::Sticky),
+            POLICY_ROUND_ROBIN | "roundrobin" => Ok(Self::RoundRobin),
+            POLICY_LEAST_LATENCY | "leastlatency" => Ok(Self::LeastLatency),
+            other => Err(format!(
+                "unsupported proxy pool policy: {other}. Expected sticky, round_robin or least_latency"
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Sticky => POLICY_STICKY,
+            Self::RoundRobin => POLICY_ROUND_ROBIN,
+            Self::LeastLatency => POLICY_LEAST_LATENCY,
+        }
+    }
+
+    fn from_stored(raw: &str) -> Self {
+        Self::parse(raw).unwrap_or(Self::Sticky)
+    }
+}
+
+#[derive(Default)]
+struct PoolHealthState {
+    /// 已分配池成员的账号 -> 成员 id；传输错误时据此判断，无需打开存储。
+    assignments: HashMap<String, String>,
+    members: HashMap<String, MemberHealth>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct MemberHealth {
+    failures: u32,
+    window_started_at: i64,
+    down_until: i64,
+}
+
+fn pool_health() -> &'static Mutex<PoolHealthState> {
+    POOL_HEALTH.get_or_init(|| Mutex::new(PoolHealthState::default()))
+}
+
+pub(crate) fn list_proxy_pools() -> Result<Vec<ProxyPoolEntry>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let pools = storage
+        .list_proxy_pools()
+        .map_err(|err| format!("list proxy pools failed: {err}"))?;
+    let profiles = storage
+        .list_proxy_profiles()
+        .map_err(|err| format!("list proxy profiles failed: {err}"))?;
+    let bindings = storage
+        .list_account_proxy_pool_bindings(None)
+        .map_err(|err| format!("list proxy pool bindings failed: {err}"))?;
+    Ok(pools
+        .into_iter()
+        .map(|pool| proxy_pool_entry(&storage, pool, &profiles, &bindings))
+        .collect())
+}
+
+pub(crate) fn set_proxy_pool(params: ProxyPoolSetParams) -> Result<ProxyPoolEntry, String> {
+    let name = params.name.trim();
+    if name.is_empty() {
+        return Err("name is required".to_string());
+    }
+    let tag = params.tag.trim();
+    if tag.is_empty() {
+        return Err("tag is required".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let existing = match params
+        .id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(id) => Some(
+            storage
+                .find_proxy_pool(id)
+                .map_err(|err| format!("read proxy pool failed: {err}"))?
+                .ok_or_else(|| "proxy pool not found".to_string())?,
+        ),
+        None => None,
+    };
+    let policy = match params.policy.as_deref() {
+        Some(raw) => ProxyPoolPolicy::parse(raw)?,
+        None => existing
+            .as_ref()
+            .map(|pool| ProxyPoolPolicy::from_stored(pool.policy.as_str()))
+            .unwrap_or(ProxyPoolPolicy::Sticky),
+    };
+    let now = now_ts();
+    let pool = ProxyPool {
+        id: existing
+            .as_ref()
+            .map(|pool| pool.id.clone())
+            .unwrap_or_else(generate_proxy_pool_id),
+        name: name.to_string(),
+        tag: tag.to_string(),
+        policy: policy.as_str().to_string(),
+        enabled: params
+            .enabled
+            .or(existing.as_ref().map(|pool| pool.enabled))
+            .unwrap_or(true),
+        created_at: existing.as_ref().map(|pool| pool.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    storage
+        .upsert_proxy_pool(&pool)
+        .map_err(|err| format!("store proxy pool failed: {err}"))?;
+
+    let bindings = storage
+        .list_account_proxy_pool_bindings(Some(pool.id.as_str()))
+        .map_err(|err| format!("list proxy pool bindings failed: {err}"))?;
+    // 中文注释：标签或开关变化可能让当前出口不再属于池，让已绑定账号下次请求时重新解析。
+    for binding in &bindings {
+        forget_account_pool_assignment(binding.account_id.as_str());
+        crate::gateway::invalidate_account_proxy_cache(binding.account_id.as_str());
+    }
+    let profiles = storage
+        .list_proxy_profiles()
+        .map_err(|err| format!("list proxy profiles failed: {err}"))?;
+    Ok(proxy_pool_entry(&storage, pool, &profiles, &bindings))
+}
+
+pub(crate) fn delete_proxy_pool(id: &str) -> Result<(), String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("id is required".to_string());
+    }
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let bound_accounts = storage
+        .list_account_proxy_pool_bindings(Some(id))
+        .map_err(|err| format!("read proxy pool bindings failed: {err}"))?;
+    if !bound_accounts.is_empty() {
+        return Err(format!(
+            "proxy pool is still bound to accounts: {}. Update or clear those account proxy bindings first.",
+            bound_accounts
+                .iter()
+                .map(|binding| binding.account_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let deleted = storage
+        .delete_proxy_pool(id)
+        .map_err(|err| format!("delete proxy pool failed: {err}"))?;
+    if deleted {
+        Ok(())
+    } else {
+        Err("proxy pool not found".to_string())
+    }
+}
+
+/// 解析池绑定账号的出口：当前成员健康时保持不变（保留出口 IP），否则按池策略重新分配。
+pub(crate) fn resolve_account_pool_proxy(
+    storage: &Storage,
+    account_id: &str,
+) -> Result<(ProxyProfile, String), String> {
+    let binding = storage
+        .find_account_proxy_pool_binding(account_id)
+        .map_err(|err| format!("read account proxy pool binding failed: {err}"))?
+        .ok_or_else(|| format!("account proxy pool for {account_id} is missing and fail-closed"))?;
+    let pool = storage
+        .find_proxy_pool(binding.proxy_pool_id.as_str())
+        .map_err(|err| format!("read proxy pool failed: {err}"))?
+        .ok_or_else(|| {
+            format!(
+                "account proxy pool for {} is missing and fail-closed: {}",
+                account_id, binding.proxy_pool_id
+            )
+        })?;
+    if !pool.enabled {
+        return Err(format!(
+            "account proxy pool for {} is disabled and fail-closed: {}",
+            account_id, pool.id
+        ));
+    }
+    let profiles = storage
+        .list_proxy_profiles()
+        .map_err(|err| format!("read proxy profiles failed: {err}"))?;
+    let members = profiles
+        .into_iter()
+        .filter(|profile| profile.enabled && profile_has_tag(profile, pool.tag.as_str()))
+        .filter_map(|profile| {
+            crate::account_proxy::normalize_supported_proxy_url(profile.proxy_url.as_str())
+                .ok()
+                .map(|proxy_url| (profile, proxy_url))
+        })
+        .collect::<Vec<_>>();
+    if members.is_empty() {
+        return Err(format!(
+            "account proxy pool for {} has no usable members and fail-closed: {}",
+            account_id, pool.id
+        ));
+    }
+
+    let now = now_ts();
+    let current = binding.proxy_profile_id.as_deref();
+    if let Some(member) =
+        current.and_then(|id| members.iter().find(|(profile, _)| profile.id == id))
+    {
+        if !member_is_down(member.0.id.as_str(), now) {
+            remember_account_pool_assignment(account_id, member.0.id.as_str());
+            return Ok(member.clone());
+        }
+    }
+
+    let healthy = members
+        .iter()
+        .filter(|(profile, _)| !member_is_down(profile.id.as_str(), now))
+        .collect::<Vec<_>>();
+    // 中文注释：成员全部被判定故障时仍按策略挑一个，避免误判把账号彻底断开。
+    let candidates = if healthy.is_empty() {
+        members.iter().collect()
+    } else {
+        healthy
+    };
+    let candidate_profiles = candidates
+        .iter()
+        .map(|(profile, _)| profile)
+        .collect::<Vec<_>>();
+    let index = select_pool_member(
+        ProxyPoolPolicy::from_stored(pool.policy.as_str()),
+        account_id,
+        &candidate_profiles,
+        |profile| member_latency_ms(storage, profile),
+    );
+    let chosen = candidates[index].clone();
+    let failover_from = current.filter(|id| *id != chosen.0.id);
+    storage
+        .assign_account_proxy_pool_member(account_id, chosen.0.id.as_str(), failover_from)
+        .map_err(|err| format!("store account proxy pool assignment failed: {err}"))?;
+    log::info!(
+        "event=proxy_pool_member_assigned account_id={} pool_id={} proxy_profile_id={} failover_from={}",
+        account_id,
+        pool.id,
+        chosen.0.id,
+        failover_from.unwrap_or("-")
+    );
+    remember_account_pool_assignment(account_id, chosen.0.id.as_str());
+    Ok(chosen)
+}
+
+/// 账号请求出现传输错误时调用；池成员累计错误达到阈值后标记故障并让使用它的账号切换出口。
+pub(crate) fn report_account_proxy_transport_failure(account_id: &str) {
+    let now = now_ts();
+    let down_secs = pool_member_down_secs();
+    let affected_accounts = {
+        let mut state = crate::lock_utils::lock_recover(pool_health(), "proxy_pool_health");
+        let Some(profile_id) = state.assignments.get(account_id.trim()).cloned() else {
+            return;
+        };
+        let health = state.members.entry(profile_id.clone()).or_default();
+        if now - health.window_started_at > down_secs {
+            health.failures = 0;
+            health.window_started_at = now;
+        }
+        health.failures += 1;
+        if health.failures < POOL_MEMBER_FAILURE_THRESHOLD {
+            return;
+        }
+        health.failures = 0;
+        health.down_until = now + down_secs;
+        let affected = state
+            .assignments
+            .iter()
+            .filter(|(_, assigned)| **assigned == profile_id)
+            .map(|(account_id, _)| account_id.clone())
+            .collect::<Vec<_>>();
+        state
+            .assignments
+            .retain(|_, assigned| *assigned != profile_id);
+        log::warn!(
+            "event=proxy_pool_member_down proxy_profile_id={} down_secs={} accounts={}",
+            profile_id,
+            down_secs,
+            affected.len()
+        );
+        affected
+    };
+    for account_id in affected_accounts {
+        crate::gateway::invalidate_account_proxy_cache(account_id.as_str());
+    }
+}
+
+pub(crate) fn forget_account_pool_assignment(account_id: &str) {
+    crate::lock_utils::lock_recover(pool_health(), "proxy_pool_health")
+        .assignments
+        .remove(account_id.trim());
+}
+
+/// 代理配置被修改或删除后，让正在使用它的池账号重新解析出口。
+pub(crate) fn forget_proxy_profile_assignments(proxy_profile_id: &str) {
+    let affected_accounts = {
+        let mut state = crate::lock_utils::lock_recover(pool_health(), "proxy_pool_health");
+        state.members.remove(proxy_profile_id);
+        let affected = state
+            .assignments
+            .iter()
+            .filter(|(_, assigned)| assigned.as_str() == proxy_profile_id)
+            .map(|(account_id, _)| account_id.clone())
+            .collect::<Vec<_>>();
+        state
+            .assignments
+            .retain(|_, assigned| assigned.as_str() != proxy_profile_id);
+        affected
+    };
+    for account_id in affected_accounts {
+        crate::gateway::invalidate_account_proxy_cache(account_id.as_str());
+    }
+}
+
+fn remember_account_pool_assignment(account_id: &str, proxy_profile_id: &str) {
+    crate::lock_utils::lock_recover(pool_health(), "proxy_pool_health")
+        .assignments
+        .insert(account_id.to_string(), proxy_profile_id.to_string());
+}
+
+fn member_down_until(proxy_profile_id: &str, now: i64) -> Option<i64> {
+    crate::lock_utils::lock_recover(pool_health(), "proxy_pool_health")
+        .members
+        .get(proxy_profile_id)
+        .map(|health| health.down_until)
+        .filter(|down_until| *down_until > now)
+}
+
+fn member_is_down(proxy_profile_id: &str, now: i64) -> bool {
+    member_down_until(proxy_profile_id, now).is_some()
+}
+
+/// 按策略从候选成员中挑选一个，返回其在 `candidates` 中的下标。
+fn select_pool_member<F>(
+    policy: ProxyPoolPolicy,
+    account_id: &str,
+    candidates: &[&ProxyProfile],
+    latency_of: F,
+) -> usize
+where
+    F: Fn(&ProxyProfile) -> Option<i64>,
+{
+    let mut order = (0..candidates.len()).collect::<Vec<_>>();
+    order.sort_by(|left, right| candidates[*left].id.cmp(&candidates[*right].id));
+    match policy {
+        // 中文注释：用 rendezvous hash，成员增减时只有落在变动成员上的账号会换出口。
+        ProxyPoolPolicy::Sticky => order
+            .into_iter()
+            .max_by_key(|index| rendezvous_score(account_id, candidates[*index].id.as_str()))
+            .unwrap_or(0),
+        ProxyPoolPolicy::RoundRobin => {
+            order[ROUND_ROBIN_CURSOR.fetch_add(1, Ordering::Relaxed) % order.len()]
+        }
+        ProxyPoolPolicy::LeastLatency => order
+            .into_iter()
+            .min_by_key(|index| latency_of(candidates[*index]).unwrap_or(i64::MAX))
+            .unwrap_or(0),
+    }
+}
+
+fn rendezvous_score(account_id: &str, proxy_profile_id: &str) -> u64 {
+    // FNV-1a：实现简单且跨进程稳定，重启后同一账号仍倾向同一出口。
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in account_id
+        .bytes()
+        .chain(std::iter::once(0))
+        .chain(proxy_profile_id.bytes())
+    {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+fn member_latency_ms(storage: &Storage, profile: &ProxyProfile) -> Option<i64> {
+    storage
+        .average_proxy_profile_url_latency_ms(profile.id.as_str(), LATENCY_SAMPLE_LIMIT)
+        .ok()
+        .flatten()
+        .or(profile.last_url_latency_ms)
+}
+
+fn profile_has_tag(profile: &ProxyProfile, tag: &str) -> bool {
+    profile
+        .tags_json
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok())
+        .is_some_and(|tags| {
+            tags.iter()
+                .any(|item| item.trim().eq_ignore_ascii_case(tag.trim()))
+        })
+}
+
+fn pool_member_down_secs() -> i64 {
+    std::env::var(POOL_MEMBER_DOWN_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(DEFAULT_POOL_MEMBER_DOWN_SECS)
+        .max(MIN_POOL_MEMBER_DOWN_SECS)
+}
+
+fn proxy_pool_entry(
+    storage: &Storage,
+    pool: ProxyPool,
+    profiles: &[ProxyProfile],
+    bindings: &[AccountProxyPoolBinding],
+) -> ProxyPoolEntry {
+    let now = now_ts();
+    let pool_bindings = bindings
+        .iter()
+        .filter(|binding| binding.proxy_pool_id == pool.id)
+        .collect::<Vec<_>>();
+    let members = profiles
+        .iter()
+        .filter(|profile| profile_has_tag(profile, pool.tag.as_str()))
+        .map(|profile| ProxyPoolMemberEntry {
+            proxy_profile_id: profile.id.clone(),
+            name: profile.name.clone(),
+            proxy_url_redacted: profile.proxy_url_redacted.clone(),
+            enabled: profile.enabled,
+            status: profile.status.clone(),
+            latency_ms: member_latency_ms(storage, profile),
+            down_until: member_down_until(profile.id.as_str(), now),
+            assigned_accounts: pool_bindings
+                .iter()
+                .filter(|binding| binding.proxy_profile_id.as_deref() == Some(profile.id.as_str()))
+                .count() as i64,
+        })
+        .collect();
+    ProxyPoolEntry {
+        accounts_count: pool_bindings.len() as i64,
+        id: pool.id,
+        name: pool.name,
+        tag: pool.tag,
+        policy: pool.policy,
+        enabled: pool.enabled,
+        members,
+        created_at: pool.created_at,
+        updated_at: pool.updated_at,
+    }
+}
+
+#[cfg(test)]
+#[path = "pools_tests.rs"]
+mod tests;
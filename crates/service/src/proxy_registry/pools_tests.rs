@@ -0,0 +1,228 @@
+use super::*;
+use codexmanager_core::storage::{Account, ProxyProfileCreateInput};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+
+use crate::account_proxy::{AccountProxyMode, AccountProxySource};
+use crate::test_env_guard;
+
+static POOLS_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = POOLS_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn init_db(prefix: &str) -> EnvGuard {
+    let dir = new_test_dir(prefix);
+    let db_path = dir.join("codexmanager.db");
+    Storage::open(&db_path)
+        .expect("open db")
+        .init()
+        .expect("init db");
+    EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref())
+}
+
+fn create_profile(storage: &Storage, id: &str, tag: &str) -> ProxyProfile {
+    storage
+        .create_proxy_profile(&ProxyProfileCreateInput {
+            id: id.to_string(),
+            name: id.to_string(),
+            proxy_url: format!("http://{id}.example:8080"),
+            enabled: true,
+            tags_json: Some(format!(r#"["{tag}"]"#)),
+            notes: None,
+        })
+        .expect("create proxy profile")
+}
+
+fn in_memory_profiles(ids: &[&str]) -> Vec<ProxyProfile> {
+    let storage = Storage::open_in_memory().expect("open in-memory storage");
+    storage.init().expect("initialize storage");
+    ids.iter()
+        .map(|id| create_profile(&storage, id, "exit"))
+        .collect()
+}
+
+#[test]
+fn sticky_policy_ignores_candidate_order() {
+    let profiles = in_memory_profiles(&["ppl-a", "ppl-b", "ppl-c"]);
+    let forward = profiles.iter().collect::<Vec<_>>();
+    let reversed = profiles.iter().rev().collect::<Vec<_>>();
+
+    for account_id in ["acc-1", "acc-2", "acc-3", "acc-4"] {
+        let picked = forward
+            [select_pool_member(ProxyPoolPolicy::Sticky, account_id, &forward, |_| None)]
+        .id
+        .clone();
+        let picked_reversed = reversed
+            [select_pool_member(ProxyPoolPolicy::Sticky, account_id, &reversed, |_| None)]
+        .id
+        .clone();
+        assert_eq!(picked, picked_reversed, "account {account_id}");
+    }
+}
+
+#[test]
+fn round_robin_and_least_latency_policies_pick_expected_members() {
+    let profiles = in_memory_profiles(&["ppl-a", "ppl-b", "ppl-c"]);
+    let candidates = profiles.iter().collect::<Vec<_>>();
+
+    let mut picked = (0..3)
+        .map(|_| {
+            candidates
+                [select_pool_member(ProxyPoolPolicy::RoundRobin, "acc-1", &candidates, |_| None)]
+            .id
+            .clone()
+        })
+        .collect::<Vec<_>>();
+    picked.sort();
+    assert_eq!(picked, vec!["ppl-a", "ppl-b", "ppl-c"]);
+
+    let latency = |profile: &ProxyProfile| match profile.id.as_str() {
+        "ppl-a" => Some(300),
+        "ppl-b" => None,
+        _ => Some(80),
+    };
+    let index = select_pool_member(ProxyPoolPolicy::LeastLatency, "acc-1", &candidates, latency);
+    assert_eq!(candidates[index].id, "ppl-c");
+}
+
+#[test]
+fn pool_account_fails_over_after_repeated_transport_errors() {
+    let _lock = test_env_guard();
+    let _guard = init_db("proxy-pool-failover");
+    let storage = open_storage().expect("open storage");
+    storage
+        .insert_account(&Account {
+            id: "pool-acc".to_string(),
+            label: "pool-acc".to_string(),
+            issuer: "chatgpt".to_string(),
+            chatgpt_account_id: None,
+            workspace_id: None,
+            group_name: None,
+            sort: 0,
+            status: "active".to_string(),
+            created_at: now_ts(),
+            updated_at: now_ts(),
+        })
+        .expect("insert account");
+    // 中文注释：成员健康度是进程级状态，用带进程号的 id 避免与其他用例串扰。
+    let suffix = std::process::id();
+    let first = format!("ppl-failover-a-{suffix}");
+    let second = format!("ppl-failover-b-{suffix}");
+    create_profile(&storage, first.as_str(), "failover");
+    create_profile(&storage, second.as_str(), "failover");
+    let pool = set_proxy_pool(ProxyPoolSetParams {
+        name: "failover".to_string(),
+        tag: "failover".to_string(),
+        ..Default::default()
+    })
+    .expect("create pool");
+    assert_eq!(pool.policy, POLICY_STICKY);
+    assert_eq!(pool.members.len(), 2);
+
+    let settings = crate::account_proxy::set_account_proxy_settings(
+        "pool-acc",
+        true,
+        None,
+        None,
+        Some(pool.id.as_str()),
+        None,
+        Some("unknown"),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .expect("bind account to pool");
+    assert_eq!(settings.source, "pool");
+    assert_eq!(settings.proxy_pool_id.as_deref(), Some(pool.id.as_str()));
+    assert!(delete_proxy_pool(pool.id.as_str()).is_err());
+
+    let initial =
+        match crate::account_proxy::resolve_account_proxy_mode_from_storage(&storage, "pool-acc") {
+            AccountProxyMode::Explicit { proxy_url, source } => {
+                assert_eq!(source, AccountProxySource::Pool);
+                proxy_url
+            }
+            _ => panic!("expected pool proxy"),
+        };
+    let initial_member = storage
+        .find_account_proxy_pool_binding("pool-acc")
+        .expect("read binding")
+        .and_then(|binding| binding.proxy_profile_id)
+        .expect("assigned member");
+    assert!(initial.contains(initial_member.as_str()));
+
+    // 中文注释：单次传输错误不应切换出口。
+    report_account_proxy_transport_failure("pool-acc");
+    let (profile, _) = resolve_account_pool_proxy(&storage, "pool-acc").expect("resolve");
+    assert_eq!(profile.id, initial_member);
+
+    report_account_proxy_transport_failure("pool-acc");
+    let (profile, proxy_url) = resolve_account_pool_proxy(&storage, "pool-acc").expect("resolve");
+    assert_ne!(profile.id, initial_member);
+    assert!(proxy_url.contains(profile.id.as_str()));
+    let binding = storage
+        .find_account_proxy_pool_binding("pool-acc")
+        .expect("read binding")
+        .expect("binding exists");
+    assert_eq!(binding.failover_count, 1);
+    assert_eq!(
+        binding.last_failover_from.as_deref(),
+        Some(initial_member.as_str())
+    );
+
+    let pools = list_proxy_pools().expect("list pools");
+    let listed = pools
+        .iter()
+        .find(|item| item.id == pool.id)
+        .expect("listed pool");
+    assert_eq!(listed.accounts_count, 1);
+    let down_member = listed
+        .members
+        .iter()
+        .find(|member| member.proxy_profile_id == initial_member)
+        .expect("down member");
+    assert!(down_member.down_until.is_some());
+
+    forget_proxy_profile_assignments(initial_member.as_str());
+    crate::account_proxy::clear_account_proxy_settings("pool-acc").expect("clear");
+    assert!(storage
+        .find_account_proxy_pool_binding("pool-acc")
+        .expect("read binding")
+        .is_none());
+    delete_proxy_pool(pool.id.as_str()).expect("delete pool");
+}
@@ -0,0 +1,290 @@
+use base64::Engine;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// 订阅里解析出的可用代理；`proxy_url` 已规范成 reqwest 可直接使用的形式。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParsedProxy {
+    pub name: String,
+    pub proxy_url: String,
+}
+
+/// 订阅里无法转换的条目（如 vmess / trojan），导入结果里原样告知调用方。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SkippedProxy {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ParsedSubscription {
+    pub proxies: Vec<ParsedProxy>,
+    pub skipped: Vec<SkippedProxy>,
+}
+
+impl ParsedSubscription {
+    fn push(&mut self, seen: &mut HashSet<String>, entry: Result<ParsedProxy, SkippedProxy>) {
+        match entry {
+            Ok(proxy) => {
+                if seen.insert(proxy.proxy_url.clone()) {
+                    self.proxies.push(proxy);
+                }
+            }
+            Err(skipped) => self.skipped.push(skipped),
+        }
+    }
+}
+
+/// 解析订阅内容：支持 base64 / 明文 URI 列表、Clash `proxies:` 与 sing-box `outbounds`。
+pub(crate) fn parse_subscription_payload(raw: &str) -> Result<ParsedSubscription, String> {
+    let text = raw.trim().trim_start_matches('\u{feff}').trim();
+    if text.is_empty() {
+        return Err("subscription payload is empty".to_string());
+    }
+    let parsed = match parse_structured_payload(text)? {
+        Some(parsed) => parsed,
+        None if text.contains("://") => parse_uri_list(text),
+        None => {
+            let decoded = decode_base64_text(text)
+                .ok_or_else(|| "unrecognized subscription payload format".to_string())?;
+            match parse_structured_payload(decoded.as_str())? {
+                Some(parsed) => parsed,
+                None => parse_uri_list(decoded.as_str()),
+            }
+        }
+    };
+    if parsed.proxies.is_empty() && parsed.skipped.is_empty() {
+        return Err("subscription payload contains no proxies".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_structured_payload(text: &str) -> Result<Option<ParsedSubscription>, String> {
+    if text.starts_with('{') {
+        let value = serde_json::from_str::<Value>(text)
+            .map_err(|err| format!("invalid sing-box config: {err}"))?;
+        let outbounds = value
+            .get("outbounds")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "sing-box config has no outbounds".to_string())?;
+        return Ok(Some(parse_sing_box_outbounds(outbounds)));
+    }
+    if text.lines().any(|line| line.trim_end() == "proxies:") {
+        let value = serde_norway::from_str::<serde_norway::Value>(text)
+            .map_err(|err| format!("invalid clash config: {err}"))?;
+        let value =
+            serde_json::to_value(value).map_err(|err| format!("invalid clash config: {err}"))?;
+        let proxies = value
+            .get("proxies")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        return Ok(Some(parse_clash_proxies(&proxies)));
+    }
+    Ok(None)
+}
+
+fn decode_base64_text(text: &str) -> Option<String> {
+    let compact = text
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>();
+    let trimmed = compact.trim_end_matches('=');
+    [
+        base64::engine::general_purpose::STANDARD_NO_PAD,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD,
+    ]
+    .iter()
+    .find_map(|engine| engine.decode(trimmed).ok())
+    .and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+fn parse_uri_list(text: &str) -> ParsedSubscription {
+    let mut parsed = ParsedSubscription::default();
+    let mut seen = HashSet::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || !line.contains("://") {
+            continue;
+        }
+        parsed.push(&mut seen, parse_proxy_uri(line));
+    }
+    parsed
+}
+
+fn parse_proxy_uri(line: &str) -> Result<ParsedProxy, SkippedProxy> {
+    let (body, fragment) = match line.split_once('#') {
+        Some((body, fragment)) => (body, Some(fragment)),
+        None => (line, None),
+    };
+    let fragment_name = fragment
+        .map(|value| {
+            urlencoding::decode(value)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| value.to_string())
+        })
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let scheme = body
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase())
+        .unwrap_or_default();
+    let skipped = |reason: String| SkippedProxy {
+        name: fragment_name.clone().unwrap_or_else(|| scheme.clone()),
+        reason,
+    };
+    let proxy_url = match scheme.as_str() {
+        "http" | "https" | "socks4" | "socks4a" | "socks5" | "socks5h" => body.to_string(),
+        // 中文注释：v2rayN 的 socks:// 把 `user:pass` 整体做了 base64。
+        "socks" => decode_v2rayn_socks_uri(body)
+            .ok_or_else(|| skipped("invalid socks subscription entry".to_string()))?,
+        _ => return Err(skipped(format!("unsupported protocol: {scheme}"))),
+    };
+    let proxy_url =
+        super::validation::normalize_proxy_profile_url(proxy_url.as_str()).map_err(skipped)?;
+    let url = url::Url::parse(proxy_url.as_str()).map_err(|err| skipped(err.to_string()))?;
+    let host = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| skipped("proxy host is missing".to_string()))?;
+    let name = fragment_name.unwrap_or_else(|| match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    });
+    Ok(ParsedProxy { name, proxy_url })
+}
+
+fn decode_v2rayn_socks_uri(body: &str) -> Option<String> {
+    let rest = body.split_once("://")?.1;
+    let (credentials, address) = rest.rsplit_once('@')?;
+    let decoded = decode_base64_text(urlencoding::decode(credentials).ok()?.as_ref())?;
+    let (username, password) = decoded.split_once(':')?;
+    Some(format!(
+        "socks5://{}:{}@{}",
+        urlencoding::encode(username),
+        urlencoding::encode(password),
+        address.trim_end_matches('/')
+    ))
+}
+
+fn parse_clash_proxies(items: &[Value]) -> ParsedSubscription {
+    let mut parsed = ParsedSubscription::default();
+    let mut seen = HashSet::new();
+    for item in items {
+        let kind = value_text(item, "type").unwrap_or_default();
+        let tls = item.get("tls").and_then(Value::as_bool).unwrap_or(false);
+        let scheme = match (kind.as_str(), tls) {
+            ("http", false) => Ok("http"),
+            ("http", true) => Ok("https"),
+            ("socks5", false) => Ok("socks5"),
+            ("socks5", true) => Err("socks5 over tls is not supported".to_string()),
+            (other, _) => Err(format!("unsupported protocol: {other}")),
+        };
+        parsed.push(
+            &mut seen,
+            build_proxy(
+                scheme,
+                value_text(item, "name"),
+                value_text(item, "server"),
+                value_port(item, "port"),
+                value_text(item, "username"),
+                value_text(item, "password"),
+            ),
+        );
+    }
+    parsed
+}
+
+fn parse_sing_box_outbounds(items: &[Value]) -> ParsedSubscription {
+    let mut parsed = ParsedSubscription::default();
+    let mut seen = HashSet::new();
+    for item in items {
+        let kind = value_text(item, "type").unwrap_or_default();
+        let tls = item
+            .get("tls")
+            .and_then(|tls| tls.get("enabled"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let scheme = match kind.as_str() {
+            // 中文注释：路由类出站不是代理节点，直接忽略而不是记为跳过。
+            "direct" | "block" | "dns" | "selector" | "urltest" => continue,
+            "http" if tls => Ok("https"),
+            "http" => Ok("http"),
+            "socks" => match value_text(item, "version").as_deref() {
+                None | Some("5") => Ok("socks5"),
+                Some("4") => Ok("socks4"),
+                Some("4a") => Ok("socks4a"),
+                Some(other) => Err(format!("unsupported socks version: {other}")),
+            },
+            other => Err(format!("unsupported protocol: {other}")),
+        };
+        parsed.push(
+            &mut seen,
+            build_proxy(
+                scheme,
+                value_text(item, "tag"),
+                value_text(item, "server"),
+                value_port(item, "server_port"),
+                value_text(item, "username"),
+                value_text(item, "password"),
+            ),
+        );
+    }
+    parsed
+}
+
+fn build_proxy(
+    scheme: Result<&str, String>,
+    name: Option<String>,
+    server: Option<String>,
+    port: Option<u16>,
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<ParsedProxy, SkippedProxy> {
+    let display_name = name.clone().or_else(|| server.clone()).unwrap_or_default();
+    let skipped = |reason: String| SkippedProxy {
+        name: display_name.clone(),
+        reason,
+    };
+    let scheme = scheme.map_err(skipped)?;
+    let server = server.ok_or_else(|| skipped("proxy server is missing".to_string()))?;
+    let port = port.ok_or_else(|| skipped("proxy port is missing".to_string()))?;
+    let host = if server.contains(':') && !server.starts_with('[') {
+        format!("[{server}]")
+    } else {
+        server.clone()
+    };
+    let userinfo = match (username, password) {
+        (Some(username), Some(password)) => format!(
+            "{}:{}@",
+            urlencoding::encode(username.as_str()),
+            urlencoding::encode(password.as_str())
+        ),
+        (Some(username), None) => format!("{}@", urlencoding::encode(username.as_str())),
+        _ => String::new(),
+    };
+    let proxy_url = super::validation::normalize_proxy_profile_url(
+        format!("{scheme}://{userinfo}{host}:{port}").as_str(),
+    )
+    .map_err(skipped)?;
+    Ok(ParsedProxy {
+        name: name.unwrap_or_else(|| format!("{server}:{port}")),
+        proxy_url,
+    })
+}
+
+fn value_text(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(text) => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+    .filter(|text| !text.is_empty())
+}
+
+fn value_port(value: &Value, key: &str) -> Option<u16> {
+    value_text(value, key).and_then(|text| text.parse::<u16>().ok())
+}
+
+#[cfg(test)]
+#[path = "subscription_parser_tests.rs"]
+mod tests;
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use codexmanager_core::rpc::types::{
+    ProxySubscriptionEntry, ProxySubscriptionSkippedItem, ProxySubscriptionSyncResult,
+};
+use codexmanager_core::storage::{
+    now_ts, ProxyProfileCreateInput, ProxySubscription, ProxySubscriptionProfile, Storage,
+};
+
+use super::subscription_parser::{parse_subscription_payload, ParsedSubscription};
+use crate::storage_helpers::{
+    generate_proxy_profile_id, generate_proxy_subscription_id, open_storage,
+};
+
+pub(crate) fn list_proxy_subscriptions() -> Result<Vec<ProxySubscriptionEntry>, String> {
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let subscriptions = storage
+        .list_proxy_subscriptions()
+        .map_err(|err| format!("list proxy subscriptions failed: {err}"))?;
+    subscriptions
+        .into_iter()
+        .map(|subscription| proxy_subscription_entry(&storage, subscription))
+        .collect()
+}
+
+/// 导入订阅：`content` 为空时从 `url` 拉取；只给 `content` 的一次性导入不能再刷新。
+pub(crate) fn import_proxy_subscription(
+    name: Option<String>,
+    url: Option<String>,
+    content: Option<String>,
+    tags_json: Option<String>,
+) -> Result<ProxySubscriptionSyncResult, String> {
+    let url = super::normalize_optional_text(url)
+        .map(|value| validate_subscription_url(value.as_str()).map(|_| value))
+        .transpose()?;
+    let content = super::normalize_optional_text(content);
+    let name = super::normalize_optional_text(name)
+        .or_else(|| {
+            url.as_deref()
+                .and_then(|value| url::Url::parse(value).ok())
+                .and_then(|parsed| parsed.host_str().map(str::to_string))
+        })
+        .ok_or_else(|| "name is required".to_string())?;
+    let tags_json = super::normalize_tags_json(tags_json)?;
+    let payload = match (content, url.as_deref()) {
+        (Some(content), _) => content,
+        (None, Some(url)) => fetch_subscription_payload(url)?,
+        (None, None) => return Err("url or content is required".to_string()),
+    };
+    let parsed = parse_subscription_payload(payload.as_str())?;
+
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let now = now_ts();
+    let subscription = ProxySubscription {
+        id: generate_proxy_subscription_id(),
+        name,
+        url,
+        tags_json,
+        enabled: true,
+        last_refreshed_at: None,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+    };
+    storage
+        .upsert_proxy_subscription(&subscription)
+        .map_err(|err| format!("store proxy subscription failed: {err}"))?;
+    sync_subscription_profiles(&storage, &subscription, parsed)
+}
+
+/// 重新拉取订阅并同步：新增条目建代理配置，消失的条目停用（不删除，账号绑定仍可追溯）。
+pub(crate) fn refresh_proxy_subscription(id: &str) -> Result<ProxySubscriptionSyncResult, String> {
+    let id = super::normalize_required_str("id", id)?;
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let subscription = storage
+        .find_proxy_subscription(id)
+        .map_err(|err| format!("read proxy subscription failed: {err}"))?
+        .ok_or_else(|| "proxy subscription not found".to_string())?;
+    let url = subscription
+        .url
+        .clone()
+        .ok_or_else(|| "proxy subscription has no url to refresh".to_string())?;
+    let parsed = fetch_subscription_payload(url.as_str())
+        .and_then(|payload| parse_subscription_payload(payload.as_str()));
+    match parsed {
+        Ok(parsed) => sync_subscription_profiles(&storage, &subscription, parsed),
+        Err(err) => {
+            log::warn!(
+                "event=proxy_subscription_refresh_failed subscription_id={} err={}",
+                subscription.id,
+                err
+            );
+            let _ = storage.update_proxy_subscription_refresh(
+                subscription.id.as_str(),
+                now_ts(),
+                Some(err.as_str()),
+            );
+            Err(err)
+        }
+    }
+}
+
+/// 删除订阅记录；`delete_profiles` 为真时一并删除未被账号绑定的代理配置。
+pub(crate) fn delete_proxy_subscription(id: &str, delete_profiles: bool) -> Result<(), String> {
+    let id = super::normalize_required_str("id", id)?;
+    let storage = open_storage().ok_or_else(|| "storage unavailable".to_string())?;
+    let links = storage
+        .list_proxy_subscription_profiles(id)
+        .map_err(|err| format!("read proxy subscription profiles failed: {err}"))?;
+    if !storage
+        .delete_proxy_subscription(id)
+        .map_err(|err| format!("delete proxy subscription failed: {err}"))?
+    {
+        return Err("proxy subscription not found".to_string());
+    }
+    if delete_profiles {
+        for link in links {
+            // 中文注释：仍被账号直接绑定的配置保留，避免删除订阅时让账号 fail-closed。
+            if super::delete_proxy_profile(link.proxy_profile_id.as_str()).is_err() {
+                log::warn!(
+                    "event=proxy_subscription_profile_kept subscription_id={} proxy_profile_id={}",
+                    id,
+                    link.proxy_profile_id
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn sync_subscription_profiles(
+    storage: &Storage,
+    subscription: &ProxySubscription,
+    parsed: ParsedSubscription,
+) -> Result<ProxySubscriptionSyncResult, String> {
+    let profiles = storage
+        .list_proxy_profiles()
+        .map_err(|err| format!("list proxy profiles failed: {err}"))?;
+    let existing_urls = profiles
+        .iter()
+        .map(|profile| profile.proxy_url.trim().to_string())
+        .collect::<std::collections::HashSet<_>>();
+    let links = storage
+        .list_proxy_subscription_profiles(subscription.id.as_str())
+        .map_err(|err| format!("read proxy subscription profiles failed: {err}"))?;
+    let mut owned = links
+        .into_iter()
+        .map(|link| (link.proxy_url.clone(), link))
+        .collect::<HashMap<String, ProxySubscriptionProfile>>();
+
+    let mut result = ProxySubscriptionSyncResult {
+        skipped: parsed
+            .skipped
+            .into_iter()
+            .map(|item| ProxySubscriptionSkippedItem {
+                name: item.name,
+                reason: item.reason,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    for proxy in parsed.proxies {
+        if let Some(link) = owned.remove(proxy.proxy_url.as_str()) {
+            if link.retired_at.is_some() {
+                set_profile_enabled(link.proxy_profile_id.as_str(), true)?;
+                result.reactivated += 1;
+            } else {
+                result.unchanged += 1;
+            }
+            link_profile(
+                storage,
+                subscription,
+                link.proxy_profile_id.as_str(),
+                &proxy.proxy_url,
+            )?;
+            continue;
+        }
+        // 中文注释：手动添加或其他订阅已有的同一代理不重复创建，也不归入本订阅管理。
+        if existing_urls.contains(proxy.proxy_url.as_str()) {
+            result.duplicates += 1;
+            continue;
+        }
+        let created = storage
+            .create_proxy_profile(&ProxyProfileCreateInput {
+                id: generate_proxy_profile_id(),
+                name: proxy.name,
+                proxy_url: proxy.proxy_url.clone(),
+                enabled: true,
+                tags_json: subscription.tags_json.clone(),
+                notes: None,
+            })
+            .map_err(|err| format!("create proxy profile failed: {err}"))?;
+        link_profile(storage, subscription, created.id.as_str(), &proxy.proxy_url)?;
+        result.created += 1;
+    }
+
+    let now = now_ts();
+    for link in owned.into_values().filter(|link| link.retired_at.is_none()) {
+        set_profile_enabled(link.proxy_profile_id.as_str(), false)?;
+        storage
+            .retire_proxy_subscription_profile(
+                subscription.id.as_str(),
+                link.proxy_profile_id.as_str(),
+                now,
+            )
+            .map_err(|err| format!("retire proxy subscription profile failed: {err}"))?;
+        result.retired += 1;
+    }
+    storage
+        .update_proxy_subscription_refresh(subscription.id.as_str(), now, None)
+        .map_err(|err| format!("store proxy subscription refresh failed: {err}"))?;
+    log::info!(
+        "event=proxy_subscription_synced subscription_id={} created={} reactivated={} unchanged={} duplicates={} retired={} skipped={}",
+        subscription.id,
+        result.created,
+        result.reactivated,
+        result.unchanged,
+        result.duplicates,
+        result.retired,
+        result.skipped.len()
+    );
+
+    let refreshed = storage
+        .find_proxy_subscription(subscription.id.as_str())
+        .map_err(|err| format!("read proxy subscription failed: {err}"))?
+        .ok_or_else(|| "proxy subscription not found".to_string())?;
+    result.subscription = proxy_subscription_entry(storage, refreshed)?;
+    Ok(result)
+}
+
+fn link_profile(
+    storage: &Storage,
+    subscription: &ProxySubscription,
+    proxy_profile_id: &str,
+    proxy_url: &str,
+) -> Result<(), String> {
+    storage
+        .upsert_proxy_subscription_profile(subscription.id.as_str(), proxy_profile_id, proxy_url)
+        .map_err(|err| format!("store proxy subscription profile failed: {err}"))
+}
+
+fn set_profile_enabled(proxy_profile_id: &str, enabled: bool) -> Result<(), String> {
+    super::storage_update_proxy_profile(
+        proxy_profile_id,
+        None,
+        None,
+        Some(enabled),
+        None,
+        None,
+        false,
+    )?;
+    // 中文注释：停用的配置会被代理池剔除，已分配到它的账号需重新解析出口。
+    super::pools::forget_proxy_profile_assignments(proxy_profile_id);
+    Ok(())
+}
+
+fn validate_subscription_url(raw: &str) -> Result<(), String> {
+    let url = url::Url::parse(raw).map_err(|err| format!("invalid subscription url: {err}"))?;
+    match url.scheme() {
+        "http" | "https" => Ok(()),
+        other => Err(format!("unsupported subscription url scheme: {other}")),
+    }
+}
+
+fn fetch_subscription_payload(url: &str) -> Result<String, String> {
+    let response = crate::gateway::upstream_client()
+        .get(url)
+        .header("accept", "*/*")
+        .send()
+        .map_err(|err| format!("fetch proxy subscription failed: {err}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("proxy subscription responded with status {status}"));
+    }
+    response
+        .text()
+        .map_err(|err| format!("read proxy subscription failed: {err}"))
+}
+
+fn proxy_subscription_entry(
+    storage: &Storage,
+    subscription: ProxySubscription,
+) -> Result<ProxySubscriptionEntry, String> {
+    let links = storage
+        .list_proxy_subscription_profiles(subscription.id.as_str())
+        .map_err(|err| format!("read proxy subscription profiles failed: {err}"))?;
+    Ok(ProxySubscriptionEntry {
+        profiles_count: links.len() as i64,
+        active_profiles_count: links
+            .iter()
+            .filter(|link| link.retired_at.is_none())
+            .count() as i64,
+        id: subscription.id,
+        name: subscription.name,
+        url: subscription.url,
+        tags_json: subscription.tags_json,
+        enabled: subscription.enabled,
+        last_refreshed_at: subscription.last_refreshed_at,
+        last_error: subscription.last_error,
+        created_at: subscription.created_at,
+        updated_at: subscription.updated_at,
+    })
+}
+
+#[cfg(test)]
+#[path = "subscriptions_tests.rs"]
+mod tests;
@@ -0,0 +1,142 @@
+use super::*;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::test_env_guard;
+
+static SUBSCRIPTIONS_TEST_DIR_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+fn new_test_dir(prefix: &str) -> PathBuf {
+    let seq = SUBSCRIPTIONS_TEST_DIR_SEQ.fetch_add(1, Ordering::Relaxed);
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("{prefix}-{}-{seq}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+struct EnvGuard {
+    key: &'static str,
+    original: Option<std::ffi::OsString>,
+}
+
+impl EnvGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        std::env::set_var(key, value);
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        if let Some(value) = &self.original {
+            std::env::set_var(self.key, value);
+        } else {
+            std::env::remove_var(self.key);
+        }
+    }
+}
+
+fn init_db(prefix: &str) -> EnvGuard {
+    let dir = new_test_dir(prefix);
+    let db_path = dir.join("codexmanager.db");
+    Storage::open(&db_path)
+        .expect("open db")
+        .init()
+        .expect("init db");
+    EnvGuard::set("CODEXMANAGER_DB_PATH", db_path.to_string_lossy().as_ref())
+}
+
+fn profile_enabled(storage: &Storage, proxy_url: &str) -> Option<bool> {
+    storage
+        .list_proxy_profiles()
+        .expect("list proxy profiles")
+        .into_iter()
+        .find(|profile| profile.proxy_url == proxy_url)
+        .map(|profile| profile.enabled)
+}
+
+#[test]
+fn import_deduplicates_and_refresh_retires_missing_entries() {
+    let _lock = test_env_guard();
+    let _guard = init_db("proxy-subscription-sync");
+    let storage = open_storage().expect("open storage");
+    storage
+        .create_proxy_profile(&ProxyProfileCreateInput {
+            id: "manual-proxy".to_string(),
+            name: "manual".to_string(),
+            proxy_url: "http://192.0.2.1:8080".to_string(),
+            enabled: true,
+            tags_json: None,
+            notes: None,
+        })
+        .expect("create manual profile");
+
+    let content = [
+        "socks5://192.0.2.10:1080#hk",
+        "socks5://192.0.2.10:1080#hk-copy",
+        "http://192.0.2.1:8080#manual-copy",
+        "http://192.0.2.11:3128#jp",
+        "vmess://abcdef#vm",
+    ]
+    .join("\n");
+    let imported = import_proxy_subscription(
+        Some("provider".to_string()),
+        None,
+        Some(content),
+        Some(r#"["provider"]"#.to_string()),
+    )
+    .expect("import subscription");
+    assert_eq!(imported.created, 2);
+    assert_eq!(imported.duplicates, 1);
+    assert_eq!(imported.skipped.len(), 1);
+    assert_eq!(imported.subscription.active_profiles_count, 2);
+    let created = storage
+        .list_proxy_profiles()
+        .expect("list proxy profiles")
+        .into_iter()
+        .find(|profile| profile.proxy_url == "socks5://192.0.2.10:1080")
+        .expect("imported profile");
+    assert_eq!(created.name, "hk");
+    assert_eq!(created.tags_json.as_deref(), Some(r#"["provider"]"#));
+
+    let subscription = storage
+        .find_proxy_subscription(imported.subscription.id.as_str())
+        .expect("find subscription")
+        .expect("subscription exists");
+    let refreshed = sync_subscription_profiles(
+        &storage,
+        &subscription,
+        parse_subscription_payload("http://192.0.2.11:3128#jp\nsocks5://192.0.2.12:1080#sg")
+            .expect("parse refresh"),
+    )
+    .expect("sync refresh");
+    assert_eq!(refreshed.created, 1);
+    assert_eq!(refreshed.unchanged, 1);
+    assert_eq!(refreshed.retired, 1);
+    assert_eq!(refreshed.subscription.profiles_count, 3);
+    assert_eq!(refreshed.subscription.active_profiles_count, 2);
+    assert_eq!(
+        profile_enabled(&storage, "socks5://192.0.2.10:1080"),
+        Some(false)
+    );
+
+    // 中文注释：退役条目重新出现时复用原配置并重新启用。
+    let reactivated = sync_subscription_profiles(
+        &storage,
+        &subscription,
+        parse_subscription_payload("socks5://192.0.2.10:1080#hk").expect("parse reactivation"),
+    )
+    .expect("sync reactivation");
+    assert_eq!(reactivated.reactivated, 1);
+    assert_eq!(reactivated.created, 0);
+    assert_eq!(reactivated.retired, 2);
+    assert_eq!(
+        profile_enabled(&storage, "socks5://192.0.2.10:1080"),
+        Some(true)
+    );
+    assert_eq!(
+        profile_enabled(&storage, "http://192.0.2.1:8080"),
+        Some(true)
+    );
+}
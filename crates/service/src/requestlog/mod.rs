@@ -1,7 +1,13 @@
 #[path = "requestlog_clear.rs"]
 pub(crate) mod clear;
+#[path = "requestlog_content_policy.rs"]
+pub(crate) mod content_policy;
 #[path = "requestlog_list.rs"]
 pub(crate) mod list;
+#[path = "requestlog_route_decision.rs"]
+pub(crate) mod route_decision;
+#[path = "requestlog_structured_output.rs"]
+pub(crate) mod structured_output;
 #[path = "requestlog_summary.rs"]
 pub(crate) mod summary;
 #[path = "requestlog_today_summary.rs"]
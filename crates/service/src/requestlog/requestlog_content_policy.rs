@@ -0,0 +1,54 @@
+use codexmanager_core::storage::{RequestContentPolicyMatches, Storage};
+use serde::Serialize;
+
+use crate::storage_helpers::open_storage;
+
+/// 请求日志关联的出站内容策略命中；只含检测器、动作、次数与字段路径，不含命中原文。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestContentPolicyResult {
+    pub(crate) request_log_id: i64,
+    pub(crate) trace_id: Option<String>,
+    pub(crate) matches: serde_json::Value,
+    pub(crate) created_at: i64,
+}
+
+fn map_content_policy_matches(item: RequestContentPolicyMatches) -> RequestContentPolicyResult {
+    RequestContentPolicyResult {
+        request_log_id: item.request_log_id,
+        trace_id: item.trace_id,
+        matches: serde_json::from_str(item.matches_json.as_str())
+            .unwrap_or(serde_json::Value::String(item.matches_json)),
+        created_at: item.created_at,
+    }
+}
+
+fn normalize_trace_id(trace_id: Option<&str>) -> Result<&str, String> {
+    trace_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "traceId is required".to_string())
+}
+
+pub(crate) fn read_request_content_policy(
+    trace_id: Option<&str>,
+) -> Result<Option<RequestContentPolicyResult>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    storage
+        .find_request_content_policy_matches_by_trace_id(trace_id)
+        .map(|item| item.map(map_content_policy_matches))
+        .map_err(|err| format!("read request content policy matches failed: {err}"))
+}
+
+pub(crate) fn read_request_content_policy_for_key_ids_with_storage(
+    storage: &Storage,
+    trace_id: Option<&str>,
+    key_ids: &[String],
+) -> Result<Option<RequestContentPolicyResult>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    storage
+        .find_request_content_policy_matches_by_trace_id_for_key_ids(trace_id, key_ids)
+        .map(|item| item.map(map_content_policy_matches))
+        .map_err(|err| format!("read request content policy matches failed: {err}"))
+}
@@ -0,0 +1,56 @@
+use codexmanager_core::storage::{RequestRouteDecision, Storage};
+use serde::Serialize;
+
+use crate::storage_helpers::open_storage;
+
+/// 请求日志关联的选路决策；`decision` 为写入时的原始决策 JSON。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestRouteDecisionResult {
+    pub(crate) request_log_id: i64,
+    pub(crate) trace_id: Option<String>,
+    pub(crate) strategy: String,
+    pub(crate) decision: serde_json::Value,
+    pub(crate) created_at: i64,
+}
+
+fn map_route_decision(item: RequestRouteDecision) -> RequestRouteDecisionResult {
+    RequestRouteDecisionResult {
+        request_log_id: item.request_log_id,
+        trace_id: item.trace_id,
+        strategy: item.strategy,
+        decision: serde_json::from_str(item.decision_json.as_str())
+            .unwrap_or(serde_json::Value::String(item.decision_json)),
+        created_at: item.created_at,
+    }
+}
+
+fn normalize_trace_id(trace_id: Option<&str>) -> Result<&str, String> {
+    trace_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "traceId is required".to_string())
+}
+
+pub(crate) fn read_request_route_decision(
+    trace_id: Option<&str>,
+) -> Result<Option<RequestRouteDecisionResult>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    storage
+        .find_request_route_decision_by_trace_id(trace_id)
+        .map(|item| item.map(map_route_decision))
+        .map_err(|err| format!("read request route decision failed: {err}"))
+}
+
+pub(crate) fn read_request_route_decision_for_key_ids_with_storage(
+    storage: &Storage,
+    trace_id: Option<&str>,
+    key_ids: &[String],
+) -> Result<Option<RequestRouteDecisionResult>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    storage
+        .find_request_route_decision_by_trace_id_for_key_ids(trace_id, key_ids)
+        .map(|item| item.map(map_route_decision))
+        .map_err(|err| format!("read request route decision failed: {err}"))
+}
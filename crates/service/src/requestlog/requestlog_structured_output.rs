@@ -0,0 +1,68 @@
+use codexmanager_core::storage::{RequestStructuredOutputResult, Storage};
+use serde::Serialize;
+
+use crate::storage_helpers::open_storage;
+
+/// 请求日志关联的结构化输出校验结果；`repair*Tokens` 为被修复替换掉的响应消耗，已计入请求用量。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestStructuredOutputResultItem {
+    pub(crate) request_log_id: i64,
+    pub(crate) trace_id: Option<String>,
+    pub(crate) outcome: String,
+    pub(crate) repairs: i64,
+    pub(crate) skip_reason: Option<String>,
+    pub(crate) errors: serde_json::Value,
+    pub(crate) repair_input_tokens: Option<i64>,
+    pub(crate) repair_output_tokens: Option<i64>,
+    pub(crate) repair_total_tokens: Option<i64>,
+    pub(crate) created_at: i64,
+}
+
+fn map_structured_output_result(
+    item: RequestStructuredOutputResult,
+) -> RequestStructuredOutputResultItem {
+    RequestStructuredOutputResultItem {
+        request_log_id: item.request_log_id,
+        trace_id: item.trace_id,
+        outcome: item.outcome,
+        repairs: item.repairs,
+        skip_reason: item.skip_reason,
+        errors: serde_json::from_str(item.errors_json.as_str())
+            .unwrap_or(serde_json::Value::String(item.errors_json)),
+        repair_input_tokens: item.repair_input_tokens,
+        repair_output_tokens: item.repair_output_tokens,
+        repair_total_tokens: item.repair_total_tokens,
+        created_at: item.created_at,
+    }
+}
+
+fn normalize_trace_id(trace_id: Option<&str>) -> Result<&str, String> {
+    trace_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "traceId is required".to_string())
+}
+
+pub(crate) fn read_request_structured_output(
+    trace_id: Option<&str>,
+) -> Result<Option<RequestStructuredOutputResultItem>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    let storage = open_storage().ok_or_else(|| "open storage failed".to_string())?;
+    storage
+        .find_request_structured_output_result_by_trace_id(trace_id)
+        .map(|item| item.map(map_structured_output_result))
+        .map_err(|err| format!("read request structured output result failed: {err}"))
+}
+
+pub(crate) fn read_request_structured_output_for_key_ids_with_storage(
+    storage: &Storage,
+    trace_id: Option<&str>,
+    key_ids: &[String],
+) -> Result<Option<RequestStructuredOutputResultItem>, String> {
+    let trace_id = normalize_trace_id(trace_id)?;
+    storage
+        .find_request_structured_output_result_by_trace_id_for_key_ids(trace_id, key_ids)
+        .map(|item| item.map(map_structured_output_result))
+        .map_err(|err| format!("read request structured output result failed: {err}"))
+}
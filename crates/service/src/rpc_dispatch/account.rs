@@ -1,10 +1,10 @@
-use codexmanager_core::rpc::types::{JsonRpcRequest, JsonRpcResponse};
+use codexmanager_core::rpc::types::{AccountGroupPolicySetParams, JsonRpcRequest, JsonRpcResponse};
 
 use crate::RpcActor;
 use crate::{
-    account_cleanup, account_delete, account_delete_many, account_export, account_import,
-    account_list, account_proxy, account_update, account_warmup, auth_account, auth_login,
-    auth_tokens,
+    account_cleanup, account_delete, account_delete_many, account_export, account_group_policy,
+    account_health_probe, account_import, account_list, account_proxy, account_update,
+    account_warmup, auth_account, auth_login, auth_tokens,
 };
 
 /// 函数 `try_handle`
@@ -100,26 +100,47 @@ pub(super) fn try_handle(req: &JsonRpcRequest, actor: &RpcActor) -> Option<JsonR
             account_sort_updates_param(req).and_then(account_update::update_account_sorts),
         ),
         "account/warmup" => {
-            let account_ids = req
-                .params
-                .as_ref()
-                .and_then(|params| {
-                    params
-                        .get("accountIds")
-                        .or_else(|| params.get("account_ids"))
-                })
-                .and_then(|value| value.as_array())
-                .map(|items| {
-                    items
-                        .iter()
-                        .filter_map(|item| item.as_str())
-                        .map(|item| item.to_string())
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_default();
+            let account_ids = account_ids_param(req);
             let message = first_string_param(req, &["message"]).unwrap_or_default();
             super::value_or_error(account_warmup::warmup_accounts(account_ids, &message))
         }
+        "account/healthProbe/run" => super::value_or_error(
+            account_health_probe::run_account_health_probes(account_ids_param(req)),
+        ),
+        "account/healthProbe/states" => {
+            super::value_or_error(account_health_probe::list_account_health_states())
+        }
+        "account/healthProbe/history" => {
+            let account_id = first_str_param(req, &["accountId", "account_id"]).unwrap_or("");
+            super::value_or_error(account_health_probe::read_account_health_history(
+                account_id,
+                super::i64_param(req, "limit"),
+            ))
+        }
+        "account/groupPolicy/list" => {
+            super::value_or_error(account_group_policy::list_account_group_policies())
+        }
+        "account/groupPolicy/set" => {
+            let params = req
+                .params
+                .clone()
+                .map(serde_json::from_value::<AccountGroupPolicySetParams>)
+                .transpose()
+                .map_err(|err| format!("invalid account group policy payload: {err}"));
+            super::value_or_error(
+                params
+                    .and_then(|params| {
+                        params.ok_or_else(|| "missing account group policy payload".to_string())
+                    })
+                    .and_then(account_group_policy::set_account_group_policy),
+            )
+        }
+        "account/groupPolicy/delete" => {
+            let group_name = first_str_param(req, &["groupName", "group_name"]).unwrap_or("");
+            super::ok_or_error(account_group_policy::delete_account_group_policy(
+                group_name,
+            ))
+        }
         "account/proxy/get" => {
             let account_id = first_str_param(req, &["accountId", "account_id"]).unwrap_or("");
             super::value_or_error(account_proxy::get_account_proxy_settings(account_id))
@@ -129,6 +150,7 @@ pub(super) fn try_handle(req: &JsonRpcRequest, actor: &RpcActor) -> Option<JsonR
             let enabled = super::bool_param(req, "enabled").unwrap_or(false);
             let source = first_str_param(req, &["source", "proxySource", "proxy_source"]);
             let proxy_profile_id = first_str_param(req, &["proxyProfileId", "proxy_profile_id"]);
+            let proxy_pool_id = first_str_param(req, &["proxyPoolId", "proxy_pool_id"]);
             let proxy_url = first_str_param(req, &["proxyUrl", "proxy_url"]);
 
             let status = super::str_param(req, "status");
@@ -155,6 +177,7 @@ pub(super) fn try_handle(req: &JsonRpcRequest, actor: &RpcActor) -> Option<JsonR
                 enabled,
                 source,
                 proxy_profile_id,
+                proxy_pool_id,
                 proxy_url,
                 status,
                 latency_ms,
@@ -446,6 +469,28 @@ fn account_sort_updates_param(
     Ok(updates)
 }
 
+/// 函数 `account_ids_param`
+///
+/// 读取 `accountIds`（兼容 `account_ids`）字符串数组，缺省为空。
+fn account_ids_param(req: &JsonRpcRequest) -> Vec<String> {
+    req.params
+        .as_ref()
+        .and_then(|params| {
+            params
+                .get("accountIds")
+                .or_else(|| params.get("account_ids"))
+        })
+        .and_then(|value| value.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
 /// 函数 `first_str_param`
 ///
 /// 作者: gaohongshun
@@ -1,8 +1,13 @@
-use codexmanager_core::rpc::types::{AggregateApiListResult, JsonRpcRequest, JsonRpcResponse};
+use codexmanager_core::rpc::types::{
+    AggregateApiDeploymentListResult, AggregateApiDeploymentSetParams, AggregateApiListResult,
+    JsonRpcRequest, JsonRpcResponse,
+};
 
 use crate::{
-    create_aggregate_api, delete_aggregate_api, list_aggregate_apis, read_aggregate_api_secret,
-    refresh_aggregate_api_balance, test_aggregate_api_connection, update_aggregate_api,
+    create_aggregate_api, delete_aggregate_api, delete_aggregate_api_deployment,
+    discover_aggregate_api_models, list_aggregate_api_deployments, list_aggregate_apis,
+    read_aggregate_api_secret, refresh_aggregate_api_balance, set_aggregate_api_deployment,
+    test_aggregate_api_connection, update_aggregate_api,
 };
 
 /// 函数 `api_id_param`
@@ -148,6 +153,36 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
             let api_id = api_id_param(req).unwrap_or("");
             super::value_or_error(refresh_aggregate_api_balance(api_id))
         }
+        "aggregateApi/deployments/list" => {
+            let api_id = api_id_param(req).unwrap_or("");
+            super::value_or_error(
+                list_aggregate_api_deployments(api_id)
+                    .map(|items| AggregateApiDeploymentListResult { items }),
+            )
+        }
+        "aggregateApi/deployments/set" => {
+            let params = req
+                .params
+                .clone()
+                .map(serde_json::from_value::<AggregateApiDeploymentSetParams>)
+                .transpose()
+                .map_err(|err| format!("invalid aggregate api deployment payload: {err}"));
+            super::value_or_error(
+                params
+                    .and_then(|params| {
+                        params.ok_or_else(|| "missing aggregate api deployment payload".to_string())
+                    })
+                    .and_then(set_aggregate_api_deployment),
+            )
+        }
+        "aggregateApi/deployments/delete" => {
+            let api_id = api_id_param(req).unwrap_or("");
+            let upstream_model = super::str_param(req, "upstreamModel").unwrap_or("");
+            super::ok_or_error(delete_aggregate_api_deployment(api_id, upstream_model))
+        }
+        "aggregateApi/models/discover" => {
+            super::value_or_error(discover_aggregate_api_models(api_id_param(req)))
+        }
         _ => return None,
     };
 
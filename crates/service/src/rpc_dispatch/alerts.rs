@@ -0,0 +1,63 @@
+use codexmanager_core::rpc::types::{
+    AlertChannelSetParams, AlertRuleSetParams, JsonRpcRequest, JsonRpcResponse,
+};
+
+use crate::alerts;
+
+pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
+    let result = match req.method.as_str() {
+        "alerts/conditions" => super::as_json(alerts::list_alert_conditions()),
+        "alerts/rule/list" => super::value_or_error(alerts::list_alert_rules()),
+        "alerts/rule/set" => {
+            let params = req
+                .params
+                .clone()
+                .map(serde_json::from_value::<AlertRuleSetParams>)
+                .transpose()
+                .map_err(|err| format!("invalid alert rule payload: {err}"));
+            super::value_or_error(
+                params
+                    .and_then(|params| {
+                        params.ok_or_else(|| "missing alert rule payload".to_string())
+                    })
+                    .and_then(alerts::set_alert_rule),
+            )
+        }
+        "alerts/rule/delete" => {
+            let id = super::str_param(req, "id").unwrap_or("");
+            super::ok_or_error(alerts::delete_alert_rule(id))
+        }
+        "alerts/channel/list" => super::value_or_error(alerts::list_alert_channels()),
+        "alerts/channel/set" => {
+            let params = req
+                .params
+                .clone()
+                .map(serde_json::from_value::<AlertChannelSetParams>)
+                .transpose()
+                .map_err(|err| format!("invalid alert channel payload: {err}"));
+            super::value_or_error(
+                params
+                    .and_then(|params| {
+                        params.ok_or_else(|| "missing alert channel payload".to_string())
+                    })
+                    .and_then(alerts::set_alert_channel),
+            )
+        }
+        "alerts/channel/delete" => {
+            let id = super::str_param(req, "id").unwrap_or("");
+            super::ok_or_error(alerts::delete_alert_channel(id))
+        }
+        "alerts/channel/test" => {
+            let id = super::str_param(req, "id").unwrap_or("");
+            super::ok_or_error(alerts::test_alert_channel(id))
+        }
+        "alerts/history/list" => super::value_or_error(alerts::list_alert_history(
+            super::str_param(req, "ruleId"),
+            super::i64_param(req, "limit"),
+        )),
+        "alerts/evaluate" => super::value_or_error(alerts::evaluate_alerts_now()),
+        _ => return None,
+    };
+
+    Some(super::response(req, result))
+}
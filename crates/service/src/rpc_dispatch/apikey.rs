@@ -1,5 +1,6 @@
 use codexmanager_core::rpc::types::{
-    ApiKeyListResult, ApiKeyUsageStatListResult, JsonRpcRequest, JsonRpcResponse,
+    ApiKeyContentPolicySetParams, ApiKeyListResult, ApiKeyResponseCacheSetParams,
+    ApiKeyStructuredOutputSetParams, ApiKeyUsageStatListResult, JsonRpcRequest, JsonRpcResponse,
 };
 use codexmanager_core::storage::{
     ManagedModelBatchStateV2Update, ManagedModelStateV2Update, ManagedModelV2,
@@ -8,8 +9,9 @@ use codexmanager_core::storage::{
 
 use crate::RpcActor;
 use crate::{
-    apikey_create, apikey_delete, apikey_disable, apikey_enable, apikey_list, apikey_read_secret,
-    apikey_update_model, apikey_usage_stats,
+    apikey_batches, apikey_content_policy, apikey_create, apikey_delete, apikey_disable,
+    apikey_enable, apikey_list, apikey_read_secret, apikey_response_cache, apikey_rotate,
+    apikey_structured_output, apikey_update_model, apikey_usage_stats,
 };
 
 fn ensure_api_key_access(actor: &RpcActor, key_id: &str) -> Result<(), String> {
@@ -193,6 +195,100 @@ pub(super) fn try_handle(req: &JsonRpcRequest, actor: &RpcActor) -> Option<JsonR
                     .and_then(|_| apikey_read_secret::read_api_key_secret(key_id)),
             )
         }
+        "apikey/rotate" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            let custom_key = super::string_param(req, "customKey");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_rotate::rotate_api_key(key_id, custom_key)),
+            )
+        }
+        "apikey/responseCache/get" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_response_cache::get_response_cache(key_id)),
+            )
+        }
+        "apikey/responseCache/set" => {
+            let params = req
+                .params
+                .clone()
+                .ok_or_else(|| "missing response cache payload".to_string())
+                .and_then(|value| {
+                    serde_json::from_value::<ApiKeyResponseCacheSetParams>(value)
+                        .map_err(|err| format!("invalid response cache payload: {err}"))
+                });
+            super::value_or_error(params.and_then(|params| {
+                ensure_api_key_access(actor, params.id.as_str())
+                    .and_then(|_| apikey_response_cache::set_response_cache(params))
+            }))
+        }
+        "apikey/responseCache/clear" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_response_cache::clear_response_cache(key_id)),
+            )
+        }
+        "apikey/structuredOutput/get" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_structured_output::get_structured_output(key_id)),
+            )
+        }
+        "apikey/structuredOutput/set" => {
+            let params = req
+                .params
+                .clone()
+                .ok_or_else(|| "missing structured output payload".to_string())
+                .and_then(|value| {
+                    serde_json::from_value::<ApiKeyStructuredOutputSetParams>(value)
+                        .map_err(|err| format!("invalid structured output payload: {err}"))
+                });
+            super::value_or_error(params.and_then(|params| {
+                ensure_api_key_access(actor, params.id.as_str())
+                    .and_then(|_| apikey_structured_output::set_structured_output(params))
+            }))
+        }
+        "apikey/contentPolicy/get" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_content_policy::get_content_policy(key_id)),
+            )
+        }
+        "apikey/contentPolicy/set" => {
+            let params = req
+                .params
+                .clone()
+                .ok_or_else(|| "missing content policy payload".to_string())
+                .and_then(|value| {
+                    serde_json::from_value::<ApiKeyContentPolicySetParams>(value)
+                        .map_err(|err| format!("invalid content policy payload: {err}"))
+                });
+            super::value_or_error(params.and_then(|params| {
+                ensure_api_key_access(actor, params.id.as_str())
+                    .and_then(|_| apikey_content_policy::set_content_policy(params))
+            }))
+        }
+        "apikey/batches/list" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            let limit = super::i64_param(req, "limit");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_batches::list_batches(key_id, limit)),
+            )
+        }
+        "apikey/batches/get" => {
+            let key_id = super::str_param(req, "id").unwrap_or("");
+            let batch_id = super::str_param(req, "batchId").unwrap_or("");
+            super::value_or_error(
+                ensure_api_key_access(actor, key_id)
+                    .and_then(|_| apikey_batches::get_batch(key_id, batch_id)),
+            )
+        }
         "apikey/managedModelListV2" => {
             let include_hidden =
                 actor.is_admin() && super::bool_param(req, "includeHidden").unwrap_or(false);
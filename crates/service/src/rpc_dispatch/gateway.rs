@@ -18,7 +18,7 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
             let strategy = crate::gateway::current_route_strategy();
             super::as_json(serde_json::json!({
                 "strategy": strategy,
-                "options": ["ordered", "balanced"],
+                "options": ["ordered", "balanced", "cheapest"],
                 "manualPreferredAccountId": crate::gateway::manual_preferred_account(),
             }))
         }
@@ -146,6 +146,10 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
                 http_stream_worker_min: usize_param(req, "httpStreamWorkerMin"),
                 warmup_cron_enabled: super::bool_param(req, "warmupCronEnabled"),
                 warmup_cron_expression: super::string_param(req, "warmupCronExpression"),
+                health_probe_enabled: super::bool_param(req, "healthProbeEnabled"),
+                health_probe_interval_secs: u64_param(req, "healthProbeIntervalSecs"),
+                health_probe_failure_threshold: u64_param(req, "healthProbeFailureThreshold"),
+                health_probe_recovery_threshold: u64_param(req, "healthProbeRecoveryThreshold"),
             };
             let input = crate::BackgroundTasksInput {
                 usage_polling_enabled: patch.usage_polling_enabled,
@@ -161,6 +165,10 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
                 http_stream_worker_min: patch.http_stream_worker_min,
                 warmup_cron_enabled: patch.warmup_cron_enabled,
                 warmup_cron_expression: patch.warmup_cron_expression,
+                health_probe_enabled: patch.health_probe_enabled,
+                health_probe_interval_secs: patch.health_probe_interval_secs,
+                health_probe_failure_threshold: patch.health_probe_failure_threshold,
+                health_probe_recovery_threshold: patch.health_probe_recovery_threshold,
             };
             super::value_or_error(crate::set_gateway_background_tasks(input))
         }
@@ -12,6 +12,7 @@ use crate::RpcActor;
 mod account;
 mod account_manager;
 mod aggregate_api;
+mod alerts;
 mod apikey;
 mod app_settings;
 mod codex_profile;
@@ -212,6 +213,10 @@ const MEMBER_METHOD_ALLOWLIST: &[&str] = &[
     "accountManager/profile/update",
     "accountManager/session/current",
     "accountManager/status",
+    "apikey/batches/get",
+    "apikey/batches/list",
+    "apikey/contentPolicy/get",
+    "apikey/contentPolicy/set",
     "apikey/create",
     "apikey/delete",
     "apikey/disable",
@@ -220,12 +225,21 @@ const MEMBER_METHOD_ALLOWLIST: &[&str] = &[
     "apikey/managedModelGetV2",
     "apikey/managedModelListV2",
     "apikey/readSecret",
+    "apikey/responseCache/clear",
+    "apikey/responseCache/get",
+    "apikey/responseCache/set",
+    "apikey/rotate",
+    "apikey/structuredOutput/get",
+    "apikey/structuredOutput/set",
     "apikey/updateModel",
     "apikey/usageStats",
     "appSettings/get",
     "dashboard/memberSummary",
+    "requestlog/contentPolicy",
     "requestlog/list",
     "requestlog/list_with_summary",
+    "requestlog/routeDecision",
+    "requestlog/structuredOutput",
     "requestlog/summary",
     "requestlog/today_summary",
     "startup/snapshot",
@@ -295,6 +309,9 @@ pub(crate) fn handle_request_with_actor(req: JsonRpcRequest, actor: RpcActor) ->
     if let Some(resp) = aggregate_api::try_handle(&req) {
         return JsonRpcMessage::Response(resp);
     }
+    if let Some(resp) = alerts::try_handle(&req) {
+        return JsonRpcMessage::Response(resp);
+    }
     if let Some(resp) = apikey::try_handle(&req, &actor) {
         return JsonRpcMessage::Response(resp);
     }
@@ -3,7 +3,10 @@ use codexmanager_core::storage::Storage;
 
 use crate::storage_helpers::StorageHandle;
 use crate::RpcActor;
-use crate::{requestlog_clear, requestlog_list, requestlog_summary, requestlog_today_summary};
+use crate::{
+    requestlog_clear, requestlog_content_policy, requestlog_list, requestlog_route_decision,
+    requestlog_structured_output, requestlog_summary, requestlog_today_summary,
+};
 
 fn actor_key_ids_with_storage(storage: &Storage, actor: &RpcActor) -> Result<Vec<String>, String> {
     if actor.is_admin() {
@@ -138,6 +141,42 @@ pub(super) fn try_handle(req: &JsonRpcRequest, actor: &RpcActor) -> Option<JsonR
                 }
             }))
         }
+        "requestlog/routeDecision" => {
+            let trace_id = super::str_param(req, "traceId");
+            super::value_or_error(if actor.is_admin() {
+                requestlog_route_decision::read_request_route_decision(trace_id)
+            } else {
+                member_requestlog_scope(actor).and_then(|(storage, key_ids)| {
+                    requestlog_route_decision::read_request_route_decision_for_key_ids_with_storage(
+                        &storage, trace_id, &key_ids,
+                    )
+                })
+            })
+        }
+        "requestlog/contentPolicy" => {
+            let trace_id = super::str_param(req, "traceId");
+            super::value_or_error(if actor.is_admin() {
+                requestlog_content_policy::read_request_content_policy(trace_id)
+            } else {
+                member_requestlog_scope(actor).and_then(|(storage, key_ids)| {
+                    requestlog_content_policy::read_request_content_policy_for_key_ids_with_storage(
+                        &storage, trace_id, &key_ids,
+                    )
+                })
+            })
+        }
+        "requestlog/structuredOutput" => {
+            let trace_id = super::str_param(req, "traceId");
+            super::value_or_error(if actor.is_admin() {
+                requestlog_structured_output::read_request_structured_output(trace_id)
+            } else {
+                member_requestlog_scope(actor).and_then(|(storage, key_ids)| {
+                    requestlog_structured_output::read_request_structured_output_for_key_ids_with_storage(
+                        &storage, trace_id, &key_ids,
+                    )
+                })
+            })
+        }
         "requestlog/clear" => super::ok_or_error(requestlog_clear::clear_request_logs()),
         "requestlog/today_summary" => {
             let day_start_ts = super::i64_param(req, "dayStartTs");
@@ -1,5 +1,7 @@
 use codexmanager_core::rpc::types::{JsonRpcRequest, JsonRpcResponse};
 
+use crate::declarative_config::{DeclarativeConfigSource, DocumentFormat};
+
 /// 函数 `try_handle`
 ///
 /// 作者: gaohongshun
@@ -34,8 +36,33 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
                 })
             }))
         }
+        "service/declarativeConfig/diff" => super::value_or_error(
+            declarative_config_source(req)
+                .and_then(crate::declarative_config::diff_declarative_config),
+        ),
+        "service/declarativeConfig/apply" => super::value_or_error(
+            declarative_config_source(req)
+                .and_then(crate::declarative_config::apply_declarative_config),
+        ),
+        "service/declarativeConfig/status" => {
+            super::as_json(crate::declarative_config::declarative_config_status())
+        }
+        "service/drain/status" => super::as_json(crate::lifecycle::drain::drain_status()),
+        "service/drain/start" => {
+            crate::lifecycle::drain::begin_drain();
+            super::as_json(crate::lifecycle::drain::drain_status())
+        }
         _ => return None,
     };
 
     Some(super::response(req, result))
 }
+
+/// 未提供 `content` 时使用 `CODEXMANAGER_CONFIG_FILE` 指向的配置文件。
+fn declarative_config_source(req: &JsonRpcRequest) -> Result<DeclarativeConfigSource, String> {
+    let Some(content) = super::string_param(req, "content") else {
+        return Ok(DeclarativeConfigSource::ConfiguredFile);
+    };
+    let format = DocumentFormat::parse(super::str_param(req, "format").unwrap_or("toml"))?;
+    Ok(DeclarativeConfigSource::Inline { content, format })
+}
@@ -1,11 +1,15 @@
-use codexmanager_core::rpc::types::{JsonRpcRequest, JsonRpcResponse, ProxyProfileListResult};
+use codexmanager_core::rpc::types::{
+    JsonRpcRequest, JsonRpcResponse, ProxyPoolListResult, ProxyPoolSetParams,
+    ProxyProfileListResult, ProxySubscriptionListResult,
+};
 
 use crate::{
     cancel_proxy_test_job, create_proxy_profile, delete_proxy_profile,
     get_proxy_profile_diagnostics_history, get_proxy_profile_latency_test_history,
-    get_proxy_profile_speed_test_history, get_proxy_test_job, list_proxy_profiles,
-    proxy_test_presets, test_proxy_profile, test_proxy_profile_cloudflare_style_speed,
-    test_proxy_profile_latency, test_proxy_profile_speed, update_proxy_profile,
+    get_proxy_profile_speed_test_history, get_proxy_test_job, list_proxy_profiles, proxy_pools,
+    proxy_subscriptions, proxy_test_presets, test_proxy_profile,
+    test_proxy_profile_cloudflare_style_speed, test_proxy_profile_latency,
+    test_proxy_profile_speed, update_proxy_profile,
 };
 
 pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
@@ -31,6 +35,53 @@ pub(super) fn try_handle(req: &JsonRpcRequest) -> Option<JsonRpcResponse> {
         "system/proxy/delete" => super::ok_or_error(delete_proxy_profile(
             proxy_profile_id_param(req).unwrap_or(""),
         )),
+        "system/proxyPool/list" => super::value_or_error(
+            proxy_pools::list_proxy_pools().map(|items| ProxyPoolListResult { items }),
+        ),
+        "system/proxyPool/set" => {
+            let params = req
+                .params
+                .clone()
+                .map(serde_json::from_value::<ProxyPoolSetParams>)
+                .transpose()
+                .map_err(|err| format!("invalid proxy pool payload: {err}"));
+            super::value_or_error(
+                params
+                    .and_then(|params| {
+                        params.ok_or_else(|| "missing proxy pool payload".to_string())
+                    })
+                    .and_then(proxy_pools::set_proxy_pool),
+            )
+        }
+        "system/proxyPool/delete" => super::ok_or_error(proxy_pools::delete_proxy_pool(
+            super::str_param(req, "id").unwrap_or(""),
+        )),
+        "system/proxySubscription/list" => super::value_or_error(
+            proxy_subscriptions::list_proxy_subscriptions()
+                .map(|items| ProxySubscriptionListResult { items }),
+        ),
+        "system/proxySubscription/import" => {
+            super::value_or_error(proxy_subscriptions::import_proxy_subscription(
+                super::string_param(req, "name"),
+                super::string_param(req, "url"),
+                super::string_param(req, "content"),
+                first_string_param(req, &["tagsJson", "tags_json"]),
+            ))
+        }
+        "system/proxySubscription/refresh" => {
+            super::value_or_error(proxy_subscriptions::refresh_proxy_subscription(
+                super::str_param(req, "id").unwrap_or(""),
+            ))
+        }
+        "system/proxySubscription/delete" => {
+            let delete_profiles = super::bool_param(req, "deleteProfiles")
+                .or_else(|| super::bool_param(req, "delete_profiles"))
+                .unwrap_or(false);
+            super::ok_or_error(proxy_subscriptions::delete_proxy_subscription(
+                super::str_param(req, "id").unwrap_or(""),
+                delete_profiles,
+            ))
+        }
         "system/proxy/test" => super::value_or_error(test_proxy_profile(
             proxy_profile_id_param(req).unwrap_or(""),
         )),
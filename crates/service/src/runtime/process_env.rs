@@ -1,112 +1,17 @@
+use codexmanager_core::process_env::{resolve_db_path, rpc_token_file_path_in};
 use rand::RngCore;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-const ENV_CANDIDATES: [&str; 3] = ["codexmanager.env", "CodexManager.env", ".env"];
-const DEFAULT_DB_FILENAME: &str = "codexmanager.db";
-const DEFAULT_RPC_TOKEN_FILENAME: &str = "codexmanager.rpc-token";
-const INSTALLATION_ID_FILENAME: &str = "installation_id";
-
-pub(crate) const ENV_DB_PATH: &str = "CODEXMANAGER_DB_PATH";
-pub(crate) const ENV_RPC_TOKEN: &str = "CODEXMANAGER_RPC_TOKEN";
-pub(crate) const ENV_RPC_TOKEN_FILE: &str = "CODEXMANAGER_RPC_TOKEN_FILE";
-
-/// 函数 `exe_dir`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - crate: 参数 crate
-///
-/// # 返回
-/// 返回函数执行结果
-pub(crate) fn exe_dir() -> PathBuf {
-    std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .or_else(|| std::env::current_dir().ok())
-        .unwrap_or_else(|| PathBuf::from("."))
-}
-
-/// 函数 `strip_inline_comment`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - value: 参数 value
-///
-/// # 返回
-/// 返回函数执行结果
-fn strip_inline_comment(value: &str) -> &str {
-    // Only treat ` #` as comment start (common dotenv behavior).
-    let Some(pos) = value.find(" #") else {
-        return value;
-    };
-    value[..pos].trim_end()
-}
-
-/// 函数 `parse_dotenv_kv`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - line: 参数 line
-///
-/// # 返回
-/// 返回函数执行结果
-fn parse_dotenv_kv(line: &str) -> Option<(String, String)> {
-    let mut line = line.trim();
-    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
-        return None;
-    }
-    if let Some(rest) = line.strip_prefix("export ") {
-        line = rest.trim();
-    }
-    let (key, raw_value) = line.split_once('=')?;
-    let key = key.trim();
-    if key.is_empty() {
-        return None;
-    }
-    let mut value = raw_value.trim();
-    // Handle quoted values: KEY="a b", KEY='a b'
-    if (value.starts_with('"') && value.ends_with('"') && value.len() >= 2)
-        || (value.starts_with('\'') && value.ends_with('\'') && value.len() >= 2)
-    {
-        value = &value[1..value.len() - 1];
-    } else {
-        value = strip_inline_comment(value);
-    }
-    Some((key.to_string(), value.to_string()))
-}
+#[cfg(test)]
+use codexmanager_core::process_env::ENV_RPC_TOKEN_FILE;
+pub(crate) use codexmanager_core::process_env::{
+    exe_dir, read_rpc_token_from_env_or_file, read_rpc_token_from_file, ENV_DB_PATH, ENV_RPC_TOKEN,
+};
 
-/// 函数 `find_env_file_in_dir`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - dir: 参数 dir
-///
-/// # 返回
-/// 返回函数执行结果
-fn find_env_file_in_dir(dir: &Path) -> Option<PathBuf> {
-    for name in ENV_CANDIDATES {
-        let candidate = dir.join(name);
-        if candidate.is_file() {
-            return Some(candidate);
-        }
-    }
-    None
-}
+const INSTALLATION_ID_FILENAME: &str = "installation_id";
 
 /// 函数 `load_env_from_exe_dir`
 ///
@@ -120,60 +25,15 @@ fn find_env_file_in_dir(dir: &Path) -> Option<PathBuf> {
 /// # 返回
 /// 无
 pub(crate) fn load_env_from_exe_dir() {
-    let dir = exe_dir();
-    let Some(path) = find_env_file_in_dir(&dir) else {
+    let Some((path, applied)) = codexmanager_core::process_env::load_env_file_from_dir(&exe_dir())
+    else {
         return;
     };
-
-    let Ok(mut f) = fs::File::open(&path) else {
-        return;
-    };
-    let mut text = String::new();
-    if f.read_to_string(&mut text).is_err() {
-        return;
-    }
-
-    let mut applied = 0usize;
-    for line in text.lines() {
-        let Some((key, value)) = parse_dotenv_kv(line) else {
-            continue;
-        };
-        if std::env::var_os(&key).is_some() {
-            continue;
-        }
-        std::env::set_var(key, value);
-        applied += 1;
-    }
-
     if applied > 0 {
         log::info!("Loaded {} env vars from {}", applied, path.display());
     }
 }
 
-/// 函数 `resolve_path_with_base`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - raw: 参数 raw
-/// - base_dir: 参数 base_dir
-///
-/// # 返回
-/// 返回函数执行结果
-fn resolve_path_with_base(raw: &str, base_dir: &Path) -> PathBuf {
-    let raw = raw.trim();
-    if raw.is_empty() {
-        return PathBuf::new();
-    }
-    let path = PathBuf::from(raw);
-    if path.is_absolute() {
-        return path;
-    }
-    base_dir.join(path)
-}
-
 /// 函数 `ensure_default_db_path`
 ///
 /// 作者: gaohongshun
@@ -186,11 +46,7 @@ fn resolve_path_with_base(raw: &str, base_dir: &Path) -> PathBuf {
 /// # 返回
 /// 返回函数执行结果
 pub(crate) fn ensure_default_db_path() -> PathBuf {
-    let dir = exe_dir();
-    let resolved = match std::env::var(ENV_DB_PATH) {
-        Ok(raw) if !raw.trim().is_empty() => resolve_path_with_base(&raw, &dir),
-        _ => dir.join(DEFAULT_DB_FILENAME),
-    };
+    let resolved = resolve_db_path();
     std::env::set_var(ENV_DB_PATH, resolved.to_string_lossy().as_ref());
     resolved
 }
@@ -295,60 +151,7 @@ fn random_uuid_v4() -> String {
 }
 
 pub(crate) fn rpc_token_file_path() -> PathBuf {
-    if let Ok(raw) = std::env::var(ENV_RPC_TOKEN_FILE) {
-        let trimmed = raw.trim();
-        if !trimmed.is_empty() {
-            return resolve_path_with_base(trimmed, &exe_dir());
-        }
-    }
-    db_dir().join(DEFAULT_RPC_TOKEN_FILENAME)
-}
-
-/// 函数 `read_rpc_token_from_file`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - crate: 参数 crate
-///
-/// # 返回
-/// 返回函数执行结果
-pub(crate) fn read_rpc_token_from_file(path: &Path) -> Option<String> {
-    let Ok(mut f) = fs::File::open(path) else {
-        return None;
-    };
-    let mut buf = String::new();
-    if f.read_to_string(&mut buf).is_err() {
-        return None;
-    }
-    let token = buf.trim();
-    if token.is_empty() {
-        return None;
-    }
-    Some(token.to_string())
-}
-
-/// 函数 `read_rpc_token_from_env_or_file`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
-///
-/// # 参数
-/// - crate: 参数 crate
-///
-/// # 返回
-/// 返回函数执行结果
-pub(crate) fn read_rpc_token_from_env_or_file() -> Option<String> {
-    if let Ok(raw) = std::env::var(ENV_RPC_TOKEN) {
-        let trimmed = raw.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
-        }
-    }
-    read_rpc_token_from_file(&rpc_token_file_path())
+    rpc_token_file_path_in(&db_dir())
 }
 
 /// 尝试把 token 写入 token file（仅在文件不存在或为空时）。
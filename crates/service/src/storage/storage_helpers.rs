@@ -326,6 +326,46 @@ pub(crate) fn generate_proxy_profile_id() -> String {
     out
 }
 
+pub(crate) fn generate_proxy_pool_id() -> String {
+    let mut buf = [0u8; 6];
+    rand::rngs::OsRng.fill_bytes(&mut buf);
+    let mut out = String::from("ppl_");
+    for b in buf {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+pub(crate) fn generate_proxy_subscription_id() -> String {
+    let mut buf = [0u8; 6];
+    rand::rngs::OsRng.fill_bytes(&mut buf);
+    let mut out = String::from("psub_");
+    for b in buf {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+pub(crate) fn generate_alert_rule_id() -> String {
+    let mut buf = [0u8; 6];
+    rand::rngs::OsRng.fill_bytes(&mut buf);
+    let mut out = String::from("ar_");
+    for b in buf {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+pub(crate) fn generate_alert_channel_id() -> String {
+    let mut buf = [0u8; 6];
+    rand::rngs::OsRng.fill_bytes(&mut buf);
+    let mut out = String::from("ac_");
+    for b in buf {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
 #[cfg(test)]
 static STORAGE_OPEN_COUNTS: std::sync::OnceLock<std::sync::Mutex<HashMap<String, usize>>> =
     std::sync::OnceLock::new();
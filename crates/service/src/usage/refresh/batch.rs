@@ -205,10 +205,17 @@ fn load_refreshable_usage_refresh_tasks(
 }
 
 fn refreshable_account_statuses() -> Vec<String> {
-    ["active", "inactive", "limited", "unavailable", "unknown"]
-        .into_iter()
-        .map(String::from)
-        .collect()
+    [
+        "active",
+        "inactive",
+        "limited",
+        "quarantined",
+        "unavailable",
+        "unknown",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
 }
 
 #[derive(Clone)]
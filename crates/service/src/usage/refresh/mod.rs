@@ -42,6 +42,7 @@ static USAGE_POLLING_STARTED: OnceLock<()> = OnceLock::new();
 static GATEWAY_KEEPALIVE_STARTED: OnceLock<()> = OnceLock::new();
 static TOKEN_REFRESH_POLLING_STARTED: OnceLock<()> = OnceLock::new();
 static WARMUP_CRON_STARTED: OnceLock<()> = OnceLock::new();
+static HEALTH_PROBE_STARTED: OnceLock<()> = OnceLock::new();
 static WARMUP_CRON_SIGNAL: OnceLock<(Mutex<u64>, Condvar)> = OnceLock::new();
 static BACKGROUND_TASKS_CONFIG_LOADED: OnceLock<()> = OnceLock::new();
 static USAGE_POLL_CURSOR: AtomicUsize = AtomicUsize::new(0);
@@ -60,6 +61,12 @@ static HTTP_STREAM_WORKER_FACTOR: AtomicUsize = AtomicUsize::new(DEFAULT_HTTP_ST
 static HTTP_STREAM_WORKER_MIN: AtomicUsize = AtomicUsize::new(DEFAULT_HTTP_STREAM_WORKER_MIN);
 static WARMUP_CRON_ENABLED: AtomicBool = AtomicBool::new(false);
 static WARMUP_CRON_EXPRESSION: OnceLock<Mutex<String>> = OnceLock::new();
+static HEALTH_PROBE_ENABLED: AtomicBool = AtomicBool::new(false);
+static HEALTH_PROBE_INTERVAL_SECS: AtomicU64 = AtomicU64::new(DEFAULT_HEALTH_PROBE_INTERVAL_SECS);
+static HEALTH_PROBE_FAILURE_THRESHOLD: AtomicU64 =
+    AtomicU64::new(DEFAULT_HEALTH_PROBE_FAILURE_THRESHOLD);
+static HEALTH_PROBE_RECOVERY_THRESHOLD: AtomicU64 =
+    AtomicU64::new(DEFAULT_HEALTH_PROBE_RECOVERY_THRESHOLD);
 
 const ENV_DISABLE_POLLING: &str = "CODEXMANAGER_DISABLE_POLLING";
 const ENV_USAGE_POLLING_ENABLED: &str = "CODEXMANAGER_USAGE_POLLING_ENABLED";
@@ -74,6 +81,10 @@ const ENV_TOKEN_REFRESH_POLLING_ENABLED: &str = "CODEXMANAGER_TOKEN_REFRESH_POLL
 const ENV_TOKEN_REFRESH_POLL_INTERVAL_SECS: &str = "CODEXMANAGER_TOKEN_REFRESH_POLL_INTERVAL_SECS";
 const ENV_WARMUP_CRON_ENABLED: &str = "CODEXMANAGER_WARMUP_CRON_ENABLED";
 const ENV_WARMUP_CRON_EXPRESSION: &str = "CODEXMANAGER_WARMUP_CRON_EXPRESSION";
+const ENV_HEALTH_PROBE_ENABLED: &str = "CODEXMANAGER_HEALTH_PROBE_ENABLED";
+const ENV_HEALTH_PROBE_INTERVAL_SECS: &str = "CODEXMANAGER_HEALTH_PROBE_INTERVAL_SECS";
+const ENV_HEALTH_PROBE_FAILURE_THRESHOLD: &str = "CODEXMANAGER_HEALTH_PROBE_FAILURE_THRESHOLD";
+const ENV_HEALTH_PROBE_RECOVERY_THRESHOLD: &str = "CODEXMANAGER_HEALTH_PROBE_RECOVERY_THRESHOLD";
 const ENV_TOKEN_REFRESH_BATCH_LIMIT: &str = "CODEXMANAGER_TOKEN_REFRESH_BATCH_LIMIT";
 const COMMON_POLL_JITTER_ENV: &str = "CODEXMANAGER_POLL_JITTER_SECS";
 const COMMON_POLL_FAILURE_BACKOFF_MAX_ENV: &str = "CODEXMANAGER_POLL_FAILURE_BACKOFF_MAX_SECS";
@@ -100,6 +111,11 @@ const TOKEN_REFRESH_FAILURE_BACKOFF_MAX_SECS: u64 = 300;
 const TOKEN_REFRESH_LOOKAHEAD_BUFFER_SECS: u64 = 60;
 const TOKEN_REFRESH_FALLBACK_AGE_SECS: i64 = 2700;
 const DEFAULT_TOKEN_REFRESH_BATCH_LIMIT: usize = 2048;
+const DEFAULT_HEALTH_PROBE_INTERVAL_SECS: u64 = 300;
+const MIN_HEALTH_PROBE_INTERVAL_SECS: u64 = 60;
+const HEALTH_PROBE_FAILURE_BACKOFF_MAX_SECS: u64 = 1800;
+const DEFAULT_HEALTH_PROBE_FAILURE_THRESHOLD: u64 = 3;
+const DEFAULT_HEALTH_PROBE_RECOVERY_THRESHOLD: u64 = 2;
 const BACKGROUND_TASK_RESTART_REQUIRED_KEYS: [&str; 5] = [
     "usageRefreshWorkers",
     "httpWorkerFactor",
@@ -181,11 +197,12 @@ use self::errors::{
 use self::queue::clear_pending_usage_refresh_tasks_for_tests;
 pub(crate) use self::queue::enqueue_usage_refresh_with_worker;
 use self::runner::{
-    gateway_keepalive_loop, token_refresh_polling_loop, usage_polling_loop, warmup_cron_loop,
+    gateway_keepalive_loop, health_probe_loop, token_refresh_polling_loop, usage_polling_loop,
+    warmup_cron_loop,
 };
 use self::settings::ensure_background_tasks_config_loaded;
 pub(crate) use self::settings::{
-    background_tasks_settings, reload_background_tasks_runtime_from_env,
+    background_tasks_settings, health_probe_policy, reload_background_tasks_runtime_from_env,
     set_background_tasks_settings, validate_background_tasks_settings_patch,
     BackgroundTasksSettingsPatch,
 };
@@ -232,6 +249,17 @@ pub(crate) fn notify_usage_refresh_completed(source: &'static str, processed: us
             Err(TrySendError::Disconnected(_)) => false,
         });
     }
+
+    crate::event_bus::publish_event(
+        crate::event_bus::TOPIC_USAGE_REFRESH,
+        crate::event_bus::EventAudience::Admin,
+        serde_json::json!({
+            "source": event.source,
+            "processed": event.processed,
+            "total": event.total,
+            "completedAt": event.completed_at,
+        }),
+    );
 }
 
 /// 函数 `ensure_usage_polling`
@@ -295,6 +323,13 @@ pub(crate) fn ensure_warmup_cron() {
     });
 }
 
+pub(crate) fn ensure_health_probe() {
+    ensure_background_tasks_config_loaded();
+    HEALTH_PROBE_STARTED.get_or_init(|| {
+        spawn_background_loop("account-health-probe", health_probe_loop);
+    });
+}
+
 /// 函数 `spawn_background_loop`
 ///
 /// 作者: gaohongshun
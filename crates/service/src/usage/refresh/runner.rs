@@ -14,7 +14,8 @@ use super::{
     DEFAULT_GATEWAY_KEEPALIVE_FAILURE_BACKOFF_MAX_SECS, DEFAULT_GATEWAY_KEEPALIVE_JITTER_SECS,
     DEFAULT_USAGE_POLL_FAILURE_BACKOFF_MAX_SECS, DEFAULT_USAGE_POLL_JITTER_SECS,
     GATEWAY_KEEPALIVE_ENABLED, GATEWAY_KEEPALIVE_FAILURE_BACKOFF_MAX_ENV,
-    GATEWAY_KEEPALIVE_INTERVAL_SECS, GATEWAY_KEEPALIVE_JITTER_ENV,
+    GATEWAY_KEEPALIVE_INTERVAL_SECS, GATEWAY_KEEPALIVE_JITTER_ENV, HEALTH_PROBE_ENABLED,
+    HEALTH_PROBE_FAILURE_BACKOFF_MAX_SECS, HEALTH_PROBE_INTERVAL_SECS,
     TOKEN_REFRESH_FAILURE_BACKOFF_MAX_SECS, TOKEN_REFRESH_POLLING_ENABLED,
     TOKEN_REFRESH_POLL_INTERVAL_SECS_ATOMIC, USAGE_POLLING_ENABLED,
     USAGE_POLL_FAILURE_BACKOFF_MAX_ENV, USAGE_POLL_INTERVAL_SECS, USAGE_POLL_JITTER_ENV,
@@ -53,7 +54,12 @@ pub(super) fn usage_polling_loop() {
                 interval_secs,
             )
         },
-        refresh_usage_and_aggregate_balances_for_polling_cycle,
+        || {
+            let result = refresh_usage_and_aggregate_balances_for_polling_cycle();
+            // 中文注释：告警规则依附已有后台轮询触发，评估与投递在独立线程执行，内部按最小间隔节流。
+            crate::alerts::evaluate_alert_rules_if_due();
+            result
+        },
         |_| true,
     );
 }
@@ -113,7 +119,11 @@ pub(super) fn token_refresh_polling_loop() {
         || TOKEN_REFRESH_POLL_INTERVAL_SECS_ATOMIC.load(Ordering::Relaxed),
         || 0,
         |interval_secs| TOKEN_REFRESH_FAILURE_BACKOFF_MAX_SECS.max(interval_secs),
-        refresh_tokens_before_expiry_for_all_accounts,
+        || {
+            let result = refresh_tokens_before_expiry_for_all_accounts();
+            crate::alerts::evaluate_alert_rules_if_due();
+            result
+        },
         |_| true,
     );
 }
@@ -144,6 +154,19 @@ fn classify_warmup_cron_wait(
     None
 }
 
+/// 账号健康探测循环：按固定间隔对可路由账号与已隔离账号各发一次最小请求。
+pub(super) fn health_probe_loop() {
+    run_dynamic_poll_loop(
+        "account health probe",
+        || HEALTH_PROBE_ENABLED.load(Ordering::Relaxed),
+        || HEALTH_PROBE_INTERVAL_SECS.load(Ordering::Relaxed),
+        || 0,
+        |interval_secs| HEALTH_PROBE_FAILURE_BACKOFF_MAX_SECS.max(interval_secs),
+        crate::account_health_probe::run_health_probe_cycle,
+        |_| true,
+    );
+}
+
 pub(super) fn warmup_cron_loop() {
     let mut last_invalid_expression = String::new();
     let mut signal_version = warmup_cron_signal_version();
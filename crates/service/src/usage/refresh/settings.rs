@@ -3,16 +3,21 @@ use std::sync::atomic::Ordering;
 
 use super::{
     parse_interval_secs, BACKGROUND_TASKS_CONFIG_LOADED, BACKGROUND_TASK_RESTART_REQUIRED_KEYS,
-    DEFAULT_GATEWAY_KEEPALIVE_INTERVAL_SECS, DEFAULT_HTTP_STREAM_WORKER_FACTOR,
-    DEFAULT_HTTP_STREAM_WORKER_MIN, DEFAULT_HTTP_WORKER_FACTOR, DEFAULT_HTTP_WORKER_MIN,
-    DEFAULT_TOKEN_REFRESH_POLL_INTERVAL_SECS, DEFAULT_USAGE_POLL_INTERVAL_SECS,
-    DEFAULT_USAGE_REFRESH_WORKERS, ENV_DISABLE_POLLING, ENV_GATEWAY_KEEPALIVE_ENABLED,
-    ENV_GATEWAY_KEEPALIVE_INTERVAL_SECS, ENV_HTTP_STREAM_WORKER_FACTOR, ENV_HTTP_STREAM_WORKER_MIN,
+    DEFAULT_GATEWAY_KEEPALIVE_INTERVAL_SECS, DEFAULT_HEALTH_PROBE_FAILURE_THRESHOLD,
+    DEFAULT_HEALTH_PROBE_INTERVAL_SECS, DEFAULT_HEALTH_PROBE_RECOVERY_THRESHOLD,
+    DEFAULT_HTTP_STREAM_WORKER_FACTOR, DEFAULT_HTTP_STREAM_WORKER_MIN, DEFAULT_HTTP_WORKER_FACTOR,
+    DEFAULT_HTTP_WORKER_MIN, DEFAULT_TOKEN_REFRESH_POLL_INTERVAL_SECS,
+    DEFAULT_USAGE_POLL_INTERVAL_SECS, DEFAULT_USAGE_REFRESH_WORKERS, ENV_DISABLE_POLLING,
+    ENV_GATEWAY_KEEPALIVE_ENABLED, ENV_GATEWAY_KEEPALIVE_INTERVAL_SECS, ENV_HEALTH_PROBE_ENABLED,
+    ENV_HEALTH_PROBE_FAILURE_THRESHOLD, ENV_HEALTH_PROBE_INTERVAL_SECS,
+    ENV_HEALTH_PROBE_RECOVERY_THRESHOLD, ENV_HTTP_STREAM_WORKER_FACTOR, ENV_HTTP_STREAM_WORKER_MIN,
     ENV_HTTP_WORKER_FACTOR, ENV_HTTP_WORKER_MIN, ENV_TOKEN_REFRESH_POLLING_ENABLED,
     ENV_TOKEN_REFRESH_POLL_INTERVAL_SECS, ENV_USAGE_POLLING_ENABLED, ENV_USAGE_POLL_INTERVAL_SECS,
     ENV_WARMUP_CRON_ENABLED, ENV_WARMUP_CRON_EXPRESSION, GATEWAY_KEEPALIVE_ENABLED,
-    GATEWAY_KEEPALIVE_INTERVAL_SECS, HTTP_STREAM_WORKER_FACTOR, HTTP_STREAM_WORKER_MIN,
-    HTTP_WORKER_FACTOR, HTTP_WORKER_MIN, MIN_GATEWAY_KEEPALIVE_INTERVAL_SECS,
+    GATEWAY_KEEPALIVE_INTERVAL_SECS, HEALTH_PROBE_ENABLED, HEALTH_PROBE_FAILURE_THRESHOLD,
+    HEALTH_PROBE_INTERVAL_SECS, HEALTH_PROBE_RECOVERY_THRESHOLD, HTTP_STREAM_WORKER_FACTOR,
+    HTTP_STREAM_WORKER_MIN, HTTP_WORKER_FACTOR, HTTP_WORKER_MIN,
+    MIN_GATEWAY_KEEPALIVE_INTERVAL_SECS, MIN_HEALTH_PROBE_INTERVAL_SECS,
     MIN_TOKEN_REFRESH_POLL_INTERVAL_SECS, MIN_USAGE_POLL_INTERVAL_SECS,
     TOKEN_REFRESH_POLLING_ENABLED, TOKEN_REFRESH_POLL_INTERVAL_SECS_ATOMIC, USAGE_POLLING_ENABLED,
     USAGE_POLL_INTERVAL_SECS, USAGE_REFRESH_WORKERS, USAGE_REFRESH_WORKERS_ENV,
@@ -20,6 +25,7 @@ use super::{
 };
 
 use super::runner::validate_warmup_cron_expression;
+use crate::account_health_probe::HealthProbePolicy;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,6 +43,10 @@ pub(crate) struct BackgroundTasksSettings {
     http_stream_worker_min: usize,
     warmup_cron_enabled: bool,
     warmup_cron_expression: String,
+    health_probe_enabled: bool,
+    health_probe_interval_secs: u64,
+    health_probe_failure_threshold: u64,
+    health_probe_recovery_threshold: u64,
     requires_restart_keys: Vec<&'static str>,
 }
 
@@ -55,6 +65,10 @@ pub(crate) struct BackgroundTasksSettingsPatch {
     pub http_stream_worker_min: Option<usize>,
     pub warmup_cron_enabled: Option<bool>,
     pub warmup_cron_expression: Option<String>,
+    pub health_probe_enabled: Option<bool>,
+    pub health_probe_interval_secs: Option<u64>,
+    pub health_probe_failure_threshold: Option<u64>,
+    pub health_probe_recovery_threshold: Option<u64>,
 }
 
 /// 函数 `background_tasks_settings`
@@ -87,6 +101,10 @@ pub(crate) fn background_tasks_settings() -> BackgroundTasksSettings {
         http_stream_worker_min: HTTP_STREAM_WORKER_MIN.load(Ordering::Relaxed),
         warmup_cron_enabled,
         warmup_cron_expression,
+        health_probe_enabled: HEALTH_PROBE_ENABLED.load(Ordering::Relaxed),
+        health_probe_interval_secs: HEALTH_PROBE_INTERVAL_SECS.load(Ordering::Relaxed),
+        health_probe_failure_threshold: HEALTH_PROBE_FAILURE_THRESHOLD.load(Ordering::Relaxed),
+        health_probe_recovery_threshold: HEALTH_PROBE_RECOVERY_THRESHOLD.load(Ordering::Relaxed),
         requires_restart_keys: BACKGROUND_TASK_RESTART_REQUIRED_KEYS.to_vec(),
     }
 }
@@ -175,6 +193,25 @@ pub(crate) fn set_background_tasks_settings(
         HTTP_STREAM_WORKER_MIN.store(normalized, Ordering::Relaxed);
         std::env::set_var(ENV_HTTP_STREAM_WORKER_MIN, normalized.to_string());
     }
+    if let Some(enabled) = patch.health_probe_enabled {
+        HEALTH_PROBE_ENABLED.store(enabled, Ordering::Relaxed);
+        std::env::set_var(ENV_HEALTH_PROBE_ENABLED, if enabled { "1" } else { "0" });
+    }
+    if let Some(secs) = patch.health_probe_interval_secs {
+        let normalized = secs.max(MIN_HEALTH_PROBE_INTERVAL_SECS);
+        HEALTH_PROBE_INTERVAL_SECS.store(normalized, Ordering::Relaxed);
+        std::env::set_var(ENV_HEALTH_PROBE_INTERVAL_SECS, normalized.to_string());
+    }
+    if let Some(value) = patch.health_probe_failure_threshold {
+        let normalized = value.max(1);
+        HEALTH_PROBE_FAILURE_THRESHOLD.store(normalized, Ordering::Relaxed);
+        std::env::set_var(ENV_HEALTH_PROBE_FAILURE_THRESHOLD, normalized.to_string());
+    }
+    if let Some(value) = patch.health_probe_recovery_threshold {
+        let normalized = value.max(1);
+        HEALTH_PROBE_RECOVERY_THRESHOLD.store(normalized, Ordering::Relaxed);
+        std::env::set_var(ENV_HEALTH_PROBE_RECOVERY_THRESHOLD, normalized.to_string());
+    }
     let mut warmup_cron_changed = false;
     if let Some(enabled) = patch.warmup_cron_enabled {
         WARMUP_CRON_ENABLED.store(enabled, Ordering::Relaxed);
@@ -342,6 +379,47 @@ fn reload_background_tasks_from_env() {
         .map(|value| normalize_text_setting(&value))
         .unwrap_or_default();
     set_mutex_string(&WARMUP_CRON_EXPRESSION, warmup_cron_expression.as_str());
+    HEALTH_PROBE_ENABLED.store(
+        env_bool_or(ENV_HEALTH_PROBE_ENABLED, false),
+        Ordering::Relaxed,
+    );
+    HEALTH_PROBE_INTERVAL_SECS.store(
+        parse_interval_secs(
+            std::env::var(ENV_HEALTH_PROBE_INTERVAL_SECS)
+                .ok()
+                .as_deref(),
+            DEFAULT_HEALTH_PROBE_INTERVAL_SECS,
+            MIN_HEALTH_PROBE_INTERVAL_SECS,
+        ),
+        Ordering::Relaxed,
+    );
+    HEALTH_PROBE_FAILURE_THRESHOLD.store(
+        env_u64_or(
+            ENV_HEALTH_PROBE_FAILURE_THRESHOLD,
+            DEFAULT_HEALTH_PROBE_FAILURE_THRESHOLD,
+        )
+        .max(1),
+        Ordering::Relaxed,
+    );
+    HEALTH_PROBE_RECOVERY_THRESHOLD.store(
+        env_u64_or(
+            ENV_HEALTH_PROBE_RECOVERY_THRESHOLD,
+            DEFAULT_HEALTH_PROBE_RECOVERY_THRESHOLD,
+        )
+        .max(1),
+        Ordering::Relaxed,
+    );
+}
+
+/// 当前生效的健康探测隔离/恢复阈值。
+pub(crate) fn health_probe_policy() -> HealthProbePolicy {
+    ensure_background_tasks_config_loaded();
+    HealthProbePolicy {
+        failure_threshold: u32::try_from(HEALTH_PROBE_FAILURE_THRESHOLD.load(Ordering::Relaxed))
+            .unwrap_or(u32::MAX),
+        recovery_threshold: u32::try_from(HEALTH_PROBE_RECOVERY_THRESHOLD.load(Ordering::Relaxed))
+            .unwrap_or(u32::MAX),
+    }
 }
 
 pub(super) fn current_mutex_string(
@@ -393,6 +471,13 @@ fn env_usize_or(name: &str, default: usize) -> usize {
         .unwrap_or(default)
 }
 
+fn env_u64_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(default)
+}
+
 /// 函数 `env_bool_or`
 ///
 /// 作者: gaohongshun
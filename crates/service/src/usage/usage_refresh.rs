@@ -3,11 +3,11 @@ mod refresh;
 
 pub(crate) use refresh::{
     background_tasks_settings, enqueue_usage_refresh_after_account_add,
-    enqueue_usage_refresh_for_account, ensure_gateway_keepalive, ensure_token_refresh_polling,
-    ensure_usage_polling, ensure_warmup_cron, refresh_usage_for_account,
-    refresh_usage_for_account_result, refresh_usage_for_all_accounts_result,
-    reload_background_tasks_runtime_from_env, set_background_tasks_settings,
-    subscribe_usage_refresh_completed, validate_background_tasks_settings_patch,
-    BackgroundTasksSettingsPatch,
+    enqueue_usage_refresh_for_account, ensure_gateway_keepalive, ensure_health_probe,
+    ensure_token_refresh_polling, ensure_usage_polling, ensure_warmup_cron, health_probe_policy,
+    refresh_usage_for_account, refresh_usage_for_account_result,
+    refresh_usage_for_all_accounts_result, reload_background_tasks_runtime_from_env,
+    set_background_tasks_settings, subscribe_usage_refresh_completed,
+    validate_background_tasks_settings_patch, BackgroundTasksSettingsPatch,
 };
 pub use refresh::{set_usage_refresh_completed_handler, UsageRefreshCompletedEvent};
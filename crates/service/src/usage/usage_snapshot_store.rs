@@ -10,7 +10,9 @@ const USAGE_SNAPSHOTS_RETAIN_PER_ACCOUNT_ENV: &str =
     "CODEXMANAGER_USAGE_SNAPSHOTS_RETAIN_PER_ACCOUNT";
 
 fn usage_status_updates_blocked(context: &AccountStatusContext) -> bool {
-    context.status.trim().eq_ignore_ascii_case("disabled")
+    let status = context.status.trim();
+    // 中文注释：健康探测隔离的账号只能由探测恢复，用量刷新不能把它改回 active。
+    status.eq_ignore_ascii_case("disabled") || status.eq_ignore_ascii_case("quarantined")
 }
 
 /// 函数 `usage_snapshots_retain_per_account`
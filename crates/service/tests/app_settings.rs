@@ -36,6 +36,10 @@ const ISOLATED_RUNTIME_ENV_KEYS: &[&str] = &[
     "CODEXMANAGER_USAGE_POLL_INTERVAL_SECS",
     "CODEXMANAGER_WARMUP_CRON_ENABLED",
     "CODEXMANAGER_WARMUP_CRON_EXPRESSION",
+    "CODEXMANAGER_HEALTH_PROBE_ENABLED",
+    "CODEXMANAGER_HEALTH_PROBE_INTERVAL_SECS",
+    "CODEXMANAGER_HEALTH_PROBE_FAILURE_THRESHOLD",
+    "CODEXMANAGER_HEALTH_PROBE_RECOVERY_THRESHOLD",
     "CODEXMANAGER_GATEWAY_KEEPALIVE_ENABLED",
     "CODEXMANAGER_GATEWAY_KEEPALIVE_INTERVAL_SECS",
     "CODEXMANAGER_TOKEN_REFRESH_POLLING_ENABLED",
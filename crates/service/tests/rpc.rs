@@ -4006,6 +4006,72 @@ fn rpc_apikey_create_accepts_custom_key_and_rejects_duplicate() {
     assert!(message.contains("custom api key already exists"));
 }
 
+#[test]
+fn rpc_apikey_rotate_replaces_secret_and_keeps_id() {
+    let ctx = RpcTestContext::new("rpc-apikey-rotate");
+    let old_key = "sk-codexmanager-rotate-old";
+    let server = codexmanager_service::start_one_shot_server().expect("start server");
+    let create_req = JsonRpcRequest {
+        id: 83.into(),
+        method: "apikey/create".to_string(),
+        params: Some(serde_json::json!({
+            "name": "rotate-key",
+            "modelSlug": "gpt-5.4",
+            "customKey": old_key
+        })),
+        trace: None,
+    };
+    let create_json = serde_json::to_string(&create_req).expect("serialize apikey create");
+    let create_resp = post_rpc(&server.addr, &create_json);
+    let key_id = create_resp
+        .get("result")
+        .and_then(|value| value.get("id"))
+        .and_then(|value| value.as_str())
+        .expect("created key id")
+        .to_string();
+    let previous_hash = Storage::open(ctx.db_path())
+        .expect("open db")
+        .find_api_key_by_id(&key_id)
+        .expect("find created key")
+        .expect("created key record")
+        .key_hash;
+
+    let rotate_server = codexmanager_service::start_one_shot_server().expect("start server");
+    let rotate_req = JsonRpcRequest {
+        id: 84.into(),
+        method: "apikey/rotate".to_string(),
+        params: Some(serde_json::json!({ "id": key_id })),
+        trace: None,
+    };
+    let rotate_json = serde_json::to_string(&rotate_req).expect("serialize apikey rotate");
+    let rotate_resp = post_rpc(&rotate_server.addr, &rotate_json);
+    let rotate_result = rotate_resp.get("result").expect("rotate result");
+    assert_eq!(
+        rotate_result.get("id").and_then(|value| value.as_str()),
+        Some(key_id.as_str())
+    );
+    let new_key = rotate_result
+        .get("key")
+        .and_then(|value| value.as_str())
+        .expect("rotated key");
+    assert_ne!(new_key, old_key);
+
+    let storage = Storage::open(ctx.db_path()).expect("open db");
+    let rotated = storage
+        .find_api_key_by_id(&key_id)
+        .expect("find rotated key")
+        .expect("rotated key record");
+    assert_ne!(rotated.key_hash, previous_hash);
+    assert_eq!(rotated.name.as_deref(), Some("rotate-key"));
+    assert_eq!(
+        storage
+            .find_api_key_secret_by_id(&key_id)
+            .expect("read rotated secret")
+            .as_deref(),
+        Some(new_key)
+    );
+}
+
 /// 函数 `rpc_apikey_update_model_updates_name_with_chinese`
 ///
 /// 作者: gaohongshun
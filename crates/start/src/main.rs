@@ -5,7 +5,7 @@ use std::io::Write;
 use std::net::TcpStream;
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command};
+use std::process::Child;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -13,6 +13,10 @@ use std::time::Duration;
 const ENV_CANDIDATES: [&str; 3] = ["codexmanager.env", "CodexManager.env", ".env"];
 const DEFAULT_SERVICE_ADDR: &str = "localhost:48760";
 
+mod supervisor;
+
+use supervisor::{ChildSpec, LogConfig, RestartPolicy, SpawnHook, Supervisor, SupervisorExit};
+
 #[cfg(target_os = "windows")]
 mod windows_job {
     use super::*;
@@ -514,7 +518,9 @@ fn wait_for_port_closed(addr: &str, attempts: usize) -> bool {
 /// 返回函数执行结果
 fn stop_existing_service_best_effort(addr: &str) -> bool {
     codexmanager_service::request_shutdown(addr);
-    wait_for_port_closed(addr, 30)
+    // 中文注释：旧服务会先排空在途请求再退出，等待时间需覆盖排空超时。
+    let drain_attempts = codexmanager_service::shutdown_drain_timeout().as_millis() / 100;
+    wait_for_port_closed(addr, 30 + drain_attempts as usize)
 }
 
 /// 函数 `stop_existing_web_best_effort`
@@ -560,24 +566,35 @@ fn bin_path(dir: &Path, name: &str) -> PathBuf {
     }
 }
 
-/// 函数 `spawn_child`
-///
-/// 作者: gaohongshun
-///
-/// 时间: 2026-04-02
+/// 函数 `data_dir`
 ///
-/// # 参数
-/// - bin: 参数 bin
-/// - service_bind_addr: 参数 service_bind_addr
+/// 数据目录取数据库所在目录，未配置时回退到启动器所在目录。
+fn data_dir() -> PathBuf {
+    std::env::var("CODEXMANAGER_DB_PATH")
+        .ok()
+        .map(PathBuf::from)
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(exe_dir)
+}
+
+/// 函数 `print_status_file`
 ///
-/// # 返回
-/// 返回函数执行结果
-fn spawn_child(bin: &Path, service_bind_addr: Option<&str>) -> std::io::Result<Child> {
-    let mut cmd = Command::new(bin);
-    if let Some(bind_addr) = service_bind_addr {
-        cmd.env("CODEXMANAGER_SERVICE_ADDR", bind_addr);
+/// `codexmanager-start status`：输出运行中启动器写入的监督状态。
+fn print_status_file(path: &Path) -> i32 {
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            println!("{}", text.trim_end());
+            0
+        }
+        Err(err) => {
+            eprintln!(
+                "读取状态文件失败（启动器可能未运行）：{}：{err}",
+                path.display()
+            );
+            1
+        }
     }
-    cmd.spawn()
 }
 
 /// 函数 `main`
@@ -597,6 +614,12 @@ fn main() {
     codexmanager_service::init_logging();
     // 进一步对齐 service/web 的便携化初始化，确保 DB/RPC token 落点一致。
     codexmanager_service::portable::bootstrap_current_process();
+    let data_dir = data_dir();
+    let status_path = supervisor::status_file_path(&data_dir);
+    if std::env::args().nth(1).as_deref() == Some("status") {
+        std::process::exit(print_status_file(&status_path));
+    }
+
     if let Err(err) = codexmanager_service::initialize_storage_if_needed() {
         eprintln!("数据库迁移失败，拒绝启动：{err}");
         std::process::exit(1);
@@ -652,25 +675,42 @@ fn main() {
         std::process::exit(1);
     }
 
-    println!("正在启动 service...");
-    let mut service_child = match spawn_child(&service_bin, Some(&service_bind_addr)) {
-        Ok(child) => child,
-        Err(err) => {
-            eprintln!("启动 service 失败：{err}");
-            std::process::exit(1);
-        }
-    };
     #[cfg(target_os = "windows")]
-    if let Some(job) = child_job.as_ref() {
-        if let Err(err) = job.assign(&service_child) {
-            eprintln!("service 未能加入 Windows 回收句柄，关闭窗口时可能残留：{err}");
+    let on_spawn: SpawnHook = Box::new(move |child: &Child, name: &str| {
+        if let Some(job) = child_job.as_ref() {
+            if let Err(err) = job.assign(child) {
+                eprintln!("{name} 未能加入 Windows 回收句柄，关闭窗口时可能残留：{err}");
+            }
         }
+    });
+    #[cfg(not(target_os = "windows"))]
+    let on_spawn: SpawnHook = Box::new(|_: &Child, _: &str| {});
+    let logs = LogConfig::from_env(&data_dir);
+    println!("- logs:    {}", logs.dir.display());
+    println!("- status:  {}", status_path.display());
+    let mut supervisor = Supervisor::new(RestartPolicy::from_env(), logs, status_path, on_spawn);
+
+    println!("正在启动 service...");
+    let service_spec = ChildSpec {
+        name: "service",
+        bin: service_bin,
+        envs: vec![(
+            "CODEXMANAGER_SERVICE_ADDR".to_string(),
+            service_bind_addr.clone(),
+        )],
+        health_addr: service_addr.clone(),
+        // 中文注释：/metrics 不经过请求队列，高负载时也不会被误判为不健康。
+        health_path: "/metrics",
+    };
+    if let Err(err) = supervisor.start(service_spec) {
+        eprintln!("启动 service 失败：{err}");
+        std::process::exit(1);
     }
 
     println!("等待 service 就绪...");
     if !wait_for_service_ready(&service_addr, 120) {
         eprintln!("service 启动后仍未通过健康检查：{service_addr}");
-        let _ = service_child.kill();
+        supervisor.kill_all();
         std::process::exit(1);
     }
 
@@ -678,37 +718,42 @@ fn main() {
         println!("检测到 web 已在运行，尝试重启以应用当前配置...");
         if !stop_existing_web_best_effort(&web_addr, &web_open_addr) {
             eprintln!("web 端口仍被占用，请先关闭旧实例：http://{web_open_addr}/");
+            supervisor.kill_all();
             std::process::exit(1);
         }
     }
 
     println!("正在启动 web...");
-    let mut web_cmd = Command::new(&web_bin);
-    // 由 start.exe 统一管理 service，避免 web 进程重复拉起/竞态。
-    web_cmd.env("CODEXMANAGER_WEB_NO_SPAWN_SERVICE", "1");
-    // 让 web 使用与本进程解析到的一致地址，避免 env 文件/系统变量差异导致难以定位。
-    web_cmd.env("CODEXMANAGER_SERVICE_ADDR", &service_addr);
-    web_cmd.env("CODEXMANAGER_WEB_ADDR", &web_addr);
-
-    let mut web_child = match web_cmd.spawn() {
-        Ok(v) => v,
-        Err(err) => {
-            eprintln!("启动 web 失败：{err}");
-            std::process::exit(1);
-        }
+    let web_spec = ChildSpec {
+        name: "web",
+        bin: web_bin,
+        envs: vec![
+            // 由 start.exe 统一管理 service，避免 web 进程重复拉起/竞态。
+            (
+                "CODEXMANAGER_WEB_NO_SPAWN_SERVICE".to_string(),
+                "1".to_string(),
+            ),
+            // 让 web 使用与本进程解析到的一致地址，避免 env 文件/系统变量差异导致难以定位。
+            (
+                "CODEXMANAGER_SERVICE_ADDR".to_string(),
+                service_addr.clone(),
+            ),
+            ("CODEXMANAGER_WEB_ADDR".to_string(), web_addr.clone()),
+        ],
+        health_addr: web_open_addr.clone(),
+        // 中文注释：web 的 /health 会转发到 service，service 故障时不能连带重启 web。
+        health_path: "/api/runtime",
     };
-    #[cfg(target_os = "windows")]
-    if let Some(job) = child_job.as_ref() {
-        if let Err(err) = job.assign(&web_child) {
-            eprintln!("web 未能加入 Windows 回收句柄，关闭窗口时可能残留：{err}");
-        }
+    if let Err(err) = supervisor.start(web_spec) {
+        eprintln!("启动 web 失败：{err}");
+        supervisor.kill_all();
+        std::process::exit(1);
     }
 
     println!("Waiting for web gateway readiness...");
     if !wait_for_service_ready(&web_open_addr, 120) {
         eprintln!("web gateway failed health check: http://{web_open_addr}/health");
-        let _ = web_child.kill();
-        let _ = service_child.kill();
+        supervisor.kill_all();
         std::process::exit(1);
     }
 
@@ -720,20 +765,10 @@ fn main() {
         });
     }
 
-    // 监督进程：Ctrl+C 或任一子进程退出则进入关闭流程。
-    loop {
-        if should_exit.load(Ordering::SeqCst) {
-            break;
-        }
-        if let Ok(Some(status)) = web_child.try_wait() {
-            println!("web 已退出：{status}");
-            break;
-        }
-        if let Ok(Some(status)) = service_child.try_wait() {
-            println!("service 已退出：{status}");
-            break;
-        }
-        std::thread::sleep(Duration::from_millis(250));
+    // 监督进程：异常退出按退避重启；Ctrl+C、子进程正常退出或崩溃循环则进入关闭流程。
+    let exit = supervisor.run(&should_exit);
+    if let SupervisorExit::CrashLoop(name) = exit {
+        eprintln!("{name} 反复崩溃，启动器将关闭全部子进程，请查看日志排查原因");
     }
 
     println!("正在关闭...");
@@ -742,22 +777,16 @@ fn main() {
     simple_get_best_effort(&web_addr, "/__quit");
     simple_get_best_effort(&service_addr, "/__shutdown");
 
-    // 最后兜底：短等后强杀
-    let deadline = std::time::Instant::now() + Duration::from_secs(3);
-    loop {
-        let web_done = web_child.try_wait().ok().flatten().is_some();
-        let service_done = service_child.try_wait().ok().flatten().is_some();
-        if web_done && service_done {
-            break;
-        }
-        if std::time::Instant::now() >= deadline {
-            break;
-        }
-        std::thread::sleep(Duration::from_millis(100));
-    }
+    // 最后兜底：等待 service 排空结束后再强杀
+    let deadline = std::time::Instant::now()
+        + codexmanager_service::shutdown_drain_timeout()
+        + Duration::from_secs(3);
+    supervisor.wait_all_until(deadline);
+    supervisor.kill_all();
 
-    let _ = web_child.kill();
-    let _ = service_child.kill();
+    if matches!(exit, SupervisorExit::CrashLoop(_)) {
+        std::process::exit(1);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,715 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const ENV_RESTART_BACKOFF_MS: &str = "CODEXMANAGER_START_RESTART_BACKOFF_MS";
+const ENV_RESTART_MAX_BACKOFF_MS: &str = "CODEXMANAGER_START_RESTART_MAX_BACKOFF_MS";
+const ENV_CRASH_LOOP_MAX_RESTARTS: &str = "CODEXMANAGER_START_CRASH_LOOP_MAX_RESTARTS";
+const ENV_CRASH_LOOP_WINDOW_SECS: &str = "CODEXMANAGER_START_CRASH_LOOP_WINDOW_SECS";
+const ENV_HEALTH_INTERVAL_SECS: &str = "CODEXMANAGER_START_HEALTH_INTERVAL_SECS";
+const ENV_HEALTH_FAILURE_THRESHOLD: &str = "CODEXMANAGER_START_HEALTH_FAILURE_THRESHOLD";
+const ENV_LOG_DIR: &str = "CODEXMANAGER_START_LOG_DIR";
+const ENV_LOG_MAX_BYTES: &str = "CODEXMANAGER_START_LOG_MAX_BYTES";
+const ENV_LOG_KEEP: &str = "CODEXMANAGER_START_LOG_KEEP";
+const ENV_STATUS_FILE: &str = "CODEXMANAGER_START_STATUS_FILE";
+
+const DEFAULT_RESTART_BACKOFF_MS: u64 = 1_000;
+const DEFAULT_RESTART_MAX_BACKOFF_MS: u64 = 60_000;
+const DEFAULT_CRASH_LOOP_MAX_RESTARTS: usize = 5;
+const DEFAULT_CRASH_LOOP_WINDOW_SECS: u64 = 300;
+const DEFAULT_HEALTH_INTERVAL_SECS: u64 = 10;
+const DEFAULT_HEALTH_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_LOG_KEEP: usize = 5;
+const DEFAULT_STATUS_FILENAME: &str = "codexmanager-start.status.json";
+
+/// 子进程连续运行超过该时长视为已恢复，退避从初始值重新计算。
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// 刚启动的子进程需要迁移数据库、预热缓存，这段时间内不做健康检查。
+const HEALTH_STARTUP_GRACE: Duration = Duration::from_secs(30);
+const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RestartPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub crash_loop_max_restarts: usize,
+    pub crash_loop_window: Duration,
+    pub health_interval: Option<Duration>,
+    pub health_failure_threshold: u32,
+}
+
+impl RestartPolicy {
+    /// 函数 `from_env`
+    ///
+    /// 读取 `CODEXMANAGER_START_*` 重启策略，非法值回退默认；健康检查间隔为 `0` 时关闭主动探活。
+    pub(crate) fn from_env() -> Self {
+        let initial_backoff = env_u64(ENV_RESTART_BACKOFF_MS)
+            .unwrap_or(DEFAULT_RESTART_BACKOFF_MS)
+            .max(1);
+        let max_backoff = env_u64(ENV_RESTART_MAX_BACKOFF_MS)
+            .unwrap_or(DEFAULT_RESTART_MAX_BACKOFF_MS)
+            .max(initial_backoff);
+        let health_interval_secs =
+            env_u64(ENV_HEALTH_INTERVAL_SECS).unwrap_or(DEFAULT_HEALTH_INTERVAL_SECS);
+        Self {
+            initial_backoff: Duration::from_millis(initial_backoff),
+            max_backoff: Duration::from_millis(max_backoff),
+            crash_loop_max_restarts: env_u64(ENV_CRASH_LOOP_MAX_RESTARTS)
+                .map(|value| value as usize)
+                .unwrap_or(DEFAULT_CRASH_LOOP_MAX_RESTARTS),
+            crash_loop_window: Duration::from_secs(
+                env_u64(ENV_CRASH_LOOP_WINDOW_SECS).unwrap_or(DEFAULT_CRASH_LOOP_WINDOW_SECS),
+            ),
+            health_interval: (health_interval_secs > 0)
+                .then(|| Duration::from_secs(health_interval_secs)),
+            health_failure_threshold: env_u64(ENV_HEALTH_FAILURE_THRESHOLD)
+                .map(|value| value.clamp(1, u32::MAX as u64) as u32)
+                .unwrap_or(DEFAULT_HEALTH_FAILURE_THRESHOLD),
+        }
+    }
+
+    /// 函数 `backoff_for`
+    ///
+    /// 第 n 次连续失败的等待时间：初始值按 2 的幂递增，封顶 `max_backoff`。
+    pub(crate) fn backoff_for(&self, consecutive_failures: u32) -> Duration {
+        let exponent = consecutive_failures.saturating_sub(1);
+        let factor = 1u32.checked_shl(exponent).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// 滑动窗口内的异常退出次数超过上限即判定为崩溃循环。
+#[derive(Debug)]
+pub(crate) struct CrashLoopDetector {
+    window: Duration,
+    max_restarts: usize,
+    failures: VecDeque<Instant>,
+}
+
+impl CrashLoopDetector {
+    pub(crate) fn new(window: Duration, max_restarts: usize) -> Self {
+        Self {
+            window,
+            max_restarts,
+            failures: VecDeque::new(),
+        }
+    }
+
+    /// 函数 `record`
+    ///
+    /// 记录一次异常退出，返回是否已进入崩溃循环。
+    pub(crate) fn record(&mut self, now: Instant) -> bool {
+        self.failures.push_back(now);
+        while self
+            .failures
+            .front()
+            .is_some_and(|first| now.saturating_duration_since(*first) > self.window)
+        {
+            self.failures.pop_front();
+        }
+        self.failures.len() > self.max_restarts
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogConfig {
+    pub dir: PathBuf,
+    pub max_bytes: u64,
+    pub keep: usize,
+}
+
+impl LogConfig {
+    /// 函数 `from_env`
+    ///
+    /// 日志默认写到数据目录下的 `logs/`，与数据库放在一起便于容器挂载。
+    pub(crate) fn from_env(data_dir: &Path) -> Self {
+        Self {
+            dir: env_path(ENV_LOG_DIR, data_dir).unwrap_or_else(|| data_dir.join("logs")),
+            max_bytes: env_u64(ENV_LOG_MAX_BYTES)
+                .unwrap_or(DEFAULT_LOG_MAX_BYTES)
+                .max(1),
+            keep: env_u64(ENV_LOG_KEEP)
+                .map(|value| value as usize)
+                .unwrap_or(DEFAULT_LOG_KEEP),
+        }
+    }
+}
+
+/// 函数 `status_file_path`
+///
+/// 状态文件默认位于数据目录，可用 `CODEXMANAGER_START_STATUS_FILE` 覆盖。
+pub(crate) fn status_file_path(data_dir: &Path) -> PathBuf {
+    env_path(ENV_STATUS_FILE, data_dir).unwrap_or_else(|| data_dir.join(DEFAULT_STATUS_FILENAME))
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+fn env_path(name: &str, base: &Path) -> Option<PathBuf> {
+    let raw = std::env::var(name).ok()?;
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(raw);
+    Some(if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    })
+}
+
+fn now_ts() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// 按大小滚动的日志文件：`name.log` 写满后依次改名为 `name.log.1`…`name.log.{keep}`。
+pub(crate) struct RotatingLog {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingLog {
+    pub(crate) fn open(path: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        let mut log = Self {
+            path,
+            max_bytes,
+            keep,
+            file: None,
+            written: 0,
+        };
+        log.reopen();
+        log
+    }
+
+    fn reopen(&mut self) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .ok();
+        self.written = fs::metadata(&self.path).map(|meta| meta.len()).unwrap_or(0);
+    }
+
+    pub(crate) fn write_line(&mut self, line: &[u8]) {
+        if self.written > 0 && self.written.saturating_add(line.len() as u64) > self.max_bytes {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(line).is_ok() {
+                self.written = self.written.saturating_add(line.len() as u64);
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        if self.keep == 0 {
+            let _ = fs::remove_file(&self.path);
+        } else {
+            let _ = fs::remove_file(rotated_path(&self.path, self.keep));
+            for index in (1..self.keep).rev() {
+                let _ = fs::rename(
+                    rotated_path(&self.path, index),
+                    rotated_path(&self.path, index + 1),
+                );
+            }
+            let _ = fs::rename(&self.path, rotated_path(&self.path, 1));
+        }
+        self.reopen();
+    }
+}
+
+pub(crate) fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+/// 把子进程输出逐行转发到启动器控制台，同时写入滚动日志。
+fn pump_output<R: Read + Send + 'static>(
+    reader: R,
+    log: Arc<Mutex<RotatingLog>>,
+    to_stderr: bool,
+    thread_name: String,
+) {
+    let _ = thread::Builder::new().name(thread_name).spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            if to_stderr {
+                let _ = io::stderr().write_all(&line);
+            } else {
+                let _ = io::stdout().write_all(&line);
+            }
+            if let Ok(mut log) = log.lock() {
+                log.write_line(&line);
+            }
+        }
+    });
+}
+
+pub(crate) struct ChildSpec {
+    pub name: &'static str,
+    pub bin: PathBuf,
+    pub envs: Vec<(String, String)>,
+    pub health_addr: String,
+    pub health_path: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChildState {
+    Running,
+    Backoff,
+    CrashLoop,
+    Stopped,
+}
+
+impl ChildState {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Backoff => "backoff",
+            Self::CrashLoop => "crash_loop",
+            Self::Stopped => "stopped",
+        }
+    }
+}
+
+struct SupervisedChild {
+    spec: ChildSpec,
+    process: Option<Child>,
+    state: ChildState,
+    started_at: Option<Instant>,
+    started_at_ts: Option<i64>,
+    restart_count: u32,
+    consecutive_failures: u32,
+    health_failures: u32,
+    last_health_check: Option<Instant>,
+    last_exit: Option<String>,
+    last_exit_at_ts: Option<i64>,
+    next_restart_at: Option<Instant>,
+    crash_loop: CrashLoopDetector,
+    stdout_path: PathBuf,
+    stderr_path: PathBuf,
+    stdout_log: Arc<Mutex<RotatingLog>>,
+    stderr_log: Arc<Mutex<RotatingLog>>,
+}
+
+impl SupervisedChild {
+    fn new(spec: ChildSpec, policy: &RestartPolicy, logs: &LogConfig) -> Self {
+        let stdout_path = logs.dir.join(format!("{}.stdout.log", spec.name));
+        let stderr_path = logs.dir.join(format!("{}.stderr.log", spec.name));
+        let stdout_log = RotatingLog::open(stdout_path.clone(), logs.max_bytes, logs.keep);
+        let stderr_log = RotatingLog::open(stderr_path.clone(), logs.max_bytes, logs.keep);
+        Self {
+            spec,
+            process: None,
+            state: ChildState::Stopped,
+            started_at: None,
+            started_at_ts: None,
+            restart_count: 0,
+            consecutive_failures: 0,
+            health_failures: 0,
+            last_health_check: None,
+            last_exit: None,
+            last_exit_at_ts: None,
+            next_restart_at: None,
+            crash_loop: CrashLoopDetector::new(
+                policy.crash_loop_window,
+                policy.crash_loop_max_restarts,
+            ),
+            stdout_path,
+            stderr_path,
+            stdout_log: Arc::new(Mutex::new(stdout_log)),
+            stderr_log: Arc::new(Mutex::new(stderr_log)),
+        }
+    }
+
+    fn spawn(&mut self, on_spawn: &dyn Fn(&Child, &str)) -> io::Result<()> {
+        let mut cmd = Command::new(&self.spec.bin);
+        for (key, value) in &self.spec.envs {
+            cmd.env(key, value);
+        }
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let mut child = cmd.spawn()?;
+        on_spawn(&child, self.spec.name);
+        if let Some(stdout) = child.stdout.take() {
+            pump_output(
+                stdout,
+                Arc::clone(&self.stdout_log),
+                false,
+                format!("{}-stdout", self.spec.name),
+            );
+        }
+        if let Some(stderr) = child.stderr.take() {
+            pump_output(
+                stderr,
+                Arc::clone(&self.stderr_log),
+                true,
+                format!("{}-stderr", self.spec.name),
+            );
+        }
+        self.process = Some(child);
+        self.state = ChildState::Running;
+        self.started_at = Some(Instant::now());
+        self.started_at_ts = Some(now_ts());
+        self.next_restart_at = None;
+        self.health_failures = 0;
+        self.last_health_check = None;
+        Ok(())
+    }
+
+    /// 函数 `schedule_restart`
+    ///
+    /// 记录异常退出并安排退避重启；崩溃循环时返回 `true`，不再重启。
+    fn schedule_restart(&mut self, reason: String, policy: &RestartPolicy, now: Instant) -> bool {
+        let uptime = self
+            .started_at
+            .map(|started| now.saturating_duration_since(started))
+            .unwrap_or_default();
+        if uptime >= STABLE_UPTIME {
+            self.consecutive_failures = 0;
+        }
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.process = None;
+        self.started_at = None;
+        self.started_at_ts = None;
+        self.health_failures = 0;
+        self.last_exit_at_ts = Some(now_ts());
+        if self.crash_loop.record(now) {
+            eprintln!(
+                "{} 在 {} 秒内异常退出超过 {} 次，判定为崩溃循环，停止重启：{reason}",
+                self.spec.name,
+                policy.crash_loop_window.as_secs(),
+                policy.crash_loop_max_restarts
+            );
+            self.last_exit = Some(reason);
+            self.state = ChildState::CrashLoop;
+            self.next_restart_at = None;
+            return true;
+        }
+        let delay = policy.backoff_for(self.consecutive_failures);
+        self.restart_count = self.restart_count.saturating_add(1);
+        eprintln!(
+            "{} 异常退出（{reason}），{} 毫秒后进行第 {} 次重启",
+            self.spec.name,
+            delay.as_millis(),
+            self.restart_count
+        );
+        self.last_exit = Some(reason);
+        self.state = ChildState::Backoff;
+        self.next_restart_at = Some(now + delay);
+        false
+    }
+
+    fn health_check_due(&self, policy: &RestartPolicy, now: Instant) -> bool {
+        let Some(interval) = policy.health_interval else {
+            return false;
+        };
+        let Some(started_at) = self.started_at else {
+            return false;
+        };
+        if now.saturating_duration_since(started_at) < HEALTH_STARTUP_GRACE {
+            return false;
+        }
+        self.last_health_check
+            .is_none_or(|last| now.saturating_duration_since(last) >= interval)
+    }
+
+    fn kill_and_reap(&mut self) {
+        if let Some(process) = self.process.as_mut() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+
+    fn status(&self, now: Instant) -> ChildStatus<'_> {
+        ChildStatus {
+            name: self.spec.name,
+            state: self.state.label(),
+            pid: self.process.as_ref().map(Child::id),
+            started_at: self.started_at_ts,
+            uptime_secs: self
+                .started_at
+                .map(|started| now.saturating_duration_since(started).as_secs()),
+            restart_count: self.restart_count,
+            consecutive_failures: self.consecutive_failures,
+            health_failures: self.health_failures,
+            last_exit: self.last_exit.as_deref(),
+            last_exit_at: self.last_exit_at_ts,
+            next_restart_in_ms: self
+                .next_restart_at
+                .map(|at| at.saturating_duration_since(now).as_millis() as u64),
+            stdout_log: self.stdout_path.display().to_string(),
+            stderr_log: self.stderr_path.display().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChildStatus<'a> {
+    name: &'a str,
+    state: &'static str,
+    pid: Option<u32>,
+    started_at: Option<i64>,
+    uptime_secs: Option<u64>,
+    restart_count: u32,
+    consecutive_failures: u32,
+    health_failures: u32,
+    last_exit: Option<&'a str>,
+    last_exit_at: Option<i64>,
+    next_restart_in_ms: Option<u64>,
+    stdout_log: String,
+    stderr_log: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SupervisorStatus<'a> {
+    pid: u32,
+    started_at: i64,
+    updated_at: i64,
+    children: Vec<ChildStatus<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SupervisorExit {
+    /// 收到 Ctrl+C / SIGTERM。
+    Interrupted,
+    /// 子进程正常退出（退出码 0），视为主动停止，启动器随之关闭。
+    ChildStopped(&'static str),
+    /// 子进程陷入崩溃循环，放弃重启。
+    CrashLoop(&'static str),
+}
+
+/// 每次（重新）拉起子进程后调用，Windows 下用于把子进程加入回收句柄。
+pub(crate) type SpawnHook = Box<dyn Fn(&Child, &str)>;
+
+pub(crate) struct Supervisor {
+    policy: RestartPolicy,
+    logs: LogConfig,
+    status_path: PathBuf,
+    started_at_ts: i64,
+    last_status_write: Option<Instant>,
+    children: Vec<SupervisedChild>,
+    on_spawn: SpawnHook,
+}
+
+impl Supervisor {
+    pub(crate) fn new(
+        policy: RestartPolicy,
+        logs: LogConfig,
+        status_path: PathBuf,
+        on_spawn: SpawnHook,
+    ) -> Self {
+        Self {
+            policy,
+            logs,
+            status_path,
+            started_at_ts: now_ts(),
+            last_status_write: None,
+            children: Vec::new(),
+            on_spawn,
+        }
+    }
+
+    /// 函数 `start`
+    ///
+    /// 首次拉起子进程并纳入监督；失败时不加入列表，由调用方决定是否退出。
+    pub(crate) fn start(&mut self, spec: ChildSpec) -> io::Result<()> {
+        let mut child = SupervisedChild::new(spec, &self.policy, &self.logs);
+        child.spawn(self.on_spawn.as_ref())?;
+        self.children.push(child);
+        self.write_status(Instant::now());
+        Ok(())
+    }
+
+    /// 函数 `run`
+    ///
+    /// 监督循环：异常退出按退避重启，健康检查连续失败则强制重启；
+    /// 收到退出信号、子进程正常退出或崩溃循环时返回。
+    pub(crate) fn run(&mut self, should_exit: &AtomicBool) -> SupervisorExit {
+        loop {
+            if should_exit.load(Ordering::SeqCst) {
+                return SupervisorExit::Interrupted;
+            }
+            let now = Instant::now();
+            let mut changed = false;
+            for index in 0..self.children.len() {
+                let (outcome, child_changed) = self.poll_child(index, now);
+                changed |= child_changed;
+                if let Some(exit) = outcome {
+                    self.write_status(now);
+                    return exit;
+                }
+            }
+            let status_due = self
+                .last_status_write
+                .is_none_or(|last| now.saturating_duration_since(last) >= STATUS_REFRESH_INTERVAL);
+            if changed || status_due {
+                self.write_status(now);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn poll_child(&mut self, index: usize, now: Instant) -> (Option<SupervisorExit>, bool) {
+        let policy = &self.policy;
+        let on_spawn = self.on_spawn.as_ref();
+        let child = &mut self.children[index];
+        let name = child.spec.name;
+        match child.state {
+            ChildState::Running => {
+                let exited = child
+                    .process
+                    .as_mut()
+                    .and_then(|process| process.try_wait().ok().flatten());
+                if let Some(status) = exited {
+                    if status.success() {
+                        println!("{name} 已退出：{status}");
+                        child.process = None;
+                        child.started_at = None;
+                        child.last_exit = Some(status.to_string());
+                        child.last_exit_at_ts = Some(now_ts());
+                        child.state = ChildState::Stopped;
+                        return (Some(SupervisorExit::ChildStopped(name)), true);
+                    }
+                    let crash_loop = child.schedule_restart(status.to_string(), policy, now);
+                    return (crash_loop.then_some(SupervisorExit::CrashLoop(name)), true);
+                }
+                if !child.health_check_due(policy, now) {
+                    return (None, false);
+                }
+                child.last_health_check = Some(now);
+                if super::http_get_status_ok(
+                    &child.spec.health_addr,
+                    child.spec.health_path,
+                    HEALTH_PROBE_TIMEOUT,
+                ) {
+                    let recovered = child.health_failures > 0;
+                    child.health_failures = 0;
+                    return (None, recovered);
+                }
+                child.health_failures = child.health_failures.saturating_add(1);
+                eprintln!(
+                    "{name} 健康检查失败（{}/{}）：{}{}",
+                    child.health_failures,
+                    policy.health_failure_threshold,
+                    child.spec.health_addr,
+                    child.spec.health_path
+                );
+                if child.health_failures < policy.health_failure_threshold {
+                    return (None, true);
+                }
+                child.kill_and_reap();
+                let crash_loop =
+                    child.schedule_restart("health check failed".to_string(), policy, now);
+                (crash_loop.then_some(SupervisorExit::CrashLoop(name)), true)
+            }
+            ChildState::Backoff => {
+                if child.next_restart_at.is_some_and(|at| now < at) {
+                    return (None, false);
+                }
+                println!("正在重启 {name}...");
+                if let Err(err) = child.spawn(on_spawn) {
+                    let crash_loop =
+                        child.schedule_restart(format!("spawn failed: {err}"), policy, now);
+                    return (crash_loop.then_some(SupervisorExit::CrashLoop(name)), true);
+                }
+                (None, true)
+            }
+            ChildState::CrashLoop | ChildState::Stopped => (None, false),
+        }
+    }
+
+    /// 函数 `wait_all_until`
+    ///
+    /// 等待全部子进程自行退出，超过截止时间后返回，由调用方兜底强杀。
+    pub(crate) fn wait_all_until(&mut self, deadline: Instant) {
+        loop {
+            let all_done = self.children.iter_mut().all(|child| {
+                child
+                    .process
+                    .as_mut()
+                    .is_none_or(|process| process.try_wait().ok().flatten().is_some())
+            });
+            if all_done || Instant::now() >= deadline {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    pub(crate) fn kill_all(&mut self) {
+        for child in &mut self.children {
+            child.kill_and_reap();
+            child.process = None;
+            child.started_at = None;
+            if child.state == ChildState::Running || child.state == ChildState::Backoff {
+                child.state = ChildState::Stopped;
+                child.next_restart_at = None;
+            }
+        }
+        self.write_status(Instant::now());
+    }
+
+    fn write_status(&mut self, now: Instant) {
+        self.last_status_write = Some(now);
+        let status = SupervisorStatus {
+            pid: std::process::id(),
+            started_at: self.started_at_ts,
+            updated_at: now_ts(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.status(now))
+                .collect(),
+        };
+        let Ok(text) = serde_json::to_string_pretty(&status) else {
+            return;
+        };
+        if let Some(parent) = self.status_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        // 中文注释：先写临时文件再改名，避免外部读取到写了一半的状态。
+        let tmp_path = rotated_path(&self.status_path, 0);
+        if fs::write(&tmp_path, text).is_ok() {
+            let _ = fs::rename(&tmp_path, &self.status_path);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "supervisor_tests.rs"]
+mod tests;
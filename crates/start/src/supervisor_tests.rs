@@ -0,0 +1,170 @@
+use super::*;
+
+fn test_policy() -> RestartPolicy {
+    RestartPolicy {
+        initial_backoff: Duration::from_millis(500),
+        max_backoff: Duration::from_secs(5),
+        crash_loop_max_restarts: 3,
+        crash_loop_window: Duration::from_secs(60),
+        health_interval: Some(Duration::from_secs(10)),
+        health_failure_threshold: 3,
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "codexmanager-start-{name}-{}-{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0)
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+#[test]
+fn backoff_doubles_and_caps_at_max() {
+    let policy = test_policy();
+    assert_eq!(policy.backoff_for(0), Duration::from_millis(500));
+    assert_eq!(policy.backoff_for(1), Duration::from_millis(500));
+    assert_eq!(policy.backoff_for(2), Duration::from_secs(1));
+    assert_eq!(policy.backoff_for(4), Duration::from_secs(4));
+    assert_eq!(policy.backoff_for(5), Duration::from_secs(5));
+    assert_eq!(policy.backoff_for(u32::MAX), Duration::from_secs(5));
+}
+
+#[test]
+fn crash_loop_detector_trips_only_within_window() {
+    let start = Instant::now();
+    let mut detector = CrashLoopDetector::new(Duration::from_secs(60), 3);
+    assert!(!detector.record(start));
+    assert!(!detector.record(start + Duration::from_secs(10)));
+    assert!(!detector.record(start + Duration::from_secs(20)));
+    assert!(detector.record(start + Duration::from_secs(30)));
+
+    let mut spaced = CrashLoopDetector::new(Duration::from_secs(60), 3);
+    for minute in 0..10 {
+        assert!(!spaced.record(start + Duration::from_secs(minute * 61)));
+    }
+}
+
+#[test]
+fn rotating_log_shifts_files_and_drops_oldest() {
+    let dir = temp_dir("rotate");
+    let path = dir.join("service.stdout.log");
+    let mut log = RotatingLog::open(path.clone(), 10, 2);
+    for line in ["first-1\n", "second\n", "third-3\n", "fourth\n"] {
+        log.write_line(line.as_bytes());
+    }
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "third-3\n"
+    );
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 2)).unwrap(),
+        "second\n"
+    );
+    assert!(!rotated_path(&path, 3).exists());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn rotating_log_appends_to_existing_file_size() {
+    let dir = temp_dir("append");
+    let path = dir.join("web.stderr.log");
+    fs::write(&path, "12345678").unwrap();
+    let mut log = RotatingLog::open(path.clone(), 10, 1);
+    log.write_line(b"abc\n");
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "abc\n");
+    assert_eq!(
+        fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+        "12345678"
+    );
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn schedule_restart_backs_off_then_reports_crash_loop() {
+    let dir = temp_dir("restart");
+    let policy = test_policy();
+    let logs = LogConfig {
+        dir: dir.join("logs"),
+        max_bytes: 1024,
+        keep: 1,
+    };
+    let spec = ChildSpec {
+        name: "service",
+        bin: dir.join("missing-bin"),
+        envs: Vec::new(),
+        health_addr: "localhost:1".to_string(),
+        health_path: "/metrics",
+    };
+    let mut child = SupervisedChild::new(spec, &policy, &logs);
+    let now = Instant::now();
+
+    assert!(!child.schedule_restart("exit status: 1".to_string(), &policy, now));
+    assert_eq!(child.state, ChildState::Backoff);
+    assert_eq!(child.restart_count, 1);
+    assert_eq!(
+        child.next_restart_at,
+        Some(now + Duration::from_millis(500))
+    );
+
+    assert!(!child.schedule_restart("exit status: 1".to_string(), &policy, now));
+    assert_eq!(child.next_restart_at, Some(now + Duration::from_secs(1)));
+    assert!(!child.schedule_restart("exit status: 1".to_string(), &policy, now));
+    assert!(child.schedule_restart("exit status: 1".to_string(), &policy, now));
+    assert_eq!(child.state, ChildState::CrashLoop);
+    assert_eq!(child.next_restart_at, None);
+    assert_eq!(child.last_exit.as_deref(), Some("exit status: 1"));
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn write_status_reports_children_as_camel_case_json() {
+    let dir = temp_dir("status");
+    let policy = test_policy();
+    let logs = LogConfig {
+        dir: dir.join("logs"),
+        max_bytes: 1024,
+        keep: 1,
+    };
+    let status_path = dir.join("codexmanager-start.status.json");
+    let mut supervisor = Supervisor::new(
+        policy.clone(),
+        logs.clone(),
+        status_path.clone(),
+        Box::new(|_: &Child, _: &str| {}),
+    );
+    let spec = ChildSpec {
+        name: "web",
+        bin: dir.join("missing-bin"),
+        envs: Vec::new(),
+        health_addr: "localhost:1".to_string(),
+        health_path: "/api/runtime",
+    };
+    let mut child = SupervisedChild::new(spec, &policy, &logs);
+    child.schedule_restart("exit status: 2".to_string(), &policy, Instant::now());
+    supervisor.children.push(child);
+    supervisor.write_status(Instant::now());
+
+    let text = fs::read_to_string(&status_path).expect("status file");
+    let value: serde_json::Value = serde_json::from_str(&text).expect("status json");
+    assert_eq!(value["pid"], std::process::id());
+    let web = &value["children"][0];
+    assert_eq!(web["name"], "web");
+    assert_eq!(web["state"], "backoff");
+    assert_eq!(web["restartCount"], 1);
+    assert_eq!(web["lastExit"], "exit status: 2");
+    assert!(web["pid"].is_null());
+    assert!(web["stdoutLog"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("web.stdout.log")));
+    assert!(!rotated_path(&status_path, 0).exists());
+    let _ = fs::remove_dir_all(dir);
+}
@@ -386,6 +386,14 @@ async fn runtime_info() -> impl IntoResponse {
     }))
 }
 
+async fn wait_for_shutdown(mut shutdown_rx: watch::Receiver<bool>) {
+    while !*shutdown_rx.borrow() {
+        if shutdown_rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
 /// 函数 `serve_on_listener`
 ///
 /// 作者: gaohongshun
@@ -402,16 +410,10 @@ async fn runtime_info() -> impl IntoResponse {
 async fn serve_on_listener(
     listener: tokio::net::TcpListener,
     app: Router,
-    mut shutdown_rx: watch::Receiver<bool>,
+    shutdown_rx: watch::Receiver<bool>,
 ) -> std::io::Result<()> {
     axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            while !*shutdown_rx.borrow() {
-                if shutdown_rx.changed().await.is_err() {
-                    break;
-                }
-            }
-        })
+        .with_graceful_shutdown(wait_for_shutdown(shutdown_rx))
         .await
         .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
 }
@@ -524,6 +526,7 @@ async fn async_main() {
             "/api/events/usage-refresh",
             get(service_gateway::usage_refresh_events),
         )
+        .route("/api/events", get(service_gateway::server_events))
         .route("/__quit", get(service_gateway::quit));
 
     let disk_ok = ensure_index_file(&index);
@@ -599,7 +602,21 @@ async fn async_main() {
         let _ = webbrowser::open(&open_url);
     }
 
-    if let Err(err) = run_web_server(&web_addr, app, shutdown_rx).await {
+    let result =
+        match codexmanager_service::tls_listener_addr(codexmanager_service::ENV_WEB_TLS_ADDR) {
+            Some(tls_addr) => {
+                println!("codexmanager-web TLS listening on {tls_addr}");
+                let tls_server = codexmanager_service::serve_tls_router(
+                    tls_addr.as_str(),
+                    app.clone(),
+                    wait_for_shutdown(shutdown_rx.clone()),
+                );
+                tokio::try_join!(run_web_server(&web_addr, app, shutdown_rx), tls_server)
+                    .map(|_| ())
+            }
+            None => run_web_server(&web_addr, app, shutdown_rx).await,
+        };
+    if let Err(err) = result {
         eprintln!("web stopped: {err}");
         std::process::exit(1);
     }
@@ -439,6 +439,101 @@ pub(super) async fn usage_refresh_events(State(state): State<Arc<AppState>>) ->
     out
 }
 
+/// 通用服务端事件流：SSE 直接转发，WebSocket 升级复用网关 WS 代理；统一补齐 RPC token 与会话身份。
+pub(super) async fn server_events(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+    drop(body);
+    let path_and_query = match parts.uri.query() {
+        Some(query) if !query.is_empty() => format!("/events?{query}"),
+        _ => "/events".to_string(),
+    };
+    // 中文注释：身份头只能由 web 层根据会话注入，先剔除浏览器自带的同名头避免伪造。
+    parts.headers.remove("x-codexmanager-rpc-token");
+    parts.headers.remove("x-codexmanager-rpc-actor-role");
+    parts.headers.remove("x-codexmanager-rpc-actor-user-id");
+    let session = auth::current_app_session_from_headers(&parts.headers);
+
+    if super::gateway_websocket::is_upgrade_request(&parts.headers) {
+        parts.uri = match path_and_query.parse() {
+            Ok(uri) => uri,
+            Err(err) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid events uri: {err}"),
+                )
+                    .into_response();
+            }
+        };
+        if let Ok(value) = HeaderValue::from_str(&state.rpc_token) {
+            parts.headers.insert("x-codexmanager-rpc-token", value);
+        }
+        if let Some(session) = session {
+            if let (Ok(role), Ok(user_id)) = (
+                HeaderValue::from_str(&session.user.role),
+                HeaderValue::from_str(&session.user.id),
+            ) {
+                parts.headers.insert("x-codexmanager-rpc-actor-role", role);
+                parts
+                    .headers
+                    .insert("x-codexmanager-rpc-actor-user-id", user_id);
+            }
+        }
+        return super::gateway_websocket::proxy(state, parts).await;
+    }
+
+    let target_url = format!("http://{}{}", state.service_addr.trim(), path_and_query);
+    let mut upstream = state
+        .client
+        .get(&target_url)
+        .header("accept", "text/event-stream")
+        .header("x-codexmanager-rpc-token", &state.rpc_token);
+    if let Some(session) = session {
+        upstream = upstream
+            .header("x-codexmanager-rpc-actor-role", session.user.role)
+            .header("x-codexmanager-rpc-actor-user-id", session.user.id);
+    }
+    if let Some(last_event_id) = parts.headers.get("last-event-id") {
+        upstream = upstream.header("last-event-id", last_event_id.clone());
+    }
+    let resp = match upstream.send().await {
+        Ok(value) => value,
+        Err(err) => {
+            let msg = format_upstream_error_message(state.service_addr.as_str(), &err);
+            return (StatusCode::BAD_GATEWAY, msg).into_response();
+        }
+    };
+
+    let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    if !status.is_success() {
+        let bytes = resp.bytes().await.unwrap_or_default();
+        let mut out = Response::new(Body::from(bytes));
+        *out.status_mut() = status;
+        out.headers_mut().insert(
+            "content-type",
+            axum::http::HeaderValue::from_static("application/json"),
+        );
+        return out;
+    }
+    let mut out = Response::new(Body::from_stream(resp.bytes_stream()));
+    *out.status_mut() = status;
+    out.headers_mut().insert(
+        "content-type",
+        axum::http::HeaderValue::from_static("text/event-stream"),
+    );
+    out.headers_mut().insert(
+        "cache-control",
+        axum::http::HeaderValue::from_static("no-cache"),
+    );
+    out.headers_mut().insert(
+        "x-accel-buffering",
+        axum::http::HeaderValue::from_static("no"),
+    );
+    out
+}
+
 const DEFAULT_GATEWAY_PROXY_MAX_BODY_BYTES: usize = 0;
 const ENV_GATEWAY_PROXY_MAX_BODY_BYTES: &str = "CODEXMANAGER_GATEWAY_PROXY_MAX_BODY_BYTES";
 
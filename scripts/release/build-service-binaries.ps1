@@ -14,6 +14,7 @@ $argsList += @(
   "-p", "codexmanager-service",
   "-p", "codexmanager-web",
   "-p", "codexmanager-start",
+  "-p", "codexmanager-cli",
   "--features", "codexmanager-web/embedded-ui"
 )
 
@@ -12,6 +12,7 @@ args+=(
   -p codexmanager-service
   -p codexmanager-web
   -p codexmanager-start
+  -p codexmanager-cli
   --features codexmanager-web/embedded-ui
 )
 